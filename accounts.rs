@@ -0,0 +1,387 @@
+use std::collections::HashSet;
+use ed25519_dalek::PublicKey;
+use rayon::prelude::*;
+
+use crate::assets::Assets;
+use crate::confidential::{pool_address, ConfidentialBalances};
+use crate::contracts::{Contracts, Execution};
+use crate::escrow::{escrow_address, Escrows};
+use crate::gas::intrinsic_gas;
+use crate::ledger::Ledger;
+use crate::nonces::Nonces;
+use crate::recovery::{PendingRecovery, Recoveries};
+use crate::scheduler::{schedule, Segment};
+use crate::vesting::Vesting;
+use crate::{Transaction, TransactionKind};
+
+// État des comptes modifié par les transactions publiques : soldes, séquestres,
+// calendriers d'acquisition, contrats, récupérations, soldes confidentiels,
+// listes d'accès des actifs et nonces utilisés
+#[derive(Clone, Debug, Default)]
+pub struct AccountState {
+    pub ledger: Ledger,
+    pub escrows: Escrows,
+    pub vesting: Vesting,
+    pub contracts: Contracts,
+    pub recoveries: Recoveries,
+    pub confidential: ConfidentialBalances,
+    pub assets: Assets,
+    pub nonces: Nonces,
+}
+
+// Effet d'une transaction vérifiée par `AccountState::prepare`, à appliquer par `commit`
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub gas_used: u64,
+    pub fee: u64,
+    pub execution: Option<Execution>,
+    // Identifiant du séquestre ouvert par un dépôt
+    pub escrow: Option<Vec<u8>>,
+    // Récupération ouverte par les gardiens d'un compte
+    pub recovery: Option<PendingRecovery>,
+    // Effets des transactions d'un lot, dans l'ordre du lot
+    pub bundle: Vec<Outcome>,
+    movements: Vec<(PublicKey, i128)>,
+}
+
+impl Outcome {
+    // Gaz compté dans la limite du bloc, transactions du lot comprises
+    pub fn block_gas(&self) -> u64 {
+        self.bundle.iter().fold(self.gas_used, |total, outcome| total.saturating_add(outcome.block_gas()))
+    }
+
+    // Frais payés, transactions du lot comprises
+    pub fn fees(&self) -> u64 {
+        self.bundle.iter().fold(self.fee, |total, outcome| total.saturating_add(outcome.fees()))
+    }
+
+    // Comptes dont le solde est modifié, hors producteur
+    fn accounts(&self) -> Vec<PublicKey> {
+        let mut accounts: Vec<PublicKey> = self.movements.iter().map(|(account, _)| *account).collect();
+        for outcome in &self.bundle {
+            accounts.extend(outcome.accounts());
+        }
+        accounts
+    }
+}
+
+// Effets d'un groupe avec leur position dans le bloc, ou position et motif du refus
+type GroupResult = Result<Vec<(usize, Outcome)>, (usize, &'static str)>;
+
+impl AccountState {
+    // Vérifie une transaction incluse au bloc `height` sans modifier l'état :
+    // nonce non utilisé, règles de récupération et de séquestre, exécution de
+    // contrat, puis solde du payeur pour le montant et les frais `gas_used * gas_price`
+    pub fn prepare(&self, transaction: &Transaction, height: u64) -> Result<Outcome, &'static str> {
+        self.prepare_on(&self.ledger, transaction, height)
+    }
+
+    // `prepare` avec les soldes de `ledger` à la place de ceux de l'état
+    fn prepare_on(&self, ledger: &Ledger, transaction: &Transaction, height: u64) -> Result<Outcome, &'static str> {
+        self.nonces.check(transaction)?;
+        let recovery = self.recoveries.check(transaction, height)?;
+        if let TransactionKind::Recover { .. } = transaction.kind {
+            if self.vesting.has_schedule(&transaction.recipient) {
+                return Err("Invalid recovery key");
+            }
+        }
+        self.escrows.check(transaction, height)?;
+        self.confidential.check(transaction)?;
+        self.assets.check(transaction)?;
+        let execution = self.contracts.check(transaction, height)?;
+        let gas_used = intrinsic_gas(transaction) + execution.as_ref().map_or(0, |execution| execution.gas_used);
+        let fee = gas_used.checked_mul(transaction.gas_price).ok_or("Transaction fee overflow")?;
+
+        // Un règlement de séquestre débite l'adresse du séquestre, un dépôt la
+        // crédite ; la réserve confidentielle reçoit les dépôts et paie les retraits
+        let (source, destination, escrow) = match &transaction.kind {
+            TransactionKind::Claim { escrow } | TransactionKind::Refund { escrow } => {
+                let address = self.escrows.get(escrow).ok_or("Unknown or settled escrow")?.address(escrow);
+                (address, transaction.recipient, None)
+            }
+            TransactionKind::Escrow { .. } => {
+                let id = transaction.hash();
+                (transaction.sender, escrow_address(&transaction.sender, &id), Some(id))
+            }
+            TransactionKind::Shield => (transaction.sender, pool_address(), None),
+            TransactionKind::Unshield { .. } => (pool_address(), transaction.recipient, None),
+            _ => (transaction.sender, transaction.recipient, None),
+        };
+        // Les fonds destinés à un compte récupéré sont versés à sa nouvelle clé
+        let destination = self.recoveries.resolve(&destination);
+        // Les frais d'une récupération sont payés par le compte récupéré, les
+        // gardiens n'ayant pas de solde à engager
+        let payer = match &transaction.kind {
+            TransactionKind::Recover { account } => *account,
+            _ => transaction.sender,
+        };
+        // Un appel dont l'exécution échoue ne paie que les frais
+        let amount = match &execution {
+            Some(Execution { failure: Some(_), .. }) => 0,
+            _ => transaction.amount,
+        };
+        let movements = vec![(payer, -(fee as i128)), (source, -(amount as i128)), (destination, amount as i128)];
+        ledger.check(&movements)?;
+
+        let debit = if source == payer { fee.saturating_add(amount) } else { fee };
+        self.vesting.check(&payer, debit, ledger.balance(&payer), height)?;
+
+        let bundle = match &transaction.kind {
+            TransactionKind::Bundle(_) => self.prepare_bundle(ledger, &movements, transaction, height)?,
+            _ => Vec::new(),
+        };
+
+        Ok(Outcome { gas_used, fee, execution, escrow, recovery, bundle, movements })
+    }
+
+    // Prépare les transactions d'un lot l'une après l'autre sur une copie de
+    // l'état où les frais et le nonce du lot sont payés et utilisés : la
+    // première refusée, ou dont l'appel de contrat échoue, fait refuser le lot entier
+    fn prepare_bundle(&self, ledger: &Ledger, fees: &[(PublicKey, i128)], bundle: &Transaction, height: u64) -> Result<Vec<Outcome>, &'static str> {
+        let mut working = AccountState {
+            ledger: ledger.clone(),
+            escrows: self.escrows.clone(),
+            vesting: self.vesting.clone(),
+            contracts: self.contracts.clone(),
+            recoveries: self.recoveries.clone(),
+            confidential: self.confidential.clone(),
+            assets: self.assets.clone(),
+            nonces: self.nonces.clone(),
+        };
+        working.ledger.apply(fees);
+        working.nonces.apply(bundle);
+        let mut outcomes = Vec::with_capacity(bundle.inner_transactions().len());
+        for transaction in bundle.inner_transactions() {
+            let outcome = working.prepare(transaction, height)?;
+            if let Some(Execution { failure: Some(_), .. }) = outcome.execution {
+                return Err("Bundled contract call failed");
+            }
+            working.settle(transaction, &outcome, height);
+            outcomes.push(outcome);
+        }
+        Ok(outcomes)
+    }
+
+    // Applique une transaction préparée ; les frais sont versés à `producer`
+    // (la réserve des récompenses, voir `rewards.rs`)
+    pub fn commit(&mut self, transaction: &Transaction, outcome: &Outcome, height: u64, producer: &PublicKey) {
+        self.settle(transaction, outcome, height);
+        self.ledger.credit(producer, outcome.fees());
+    }
+
+    // `commit` sans le versement des frais au producteur
+    fn settle(&mut self, transaction: &Transaction, outcome: &Outcome, height: u64) {
+        self.ledger.apply(&outcome.movements);
+        let _ = self.escrows.apply(transaction, height);
+        self.contracts.commit(transaction, outcome.execution.as_ref());
+        self.confidential.apply(transaction, &self.recoveries.resolve(&transaction.recipient));
+        let _ = self.recoveries.apply(transaction, height);
+        let _ = self.assets.apply(transaction);
+        self.nonces.apply(transaction);
+        for (inner, outcome) in transaction.inner_transactions().iter().zip(&outcome.bundle) {
+            self.settle(inner, outcome, height);
+        }
+    }
+
+    pub fn apply(&mut self, transaction: &Transaction, height: u64, producer: &PublicKey) -> Result<Outcome, &'static str> {
+        let outcome = self.prepare(transaction, height)?;
+        self.commit(transaction, &outcome, height, producer);
+        Ok(outcome)
+    }
+
+    // Exécute les transactions d'un bloc sur une copie de travail, qui remplace
+    // l'état seulement si toutes sont applicables et que la somme des soldes
+    // touchés est conservée (les frais passent des payeurs au producteur). Les
+    // groupes de transferts indépendants (voir `scheduler`) sont vérifiés en
+    // parallèle puis appliqués dans l'ordre du bloc.
+    pub fn apply_block(&mut self, transactions: &[Transaction], height: u64, producer: &PublicKey) -> Result<Vec<Outcome>, &'static str> {
+        let mut working = self.clone();
+        let mut outcomes = Vec::with_capacity(transactions.len());
+        let mut touched: HashSet<PublicKey> = HashSet::from([*producer]);
+        for segment in schedule(transactions, producer, &self.recoveries) {
+            let segment_outcomes = match segment {
+                Segment::Serial(range) => {
+                    let mut segment_outcomes = Vec::with_capacity(range.len());
+                    for transaction in &transactions[range] {
+                        segment_outcomes.push(working.apply(transaction, height, producer)?);
+                    }
+                    segment_outcomes
+                }
+                Segment::Parallel(groups) => {
+                    let start = groups.iter().flatten().min().copied().unwrap_or_default();
+                    let prepared = working.prepare_groups(transactions, &groups, height)?;
+                    for (offset, outcome) in prepared.iter().enumerate() {
+                        working.commit(&transactions[start + offset], outcome, height, producer);
+                    }
+                    prepared
+                }
+            };
+            for outcome in segment_outcomes {
+                touched.extend(outcome.accounts());
+                outcomes.push(outcome);
+            }
+        }
+        let supply = |ledger: &Ledger| touched.iter().map(|account| ledger.balance(account) as u128).sum::<u128>();
+        if supply(&working.ledger) != supply(&self.ledger) {
+            return Err("Block does not conserve funds");
+        }
+        *self = working;
+        Ok(outcomes)
+    }
+
+    // Vérifie chaque groupe sur ses propres soldes et les nonces qu'il utilise,
+    // les groupes en parallèle ; retourne les effets dans l'ordre du bloc, ou
+    // l'erreur de la première transaction refusée, celle qu'aurait donnée
+    // l'exécution en série
+    fn prepare_groups(&self, transactions: &[Transaction], groups: &[Vec<usize>], height: u64) -> Result<Vec<Outcome>, &'static str> {
+        let results: Vec<GroupResult> = groups.par_iter()
+            .map(|group| {
+                let mut ledger = Ledger::default();
+                for position in group {
+                    let transaction = &transactions[*position];
+                    for account in [transaction.sender, self.recoveries.resolve(&transaction.recipient)] {
+                        if ledger.balance(&account) == 0 {
+                            ledger.credit(&account, self.ledger.balance(&account));
+                        }
+                    }
+                }
+                let mut nonces = HashSet::new();
+                let mut prepared = Vec::with_capacity(group.len());
+                for position in group {
+                    let transaction = &transactions[*position];
+                    if !nonces.insert((transaction.sender, transaction.nonce)) {
+                        return Err((*position, "Nonce already used"));
+                    }
+                    let outcome = self.prepare_on(&ledger, transaction, height).map_err(|e| (*position, e))?;
+                    ledger.apply(&outcome.movements);
+                    prepared.push((*position, outcome));
+                }
+                Ok(prepared)
+            })
+            .collect();
+        let mut prepared = Vec::new();
+        let mut first_error: Option<(usize, &'static str)> = None;
+        for result in results {
+            match result {
+                Ok(group) => prepared.extend(group),
+                Err(error) => {
+                    if first_error.is_none_or(|(first, _)| error.0 < first) {
+                        first_error = Some(error);
+                    }
+                }
+            }
+        }
+        if let Some((_, error)) = first_error {
+            return Err(error);
+        }
+        prepared.sort_by_key(|(position, _)| *position);
+        Ok(prepared.into_iter().map(|(_, outcome)| outcome).collect())
+    }
+
+    // Rotations dont le délai de contestation expire au bloc `height` : soldes,
+    // calendrier d'acquisition, droits sur les séquestres et accès aux actifs
+    // passent à la nouvelle clé. Retourne les paires (ancienne clé, nouvelle clé).
+    pub fn complete_recoveries(&mut self, height: u64) -> Vec<(PublicKey, PublicKey)> {
+        let recovered = self.recoveries.complete(height);
+        for (account, new_key) in &recovered {
+            let balance = self.ledger.balance(account);
+            self.ledger.apply(&[(*account, -(balance as i128))]);
+            self.ledger.credit(new_key, balance);
+            self.vesting.rotate(account, new_key);
+            self.escrows.rotate(account, new_key);
+            self.confidential.rotate(account, new_key);
+            self.assets.rotate(account, new_key);
+        }
+        recovered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+
+    use super::{AccountState, Outcome};
+    use crate::scheduler::{schedule, Segment};
+    use crate::simulation::SplitMix64;
+    use crate::{Expiry, Transaction, TransactionKind};
+
+    const ACCOUNTS: usize = 12;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    fn balances(state: &AccountState) -> BTreeMap<[u8; 32], u64> {
+        state.ledger.iter().filter(|(_, balance)| *balance > 0).map(|(account, balance)| (account.to_bytes(), balance)).collect()
+    }
+
+    fn summary(outcomes: &[Outcome]) -> Vec<(u64, u64)> {
+        outcomes.iter().map(|outcome| (outcome.gas_used, outcome.fee)).collect()
+    }
+
+    // Bloc aléatoire de transferts entre quelques comptes, avec des soldes
+    // parfois insuffisants, le producteur comme émetteur ou destinataire et,
+    // rarement, un ancrage qui interrompt la suite de transferts ; les nonces
+    // se répètent souvent si `reused_nonces`
+    fn random_block(random: &mut SplitMix64, accounts: &[Keypair], producer: &Keypair, reused_nonces: bool) -> Vec<Transaction> {
+        let length = 32 + (random.next() % 96) as usize;
+        let nonces = if reused_nonces { 8 } else { u64::MAX };
+        (0..length).map(|_| {
+            let pick = |random: &mut SplitMix64| match random.next() % 64 {
+                0 => producer,
+                choice => &accounts[choice as usize % accounts.len()],
+            };
+            let (sender, recipient) = (pick(random), pick(random));
+            let kind = match random.next() % 128 {
+                0 => TransactionKind::Anchor { document: vec![7; 32], cid: None },
+                _ => TransactionKind::Transfer,
+            };
+            Transaction::signed(1, sender, random.next() % nonces, &recipient.public, random.next() % 400, kind, 1 + random.next() % 3, 0, Expiry::Never)
+        }).collect()
+    }
+
+    #[test]
+    fn parallel_execution_matches_serial_execution() {
+        let accounts: Vec<Keypair> = (1..=ACCOUNTS as u8).map(keypair).collect();
+        let producer = keypair(100);
+        let (mut parallel_blocks, mut applied_blocks) = (0, 0);
+        for seed in 0..64 {
+            let mut random = SplitMix64(seed);
+            let mut state = AccountState::default();
+            // Un quart des blocs réutilise des nonces, un autre quart part de
+            // soldes trop faibles pour payer tous les frais
+            let funds = if seed % 4 == 1 { 100_000 } else { 10_000_000 };
+            for account in accounts.iter().chain([&producer]) {
+                state.ledger.credit(&account.public, random.next() % funds);
+            }
+            let transactions = random_block(&mut random, &accounts, &producer, seed % 4 == 0);
+            if schedule(&transactions, &producer.public, &state.recoveries).iter().any(|segment| matches!(segment, Segment::Parallel(_))) {
+                parallel_blocks += 1;
+            }
+
+            let mut serial = state.clone();
+            let serial_outcomes: Result<Vec<Outcome>, &'static str> = transactions.iter()
+                .map(|transaction| serial.apply(transaction, 1, &producer.public))
+                .collect();
+            let mut parallel = state.clone();
+            let parallel_outcomes = parallel.apply_block(&transactions, 1, &producer.public);
+            match (serial_outcomes, parallel_outcomes) {
+                (Ok(serial_outcomes), Ok(parallel_outcomes)) => {
+                    assert_eq!(summary(&parallel_outcomes), summary(&serial_outcomes), "seed {}", seed);
+                    assert_eq!(balances(&parallel), balances(&serial), "seed {}", seed);
+                    applied_blocks += 1;
+                }
+                (Err(serial_error), Err(parallel_error)) => {
+                    assert_eq!(parallel_error, serial_error, "seed {}", seed);
+                    // Un bloc refusé laisse l'état intact
+                    assert_eq!(balances(&parallel), balances(&state), "seed {}", seed);
+                }
+                (serial, parallel) => panic!("seed {}: serial {:?}, parallel {:?}", seed, serial.map(|o| summary(&o)), parallel.map(|o| summary(&o))),
+            }
+        }
+        // Les deux issues sont couvertes, avec des suites exécutées en parallèle
+        assert!(parallel_blocks >= 32 && (16..64).contains(&applied_blocks));
+    }
+}
@@ -0,0 +1,40 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use ed25519_dalek::PublicKey;
+
+use crate::encoding::{Encoder, ADDRESS_DOMAIN, SYSTEM_ADDRESS_DOMAIN};
+
+// Adresse d'un compte système (séquestre, contrat) : point de la courbe obtenu
+// par hachage du créateur, d'un nonce et d'un sel. Personne ne connaît la clé
+// privée correspondante, aucune signature ne peut donc en dépenser les fonds.
+pub fn derive_system_address(creator: &PublicKey, nonce: u64, salt: &[u8]) -> PublicKey {
+    let mut encoder = Encoder::new(SYSTEM_ADDRESS_DOMAIN);
+    encoder.put_public_key(creator);
+    encoder.put_u64(nonce);
+    encoder.put_bytes(salt);
+    derive_address(&encoder.finish())
+}
+
+// Point de la courbe sans clé privée connue, dérivé de façon déterministe de `seed`
+// (lui-même un encodage portant son propre domaine)
+pub fn derive_address(seed: &[u8]) -> PublicKey {
+    for counter in 0u32.. {
+        let mut encoder = Encoder::new(ADDRESS_DOMAIN);
+        encoder.put_bytes(seed);
+        encoder.put_u32(counter);
+        let candidate = CompressedEdwardsY(encoder.hash().try_into().unwrap());
+
+        // Environ un hash sur deux est un point valide ; la multiplication par le
+        // cofacteur le ramène dans le sous-groupe principal
+        let Some(point) = candidate.decompress() else {
+            continue;
+        };
+        let point = point.mul_by_cofactor();
+        if point.is_small_order() {
+            continue;
+        }
+        if let Ok(address) = PublicKey::from_bytes(point.compress().as_bytes()) {
+            return address;
+        }
+    }
+    unreachable!()
+}
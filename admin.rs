@@ -0,0 +1,110 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tonic::{transport::Server, Request, Response, Status};
+
+use crate::config::ChainConfig;
+use crate::dryrun::{self, DryRun};
+use crate::gossip::Gossip;
+use crate::receipts::Receipt;
+use crate::reputation::{host, DEFAULT_BAN_DURATION};
+use crate::Blockchain;
+
+pub mod proto {
+    tonic::include_proto!("supplyx.admin");
+}
+
+use proto::admin_server::{Admin, AdminServer};
+use proto::{
+    Ban, BanPeerRequest, BanPeerResponse, Divergence, DryRunUpgradeReport, DryRunUpgradeRequest, ListPeersRequest, PeerList,
+    UnbanPeerRequest, UnbanPeerResponse,
+};
+
+pub struct AdminService {
+    gossip: Gossip,
+    blockchain: Arc<Mutex<Blockchain>>,
+}
+
+#[tonic::async_trait]
+impl Admin for AdminService {
+    async fn list_peers(&self, _request: Request<ListPeersRequest>) -> Result<Response<PeerList>, Status> {
+        let peers = self.gossip.peers().await.into_iter()
+            .map(|(remote, score)| proto::Peer { remote, penalty: score.penalty, violations: score.violations })
+            .collect();
+        let bans = self.gossip.bans().into_iter()
+            .map(|(host, expires_at)| Ban { host, expires_at })
+            .collect();
+        Ok(Response::new(PeerList { peers, bans }))
+    }
+
+    async fn ban_peer(&self, request: Request<BanPeerRequest>) -> Result<Response<BanPeerResponse>, Status> {
+        let request = request.into_inner();
+        if host(&request.host).is_empty() {
+            return Err(Status::invalid_argument("Missing host"));
+        }
+        let duration = match request.duration_secs {
+            0 => DEFAULT_BAN_DURATION,
+            secs => Duration::from_secs(secs),
+        };
+        self.gossip.ban(&request.host, duration).await;
+        let expires_at = self.gossip.bans().into_iter()
+            .find(|(banned, _)| banned == host(&request.host))
+            .map_or(0, |(_, expires_at)| expires_at);
+        Ok(Response::new(BanPeerResponse { expires_at }))
+    }
+
+    async fn unban_peer(&self, request: Request<UnbanPeerRequest>) -> Result<Response<UnbanPeerResponse>, Status> {
+        let removed = self.gossip.unban(&request.into_inner().host);
+        Ok(Response::new(UnbanPeerResponse { removed }))
+    }
+
+    async fn dry_run_upgrade(&self, request: Request<DryRunUpgradeRequest>) -> Result<Response<DryRunUpgradeReport>, Status> {
+        let candidate = ChainConfig::from_toml(&request.into_inner().candidate_config).map_err(Status::invalid_argument)?;
+        let dry_run = DryRun::capture(&*self.blockchain.lock().await).map_err(Status::failed_precondition)?;
+        // Réexécution hors du verrou : le nœud continue d'avancer pendant ce temps
+        let report = tokio::task::spawn_blocking(move || dry_run.run(&candidate))
+            .await
+            .map_err(|_| Status::internal("Dry run aborted"))?
+            .map_err(Status::invalid_argument)?;
+        let divergences = report.divergences.iter().map(|divergence| {
+            let height = divergence.height();
+            match divergence {
+                dryrun::Divergence::Rejected { reason, .. } => Divergence {
+                    height,
+                    kind: "rejected".to_string(),
+                    transaction_hash: Vec::new(),
+                    detail: reason.to_string(),
+                },
+                dryrun::Divergence::StateRoot { expected, actual, .. } => Divergence {
+                    height,
+                    kind: "state_root".to_string(),
+                    transaction_hash: Vec::new(),
+                    detail: format!("expected {}, got {}", hex::encode(expected), hex::encode(actual)),
+                },
+                dryrun::Divergence::Receipt { transaction_hash, expected, actual, .. } => Divergence {
+                    height,
+                    kind: "receipt".to_string(),
+                    transaction_hash: transaction_hash.clone(),
+                    detail: format!("expected {}, got {}", outcome(expected.as_ref()), outcome(actual.as_ref())),
+                },
+            }
+        }).collect();
+        Ok(Response::new(DryRunUpgradeReport { from_height: report.from_height, replayed: report.replayed, divergences }))
+    }
+}
+
+fn outcome(receipt: Option<&Receipt>) -> String {
+    match receipt {
+        Some(receipt) => format!("{:?} (gas {}, fee {})", receipt.status, receipt.gas_used, receipt.fee),
+        None => "no receipt".to_string(),
+    }
+}
+
+pub async fn serve(addr: SocketAddr, gossip: Gossip, blockchain: Arc<Mutex<Blockchain>>) -> Result<(), &'static str> {
+    Server::builder()
+        .add_service(AdminServer::new(AdminService { gossip, blockchain }))
+        .serve(addr)
+        .await
+        .map_err(|_| "Admin server failed")
+}
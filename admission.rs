@@ -0,0 +1,281 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use ed25519_dalek::PublicKey;
+use futures_util::future::join_all;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+// Délai accordé au service externe ; au-delà, la politique d'échec s'applique
+const HOOK_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Nombre maximal de décisions conservées
+const MAX_CACHE_ENTRIES: usize = 65_536;
+
+// Vérification externe des émetteurs (liste d'autorisation, KYC) à l'entrée
+// du mempool. Elle ne fait pas partie du consensus : un bloc contenant une
+// transaction refusée ici reste valide.
+#[async_trait]
+pub trait AdmissionHook: Send + Sync {
+    // `Ok(true)` : émetteur autorisé, `Ok(false)` : refusé, `Err` : service indisponible
+    async fn check(&self, sender: &PublicKey) -> Result<bool, String>;
+}
+
+// Décision lorsque le service ne répond pas (erreur ou délai dépassé)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FailureMode {
+    // Transaction admise
+    Open,
+    // Transaction refusée
+    Closed,
+}
+
+impl FromStr for FailureMode {
+    type Err = &'static str;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "open" => Ok(FailureMode::Open),
+            "closed" => Ok(FailureMode::Closed),
+            _ => Err("Unknown failure mode"),
+        }
+    }
+}
+
+// Contrôle d'admission : décisions du crochet mises en cache par émetteur
+// pendant `cache_ttl` ; les échecs du service ne sont pas mis en cache
+pub struct AdmissionGate {
+    hook: Arc<dyn AdmissionHook>,
+    failure_mode: FailureMode,
+    cache_ttl: Duration,
+    cache: Mutex<HashMap<PublicKey, (bool, Instant)>>,
+}
+
+impl AdmissionGate {
+    pub fn new(hook: Arc<dyn AdmissionHook>, failure_mode: FailureMode, cache_ttl: Duration) -> Self {
+        AdmissionGate { hook, failure_mode, cache_ttl, cache: Mutex::new(HashMap::new()) }
+    }
+
+    pub async fn check(&self, sender: &PublicKey) -> Result<(), &'static str> {
+        let now = Instant::now();
+        let cached = self.cache.lock().unwrap().get(sender).filter(|(_, expires)| *expires > now).map(|(allowed, _)| *allowed);
+        let allowed = match cached {
+            Some(allowed) => allowed,
+            None => match tokio::time::timeout(HOOK_TIMEOUT, self.hook.check(sender)).await {
+                Ok(Ok(allowed)) => {
+                    self.remember(*sender, allowed, now);
+                    allowed
+                }
+                Ok(Err(_)) | Err(_) => match self.failure_mode {
+                    FailureMode::Open => true,
+                    FailureMode::Closed => return Err("Admission check unavailable"),
+                },
+            },
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err("Sender not admitted")
+        }
+    }
+
+    // Décision pour chaque émetteur distinct, les appels au service étant concurrents
+    pub async fn check_senders(&self, senders: impl IntoIterator<Item = PublicKey>) -> HashMap<PublicKey, Result<(), &'static str>> {
+        let senders: HashSet<PublicKey> = senders.into_iter().collect();
+        let verdicts = join_all(senders.iter().map(|sender| self.check(sender))).await;
+        senders.into_iter().zip(verdicts).collect()
+    }
+
+    fn remember(&self, sender: PublicKey, allowed: bool, now: Instant) {
+        let mut cache = self.cache.lock().unwrap();
+        if cache.len() >= MAX_CACHE_ENTRIES {
+            cache.retain(|_, (_, expires)| *expires > now);
+            if cache.len() >= MAX_CACHE_ENTRIES {
+                cache.clear();
+            }
+        }
+        cache.insert(sender, (allowed, now + self.cache_ttl));
+    }
+}
+
+// Service HTTP : `GET <chemin>/<clé hex>` répond 200 si l'émetteur est
+// autorisé, 403 ou 404 s'il ne l'est pas
+pub struct HttpAllowlist {
+    host: String,
+    path: String,
+}
+
+impl HttpAllowlist {
+    // `url` de la forme `http://hôte:port[/chemin]`
+    pub fn new(url: &str) -> Result<Self, &'static str> {
+        let rest = url.strip_prefix("http://").ok_or("Admission URL must use http://")?;
+        let (host, path) = match rest.find('/') {
+            Some(slash) => (&rest[..slash], rest[slash..].trim_end_matches('/')),
+            None => (rest, ""),
+        };
+        if host.is_empty() {
+            return Err("Admission URL without host");
+        }
+        Ok(HttpAllowlist { host: host.to_string(), path: path.to_string() })
+    }
+}
+
+#[async_trait]
+impl AdmissionHook for HttpAllowlist {
+    async fn check(&self, sender: &PublicKey) -> Result<bool, String> {
+        let mut stream = TcpStream::connect(&self.host).await.map_err(|e| e.to_string())?;
+        let request = format!(
+            "GET {}/{} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            self.path, hex::encode(sender.as_bytes()), self.host,
+        );
+        stream.write_all(request.as_bytes()).await.map_err(|e| e.to_string())?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.map_err(|e| e.to_string())?;
+        let status_line = response.split(|byte| *byte == b'\n').next().unwrap_or_default();
+        let status = String::from_utf8_lossy(status_line);
+        match status.split_whitespace().nth(1) {
+            Some("200") => Ok(true),
+            Some("403") | Some("404") => Ok(false),
+            _ => Err(format!("Unexpected admission response: {}", status.trim())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use ed25519_dalek::SecretKey;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    fn key(seed: u8) -> PublicKey {
+        PublicKey::from(&SecretKey::from_bytes(&[seed; 32]).unwrap())
+    }
+
+    // Réponse du service par émetteur ; `None` : le service ne répond jamais
+    #[derive(Default)]
+    struct ScriptedHook {
+        answers: Mutex<HashMap<PublicKey, Option<Result<bool, String>>>>,
+        calls: AtomicUsize,
+    }
+
+    impl ScriptedHook {
+        fn answer(&self, sender: PublicKey, answer: Option<Result<bool, String>>) {
+            self.answers.lock().unwrap().insert(sender, answer);
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl AdmissionHook for ScriptedHook {
+        async fn check(&self, sender: &PublicKey) -> Result<bool, String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let answer = self.answers.lock().unwrap().get(sender).cloned().unwrap_or(Some(Ok(true)));
+            match answer {
+                Some(answer) => answer,
+                None => std::future::pending().await,
+            }
+        }
+    }
+
+    fn gate(hook: &Arc<ScriptedHook>, failure_mode: FailureMode, cache_ttl: Duration) -> AdmissionGate {
+        AdmissionGate::new(hook.clone(), failure_mode, cache_ttl)
+    }
+
+    #[tokio::test]
+    async fn decisions_are_cached_but_failures_are_not() {
+        let hook = Arc::new(ScriptedHook::default());
+        let (allowed, denied, flaky) = (key(1), key(2), key(3));
+        hook.answer(denied, Some(Ok(false)));
+        hook.answer(flaky, Some(Err("unavailable".to_string())));
+        let cached = gate(&hook, FailureMode::Open, Duration::from_secs(60));
+
+        for _ in 0..3 {
+            assert_eq!(cached.check(&allowed).await, Ok(()));
+            assert_eq!(cached.check(&denied).await, Err("Sender not admitted"));
+        }
+        assert_eq!(hook.calls(), 2);
+
+        // La décision en cache l'emporte sur un changement du service jusqu'à expiration
+        hook.answer(allowed, Some(Ok(false)));
+        assert_eq!(cached.check(&allowed).await, Ok(()));
+        assert_eq!(hook.calls(), 2);
+
+        assert_eq!(cached.check(&flaky).await, Ok(()));
+        hook.answer(flaky, Some(Ok(false)));
+        assert_eq!(cached.check(&flaky).await, Err("Sender not admitted"));
+        assert_eq!(hook.calls(), 4);
+
+        let uncached = gate(&hook, FailureMode::Open, Duration::ZERO);
+        assert_eq!(uncached.check(&allowed).await, Err("Sender not admitted"));
+        assert_eq!(uncached.check(&allowed).await, Err("Sender not admitted"));
+        assert_eq!(hook.calls(), 6);
+    }
+
+    #[tokio::test]
+    async fn unavailable_service_follows_the_failure_mode() {
+        let hook = Arc::new(ScriptedHook::default());
+        let (failing, silent) = (key(1), key(2));
+        hook.answer(failing, Some(Err("connection refused".to_string())));
+        hook.answer(silent, None);
+        let open = gate(&hook, FailureMode::Open, Duration::from_secs(60));
+        let closed = gate(&hook, FailureMode::Closed, Duration::from_secs(60));
+
+        assert_eq!(open.check(&failing).await, Ok(()));
+        assert_eq!(closed.check(&failing).await, Err("Admission check unavailable"));
+
+        // Le délai dépassé compte comme une panne
+        let (open, closed) = tokio::join!(open.check(&silent), closed.check(&silent));
+        assert_eq!((open, closed), (Ok(()), Err("Admission check unavailable")));
+        assert_eq!(hook.calls(), 4);
+        assert_eq!("closed".parse(), Ok(FailureMode::Closed));
+        assert_eq!("fail".parse::<FailureMode>(), Err("Unknown failure mode"));
+    }
+
+    #[tokio::test]
+    async fn each_distinct_sender_is_checked_once() {
+        let hook = Arc::new(ScriptedHook::default());
+        hook.answer(key(2), Some(Ok(false)));
+        let gate = gate(&hook, FailureMode::Closed, Duration::from_secs(60));
+
+        let verdicts = gate.check_senders([key(1), key(2), key(1), key(2), key(3)]).await;
+        assert_eq!(verdicts.len(), 3);
+        assert_eq!((verdicts[&key(1)], verdicts[&key(2)], verdicts[&key(3)]), (Ok(()), Err("Sender not admitted"), Ok(())));
+        assert_eq!(hook.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn http_allowlist_maps_status_codes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut paths = Vec::new();
+            for status in ["200 OK", "403 Forbidden", "404 Not Found", "500 Internal Server Error"] {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![0; 1024];
+                let read = stream.read(&mut request).await.unwrap();
+                let request = String::from_utf8_lossy(&request[..read]).to_string();
+                paths.push(request.split_whitespace().nth(1).unwrap().to_string());
+                stream.write_all(format!("HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status).as_bytes()).await.unwrap();
+            }
+            paths
+        });
+
+        let allowlist = HttpAllowlist::new(&format!("http://{}/kyc/", address)).unwrap();
+        assert_eq!(allowlist.check(&key(1)).await, Ok(true));
+        assert_eq!(allowlist.check(&key(1)).await, Ok(false));
+        assert_eq!(allowlist.check(&key(1)).await, Ok(false));
+        assert!(allowlist.check(&key(1)).await.unwrap_err().contains("500"));
+        assert_eq!(server.await.unwrap(), vec![format!("/kyc/{}", hex::encode(key(1).as_bytes())); 4]);
+
+        assert_eq!(HttpAllowlist::new("https://kyc.example").err(), Some("Admission URL must use http://"));
+        assert_eq!(HttpAllowlist::new("http:///path").err(), Some("Admission URL without host"));
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::assets::{asset_events, AssetEvent, AssetEventKind};
+use crate::mempool::kind_name;
+use crate::{Block, EPOCH_LENGTH};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+// Agrégats d'une période (jour ou époque)
+#[derive(Default)]
+pub struct Period {
+    pub blocks: u64,
+    // (nombre, volume) des transactions publiques par type
+    pub volume: BTreeMap<&'static str, (u64, u64)>,
+    pub private_transactions: u64,
+    pub governance_transactions: u64,
+    pub active_addresses: HashSet<[u8; 32]>,
+    pub asset_registrations: u64,
+    pub attestations: u64,
+    // Longueur moyenne de la chaîne de garde (attestations par actif enregistré)
+    // à la fin de la période, depuis la genèse
+    pub custody_chain_length: f64,
+}
+
+impl Period {
+    fn add(&mut self, block: &Block, events: &[AssetEvent], custody_chain_length: f64) {
+        self.blocks += 1;
+        for transaction in &block.transactions {
+            let (count, volume) = self.volume.entry(kind_name(&transaction.kind)).or_insert((0, 0));
+            *count += 1;
+            *volume = volume.saturating_add(transaction.amount);
+            self.active_addresses.insert(transaction.sender.to_bytes());
+            self.active_addresses.insert(transaction.recipient.to_bytes());
+        }
+        self.private_transactions += block.private_transactions.len() as u64;
+        self.governance_transactions += block.governance_transactions.len() as u64;
+        self.asset_registrations += events.iter().filter(|event| event.kind == AssetEventKind::Registered).count() as u64;
+        self.attestations += events.iter().filter(|event| event.kind == AssetEventKind::Attested).count() as u64;
+        self.custody_chain_length = custody_chain_length;
+    }
+}
+
+// Agrégats précalculés par jour (UTC, d'après l'horodatage des blocs) et par époque
+#[derive(Default)]
+pub struct Aggregates {
+    pub daily: BTreeMap<u64, Period>,
+    pub epochs: BTreeMap<u64, Period>,
+    // Attestations par actif enregistré
+    custody: HashMap<Vec<u8>, u64>,
+}
+
+impl Aggregates {
+    pub fn add(&mut self, block: &Block) {
+        let events = asset_events(block);
+        for event in &events {
+            match event.kind {
+                AssetEventKind::Registered => {
+                    self.custody.insert(event.asset.clone(), 0);
+                }
+                AssetEventKind::Attested => {
+                    if let Some(attestations) = self.custody.get_mut(&event.asset) {
+                        *attestations += 1;
+                    }
+                }
+                AssetEventKind::AccessGranted | AssetEventKind::AccessRevoked => {}
+            }
+        }
+        let custody_chain_length = match self.custody.len() {
+            0 => 0.0,
+            assets => self.custody.values().sum::<u64>() as f64 / assets as f64,
+        };
+        self.daily.entry(block.timestamp / SECONDS_PER_DAY).or_default().add(block, &events, custody_chain_length);
+        self.epochs.entry(block.index / EPOCH_LENGTH).or_default().add(block, &events, custody_chain_length);
+    }
+
+    // Base SQLite (`.db`, `.sqlite`, `.sqlite3`) ou DuckDB (`.duckdb`) chargée par
+    // le client en ligne de commande du moteur (`sqlite3`, `duckdb`), sinon le
+    // script SQL lui-même
+    pub fn export(&self, path: &Path) -> Result<(), String> {
+        let script = self.to_sql();
+        let Some(engine) = database_engine(path) else {
+            return std::fs::write(path, script).map_err(|e| e.to_string());
+        };
+        let mut child = Command::new(engine)
+            .arg(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Cannot run {}: {}", engine, e))?;
+        child.stdin.take().ok_or("No stdin")?.write_all(script.as_bytes()).map_err(|e| e.to_string())?;
+        let output = child.wait_with_output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(format!("{} failed: {}", engine, String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(())
+    }
+
+    // Script SQL chargeable tel quel par SQLite (`sqlite3 chain.db < fichier`)
+    // ou DuckDB (`duckdb chain.duckdb < fichier`)
+    pub fn to_sql(&self) -> String {
+        let mut out = String::from("BEGIN;\n");
+        for (table, key) in [("daily", "day DATE"), ("epoch", "epoch BIGINT")] {
+            let _ = writeln!(out, "DROP TABLE IF EXISTS {}_activity;", table);
+            let _ = writeln!(
+                out,
+                "CREATE TABLE {}_activity ({}, blocks BIGINT, private_transactions BIGINT, governance_transactions BIGINT, active_addresses BIGINT, asset_registrations BIGINT, attestations BIGINT, custody_chain_length DOUBLE);",
+                table, key,
+            );
+            let _ = writeln!(out, "DROP TABLE IF EXISTS {}_volume;", table);
+            let _ = writeln!(out, "CREATE TABLE {}_volume ({}, kind TEXT, transactions BIGINT, volume BIGINT);", table, key);
+        }
+        for (day, period) in &self.daily {
+            insert(&mut out, "daily", &format!("'{}'", civil_date(*day)), period);
+        }
+        for (epoch, period) in &self.epochs {
+            insert(&mut out, "epoch", &epoch.to_string(), period);
+        }
+        out.push_str("COMMIT;\n");
+        out
+    }
+}
+
+fn insert(out: &mut String, table: &str, key: &str, period: &Period) {
+    let _ = writeln!(
+        out,
+        "INSERT INTO {}_activity VALUES ({}, {}, {}, {}, {}, {}, {}, {});",
+        table, key, period.blocks, period.private_transactions, period.governance_transactions, period.active_addresses.len(),
+        period.asset_registrations, period.attestations, period.custody_chain_length,
+    );
+    for (kind, (count, volume)) in &period.volume {
+        let _ = writeln!(out, "INSERT INTO {}_volume VALUES ({}, '{}', {}, {});", table, key, kind, count, volume);
+    }
+}
+
+fn database_engine(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()? {
+        "db" | "sqlite" | "sqlite3" => Some("sqlite3"),
+        "duckdb" => Some("duckdb"),
+        _ => None,
+    }
+}
+
+// Date grégorienne (AAAA-MM-JJ) du jour `days` depuis l'époque Unix
+fn civil_date(days: u64) -> String {
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+
+    use super::{database_engine, Aggregates};
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::privacy::EncryptedPayload;
+    use crate::txbuilder::TxBuilder;
+    use crate::Blockchain;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    fn submit(chain: &mut Blockchain, signer: &Keypair, builder: TxBuilder) -> Vec<u8> {
+        let transaction = builder.chain_id(chain.config.chain_id).nonce(chain.next_nonce(&signer.public)).timestamp(chain.now()).sign(signer).unwrap();
+        let hash = transaction.hash();
+        chain.add_transaction(transaction).unwrap();
+        hash
+    }
+
+    #[test]
+    fn aggregates_asset_activity() {
+        let (validator, owner) = (keypair(1), keypair(2));
+        let clock = MockClock::new(Duration::from_secs(1_700_000_000));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain.allocate(&owner.public, 1_000_000);
+        let first = submit(&mut chain, &owner, TxBuilder::register_asset(Vec::new()).to(owner.public));
+        submit(&mut chain, &owner, TxBuilder::register_asset(Vec::new()).to(owner.public));
+        for slot in 1..=4 {
+            if slot > 1 {
+                let payload = EncryptedPayload::seal_to(&[owner.public], b"received at the warehouse").unwrap();
+                submit(&mut chain, &owner, TxBuilder::attest(first.clone(), payload).to(owner.public));
+            }
+            clock.set(Duration::from_secs(1_700_000_000 + slot * 5));
+            chain.validate_and_create_block(&validator, slot).unwrap();
+        }
+
+        let mut aggregates = Aggregates::default();
+        for block in &chain.chain {
+            aggregates.add(block);
+        }
+        let (_, epoch) = aggregates.epochs.iter().next().unwrap();
+        assert_eq!((epoch.blocks, epoch.asset_registrations, epoch.attestations), (4, 2, 3));
+        assert_eq!(epoch.custody_chain_length, 1.5);
+        assert!(aggregates.to_sql().contains("INSERT INTO epoch_activity VALUES (0, 4, 0, 0, 1, 2, 3, 1.5);"));
+    }
+
+    #[test]
+    fn picks_the_database_engine_from_the_extension() {
+        assert_eq!(database_engine(Path::new("chain.db")), Some("sqlite3"));
+        assert_eq!(database_engine(Path::new("chain.sqlite3")), Some("sqlite3"));
+        assert_eq!(database_engine(Path::new("chain.duckdb")), Some("duckdb"));
+        assert_eq!(database_engine(Path::new("analytics.sql")), None);
+        assert_eq!(database_engine(Path::new("analytics")), None);
+    }
+}
@@ -0,0 +1,381 @@
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+
+use crate::encoding::{Encoder, EXTERNAL_ANCHOR_DOMAIN};
+use crate::events::ChainEvent;
+use crate::Block;
+
+// Délai accordé au nœud de la chaîne externe pour chaque appel
+const RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Données publiées sur la chaîne externe : dernier bloc finalisé (checkpoint)
+// du réseau `chain_id`. Encodées, elles tiennent dans une sortie OP_RETURN
+// (80 octets au plus).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnchorPayload {
+    pub chain_id: u64,
+    pub height: u64,
+    pub block_hash: Vec<u8>,
+}
+
+impl AnchorPayload {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(EXTERNAL_ANCHOR_DOMAIN);
+        encoder.put_u64(self.chain_id);
+        encoder.put_u64(self.height);
+        encoder.put_bytes(&self.block_hash);
+        encoder.finish()
+    }
+
+    // Le bloc ancré doit être finalisé dans la chaîne rejouée et avoir le hash annoncé
+    pub fn verify(&self, chain_id: u64, blocks: &[Block], finalized_height: Option<u64>) -> Result<(), &'static str> {
+        if self.chain_id != chain_id {
+            return Err("Anchor for another chain");
+        }
+        if finalized_height.is_none_or(|finalized| self.height > finalized) {
+            return Err("Anchored block not finalized");
+        }
+        let block = blocks.iter().find(|block| block.index == self.height).ok_or("Anchored block missing")?;
+        if block.current_hash != self.block_hash {
+            return Err("Anchored block hash mismatch");
+        }
+        Ok(())
+    }
+}
+
+// Chaîne publique sur laquelle les checkpoints sont ancrés. Les transactions
+// externes sont payées par un portefeuille géré par le nœud de cette chaîne.
+#[async_trait]
+pub trait Anchorer: Send + Sync {
+    fn network(&self) -> &'static str;
+    // Publie `payload` ; retourne la référence (hash) de la transaction externe
+    async fn publish(&self, payload: &[u8]) -> Result<String, String>;
+    // Données portées par la transaction externe `reference`, une fois incluse dans un bloc
+    async fn fetch(&self, reference: &str) -> Result<Vec<u8>, String>;
+}
+
+// Ancrage publié : réseau externe, référence de la transaction et données
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnchorRecord {
+    pub network: String,
+    pub reference: String,
+    pub payload: AnchorPayload,
+}
+
+impl AnchorRecord {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "network": self.network,
+            "reference": self.reference,
+            "chain_id": self.payload.chain_id,
+            "height": self.payload.height,
+            "block_hash": hex::encode(&self.payload.block_hash),
+        })
+    }
+
+    pub fn from_json(record: &Value) -> Result<AnchorRecord, String> {
+        let text = |name: &str| record[name].as_str().map(str::to_string).ok_or(format!("missing field {}", name));
+        let number = |name: &str| record[name].as_u64().ok_or(format!("missing field {}", name));
+        let block_hash = hex::decode(text("block_hash")?).map_err(|_| "invalid block_hash".to_string())?;
+        let payload = AnchorPayload { chain_id: number("chain_id")?, height: number("height")?, block_hash };
+        Ok(AnchorRecord { network: text("network")?, reference: text("reference")?, payload })
+    }
+
+    // Les données de la transaction externe doivent être exactement celles de l'ancrage
+    pub async fn check_published(&self, anchorer: &dyn Anchorer) -> Result<(), String> {
+        if anchorer.network() != self.network {
+            return Err(format!("Anchor published on {}, not {}", self.network, anchorer.network()));
+        }
+        if anchorer.fetch(&self.reference).await? != self.payload.encode() {
+            return Err("External transaction does not carry the anchor".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Journal des ancrages publiés, un enregistrement JSON par ligne
+pub fn read_records(input: impl BufRead) -> Result<Vec<AnchorRecord>, String> {
+    let mut records = Vec::new();
+    for (number, line) in input.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Value = serde_json::from_str(&line).map_err(|e| format!("line {}: {}", number + 1, e))?;
+        records.push(AnchorRecord::from_json(&record).map_err(|e| format!("line {}: {}", number + 1, e))?);
+    }
+    Ok(records)
+}
+
+fn append_record(log: &PathBuf, record: &AnchorRecord) -> Result<(), String> {
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(log).map_err(|e| format!("{}: {}", log.display(), e))?;
+    writeln!(file, "{}", record.to_json()).map_err(|e| format!("{}: {}", log.display(), e))
+}
+
+// Ancre le checkpoint finalisé au plus une fois par `interval` : un checkpoint
+// finalisé avant la fin de l'intervalle attend le suivant. Un échec de
+// publication est réessayé au checkpoint suivant.
+pub fn follow(anchorer: Arc<dyn Anchorer>, chain_id: u64, interval: Duration, log: PathBuf, mut events: broadcast::Receiver<ChainEvent>) {
+    tokio::spawn(async move {
+        let mut last_anchor: Option<Instant> = None;
+        loop {
+            let (height, block_hash) = match events.recv().await {
+                Ok(ChainEvent::CheckpointFinalized { height, block_hash }) => (height, block_hash),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(missed, "Anchoring fell behind chain events");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if last_anchor.is_some_and(|last| last.elapsed() < interval) {
+                continue;
+            }
+            let payload = AnchorPayload { chain_id, height, block_hash };
+            match anchorer.publish(&payload.encode()).await {
+                Ok(reference) => {
+                    info!(height, network = anchorer.network(), reference, "Checkpoint anchored");
+                    let record = AnchorRecord { network: anchorer.network().to_string(), reference, payload };
+                    if let Err(e) = append_record(&log, &record) {
+                        warn!(error = e, "Anchor log not written");
+                    }
+                    last_anchor = Some(Instant::now());
+                }
+                Err(e) => warn!(height, network = anchorer.network(), error = e, "Checkpoint anchoring failed"),
+            }
+        }
+    });
+}
+
+// Ancrage dans une sortie OP_RETURN, par le portefeuille de bitcoind (JSON-RPC) ;
+// la vérification demande `txindex=1` ou une transaction du portefeuille
+pub struct BitcoinAnchorer {
+    rpc: JsonRpc,
+}
+
+impl BitcoinAnchorer {
+    pub fn new(url: &str) -> Result<Self, &'static str> {
+        Ok(BitcoinAnchorer { rpc: JsonRpc::new(url)? })
+    }
+}
+
+#[async_trait]
+impl Anchorer for BitcoinAnchorer {
+    fn network(&self) -> &'static str {
+        "bitcoin"
+    }
+
+    async fn publish(&self, payload: &[u8]) -> Result<String, String> {
+        let raw = self.rpc.call("createrawtransaction", json!([[], { "data": hex::encode(payload) }])).await?;
+        let funded = self.rpc.call("fundrawtransaction", json!([raw])).await?;
+        let signed = self.rpc.call("signrawtransactionwithwallet", json!([funded["hex"]])).await?;
+        if signed["complete"] != true {
+            return Err("Anchor transaction not fully signed".to_string());
+        }
+        let txid = self.rpc.call("sendrawtransaction", json!([signed["hex"]])).await?;
+        txid.as_str().map(str::to_string).ok_or("Invalid transaction id".to_string())
+    }
+
+    async fn fetch(&self, reference: &str) -> Result<Vec<u8>, String> {
+        let transaction = self.rpc.call("getrawtransaction", json!([reference, true])).await?;
+        if transaction["blockhash"].is_null() {
+            return Err("Anchor transaction not yet confirmed".to_string());
+        }
+        let outputs = transaction["vout"].as_array().ok_or("Invalid transaction")?;
+        let script = outputs.iter()
+            .find(|output| output["scriptPubKey"]["type"] == "nulldata")
+            .and_then(|output| output["scriptPubKey"]["hex"].as_str())
+            .ok_or("No OP_RETURN output")?;
+        let script = hex::decode(script).map_err(|_| "Invalid OP_RETURN script")?;
+        op_return_data(&script).map(<[u8]>::to_vec).ok_or("Invalid OP_RETURN script".to_string())
+    }
+}
+
+// Donnée poussée par un script `OP_RETURN <push>`
+fn op_return_data(script: &[u8]) -> Option<&[u8]> {
+    let (&opcode, rest) = script.strip_prefix(&[0x6a])?.split_first()?;
+    let (length, data) = match opcode {
+        0x01..=0x4b => (opcode as usize, rest),
+        0x4c => (*rest.first()? as usize, &rest[1..]),
+        0x4d => (u16::from_le_bytes([*rest.first()?, *rest.get(1)?]) as usize, &rest[2..]),
+        _ => return None,
+    };
+    (data.len() == length).then_some(data)
+}
+
+// Ancrage dans les données d'appel d'une transaction de `account` vers
+// lui-même, signée par le nœud Ethereum (compte déverrouillé ou clef)
+pub struct EthereumAnchorer {
+    rpc: JsonRpc,
+    account: String,
+}
+
+impl EthereumAnchorer {
+    pub fn new(url: &str, account: &str) -> Result<Self, &'static str> {
+        let digits = account.strip_prefix("0x").ok_or("Ethereum account must be 0x-prefixed")?;
+        if digits.len() != 40 || hex::decode(digits).is_err() {
+            return Err("Invalid Ethereum account");
+        }
+        Ok(EthereumAnchorer { rpc: JsonRpc::new(url)?, account: account.to_string() })
+    }
+}
+
+#[async_trait]
+impl Anchorer for EthereumAnchorer {
+    fn network(&self) -> &'static str {
+        "ethereum"
+    }
+
+    async fn publish(&self, payload: &[u8]) -> Result<String, String> {
+        let transaction = json!({ "from": self.account, "to": self.account, "data": format!("0x{}", hex::encode(payload)) });
+        let hash = self.rpc.call("eth_sendTransaction", json!([transaction])).await?;
+        hash.as_str().map(str::to_string).ok_or("Invalid transaction hash".to_string())
+    }
+
+    async fn fetch(&self, reference: &str) -> Result<Vec<u8>, String> {
+        let transaction = self.rpc.call("eth_getTransactionByHash", json!([reference])).await?;
+        if transaction.is_null() {
+            return Err("Unknown anchor transaction".to_string());
+        }
+        if transaction["blockNumber"].is_null() {
+            return Err("Anchor transaction not yet mined".to_string());
+        }
+        let input = transaction["input"].as_str().and_then(|input| input.strip_prefix("0x")).ok_or("Invalid transaction input")?;
+        hex::decode(input).map_err(|_| "Invalid transaction input".to_string())
+    }
+}
+
+// Client JSON-RPC sur HTTP : `url` de la forme `http://[utilisateur:mot de passe@]hôte:port[/chemin]`
+struct JsonRpc {
+    host: String,
+    path: String,
+    credentials: Option<String>,
+}
+
+impl JsonRpc {
+    fn new(url: &str) -> Result<Self, &'static str> {
+        let rest = url.strip_prefix("http://").ok_or("Anchor URL must use http://")?;
+        let (authority, path) = match rest.find('/') {
+            Some(slash) => (&rest[..slash], &rest[slash..]),
+            None => (rest, "/"),
+        };
+        let (credentials, host) = match authority.rsplit_once('@') {
+            Some((credentials, host)) => (Some(base64(credentials.as_bytes())), host),
+            None => (None, authority),
+        };
+        if host.is_empty() {
+            return Err("Anchor URL without host");
+        }
+        Ok(JsonRpc { host: host.to_string(), path: path.to_string(), credentials })
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+        tokio::time::timeout(RPC_TIMEOUT, self.request(method, params)).await
+            .map_err(|_| format!("{}: timed out", method))?
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }).to_string();
+        let authorization = self.credentials.as_ref().map(|credentials| format!("Authorization: Basic {}\r\n", credentials)).unwrap_or_default();
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path, self.host, authorization, body.len(), body,
+        );
+        let mut stream = TcpStream::connect(&self.host).await.map_err(|e| e.to_string())?;
+        stream.write_all(request.as_bytes()).await.map_err(|e| e.to_string())?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.map_err(|e| e.to_string())?;
+        let start = response.windows(4).position(|window| window == b"\r\n\r\n").ok_or("Malformed JSON-RPC response")? + 4;
+        let reply: Value = serde_json::from_slice(&response[start..]).map_err(|e| format!("{}: {}", method, e))?;
+        if !reply["error"].is_null() {
+            let message = reply["error"]["message"].as_str().unwrap_or("unknown error");
+            return Err(format!("{}: {}", method, message));
+        }
+        Ok(reply["result"].clone())
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::new();
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for position in 0..4 {
+            if position <= chunk.len() {
+                output.push(ALPHABET[(triple >> (18 - 6 * position)) as usize & 0x3f] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Chaîne externe simulée : une seule transaction, aux données fixées
+    struct FixedAnchorer(Vec<u8>);
+
+    #[async_trait]
+    impl Anchorer for FixedAnchorer {
+        fn network(&self) -> &'static str {
+            "bitcoin"
+        }
+
+        async fn publish(&self, _payload: &[u8]) -> Result<String, String> {
+            Ok("txid".to_string())
+        }
+
+        async fn fetch(&self, _reference: &str) -> Result<Vec<u8>, String> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn record() -> AnchorRecord {
+        let payload = AnchorPayload { chain_id: 1, height: 300, block_hash: vec![7; 32] };
+        AnchorRecord { network: "bitcoin".to_string(), reference: "txid".to_string(), payload }
+    }
+
+    #[test]
+    fn payload_fits_in_an_op_return_output() {
+        let data = record().payload.encode();
+        assert!(data.len() <= 80);
+
+        // Au-delà de 75 octets, la donnée est poussée par OP_PUSHDATA1
+        let mut script = vec![0x6a, 0x4c, data.len() as u8];
+        script.extend_from_slice(&data);
+        assert_eq!(op_return_data(&script), Some(&data[..]));
+        assert_eq!(op_return_data(&script[..script.len() - 1]), None);
+    }
+
+    #[test]
+    fn records_round_trip_through_the_log() {
+        let line = format!("{}\n\n{}\n", record().to_json(), record().to_json());
+        assert_eq!(read_records(line.as_bytes()).unwrap(), vec![record(), record()]);
+        assert!(read_records("{}".as_bytes()).unwrap_err().starts_with("line 1"));
+    }
+
+    #[tokio::test]
+    async fn checks_the_published_data() {
+        let record = record();
+        assert_eq!(record.check_published(&FixedAnchorer(record.payload.encode())).await, Ok(()));
+
+        let mut other = record.payload.clone();
+        other.height += 1;
+        assert_eq!(
+            record.check_published(&FixedAnchorer(other.encode())).await,
+            Err("External transaction does not carry the anchor".to_string()),
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use sha3::{Digest, Sha3_256};
+
+// Longueur maximale d'un CID IPFS (un CIDv1 en base32 sur SHA2-256 en compte 59)
+pub const MAX_CID_LENGTH: usize = 128;
+
+// Empreinte SHA3-256 d'un document, calculable hors du nœud (`sha3sum -a 256`)
+pub fn document_hash(document: &[u8]) -> Vec<u8> {
+    Sha3_256::digest(document).to_vec()
+}
+
+// Règles d'un ancrage : empreinte de 32 octets, CID éventuel non vide et en
+// caractères alphanumériques ASCII (encodages base32 et base58 des CID)
+pub fn check(document: &[u8], cid: Option<&str>) -> Result<(), &'static str> {
+    if document.len() != 32 {
+        return Err("Invalid document hash");
+    }
+    if let Some(cid) = cid {
+        if cid.is_empty() || cid.len() > MAX_CID_LENGTH || !cid.bytes().all(|byte| byte.is_ascii_alphanumeric()) {
+            return Err("Invalid document CID");
+        }
+    }
+    Ok(())
+}
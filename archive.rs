@@ -0,0 +1,76 @@
+use std::collections::{HashMap, HashSet};
+use ed25519_dalek::PublicKey;
+
+use crate::ledger::Ledger;
+
+// Historique des soldes (mode archive) : pour chaque compte, son solde après
+// chaque bloc qui l'a modifié, depuis la hauteur à laquelle l'archive a démarré
+#[derive(Debug)]
+pub struct BalanceArchive {
+    since: u64,
+    // (hauteur, solde après ce bloc), par hauteur croissante
+    history: HashMap<PublicKey, Vec<(u64, u64)>>,
+}
+
+impl BalanceArchive {
+    // Archive démarrant avec les soldes de `ledger` à la hauteur `since`
+    pub fn new(ledger: &Ledger, since: u64) -> Self {
+        let history = ledger.iter().map(|(account, balance)| (*account, vec![(since, balance)])).collect();
+        BalanceArchive { since, history }
+    }
+
+    // Soldes après le bloc `height` des comptes qu'il a modifiés
+    pub fn record(&mut self, height: u64, ledger: &Ledger, accounts: &HashSet<PublicKey>) {
+        for account in accounts {
+            let versions = self.history.entry(*account).or_default();
+            let balance = ledger.balance(account);
+            match versions.last_mut() {
+                Some((last, previous)) if *last == height => *previous = balance,
+                _ => versions.push((height, balance)),
+            }
+        }
+    }
+
+    // Solde après le bloc `height` ; la hauteur doit être couverte par l'archive
+    pub fn balance_at(&self, account: &PublicKey, height: u64) -> Result<u64, &'static str> {
+        if height < self.since {
+            return Err("Height before the start of the archive");
+        }
+        let Some(versions) = self.history.get(account) else {
+            return Ok(0);
+        };
+        let count = versions.partition_point(|(version, _)| *version <= height);
+        Ok(count.checked_sub(1).map_or(0, |index| versions[index].1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::Keypair;
+    use rand::rngs::OsRng;
+
+    use super::*;
+
+    #[test]
+    fn keeps_the_last_balance_of_each_block() {
+        let (alice, bob) = (Keypair::generate(&mut OsRng).public, Keypair::generate(&mut OsRng).public);
+        let mut ledger = Ledger::default();
+        ledger.credit(&alice, 500);
+        let mut archive = BalanceArchive::new(&ledger, 10);
+
+        ledger.set_balance(&alice, 400);
+        ledger.credit(&bob, 100);
+        archive.record(12, &ledger, &HashSet::from([alice, bob]));
+        // Un second enregistrement du même bloc remplace le premier
+        ledger.set_balance(&alice, 350);
+        archive.record(12, &ledger, &HashSet::from([alice]));
+        ledger.set_balance(&bob, 0);
+        archive.record(15, &ledger, &HashSet::from([bob]));
+
+        let balances = |account| (10..=16).map(|height| archive.balance_at(account, height).unwrap()).collect::<Vec<_>>();
+        assert_eq!(balances(&alice), vec![500, 500, 350, 350, 350, 350, 350]);
+        assert_eq!(balances(&bob), vec![0, 0, 100, 100, 100, 0, 0]);
+        assert_eq!(archive.balance_at(&alice, 9), Err("Height before the start of the archive"));
+        assert_eq!(archive.balance_at(&Keypair::generate(&mut OsRng).public, 12), Ok(0));
+    }
+}
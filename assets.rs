@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use ed25519_dalek::PublicKey;
+
+use crate::encoding::{Decoder, Encoder};
+use crate::{Block, Transaction, TransactionKind};
+
+// Lecteurs d'un actif au plus, propriétaire non compris
+pub const MAX_ASSET_READERS: usize = 64;
+
+// Actif enregistré par `RegisterAsset` : ses attestations sont chiffrées pour
+// le propriétaire et les lecteurs autorisés au moment de leur inclusion, et
+// seul le propriétaire accorde ou retire l'accès
+#[derive(Clone, Debug, PartialEq)]
+pub struct Asset {
+    pub owner: PublicKey,
+    // Triés, sans doublon ni propriétaire
+    pub readers: Vec<PublicKey>,
+}
+
+impl Asset {
+    // Destinataires d'une attestation, dans l'ordre des clés chiffrées
+    pub fn participants(&self) -> Vec<PublicKey> {
+        let mut participants = self.readers.clone();
+        participants.push(self.owner);
+        participants.sort_by_key(|key| key.to_bytes());
+        participants
+    }
+
+    pub fn is_participant(&self, key: &PublicKey) -> bool {
+        self.owner == *key || self.readers.contains(key)
+    }
+}
+
+// Lecteurs initiaux d'un actif : triés, sans doublon ni propriétaire
+pub fn check_readers(owner: &PublicKey, readers: &[PublicKey]) -> Result<(), &'static str> {
+    if readers.len() > MAX_ASSET_READERS {
+        return Err("Too many asset readers");
+    }
+    if readers.windows(2).any(|pair| pair[0].to_bytes() >= pair[1].to_bytes()) {
+        return Err("Asset readers not in canonical order");
+    }
+    if readers.contains(owner) {
+        return Err("Asset owner listed as a reader");
+    }
+    Ok(())
+}
+
+// Changement d'un actif porté par une transaction incluse
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AssetEventKind {
+    Registered,
+    AccessGranted,
+    AccessRevoked,
+    Attested,
+}
+
+impl AssetEventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AssetEventKind::Registered => "registered",
+            AssetEventKind::AccessGranted => "access_granted",
+            AssetEventKind::AccessRevoked => "access_revoked",
+            AssetEventKind::Attested => "attested",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetEvent {
+    pub asset: Vec<u8>,
+    pub kind: AssetEventKind,
+    // Propriétaire à l'enregistrement, lecteur concerné par un accès, auteur d'une attestation
+    pub account: PublicKey,
+    pub block_index: u64,
+    pub transaction_hash: Vec<u8>,
+}
+
+// Événements des actifs d'un bloc, dans l'ordre d'application (transactions
+// des lots atomiques comprises)
+pub fn asset_events(block: &Block) -> Vec<AssetEvent> {
+    block.transactions.iter()
+        .flat_map(|transaction| std::iter::once(transaction).chain(transaction.inner_transactions()))
+        .filter_map(|transaction| {
+            let (asset, kind) = match &transaction.kind {
+                TransactionKind::RegisterAsset { .. } => (transaction.hash(), AssetEventKind::Registered),
+                TransactionKind::GrantAccess { asset } => (asset.clone(), AssetEventKind::AccessGranted),
+                TransactionKind::RevokeAccess { asset } => (asset.clone(), AssetEventKind::AccessRevoked),
+                TransactionKind::Attest { asset, .. } => (asset.clone(), AssetEventKind::Attested),
+                _ => return None,
+            };
+            Some(AssetEvent { asset, kind, account: transaction.recipient, block_index: block.index, transaction_hash: transaction.hash() })
+        })
+        .collect()
+}
+
+// Actifs enregistrés, par identifiant (hash de la transaction d'enregistrement)
+#[derive(Clone, Debug, Default)]
+pub struct Assets {
+    registered: HashMap<Vec<u8>, Asset>,
+}
+
+impl Assets {
+    pub fn get(&self, id: &[u8]) -> Option<&Asset> {
+        self.registered.get(id)
+    }
+
+    // Règles d'accès, pour des transactions adressées à leur émetteur sauf les
+    // accès, adressés au lecteur concerné :
+    // - `GrantAccess` et `RevokeAccess` sont émises par le propriétaire
+    // - `Attest` est émise par un participant et chiffrée pour exactement les
+    //   participants actuels
+    pub fn check(&self, transaction: &Transaction) -> Result<(), &'static str> {
+        match &transaction.kind {
+            TransactionKind::RegisterAsset { readers } => {
+                if transaction.recipient != transaction.sender {
+                    return Err("Asset must be addressed to its owner");
+                }
+                check_readers(&transaction.sender, readers)
+            }
+            TransactionKind::GrantAccess { asset } => {
+                let asset = self.owned(asset, &transaction.sender)?;
+                if asset.is_participant(&transaction.recipient) {
+                    return Err("Reader already granted");
+                }
+                if asset.readers.len() >= MAX_ASSET_READERS {
+                    return Err("Too many asset readers");
+                }
+                Ok(())
+            }
+            TransactionKind::RevokeAccess { asset } => {
+                let asset = self.owned(asset, &transaction.sender)?;
+                if !asset.readers.contains(&transaction.recipient) {
+                    return Err("Not a reader of this asset");
+                }
+                Ok(())
+            }
+            TransactionKind::Attest { asset, payload } => {
+                if transaction.recipient != transaction.sender {
+                    return Err("Attestation must be addressed to its author");
+                }
+                let asset = self.registered.get(asset).ok_or("Unknown asset")?;
+                if !asset.is_participant(&transaction.sender) {
+                    return Err("Not a participant of this asset");
+                }
+                if !payload.wrapped_keys.iter().map(|(member, _)| *member).eq(asset.participants()) {
+                    return Err("Attestation not encrypted for the asset participants");
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn owned(&self, id: &[u8], sender: &PublicKey) -> Result<&Asset, &'static str> {
+        let asset = self.registered.get(id).ok_or("Unknown asset")?;
+        if asset.owner != *sender {
+            return Err("Access granted by the asset owner only");
+        }
+        Ok(asset)
+    }
+
+    pub fn apply(&mut self, transaction: &Transaction) -> Result<(), &'static str> {
+        self.check(transaction)?;
+        match &transaction.kind {
+            TransactionKind::RegisterAsset { readers } => {
+                self.registered.insert(transaction.hash(), Asset { owner: transaction.sender, readers: readers.clone() });
+            }
+            TransactionKind::GrantAccess { asset } => {
+                if let Some(asset) = self.registered.get_mut(asset) {
+                    asset.readers.push(transaction.recipient);
+                    asset.readers.sort_by_key(|key| key.to_bytes());
+                }
+            }
+            TransactionKind::RevokeAccess { asset } => {
+                if let Some(asset) = self.registered.get_mut(asset) {
+                    asset.readers.retain(|reader| *reader != transaction.recipient);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Compte récupéré : sa propriété et ses accès passent à sa nouvelle clé
+    pub fn rotate(&mut self, account: &PublicKey, new_key: &PublicKey) {
+        for asset in self.registered.values_mut() {
+            if asset.owner == *account {
+                asset.owner = *new_key;
+            }
+            if asset.readers.contains(account) {
+                asset.readers.retain(|reader| reader != account && reader != new_key);
+                if asset.owner != *new_key {
+                    asset.readers.push(*new_key);
+                }
+                asset.readers.sort_by_key(|key| key.to_bytes());
+            }
+        }
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        let mut registered: Vec<(&Vec<u8>, &Asset)> = self.registered.iter().collect();
+        registered.sort_by_key(|(id, _)| *id);
+        encoder.put_u32(registered.len() as u32);
+        for (id, asset) in registered {
+            encoder.put_bytes(id);
+            encoder.put_public_key(&asset.owner);
+            encoder.put_u32(asset.readers.len() as u32);
+            for reader in &asset.readers {
+                encoder.put_public_key(reader);
+            }
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Assets, &'static str> {
+        let mut registered = HashMap::new();
+        let mut previous: Option<Vec<u8>> = None;
+        for _ in 0..decoder.get_u32()? {
+            let id = decoder.get_bytes()?.to_vec();
+            if previous.as_ref().is_some_and(|previous| *previous >= id) {
+                return Err("Assets not in canonical order");
+            }
+            let owner = decoder.get_public_key()?;
+            let mut readers = Vec::new();
+            for _ in 0..decoder.get_u32()? {
+                readers.push(decoder.get_public_key()?);
+            }
+            check_readers(&owner, &readers)?;
+            previous = Some(id.clone());
+            registered.insert(id, Asset { owner, readers });
+        }
+        Ok(Assets { registered })
+    }
+}
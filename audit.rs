@@ -0,0 +1,254 @@
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+use serde_json::{json, Value};
+
+use crate::assets::asset_events;
+use crate::encoding::{decode_block, encode_block};
+use crate::mempool::kind_name;
+use crate::{Block, Transaction, TransactionKind};
+
+// Colonnes du format CSV : une ligne par transaction publique, celles d'un lot
+// à la position du lot
+const CSV_HEADER: &str = "block,block_timestamp,block_hash,position,hash,bundle,sender,recipient,amount,kind,gas_price,timestamp,document,asset";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DumpFormat {
+    // Un enregistrement JSON par ligne : chaque bloc (avec son encodage
+    // canonique, qui permet de le rejouer) suivi de ses transactions publiques,
+    // des événements de ses actifs et de ses transactions de gouvernance
+    Jsonl,
+    // Transactions publiques seulement, pour les tableurs ; ne se réimporte pas
+    Csv,
+}
+
+impl FromStr for DumpFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, String> {
+        match format {
+            "jsonl" => Ok(DumpFormat::Jsonl),
+            "csv" => Ok(DumpFormat::Csv),
+            _ => Err(format!("Unknown dump format: {}", format)),
+        }
+    }
+}
+
+// Écrit les blocs dans le format demandé ; retourne le nombre de blocs écrits
+pub fn export(blocks: impl Iterator<Item = Result<Block, String>>, format: DumpFormat, out: &mut impl Write) -> Result<u64, String> {
+    let io = |e: std::io::Error| e.to_string();
+    if format == DumpFormat::Csv {
+        writeln!(out, "{}", CSV_HEADER).map_err(io)?;
+    }
+    let mut count = 0;
+    for block in blocks {
+        let block = block?;
+        match format {
+            DumpFormat::Jsonl => {
+                for record in records(&block) {
+                    writeln!(out, "{}", record).map_err(io)?;
+                }
+            }
+            DumpFormat::Csv => {
+                for (position, transaction, bundle) in public_transactions(&block) {
+                    writeln!(out, "{}", csv_row(&block, position, transaction, bundle)).map_err(io)?;
+                }
+            }
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+// Blocs d'un vidage JSONL, dans l'ordre. Les enregistrements lisibles doivent
+// être exactement ceux que donne l'encodage de leur bloc : une ligne modifiée
+// ou supprimée est refusée.
+pub fn read_blocks(input: impl BufRead) -> Result<Vec<Block>, String> {
+    let mut lines = Vec::new();
+    for (number, line) in input.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Value = serde_json::from_str(&line).map_err(|e| format!("line {}: {}", number + 1, e))?;
+        lines.push((number + 1, record));
+    }
+    let mut blocks = Vec::new();
+    let mut position = 0;
+    while let Some((number, record)) = lines.get(position) {
+        if record["record"] != "block" {
+            return Err(format!("line {}: record outside of its block", number));
+        }
+        let data = record["data"].as_str().and_then(|data| hex::decode(data).ok())
+            .ok_or_else(|| format!("line {}: missing block data", number))?;
+        let block = decode_block(&data).map_err(|e| format!("line {}: {}", number, e))?;
+        for expected in records(&block) {
+            match lines.get(position) {
+                Some((_, record)) if *record == expected => position += 1,
+                Some((number, _)) => return Err(format!("line {}: record does not match block {}", number, block.index)),
+                None => return Err(format!("Dump truncated in block {}", block.index)),
+            }
+        }
+        blocks.push(block);
+    }
+    Ok(blocks)
+}
+
+fn records(block: &Block) -> Vec<Value> {
+    let mut records = vec![json!({
+        "record": "block",
+        "chain_id": block.chain_id,
+        "index": block.index,
+        "timestamp": block.timestamp,
+        "hash": hex::encode(&block.current_hash),
+        "previous_hash": hex::encode(&block.previous_hash),
+        "validator": hex::encode(block.validator_pubkey.as_bytes()),
+        "transactions": block.transactions.len(),
+        "private_transactions": block.private_transactions.len(),
+        "governance_transactions": block.governance_transactions.len(),
+        "data": hex::encode(encode_block(block)),
+    })];
+    for (position, transaction, bundle) in public_transactions(block) {
+        records.push(json!({
+            "record": "transaction",
+            "block": block.index,
+            "position": position,
+            "hash": hex::encode(transaction.hash()),
+            "bundle": bundle.map(hex::encode),
+            "sender": hex::encode(transaction.sender.as_bytes()),
+            "recipient": hex::encode(transaction.recipient.as_bytes()),
+            "amount": transaction.amount,
+            "kind": kind_name(&transaction.kind),
+            "gas_price": transaction.gas_price,
+            "timestamp": transaction.timestamp,
+            "document": document(transaction).map(hex::encode),
+        }));
+    }
+    for event in asset_events(block) {
+        records.push(json!({
+            "record": "asset_event",
+            "block": block.index,
+            "asset": hex::encode(&event.asset),
+            "kind": event.kind.name(),
+            "account": hex::encode(event.account.as_bytes()),
+            "transaction": hex::encode(&event.transaction_hash),
+        }));
+    }
+    for (position, transaction) in block.governance_transactions.iter().enumerate() {
+        records.push(json!({
+            "record": "governance",
+            "block": block.index,
+            "position": position,
+            "hash": hex::encode(transaction.hash()),
+            "kind": transaction.name(),
+        }));
+    }
+    records
+}
+
+// Transactions publiques du bloc avec leur position et, pour celles d'un lot,
+// le hash du lot
+fn public_transactions(block: &Block) -> Vec<(usize, &Transaction, Option<Vec<u8>>)> {
+    let mut transactions = Vec::new();
+    for (position, transaction) in block.transactions.iter().enumerate() {
+        transactions.push((position, transaction, None));
+        let bundle = transaction.hash();
+        for inner in transaction.inner_transactions() {
+            transactions.push((position, inner, Some(bundle.clone())));
+        }
+    }
+    transactions
+}
+
+fn document(transaction: &Transaction) -> Option<&Vec<u8>> {
+    match &transaction.kind {
+        TransactionKind::Anchor { document, .. } => Some(document),
+        _ => None,
+    }
+}
+
+// Actif concerné : le sien pour un enregistrement
+fn asset(transaction: &Transaction) -> Option<Vec<u8>> {
+    match &transaction.kind {
+        TransactionKind::RegisterAsset { .. } => Some(transaction.hash()),
+        TransactionKind::GrantAccess { asset } | TransactionKind::RevokeAccess { asset } | TransactionKind::Attest { asset, .. } => Some(asset.clone()),
+        _ => None,
+    }
+}
+
+fn csv_row(block: &Block, position: usize, transaction: &Transaction, bundle: Option<Vec<u8>>) -> String {
+    [
+        block.index.to_string(),
+        block.timestamp.to_string(),
+        hex::encode(&block.current_hash),
+        position.to_string(),
+        hex::encode(transaction.hash()),
+        bundle.map(hex::encode).unwrap_or_default(),
+        hex::encode(transaction.sender.as_bytes()),
+        hex::encode(transaction.recipient.as_bytes()),
+        transaction.amount.to_string(),
+        kind_name(&transaction.kind).to_string(),
+        transaction.gas_price.to_string(),
+        transaction.timestamp.to_string(),
+        document(transaction).map(hex::encode).unwrap_or_default(),
+        asset(transaction).map(hex::encode).unwrap_or_default(),
+    ]
+    .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+
+    use super::{export, read_blocks, DumpFormat};
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::txbuilder::TxBuilder;
+    use crate::Blockchain;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    fn submit(chain: &mut Blockchain, signer: &Keypair, builder: TxBuilder) -> Vec<u8> {
+        let transaction = builder.chain_id(chain.config.chain_id).nonce(chain.next_nonce(&signer.public)).timestamp(chain.now()).sign(signer).unwrap();
+        let hash = transaction.hash();
+        chain.add_transaction(transaction).unwrap();
+        hash
+    }
+
+    #[test]
+    fn dumps_asset_events_and_rejects_altered_ones() {
+        let (validator, owner, reader) = (keypair(1), keypair(2), keypair(3));
+        let clock = MockClock::new(Duration::from_secs(1_700_000_000));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain.allocate(&owner.public, 1_000_000);
+        let asset = submit(&mut chain, &owner, TxBuilder::register_asset(Vec::new()).to(owner.public));
+        clock.set(Duration::from_secs(1_700_000_005));
+        chain.validate_and_create_block(&validator, 1).unwrap();
+        submit(&mut chain, &owner, TxBuilder::grant_access(asset.clone()).to(reader.public));
+        clock.set(Duration::from_secs(1_700_000_010));
+        chain.validate_and_create_block(&validator, 2).unwrap();
+
+        let mut dump = Vec::new();
+        assert_eq!(export(chain.chain.iter().cloned().map(Ok), DumpFormat::Jsonl, &mut dump), Ok(2));
+        let dump = String::from_utf8(dump).unwrap();
+        let events: Vec<&str> = dump.lines().filter(|line| line.contains("\"asset_event\"")).collect();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].contains("\"registered\"") && events[1].contains("\"access_granted\""));
+        assert!(events[1].contains(&hex::encode(reader.public.as_bytes())));
+        assert_eq!(read_blocks(dump.as_bytes()).unwrap().len(), 2);
+
+        let altered = dump.replace("\"access_granted\"", "\"access_revoked\"");
+        assert!(read_blocks(altered.as_bytes()).unwrap_err().contains("does not match block 1"));
+
+        let mut csv = Vec::new();
+        export(chain.chain.iter().cloned().map(Ok), DumpFormat::Csv, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.lines().skip(1).all(|row| row.ends_with(&hex::encode(&asset))));
+    }
+}
@@ -0,0 +1,303 @@
+use std::path::{Path, PathBuf};
+use ed25519_dalek::{PublicKey, Signature, Verifier};
+use sha3::{Digest, Sha3_256};
+
+use crate::encoding::{Decoder, Encoder, BACKUP_DOMAIN};
+use crate::signer::Signer;
+
+// Variables d'environnement désignant des clés ou des certificats hors du
+// répertoire de données : la sauvegarde n'en garde que la valeur, jamais le
+// contenu, pour les reconfigurer sur le nœud restauré
+pub const KEY_REFERENCES: [&str; 6] = [
+    "SUPPLYX_CONFIG",
+    "SUPPLYX_REMOTE_SIGNER",
+    "SUPPLYX_FAUCET_KEY",
+    "SUPPLYX_QUIC_CERT",
+    "SUPPLYX_QUIC_KEY",
+    "SUPPLYX_QUIC_CA",
+];
+
+// Fichier sauvegardé : chemin relatif au répertoire de données (séparateur `/`),
+// taille et hash du contenu
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+    pub hash: Vec<u8>,
+}
+
+// Manifeste signé d'une sauvegarde : fichiers triés par chemin et références
+// de clés (nom de la variable, valeur) au moment de la sauvegarde
+#[derive(Clone, Debug)]
+pub struct Manifest {
+    pub created: u64,
+    pub entries: Vec<ManifestEntry>,
+    pub references: Vec<(String, String)>,
+    pub signer: PublicKey,
+    pub signature: Signature,
+}
+
+impl Manifest {
+    pub fn signing_hash(created: u64, entries: &[ManifestEntry], references: &[(String, String)], signer: &PublicKey) -> Vec<u8> {
+        let mut encoder = Encoder::new(BACKUP_DOMAIN);
+        encode_manifest(created, entries, references, signer, &mut encoder);
+        encoder.hash()
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Self::signing_hash(self.created, &self.entries, &self.references, &self.signer)
+    }
+}
+
+fn encode_manifest(created: u64, entries: &[ManifestEntry], references: &[(String, String)], signer: &PublicKey, encoder: &mut Encoder) {
+    encoder.put_u64(created);
+    encoder.put_u32(entries.len() as u32);
+    for entry in entries {
+        encoder.put_bytes(entry.path.as_bytes());
+        encoder.put_u64(entry.size);
+        encoder.put_bytes(&entry.hash);
+    }
+    encoder.put_u32(references.len() as u32);
+    for (name, value) in references {
+        encoder.put_bytes(name.as_bytes());
+        encoder.put_bytes(value.as_bytes());
+    }
+    encoder.put_public_key(signer);
+}
+
+// Sauvegarde d'un répertoire de données : le manifeste, puis le contenu des
+// fichiers dans l'ordre du manifeste
+#[derive(Clone, Debug)]
+pub struct Backup {
+    pub manifest: Manifest,
+    pub contents: Vec<Vec<u8>>,
+}
+
+impl Backup {
+    // Sauvegarde de tous les fichiers sous `dir`
+    pub fn create(dir: &Path, references: Vec<(String, String)>, created: u64, signer: &dyn Signer) -> Result<Backup, String> {
+        let mut files = Vec::new();
+        collect(dir, dir, &mut files)?;
+        files.sort();
+        let mut entries = Vec::with_capacity(files.len());
+        let mut contents = Vec::with_capacity(files.len());
+        for (path, file) in files {
+            let data = std::fs::read(&file).map_err(|e| format!("{}: {}", file.display(), e))?;
+            entries.push(ManifestEntry { path, size: data.len() as u64, hash: Sha3_256::digest(&data).to_vec() });
+            contents.push(data);
+        }
+        let key = signer.public_key();
+        let signature = signer.sign(&Manifest::signing_hash(created, &entries, &references, &key))?;
+        Ok(Backup { manifest: Manifest { created, entries, references, signer: key, signature }, contents })
+    }
+
+    // Manifeste signé par `signer`, chemins relatifs sans `..`, uniques et
+    // triés, contenus conformes aux tailles et hash annoncés
+    pub fn verify(&self, signer: &PublicKey) -> Result<(), &'static str> {
+        let manifest = &self.manifest;
+        if manifest.signer != *signer {
+            return Err("Backup signed by another key");
+        }
+        manifest.signer.verify(&manifest.hash(), &manifest.signature).map_err(|_| "Invalid manifest signature")?;
+        if manifest.entries.windows(2).any(|pair| pair[0].path >= pair[1].path) {
+            return Err("Manifest entries not in canonical order");
+        }
+        for (entry, data) in manifest.entries.iter().zip(&self.contents) {
+            if !is_safe_path(&entry.path) {
+                return Err("Unsafe path in manifest");
+            }
+            if data.len() as u64 != entry.size || Sha3_256::digest(data)[..] != entry.hash[..] {
+                return Err("File does not match the manifest");
+            }
+        }
+        Ok(())
+    }
+
+    // Écrit les fichiers sous `dir`, qui doit être absent ou vide ; à appeler
+    // après `verify`
+    pub fn unpack(&self, dir: &Path) -> Result<(), String> {
+        if dir.read_dir().is_ok_and(|mut entries| entries.next().is_some()) {
+            return Err(format!("{}: directory is not empty", dir.display()));
+        }
+        for (entry, data) in self.manifest.entries.iter().zip(&self.contents) {
+            let path = dir.join(&entry.path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+            }
+            std::fs::write(&path, data).map_err(|e| format!("{}: {}", path.display(), e))?;
+        }
+        Ok(())
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(BACKUP_DOMAIN);
+        let manifest = &self.manifest;
+        encode_manifest(manifest.created, &manifest.entries, &manifest.references, &manifest.signer, &mut encoder);
+        encoder.put_signature(&manifest.signature);
+        for data in &self.contents {
+            encoder.put_bytes(data);
+        }
+        encoder.finish()
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Backup, &'static str> {
+        let mut decoder = Decoder::new(data, BACKUP_DOMAIN)?;
+        let created = decoder.get_u64()?;
+        let mut entries = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            let path = String::from_utf8(decoder.get_bytes()?.to_vec()).map_err(|_| "Invalid path")?;
+            entries.push(ManifestEntry { path, size: decoder.get_u64()?, hash: decoder.get_bytes()?.to_vec() });
+        }
+        let mut references = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            let name = String::from_utf8(decoder.get_bytes()?.to_vec()).map_err(|_| "Invalid key reference")?;
+            let value = String::from_utf8(decoder.get_bytes()?.to_vec()).map_err(|_| "Invalid key reference")?;
+            references.push((name, value));
+        }
+        let signer = decoder.get_public_key()?;
+        let signature = decoder.get_signature()?;
+        let mut contents = Vec::with_capacity(entries.len());
+        for _ in 0..entries.len() {
+            contents.push(decoder.get_bytes()?.to_vec());
+        }
+        decoder.finish()?;
+        Ok(Backup { manifest: Manifest { created, entries, references, signer, signature }, contents })
+    }
+}
+
+fn collect(root: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) -> Result<(), String> {
+    for entry in std::fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))? {
+        let path = entry.map_err(|e| format!("{}: {}", dir.display(), e))?.path();
+        if path.is_dir() {
+            collect(root, &path, files)?;
+        } else {
+            let relative = path.strip_prefix(root).unwrap();
+            let name: Vec<&str> = relative.iter().map(|part| part.to_str().ok_or(format!("{}: invalid file name", path.display()))).collect::<Result<_, _>>()?;
+            files.push((name.join("/"), path));
+        }
+    }
+    Ok(())
+}
+
+fn is_safe_path(path: &str) -> bool {
+    !path.is_empty() && path.split('/').all(|part| !part.is_empty() && part != "." && part != ".." && !part.contains('\\'))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ed25519_dalek::{Keypair, SecretKey};
+
+    use super::*;
+    use crate::blockstore::ChainStore;
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::sync::SyncJournal;
+    use crate::txbuilder::TxBuilder;
+    use crate::Blockchain;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    // Répertoire vide propre au test
+    fn data_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("supplyx-backup-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn genesis(validator: &Keypair, alice: &Keypair, clock: &MockClock) -> Blockchain {
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(std::sync::Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain.allocate(&alice.public, 1_000_000);
+        chain
+    }
+
+    // Sauvegarde d'un journal de synchronisation de quelques blocs, avec la
+    // chaîne qui les a produits
+    fn journaled_chain(dir: &Path) -> (Blockchain, Backup) {
+        let (validator, alice, bob) = (keypair(1), keypair(2), keypair(3));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = genesis(&validator, &alice, &clock);
+        let mut journal = SyncJournal::open(dir).unwrap();
+        for slot in 1..=3 {
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            let transfer = TxBuilder::transfer().to(bob.public).amount(100).chain_id(chain.config.chain_id)
+                .nonce(chain.next_nonce(&alice.public)).timestamp(chain.now()).sign(&alice).unwrap();
+            chain.add_transaction(transfer).unwrap();
+            let block = chain.validate_and_create_block(&validator, slot).unwrap();
+            journal.append_blocks(&[block]).unwrap();
+        }
+        let backup = Backup::create(dir, vec![("SUPPLYX_CONFIG".to_string(), "node.toml".to_string())], GENESIS_TIME, &validator).unwrap();
+        (chain, backup)
+    }
+
+    #[test]
+    fn restored_journal_replays_to_the_same_state() {
+        let (source, target) = (data_dir("source"), data_dir("target"));
+        let (chain, backup) = journaled_chain(&source);
+        let backup = Backup::decode(&backup.encode()).unwrap();
+        backup.verify(&keypair(1).public).unwrap();
+        backup.unpack(&target).unwrap();
+        for entry in &backup.manifest.entries {
+            assert_eq!(std::fs::read(target.join(&entry.path)).unwrap(), std::fs::read(source.join(&entry.path)).unwrap());
+        }
+        assert_eq!(backup.manifest.references, vec![("SUPPLYX_CONFIG".to_string(), "node.toml".to_string())]);
+
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME + 15));
+        let mut restored = genesis(&keypair(1), &keypair(2), &clock);
+        let journal = SyncJournal::open(&target).unwrap();
+        for block in journal.blocks().blocks(journal.blocks().range()) {
+            restored.add_block(block.unwrap()).unwrap();
+        }
+        assert_eq!(restored.snapshot().hash(), chain.snapshot().hash());
+        assert_eq!(restored.balance(&keypair(3).public), 300);
+
+        // Un répertoire non vide n'est pas écrasé
+        assert!(backup.unpack(&target).unwrap_err().contains("directory is not empty"));
+        let _ = std::fs::remove_dir_all(&source);
+        let _ = std::fs::remove_dir_all(&target);
+    }
+
+    #[test]
+    fn tampered_backups_are_rejected() {
+        let dir = data_dir("tampered");
+        let (_, backup) = journaled_chain(&dir);
+        let validator = keypair(1);
+        assert_eq!(backup.verify(&keypair(2).public), Err("Backup signed by another key"));
+
+        let mut contents = backup.clone();
+        contents.contents[0][0] ^= 1;
+        assert_eq!(contents.verify(&validator.public), Err("File does not match the manifest"));
+
+        // Manifeste modifié pour couvrir le contenu altéré, sans nouvelle signature
+        let mut manifest = contents.clone();
+        manifest.manifest.entries[0].hash = Sha3_256::digest(&manifest.contents[0]).to_vec();
+        assert_eq!(manifest.verify(&validator.public), Err("Invalid manifest signature"));
+        let mut references = backup.clone();
+        references.manifest.references[0].1 = "/tmp/other.toml".to_string();
+        assert_eq!(references.verify(&validator.public), Err("Invalid manifest signature"));
+
+        // Même signé par la clé attendue, un chemin sortant du répertoire est refusé
+        let mut escape = backup.clone();
+        escape.manifest.entries[0].path = "../escape".to_string();
+        let resign = |backup: &mut Backup| {
+            let manifest = &mut backup.manifest;
+            manifest.signature = validator.sign(&manifest.hash()).unwrap();
+        };
+        resign(&mut escape);
+        assert_eq!(escape.verify(&validator.public), Err("Unsafe path in manifest"));
+        let mut unordered = backup.clone();
+        unordered.manifest.entries.reverse();
+        unordered.contents.reverse();
+        resign(&mut unordered);
+        assert_eq!(unordered.verify(&validator.public), Err("Manifest entries not in canonical order"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::{BTreeMap, HashMap};
+use ed25519_dalek::{PublicKey, Signature};
+
+use crate::encoding::{Decoder, Encoder, BEACON_DOMAIN};
+use crate::governance::GovernanceTransaction;
+use crate::signer::Signer;
+use crate::{Validator, EPOCH_LENGTH};
+
+// Les engagements sont reçus dans la première moitié de l'époque, les
+// révélations dans la seconde
+pub const REVEAL_OFFSET: u64 = EPOCH_LENGTH / 2;
+
+// Part de la mise (en pourcentage) retirée à un validateur engagé qui ne révèle pas
+pub const WITHHOLDING_PENALTY_PERCENT: u64 = 10;
+
+pub fn epoch_of(height: u64) -> u64 {
+    height / EPOCH_LENGTH
+}
+
+pub fn is_reveal_phase(height: u64) -> bool {
+    height % EPOCH_LENGTH >= REVEAL_OFFSET
+}
+
+// Secret d'un validateur pour une époque : dérivé de sa signature (déterministe),
+// il n'a pas à être conservé entre l'engagement et la révélation et ne peut pas
+// être choisi
+fn derive_secret(signer: &dyn Signer, epoch: u64) -> Result<Vec<u8>, &'static str> {
+    let mut encoder = Encoder::new(BEACON_DOMAIN);
+    encoder.put_u8(0);
+    encoder.put_u64(epoch);
+    let mut secret = Encoder::new(BEACON_DOMAIN);
+    secret.put_signature(&signer.sign(&encoder.hash())?);
+    Ok(secret.hash())
+}
+
+fn commitment(validator: &PublicKey, epoch: u64, secret: &[u8]) -> Vec<u8> {
+    let mut encoder = Encoder::new(BEACON_DOMAIN);
+    encoder.put_u8(1);
+    encoder.put_public_key(validator);
+    encoder.put_u64(epoch);
+    encoder.put_bytes(secret);
+    encoder.hash()
+}
+
+// Engagement d'un validateur sur son secret de l'époque `epoch`
+#[derive(Clone, Debug)]
+pub struct BeaconCommit {
+    pub validator: PublicKey,
+    pub epoch: u64,
+    pub commitment: Vec<u8>,
+    pub signature: Signature,
+}
+
+impl BeaconCommit {
+    pub fn new(signer: &dyn Signer, epoch: u64) -> Result<Self, &'static str> {
+        let validator = signer.public_key();
+        let commitment = commitment(&validator, epoch, &derive_secret(signer, epoch)?);
+        let signature = signer.sign(&Self::signing_hash(&validator, epoch, &commitment))?;
+        Ok(BeaconCommit { validator, epoch, commitment, signature })
+    }
+
+    pub fn signing_hash(validator: &PublicKey, epoch: u64, commitment: &[u8]) -> Vec<u8> {
+        let mut encoder = Encoder::new(BEACON_DOMAIN);
+        encoder.put_u8(2);
+        encoder.put_public_key(validator);
+        encoder.put_u64(epoch);
+        encoder.put_bytes(commitment);
+        encoder.hash()
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Self::signing_hash(&self.validator, self.epoch, &self.commitment)
+    }
+}
+
+// Révélation du secret engagé par un validateur pour l'époque `epoch`
+#[derive(Clone, Debug)]
+pub struct BeaconReveal {
+    pub validator: PublicKey,
+    pub epoch: u64,
+    pub secret: Vec<u8>,
+    pub signature: Signature,
+}
+
+impl BeaconReveal {
+    pub fn new(signer: &dyn Signer, epoch: u64) -> Result<Self, &'static str> {
+        let validator = signer.public_key();
+        let secret = derive_secret(signer, epoch)?;
+        let signature = signer.sign(&Self::signing_hash(&validator, epoch, &secret))?;
+        Ok(BeaconReveal { validator, epoch, secret, signature })
+    }
+
+    pub fn signing_hash(validator: &PublicKey, epoch: u64, secret: &[u8]) -> Vec<u8> {
+        let mut encoder = Encoder::new(BEACON_DOMAIN);
+        encoder.put_u8(3);
+        encoder.put_public_key(validator);
+        encoder.put_u64(epoch);
+        encoder.put_bytes(secret);
+        encoder.hash()
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Self::signing_hash(&self.validator, self.epoch, &self.secret)
+    }
+}
+
+// Valeur aléatoire d'une époque, avec les validateurs engagés n'ayant pas révélé
+#[derive(Clone, Debug)]
+pub struct BeaconOutput {
+    pub epoch: u64,
+    pub value: Vec<u8>,
+    pub withheld: Vec<PublicKey>,
+}
+
+// Balise d'aléa par engagement-révélation : engagements et révélations de
+// l'époque en cours, et valeur produite à la fin de chaque époque passée
+#[derive(Clone, Debug, Default)]
+pub struct Beacon {
+    commitments: HashMap<PublicKey, Vec<u8>>,
+    reveals: HashMap<PublicKey, Vec<u8>>,
+    values: BTreeMap<u64, Vec<u8>>,
+}
+
+impl Beacon {
+    pub fn value(&self, epoch: u64) -> Option<&Vec<u8>> {
+        self.values.get(&epoch)
+    }
+
+    pub fn latest(&self) -> Option<(u64, &Vec<u8>)> {
+        self.values.iter().next_back().map(|(epoch, value)| (*epoch, value))
+    }
+
+    pub fn has_committed(&self, validator: &PublicKey) -> bool {
+        self.commitments.contains_key(validator)
+    }
+
+    // Engagé pour l'époque en cours sans avoir encore révélé
+    pub fn awaits_reveal(&self, validator: &PublicKey) -> bool {
+        self.commitments.contains_key(validator) && !self.reveals.contains_key(validator)
+    }
+
+    // Contributions de validateurs enregistrés pour l'époque du bloc `height`,
+    // dans la bonne phase : un engagement puis une révélation conforme par validateur
+    pub fn validate(&self, validators: &HashMap<PublicKey, Validator>, transactions: &[GovernanceTransaction], height: u64) -> Result<(), &'static str> {
+        let mut committed: Vec<PublicKey> = Vec::new();
+        let mut revealed: Vec<PublicKey> = Vec::new();
+        for transaction in transactions {
+            match transaction {
+                GovernanceTransaction::BeaconCommit(commit) => {
+                    if !validators.contains_key(&commit.validator) {
+                        return Err("Beacon commitment from unknown validator");
+                    }
+                    if commit.epoch != epoch_of(height) || is_reveal_phase(height) {
+                        return Err("Beacon commitment outside its commit phase");
+                    }
+                    if self.commitments.contains_key(&commit.validator) || committed.contains(&commit.validator) {
+                        return Err("Duplicate beacon commitment");
+                    }
+                    committed.push(commit.validator);
+                }
+                GovernanceTransaction::BeaconReveal(reveal) => {
+                    if reveal.epoch != epoch_of(height) || !is_reveal_phase(height) {
+                        return Err("Beacon reveal outside its reveal phase");
+                    }
+                    let expected = self.commitments.get(&reveal.validator).ok_or("Beacon reveal without commitment")?;
+                    if *expected != commitment(&reveal.validator, reveal.epoch, &reveal.secret) {
+                        return Err("Beacon reveal does not match commitment");
+                    }
+                    if self.reveals.contains_key(&reveal.validator) || revealed.contains(&reveal.validator) {
+                        return Err("Duplicate beacon reveal");
+                    }
+                    revealed.push(reveal.validator);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    // Applique les contributions (déjà validées) du bloc `height` ; au dernier bloc
+    // de l'époque, produit la valeur de l'époque à partir de la précédente et des
+    // secrets révélés, triés par validateur
+    pub fn apply(&mut self, transactions: &[GovernanceTransaction], height: u64) -> Option<BeaconOutput> {
+        for transaction in transactions {
+            match transaction {
+                GovernanceTransaction::BeaconCommit(commit) => {
+                    self.commitments.insert(commit.validator, commit.commitment.clone());
+                }
+                GovernanceTransaction::BeaconReveal(reveal) => {
+                    self.reveals.insert(reveal.validator, reveal.secret.clone());
+                }
+                _ => {}
+            }
+        }
+
+        if height % EPOCH_LENGTH != EPOCH_LENGTH - 1 {
+            return None;
+        }
+        let epoch = epoch_of(height);
+        let mut reveals: Vec<(&PublicKey, &Vec<u8>)> = self.reveals.iter().collect();
+        reveals.sort_by_key(|(validator, _)| validator.to_bytes());
+        let mut encoder = Encoder::new(BEACON_DOMAIN);
+        encoder.put_u8(4);
+        encoder.put_u64(epoch);
+        encoder.put_bytes(epoch.checked_sub(1).and_then(|previous| self.values.get(&previous)).map_or(&[][..], |value| value.as_slice()));
+        encoder.put_u32(reveals.len() as u32);
+        for (validator, secret) in reveals {
+            encoder.put_public_key(validator);
+            encoder.put_bytes(secret);
+        }
+        let value = encoder.hash();
+
+        let mut withheld: Vec<PublicKey> = self.commitments.keys()
+            .filter(|validator| !self.reveals.contains_key(*validator))
+            .copied()
+            .collect();
+        withheld.sort_by_key(|validator| validator.to_bytes());
+        self.commitments.clear();
+        self.reveals.clear();
+        self.values.insert(epoch, value.clone());
+        Some(BeaconOutput { epoch, value, withheld })
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        for entries in [&self.commitments, &self.reveals] {
+            let mut entries: Vec<(&PublicKey, &Vec<u8>)> = entries.iter().collect();
+            entries.sort_by_key(|(validator, _)| validator.to_bytes());
+            encoder.put_u32(entries.len() as u32);
+            for (validator, data) in entries {
+                encoder.put_public_key(validator);
+                encoder.put_bytes(data);
+            }
+        }
+        encoder.put_u32(self.values.len() as u32);
+        for (epoch, value) in &self.values {
+            encoder.put_u64(*epoch);
+            encoder.put_bytes(value);
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Beacon, &'static str> {
+        let commitments = decode_contributions(decoder)?;
+        let reveals = decode_contributions(decoder)?;
+        let mut values = BTreeMap::new();
+        let mut previous: Option<u64> = None;
+        for _ in 0..decoder.get_u32()? {
+            let epoch = decoder.get_u64()?;
+            if previous.is_some_and(|previous| previous >= epoch) {
+                return Err("Beacon values not in canonical order");
+            }
+            previous = Some(epoch);
+            values.insert(epoch, decoder.get_bytes()?.to_vec());
+        }
+        Ok(Beacon { commitments, reveals, values })
+    }
+}
+
+fn decode_contributions(decoder: &mut Decoder) -> Result<HashMap<PublicKey, Vec<u8>>, &'static str> {
+    let mut contributions = HashMap::new();
+    let mut previous: Option<PublicKey> = None;
+    for _ in 0..decoder.get_u32()? {
+        let validator = decoder.get_public_key()?;
+        if previous.is_some_and(|previous| previous.to_bytes() >= validator.to_bytes()) {
+            return Err("Beacon contributions not in canonical order");
+        }
+        previous = Some(validator);
+        contributions.insert(validator, decoder.get_bytes()?.to_vec());
+    }
+    Ok(contributions)
+}
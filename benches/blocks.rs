@@ -0,0 +1,129 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ed25519_dalek::{verify_batch, Keypair, PublicKey, Signature, Signer, Verifier};
+use rand::rngs::OsRng;
+use rayon::prelude::*;
+use supplyx_core::encoding::Encoder;
+use supplyx_core::merkle::merkle_root;
+use supplyx_core::transaction::{encode_transactions, Expiry, Kind, Transaction};
+
+// Étapes sans état de la production et de la validation des blocs, avec les
+// primitives de `supplyx-core` : hash des transactions, racine de Merkle,
+// encodage du corps, hash et signature de l'en-tête (même encodage que
+// `BlockHeader::encoder`, sans checkpoint), signatures des transactions par
+// lots. L'exécution (soldes, gaz, contrats) demande le nœud et n'est pas mesurée.
+const BLOCK_DOMAIN: &[u8] = b"SUPPLYX_BLOCK_V1";
+const SIGNATURE_BATCH_SIZE: usize = 256;
+
+struct Block {
+    index: u64,
+    previous_hash: Vec<u8>,
+    transactions: Vec<Transaction>,
+    transactions_root: Vec<u8>,
+    body: Vec<u8>,
+    hash: Vec<u8>,
+    signature: Signature,
+}
+
+fn transactions(count: usize, seed: u64) -> Vec<Transaction> {
+    let keypairs: Vec<Keypair> = (0..16).map(|_| Keypair::generate(&mut OsRng)).collect();
+    (0..count)
+        .map(|i| {
+            let sender = &keypairs[i % keypairs.len()];
+            let recipient = keypairs[(i + 1) % keypairs.len()].public;
+            Transaction::signed(1, sender, i as u64, &recipient, i as u64 + 1, Kind::Transfer, 1, 1_700_000_000 + seed, Expiry::Never)
+        })
+        .collect()
+}
+
+fn header_hash(index: u64, previous_hash: &[u8], transactions_root: &[u8], validator: &PublicKey) -> Vec<u8> {
+    let mut encoder = Encoder::new(BLOCK_DOMAIN);
+    encoder.put_u64(1);
+    encoder.put_u64(index);
+    encoder.put_u64(index);
+    encoder.put_u64(1_700_000_000 + index);
+    encoder.put_u64(1);
+    encoder.put_u32(1);
+    encoder.put_bytes(&[]);
+    encoder.put_bytes(previous_hash);
+    encoder.put_bytes(transactions_root);
+    encoder.put_bytes(&[0; 32]);
+    encoder.put_bytes(&[]);
+    encoder.put_bytes(&[]);
+    encoder.put_public_key(validator);
+    encoder.hash()
+}
+
+fn build_block(validator: &Keypair, index: u64, previous_hash: Vec<u8>, transactions: Vec<Transaction>) -> Block {
+    let leaves: Vec<Vec<u8>> = transactions.iter().map(Transaction::hash).collect();
+    let transactions_root = merkle_root(&leaves);
+    let body = encode_transactions(&transactions);
+    let hash = header_hash(index, &previous_hash, &transactions_root, &validator.public);
+    let signature = validator.sign(&hash);
+    Block { index, previous_hash, transactions, transactions_root, body, hash, signature }
+}
+
+fn validate_block(validator: &PublicKey, parent_hash: &[u8], block: &Block) -> Result<(), &'static str> {
+    if block.previous_hash != parent_hash {
+        return Err("Invalid previous hash");
+    }
+    let hashes: Vec<Vec<u8>> = block.transactions.iter().map(Transaction::hash).collect();
+    if merkle_root(&hashes) != block.transactions_root {
+        return Err("Invalid transactions root");
+    }
+    let hash = header_hash(block.index, &block.previous_hash, &block.transactions_root, validator);
+    if hash != block.hash || validator.verify(&hash, &block.signature).is_err() {
+        return Err("Invalid block signature");
+    }
+    let signed: Vec<(&Transaction, &Vec<u8>)> = block.transactions.iter().zip(&hashes).collect();
+    signed.par_chunks(SIGNATURE_BATCH_SIZE).try_for_each(|chunk| {
+        let messages: Vec<&[u8]> = chunk.iter().map(|(_, hash)| hash.as_slice()).collect();
+        let signatures: Vec<Signature> = chunk.iter().map(|(transaction, _)| transaction.signature).collect();
+        let public_keys: Vec<PublicKey> = chunk.iter().map(|(transaction, _)| transaction.sender).collect();
+        verify_batch(&messages, &signatures, &public_keys).map_err(|_| "Invalid transaction signature")
+    })
+}
+
+fn validate_chain(validator: &PublicKey, genesis_hash: &[u8], blocks: &[Block]) -> Result<(), &'static str> {
+    let mut parent_hash = genesis_hash;
+    for block in blocks {
+        validate_block(validator, parent_hash, block)?;
+        parent_hash = &block.hash;
+    }
+    Ok(())
+}
+
+fn bench_block_building(c: &mut Criterion) {
+    let validator = Keypair::generate(&mut OsRng);
+    let transactions = transactions(10_000, 0);
+
+    let mut group = c.benchmark_group("block_building");
+    group.sample_size(20);
+    group.throughput(Throughput::Elements(transactions.len() as u64));
+    group.bench_function("10000_transfers", |b| {
+        b.iter(|| build_block(&validator, 1, vec![0; 32], transactions.clone()).body.len())
+    });
+    group.finish();
+}
+
+fn bench_chain_validation(c: &mut Criterion) {
+    let validator = Keypair::generate(&mut OsRng);
+    let genesis_hash = vec![0; 32];
+
+    let mut group = c.benchmark_group("chain_validation");
+    group.sample_size(10);
+    for (length, per_block) in [(100, 100), (20, 1_000)] {
+        let mut blocks: Vec<Block> = Vec::with_capacity(length);
+        for index in 0..length as u64 {
+            let previous_hash = blocks.last().map_or(genesis_hash.clone(), |block| block.hash.clone());
+            blocks.push(build_block(&validator, index + 1, previous_hash, transactions(per_block, index)));
+        }
+        group.throughput(Throughput::Elements((length * per_block) as u64));
+        group.bench_with_input(BenchmarkId::new(format!("{}_blocks", length), per_block), &blocks, |b, blocks| {
+            b.iter(|| assert!(validate_chain(&validator.public, &genesis_hash, blocks).is_ok()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_block_building, bench_chain_validation);
+criterion_main!(benches);
@@ -0,0 +1,22 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sha3::{Digest, Sha3_256};
+use supplyx_core::merkle::{merkle_proof, merkle_root};
+
+// Feuilles de 32 octets, comme les hash des transactions d'un bloc
+fn leaves(count: usize) -> Vec<Vec<u8>> {
+    (0..count as u64).map(|i| Sha3_256::digest(i.to_be_bytes()).to_vec()).collect()
+}
+
+fn bench_merkle_root(c: &mut Criterion) {
+    let mut group = c.benchmark_group("merkle_root");
+    for count in [1_000, 10_000, 100_000] {
+        let leaves = leaves(count);
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::new("root", count), &leaves, |b, leaves| b.iter(|| merkle_root(leaves)));
+        group.bench_with_input(BenchmarkId::new("proof", count), &leaves, |b, leaves| b.iter(|| merkle_proof(leaves, count / 2)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_merkle_root);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ed25519_dalek::{verify_batch, Keypair, PublicKey, Signature, Signer, Verifier};
+use rand::rngs::OsRng;
+use rayon::prelude::*;
+
+// Même taille de lot que la validation de bloc
+const SIGNATURE_BATCH_SIZE: usize = 256;
+
+fn signed_messages(count: usize) -> (Vec<Vec<u8>>, Vec<Signature>, Vec<PublicKey>) {
+    let keypairs: Vec<Keypair> = (0..16).map(|_| Keypair::generate(&mut OsRng)).collect();
+    let mut messages = Vec::with_capacity(count);
+    let mut signatures = Vec::with_capacity(count);
+    let mut public_keys = Vec::with_capacity(count);
+
+    for i in 0..count {
+        let keypair = &keypairs[i % keypairs.len()];
+        let message = (i as u64).to_be_bytes().to_vec();
+        signatures.push(keypair.sign(&message));
+        public_keys.push(keypair.public);
+        messages.push(message);
+    }
+    (messages, signatures, public_keys)
+}
+
+fn verify_sequential(messages: &[Vec<u8>], signatures: &[Signature], public_keys: &[PublicKey]) -> bool {
+    messages.iter().zip(signatures).zip(public_keys)
+        .all(|((message, signature), key)| key.verify(message, signature).is_ok())
+}
+
+fn verify_batched_serial(messages: &[Vec<u8>], signatures: &[Signature], public_keys: &[PublicKey]) -> bool {
+    messages.chunks(SIGNATURE_BATCH_SIZE)
+        .zip(signatures.chunks(SIGNATURE_BATCH_SIZE))
+        .zip(public_keys.chunks(SIGNATURE_BATCH_SIZE))
+        .all(|((messages, signatures), keys)| {
+            let messages: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+            verify_batch(&messages, signatures, keys).is_ok()
+        })
+}
+
+fn verify_batched(messages: &[Vec<u8>], signatures: &[Signature], public_keys: &[PublicKey]) -> bool {
+    messages.par_chunks(SIGNATURE_BATCH_SIZE)
+        .zip(signatures.par_chunks(SIGNATURE_BATCH_SIZE))
+        .zip(public_keys.par_chunks(SIGNATURE_BATCH_SIZE))
+        .all(|((messages, signatures), keys)| {
+            let messages: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+            verify_batch(&messages, signatures, keys).is_ok()
+        })
+}
+
+fn bench_signature_verification(c: &mut Criterion) {
+    let mut group = c.benchmark_group("block_signature_verification");
+
+    for count in [1_000, 10_000] {
+        let (messages, signatures, public_keys) = signed_messages(count);
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(BenchmarkId::new("sequential", count), &count, |b, _| {
+            b.iter(|| assert!(verify_sequential(&messages, &signatures, &public_keys)))
+        });
+        group.bench_with_input(BenchmarkId::new("batch", count), &count, |b, _| {
+            b.iter(|| assert!(verify_batched_serial(&messages, &signatures, &public_keys)))
+        });
+        group.bench_with_input(BenchmarkId::new("batch_parallel", count), &count, |b, _| {
+            b.iter(|| assert!(verify_batched(&messages, &signatures, &public_keys)))
+        });
+    }
+    group.finish();
+
+    // Une signature isolée, comme à l'admission d'une transaction au mempool
+    let (messages, signatures, public_keys) = signed_messages(1);
+    c.bench_function("single_signature_verification", |b| {
+        b.iter(|| assert!(public_keys[0].verify(&messages[0], &signatures[0]).is_ok()))
+    });
+}
+
+criterion_group!(benches, bench_signature_verification);
+criterion_main!(benches);
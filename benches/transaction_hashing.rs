@@ -0,0 +1,40 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ed25519_dalek::Keypair;
+use rand::rngs::OsRng;
+use supplyx_core::transaction::{encode_transactions, Expiry, Kind, Transaction};
+
+// Primitives de `supplyx-core`, celles qu'utilise le nœud pour le hash signé
+// et l'encodage des corps de bloc
+fn transactions(count: usize) -> Vec<Transaction> {
+    let keypairs: Vec<Keypair> = (0..16).map(|_| Keypair::generate(&mut OsRng)).collect();
+    (0..count)
+        .map(|i| {
+            let sender = &keypairs[i % keypairs.len()];
+            let recipient = keypairs[(i + 1) % keypairs.len()].public;
+            Transaction::signed(1, sender, i as u64, &recipient, i as u64 + 1, Kind::Transfer, 1, 1_700_000_000, Expiry::Never)
+        })
+        .collect()
+}
+
+fn bench_transaction_hashing(c: &mut Criterion) {
+    let transfer = transactions(1).remove(0);
+    let anchor = Transaction { kind: Kind::Anchor { document: vec![0xab; 32], cid: Some("bafkreidgvpkjawlxz6sffxzwgooowe5yt7i6wsyg236mfoks77nywkptdq".to_string()) }, ..transfer.clone() };
+
+    let mut group = c.benchmark_group("transaction_hash");
+    group.bench_function("transfer", |b| b.iter(|| transfer.hash()));
+    group.bench_function("anchor", |b| b.iter(|| anchor.hash()));
+    group.finish();
+
+    let mut group = c.benchmark_group("block_body_encoding");
+    for count in [1_000, 10_000] {
+        let transactions = transactions(count);
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &transactions, |b, transactions| {
+            b.iter(|| encode_transactions(transactions))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_transaction_hashing);
+criterion_main!(benches);
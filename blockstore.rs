@@ -0,0 +1,502 @@
+use std::fs::{File, OpenOptions};
+use std::io::Read;
+use std::ops::Range;
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+
+use crate::encoding::{decode_block, encode_block};
+use crate::Block;
+
+// Taille au-delà de laquelle les blocs suivants sont écrits dans un nouveau segment
+const SEGMENT_SIZE: u64 = 128 * 1024 * 1024;
+
+// En-tête d'un enregistrement : longueur puis CRC-32 du bloc encodé (u32 big-endian)
+const RECORD_HEADER: usize = 8;
+
+// Entrée de l'index : position de l'enregistrement dans le segment (u64 big-endian)
+const INDEX_ENTRY: usize = 8;
+
+// Blocs consécutifs stockés par le nœud
+pub trait ChainStore {
+    // Index des blocs stockés
+    fn range(&self) -> Range<u64>;
+
+    fn get(&self, index: u64) -> Result<Block, String>;
+
+    // Blocs à la suite du dernier bloc stocké (à partir de n'importe quel index
+    // si le stockage est vide)
+    fn append(&mut self, blocks: &[Block]) -> Result<(), String>;
+
+    // Supprime les blocs d'index supérieur ou égal à `index`
+    fn truncate(&mut self, index: u64) -> Result<(), String>;
+
+    // Blocs de `range`, lus un à un
+    fn blocks(&self, range: Range<u64>) -> impl Iterator<Item = Result<Block, String>> + '_
+    where
+        Self: Sized,
+    {
+        range.map(move |index| self.get(index))
+    }
+}
+
+// CRC-32 (IEEE 802.3, polynôme réfléchi)
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut crc = byte as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| CRC_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8))
+}
+
+// Bloc encodé de l'enregistrement à `offset`, si celui-ci est complet et intègre
+fn record(bytes: &[u8], offset: u64) -> Option<&[u8]> {
+    let rest = bytes.get(offset as usize..)?;
+    let (header, rest) = rest.split_first_chunk::<RECORD_HEADER>()?;
+    let length = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+    let checksum = u32::from_be_bytes(header[4..].try_into().unwrap());
+    let payload = rest.get(..length)?;
+    (crc32(payload) == checksum).then_some(payload)
+}
+
+// Projection en lecture seule d'un fichier. Les fichiers d'un stockage
+// appartiennent au nœud : ils ne sont tronqués que par lui, après suppression
+// de leur projection.
+struct Mapping {
+    address: *mut libc::c_void,
+    length: usize,
+}
+
+// La projection n'est jamais modifiée à travers ce pointeur
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    fn new(file: &File, length: u64) -> Result<Option<Mapping>, String> {
+        if length == 0 {
+            return Ok(None);
+        }
+        let length = length as usize;
+        // SAFETY: projection partagée en lecture seule d'un descripteur valide, sur
+        // la longueur actuelle du fichier
+        let address = unsafe {
+            libc::mmap(std::ptr::null_mut(), length, libc::PROT_READ, libc::MAP_SHARED, file.as_raw_fd(), 0)
+        };
+        if address == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        Ok(Some(Mapping { address, length }))
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // SAFETY: `length` octets projetés jusqu'à la destruction de la projection
+        unsafe { std::slice::from_raw_parts(self.address as *const u8, self.length) }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // SAFETY: projection créée par `Mapping::new`, plus référencée
+        unsafe {
+            libc::munmap(self.address, self.length);
+        }
+    }
+}
+
+// Segment : blocs consécutifs à partir de `first` (`<first>.blk`) et position
+// de chacun (`<first>.idx`)
+struct Segment {
+    first: u64,
+    data: File,
+    index: File,
+    offsets: Vec<u64>,
+    length: u64,
+    mapping: Option<Mapping>,
+}
+
+fn segment_paths(dir: &Path, first: u64) -> (PathBuf, PathBuf) {
+    (dir.join(format!("{:020}.blk", first)), dir.join(format!("{:020}.idx", first)))
+}
+
+impl Segment {
+    fn open(dir: &Path, first: u64) -> Result<Self, String> {
+        let (data_path, index_path) = segment_paths(dir, first);
+        let open = |path: &Path| {
+            OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)
+                .map_err(|e| format!("{}: {}", path.display(), e))
+        };
+        let (data, mut index) = (open(&data_path)?, open(&index_path)?);
+        let length = data.metadata().map_err(|e| format!("{}: {}", data_path.display(), e))?.len();
+        let mut entries = Vec::new();
+        index.read_to_end(&mut entries).map_err(|e| format!("{}: {}", index_path.display(), e))?;
+        let offsets = entries.chunks_exact(INDEX_ENTRY)
+            .map(|entry| u64::from_be_bytes(entry.try_into().unwrap()))
+            .collect();
+        let mapping = Mapping::new(&data, length)?;
+        Ok(Segment { first, data, index, offsets, length, mapping })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self.mapping.as_ref().map_or(&[][..], Mapping::bytes)
+    }
+
+    fn end(&self) -> u64 {
+        self.first + self.offsets.len() as u64
+    }
+
+    // Fin du dernier enregistrement indexé
+    fn records_end(&self) -> Option<u64> {
+        match self.offsets.last() {
+            Some(offset) => record(self.bytes(), *offset).map(|payload| offset + (RECORD_HEADER + payload.len()) as u64),
+            None => Some(0),
+        }
+    }
+
+    // Après une écriture interrompue : garde les enregistrements intègres qui se
+    // suivent depuis le début du segment, y compris ceux écrits sans avoir été
+    // indexés, et tronque le reste
+    fn recover(&mut self) -> Result<(), String> {
+        let bytes = self.mapping.as_ref().map_or(&[][..], Mapping::bytes);
+        let mut offsets = Vec::new();
+        let mut end = 0;
+        while let Some(payload) = record(bytes, end) {
+            offsets.push(end);
+            end += (RECORD_HEADER + payload.len()) as u64;
+        }
+        self.offsets = offsets;
+        self.mapping = None;
+        self.data.set_len(end).map_err(|e| e.to_string())?;
+        let entries: Vec<u8> = self.offsets.iter().flat_map(|offset| offset.to_be_bytes()).collect();
+        self.index.set_len(0).and_then(|_| self.index.write_all_at(&entries, 0)).map_err(|e| e.to_string())?;
+        self.length = end;
+        self.remap()
+    }
+
+    fn remap(&mut self) -> Result<(), String> {
+        self.mapping = None;
+        self.mapping = Mapping::new(&self.data, self.length)?;
+        Ok(())
+    }
+
+    fn append(&mut self, block: &Block) -> Result<(), String> {
+        let payload = encode_block(block);
+        let mut record = Vec::with_capacity(RECORD_HEADER + payload.len());
+        record.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        record.extend_from_slice(&crc32(&payload).to_be_bytes());
+        record.extend_from_slice(&payload);
+        // Le bloc avant son entrée d'index : un enregistrement non indexé est
+        // retrouvé à la réouverture
+        self.data.write_all_at(&record, self.length).map_err(|e| e.to_string())?;
+        let entry = (self.offsets.len() * INDEX_ENTRY) as u64;
+        self.index.write_all_at(&self.length.to_be_bytes(), entry).map_err(|e| e.to_string())?;
+        self.offsets.push(self.length);
+        self.length += record.len() as u64;
+        Ok(())
+    }
+
+    // Données puis index sur disque : un index durable ne désigne jamais un
+    // enregistrement perdu
+    fn sync(&self) -> Result<(), String> {
+        self.data.sync_data().and_then(|_| self.index.sync_data()).map_err(|e| e.to_string())
+    }
+
+    // Garde les `count` premiers blocs
+    fn truncate(&mut self, count: usize) -> Result<(), String> {
+        let Some(&end) = self.offsets.get(count) else {
+            return Ok(());
+        };
+        self.mapping = None;
+        self.data.set_len(end).map_err(|e| e.to_string())?;
+        self.index.set_len((count * INDEX_ENTRY) as u64).map_err(|e| e.to_string())?;
+        self.offsets.truncate(count);
+        self.length = end;
+        self.remap()
+    }
+}
+
+// Blocs dans des segments en ajout seul, lus par projection en mémoire. Chaque
+// enregistrement porte le CRC-32 du bloc encodé, vérifié à chaque lecture ; à
+// l'ouverture, la fin d'un segment interrompue par un arrêt brutal est tronquée.
+//
+// `append` ne rend la main qu'une fois les blocs sur disque (fdatasync des
+// données puis de l'index de chaque segment écrit, fsync du répertoire à la
+// création d'un segment) : un bloc ajouté survit à une coupure de courant. Les
+// troncatures ne sont pas synchronisées, des blocs supprimés pouvant
+// réapparaître après une coupure sans que la chaîne stockée cesse d'être valide.
+pub struct BlockFile {
+    dir: PathBuf,
+    segments: Vec<Segment>,
+    segment_size: u64,
+}
+
+impl BlockFile {
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, String> {
+        Self::with_segment_size(dir, SEGMENT_SIZE)
+    }
+
+    fn with_segment_size(dir: impl Into<PathBuf>, segment_size: u64) -> Result<Self, String> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let mut firsts: Vec<u64> = std::fs::read_dir(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.strip_suffix(".blk")?.parse().ok())
+            .collect();
+        firsts.sort_unstable();
+
+        let mut segments: Vec<Segment> = Vec::new();
+        for (position, first) in firsts.iter().enumerate() {
+            let mut segment = Segment::open(&dir, *first)?;
+            // L'index d'un segment plein est vérifié par son dernier enregistrement
+            if position + 1 == firsts.len() || segment.records_end() != Some(segment.length) {
+                segment.recover()?;
+            }
+            if segments.last().is_some_and(|previous| previous.end() != segment.first) {
+                return Err(format!("{}: segment {} does not follow the previous one", dir.display(), first));
+            }
+            segments.push(segment);
+        }
+        let mut store = BlockFile { dir, segments, segment_size };
+        // Segment créé mais resté vide
+        if store.segments.last().is_some_and(|segment| segment.offsets.is_empty()) {
+            let first = store.segments.last().unwrap().first;
+            store.remove_segments(first)?;
+        }
+        Ok(store)
+    }
+
+    // Supprime les segments commençant à `first` ou après
+    fn remove_segments(&mut self, first: u64) -> Result<(), String> {
+        while self.segments.last().is_some_and(|segment| segment.first >= first) {
+            let segment = self.segments.pop().unwrap();
+            let (data_path, index_path) = segment_paths(&self.dir, segment.first);
+            drop(segment);
+            for path in [data_path, index_path] {
+                std::fs::remove_file(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ChainStore for BlockFile {
+    fn range(&self) -> Range<u64> {
+        match (self.segments.first(), self.segments.last()) {
+            (Some(first), Some(last)) => first.first..last.end(),
+            _ => 0..0,
+        }
+    }
+
+    fn get(&self, index: u64) -> Result<Block, String> {
+        let segment = self.segments.iter().rev()
+            .find(|segment| segment.first <= index)
+            .filter(|segment| index < segment.end())
+            .ok_or_else(|| format!("Block {} not stored", index))?;
+        let offset = segment.offsets[(index - segment.first) as usize];
+        let payload = record(segment.bytes(), offset).ok_or_else(|| format!("Block {}: corrupted record", index))?;
+        let block = decode_block(payload).map_err(|e| format!("Block {}: {}", index, e))?;
+        if block.index != index {
+            return Err(format!("Block {}: stored block has index {}", index, block.index));
+        }
+        Ok(block)
+    }
+
+    fn append(&mut self, blocks: &[Block]) -> Result<(), String> {
+        for block in blocks {
+            let stored = self.range();
+            if !stored.is_empty() && block.index != stored.end {
+                return Err(format!("Block {} does not follow the stored blocks", block.index));
+            }
+            if self.segments.last().is_none_or(|segment| segment.length >= self.segment_size) {
+                self.segments.push(Segment::open(&self.dir, block.index)?);
+                File::open(&self.dir).and_then(|dir| dir.sync_all()).map_err(|e| format!("{}: {}", self.dir.display(), e))?;
+            }
+            self.segments.last_mut().unwrap().append(block)?;
+        }
+        // Synchronisation et projections des segments écrits
+        for segment in &mut self.segments {
+            if segment.bytes().len() as u64 != segment.length {
+                segment.sync()?;
+                segment.remap()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn truncate(&mut self, index: u64) -> Result<(), String> {
+        let stored = self.range();
+        if index <= stored.start {
+            return self.remove_segments(stored.start);
+        }
+        self.remove_segments(index)?;
+        if let Some(segment) = self.segments.last_mut() {
+            segment.truncate((index - segment.first) as usize)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::OpenOptions;
+    use std::os::unix::fs::FileExt;
+    use std::path::PathBuf;
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+
+    use super::{segment_paths, BlockFile, ChainStore, INDEX_ENTRY, RECORD_HEADER};
+    use crate::encoding::encode_block;
+    use crate::Block;
+
+    // Répertoire vide propre au test
+    fn store_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("supplyx-blockstore-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    // Bloc vide d'index `index`, signé par une clé fixe
+    fn block(index: u64) -> Block {
+        let secret = SecretKey::from_bytes(&[9; 32]).unwrap();
+        let validator = Keypair { public: PublicKey::from(&secret), secret };
+        let current_hash = vec![index as u8; 32];
+        Block {
+            chain_id: 1,
+            index,
+            slot: index,
+            timestamp: 1_700_000_000 + index * 5,
+            difficulty: 1,
+            protocol_version: 1,
+            rules_fingerprint: Vec::new(),
+            transactions: Vec::new(),
+            private_transactions: Vec::new(),
+            governance_transactions: Vec::new(),
+            previous_hash: vec![index.wrapping_sub(1) as u8; 32],
+            transactions_root: vec![0; 32],
+            state_root: vec![0; 32],
+            snapshot_hash: Vec::new(),
+            checkpoint: None,
+            validator_signature: validator.sign(&current_hash),
+            current_hash,
+            validator_pubkey: validator.public,
+        }
+    }
+
+    fn blocks(range: std::ops::Range<u64>) -> Vec<Block> {
+        range.map(block).collect()
+    }
+
+    fn encoded_length(index: u64) -> u64 {
+        encode_block(&block(index)).len() as u64
+    }
+
+    fn stored(store: &BlockFile) -> Vec<u64> {
+        store.blocks(store.range()).map(|block| block.unwrap().index).collect()
+    }
+
+    // Position de l'enregistrement du `position`-ième bloc du segment `first`
+    fn record_offset(dir: &std::path::Path, first: u64, position: usize) -> u64 {
+        let index = std::fs::read(segment_paths(dir, first).1).unwrap();
+        u64::from_be_bytes(index[position * INDEX_ENTRY..][..INDEX_ENTRY].try_into().unwrap())
+    }
+
+    fn open_file(path: PathBuf) -> std::fs::File {
+        OpenOptions::new().read(true).write(true).open(path).unwrap()
+    }
+
+    #[test]
+    fn recovers_unindexed_and_torn_records() {
+        let dir = store_dir("torn");
+        let mut store = BlockFile::open(&dir).unwrap();
+        store.append(&blocks(0..6)).unwrap();
+        drop(store);
+        let (data_path, index_path) = segment_paths(&dir, 0);
+
+        // Arrêt entre l'écriture du bloc et celle de son entrée d'index
+        let index = open_file(index_path.clone());
+        index.set_len(5 * INDEX_ENTRY as u64).unwrap();
+        let store = BlockFile::open(&dir).unwrap();
+        assert_eq!(stored(&store), (0..6).collect::<Vec<_>>());
+        assert_eq!(std::fs::metadata(&index_path).unwrap().len(), 6 * INDEX_ENTRY as u64);
+        drop(store);
+
+        // Arrêt au milieu de l'écriture du dernier bloc
+        let data = open_file(data_path.clone());
+        data.set_len(data.metadata().unwrap().len() - 3).unwrap();
+        let mut store = BlockFile::open(&dir).unwrap();
+        assert_eq!(stored(&store), (0..5).collect::<Vec<_>>());
+        assert_eq!(data.metadata().unwrap().len(), record_offset(&dir, 0, 4) + (RECORD_HEADER as u64) + encoded_length(4));
+        store.append(&[block(5)]).unwrap();
+        drop(store);
+        assert_eq!(stored(&BlockFile::open(&dir).unwrap()), (0..6).collect::<Vec<_>>());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn drops_records_with_a_bad_checksum() {
+        let dir = store_dir("checksum");
+        let mut store = BlockFile::open(&dir).unwrap();
+        store.append(&blocks(0..5)).unwrap();
+
+        // Le bloc altéré est refusé à la lecture, puis tronqué à l'ouverture avec
+        // les blocs qui le suivent
+        let data = open_file(segment_paths(&dir, 0).0);
+        let offset = record_offset(&dir, 0, 3) + RECORD_HEADER as u64 + 10;
+        let mut byte = [0];
+        data.read_exact_at(&mut byte, offset).unwrap();
+        data.write_all_at(&[byte[0] ^ 1], offset).unwrap();
+        assert!(store.get(3).unwrap_err().contains("corrupted record"));
+        assert_eq!(store.get(4).unwrap().index, 4);
+        drop(store);
+        let store = BlockFile::open(&dir).unwrap();
+        assert_eq!(stored(&store), (0..3).collect::<Vec<_>>());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn spans_several_segments() {
+        let dir = store_dir("segments");
+        let segment_size = 2 * (RECORD_HEADER as u64 + encoded_length(0));
+        let mut store = BlockFile::with_segment_size(&dir, segment_size).unwrap();
+        store.append(&blocks(0..3)).unwrap();
+        store.append(&blocks(3..7)).unwrap();
+        assert!(store.append(&[block(8)]).is_err());
+        drop(store);
+
+        let firsts = |dir: &std::path::Path| {
+            let mut firsts: Vec<String> = std::fs::read_dir(dir).unwrap()
+                .filter_map(|entry| entry.unwrap().file_name().to_str()?.strip_suffix(".blk").map(str::to_string))
+                .collect();
+            firsts.sort();
+            firsts.iter().map(|first| first.parse().unwrap()).collect::<Vec<u64>>()
+        };
+        assert_eq!(firsts(&dir), vec![0, 2, 4, 6]);
+        let mut store = BlockFile::with_segment_size(&dir, segment_size).unwrap();
+        assert_eq!(stored(&store), (0..7).collect::<Vec<_>>());
+
+        // Une troncature supprime les segments suivants et raccourcit le dernier
+        store.truncate(3).unwrap();
+        assert_eq!(firsts(&dir), vec![0, 2]);
+        store.append(&blocks(3..5)).unwrap();
+        drop(store);
+        let store = BlockFile::with_segment_size(&dir, segment_size).unwrap();
+        assert_eq!(stored(&store), (0..5).collect::<Vec<_>>());
+        drop(store);
+
+        // Un segment plein endommagé rompt la suite des segments
+        let data = open_file(segment_paths(&dir, 0).0);
+        data.set_len(data.metadata().unwrap().len() - 1).unwrap();
+        let error = BlockFile::with_segment_size(&dir, segment_size).err().unwrap();
+        assert!(error.contains("does not follow"), "{}", error);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
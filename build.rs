@@ -0,0 +1,79 @@
+// Modules dont dépend la validité des blocs et de l'état : compilés à
+// l'identique dans tous les profils, ils ne peuvent dépendre d'aucune feature
+const CONSENSUS_SOURCES: &[&str] = &[
+    "src/accounts.rs",
+    "src/address.rs",
+    "src/anchors.rs",
+    "src/assets.rs",
+    "src/beacon.rs",
+    "src/buildinfo.rs",
+    "src/checkpoint.rs",
+    "src/clock.rs",
+    "src/compliance.rs",
+    "src/confidential.rs",
+    "src/config.rs",
+    "src/conformance.rs",
+    "src/contracts.rs",
+    "src/difficulty.rs",
+    "src/encoding.rs",
+    "src/escrow.rs",
+    "src/gas.rs",
+    "src/governance.rs",
+    "src/incidents.rs",
+    "src/ledger.rs",
+    "src/liveness.rs",
+    "src/main.rs",
+    "src/multisig.rs",
+    "src/privacy.rs",
+    "src/recovery.rs",
+    "src/rewards.rs",
+    "src/rotation.rs",
+    "src/scheduler.rs",
+    "src/scoring.rs",
+    "src/snapshot.rs",
+    "src/statetree.rs",
+    "src/upgrade.rs",
+    "src/vesting.rs",
+    "supplyx-core/src/encoding.rs",
+    "supplyx-core/src/merkle.rs",
+    "supplyx-core/src/transaction.rs",
+];
+
+// Dans un fichier, seul le code qui précède ce marqueur est vérifié : main.rs
+// démarre ensuite les services de chaque profil. Avant lui, seules les
+// déclarations des modules hors consensus peuvent dépendre d'une feature.
+const CONSENSUS_END_MARKER: &str = "// Fin du code de consensus";
+
+// Déclaration `mod <nom>;` d'un module hors consensus, qui peut dépendre d'une feature
+fn optional_module(line: &str) -> bool {
+    line.trim().strip_prefix("mod ").and_then(|rest| rest.strip_suffix(';'))
+        .is_some_and(|name| !CONSENSUS_SOURCES.contains(&format!("src/{}.rs", name).as_str()))
+}
+
+fn check_consensus_sources() -> Result<(), String> {
+    for path in CONSENSUS_SOURCES {
+        println!("cargo:rerun-if-changed={}", path);
+        let source = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let consensus = source.split(CONSENSUS_END_MARKER).next().unwrap_or_default();
+        let lines: Vec<&str> = consensus.lines().collect();
+        for (number, line) in lines.iter().enumerate() {
+            if !(line.contains("cfg") && line.contains("feature")) {
+                continue;
+            }
+            if !lines.get(number + 1).is_some_and(|next| optional_module(next)) {
+                return Err(format!("{}:{}: consensus code must not depend on Cargo features", path, number + 1));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    check_consensus_sources()?;
+    // protoc fourni par protoc-bin-vendored : aucune installation système requise
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    tonic_build::compile_protos("proto/replication.proto")?;
+    tonic_build::compile_protos("proto/query.proto")?;
+    tonic_build::compile_protos("proto/admin.proto")?;
+    Ok(())
+}
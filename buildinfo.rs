@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use ed25519_dalek::PublicKey;
+use sha3::{Digest, Sha3_256};
+
+use crate::config::ChainConfig;
+use crate::conformance;
+use crate::encoding::{Encoder, RULES_FINGERPRINT_DOMAIN};
+use crate::upgrade::PROTOCOL_VERSION;
+
+// Version des règles de consensus, incrémentée à chaque changement d'encodage
+// ou de validation
+pub const CONSENSUS_RULES_VERSION: u32 = 23;
+
+pub const PACKAGE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Hash des jeux de conformance produits par ce binaire : il reflète le
+// comportement effectif des encodages et de la validation, indépendamment de
+// la plateforme, du compilateur ou de la mise en forme des sources
+fn conformance_digest() -> &'static [u8] {
+    static DIGEST: OnceLock<Vec<u8>> = OnceLock::new();
+    DIGEST.get_or_init(|| {
+        let vectors = serde_json::to_vec(&conformance::generate()).unwrap();
+        Sha3_256::digest(&vectors).to_vec()
+    })
+}
+
+// Empreinte des règles de consensus d'un nœud : deux nœuds de même empreinte
+// appliquent les mêmes règles à partir des mêmes paramètres
+pub fn consensus_fingerprint(config: &ChainConfig) -> Vec<u8> {
+    let params = &config.consensus;
+    let mut encoder = Encoder::new(RULES_FINGERPRINT_DOMAIN);
+    encoder.put_u32(CONSENSUS_RULES_VERSION);
+    encoder.put_u32(PROTOCOL_VERSION);
+    for value in [
+        config.chain_id,
+        params.min_registration_stake,
+        params.min_producer_stake,
+        params.max_block_transactions,
+        params.max_block_gas,
+        params.upgrade_threshold,
+        params.upgrade_epochs,
+        params.max_active_validators,
+        config.min_contribution_score.to_bits(),
+        config.initial_difficulty,
+        config.target_block_interval,
+        config.community_pool_percent,
+        config.max_timestamp_step.unwrap_or(0),
+    ] {
+        encoder.put_u64(value);
+    }
+    encoder.put_bytes(conformance_digest());
+    encoder.hash()
+}
+
+// Empreinte différente de l'empreinte locale, annoncée par un producteur dans
+// l'en-tête de son dernier bloc
+#[derive(Clone, Debug, PartialEq)]
+pub struct FingerprintMismatch {
+    pub fingerprint: Vec<u8>,
+    pub height: u64,
+}
+
+// Empreinte locale et producteurs dont le dernier bloc en annonce une autre
+#[derive(Clone, Debug, Default)]
+pub struct RulesMonitor {
+    fingerprint: Vec<u8>,
+    mismatches: HashMap<PublicKey, FingerprintMismatch>,
+}
+
+impl RulesMonitor {
+    pub fn new(fingerprint: Vec<u8>) -> Self {
+        RulesMonitor { fingerprint, mismatches: HashMap::new() }
+    }
+
+    pub fn fingerprint(&self) -> &[u8] {
+        &self.fingerprint
+    }
+
+    pub fn mismatches(&self) -> &HashMap<PublicKey, FingerprintMismatch> {
+        &self.mismatches
+    }
+
+    // Sans empreinte locale, rien n'est comparé. Retourne `true` lorsque le
+    // producteur vient de diverger (ou d'annoncer une nouvelle empreinte différente).
+    pub fn record(&mut self, producer: PublicKey, fingerprint: &[u8], height: u64) -> bool {
+        if self.fingerprint.is_empty() || fingerprint == self.fingerprint {
+            self.mismatches.remove(&producer);
+            return false;
+        }
+        let mismatch = FingerprintMismatch { fingerprint: fingerprint.to_vec(), height };
+        let previous = self.mismatches.insert(producer, mismatch);
+        previous.is_none_or(|previous| previous.fingerprint != fingerprint)
+    }
+}
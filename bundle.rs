@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use ed25519_dalek::PublicKey;
+
+use crate::checkpoint::Checkpoint;
+use crate::encoding::{decode_header_from, encode_header_into, Decoder, Encoder, PROOF_BUNDLE_DOMAIN};
+use crate::light::HeaderSource;
+use crate::merkle::{verify_proof, MerkleProof};
+use crate::snapshot::StateSnapshot;
+use crate::{BlockHeader, Blockchain, Validator};
+
+// Fait attesté par un lot de preuves
+#[derive(Clone, Debug)]
+pub enum Fact {
+    // Transaction incluse dans le bloc `height`
+    TransactionIncluded { transaction_hash: Vec<u8>, height: u64, proof: MerkleProof },
+    // Solde d'un compte dans l'instantané d'état (encodé) engagé par le bloc suivant
+    Balance { account: PublicKey, balance: u64, snapshot: Vec<u8> },
+}
+
+// Lot autonome pour une notarisation par un tiers, vérifiable sans accès au
+// réseau : le fait, les en-têtes chaînés depuis le bloc qui l'engage jusqu'au
+// bloc de checkpoint et le certificat de quorum de ce checkpoint. Le verdict
+// vaut pour l'ensemble de validateurs inclus, dont le hash est à comparer à
+// une référence de confiance.
+#[derive(Clone, Debug)]
+pub struct ProofBundle {
+    pub fact: Fact,
+    // (clé, mise) triés par clé
+    pub validators: Vec<(PublicKey, u64)>,
+    pub headers: Vec<BlockHeader>,
+    pub checkpoint: Checkpoint,
+}
+
+impl ProofBundle {
+    // Lot pour une transaction incluse dans un bloc finalisé
+    pub fn transaction(chain: &Blockchain, transaction_hash: &[u8]) -> Result<ProofBundle, &'static str> {
+        let (height, proof) = chain.transaction_proof(transaction_hash).ok_or("Unknown transaction")?;
+        let fact = Fact::TransactionIncluded { transaction_hash: transaction_hash.to_vec(), height, proof };
+        Self::finalized(chain, fact, height)
+    }
+
+    // Lot pour le solde d'un compte dans le dernier instantané d'état finalisé
+    pub fn balance(chain: &Blockchain, account: &PublicKey) -> Result<ProofBundle, &'static str> {
+        let snapshot = chain.latest_snapshot.as_ref().ok_or("No snapshot available")?;
+        let fact = Fact::Balance { account: *account, balance: snapshot.ledger.balance(account), snapshot: snapshot.encode() };
+        Self::finalized(chain, fact, snapshot.height + 1)
+    }
+
+    // En-têtes depuis `anchor` jusqu'au premier checkpoint finalisé qui le couvre
+    fn finalized(chain: &Blockchain, fact: Fact, anchor: u64) -> Result<ProofBundle, &'static str> {
+        let (included_at, checkpoint) = chain.chain.iter()
+            .filter_map(|block| Some((block.index, block.checkpoint.as_ref()?)))
+            .find(|(_, checkpoint)| checkpoint.height >= anchor)
+            .ok_or("Fact not finalized yet")?;
+        let checkpoint = checkpoint.clone();
+        let count = (checkpoint.height - anchor + 1) as usize;
+        let headers = chain.headers(anchor, count);
+        if headers.len() != count {
+            return Err("Headers not available");
+        }
+        // Clés en vigueur au bloc qui a inclus le certificat, signé avant d'éventuelles rotations
+        let mut validators: Vec<(PublicKey, u64)> = chain.validators.values()
+            .map(|v| (chain.key_history.key_at(&v.public_key, included_at), v.stake))
+            .collect();
+        validators.sort_by_key(|(key, _)| key.to_bytes());
+        Ok(ProofBundle { fact, validators, headers, checkpoint })
+    }
+
+    // Hash de l'ensemble de validateurs auquel le certificat de quorum est vérifié
+    pub fn validator_set_hash(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(PROOF_BUNDLE_DOMAIN);
+        encoder.put_u8(0);
+        encode_validators(&self.validators, &mut encoder);
+        encoder.hash()
+    }
+
+    // Chaînage et signatures des en-têtes, quorum du checkpoint sur le dernier
+    // en-tête, puis preuve du fait contre le premier
+    pub fn verify(&self) -> Result<(), &'static str> {
+        let first = self.headers.first().ok_or("Bundle without headers")?;
+        for header in &self.headers {
+            header.verify()?;
+        }
+        for pair in self.headers.windows(2) {
+            if pair[1].chain_id != pair[0].chain_id {
+                return Err("Bundle headers from different chains");
+            }
+            if pair[1].index != pair[0].index + 1 || pair[1].previous_hash != pair[0].current_hash {
+                return Err("Bundle headers not chained");
+            }
+        }
+        let last = self.headers.last().unwrap();
+        if self.checkpoint.height != last.index || self.checkpoint.block_hash != last.current_hash {
+            return Err("Checkpoint does not certify the last header");
+        }
+        let validators: HashMap<PublicKey, Validator> = self.validators.iter()
+            .map(|(key, stake)| {
+                let validator = Validator {
+                    public_key: *key,
+                    stake: *stake,
+                    contribution_score: 0.0,
+                    last_validated_block: None,
+                    missed_slots: 0,
+                    inactive_since: None,
+                    standby: false,
+                };
+                (*key, validator)
+            })
+            .collect();
+        self.checkpoint.verify(&validators)?;
+
+        match &self.fact {
+            Fact::TransactionIncluded { transaction_hash, height, proof } => {
+                if first.index != *height {
+                    return Err("Bundle does not start at the transaction block");
+                }
+                if !verify_proof(&first.transactions_root, transaction_hash, proof) {
+                    return Err("Invalid inclusion proof");
+                }
+            }
+            Fact::Balance { account, balance, snapshot } => {
+                let snapshot = StateSnapshot::decode(snapshot)?;
+                if first.index != snapshot.height + 1 || first.previous_hash != snapshot.block_hash {
+                    return Err("Bundle does not start at the snapshot commitment");
+                }
+                if first.snapshot_hash != snapshot.hash() {
+                    return Err("Snapshot hash mismatch");
+                }
+                if snapshot.ledger.balance(account) != *balance {
+                    return Err("Balance does not match the snapshot");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(PROOF_BUNDLE_DOMAIN);
+        match &self.fact {
+            Fact::TransactionIncluded { transaction_hash, height, proof } => {
+                encoder.put_u8(0);
+                encoder.put_bytes(transaction_hash);
+                encoder.put_u64(*height);
+                encoder.put_u64(proof.index);
+                encoder.put_u64(proof.leaf_count);
+                encoder.put_u32(proof.siblings.len() as u32);
+                for sibling in &proof.siblings {
+                    encoder.put_bytes(sibling);
+                }
+            }
+            Fact::Balance { account, balance, snapshot } => {
+                encoder.put_u8(1);
+                encoder.put_public_key(account);
+                encoder.put_u64(*balance);
+                encoder.put_bytes(snapshot);
+            }
+        }
+        encode_validators(&self.validators, &mut encoder);
+        encoder.put_u32(self.headers.len() as u32);
+        for header in &self.headers {
+            encode_header_into(header, &mut encoder);
+        }
+        self.checkpoint.encode_into(&mut encoder);
+        encoder.finish()
+    }
+
+    pub fn decode(data: &[u8]) -> Result<ProofBundle, &'static str> {
+        let mut decoder = Decoder::new(data, PROOF_BUNDLE_DOMAIN)?;
+        let fact = match decoder.get_u8()? {
+            0 => {
+                let transaction_hash = decoder.get_bytes()?.to_vec();
+                let height = decoder.get_u64()?;
+                let index = decoder.get_u64()?;
+                let leaf_count = decoder.get_u64()?;
+                let mut siblings = Vec::new();
+                for _ in 0..decoder.get_u32()? {
+                    siblings.push(decoder.get_bytes()?.to_vec());
+                }
+                Fact::TransactionIncluded { transaction_hash, height, proof: MerkleProof { index, leaf_count, siblings } }
+            }
+            1 => Fact::Balance {
+                account: decoder.get_public_key()?,
+                balance: decoder.get_u64()?,
+                snapshot: decoder.get_bytes()?.to_vec(),
+            },
+            _ => return Err("Unknown bundle fact"),
+        };
+        let mut validators: Vec<(PublicKey, u64)> = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            let key = decoder.get_public_key()?;
+            if validators.last().is_some_and(|(previous, _)| previous.to_bytes() >= key.to_bytes()) {
+                return Err("Validators not in canonical order");
+            }
+            validators.push((key, decoder.get_u64()?));
+        }
+        let mut headers = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            headers.push(decode_header_from(&mut decoder)?);
+        }
+        let checkpoint = Checkpoint::decode_from(&mut decoder)?;
+        decoder.finish()?;
+        Ok(ProofBundle { fact, validators, headers, checkpoint })
+    }
+}
+
+fn encode_validators(validators: &[(PublicKey, u64)], encoder: &mut Encoder) {
+    encoder.put_u32(validators.len() as u32);
+    for (key, stake) in validators {
+        encoder.put_public_key(key);
+        encoder.put_u64(*stake);
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::{HashMap, HashSet};
+use ed25519_dalek::{PublicKey, Signature, Verifier};
+
+use crate::encoding::{Decoder, Encoder, CHECKPOINT_DOMAIN, CHECKPOINT_VOTE_DOMAIN};
+use crate::signer::Signer;
+use crate::Validator;
+
+// Signature d'un validateur sur le bloc de checkpoint d'une époque
+#[derive(Clone, Debug)]
+pub struct CheckpointVote {
+    pub height: u64,
+    pub block_hash: Vec<u8>,
+    pub validator: PublicKey,
+    pub signature: Signature,
+}
+
+impl CheckpointVote {
+    fn signing_hash(height: u64, block_hash: &[u8]) -> Vec<u8> {
+        let mut encoder = Encoder::new(CHECKPOINT_VOTE_DOMAIN);
+        encoder.put_u64(height);
+        encoder.put_bytes(block_hash);
+        encoder.hash()
+    }
+
+    pub fn new(signer: &dyn Signer, height: u64, block_hash: Vec<u8>) -> Result<Self, &'static str> {
+        let signature = signer.sign(&Self::signing_hash(height, &block_hash))?;
+        Ok(CheckpointVote {
+            height,
+            block_hash,
+            validator: signer.public_key(),
+            signature,
+        })
+    }
+
+    pub fn verify(&self) -> Result<(), &'static str> {
+        self.validator.verify(&Self::signing_hash(self.height, &self.block_hash), &self.signature)
+            .map_err(|_| "Invalid checkpoint vote signature")
+    }
+}
+
+// Votes agrégés d'au moins 2/3 de la mise, inclus dans un bloc ultérieur
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    pub height: u64,
+    pub block_hash: Vec<u8>,
+    pub votes: Vec<CheckpointVote>,
+}
+
+impl Checkpoint {
+    pub fn hash(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(CHECKPOINT_DOMAIN);
+        self.encode_into(&mut encoder);
+        encoder.hash()
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        encoder.put_u64(self.height);
+        encoder.put_bytes(&self.block_hash);
+        encoder.put_u32(self.votes.len() as u32);
+        for vote in &self.votes {
+            encoder.put_public_key(&vote.validator);
+            encoder.put_signature(&vote.signature);
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Checkpoint, &'static str> {
+        let height = decoder.get_u64()?;
+        let block_hash = decoder.get_bytes()?.to_vec();
+        let count = decoder.get_u32()?;
+        let mut votes = Vec::new();
+        for _ in 0..count {
+            votes.push(CheckpointVote {
+                height,
+                block_hash: block_hash.clone(),
+                validator: decoder.get_public_key()?,
+                signature: decoder.get_signature()?,
+            });
+        }
+        Ok(Checkpoint { height, block_hash, votes })
+    }
+
+    // Votes distincts, valides, de validateurs enregistrés, totalisant au moins 2/3 de la mise
+    pub fn verify(&self, validators: &HashMap<PublicKey, Validator>) -> Result<(), &'static str> {
+        let mut signers = HashSet::new();
+        let mut signed_stake: u128 = 0;
+        for vote in &self.votes {
+            if vote.height != self.height || vote.block_hash != self.block_hash {
+                return Err("Checkpoint vote for another block");
+            }
+            if !signers.insert(vote.validator.to_bytes()) {
+                return Err("Duplicate checkpoint vote");
+            }
+            let validator = validators.get(&vote.validator).ok_or("Checkpoint vote from unknown validator")?;
+            vote.verify()?;
+            signed_stake += validator.stake as u128;
+        }
+
+        if !has_quorum(signed_stake, validators) {
+            return Err("Checkpoint lacks two-thirds of stake");
+        }
+        Ok(())
+    }
+}
+
+pub fn has_quorum(signed_stake: u128, validators: &HashMap<PublicKey, Validator>) -> bool {
+    let total_stake: u128 = validators.values().map(|v| v.stake as u128).sum();
+    total_stake > 0 && signed_stake * 3 >= total_stake * 2
+}
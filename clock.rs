@@ -0,0 +1,45 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+// Heure du nœud : horodatage des blocs et transactions, échéances, créneaux
+pub trait Clock: Send + Sync {
+    // Durée écoulée depuis l'époque Unix
+    fn now(&self) -> Duration;
+
+    fn unix_secs(&self) -> u64 {
+        self.now().as_secs()
+    }
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap()
+    }
+}
+
+// Horloge déterministe, avancée à la main ; ses clones partagent la même heure
+#[derive(Clone, Default)]
+pub struct MockClock {
+    millis: Arc<AtomicU64>,
+}
+
+impl MockClock {
+    pub fn new(start: Duration) -> Self {
+        let clock = MockClock::default();
+        clock.set(start);
+        clock
+    }
+
+    pub fn set(&self, now: Duration) {
+        self.millis.store(now.as_millis() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Duration {
+        Duration::from_millis(self.millis.load(Ordering::SeqCst))
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::{HashMap, HashSet};
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, Verifier};
+
+use crate::encoding::{Decoder, Encoder, FREEZE_DOMAIN};
+use crate::governance::GovernanceTransaction;
+
+// Ordre de gel (ou de dégel) d'un compte, signé par un administrateur de
+// conformité. Le motif reste hors chaîne ; seul son hash est publié.
+#[derive(Clone, Debug)]
+pub struct FreezeOrder {
+    pub issuer: PublicKey,
+    pub account: PublicKey,
+    pub freeze: bool,
+    pub reason_hash: Vec<u8>,
+    pub timestamp: u64,
+    pub signature: Signature,
+}
+
+impl FreezeOrder {
+    pub fn new(keypair: &Keypair, account: PublicKey, freeze: bool, reason_hash: Vec<u8>, timestamp: u64) -> Self {
+        let signature = keypair.sign(&Self::signing_hash(&keypair.public, &account, freeze, &reason_hash, timestamp));
+        FreezeOrder { issuer: keypair.public, account, freeze, reason_hash, timestamp, signature }
+    }
+
+    pub fn signing_hash(issuer: &PublicKey, account: &PublicKey, freeze: bool, reason_hash: &[u8], timestamp: u64) -> Vec<u8> {
+        let mut encoder = Encoder::new(FREEZE_DOMAIN);
+        encoder.put_public_key(issuer);
+        encoder.put_public_key(account);
+        encoder.put_u8(freeze as u8);
+        encoder.put_bytes(reason_hash);
+        encoder.put_u64(timestamp);
+        encoder.hash()
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Self::signing_hash(&self.issuer, &self.account, self.freeze, &self.reason_hash, self.timestamp)
+    }
+
+    pub fn verify(&self) -> Result<(), &'static str> {
+        if self.reason_hash.len() != 32 {
+            return Err("Invalid freeze reason hash");
+        }
+        self.issuer.verify(&self.hash(), &self.signature).map_err(|_| "Invalid freeze order signature")
+    }
+}
+
+// Gel en vigueur sur un compte : qui l'a ordonné, pourquoi et à quelle hauteur
+#[derive(Clone, Debug, PartialEq)]
+pub struct FreezeRecord {
+    pub issuer: PublicKey,
+    pub reason_hash: Vec<u8>,
+    pub height: u64,
+}
+
+// Mode permissionné : administrateurs de conformité fixés à la genèse et comptes gelés
+#[derive(Clone, Debug, Default)]
+pub struct Compliance {
+    admins: HashSet<PublicKey>,
+    frozen: HashMap<PublicKey, FreezeRecord>,
+}
+
+impl Compliance {
+    pub fn add_admin(&mut self, admin: PublicKey) {
+        self.admins.insert(admin);
+    }
+
+    pub fn freeze_record(&self, account: &PublicKey) -> Option<&FreezeRecord> {
+        self.frozen.get(account)
+    }
+
+    // Un compte gelé ne peut ni envoyer ni recevoir
+    pub fn check_transfer(&self, sender: &PublicKey, recipient: &PublicKey) -> Result<(), &'static str> {
+        if self.frozen.contains_key(sender) || self.frozen.contains_key(recipient) {
+            return Err("Account is frozen");
+        }
+        Ok(())
+    }
+
+    // Ordres d'administrateurs connus, chacun changeant effectivement l'état du compte visé
+    pub fn validate(&self, transactions: &[GovernanceTransaction]) -> Result<(), &'static str> {
+        let mut changed: HashMap<PublicKey, bool> = HashMap::new();
+        for transaction in transactions {
+            let GovernanceTransaction::Freeze(order) = transaction else {
+                continue;
+            };
+            if !self.admins.contains(&order.issuer) {
+                return Err("Freeze order from unknown compliance admin");
+            }
+            let frozen = changed.get(&order.account).copied().unwrap_or_else(|| self.frozen.contains_key(&order.account));
+            if frozen == order.freeze {
+                return Err(if order.freeze { "Account already frozen" } else { "Account not frozen" });
+            }
+            changed.insert(order.account, order.freeze);
+        }
+        Ok(())
+    }
+
+    // Applique les ordres (déjà validés) du bloc `height` ; retourne ceux appliqués
+    pub fn apply(&mut self, transactions: &[GovernanceTransaction], height: u64) -> Vec<FreezeOrder> {
+        let mut applied = Vec::new();
+        for transaction in transactions {
+            let GovernanceTransaction::Freeze(order) = transaction else {
+                continue;
+            };
+            if order.freeze {
+                self.frozen.insert(order.account, FreezeRecord {
+                    issuer: order.issuer,
+                    reason_hash: order.reason_hash.clone(),
+                    height,
+                });
+            } else {
+                self.frozen.remove(&order.account);
+            }
+            applied.push(order.clone());
+        }
+        applied
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        let mut admins: Vec<&PublicKey> = self.admins.iter().collect();
+        admins.sort_by_key(|admin| admin.to_bytes());
+        encoder.put_u32(admins.len() as u32);
+        for admin in admins {
+            encoder.put_public_key(admin);
+        }
+
+        let mut frozen: Vec<(&PublicKey, &FreezeRecord)> = self.frozen.iter().collect();
+        frozen.sort_by_key(|(account, _)| account.to_bytes());
+        encoder.put_u32(frozen.len() as u32);
+        for (account, record) in frozen {
+            encoder.put_public_key(account);
+            encoder.put_public_key(&record.issuer);
+            encoder.put_bytes(&record.reason_hash);
+            encoder.put_u64(record.height);
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Compliance, &'static str> {
+        let mut admins = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            admins.push(decoder.get_public_key()?);
+        }
+        if admins.windows(2).any(|pair| pair[0].to_bytes() >= pair[1].to_bytes()) {
+            return Err("Compliance admins not in canonical order");
+        }
+
+        let mut frozen = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            let account = decoder.get_public_key()?;
+            let record = FreezeRecord {
+                issuer: decoder.get_public_key()?,
+                reason_hash: decoder.get_bytes()?.to_vec(),
+                height: decoder.get_u64()?,
+            };
+            frozen.push((account, record));
+        }
+        if frozen.windows(2).any(|pair| pair[0].0.to_bytes() >= pair[1].0.to_bytes()) {
+            return Err("Frozen accounts not in canonical order");
+        }
+        Ok(Compliance {
+            admins: admins.into_iter().collect(),
+            frozen: frozen.into_iter().collect(),
+        })
+    }
+}
@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::{Identity, VartimeMultiscalarMul};
+use ed25519_dalek::PublicKey;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+
+use crate::address::derive_address;
+use crate::encoding::{
+    Decoder, Encoder, CONFIDENTIAL_GENERATOR_DOMAIN, CONFIDENTIAL_POOL_DOMAIN, CONFIDENTIAL_TRANSFER_DOMAIN, RANGE_PROOF_DOMAIN,
+};
+use crate::privacy::EncryptedPayload;
+use crate::{Transaction, TransactionKind};
+
+// Les montants confidentiels sont des entiers de 64 bits
+pub const BITS: usize = 64;
+// Tours de l'argument de produit scalaire : log2(BITS)
+pub const ROUNDS: usize = 6;
+
+// Bases des engagements et des preuves, sans logarithme discret connu entre elles :
+// `value` et `blinding` pour les engagements, `g` et `h` pour les vecteurs de bits
+pub struct Generators {
+    pub value: RistrettoPoint,
+    pub blinding: RistrettoPoint,
+    pub g: Vec<RistrettoPoint>,
+    pub h: Vec<RistrettoPoint>,
+}
+
+fn generator(label: &[u8], index: u32) -> RistrettoPoint {
+    let mut encoder = Encoder::new(CONFIDENTIAL_GENERATOR_DOMAIN);
+    encoder.put_bytes(label);
+    encoder.put_u32(index);
+    let mut shake = Shake256::default();
+    shake.update(&encoder.finish());
+    let mut bytes = [0u8; 64];
+    shake.finalize_xof().read(&mut bytes);
+    RistrettoPoint::from_uniform_bytes(&bytes)
+}
+
+pub fn generators() -> &'static Generators {
+    static GENERATORS: OnceLock<Generators> = OnceLock::new();
+    GENERATORS.get_or_init(|| Generators {
+        value: RISTRETTO_BASEPOINT_POINT,
+        blinding: generator(b"blinding", 0),
+        g: (0..BITS as u32).map(|i| generator(b"g", i)).collect(),
+        h: (0..BITS as u32).map(|i| generator(b"h", i)).collect(),
+    })
+}
+
+// Engagement de Pedersen `value * V + blinding * B` : cache `value`, additif
+pub fn commit(value: u64, blinding: &Scalar) -> RistrettoPoint {
+    let generators = generators();
+    Scalar::from(value) * generators.value + blinding * generators.blinding
+}
+
+// Adresse publique détenant la contrepartie de tous les soldes confidentiels :
+// la masse monétaire reste vérifiable, seule sa répartition est cachée
+pub fn pool_address() -> PublicKey {
+    derive_address(&Encoder::new(CONFIDENTIAL_POOL_DOMAIN).finish())
+}
+
+// Transcription Fiat-Shamir : chaque défi dépend de tout ce qui précède
+pub struct Transcript {
+    data: Vec<u8>,
+}
+
+impl Transcript {
+    pub fn new(commitment: &CompressedRistretto) -> Self {
+        let mut encoder = Encoder::new(RANGE_PROOF_DOMAIN);
+        encoder.put_bytes(commitment.as_bytes());
+        Transcript { data: encoder.finish() }
+    }
+
+    pub fn append(&mut self, bytes: &[u8; 32]) {
+        self.data.extend_from_slice(bytes);
+    }
+
+    pub fn challenge(&mut self) -> Scalar {
+        let mut shake = Shake256::default();
+        shake.update(&self.data);
+        let mut bytes = [0u8; 64];
+        shake.finalize_xof().read(&mut bytes);
+        let challenge = Scalar::from_bytes_mod_order_wide(&bytes);
+        self.append(challenge.as_bytes());
+        challenge
+    }
+}
+
+pub fn powers(base: Scalar) -> Vec<Scalar> {
+    std::iter::successors(Some(Scalar::one()), |power| Some(power * base)).take(BITS).collect()
+}
+
+pub fn combine(scalars: &[Scalar], points: &[RistrettoPoint]) -> RistrettoPoint {
+    RistrettoPoint::vartime_multiscalar_mul(scalars, points)
+}
+
+fn point(bytes: &[u8; 32]) -> Result<RistrettoPoint, &'static str> {
+    CompressedRistretto(*bytes).decompress().ok_or("Invalid range proof point")
+}
+
+fn element(decoder: &mut Decoder) -> Result<[u8; 32], &'static str> {
+    decoder.get_bytes()?.try_into().map_err(|_| "Invalid range proof element")
+}
+
+fn scalar(bytes: &[u8; 32]) -> Result<Scalar, &'static str> {
+    Scalar::from_canonical_bytes(*bytes).ok_or("Invalid range proof scalar")
+}
+
+// Preuve Bulletproofs qu'un engagement cache une valeur de [0, 2^64), sans la
+// révéler ; 672 octets, indépendamment de la valeur
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeProof {
+    pub a: [u8; 32],
+    pub s: [u8; 32],
+    pub t1: [u8; 32],
+    pub t2: [u8; 32],
+    pub tau_x: [u8; 32],
+    pub mu: [u8; 32],
+    pub t_hat: [u8; 32],
+    // Points de chaque tour de l'argument de produit scalaire
+    pub l: Vec<[u8; 32]>,
+    pub r: Vec<[u8; 32]>,
+    // Scalaires restants après le dernier tour
+    pub a_final: [u8; 32],
+    pub b_final: [u8; 32],
+}
+
+impl RangeProof {
+    // Vérifie que `commitment` cache une valeur de [0, 2^64)
+    pub fn verify(&self, commitment: &RistrettoPoint) -> Result<(), &'static str> {
+        let generators = generators();
+        if self.l.len() != ROUNDS || self.r.len() != ROUNDS {
+            return Err("Invalid range proof length");
+        }
+        let mut transcript = Transcript::new(&commitment.compress());
+        transcript.append(&self.a);
+        transcript.append(&self.s);
+        let y = transcript.challenge();
+        let z = transcript.challenge();
+        transcript.append(&self.t1);
+        transcript.append(&self.t2);
+        let x = transcript.challenge();
+        transcript.append(&self.tau_x);
+        transcript.append(&self.mu);
+        transcript.append(&self.t_hat);
+        let w = transcript.challenge();
+        let (tau_x, mu, t_hat) = (scalar(&self.tau_x)?, scalar(&self.mu)?, scalar(&self.t_hat)?);
+
+        // t(x) = t_hat engage sur z² v + δ(y, z) + t1 x + t2 x²
+        let y_powers = powers(y);
+        let two_powers = powers(Scalar::from(2u64));
+        let z2 = z * z;
+        let delta = (z - z2) * y_powers.iter().sum::<Scalar>() - z2 * z * two_powers.iter().sum::<Scalar>();
+        let expected = z2 * commitment + delta * generators.value + x * point(&self.t1)? + x * x * point(&self.t2)?;
+        if t_hat * generators.value + tau_x * generators.blinding != expected {
+            return Err("Range proof polynomial check failed");
+        }
+
+        // P = A + x S - z <1, g> + <z y^i + z² 2^i, h'>, ramené à <l, g> + <r, h'> + t_hat Q
+        let q = w * generators.value;
+        let y_inverse_powers = powers(y.invert());
+        let mut p = point(&self.a)? + x * point(&self.s)? - mu * generators.blinding + t_hat * q;
+        let g_coefficients = vec![-z; BITS];
+        let h_coefficients: Vec<Scalar> = (0..BITS).map(|i| z + z2 * two_powers[i] * y_inverse_powers[i]).collect();
+        p += combine(&g_coefficients, &generators.g) + combine(&h_coefficients, &generators.h);
+
+        let mut g = generators.g.clone();
+        let mut h: Vec<RistrettoPoint> = generators.h.iter().zip(y_inverse_powers).map(|(h, power)| power * h).collect();
+        for (l_bytes, r_bytes) in self.l.iter().zip(&self.r) {
+            transcript.append(l_bytes);
+            transcript.append(r_bytes);
+            let u = transcript.challenge();
+            let u_inverse = u.invert();
+            p += u * u * point(l_bytes)? + u_inverse * u_inverse * point(r_bytes)?;
+            let half = g.len() / 2;
+            g = (0..half).map(|i| g[i] * u_inverse + g[half + i] * u).collect();
+            h = (0..half).map(|i| h[i] * u + h[half + i] * u_inverse).collect();
+        }
+        let (a_final, b_final) = (scalar(&self.a_final)?, scalar(&self.b_final)?);
+        if p != a_final * g[0] + b_final * h[0] + a_final * b_final * q {
+            return Err("Range proof inner product check failed");
+        }
+        Ok(())
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        for bytes in [&self.a, &self.s, &self.t1, &self.t2, &self.tau_x, &self.mu, &self.t_hat] {
+            encoder.put_bytes(bytes);
+        }
+        encoder.put_u32(self.l.len() as u32);
+        for (l, r) in self.l.iter().zip(&self.r) {
+            encoder.put_bytes(l);
+            encoder.put_bytes(r);
+        }
+        encoder.put_bytes(&self.a_final);
+        encoder.put_bytes(&self.b_final);
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<RangeProof, &'static str> {
+        let (a, s, t1, t2) = (element(decoder)?, element(decoder)?, element(decoder)?, element(decoder)?);
+        let (tau_x, mu, t_hat) = (element(decoder)?, element(decoder)?, element(decoder)?);
+        if decoder.get_u32()? as usize != ROUNDS {
+            return Err("Invalid range proof length");
+        }
+        let (mut l, mut r) = (Vec::with_capacity(ROUNDS), Vec::with_capacity(ROUNDS));
+        for _ in 0..ROUNDS {
+            l.push(element(decoder)?);
+            r.push(element(decoder)?);
+        }
+        Ok(RangeProof { a, s, t1, t2, tau_x, mu, t_hat, l, r, a_final: element(decoder)?, b_final: element(decoder)? })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(RANGE_PROOF_DOMAIN);
+        self.encode_into(&mut encoder);
+        encoder.finish()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<RangeProof, &'static str> {
+        let mut decoder = Decoder::new(data, RANGE_PROOF_DOMAIN)?;
+        let proof = RangeProof::decode_from(&mut decoder)?;
+        decoder.finish()?;
+        Ok(proof)
+    }
+}
+
+// Transfert entre soldes confidentiels : le montant n'apparaît que sous forme
+// d'engagement, accompagné de preuves que ni lui ni le solde restant de
+// l'émetteur ne sont négatifs
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfidentialTransfer {
+    pub commitment: [u8; 32],
+    pub amount_proof: RangeProof,
+    // Preuve sur le solde confidentiel de l'émetteur diminué de `commitment`
+    pub balance_proof: RangeProof,
+    // Ouverture de `commitment`, chiffrée pour l'émetteur et le destinataire
+    pub note: EncryptedPayload,
+}
+
+impl ConfidentialTransfer {
+    pub fn amount(&self) -> Result<RistrettoPoint, &'static str> {
+        CompressedRistretto(self.commitment).decompress().ok_or("Invalid amount commitment")
+    }
+
+    // Vérification sans état : le montant engagé est dans [0, 2^64)
+    pub fn verify(&self) -> Result<(), &'static str> {
+        self.amount_proof.verify(&self.amount()?)
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        encoder.put_bytes(&self.commitment);
+        self.amount_proof.encode_into(encoder);
+        self.balance_proof.encode_into(encoder);
+        self.note.encode_into(encoder);
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<ConfidentialTransfer, &'static str> {
+        Ok(ConfidentialTransfer {
+            commitment: decoder.get_bytes()?.try_into().map_err(|_| "Invalid amount commitment")?,
+            amount_proof: RangeProof::decode_from(decoder)?,
+            balance_proof: RangeProof::decode_from(decoder)?,
+            note: EncryptedPayload::decode_from(decoder)?,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(CONFIDENTIAL_TRANSFER_DOMAIN);
+        self.encode_into(&mut encoder);
+        encoder.finish()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<ConfidentialTransfer, &'static str> {
+        let mut decoder = Decoder::new(data, CONFIDENTIAL_TRANSFER_DOMAIN)?;
+        let transfer = ConfidentialTransfer::decode_from(&mut decoder)?;
+        decoder.finish()?;
+        Ok(transfer)
+    }
+}
+
+// Soldes confidentiels : un engagement de Pedersen par compte, l'identité
+// (engagement de zéro) pour un compte sans solde
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConfidentialBalances {
+    balances: HashMap<PublicKey, CompressedRistretto>,
+}
+
+impl ConfidentialBalances {
+    pub fn balance(&self, account: &PublicKey) -> RistrettoPoint {
+        self.balances.get(account)
+            .and_then(|balance| balance.decompress())
+            .unwrap_or_else(RistrettoPoint::identity)
+    }
+
+    // Solde de l'émetteur après la transaction, dont la preuve doit établir la positivité
+    fn remaining<'a>(&self, transaction: &'a Transaction) -> Result<Option<(RistrettoPoint, &'a RangeProof)>, &'static str> {
+        let balance = self.balance(&transaction.sender);
+        match &transaction.kind {
+            TransactionKind::ConfidentialTransfer(transfer) => Ok(Some((balance - transfer.amount()?, &transfer.balance_proof))),
+            TransactionKind::Unshield { balance_proof } => Ok(Some((balance - commit(transaction.amount, &Scalar::zero()), balance_proof))),
+            _ => Ok(None),
+        }
+    }
+
+    // Le solde restant doit correspondre à l'état au moment de l'inclusion : une
+    // preuve établie avant un versement reçu entre-temps est refusée
+    pub fn check(&self, transaction: &Transaction) -> Result<(), &'static str> {
+        match self.remaining(transaction)? {
+            Some((remaining, proof)) => proof.verify(&remaining).map_err(|_| "Insufficient confidential balance"),
+            None => Ok(()),
+        }
+    }
+
+    // Applique une transaction vérifiée ; `destination` est le destinataire après
+    // résolution des récupérations
+    pub fn apply(&mut self, transaction: &Transaction, destination: &PublicKey) {
+        let credit = match &transaction.kind {
+            TransactionKind::Shield => commit(transaction.amount, &Scalar::zero()),
+            TransactionKind::ConfidentialTransfer(transfer) => match transfer.amount() {
+                Ok(amount) => amount,
+                Err(_) => return,
+            },
+            TransactionKind::Unshield { .. } => {
+                let debit = commit(transaction.amount, &Scalar::zero());
+                self.set(&transaction.sender, self.balance(&transaction.sender) - debit);
+                return;
+            }
+            _ => return,
+        };
+        if let TransactionKind::ConfidentialTransfer(_) = transaction.kind {
+            self.set(&transaction.sender, self.balance(&transaction.sender) - credit);
+        }
+        self.set(destination, self.balance(destination) + credit);
+    }
+
+    fn set(&mut self, account: &PublicKey, balance: RistrettoPoint) {
+        if balance == RistrettoPoint::identity() {
+            self.balances.remove(account);
+        } else {
+            self.balances.insert(*account, balance.compress());
+        }
+    }
+
+    // Le solde confidentiel suit le compte vers sa nouvelle clé
+    pub fn rotate(&mut self, account: &PublicKey, new_key: &PublicKey) {
+        if let Some(balance) = self.balances.remove(account) {
+            let merged = self.balance(new_key) + balance.decompress().unwrap_or_else(RistrettoPoint::identity);
+            self.set(new_key, merged);
+        }
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        let mut balances: Vec<(&PublicKey, &CompressedRistretto)> = self.balances.iter().collect();
+        balances.sort_by_key(|(account, _)| account.to_bytes());
+        encoder.put_u32(balances.len() as u32);
+        for (account, balance) in balances {
+            encoder.put_public_key(account);
+            encoder.put_bytes(balance.as_bytes());
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<ConfidentialBalances, &'static str> {
+        let mut balances = HashMap::new();
+        let mut previous: Option<PublicKey> = None;
+        for _ in 0..decoder.get_u32()? {
+            let account = decoder.get_public_key()?;
+            if previous.is_some_and(|previous| previous.to_bytes() >= account.to_bytes()) {
+                return Err("Confidential balances not in canonical order");
+            }
+            previous = Some(account);
+            let balance = CompressedRistretto(decoder.get_bytes()?.try_into().map_err(|_| "Invalid confidential balance")?);
+            if balance.decompress().is_none() {
+                return Err("Invalid confidential balance");
+            }
+            balances.insert(account, balance);
+        }
+        Ok(ConfidentialBalances { balances })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Preuve de `commit(1000, 7)`, encodée (domaine compris)
+    const PROOF_VECTOR: &str = concat!(
+        "00000016535550504c59585f52414e47455f50524f4f465f56310000002072c09bc9c0202823d11003b0e6d891e44e97459db9beb1ebcb6d",
+        "77ae5d73673300000020ae7593df23dfa265da28d13d4cf926e713e3b724c8465f8bce4a74fa61a77c72000000206cb9f05d5279dcfc0ce7",
+        "ca4a1705d9e91603c1bf117b3e7ec7b4a4f29d2bdd44000000208c51222a947ecfaa73033c591ffafcdef1e8eada3bd42ec81e6b9376b595",
+        "b06b00000020a04cf8348b869bec791b4d5b385d93fe6a433aaaf1f4538946d5f6350be20107000000209344acce0f440306d9bc5592bb7a",
+        "4aefc47b08accd68bca171031025f134220d00000020061d8623c953ad289ec2d9c9dd4c2ad2a5bfad031b282089e3b9701dafde5e050000",
+        "000600000020ccbe6d75c55cb0208415c61fb33106fba7b5b3fe2e0234e1392c84f3db2f45710000002040390b11cf4c40caedfcfafa1108",
+        "8b60be2cf14cc88767549880b57c68aea85c000000200a2438a552f01e4fb9006b24341cedb0bb3a4ba10ed07fba8ceea86be65e8c5b0000",
+        "0020e4de6c03e419486b00c68c31bcd68d1575afc02f3ddb21454dd309fa6e8a6d0900000020c2f9daa52ac1781d14d2c0ebfe2b36c674db",
+        "1990c55b24dfeb5b599d683c5961000000200e706366906eb9131c81735640748b8371a38e435bd903b250050aa98a99127700000020b85c",
+        "345e7125f1dd427ee94349c6e454383db8af45224574f1b3aecdad96ff0a00000020e05fcebc1df5840c01ac208c958f948be5ff959a0179",
+        "e3a3ae4574dddc71ed5000000020eebd813a7635c79445d41586fb401fe08212558f11e82f35bd03527c3709852200000020ae39223dda67",
+        "2c027a32f6b6abb36d12a6818b9ecd16a73bfb161674133f537a000000201083faa9af9809f18dc79470fc638d606e2a676508d939d5b409",
+        "176c0b63d95c00000020504cf287532a44b6a8c77557358b2f3d6cd34a6a58c9d74d3a35cc47752ef1070000002077073a605879f3c8b93d",
+        "b9c2a0c788c8dcc82e23666f2e72182c05be4b98a202000000205d8cfb649e0f73967129769fbc89572d4be28a4aa0bc326d235cf8f8ed61",
+        "4904",
+    );
+
+    #[test]
+    fn matches_the_fixed_vector() {
+        let proof = RangeProof::from_bytes(&hex::decode(PROOF_VECTOR).unwrap()).unwrap();
+        assert_eq!(hex::encode(proof.to_bytes()), PROOF_VECTOR);
+        assert_eq!(proof.verify(&commit(1_000, &Scalar::from(7u64))), Ok(()));
+        assert_eq!(proof.verify(&commit(1_001, &Scalar::from(7u64))), Err("Range proof polynomial check failed"));
+        assert_eq!(proof.verify(&commit(1_000, &Scalar::from(8u64))), Err("Range proof polynomial check failed"));
+    }
+
+    #[test]
+    fn rejects_tampered_proofs() {
+        let commitment = commit(1_000, &Scalar::from(7u64));
+        let proof = RangeProof::from_bytes(&hex::decode(PROOF_VECTOR).unwrap()).unwrap();
+
+        let mut tampered = proof.clone();
+        tampered.t_hat = (scalar(&proof.t_hat).unwrap() + Scalar::one()).to_bytes();
+        assert_eq!(tampered.verify(&commitment), Err("Range proof polynomial check failed"));
+        let mut tampered = proof.clone();
+        tampered.a_final = (scalar(&proof.a_final).unwrap() + Scalar::one()).to_bytes();
+        assert_eq!(tampered.verify(&commitment), Err("Range proof inner product check failed"));
+        let mut tampered = proof.clone();
+        tampered.l.swap(0, 1);
+        assert_eq!(tampered.verify(&commitment), Err("Range proof inner product check failed"));
+        let mut tampered = proof.clone();
+        tampered.r.pop();
+        assert_eq!(tampered.verify(&commitment), Err("Invalid range proof length"));
+        // Scalaire non réduit : un même scalaire aurait deux encodages
+        let mut tampered = proof.clone();
+        tampered.mu = [0xff; 32];
+        assert_eq!(tampered.verify(&commitment), Err("Invalid range proof scalar"));
+
+        let encoded = proof.to_bytes();
+        assert!(RangeProof::from_bytes(&encoded[..encoded.len() - 1]).is_err());
+        let mut extended = encoded.clone();
+        extended.push(0);
+        assert!(RangeProof::from_bytes(&extended).is_err());
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::HashSet;
+
+use crate::governance::ConsensusParams;
+use crate::MAX_CONTRIBUTION_SCORE;
+
+// Paramètres de consensus de départ, identiques sur tous les nœuds d'un réseau.
+// Ceux de `consensus` restent ensuite modifiables par la gouvernance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainConfig {
+    // Identifiant du réseau, signé avec chaque transaction et chaque en-tête de
+    // bloc : une signature produite pour un réseau n'est valide sur aucun autre
+    pub chain_id: u64,
+    // Réseau de test : active le robinet (`faucet.rs`) ; sans effet sur le consensus
+    pub testnet: bool,
+    pub consensus: ConsensusParams,
+    // Score de contribution minimal pour être choisi comme producteur
+    pub min_contribution_score: f64,
+    // Difficulté des blocs de la première époque (voir `difficulty.rs`)
+    pub initial_difficulty: u64,
+    // Intervalle moyen visé entre deux blocs, en secondes
+    pub target_block_interval: u64,
+    // Part des frais de chaque époque versée à la réserve communautaire, en pourcentage
+    pub community_pool_percent: u64,
+    // Avance maximale, en secondes, de l'horodatage d'un bloc sur l'heure
+    // locale ; tolérance propre à chaque nœud, hors empreinte des règles
+    pub max_clock_drift: u64,
+    // Avance maximale, en secondes, de l'horodatage d'un bloc sur celui de son
+    // parent ; aucune par défaut
+    pub max_timestamp_step: Option<u64>,
+}
+
+impl Default for ChainConfig {
+    fn default() -> Self {
+        ChainConfig {
+            chain_id: 1,
+            testnet: false,
+            consensus: ConsensusParams::default(),
+            min_contribution_score: 0.5,
+            initial_difficulty: 1,
+            target_block_interval: 5,
+            community_pool_percent: 10,
+            max_clock_drift: 15,
+            max_timestamp_step: None,
+        }
+    }
+}
+
+impl ChainConfig {
+    pub fn builder() -> ChainConfigBuilder {
+        ChainConfigBuilder { config: ChainConfig::default() }
+    }
+
+    // Cohérence des paramètres, vérifiée au démarrage
+    pub fn validate(&self) -> Result<(), &'static str> {
+        let consensus = &self.consensus;
+        if consensus.min_registration_stake == 0 {
+            return Err("Minimum registration stake must be positive");
+        }
+        if consensus.min_producer_stake < consensus.min_registration_stake {
+            return Err("Minimum producer stake below minimum registration stake");
+        }
+        if consensus.max_block_transactions == 0 || consensus.max_block_gas == 0 {
+            return Err("Block limits must be positive");
+        }
+        if !(1..=100).contains(&consensus.upgrade_threshold) {
+            return Err("Upgrade threshold must be a percentage between 1 and 100");
+        }
+        if consensus.upgrade_epochs == 0 {
+            return Err("Upgrade epochs must be positive");
+        }
+        if consensus.max_active_validators == 0 {
+            return Err("Active validator set size must be positive");
+        }
+        if !(0.0..=MAX_CONTRIBUTION_SCORE).contains(&self.min_contribution_score) {
+            return Err("Minimum contribution score out of range");
+        }
+        if self.initial_difficulty == 0 {
+            return Err("Initial difficulty must be positive");
+        }
+        if self.target_block_interval == 0 {
+            return Err("Target block interval must be positive");
+        }
+        if self.community_pool_percent > 100 {
+            return Err("Community pool share must be a percentage");
+        }
+        if self.max_timestamp_step == Some(0) {
+            return Err("Maximum timestamp step must be positive");
+        }
+        Ok(())
+    }
+
+    pub fn load(path: &str) -> Result<ChainConfig, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::from_toml(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    // Sous-ensemble de TOML : une table `[consensus]` de clés entières (ou
+    // décimale pour le score, booléenne pour `testnet`) et une table `[node]` des
+    // tolérances propres au nœud, hors consensus ; commentaires `#`. Les clés
+    // absentes gardent leur valeur par défaut, une table ou une clé répétée est refusée.
+    pub fn from_toml(text: &str) -> Result<ChainConfig, String> {
+        let mut builder = ChainConfig::builder();
+        let mut table: Option<&str> = None;
+        let mut seen: HashSet<String> = HashSet::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |message: &str| format!("line {}: {}", number + 1, message);
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                let name = name.trim();
+                if name != "consensus" && name != "node" {
+                    return Err(error("unknown table"));
+                }
+                if !seen.insert(name.to_string()) {
+                    return Err(error("duplicate table"));
+                }
+                table = Some(name);
+                continue;
+            }
+            let table = table.ok_or_else(|| error("key outside a table"))?;
+            let (key, value) = line.split_once('=').ok_or_else(|| error("expected key = value"))?;
+            let key = key.trim();
+            if !seen.insert(format!("{}.{}", table, key)) {
+                return Err(error("duplicate key"));
+            }
+            let value = value.trim().replace('_', "");
+            let integer = || value.parse::<u64>().map_err(|_| error("expected an unsigned integer"));
+            builder = match (table, key) {
+                ("consensus", "chain_id") => builder.chain_id(integer()?),
+                ("consensus", "testnet") => builder.testnet(value.parse().map_err(|_| error("expected true or false"))?),
+                ("consensus", "min_registration_stake") => builder.min_registration_stake(integer()?),
+                ("consensus", "min_producer_stake") => builder.min_producer_stake(integer()?),
+                ("consensus", "max_block_transactions") => builder.max_block_transactions(integer()?),
+                ("consensus", "max_block_gas") => builder.max_block_gas(integer()?),
+                ("consensus", "upgrade_threshold") => builder.upgrade_threshold(integer()?),
+                ("consensus", "upgrade_epochs") => builder.upgrade_epochs(integer()?),
+                ("consensus", "max_active_validators") => builder.max_active_validators(integer()?),
+                ("consensus", "min_contribution_score") => {
+                    builder.min_contribution_score(value.parse().map_err(|_| error("expected a number"))?)
+                }
+                ("consensus", "initial_difficulty") => builder.initial_difficulty(integer()?),
+                ("consensus", "target_block_interval") => builder.target_block_interval(integer()?),
+                ("consensus", "community_pool_percent") => builder.community_pool_percent(integer()?),
+                ("consensus", "max_timestamp_step") => builder.max_timestamp_step(integer()?),
+                ("node", "max_clock_drift") => builder.max_clock_drift(integer()?),
+                _ => return Err(error("unknown key")),
+            };
+        }
+        builder.build().map_err(str::to_string)
+    }
+}
+
+pub struct ChainConfigBuilder {
+    config: ChainConfig,
+}
+
+impl ChainConfigBuilder {
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.config.chain_id = chain_id;
+        self
+    }
+
+    pub fn testnet(mut self, testnet: bool) -> Self {
+        self.config.testnet = testnet;
+        self
+    }
+
+    pub fn min_registration_stake(mut self, stake: u64) -> Self {
+        self.config.consensus.min_registration_stake = stake;
+        self
+    }
+
+    pub fn min_producer_stake(mut self, stake: u64) -> Self {
+        self.config.consensus.min_producer_stake = stake;
+        self
+    }
+
+    pub fn max_block_transactions(mut self, count: u64) -> Self {
+        self.config.consensus.max_block_transactions = count;
+        self
+    }
+
+    pub fn max_block_gas(mut self, gas: u64) -> Self {
+        self.config.consensus.max_block_gas = gas;
+        self
+    }
+
+    pub fn upgrade_threshold(mut self, percent: u64) -> Self {
+        self.config.consensus.upgrade_threshold = percent;
+        self
+    }
+
+    pub fn upgrade_epochs(mut self, epochs: u64) -> Self {
+        self.config.consensus.upgrade_epochs = epochs;
+        self
+    }
+
+    pub fn max_active_validators(mut self, count: u64) -> Self {
+        self.config.consensus.max_active_validators = count;
+        self
+    }
+
+    pub fn min_contribution_score(mut self, score: f64) -> Self {
+        self.config.min_contribution_score = score;
+        self
+    }
+
+    pub fn initial_difficulty(mut self, difficulty: u64) -> Self {
+        self.config.initial_difficulty = difficulty;
+        self
+    }
+
+    pub fn target_block_interval(mut self, seconds: u64) -> Self {
+        self.config.target_block_interval = seconds;
+        self
+    }
+
+    pub fn community_pool_percent(mut self, percent: u64) -> Self {
+        self.config.community_pool_percent = percent;
+        self
+    }
+
+    pub fn max_clock_drift(mut self, seconds: u64) -> Self {
+        self.config.max_clock_drift = seconds;
+        self
+    }
+
+    pub fn max_timestamp_step(mut self, seconds: u64) -> Self {
+        self.config.max_timestamp_step = Some(seconds);
+        self
+    }
+
+    pub fn build(self) -> Result<ChainConfig, &'static str> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_consensus_and_node_tables() {
+        let config = ChainConfig::from_toml("# réseau de test\n[consensus]\nchain_id = 7\ntestnet = true\nmin_producer_stake = 2_000 # en unités\nmin_contribution_score = 0.25\n\n[node]\nmax_clock_drift = 30\n").unwrap();
+        let expected = ChainConfig::builder().chain_id(7).testnet(true).min_producer_stake(2000).min_contribution_score(0.25).max_clock_drift(30).build().unwrap();
+        assert_eq!(config, expected);
+        assert_eq!(ChainConfig::from_toml(""), Ok(ChainConfig::default()));
+    }
+
+    #[test]
+    fn rejects_unknown_misplaced_and_repeated_entries() {
+        for (text, error) in [
+            ("[consensus]\nblock_reward = 5\n", "line 2: unknown key"),
+            ("[network]\n", "line 1: unknown table"),
+            ("chain_id = 7\n[consensus]\n", "line 1: key outside a table"),
+            ("[consensus]\nmax_clock_drift = 30\n", "line 2: unknown key"),
+            ("[node]\nchain_id = 7\n", "line 2: unknown key"),
+            ("[consensus]\nchain_id = 7\nchain_id = 8\n", "line 3: duplicate key"),
+            ("[consensus]\nchain_id = 7\n[node]\n[consensus]\n", "line 4: duplicate table"),
+            ("[consensus]\nchain_id\n", "line 2: expected key = value"),
+            ("[consensus]\nchain_id = -1\n", "line 2: expected an unsigned integer"),
+            ("[consensus]\ntestnet = yes\n", "line 2: expected true or false"),
+        ] {
+            assert_eq!(ChainConfig::from_toml(text), Err(error.to_string()), "{}", text);
+        }
+    }
+
+    #[test]
+    fn rejects_inconsistent_parameters() {
+        let stakes = "[consensus]\nmin_registration_stake = 1000\nmin_producer_stake = 999\n";
+        assert_eq!(ChainConfig::from_toml(stakes), Err("Minimum producer stake below minimum registration stake".to_string()));
+        for score in ["-0.1", "10.5"] {
+            let text = format!("[consensus]\nmin_contribution_score = {}\n", score);
+            assert_eq!(ChainConfig::from_toml(&text), Err("Minimum contribution score out of range".to_string()));
+        }
+        assert!(ChainConfig::from_toml("[consensus]\nmin_contribution_score = 0\n").is_ok());
+        assert_eq!(ChainConfig::builder().min_registration_stake(0).build(), Err("Minimum registration stake must be positive"));
+    }
+}
@@ -0,0 +1,891 @@
+use std::sync::Arc;
+use std::time::Duration;
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signature, Signer};
+use serde_json::{json, Value};
+
+use crate::anchors::document_hash;
+use crate::clock::MockClock;
+use crate::confidential::{ConfidentialTransfer, RangeProof};
+use crate::config::ChainConfig;
+use crate::contracts::contract_address;
+use crate::encoding::{decode_block, decode_bundle_body, encode_block, encode_block_body};
+use crate::gas::DEFAULT_GAS_PRICE;
+use crate::ledger::Ledger;
+use crate::merkle::{merkle_proof, merkle_root, verify_proof};
+use crate::multisig::MultisigPolicy;
+use crate::privacy::EncryptedPayload;
+use crate::statetree::StateTree;
+use crate::upgrade::PROTOCOL_VERSION;
+use crate::{Authorization, Block, BlockHeader, Blockchain, Expiry, Transaction, TransactionKind, MAX_TRANSACTION_LIFETIME_BLOCKS};
+
+// Jeux de tests pour les implémentations tierces (Go, TypeScript...) : encodages
+// canoniques, hashes, signatures, preuves de Merkle (arbre des transactions et
+// arbre des soldes) et verdicts de validation.
+// Les octets sont en hexadécimal, les entiers 64 bits en chaînes décimales.
+const VECTORS_VERSION: u64 = 23;
+
+// Réseau des transactions et blocs de test
+const CHAIN_ID: u64 = 1;
+// Réseau d'une autre chaîne, pour les signatures rejouées
+const FOREIGN_CHAIN_ID: u64 = 2;
+
+// Stake du validateur enregistré sur la chaîne vierge où sont validés les blocs
+const VALIDATOR_STAKE: u64 = 1000;
+const BLOCK_SLOT: u64 = 1;
+const BLOCK_TIMESTAMP: u64 = 1_700_000_000;
+// Heure locale du nœud qui valide les blocs de test
+const LOCAL_TIME: u64 = BLOCK_TIMESTAMP;
+// Solde de genèse des comptes émetteurs (graines 1 à 3 et compte multisig)
+const GENESIS_BALANCE: u64 = 1_000_000;
+// Échéance des transactions de test, dans la durée de vie maximale d'une transaction
+const VALID_UNTIL: Expiry = Expiry::Timestamp(BLOCK_TIMESTAMP + 3600);
+
+// Clés déterministes : la graine est l'octet `seed` répété
+fn keypair(seed: u8) -> Keypair {
+    let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+fn verdict(result: Result<(), &'static str>) -> String {
+    match result {
+        Ok(()) => "valid".to_string(),
+        Err(e) => e.to_string(),
+    }
+}
+
+fn signed_transaction(sender: &Keypair, nonce: u64, recipient: &PublicKey, amount: u64, timestamp: u64) -> Transaction {
+    Transaction::signed(CHAIN_ID, sender, nonce, recipient, amount, TransactionKind::Transfer, DEFAULT_GAS_PRICE, timestamp, VALID_UNTIL)
+}
+
+fn kind_json(kind: &TransactionKind) -> Value {
+    match kind {
+        TransactionKind::Transfer => json!({ "type": "transfer" }),
+        TransactionKind::Escrow { unlock_height, arbiter } => json!({
+            "type": "escrow",
+            "unlock_height": unlock_height.to_string(),
+            "arbiter": arbiter.map(|key| hex::encode(key.as_bytes())),
+        }),
+        TransactionKind::Claim { escrow } => json!({ "type": "claim", "escrow": hex::encode(escrow) }),
+        TransactionKind::Refund { escrow } => json!({ "type": "refund", "escrow": hex::encode(escrow) }),
+        TransactionKind::Deploy { code } => json!({ "type": "deploy", "code": hex::encode(code) }),
+        TransactionKind::Call { input, gas_limit } => json!({
+            "type": "call",
+            "input": hex::encode(input),
+            "gas_limit": gas_limit.to_string(),
+        }),
+        TransactionKind::SetRecovery { guardians, challenge_period } => json!({
+            "type": "set_recovery",
+            "threshold": guardians.threshold,
+            "guardians": guardians.members.iter().map(|key| hex::encode(key.as_bytes())).collect::<Vec<_>>(),
+            "challenge_period": challenge_period.to_string(),
+        }),
+        TransactionKind::Recover { account } => json!({ "type": "recover", "account": hex::encode(account.as_bytes()) }),
+        TransactionKind::VetoRecovery => json!({ "type": "veto_recovery" }),
+        TransactionKind::Shield => json!({ "type": "shield" }),
+        TransactionKind::ConfidentialTransfer(transfer) => json!({
+            "type": "confidential_transfer",
+            "transfer": hex::encode(transfer.to_bytes()),
+        }),
+        TransactionKind::Unshield { balance_proof } => json!({
+            "type": "unshield",
+            "balance_proof": hex::encode(balance_proof.to_bytes()),
+        }),
+        TransactionKind::Anchor { document, cid } => json!({ "type": "anchor", "document": hex::encode(document), "cid": cid }),
+        TransactionKind::RegisterAsset { readers } => json!({
+            "type": "register_asset",
+            "readers": readers.iter().map(|key| hex::encode(key.as_bytes())).collect::<Vec<_>>(),
+        }),
+        TransactionKind::GrantAccess { asset } => json!({ "type": "grant_access", "asset": hex::encode(asset) }),
+        TransactionKind::RevokeAccess { asset } => json!({ "type": "revoke_access", "asset": hex::encode(asset) }),
+        TransactionKind::Attest { asset, payload } => json!({
+            "type": "attest",
+            "asset": hex::encode(asset),
+            "payload": hex::encode(payload.to_bytes()),
+        }),
+        // Transactions du lot encodées comme un corps de bloc
+        TransactionKind::Bundle(transactions) => json!({
+            "type": "bundle",
+            "id": hex::encode(Transaction::bundle_id(transactions)),
+            "transactions": hex::encode(encode_block_body(transactions)),
+        }),
+    }
+}
+
+fn expiry_json(expiry: &Expiry) -> Value {
+    match expiry {
+        Expiry::Never => json!({ "type": "never" }),
+        Expiry::Height(height) => json!({ "type": "height", "value": height.to_string() }),
+        Expiry::Timestamp(timestamp) => json!({ "type": "timestamp", "value": timestamp.to_string() }),
+    }
+}
+
+fn parse_expiry(expiry: &Value) -> Result<Expiry, String> {
+    match field(expiry, "type")? {
+        "never" => Ok(VALID_UNTIL),
+        "height" => Ok(Expiry::Height(number(expiry, "value")?)),
+        "timestamp" => Ok(Expiry::Timestamp(number(expiry, "value")?)),
+        other => Err(format!("unknown expiry {}", other)),
+    }
+}
+
+fn parse_kind(kind: &Value) -> Result<TransactionKind, String> {
+    match field(kind, "type")? {
+        "transfer" => Ok(TransactionKind::Transfer),
+        "escrow" => Ok(TransactionKind::Escrow {
+            unlock_height: number(kind, "unlock_height")?,
+            arbiter: if kind["arbiter"].is_null() {
+                None
+            } else {
+                Some(PublicKey::from_bytes(&bytes(kind, "arbiter")?).map_err(|_| "invalid arbiter".to_string())?)
+            },
+        }),
+        "claim" => Ok(TransactionKind::Claim { escrow: bytes(kind, "escrow")? }),
+        "refund" => Ok(TransactionKind::Refund { escrow: bytes(kind, "escrow")? }),
+        "deploy" => Ok(TransactionKind::Deploy { code: bytes(kind, "code")? }),
+        "call" => Ok(TransactionKind::Call { input: bytes(kind, "input")?, gas_limit: number(kind, "gas_limit")? }),
+        "set_recovery" => {
+            let threshold = kind["threshold"].as_u64().and_then(|t| u8::try_from(t).ok()).ok_or("invalid threshold")?;
+            let guardians = public_keys(kind, "guardians")?;
+            Ok(TransactionKind::SetRecovery {
+                guardians: MultisigPolicy::new(threshold, guardians).map_err(|e| e.to_string())?,
+                challenge_period: number(kind, "challenge_period")?,
+            })
+        }
+        "recover" => Ok(TransactionKind::Recover {
+            account: PublicKey::from_bytes(&bytes(kind, "account")?).map_err(|_| "invalid account".to_string())?,
+        }),
+        "veto_recovery" => Ok(TransactionKind::VetoRecovery),
+        "shield" => Ok(TransactionKind::Shield),
+        "confidential_transfer" => Ok(TransactionKind::ConfidentialTransfer(Box::new(
+            ConfidentialTransfer::from_bytes(&bytes(kind, "transfer")?).map_err(|e| e.to_string())?,
+        ))),
+        "unshield" => Ok(TransactionKind::Unshield {
+            balance_proof: Box::new(RangeProof::from_bytes(&bytes(kind, "balance_proof")?).map_err(|e| e.to_string())?),
+        }),
+        "anchor" => Ok(TransactionKind::Anchor {
+            document: bytes(kind, "document")?,
+            cid: kind["cid"].as_str().map(str::to_string),
+        }),
+        "register_asset" => Ok(TransactionKind::RegisterAsset { readers: public_keys(kind, "readers")? }),
+        "grant_access" => Ok(TransactionKind::GrantAccess { asset: bytes(kind, "asset")? }),
+        "revoke_access" => Ok(TransactionKind::RevokeAccess { asset: bytes(kind, "asset")? }),
+        "attest" => Ok(TransactionKind::Attest {
+            asset: bytes(kind, "asset")?,
+            payload: Box::new(EncryptedPayload::from_bytes(&bytes(kind, "payload")?).map_err(|e| e.to_string())?),
+        }),
+        "bundle" => Ok(TransactionKind::Bundle(decode_bundle_body(&bytes(kind, "transactions")?).map_err(|e| e.to_string())?)),
+        other => Err(format!("unknown transaction kind {}", other)),
+    }
+}
+
+fn signature_hex(transaction: &Transaction) -> String {
+    match &transaction.authorization {
+        Authorization::Signature(signature) => hex::encode(signature.to_bytes()),
+        Authorization::Multisig { .. } => unreachable!(),
+    }
+}
+
+fn transaction_case(description: &str, sender_seed: u8, transaction: &Transaction) -> Value {
+    json!({
+        "description": description,
+        "sender_seed": sender_seed,
+        "version": transaction.version,
+        "chain_id": transaction.chain_id.to_string(),
+        "sender": hex::encode(transaction.sender.as_bytes()),
+        "nonce": transaction.nonce.to_string(),
+        "recipient": hex::encode(transaction.recipient.as_bytes()),
+        "amount": transaction.amount.to_string(),
+        "kind": kind_json(&transaction.kind),
+        "gas_price": transaction.gas_price.to_string(),
+        "timestamp": transaction.timestamp.to_string(),
+        "valid_from": transaction.valid_from.to_string(),
+        "valid_until": expiry_json(&transaction.valid_until),
+        "signing_bytes": hex::encode(transaction.signing_bytes()),
+        "hash": hex::encode(transaction.hash()),
+        "signature": signature_hex(transaction),
+        "verdict": verdict(transaction.verify()),
+    })
+}
+
+// Transfert au format 2, signé dans son propre domaine mais introduit par
+// aucune version du protocole
+fn future_transaction(sender: &Keypair, recipient: &PublicKey) -> Transaction {
+    let mut transaction = signed_transaction(sender, 0, recipient, 50, BLOCK_TIMESTAMP);
+    transaction.version = 2;
+    transaction.authorization = Authorization::Signature(sender.sign(&transaction.hash()));
+    transaction
+}
+
+// Attestation au contenu fixe (le chiffrement est aléatoire) : la validation ne
+// vérifie que ses destinataires
+fn attestation(author: &Keypair, asset: &[u8], recipients: &[PublicKey], timestamp: u64) -> Transaction {
+    let payload = EncryptedPayload {
+        ephemeral: [0x11; 32],
+        nonce: [0x22; 16],
+        wrapped_keys: recipients.iter().map(|recipient| (*recipient, [0x33; 32])).collect(),
+        ciphertext: b"temperature within range".to_vec(),
+        tag: vec![0x44; 32],
+    };
+    Transaction::signed(CHAIN_ID, author, 0, &author.public, 0, TransactionKind::Attest { asset: asset.to_vec(), payload: Box::new(payload) }, DEFAULT_GAS_PRICE, timestamp, VALID_UNTIL)
+}
+
+// Participants triés comme les clés d'une attestation
+fn sorted_keys(keys: &[&Keypair]) -> Vec<PublicKey> {
+    let mut keys: Vec<PublicKey> = keys.iter().map(|keypair| keypair.public).collect();
+    keys.sort_by_key(|key| key.to_bytes());
+    keys
+}
+
+fn transaction_cases() -> Vec<Value> {
+    let (alice, bob, carol) = (keypair(1), keypair(2), keypair(3));
+
+    let transfer = signed_transaction(&alice, 0, &bob.public, 50, BLOCK_TIMESTAMP);
+    let max_amount = signed_transaction(&carol, 0, &alice.public, u64::MAX, 0);
+    let zero_amount = signed_transaction(&alice, 0, &bob.public, 0, BLOCK_TIMESTAMP);
+    let mut foreign_signature = signed_transaction(&alice, 0, &bob.public, 10, BLOCK_TIMESTAMP);
+    foreign_signature.authorization = Authorization::Signature(carol.sign(&foreign_signature.hash()));
+    let escrow = Transaction::signed(CHAIN_ID, &alice, 0, &bob.public, 40, TransactionKind::Escrow { unlock_height: 10, arbiter: Some(carol.public) }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let time_lock = Transaction::signed(CHAIN_ID, &alice, 0, &bob.public, 40, TransactionKind::Escrow { unlock_height: 10, arbiter: None }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let claim = Transaction::signed(CHAIN_ID, &bob, 0, &bob.public, 40, TransactionKind::Claim { escrow: escrow.hash() }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP + 1, VALID_UNTIL);
+    let refund = Transaction::signed(CHAIN_ID, &carol, 0, &alice.public, 40, TransactionKind::Refund { escrow: escrow.hash() }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP + 1, VALID_UNTIL);
+    let foreign_chain = Transaction::signed(FOREIGN_CHAIN_ID, &alice, 0, &bob.public, 50, TransactionKind::Transfer, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let until_height = Transaction::signed(CHAIN_ID, &alice, 1, &bob.public, 50, TransactionKind::Transfer, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, Expiry::Height(100));
+    let until_timestamp = Transaction::signed(CHAIN_ID, &alice, 300, &bob.public, 50, TransactionKind::Transfer, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, Expiry::Timestamp(BLOCK_TIMESTAMP + 3600));
+    let shield = Transaction::signed(CHAIN_ID, &alice, 0, &bob.public, 30, TransactionKind::Shield, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let anchor = Transaction::signed(CHAIN_ID, &alice, 0, &bob.public, 0, TransactionKind::Anchor { document: document_hash(b"bill of lading"), cid: Some("bafkreidgvpkjawlxz6sffxzwgooowe5yt7i6wsyg236mfoks77nywkptdq".to_string()) }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let anchor_with_amount = Transaction::signed(CHAIN_ID, &alice, 0, &bob.public, 5, TransactionKind::Anchor { document: document_hash(b"bill of lading"), cid: None }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let mut bundled = vec![signed_transaction(&alice, 0, &bob.public, 25, BLOCK_TIMESTAMP), signed_transaction(&bob, 0, &alice.public, 10, BLOCK_TIMESTAMP)];
+    let bundle_id = Transaction::bundle_id(&bundled);
+    bundled[0].sign_for_bundle(&alice, &bundle_id).unwrap();
+    bundled[1].sign_for_bundle(&bob, &bundle_id).unwrap();
+    let bundle = Transaction::signed(CHAIN_ID, &carol, 0, &carol.public, 0, TransactionKind::Bundle(bundled.clone()), DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    // Transaction signée pour elle-même, donc hors de tout lot
+    bundled[1] = signed_transaction(&bob, 0, &alice.public, 10, BLOCK_TIMESTAMP);
+    let unbundled_signature = Transaction::signed(CHAIN_ID, &carol, 0, &carol.public, 0, TransactionKind::Bundle(bundled), DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let scheduled = Transaction::scheduled(CHAIN_ID, &alice, 0, &bob.public, 50, TransactionKind::Transfer, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, 10, Expiry::Height(19));
+    let empty_window = Transaction::scheduled(CHAIN_ID, &alice, 0, &bob.public, 50, TransactionKind::Transfer, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, 10, Expiry::Height(9));
+    let future_version = future_transaction(&alice, &bob.public);
+    let register_asset = Transaction::signed(CHAIN_ID, &alice, 0, &alice.public, 0, TransactionKind::RegisterAsset { readers: sorted_keys(&[&bob, &carol]) }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let owner_as_reader = Transaction::signed(CHAIN_ID, &alice, 0, &alice.public, 0, TransactionKind::RegisterAsset { readers: sorted_keys(&[&alice, &bob]) }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let attest = attestation(&bob, &register_asset.hash(), &sorted_keys(&[&alice, &bob, &carol]), BLOCK_TIMESTAMP + 1);
+
+    vec![
+        transaction_case("transfer", 1, &transfer),
+        transaction_case("maximum amount at timestamp zero", 3, &max_amount),
+        transaction_case("zero amount", 1, &zero_amount),
+        transaction_case("signature by another key", 1, &foreign_signature),
+        transaction_case("escrow with an arbiter", 1, &escrow),
+        transaction_case("time lock without arbiter", 1, &time_lock),
+        transaction_case("escrow claim", 2, &claim),
+        transaction_case("escrow refund by the arbiter", 3, &refund),
+        transaction_case("transfer signed for another chain", 1, &foreign_chain),
+        transaction_case("transfer valid until a height", 1, &until_height),
+        transaction_case("transfer valid until a timestamp", 1, &until_timestamp),
+        transaction_case("deposit into a confidential balance", 1, &shield),
+        transaction_case("document anchor with an IPFS CID", 1, &anchor),
+        transaction_case("document anchor carrying an amount", 1, &anchor_with_amount),
+        transaction_case("atomic bundle of two transfers", 3, &bundle),
+        transaction_case("bundled transaction signed for its own hash", 3, &unbundled_signature),
+        transaction_case("transfer scheduled between two heights", 1, &scheduled),
+        transaction_case("scheduled transfer with an empty window", 1, &empty_window),
+        transaction_case("transfer in a future transaction format", 1, &future_version),
+        transaction_case("asset registered with two readers", 1, &register_asset),
+        transaction_case("asset listing its owner as a reader", 1, &owner_as_reader),
+        transaction_case("attestation on an asset by a reader", 2, &attest),
+    ]
+}
+
+fn merkle_leaves(count: u8) -> Vec<Vec<u8>> {
+    (0..count).map(|i| vec![i; 32]).collect()
+}
+
+fn merkle_cases() -> Vec<Value> {
+    (0..=5).map(|count| {
+        let leaves = merkle_leaves(count);
+        let proofs: Vec<Value> = (0..leaves.len()).map(|index| {
+            let proof = merkle_proof(&leaves, index).unwrap();
+            json!({
+                "index": proof.index.to_string(),
+                "leaf_count": proof.leaf_count.to_string(),
+                "siblings": proof.siblings.iter().map(hex::encode).collect::<Vec<_>>(),
+            })
+        }).collect();
+        json!({
+            "leaves": leaves.iter().map(hex::encode).collect::<Vec<_>>(),
+            "root": hex::encode(merkle_root(&leaves)),
+            "proofs": proofs,
+        })
+    }).collect()
+}
+
+// Soldes distincts pour que chaque feuille de l'arbre soit différente
+fn state_balances(count: u8) -> Vec<(PublicKey, u64)> {
+    (1..=count).map(|seed| (keypair(seed).public, seed as u64 * 1000)).collect()
+}
+
+fn state_tree(balances: &[(PublicKey, u64)]) -> StateTree {
+    let mut ledger = Ledger::default();
+    for (account, balance) in balances {
+        ledger.credit(account, *balance);
+    }
+    StateTree::from_ledger(&ledger)
+}
+
+// Preuves de chaque compte de l'arbre et de deux comptes absents (graines 20 et 21)
+fn state_cases() -> Vec<Value> {
+    [0, 1, 2, 6].into_iter().map(|count| {
+        let balances = state_balances(count);
+        let tree = state_tree(&balances);
+        let accounts = balances.iter().map(|(account, _)| *account).chain([keypair(20).public, keypair(21).public]);
+        let proofs: Vec<Value> = accounts.map(|account| {
+            let proof = tree.prove(&account);
+            json!({
+                "account": hex::encode(account.as_bytes()),
+                "balance": proof.verify(tree.root(), &account).unwrap().to_string(),
+                "siblings": proof.siblings.iter().map(hex::encode).collect::<Vec<_>>(),
+                "leaf": proof.leaf.map(|(leaf, balance)| json!({ "account": hex::encode(leaf), "balance": balance.to_string() })),
+            })
+        }).collect();
+        json!({
+            "balances": balances.iter().map(|(account, balance)| json!({
+                "account": hex::encode(account.as_bytes()),
+                "balance": balance.to_string(),
+            })).collect::<Vec<_>>(),
+            "root": hex::encode(tree.root()),
+            "proofs": proofs,
+        })
+    }).collect()
+}
+
+fn genesis_accounts() -> Vec<PublicKey> {
+    vec![keypair(1).public, keypair(2).public, keypair(3).public, multisig_policy().address()]
+}
+
+// Tolérance par défaut du nœud qui valide les blocs de test
+fn max_clock_drift() -> u64 {
+    ChainConfig::default().max_clock_drift
+}
+
+// Chaîne vierge sur laquelle chaque bloc de test est validé comme premier bloc
+fn reference_chain(validator: &Keypair) -> Blockchain {
+    let mut chain = Blockchain::new(ChainConfig::builder().chain_id(CHAIN_ID).build().unwrap());
+    chain.set_clock(Arc::new(MockClock::new(Duration::from_secs(LOCAL_TIME))));
+    chain.register_validator(&validator.public, VALIDATOR_STAKE).unwrap();
+    for account in genesis_accounts() {
+        chain.allocate(&account, GENESIS_BALANCE);
+    }
+    chain
+}
+
+fn build_block(validator: &Keypair, previous_hash: Vec<u8>, transactions: Vec<Transaction>) -> Block {
+    build_block_for(CHAIN_ID, validator, previous_hash, transactions)
+}
+
+fn build_block_for(chain_id: u64, validator: &Keypair, previous_hash: Vec<u8>, transactions: Vec<Transaction>) -> Block {
+    let state_root = reference_chain(validator).state_tree.root().to_vec();
+    build_block_with(chain_id, validator, previous_hash, state_root, BLOCK_TIMESTAMP, transactions)
+}
+
+fn build_block_with(chain_id: u64, validator: &Keypair, previous_hash: Vec<u8>, state_root: Vec<u8>, timestamp: u64, transactions: Vec<Transaction>) -> Block {
+    build_block_at_difficulty(chain_id, validator, previous_hash, state_root, timestamp, ChainConfig::default().initial_difficulty, transactions)
+}
+
+fn build_block_at_difficulty(chain_id: u64, validator: &Keypair, previous_hash: Vec<u8>, state_root: Vec<u8>, timestamp: u64, difficulty: u64, transactions: Vec<Transaction>) -> Block {
+    let transactions_root = Block::transactions_root(&transactions, &[], &[]);
+    let current_hash = BlockHeader::compute_hash(chain_id, 0, BLOCK_SLOT, timestamp, difficulty, PROTOCOL_VERSION, &[], &previous_hash, &transactions_root, &state_root, &[], None, &validator.public);
+    Block {
+        chain_id,
+        index: 0,
+        slot: BLOCK_SLOT,
+        timestamp,
+        difficulty,
+        protocol_version: PROTOCOL_VERSION,
+        rules_fingerprint: Vec::new(),
+        transactions,
+        private_transactions: Vec::new(),
+        governance_transactions: Vec::new(),
+        previous_hash,
+        transactions_root,
+        state_root,
+        snapshot_hash: Vec::new(),
+        checkpoint: None,
+        validator_signature: validator.sign(&current_hash),
+        current_hash,
+        validator_pubkey: validator.public,
+    }
+}
+
+fn block_case(description: &str, validator_seed: u8, block: &Block) -> Value {
+    let validator = keypair(validator_seed);
+    json!({
+        "description": description,
+        "validator_seed": validator_seed,
+        "chain_id": CHAIN_ID.to_string(),
+        "validator_stake": VALIDATOR_STAKE.to_string(),
+        "local_time": LOCAL_TIME.to_string(),
+        "max_clock_drift": max_clock_drift().to_string(),
+        "genesis_balances": genesis_accounts().iter().map(|account| json!({
+            "account": hex::encode(account.as_bytes()),
+            "balance": GENESIS_BALANCE.to_string(),
+        })).collect::<Vec<_>>(),
+        "encoding": hex::encode(encode_block(block)),
+        "header_signing_bytes": hex::encode(block.header().signing_bytes()),
+        "hash": hex::encode(&block.current_hash),
+        "transactions_root": hex::encode(&block.transactions_root),
+        "state_root": hex::encode(&block.state_root),
+        "verdict": verdict(reference_chain(&validator).validate_block(block)),
+    })
+}
+
+fn block_cases() -> Vec<Value> {
+    let (validator, alice, bob) = (keypair(7), keypair(1), keypair(2));
+    let genesis_parent = reference_chain(&validator).last_hash();
+    let transactions = vec![
+        signed_transaction(&alice, 0, &bob.public, 50, BLOCK_TIMESTAMP),
+        signed_transaction(&bob, 0, &alice.public, 20, BLOCK_TIMESTAMP + 1),
+        signed_transaction(&alice, 1, &bob.public, 5, BLOCK_TIMESTAMP + 2),
+    ];
+
+    let empty = build_block(&validator, genesis_parent.clone(), Vec::new());
+    let valid = build_block(&validator, genesis_parent.clone(), transactions.clone());
+
+    let mut tampered = valid.clone();
+    tampered.transactions[1].amount += 1;
+
+    let wrong_parent = build_block(&validator, vec![0xff; 32], transactions.clone());
+
+    let mut foreign_signature = valid.clone();
+    foreign_signature.validator_signature = alice.sign(&foreign_signature.current_hash);
+
+    let mut forged_transaction = build_block(&validator, genesis_parent.clone(), transactions);
+    forged_transaction.transactions[0].authorization = Authorization::Signature(bob.sign(b"forged"));
+
+    // Séquestre arbitré par carol (graine 3), bloqué jusqu'à la hauteur 10
+    let carol = keypair(3);
+    let escrow = Transaction::signed(CHAIN_ID, &alice, 0, &bob.public, 40, TransactionKind::Escrow { unlock_height: 10, arbiter: Some(carol.public) }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let early_claim = Transaction::signed(CHAIN_ID, &bob, 0, &bob.public, 40, TransactionKind::Claim { escrow: escrow.hash() }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP + 1, VALID_UNTIL);
+    let released = Transaction::signed(CHAIN_ID, &carol, 0, &bob.public, 40, TransactionKind::Claim { escrow: escrow.hash() }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP + 1, VALID_UNTIL);
+    let refunded = Transaction::signed(CHAIN_ID, &carol, 1, &alice.public, 40, TransactionKind::Refund { escrow: escrow.hash() }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP + 2, VALID_UNTIL);
+    let escrow_released = build_block(&validator, genesis_parent.clone(), vec![escrow.clone(), released.clone()]);
+    let escrow_locked = build_block(&validator, genesis_parent.clone(), vec![escrow.clone(), early_claim]);
+    let escrow_settled_twice = build_block(&validator, genesis_parent.clone(), vec![escrow, released, refunded]);
+
+    // Compteur : incrémente la clé "c" et retourne sa nouvelle valeur
+    let counter = vec![0x01, 0x01, b'c', 0x30, 0x01, 0x01, 0x01, 0x10, 0x03, 0x00, 0x01, 0x01, b'c', 0x31, 0x50];
+    let contract = contract_address(&alice.public, BLOCK_TIMESTAMP, &counter);
+    let deploy = Transaction::signed(CHAIN_ID, &alice, 0, &contract, 0, TransactionKind::Deploy { code: counter }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let call = Transaction::signed(CHAIN_ID, &bob, 0, &contract, 0, TransactionKind::Call { input: Vec::new(), gas_limit: 1000 }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP + 1, VALID_UNTIL);
+    let starved_call = Transaction::signed(CHAIN_ID, &bob, 0, &contract, 0, TransactionKind::Call { input: Vec::new(), gas_limit: 3 }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP + 1, VALID_UNTIL);
+    let contract_called = build_block(&validator, genesis_parent.clone(), vec![deploy.clone(), call.clone()]);
+    let contract_out_of_gas = build_block(&validator, genesis_parent.clone(), vec![deploy, starved_call]);
+    let unknown_contract = build_block(&validator, genesis_parent.clone(), vec![call]);
+
+    // Gardiens d'alice : le compte multisig 2-parmi-3 (graines 4, 5 et 6), vers la clé de graine 8
+    let set_recovery = Transaction::signed(CHAIN_ID, &alice, 0, &alice.public, 0, TransactionKind::SetRecovery { guardians: multisig_policy(), challenge_period: 100 }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let mut recover = Transaction::multisig(CHAIN_ID, multisig_policy(), 0, &keypair(8).public, 0, TransactionKind::Recover { account: alice.public }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP + 1, VALID_UNTIL);
+    recover.cosign(&keypair(4)).unwrap();
+    recover.cosign(&keypair(5)).unwrap();
+    let foreign_recover = Transaction::signed(CHAIN_ID, &bob, 0, &keypair(8).public, 0, TransactionKind::Recover { account: alice.public }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP + 1, VALID_UNTIL);
+    let recovery_started = build_block(&validator, genesis_parent.clone(), vec![set_recovery.clone(), recover]);
+    let recovery_by_stranger = build_block(&validator, genesis_parent.clone(), vec![set_recovery, foreign_recover]);
+
+    // Actif d'alice lisible par bob, puis accès accordé à carol
+    let register_asset = Transaction::signed(CHAIN_ID, &alice, 0, &alice.public, 0, TransactionKind::RegisterAsset { readers: vec![bob.public] }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let asset = register_asset.hash();
+    let grant = Transaction::signed(CHAIN_ID, &alice, 1, &carol.public, 0, TransactionKind::GrantAccess { asset: asset.clone() }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP + 1, VALID_UNTIL);
+    let foreign_grant = Transaction::signed(CHAIN_ID, &bob, 0, &carol.public, 0, TransactionKind::GrantAccess { asset: asset.clone() }, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP + 1, VALID_UNTIL);
+    let attested = attestation(&bob, &asset, &sorted_keys(&[&alice, &bob, &carol]), BLOCK_TIMESTAMP + 2);
+    let stale_attestation = attestation(&bob, &asset, &sorted_keys(&[&alice, &bob]), BLOCK_TIMESTAMP + 2);
+    let asset_attested = build_block(&validator, genesis_parent.clone(), vec![register_asset.clone(), grant.clone(), attested]);
+    let asset_grant_by_reader = build_block(&validator, genesis_parent.clone(), vec![register_asset.clone(), foreign_grant]);
+    let asset_stale_attestation = build_block(&validator, genesis_parent.clone(), vec![register_asset, grant, stale_attestation]);
+
+    let other_chain = build_block_for(FOREIGN_CHAIN_ID, &validator, genesis_parent.clone(), Vec::new());
+    let replayed = Transaction::signed(FOREIGN_CHAIN_ID, &alice, 0, &bob.public, 50, TransactionKind::Transfer, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let replayed_transaction = build_block(&validator, genesis_parent.clone(), vec![replayed]);
+
+    let expiring = |valid_until| Transaction::signed(CHAIN_ID, &alice, 0, &bob.public, 50, TransactionKind::Transfer, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, valid_until);
+    let last_height = build_block(&validator, genesis_parent.clone(), vec![expiring(Expiry::Height(0))]);
+    let last_second = build_block(&validator, genesis_parent.clone(), vec![expiring(Expiry::Timestamp(BLOCK_TIMESTAMP))]);
+    let expired = build_block(&validator, genesis_parent.clone(), vec![expiring(Expiry::Timestamp(BLOCK_TIMESTAMP - 1))]);
+    let never_expiring = build_block(&validator, genesis_parent.clone(), vec![expiring(Expiry::Never)]);
+    let beyond_lifetime = build_block(&validator, genesis_parent.clone(), vec![expiring(Expiry::Height(MAX_TRANSACTION_LIFETIME_BLOCKS + 1))]);
+
+    // Une transaction rejouée, ou une seconde version signée avec le même nonce
+    let once = signed_transaction(&alice, 0, &bob.public, 50, BLOCK_TIMESTAMP);
+    let replayed_in_block = build_block(&validator, genesis_parent.clone(), vec![once.clone(), once]);
+    let nonce_reused = build_block(&validator, genesis_parent.clone(), vec![signed_transaction(&alice, 7, &bob.public, 50, BLOCK_TIMESTAMP), signed_transaction(&alice, 7, &bob.public, 60, BLOCK_TIMESTAMP + 1)]);
+
+    let empty_root = reference_chain(&validator).state_tree.root().to_vec();
+    let wrong_state_root = build_block_with(CHAIN_ID, &validator, genesis_parent.clone(), state_tree(&state_balances(1)).root().to_vec(), BLOCK_TIMESTAMP, Vec::new());
+    let drift_bound = build_block_with(CHAIN_ID, &validator, genesis_parent.clone(), empty_root.clone(), LOCAL_TIME + max_clock_drift(), Vec::new());
+    let beyond_drift = build_block_with(CHAIN_ID, &validator, genesis_parent.clone(), empty_root.clone(), LOCAL_TIME + max_clock_drift() + 1, Vec::new());
+    let wrong_difficulty = build_block_at_difficulty(CHAIN_ID, &validator, genesis_parent.clone(), empty_root, BLOCK_TIMESTAMP, ChainConfig::default().initial_difficulty + 1, Vec::new());
+
+    // Fenêtre d'exécution ouverte au plus tard jusqu'à la hauteur 1 ; les blocs de test sont à la hauteur 0
+    let scheduled = |valid_from| Transaction::scheduled(CHAIN_ID, &alice, 0, &bob.public, 50, TransactionKind::Transfer, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, valid_from, Expiry::Height(1));
+    let scheduled_due = build_block(&validator, genesis_parent.clone(), vec![scheduled(0)]);
+    let scheduled_early = build_block(&validator, genesis_parent.clone(), vec![scheduled(1)]);
+
+    let future_version = build_block(&validator, genesis_parent.clone(), vec![future_transaction(&alice, &bob.public)]);
+
+    let overdrawn = build_block(&validator, genesis_parent.clone(), vec![signed_transaction(&alice, 0, &bob.public, GENESIS_BALANCE, BLOCK_TIMESTAMP)]);
+
+    // Lots soumis par carol : dave (graine 9) n'a de solde qu'après le premier transfert
+    let dave = keypair(9);
+    let bundle = |second: u64, forged: bool| {
+        let mut bundled = vec![signed_transaction(&alice, 0, &dave.public, 100_000, BLOCK_TIMESTAMP), signed_transaction(&dave, 0, &bob.public, second, BLOCK_TIMESTAMP)];
+        let id = Transaction::bundle_id(&bundled);
+        bundled[0].sign_for_bundle(&alice, &id).unwrap();
+        if !forged {
+            bundled[1].sign_for_bundle(&dave, &id).unwrap();
+        }
+        Transaction::signed(CHAIN_ID, &carol, 0, &carol.public, 0, TransactionKind::Bundle(bundled), DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL)
+    };
+    let bundle_applied = build_block(&validator, genesis_parent.clone(), vec![bundle(10, false)]);
+    let bundle_overdrawn = build_block(&validator, genesis_parent.clone(), vec![bundle(200_000, false)]);
+    let bundle_forged = build_block(&validator, genesis_parent, vec![bundle(10, true)]);
+
+    let mut with_multisig = transactions_with_multisig();
+    with_multisig.insert(0, signed_transaction(&alice, 0, &bob.public, 1, BLOCK_TIMESTAMP));
+    let with_multisig = build_block(&validator, reference_chain(&validator).last_hash(), with_multisig);
+
+    vec![
+        block_case("empty block", 7, &empty),
+        block_case("block with three transactions", 7, &valid),
+        block_case("transaction altered after signing the block", 7, &tampered),
+        block_case("unknown previous hash", 7, &wrong_parent),
+        block_case("state root of other balances", 7, &wrong_state_root),
+        block_case("timestamp at the maximum clock drift", 7, &drift_bound),
+        block_case("timestamp beyond the maximum clock drift", 7, &beyond_drift),
+        block_case("difficulty other than the chain's", 7, &wrong_difficulty),
+        block_case("block signed by another key", 7, &foreign_signature),
+        block_case("transaction with an invalid signature", 7, &forged_transaction),
+        block_case("block with a multisig transaction", 7, &with_multisig),
+        block_case("transfer of the whole balance, leaving nothing for the fee", 7, &overdrawn),
+        block_case("escrow released by its arbiter", 7, &escrow_released),
+        block_case("escrow claimed before its unlock height", 7, &escrow_locked),
+        block_case("escrow claimed then refunded", 7, &escrow_settled_twice),
+        block_case("contract deployed then called", 7, &contract_called),
+        block_case("contract call out of gas, included as failed", 7, &contract_out_of_gas),
+        block_case("call to an undeployed contract", 7, &unknown_contract),
+        block_case("recovery started by the guardians", 7, &recovery_started),
+        block_case("recovery requested by a non-guardian", 7, &recovery_by_stranger),
+        block_case("asset access granted, then attestation for all participants", 7, &asset_attested),
+        block_case("asset access granted by a reader", 7, &asset_grant_by_reader),
+        block_case("attestation not encrypted for a newly granted reader", 7, &asset_stale_attestation),
+        block_case("atomic bundle applied in order", 7, &bundle_applied),
+        block_case("atomic bundle with an overdrawn transfer", 7, &bundle_overdrawn),
+        block_case("bundled transaction signed for its own hash", 7, &bundle_forged),
+        block_case("block signed for another chain", 7, &other_chain),
+        block_case("transaction signed for another chain", 7, &replayed_transaction),
+        block_case("transaction included at its last valid height", 7, &last_height),
+        block_case("transaction included at its last valid timestamp", 7, &last_second),
+        block_case("transaction expired before the block timestamp", 7, &expired),
+        block_case("transaction that never expires", 7, &never_expiring),
+        block_case("transaction expiring beyond the maximum lifetime", 7, &beyond_lifetime),
+        block_case("transaction included twice", 7, &replayed_in_block),
+        block_case("two transactions with the same sender nonce", 7, &nonce_reused),
+        block_case("scheduled transaction included at its first height", 7, &scheduled_due),
+        block_case("scheduled transaction included before its first height", 7, &scheduled_early),
+        block_case("transaction in a format not yet introduced", 7, &future_version),
+    ]
+}
+
+// Membres 2-parmi-3 : graines 4, 5 et 6
+fn multisig_policy() -> MultisigPolicy {
+    MultisigPolicy::new(2, vec![keypair(4).public, keypair(5).public, keypair(6).public]).unwrap()
+}
+
+fn transactions_with_multisig() -> Vec<Transaction> {
+    let mut transaction = Transaction::multisig(CHAIN_ID, multisig_policy(), 0, &keypair(1).public, 75, TransactionKind::Transfer, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    transaction.cosign(&keypair(4)).unwrap();
+    transaction.cosign(&keypair(6)).unwrap();
+    vec![transaction]
+}
+
+fn multisig_case(description: &str, signer_seeds: &[u8]) -> Value {
+    let policy = multisig_policy();
+    let mut transaction = Transaction::multisig(CHAIN_ID, policy.clone(), 0, &keypair(1).public, 75, TransactionKind::Transfer, DEFAULT_GAS_PRICE, BLOCK_TIMESTAMP, VALID_UNTIL);
+    let hash = transaction.hash();
+    let signatures: Vec<(u8, Signature)> = signer_seeds.iter()
+        .map(|seed| policy.approve(&keypair(*seed), &hash).unwrap())
+        .collect();
+    if let Authorization::Multisig { signatures: collected, .. } = &mut transaction.authorization {
+        *collected = signatures.clone();
+    }
+    json!({
+        "description": description,
+        "threshold": policy.threshold,
+        "member_seeds": [4, 5, 6],
+        "members": policy.members.iter().map(|key| hex::encode(key.as_bytes())).collect::<Vec<_>>(),
+        "address": hex::encode(policy.address().as_bytes()),
+        "chain_id": transaction.chain_id.to_string(),
+        "nonce": transaction.nonce.to_string(),
+        "recipient": hex::encode(transaction.recipient.as_bytes()),
+        "amount": transaction.amount.to_string(),
+        "gas_price": transaction.gas_price.to_string(),
+        "timestamp": transaction.timestamp.to_string(),
+        "valid_until": expiry_json(&transaction.valid_until),
+        "hash": hex::encode(&hash),
+        "signatures": signatures.iter().map(|(index, signature)| json!({
+            "index": index,
+            "signature": hex::encode(signature.to_bytes()),
+        })).collect::<Vec<_>>(),
+        "verdict": verdict(transaction.verify()),
+    })
+}
+
+fn multisig_cases() -> Vec<Value> {
+    vec![
+        multisig_case("two of three signatures", &[4, 6]),
+        multisig_case("three of three signatures", &[4, 5, 6]),
+        multisig_case("one of three signatures", &[5]),
+        multisig_case("same member twice", &[5, 5]),
+    ]
+}
+
+pub fn generate() -> Value {
+    json!({
+        "version": VECTORS_VERSION,
+        "transactions": transaction_cases(),
+        "multisig": multisig_cases(),
+        "merkle": merkle_cases(),
+        "state": state_cases(),
+        "blocks": block_cases(),
+    })
+}
+
+fn field<'a>(case: &'a Value, name: &str) -> Result<&'a str, String> {
+    case[name].as_str().ok_or(format!("missing field {}", name))
+}
+
+fn number(case: &Value, name: &str) -> Result<u64, String> {
+    field(case, name)?.parse().map_err(|_| format!("invalid number in {}", name))
+}
+
+fn bytes(case: &Value, name: &str) -> Result<Vec<u8>, String> {
+    hex::decode(field(case, name)?).map_err(|_| format!("invalid hex in {}", name))
+}
+
+fn public_keys(case: &Value, name: &str) -> Result<Vec<PublicKey>, String> {
+    case[name].as_array().ok_or(format!("missing field {}", name))?.iter()
+        .map(|key| hex::decode(key.as_str().unwrap_or_default()).ok()
+            .and_then(|key| PublicKey::from_bytes(&key).ok())
+            .ok_or(format!("invalid key in {}", name)))
+        .collect()
+}
+
+fn seed(case: &Value, name: &str) -> Result<u8, String> {
+    case[name].as_u64().and_then(|seed| u8::try_from(seed).ok()).ok_or(format!("invalid {}", name))
+}
+
+fn expect(failures: &mut Vec<String>, context: &str, name: &str, actual: &str, expected: &str) {
+    if actual != expected {
+        failures.push(format!("{}: {} is {}, expected {}", context, name, actual, expected));
+    }
+}
+
+fn check_transaction(case: &Value, failures: &mut Vec<String>) -> Result<(), String> {
+    let context = format!("transaction \"{}\"", field(case, "description")?);
+    let sender = keypair(seed(case, "sender_seed")?);
+    let recipient = PublicKey::from_bytes(&bytes(case, "recipient")?).map_err(|_| "invalid recipient".to_string())?;
+    let signature = Signature::from_bytes(&bytes(case, "signature")?).map_err(|_| "invalid signature".to_string())?;
+    let version = case["version"].as_u64().and_then(|version| u32::try_from(version).ok()).ok_or("invalid version")?;
+    let transaction = Transaction {
+        version,
+        chain_id: number(case, "chain_id")?,
+        sender: sender.public,
+        nonce: number(case, "nonce")?,
+        recipient,
+        amount: number(case, "amount")?,
+        kind: parse_kind(&case["kind"])?,
+        gas_price: number(case, "gas_price")?,
+        authorization: Authorization::Signature(signature),
+        timestamp: number(case, "timestamp")?,
+        valid_from: number(case, "valid_from")?,
+        valid_until: parse_expiry(&case["valid_until"])?,
+    };
+
+    expect(failures, &context, "sender", &hex::encode(sender.public.as_bytes()), field(case, "sender")?);
+    expect(failures, &context, "signing_bytes", &hex::encode(transaction.signing_bytes()), field(case, "signing_bytes")?);
+    expect(failures, &context, "hash", &hex::encode(transaction.hash()), field(case, "hash")?);
+    expect(failures, &context, "verdict", &verdict(transaction.verify()), field(case, "verdict")?);
+    // Les signatures ed25519 sont déterministes : une transaction valide doit être re-signée à l'identique
+    if field(case, "verdict")? == "valid" {
+        let resigned = sender.sign(&transaction.hash());
+        expect(failures, &context, "signature", &hex::encode(resigned.to_bytes()), field(case, "signature")?);
+    }
+    Ok(())
+}
+
+fn check_multisig(case: &Value, failures: &mut Vec<String>) -> Result<(), String> {
+    let context = format!("multisig \"{}\"", field(case, "description")?);
+    let threshold = case["threshold"].as_u64().and_then(|t| u8::try_from(t).ok()).ok_or("invalid threshold")?;
+    let members = case["member_seeds"].as_array().ok_or("missing member_seeds")?.iter()
+        .map(|seed| seed.as_u64().and_then(|seed| u8::try_from(seed).ok()).map(|seed| keypair(seed).public).ok_or("invalid seed".to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let policy = MultisigPolicy::new(threshold, members).map_err(|e| e.to_string())?;
+    let recipient = PublicKey::from_bytes(&bytes(case, "recipient")?).map_err(|_| "invalid recipient".to_string())?;
+
+    let mut signatures = Vec::new();
+    for entry in case["signatures"].as_array().ok_or("missing signatures")? {
+        let index = entry["index"].as_u64().and_then(|i| u8::try_from(i).ok()).ok_or("invalid signature index")?;
+        let signature = Signature::from_bytes(&bytes(entry, "signature")?).map_err(|_| "invalid signature".to_string())?;
+        signatures.push((index, signature));
+    }
+    let mut transaction = Transaction::multisig(number(case, "chain_id")?, policy.clone(), number(case, "nonce")?, &recipient, number(case, "amount")?, TransactionKind::Transfer, number(case, "gas_price")?, number(case, "timestamp")?, parse_expiry(&case["valid_until"])?);
+    if let Authorization::Multisig { signatures: collected, .. } = &mut transaction.authorization {
+        *collected = signatures;
+    }
+
+    let members: Vec<String> = policy.members.iter().map(|key| hex::encode(key.as_bytes())).collect();
+    let expected_members: Vec<&str> = case["members"].as_array().ok_or("missing members")?.iter().filter_map(|m| m.as_str()).collect();
+    expect(failures, &context, "members", &members.join(","), &expected_members.join(","));
+    expect(failures, &context, "address", &hex::encode(policy.address().as_bytes()), field(case, "address")?);
+    expect(failures, &context, "hash", &hex::encode(transaction.hash()), field(case, "hash")?);
+    expect(failures, &context, "verdict", &verdict(transaction.verify()), field(case, "verdict")?);
+    Ok(())
+}
+
+fn check_merkle(case: &Value, failures: &mut Vec<String>) -> Result<(), String> {
+    let leaves = case["leaves"].as_array().ok_or("missing leaves")?.iter()
+        .map(|leaf| leaf.as_str().and_then(|leaf| hex::decode(leaf).ok()).ok_or("invalid leaf".to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let context = format!("merkle tree of {} leaves", leaves.len());
+    let root = merkle_root(&leaves);
+    expect(failures, &context, "root", &hex::encode(&root), field(case, "root")?);
+
+    for (index, expected) in case["proofs"].as_array().ok_or("missing proofs")?.iter().enumerate() {
+        let proof = merkle_proof(&leaves, index).ok_or("proof index out of range")?;
+        let siblings: Vec<String> = proof.siblings.iter().map(hex::encode).collect();
+        expect(failures, &context, "proof siblings", &siblings.join(","), &expected["siblings"].as_array()
+            .ok_or("missing siblings")?.iter().filter_map(|s| s.as_str()).collect::<Vec<_>>().join(","));
+        if !verify_proof(&root, &leaves[index], &proof) {
+            failures.push(format!("{}: proof {} does not verify", context, index));
+        }
+    }
+    Ok(())
+}
+
+fn check_state(case: &Value, failures: &mut Vec<String>) -> Result<(), String> {
+    let balances = case["balances"].as_array().ok_or("missing balances")?.iter()
+        .map(|entry| {
+            let account = PublicKey::from_bytes(&bytes(entry, "account")?).map_err(|_| "invalid account".to_string())?;
+            Ok((account, number(entry, "balance")?))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let context = format!("state tree of {} accounts", balances.len());
+    let tree = state_tree(&balances);
+    expect(failures, &context, "root", &hex::encode(tree.root()), field(case, "root")?);
+
+    for expected in case["proofs"].as_array().ok_or("missing proofs")? {
+        let account = PublicKey::from_bytes(&bytes(expected, "account")?).map_err(|_| "invalid account".to_string())?;
+        let proof = tree.prove(&account);
+        let siblings: Vec<String> = proof.siblings.iter().map(hex::encode).collect();
+        expect(failures, &context, "proof siblings", &siblings.join(","), &expected["siblings"].as_array()
+            .ok_or("missing siblings")?.iter().filter_map(|s| s.as_str()).collect::<Vec<_>>().join(","));
+        let leaf = match &expected["leaf"] {
+            Value::Null => None,
+            leaf => Some((bytes(leaf, "account")?, number(leaf, "balance")?)),
+        };
+        if proof.leaf.map(|(account, balance)| (account.to_vec(), balance)) != leaf {
+            failures.push(format!("{}: proof leaf differs for {}", context, field(expected, "account")?));
+        }
+        match proof.verify(tree.root(), &account) {
+            Ok(balance) => expect(failures, &context, "proven balance", &balance.to_string(), field(expected, "balance")?),
+            Err(e) => failures.push(format!("{}: {}", context, e)),
+        }
+    }
+    Ok(())
+}
+
+fn check_block(case: &Value, failures: &mut Vec<String>) -> Result<(), String> {
+    let context = format!("block \"{}\"", field(case, "description")?);
+    let encoding = bytes(case, "encoding")?;
+    let block = decode_block(&encoding).map_err(|e| format!("{}: {}", context, e))?;
+    let validator = keypair(seed(case, "validator_seed")?);
+    let config = ChainConfig::builder().chain_id(number(case, "chain_id")?).max_clock_drift(number(case, "max_clock_drift")?).build().map_err(str::to_string)?;
+    let mut chain = Blockchain::new(config);
+    chain.set_clock(Arc::new(MockClock::new(Duration::from_secs(number(case, "local_time")?))));
+    chain.register_validator(&validator.public, number(case, "validator_stake")?).map_err(|e| e.to_string())?;
+    for entry in case["genesis_balances"].as_array().ok_or("missing genesis_balances")? {
+        let account = PublicKey::from_bytes(&bytes(entry, "account")?).map_err(|_| "invalid genesis account".to_string())?;
+        chain.allocate(&account, number(entry, "balance")?);
+    }
+
+    expect(failures, &context, "encoding", &hex::encode(encode_block(&block)), field(case, "encoding")?);
+    expect(failures, &context, "header_signing_bytes", &hex::encode(block.header().signing_bytes()), field(case, "header_signing_bytes")?);
+    expect(failures, &context, "hash", &hex::encode(&block.current_hash), field(case, "hash")?);
+    expect(failures, &context, "transactions_root", &hex::encode(&block.transactions_root), field(case, "transactions_root")?);
+    expect(failures, &context, "state_root", &hex::encode(&block.state_root), field(case, "state_root")?);
+    expect(failures, &context, "verdict", &verdict(chain.validate_block(&block)), field(case, "verdict")?);
+    Ok(())
+}
+
+// Rejoue un jeu de tests ; retourne la liste des écarts constatés
+pub fn verify(vectors: &Value) -> Result<Vec<String>, String> {
+    if vectors["version"].as_u64() != Some(VECTORS_VERSION) {
+        return Err("Unsupported conformance vectors version".to_string());
+    }
+    let mut failures = Vec::new();
+    for case in vectors["transactions"].as_array().ok_or("missing transactions")? {
+        check_transaction(case, &mut failures)?;
+    }
+    for case in vectors["multisig"].as_array().ok_or("missing multisig")? {
+        check_multisig(case, &mut failures)?;
+    }
+    for case in vectors["merkle"].as_array().ok_or("missing merkle")? {
+        check_merkle(case, &mut failures)?;
+    }
+    for case in vectors["state"].as_array().ok_or("missing state")? {
+        check_state(case, &mut failures)?;
+    }
+    for case in vectors["blocks"].as_array().ok_or("missing blocks")? {
+        check_block(case, &mut failures)?;
+    }
+    Ok(failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn committed_vectors_are_those_generated_and_pass() {
+        let vectors = generate();
+        let committed: Value = serde_json::from_str(include_str!("conformance/vectors.json")).unwrap();
+        assert_eq!(committed, vectors, "conformance/vectors.json is stale, regenerate it");
+        assert_eq!(verify(&vectors), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn mismatches_are_reported_per_case() {
+        let mut vectors = generate();
+        let description = vectors["transactions"][0]["description"].as_str().unwrap().to_string();
+        vectors["transactions"][0]["hash"] = json!("00");
+        let verdict = vectors["blocks"][0]["verdict"].as_str().unwrap().to_string();
+        vectors["blocks"][0]["verdict"] = json!("Accepted by mistake");
+
+        let failures = verify(&vectors).unwrap();
+        assert_eq!(failures.len(), 2, "{:?}", failures);
+        assert!(failures[0].starts_with(&format!("transaction \"{}\": hash is ", description)));
+        assert!(failures[0].ends_with("expected 00"));
+        assert!(failures[1].ends_with(&format!("verdict is {}, expected Accepted by mistake", verdict)));
+    }
+
+    #[test]
+    fn unreadable_vectors_are_errors() {
+        let mut vectors = generate();
+        vectors["version"] = json!(VECTORS_VERSION + 1);
+        assert_eq!(verify(&vectors), Err("Unsupported conformance vectors version".to_string()));
+
+        let mut vectors = generate();
+        vectors.as_object_mut().unwrap().remove("blocks");
+        assert_eq!(verify(&vectors), Err("missing blocks".to_string()));
+        let mut vectors = generate();
+        vectors["transactions"][0]["chain_id"] = json!("one");
+        assert_eq!(verify(&vectors), Err("invalid number in chain_id".to_string()));
+    }
+}
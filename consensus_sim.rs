@@ -0,0 +1,406 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestCaseError, TestRunner};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+use crate::checkpoint::CheckpointVote;
+use crate::clock::{Clock, MockClock};
+use crate::config::ChainConfig;
+use crate::events::ChainEvent;
+use crate::gas::DEFAULT_GAS_PRICE;
+use crate::governance::GovernanceTransaction;
+use crate::node::Node;
+use crate::orphans::{OrphanLimits, OrphanPool};
+use crate::simulation::SplitMix64;
+use crate::{Block, Blockchain, Expiry, Transaction, TransactionKind, TRANSACTION_VALIDITY};
+
+// Mise de chaque validateur simulé et solde de départ de chaque compte
+const VALIDATOR_STAKE: u64 = 10_000;
+const ACCOUNT_BALANCE: u64 = 1_000_000;
+
+// Pas de l'horloge virtuelle par créneau : les messages sont remis pas à pas,
+// les blocs produits au début de chaque créneau
+const TICKS_PER_SLOT: u64 = 10;
+const TICK: Duration = Duration::from_millis(100);
+// Heure partagée par les nœuds simulés au premier pas
+const START_TIME: Duration = Duration::from_secs(1_700_000_000);
+
+// Comptes émetteurs et destinataires des transferts, communs à tous les scénarios
+const ACCOUNTS: usize = 8;
+
+// Transfert soumis au nœud `node` au créneau `slot`
+#[derive(Clone, Debug)]
+pub struct Transfer {
+    pub slot: u64,
+    pub from: usize,
+    pub to: usize,
+    pub amount: u64,
+    pub node: usize,
+}
+
+// Les nœuds dont le bit est à 1 dans `side` ne communiquent plus avec les autres
+// du créneau `start` au créneau `end` exclu
+#[derive(Clone, Debug)]
+pub struct Partition {
+    pub start: u64,
+    pub end: u64,
+    pub side: u64,
+}
+
+// Réseau simulé : validateurs, pertes et délais des messages, charge et partitions.
+// Un même scénario produit toujours la même exécution.
+#[derive(Clone, Debug)]
+pub struct Scenario {
+    pub validators: usize,
+    pub slots: u64,
+    // Probabilité de perte de chaque message, en pourcentage
+    pub loss_percent: u64,
+    // Délai d'acheminement tiré entre 1 et `max_delay` pas de l'horloge virtuelle
+    pub max_delay: u64,
+    pub seed: u64,
+    pub transfers: Vec<Transfer>,
+    pub partitions: Vec<Partition>,
+}
+
+impl Scenario {
+    fn connected(&self, slot: u64, from: usize, to: usize) -> bool {
+        self.partitions.iter()
+            .filter(|partition| (partition.start..partition.end).contains(&slot))
+            .all(|partition| ((partition.side >> from) & 1) == ((partition.side >> to) & 1))
+    }
+}
+
+// Déroulement d'un scénario dont les invariants ont tenu
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    // Hauteur de la chaîne la plus longue
+    pub height: u64,
+    // Plus haut checkpoint finalisé par au moins un nœud
+    pub finalized: Option<u64>,
+    // Têtes distinctes à la fin du scénario (plus d'une après une partition non résorbée)
+    pub heads: usize,
+    pub delivered: u64,
+    pub lost: u64,
+}
+
+#[derive(Clone)]
+enum Message {
+    Block(Block),
+    Vote(CheckpointVote),
+    Transaction(Transaction),
+    Governance(GovernanceTransaction),
+}
+
+impl Message {
+    fn id(&self) -> Vec<u8> {
+        match self {
+            Message::Block(block) => block.current_hash.clone(),
+            Message::Vote(vote) => [vote.block_hash.as_slice(), vote.validator.as_bytes()].concat(),
+            Message::Transaction(transaction) => transaction.hash(),
+            Message::Governance(transaction) => transaction.hash(),
+        }
+    }
+}
+
+struct Envelope {
+    deliver_at: u64,
+    from: usize,
+    to: usize,
+    message: Message,
+}
+
+// Bus de messages avec pertes et délais ; une partition coupe les messages au
+// moment de leur remise
+struct MessageBus {
+    queue: Vec<Envelope>,
+    random: SplitMix64,
+    loss_percent: u64,
+    max_delay: u64,
+}
+
+impl MessageBus {
+    fn send(&mut self, tick: u64, from: usize, to: usize, message: Message) -> bool {
+        if self.random.next() % 100 < self.loss_percent {
+            return false;
+        }
+        let deliver_at = tick + 1 + self.random.next() % self.max_delay.max(1);
+        self.queue.push(Envelope { deliver_at, from, to, message });
+        true
+    }
+
+    fn due(&mut self, tick: u64) -> Vec<Envelope> {
+        let (due, later): (Vec<Envelope>, Vec<Envelope>) = std::mem::take(&mut self.queue).into_iter().partition(|envelope| envelope.deliver_at <= tick);
+        self.queue = later;
+        due
+    }
+}
+
+// Validateur simulé, qui réagit aux blocs comme un nœud (votes de checkpoint,
+// balise d'aléa, réactivation) et relaie une fois chaque message, comme la
+// diffusion entre pairs
+struct SimNode {
+    keypair: Keypair,
+    chain: Blockchain,
+    events: broadcast::Receiver<ChainEvent>,
+    orphans: OrphanPool,
+    // Votes reçus avant leur bloc de checkpoint
+    early_votes: Vec<CheckpointVote>,
+    seen: HashSet<Vec<u8>>,
+    relayed: Vec<Message>,
+}
+
+impl SimNode {
+    fn apply_block(&mut self, block: Block) {
+        if block.index > self.chain.next_index() {
+            if block.header().verify().is_ok() {
+                self.orphans.insert(block);
+            }
+            return;
+        }
+        let mut pending = vec![block];
+        while let Some(block) = pending.pop() {
+            let (index, hash) = (block.index, block.current_hash.clone());
+            if self.chain.add_block(block).is_ok() {
+                self.after_block(index, hash.clone());
+                pending.extend(self.orphans.take_children(&hash));
+            }
+        }
+        self.orphans.prune(self.chain.next_index());
+    }
+
+    fn after_block(&mut self, index: u64, block_hash: Vec<u8>) {
+        Node::vote_checkpoint(&mut self.chain, &self.keypair, index, block_hash);
+        Node::contribute_beacon(&mut self.chain, &self.keypair);
+        Node::reactivate(&mut self.chain, &self.keypair);
+        let next_index = self.chain.next_index();
+        for vote in std::mem::take(&mut self.early_votes) {
+            self.add_vote(vote, next_index);
+        }
+    }
+
+    fn add_vote(&mut self, vote: CheckpointVote, next_index: u64) {
+        if vote.height >= next_index {
+            self.early_votes.push(vote);
+        } else {
+            let _ = self.chain.add_checkpoint_vote(vote);
+        }
+    }
+
+    fn receive(&mut self, message: Message) {
+        if !self.seen.insert(message.id()) {
+            return;
+        }
+        self.relayed.push(message.clone());
+        match message {
+            Message::Block(block) => self.apply_block(block),
+            Message::Vote(vote) => self.add_vote(vote, self.chain.next_index()),
+            Message::Transaction(transaction) => {
+                let _ = self.chain.add_transaction(transaction);
+            }
+            Message::Governance(transaction) => {
+                let _ = self.chain.add_governance_transaction(transaction);
+            }
+        }
+    }
+
+    fn produce(&mut self, slot: u64) {
+        if self.chain.select_leader(slot) != Some(self.keypair.public) {
+            return;
+        }
+        let Ok(template) = self.chain.build_block(self.keypair.public, slot) else {
+            return;
+        };
+        let signature = self.keypair.sign(&template.current_hash);
+        if let Ok(block) = self.chain.commit_block(template, signature) {
+            self.after_block(block.index, block.current_hash);
+        }
+    }
+
+    // Messages reçus ou produits par le nœud depuis le dernier appel, à diffuser
+    fn outbox(&mut self) -> Vec<Message> {
+        let mut messages = std::mem::take(&mut self.relayed);
+        loop {
+            let message = match self.events.try_recv() {
+                Ok(ChainEvent::BlockCommitted(block)) => Message::Block(block),
+                Ok(ChainEvent::CheckpointVoteAdded(vote)) => Message::Vote(vote),
+                Ok(ChainEvent::TransactionAccepted(transaction)) => Message::Transaction(transaction),
+                Ok(ChainEvent::GovernanceTransactionAccepted(transaction)) => Message::Governance(transaction),
+                Ok(_) | Err(TryRecvError::Lagged(_)) => continue,
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            };
+            if self.seen.insert(message.id()) {
+                messages.push(message);
+            }
+        }
+        messages
+    }
+
+    fn supply(&self) -> u128 {
+        self.chain.accounts.ledger.iter().map(|(_, balance)| balance as u128).sum()
+    }
+}
+
+fn keypair(seed: u8) -> Keypair {
+    let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+    Keypair { public: PublicKey::from(&secret), secret }
+}
+
+fn genesis(validators: &[PublicKey], accounts: &[Keypair]) -> Blockchain {
+    let mut chain = Blockchain::new(ChainConfig::default());
+    for validator in validators {
+        chain.register_validator(validator, VALIDATOR_STAKE).expect("Simulated validator stake below the registration minimum");
+    }
+    for account in accounts {
+        chain.allocate(&account.public, ACCOUNT_BALANCE);
+    }
+    chain
+}
+
+// Exécute un scénario créneau par créneau (horloge virtuelle) en vérifiant après
+// chaque créneau que la masse monétaire de chaque nœud est conservée et
+// qu'aucun checkpoint finalisé ne désigne deux blocs différents
+pub fn run(scenario: &Scenario) -> Result<Report, String> {
+    let accounts: Vec<Keypair> = (0..ACCOUNTS).map(|i| keypair(100 + i as u8)).collect();
+    let validator_keys: Vec<Keypair> = (0..scenario.validators).map(|i| keypair(1 + i as u8)).collect();
+    let clock = MockClock::new(START_TIME);
+    let public_keys: Vec<PublicKey> = validator_keys.iter().map(|keypair| keypair.public).collect();
+    let mut nodes: Vec<SimNode> = validator_keys.into_iter()
+        .map(|keypair| {
+            let mut chain = genesis(&public_keys, &accounts);
+            chain.set_clock(Arc::new(clock.clone()));
+            let events = chain.subscribe();
+            SimNode {
+                keypair,
+                chain,
+                events,
+                orphans: OrphanPool::new(OrphanLimits::default()),
+                early_votes: Vec::new(),
+                seen: HashSet::new(),
+                relayed: Vec::new(),
+            }
+        })
+        .collect();
+    let supply = ACCOUNT_BALANCE as u128 * ACCOUNTS as u128;
+    let mut bus = MessageBus {
+        queue: Vec::new(),
+        random: SplitMix64(scenario.seed),
+        loss_percent: scenario.loss_percent,
+        max_delay: scenario.max_delay,
+    };
+    let mut report = Report::default();
+    // Bloc de chaque checkpoint finalisé, tous nœuds confondus
+    let mut finalized: BTreeMap<u64, Vec<u8>> = BTreeMap::new();
+    let mut finalized_heights: HashMap<usize, u64> = HashMap::new();
+    // Nonce suivant de chaque compte, tenu par les portefeuilles
+    let mut nonces = [0u64; ACCOUNTS];
+
+    for tick in TICKS_PER_SLOT..(scenario.slots + 1) * TICKS_PER_SLOT {
+        clock.set(clock.now() + TICK);
+        let slot = tick / TICKS_PER_SLOT;
+        let slot_start = tick % TICKS_PER_SLOT == 0;
+        if slot_start {
+            for transfer in scenario.transfers.iter().filter(|transfer| transfer.slot == slot) {
+                let (from, to) = (&accounts[transfer.from % ACCOUNTS], &accounts[transfer.to % ACCOUNTS]);
+                let nonce = nonces[transfer.from % ACCOUNTS];
+                nonces[transfer.from % ACCOUNTS] += 1;
+                let timestamp = clock.unix_secs();
+                let transaction = Transaction::signed(nodes[0].chain.config.chain_id, from, nonce, &to.public, transfer.amount, TransactionKind::Transfer, DEFAULT_GAS_PRICE, timestamp, Expiry::Timestamp(timestamp + TRANSACTION_VALIDITY));
+                let _ = nodes[transfer.node % scenario.validators].chain.add_transaction(transaction);
+            }
+        }
+        for envelope in bus.due(tick) {
+            if scenario.connected(slot, envelope.from, envelope.to) {
+                report.delivered += 1;
+                nodes[envelope.to].receive(envelope.message);
+            } else {
+                report.lost += 1;
+            }
+        }
+        if slot_start {
+            for node in &mut nodes {
+                node.produce(slot);
+            }
+        }
+        for from in 0..nodes.len() {
+            for message in nodes[from].outbox() {
+                for to in (0..nodes.len()).filter(|to| *to != from) {
+                    if !bus.send(tick, from, to, message.clone()) {
+                        report.lost += 1;
+                    }
+                }
+            }
+        }
+
+        for (index, node) in nodes.iter().enumerate() {
+            if node.supply() != supply {
+                return Err(format!("slot {}: node {} holds a supply of {} instead of {}", slot, index, node.supply(), supply));
+            }
+            let Some(height) = node.chain.finalized_height() else {
+                continue;
+            };
+            if finalized_heights.insert(index, height).is_some_and(|previous| previous > height) {
+                return Err(format!("slot {}: node {} finality went back to height {}", slot, index, height));
+            }
+            let block_hash = node.chain.block_at(height).map(|block| block.current_hash.clone())
+                .ok_or(format!("slot {}: node {} lost finalized block {}", slot, index, height))?;
+            let agreed = finalized.entry(height).or_insert_with(|| block_hash.clone());
+            if *agreed != block_hash {
+                return Err(format!("slot {}: conflicting blocks finalized at height {} ({} and {})", slot, height, hex::encode(agreed), hex::encode(&block_hash)));
+            }
+        }
+    }
+
+    report.height = nodes.iter().map(|node| node.chain.next_index()).max().unwrap_or(0);
+    report.finalized = finalized.keys().next_back().copied();
+    let mut heads: Vec<Vec<u8>> = nodes.iter().map(|node| node.chain.last_hash()).collect();
+    heads.sort();
+    heads.dedup();
+    report.heads = heads.len();
+    Ok(report)
+}
+
+// Scénarios de 3 à 7 validateurs sur assez de créneaux pour atteindre le premier
+// checkpoint, avec jusqu'à 20 % de pertes, des délais jusqu'à un créneau et
+// demi, 200 transferts et 3 partitions
+pub fn scenarios() -> impl Strategy<Value = Scenario> {
+    (3usize..=7, 120u64..=260).prop_flat_map(|(validators, slots)| {
+        let transfer = (1..=slots, 0..ACCOUNTS, 0..ACCOUNTS, 1u64..=50_000, 0..validators)
+            .prop_map(|(slot, from, to, amount, node)| Transfer { slot, from, to, amount, node });
+        let partition = (1..=slots, 1..=slots / 2, 1u64..(1 << validators) - 1)
+            .prop_map(|(start, length, side)| Partition { start, end: start + length, side });
+        (
+            Just(validators),
+            Just(slots),
+            0u64..=20,
+            1u64..=15,
+            any::<u64>(),
+            prop::collection::vec(transfer, 0..200),
+            prop::collection::vec(partition, 0..=3),
+        )
+    })
+    .prop_map(|(validators, slots, loss_percent, max_delay, seed, transfers, partitions)| Scenario {
+        validators,
+        slots,
+        loss_percent,
+        max_delay,
+        seed,
+        transfers,
+        partitions,
+    })
+}
+
+// `cases` scénarios aléatoires ; en cas d'échec, le scénario minimal qui le reproduit
+pub fn check(cases: u32) -> Result<Vec<Report>, String> {
+    let mut runner = TestRunner::new(Config { cases, ..Config::default() });
+    let reports = RefCell::new(Vec::new());
+    runner.run(&scenarios(), |scenario| {
+        let report = run(&scenario).map_err(TestCaseError::fail)?;
+        reports.borrow_mut().push(report);
+        Ok(())
+    }).map_err(|e| e.to_string())?;
+    Ok(reports.into_inner())
+}
@@ -0,0 +1,455 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use ed25519_dalek::PublicKey;
+
+use crate::address::derive_system_address;
+use crate::encoding::{Decoder, Encoder, CONTRACT_DOMAIN};
+use crate::{Transaction, TransactionKind};
+
+// Limites d'exécution, identiques sur tous les nœuds
+const MAX_CODE_SIZE: usize = 24 * 1024;
+const MAX_STACK_DEPTH: usize = 256;
+const MAX_VALUE_SIZE: usize = 1024;
+pub const MAX_CALL_GAS: u64 = 1_000_000;
+
+// Coût en gaz : chaque instruction coûte GAS_STEP, les accès au stockage davantage
+const GAS_STEP: u64 = 1;
+const GAS_SLOAD: u64 = 20;
+const GAS_SSTORE: u64 = 100;
+const GAS_PER_STORED_BYTE: u64 = 1;
+
+// Jeu d'instructions de la machine à pile. Les entiers sont des u64 gros-boutistes
+// d'au plus 8 octets ; toute valeur non nulle est vraie.
+const OP_STOP: u8 = 0x00;
+const OP_PUSH: u8 = 0x01; // longueur (1 octet) puis octets littéraux
+const OP_POP: u8 = 0x02;
+const OP_DUP: u8 = 0x03; // profondeur (1 octet), 0 = sommet
+const OP_SWAP: u8 = 0x04;
+const OP_ADD: u8 = 0x10;
+const OP_SUB: u8 = 0x11;
+const OP_MUL: u8 = 0x12;
+const OP_DIV: u8 = 0x13;
+const OP_MOD: u8 = 0x14;
+const OP_EQ: u8 = 0x18;
+const OP_LT: u8 = 0x19;
+const OP_GT: u8 = 0x1a;
+const OP_NOT: u8 = 0x1b;
+const OP_JUMP: u8 = 0x20; // cible (2 octets)
+const OP_JUMPI: u8 = 0x21; // cible (2 octets), saut si la valeur dépilée est vraie
+const OP_SLOAD: u8 = 0x30; // clé -> valeur (vide si absente)
+const OP_SSTORE: u8 = 0x31; // valeur, clé -> ; une valeur vide efface la clé
+const OP_INPUT: u8 = 0x40;
+const OP_CALLER: u8 = 0x41;
+const OP_VALUE: u8 = 0x42;
+const OP_HEIGHT: u8 = 0x43;
+const OP_RETURN: u8 = 0x50;
+const OP_REVERT: u8 = 0x51;
+
+// Taille des opérandes immédiats de chaque instruction, `None` si inconnue
+fn immediate_size(code: &[u8], position: usize) -> Option<usize> {
+    match code[position] {
+        OP_PUSH => code.get(position + 1).map(|length| 1 + *length as usize),
+        OP_DUP => Some(1),
+        OP_JUMP | OP_JUMPI => Some(2),
+        OP_STOP | OP_POP | OP_SWAP | OP_ADD | OP_SUB | OP_MUL | OP_DIV | OP_MOD | OP_EQ | OP_LT | OP_GT | OP_NOT
+        | OP_SLOAD | OP_SSTORE | OP_INPUT | OP_CALLER | OP_VALUE | OP_HEIGHT | OP_RETURN | OP_REVERT => Some(0),
+        _ => None,
+    }
+}
+
+// Débuts d'instruction du code, seules cibles de saut valides
+fn instruction_starts(code: &[u8]) -> Result<HashSet<usize>, &'static str> {
+    if code.is_empty() || code.len() > MAX_CODE_SIZE {
+        return Err("Invalid contract code size");
+    }
+    let mut starts = HashSet::new();
+    let mut position = 0;
+    while position < code.len() {
+        starts.insert(position);
+        let size = immediate_size(code, position).ok_or("Invalid contract opcode")?;
+        position += 1 + size;
+        if position > code.len() {
+            return Err("Truncated contract instruction");
+        }
+    }
+    Ok(starts)
+}
+
+// Adresse d'un contrat, dérivée du déployeur, de l'horodatage du déploiement et du code
+pub fn contract_address(deployer: &PublicKey, timestamp: u64, code: &[u8]) -> PublicKey {
+    let mut encoder = Encoder::new(CONTRACT_DOMAIN);
+    encoder.put_bytes(code);
+    derive_system_address(deployer, timestamp, &encoder.hash())
+}
+
+struct CallContext<'a> {
+    caller: PublicKey,
+    value: u64,
+    height: u64,
+    input: &'a [u8],
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Execution {
+    pub output: Vec<u8>,
+    pub gas_used: u64,
+    // Écritures à appliquer au stockage du contrat (valeur vide = suppression)
+    writes: BTreeMap<Vec<u8>, Vec<u8>>,
+    // Motif d'échec (épuisement du gaz, REVERT...) ; l'appel consomme alors tout
+    // son gaz et n'a aucun effet
+    pub failure: Option<&'static str>,
+}
+
+impl Execution {
+    fn failed(reason: &'static str, gas_limit: u64) -> Self {
+        Execution { output: Vec::new(), gas_used: gas_limit, writes: BTreeMap::new(), failure: Some(reason) }
+    }
+}
+
+fn to_integer(value: &[u8]) -> Result<u64, &'static str> {
+    if value.len() > 8 {
+        return Err("Integer operand too long");
+    }
+    Ok(value.iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64))
+}
+
+fn from_integer(value: u64) -> Vec<u8> {
+    value.to_be_bytes().to_vec()
+}
+
+// Exécution déterministe du code ; aucune écriture n'est appliquée en cas d'erreur
+fn execute(code: &[u8], storage: &BTreeMap<Vec<u8>, Vec<u8>>, context: &CallContext, gas_limit: u64) -> Result<Execution, &'static str> {
+    let starts = instruction_starts(code)?;
+    let mut stack: Vec<Vec<u8>> = Vec::new();
+    let mut writes: BTreeMap<Vec<u8>, Vec<u8>> = BTreeMap::new();
+    let mut gas_used: u64 = 0;
+    let mut position = 0;
+
+    let charge = |gas_used: &mut u64, cost: u64| -> Result<(), &'static str> {
+        *gas_used = gas_used.saturating_add(cost);
+        if *gas_used > gas_limit {
+            return Err("Out of gas");
+        }
+        Ok(())
+    };
+
+    loop {
+        let Some(&opcode) = code.get(position) else {
+            return Ok(Execution { output: Vec::new(), gas_used, writes, failure: None });
+        };
+        charge(&mut gas_used, GAS_STEP)?;
+        let mut next = position + 1 + immediate_size(code, position).ok_or("Invalid contract opcode")?;
+
+        match opcode {
+            OP_STOP => return Ok(Execution { output: Vec::new(), gas_used, writes, failure: None }),
+            OP_PUSH => {
+                let length = code[position + 1] as usize;
+                stack.push(code[position + 2..position + 2 + length].to_vec());
+            }
+            OP_POP => {
+                stack.pop().ok_or("Stack underflow")?;
+            }
+            OP_DUP => {
+                let depth = code[position + 1] as usize;
+                let value = stack.iter().rev().nth(depth).ok_or("Stack underflow")?.clone();
+                stack.push(value);
+            }
+            OP_SWAP => {
+                let length = stack.len();
+                if length < 2 {
+                    return Err("Stack underflow");
+                }
+                stack.swap(length - 1, length - 2);
+            }
+            OP_ADD | OP_SUB | OP_MUL | OP_DIV | OP_MOD | OP_EQ | OP_LT | OP_GT => {
+                let right = to_integer(&stack.pop().ok_or("Stack underflow")?)?;
+                let left = to_integer(&stack.pop().ok_or("Stack underflow")?)?;
+                let result = match opcode {
+                    OP_ADD => left.checked_add(right).ok_or("Arithmetic overflow")?,
+                    OP_SUB => left.checked_sub(right).ok_or("Arithmetic overflow")?,
+                    OP_MUL => left.checked_mul(right).ok_or("Arithmetic overflow")?,
+                    OP_DIV => left.checked_div(right).ok_or("Division by zero")?,
+                    OP_MOD => left.checked_rem(right).ok_or("Division by zero")?,
+                    OP_EQ => (left == right) as u64,
+                    OP_LT => (left < right) as u64,
+                    _ => (left > right) as u64,
+                };
+                stack.push(from_integer(result));
+            }
+            OP_NOT => {
+                let value = to_integer(&stack.pop().ok_or("Stack underflow")?)?;
+                stack.push(from_integer((value == 0) as u64));
+            }
+            OP_JUMP | OP_JUMPI => {
+                let target = u16::from_be_bytes([code[position + 1], code[position + 2]]) as usize;
+                let taken = opcode == OP_JUMP || to_integer(&stack.pop().ok_or("Stack underflow")?)? != 0;
+                if taken {
+                    if !starts.contains(&target) {
+                        return Err("Invalid jump target");
+                    }
+                    next = target;
+                }
+            }
+            OP_SLOAD => {
+                charge(&mut gas_used, GAS_SLOAD)?;
+                let key = stack.pop().ok_or("Stack underflow")?;
+                let value = writes.get(&key).or_else(|| storage.get(&key)).cloned().unwrap_or_default();
+                stack.push(value);
+            }
+            OP_SSTORE => {
+                let key = stack.pop().ok_or("Stack underflow")?;
+                let value = stack.pop().ok_or("Stack underflow")?;
+                charge(&mut gas_used, GAS_SSTORE + GAS_PER_STORED_BYTE * (key.len() + value.len()) as u64)?;
+                writes.insert(key, value);
+            }
+            OP_INPUT => stack.push(context.input.to_vec()),
+            OP_CALLER => stack.push(context.caller.to_bytes().to_vec()),
+            OP_VALUE => stack.push(from_integer(context.value)),
+            OP_HEIGHT => stack.push(from_integer(context.height)),
+            OP_RETURN => {
+                let output = stack.pop().ok_or("Stack underflow")?;
+                return Ok(Execution { output, gas_used, writes, failure: None });
+            }
+            OP_REVERT => return Err("Contract reverted"),
+            _ => return Err("Invalid contract opcode"),
+        }
+
+        if stack.len() > MAX_STACK_DEPTH {
+            return Err("Stack overflow");
+        }
+        if stack.last().is_some_and(|value| value.len() > MAX_VALUE_SIZE) {
+            return Err("Value too large");
+        }
+        position = next;
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Contract {
+    code: Vec<u8>,
+    storage: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+// Contrats déployés, par adresse, avec leur stockage clé-valeur
+#[derive(Clone, Debug, Default)]
+pub struct Contracts {
+    deployed: HashMap<PublicKey, Contract>,
+}
+
+impl Contracts {
+    pub fn storage(&self, contract: &PublicKey, key: &[u8]) -> Option<&Vec<u8>> {
+        self.deployed.get(contract)?.storage.get(key)
+    }
+
+    // Déploiement ou appel valide pour le bloc `height` ; un appel est exécuté
+    // sans que ses écritures soient appliquées. Un appel dont l'exécution échoue
+    // reste valide : l'échec est rapporté dans `Execution::failure`
+    pub fn check(&self, transaction: &Transaction, height: u64) -> Result<Option<Execution>, &'static str> {
+        match &transaction.kind {
+            TransactionKind::Deploy { code } => {
+                instruction_starts(code)?;
+                if self.deployed.contains_key(&transaction.recipient) {
+                    return Err("Contract already deployed");
+                }
+                Ok(None)
+            }
+            TransactionKind::Call { input, gas_limit } => {
+                if *gas_limit > MAX_CALL_GAS {
+                    return Err("Gas limit too high");
+                }
+                let contract = self.deployed.get(&transaction.recipient).ok_or("Unknown contract")?;
+                let context = CallContext {
+                    caller: transaction.sender,
+                    value: transaction.amount,
+                    height,
+                    input,
+                };
+                let execution = execute(&contract.code, &contract.storage, &context, *gas_limit)
+                    .unwrap_or_else(|reason| Execution::failed(reason, *gas_limit));
+                Ok(Some(execution))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // Applique un déploiement, ou les écritures d'un appel déjà exécuté par `check`
+    pub fn commit(&mut self, transaction: &Transaction, execution: Option<&Execution>) {
+        match (&transaction.kind, execution) {
+            (TransactionKind::Deploy { code }, _) => {
+                self.deployed.insert(transaction.recipient, Contract { code: code.clone(), storage: BTreeMap::new() });
+            }
+            (TransactionKind::Call { .. }, Some(execution)) => {
+                let Some(contract) = self.deployed.get_mut(&transaction.recipient) else {
+                    return;
+                };
+                for (key, value) in &execution.writes {
+                    if value.is_empty() {
+                        contract.storage.remove(key);
+                    } else {
+                        contract.storage.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        let mut deployed: Vec<(&PublicKey, &Contract)> = self.deployed.iter().collect();
+        deployed.sort_by_key(|(address, _)| address.to_bytes());
+        encoder.put_u32(deployed.len() as u32);
+        for (address, contract) in deployed {
+            encoder.put_public_key(address);
+            encoder.put_bytes(&contract.code);
+            encoder.put_u32(contract.storage.len() as u32);
+            for (key, value) in &contract.storage {
+                encoder.put_bytes(key);
+                encoder.put_bytes(value);
+            }
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Contracts, &'static str> {
+        let mut deployed = HashMap::new();
+        let mut previous: Option<PublicKey> = None;
+        for _ in 0..decoder.get_u32()? {
+            let address = decoder.get_public_key()?;
+            if previous.is_some_and(|previous| previous.to_bytes() >= address.to_bytes()) {
+                return Err("Contracts not in canonical order");
+            }
+            let code = decoder.get_bytes()?.to_vec();
+            instruction_starts(&code)?;
+            let mut storage = BTreeMap::new();
+            let mut previous_key: Option<Vec<u8>> = None;
+            for _ in 0..decoder.get_u32()? {
+                let key = decoder.get_bytes()?.to_vec();
+                if previous_key.as_ref().is_some_and(|previous| *previous >= key) {
+                    return Err("Contract storage not in canonical order");
+                }
+                previous_key = Some(key.clone());
+                storage.insert(key, decoder.get_bytes()?.to_vec());
+            }
+            previous = Some(address);
+            deployed.insert(address, Contract { code, storage });
+        }
+        Ok(Contracts { deployed })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Keypair, SecretKey};
+
+    use super::*;
+    use crate::txbuilder::TxBuilder;
+
+    const TIMESTAMP: u64 = 1_700_000_000;
+
+    // Incrémente la clé "c" et retourne sa nouvelle valeur
+    const COUNTER: [u8; 15] = [OP_PUSH, 1, b'c', OP_SLOAD, OP_PUSH, 1, 1, OP_ADD, OP_DUP, 0, OP_PUSH, 1, b'c', OP_SSTORE, OP_RETURN];
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: (&secret).into(), secret }
+    }
+
+    fn transaction(sender: &Keypair, contract: PublicKey, kind: TransactionKind) -> Transaction {
+        TxBuilder::new(kind).to(contract).nonce(0).timestamp(TIMESTAMP).sign(sender).unwrap()
+    }
+
+    // Contrats où `code` est déployé par la graine 1
+    fn deployed(code: &[u8]) -> (Contracts, PublicKey) {
+        let deployer = keypair(1);
+        let contract = contract_address(&deployer.public, TIMESTAMP, code);
+        let deploy = transaction(&deployer, contract, TransactionKind::Deploy { code: code.to_vec() });
+        let mut contracts = Contracts::default();
+        assert_eq!(contracts.check(&deploy, 1), Ok(None));
+        contracts.commit(&deploy, None);
+        assert_eq!(contracts.check(&deploy, 2), Err("Contract already deployed"));
+        (contracts, contract)
+    }
+
+    fn call(contracts: &mut Contracts, contract: PublicKey, input: &[u8], gas_limit: u64) -> Execution {
+        let call = transaction(&keypair(2), contract, TransactionKind::Call { input: input.to_vec(), gas_limit });
+        let execution = contracts.check(&call, 1).unwrap().unwrap();
+        contracts.commit(&call, Some(&execution));
+        execution
+    }
+
+    #[test]
+    fn deployed_code_is_validated() {
+        let deployer = keypair(1);
+        for (code, error) in [(vec![], "Invalid contract code size"), (vec![0xff], "Invalid contract opcode"), (vec![OP_PUSH, 4, 1], "Truncated contract instruction")] {
+            let deploy = transaction(&deployer, contract_address(&deployer.public, TIMESTAMP, &code), TransactionKind::Deploy { code });
+            assert_eq!(Contracts::default().check(&deploy, 1), Err(error));
+        }
+        let unknown = transaction(&keypair(2), deployer.public, TransactionKind::Call { input: Vec::new(), gas_limit: 1000 });
+        assert_eq!(Contracts::default().check(&unknown, 1), Err("Unknown contract"));
+    }
+
+    #[test]
+    fn calls_update_storage_and_charge_their_gas() {
+        let (mut contracts, contract) = deployed(&COUNTER);
+        for count in 1..=3u64 {
+            let execution = call(&mut contracts, contract, b"", 1000);
+            assert_eq!((execution.output, execution.failure), (count.to_be_bytes().to_vec(), None));
+            // 8 instructions, une lecture et l'écriture de 1 + 8 octets
+            assert_eq!(execution.gas_used, 8 * GAS_STEP + GAS_SLOAD + GAS_SSTORE + 9 * GAS_PER_STORED_BYTE);
+        }
+        assert_eq!(contracts.storage(&contract, b"c"), Some(&3u64.to_be_bytes().to_vec()));
+        let too_much = transaction(&keypair(2), contract, TransactionKind::Call { input: Vec::new(), gas_limit: MAX_CALL_GAS + 1 });
+        assert_eq!(contracts.check(&too_much, 1), Err("Gas limit too high"));
+    }
+
+    #[test]
+    fn exhausted_gas_consumes_the_limit_without_effect() {
+        let (mut contracts, contract) = deployed(&COUNTER);
+        call(&mut contracts, contract, b"", 1000);
+        let execution = call(&mut contracts, contract, b"", 100);
+        assert_eq!((execution.failure, execution.gas_used), (Some("Out of gas"), 100));
+        assert_eq!(contracts.storage(&contract, b"c"), Some(&1u64.to_be_bytes().to_vec()));
+
+        // Boucle sans fin
+        let (mut contracts, contract) = deployed(&[OP_JUMP, 0, 0]);
+        let execution = call(&mut contracts, contract, b"", 5000);
+        assert_eq!((execution.failure, execution.gas_used), (Some("Out of gas"), 5000));
+    }
+
+    #[test]
+    fn failed_calls_revert_their_writes() {
+        // Enregistre l'entrée sous "k", puis échoue si l'entrée vaut 1
+        let code = [OP_INPUT, OP_PUSH, 1, b'k', OP_SSTORE, OP_INPUT, OP_PUSH, 1, 1, OP_EQ, OP_JUMPI, 0, 14, OP_STOP, OP_REVERT];
+        let (mut contracts, contract) = deployed(&code);
+        assert_eq!(call(&mut contracts, contract, &[2], 1000).failure, None);
+        assert_eq!(contracts.storage(&contract, b"k"), Some(&vec![2]));
+
+        let execution = call(&mut contracts, contract, &[1], 1000);
+        assert_eq!((execution.failure, execution.gas_used), (Some("Contract reverted"), 1000));
+        assert_eq!(contracts.storage(&contract, b"k"), Some(&vec![2]));
+
+        // Dépassement de capacité, division par zéro, pile vide
+        for (code, error) in [
+            (vec![OP_PUSH, 8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, OP_PUSH, 1, 1, OP_ADD], "Arithmetic overflow"),
+            (vec![OP_PUSH, 1, 1, OP_PUSH, 1, 0, OP_DIV], "Division by zero"),
+            (vec![OP_POP], "Stack underflow"),
+            (vec![OP_JUMP, 0, 1], "Invalid jump target"),
+        ] {
+            let (mut contracts, contract) = deployed(&code);
+            assert_eq!(call(&mut contracts, contract, b"", 1000).failure, Some(error));
+        }
+    }
+
+    #[test]
+    fn deployed_contracts_round_trip_through_their_encoding() {
+        let (mut contracts, contract) = deployed(&COUNTER);
+        call(&mut contracts, contract, b"", 1000);
+        let mut encoder = Encoder::new(CONTRACT_DOMAIN);
+        contracts.encode_into(&mut encoder);
+        let bytes = encoder.finish();
+
+        let mut decoder = Decoder::new(&bytes, CONTRACT_DOMAIN).unwrap();
+        let mut decoded = Contracts::decode_from(&mut decoder).unwrap();
+        decoder.finish().unwrap();
+        assert_eq!(decoded.storage(&contract, b"c"), contracts.storage(&contract, b"c"));
+        assert_eq!(call(&mut decoded, contract, b"", 1000).output, 2u64.to_be_bytes().to_vec());
+    }
+}
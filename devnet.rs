@@ -0,0 +1,92 @@
+use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+use sha3::{Digest, Sha3_256};
+
+use crate::config::ChainConfig;
+use crate::faucet::FAUCET_ALLOCATION;
+use crate::{init_genesis, Blockchain};
+
+// Réseau de développement, distinct du réseau par défaut : une transaction
+// signée pour l'un n'est pas valide sur l'autre
+pub const DEV_CHAIN_ID: u64 = 1337;
+
+// Comptes dotés à la genèse par défaut, et dotation de chacun
+pub const DEV_ACCOUNTS: usize = 10;
+pub const DEV_ALLOCATION: u64 = 1_000_000_000;
+
+// Intervalle des créneaux : un bloc est produit au plus tard un créneau après
+// l'arrivée d'une transaction, aucun bloc sans transaction
+pub const DEV_SLOT_MILLIS: u64 = 200;
+
+// Clé déterministe d'un rôle du réseau de développement : les mêmes à chaque
+// lancement, donc publiques, à n'utiliser sur aucun autre réseau
+pub fn dev_keypair(name: &str) -> Keypair {
+    let seed = Sha3_256::digest(format!("supplyx devnet {}", name).as_bytes());
+    let secret = SecretKey::from_bytes(&seed).expect("32-byte seed");
+    Keypair { public: PublicKey::from(&secret), secret }
+}
+
+pub fn dev_config() -> ChainConfig {
+    ChainConfig::builder().chain_id(DEV_CHAIN_ID).testnet(true).build().expect("valid devnet configuration")
+}
+
+// Réseau de développement à un seul validateur : chaîne de la configuration
+// `dev_config`, validateur, robinet et `accounts` comptes dotés à la genèse
+pub struct Devnet {
+    pub blockchain: Blockchain,
+    pub validator: Keypair,
+    pub faucet: Keypair,
+    pub accounts: Vec<Keypair>,
+}
+
+impl Devnet {
+    pub fn new(accounts: usize) -> Self {
+        let mut blockchain = Blockchain::new(dev_config());
+        let validator = dev_keypair("validator");
+        init_genesis(&mut blockchain, &validator.public);
+        let faucet = dev_keypair("faucet");
+        blockchain.allocate(&faucet.public, FAUCET_ALLOCATION);
+        let accounts: Vec<Keypair> = (0..accounts).map(|index| dev_keypair(&format!("account {}", index))).collect();
+        for account in &accounts {
+            blockchain.allocate(&account.public, DEV_ALLOCATION);
+        }
+        Devnet { blockchain, validator, faucet, accounts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::txbuilder::TxBuilder;
+
+    #[test]
+    fn genesis_funds_the_same_roles_at_every_launch() {
+        let devnet = Devnet::new(3);
+        let again = Devnet::new(3);
+        assert_eq!(devnet.validator.public, again.validator.public);
+        assert_eq!(devnet.accounts.iter().map(|account| account.public).collect::<Vec<_>>(), again.accounts.iter().map(|account| account.public).collect::<Vec<_>>());
+        let keys: HashSet<[u8; 32]> = [&devnet.validator, &devnet.faucet].into_iter().chain(&devnet.accounts).map(|keypair| keypair.public.to_bytes()).collect();
+        assert_eq!(keys.len(), 5);
+
+        let chain = &devnet.blockchain;
+        assert_eq!((chain.config.chain_id, chain.config.testnet), (DEV_CHAIN_ID, true));
+        assert!(chain.validators.contains_key(&devnet.validator.public));
+        assert_eq!(chain.balance(&devnet.faucet.public), FAUCET_ALLOCATION);
+        assert!(devnet.accounts.iter().all(|account| chain.balance(&account.public) == DEV_ALLOCATION));
+        assert_eq!(Devnet::new(DEV_ACCOUNTS).accounts.len(), DEV_ACCOUNTS);
+    }
+
+    #[test]
+    fn transactions_signed_for_another_network_are_rejected() {
+        let mut devnet = Devnet::new(2);
+        let (alice, bob) = (&devnet.accounts[0], devnet.accounts[1].public);
+        let chain = &mut devnet.blockchain;
+        let transfer = |chain_id| TxBuilder::transfer().to(bob).amount(10).chain_id(chain_id)
+            .nonce(chain.next_nonce(&alice.public)).timestamp(chain.now()).sign(alice).unwrap();
+        let (default, dev) = (transfer(ChainConfig::default().chain_id), transfer(DEV_CHAIN_ID));
+        assert_ne!(ChainConfig::default().chain_id, DEV_CHAIN_ID);
+        assert_eq!(chain.add_transaction(default), Err("Transaction signed for another chain"));
+        assert_eq!(chain.add_transaction(dev), Ok(()));
+    }
+}
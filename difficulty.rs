@@ -0,0 +1,62 @@
+// Difficulté de production : un créneau sur `difficulty` en moyenne a un leader
+// (tirage avec la graine du leader, voir `select_leader`). Elle est réajustée à
+// chaque fin d'époque pour rapprocher l'intervalle moyen entre blocs de
+// l'intervalle cible ; 1 donne un leader à chaque créneau.
+
+// Variation maximale de la difficulté à chaque réajustement
+const MAX_RETARGET_FACTOR: u64 = 4;
+
+// Nouvelle difficulté après une époque dont les `intervals` intervalles entre
+// blocs ont duré `span` secondes au total, pour `target_interval` secondes par bloc
+pub fn retarget(difficulty: u64, span: u64, intervals: u64, target_interval: u64) -> u64 {
+    let expected = intervals as u128 * target_interval as u128;
+    if expected == 0 {
+        return difficulty;
+    }
+    let observed = (span as u128).clamp((expected / MAX_RETARGET_FACTOR as u128).max(1), expected * MAX_RETARGET_FACTOR as u128);
+    let retargeted = difficulty as u128 * expected / observed;
+    retargeted.clamp(1, u64::MAX as u128) as u64
+}
+
+// Le créneau a-t-il un leader, d'après la graine du tirage du leader
+pub fn is_production_slot(seed: &[u8], difficulty: u64) -> bool {
+    let draw = u64::from_be_bytes(seed[8..16].try_into().unwrap());
+    draw % difficulty.max(1) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retarget_follows_the_observed_interval_within_the_factor() {
+        // 99 intervalles de 5 secondes attendus
+        assert_eq!(retarget(8, 495, 99, 5), 8);
+        assert_eq!(retarget(8, 990, 99, 5), 4);
+        assert_eq!(retarget(8, 248, 99, 5), 15);
+
+        // Une époque beaucoup trop lente ou trop rapide ne varie que d'un facteur 4
+        assert_eq!(retarget(8, 1_000_000, 99, 5), 2);
+        assert_eq!(retarget(8, 0, 99, 5), 32);
+        assert_eq!(retarget(1, 1_000_000, 99, 5), 1);
+        assert_eq!(retarget(u64::MAX, 0, 99, 5), u64::MAX);
+    }
+
+    #[test]
+    fn retarget_keeps_the_difficulty_without_an_expected_span() {
+        assert_eq!(retarget(8, 500, 0, 5), 8);
+        assert_eq!(retarget(8, 500, 99, 0), 8);
+    }
+
+    #[test]
+    fn one_slot_in_difficulty_has_a_leader() {
+        let seeds: Vec<[u8; 32]> = (0..4000u32).map(|i| {
+            let mut seed = [0; 32];
+            seed[8..16].copy_from_slice(&(i as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15).to_be_bytes());
+            seed
+        }).collect();
+        assert!(seeds.iter().all(|seed| is_production_slot(seed, 1) && is_production_slot(seed, 0)));
+        let produced = seeds.iter().filter(|seed| is_production_slot(&seed[..], 4)).count();
+        assert!((800..1200).contains(&produced), "{}", produced);
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use crate::config::ChainConfig;
+use crate::receipts::Receipt;
+use crate::snapshot::StateSnapshot;
+use crate::{Block, BlockHeader, Blockchain};
+
+// Écart entre la chaîne locale et sa réexécution sous les règles candidates
+#[derive(Clone, Debug)]
+pub enum Divergence {
+    // Bloc accepté par la chaîne mais refusé par les règles candidates ; la réexécution s'arrête là
+    Rejected { height: u64, reason: &'static str },
+    // État final (hors paramètres de consensus) différent de celui du nœud
+    StateRoot { height: u64, expected: Vec<u8>, actual: Vec<u8> },
+    // Statut, gaz ou frais différents pour une transaction incluse
+    Receipt { height: u64, transaction_hash: Vec<u8>, expected: Option<Receipt>, actual: Option<Receipt> },
+}
+
+impl Divergence {
+    pub fn height(&self) -> u64 {
+        match self {
+            Divergence::Rejected { height, .. } | Divergence::StateRoot { height, .. } | Divergence::Receipt { height, .. } => *height,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DryRunReport {
+    // Hauteur de l'instantané de départ
+    pub from_height: u64,
+    // Blocs réexécutés sans rejet
+    pub replayed: u64,
+    pub divergences: Vec<Divergence>,
+}
+
+// Blocs, reçus et état final de la chaîne locale depuis son dernier instantané,
+// copiés pour être réexécutés hors du verrou de la chaîne
+pub struct DryRun {
+    snapshot: StateSnapshot,
+    header: BlockHeader,
+    blocks: Vec<Block>,
+    receipts: HashMap<Vec<u8>, Receipt>,
+    state: StateSnapshot,
+}
+
+impl DryRun {
+    pub fn capture(blockchain: &Blockchain) -> Result<Self, &'static str> {
+        let snapshot = blockchain.latest_snapshot.clone().ok_or("No state snapshot to replay from")?;
+        let header = match blockchain.block_at(snapshot.height) {
+            Some(block) => block.header(),
+            None => blockchain.base.clone()
+                .filter(|base| base.index == snapshot.height)
+                .ok_or("Snapshot block not available")?,
+        };
+        let blocks: Vec<Block> = blockchain.chain.iter().filter(|block| block.index > snapshot.height).cloned().collect();
+        let receipts = blocks.iter()
+            .flat_map(|block| &block.transactions)
+            .filter_map(|tx| blockchain.receipts.get(&tx.hash()).map(|receipt| (tx.hash(), receipt.clone())))
+            .collect();
+        Ok(DryRun { snapshot, header, blocks, receipts, state: blockchain.snapshot() })
+    }
+
+    // Réexécute les blocs capturés à partir de l'instantané, les paramètres de
+    // consensus de `candidate` remplaçant ceux en vigueur après le premier bloc :
+    // celui-ci engage le hash de l'instantané, paramètres compris, et est rejoué
+    // sous les règles d'origine
+    pub fn run(self, candidate: &ChainConfig) -> Result<DryRunReport, &'static str> {
+        candidate.validate()?;
+        let from_height = self.snapshot.height;
+        let mut replay = Blockchain::new(candidate.clone());
+        replay.restore_snapshot(self.snapshot, self.header)?;
+
+        let mut report = DryRunReport { from_height, replayed: 0, divergences: Vec::new() };
+        let mut last_height = from_height;
+        for block in self.blocks {
+            let height = block.index;
+            let hashes: Vec<Vec<u8>> = block.transactions.iter().map(|tx| tx.hash()).collect();
+            if let Err(reason) = replay.add_block(block) {
+                report.divergences.push(Divergence::Rejected { height, reason });
+                break;
+            }
+            if report.replayed == 0 {
+                replay.governance.params = candidate.consensus.clone();
+            }
+            report.replayed += 1;
+            last_height = height;
+            for hash in hashes {
+                let expected = self.receipts.get(&hash);
+                let actual = replay.receipts.get(&hash);
+                if !same_outcome(expected, actual) {
+                    report.divergences.push(Divergence::Receipt {
+                        height,
+                        transaction_hash: hash,
+                        expected: expected.cloned(),
+                        actual: actual.cloned(),
+                    });
+                }
+            }
+        }
+
+        if !report.divergences.iter().any(|divergence| matches!(divergence, Divergence::Rejected { .. })) {
+            // Les paramètres diffèrent par construction : seul le reste de l'état est comparé
+            let mut actual = replay.snapshot();
+            actual.governance.params = self.state.governance.params.clone();
+            let (expected, actual) = (self.state.hash(), actual.hash());
+            if expected != actual {
+                report.divergences.push(Divergence::StateRoot { height: last_height, expected, actual });
+            }
+        }
+        Ok(report)
+    }
+}
+
+fn same_outcome(expected: Option<&Receipt>, actual: Option<&Receipt>) -> bool {
+    match (expected, actual) {
+        (Some(expected), Some(actual)) => {
+            expected.status == actual.status && expected.gas_used == actual.gas_used && expected.fee == actual.fee
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::gas::intrinsic_gas;
+    use crate::txbuilder::TxBuilder;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    fn transfer(chain: &Blockchain, sender: &Keypair, recipient: &PublicKey) -> crate::Transaction {
+        TxBuilder::transfer().to(*recipient).amount(10).chain_id(chain.config.chain_id)
+            .nonce(chain.next_nonce(&sender.public)).timestamp(chain.now()).sign(sender).unwrap()
+    }
+
+    // Deux blocs avant l'instantané, trois blocs de transferts après, et une
+    // transaction en attente
+    fn replayable_chain() -> (Blockchain, u64) {
+        let (validator, alice, bob) = (keypair(1), keypair(2), keypair(3));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain.allocate(&alice.public, 1_000_000);
+        let mut transfer_gas = 0;
+        for slot in 1..=5 {
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            if slot > 2 {
+                let transaction = transfer(&chain, &alice, &bob.public);
+                transfer_gas = intrinsic_gas(&transaction);
+                chain.add_transaction(transaction).unwrap();
+            }
+            chain.validate_and_create_block(&validator, slot).unwrap();
+            // Instantané pris comme à une hauteur d'instantané, sans produire SNAPSHOT_INTERVAL blocs
+            if slot == 2 {
+                chain.latest_snapshot = Some(chain.snapshot());
+            }
+        }
+        let pending = transfer(&chain, &alice, &bob.public);
+        chain.add_transaction(pending).unwrap();
+        (chain, transfer_gas)
+    }
+
+    #[test]
+    fn replay_reports_the_first_block_rejected_by_candidate_rules() {
+        let (chain, transfer_gas) = replayable_chain();
+
+        let report = DryRun::capture(&chain).unwrap().run(&chain.config).unwrap();
+        assert_eq!((report.from_height, report.replayed), (1, 3));
+        assert!(report.divergences.is_empty());
+
+        // Le premier bloc rejoué garde les règles d'origine, le suivant dépasse la limite candidate
+        let candidate = ChainConfig::builder().max_block_gas(transfer_gas - 1).build().unwrap();
+        let report = DryRun::capture(&chain).unwrap().run(&candidate).unwrap();
+        assert_eq!(report.replayed, 1);
+        assert!(matches!(report.divergences[..], [Divergence::Rejected { height: 3, reason: "Block gas limit exceeded" }]));
+
+        let mut invalid = ChainConfig::default();
+        invalid.consensus.upgrade_epochs = 0;
+        assert_eq!(DryRun::capture(&chain).unwrap().run(&invalid).err(), Some("Upgrade epochs must be positive"));
+    }
+
+    #[test]
+    fn dry_run_leaves_the_chain_and_mempool_untouched() {
+        let (chain, transfer_gas) = replayable_chain();
+        let pending: Vec<Vec<u8>> = chain.pending_transactions.iter().map(|tx| tx.hash()).collect();
+        let state = chain.snapshot().hash();
+        let (height, receipts) = (chain.next_index(), chain.receipts.len());
+
+        for candidate in [chain.config.clone(), ChainConfig::builder().max_block_gas(transfer_gas - 1).build().unwrap()] {
+            DryRun::capture(&chain).unwrap().run(&candidate).unwrap();
+        }
+        assert_eq!(chain.snapshot().hash(), state);
+        assert_eq!(chain.pending_transactions.iter().map(|tx| tx.hash()).collect::<Vec<_>>(), pending);
+        assert_eq!((chain.next_index(), chain.receipts.len(), pending.len()), (height, receipts, 1));
+        assert_eq!(chain.governance.params, ChainConfig::default().consensus);
+    }
+}
@@ -0,0 +1,432 @@
+use supplyx_core::encoding::encode_body;
+
+use crate::checkpoint::Checkpoint;
+use crate::governance::GovernanceTransaction;
+use crate::privacy::PrivateTransaction;
+use crate::multisig::MultisigPolicy;
+use crate::{Authorization, Block, BlockHeader, Expiry, Transaction, TransactionKind};
+
+// Encodage binaire canonique utilisé pour tous les hachages et signatures
+// (`Encoder`, `Decoder`, partagés avec les clients embarqués par supplyx-core).
+// Chaque message commence par une étiquette de domaine (transaction, bloc...)
+// afin qu'une signature produite pour un type ne soit jamais valide pour un autre,
+// et chaque champ de taille variable est préfixé par sa longueur.
+pub use supplyx_core::encoding::{Decoder, Encoder, BLOCK_BODY_DOMAIN, VERSIONED_BLOCK_BODY_DOMAIN};
+pub const BLOCK_DOMAIN: &[u8] = b"SUPPLYX_BLOCK_V1";
+pub const LEADER_DOMAIN: &[u8] = b"SUPPLYX_LEADER_V1";
+pub const BLOCK_DATA_DOMAIN: &[u8] = b"SUPPLYX_BLOCK_DATA_V1";
+pub const SNAPSHOT_DOMAIN: &[u8] = b"SUPPLYX_SNAPSHOT_V1";
+pub const CHECKPOINT_DOMAIN: &[u8] = b"SUPPLYX_CHECKPOINT_V1";
+pub const CHECKPOINT_VOTE_DOMAIN: &[u8] = b"SUPPLYX_CHECKPOINT_VOTE_V1";
+pub const PRIVACY_GROUP_DOMAIN: &[u8] = b"SUPPLYX_PRIVACY_GROUP_V1";
+pub const PRIVATE_TRANSACTION_DOMAIN: &[u8] = b"SUPPLYX_PRIVATE_TRANSACTION_V1";
+pub const PRIVATE_PAYLOAD_DOMAIN: &[u8] = b"SUPPLYX_PRIVATE_PAYLOAD_V1";
+pub const PRIVATE_PAYLOAD_MAC_DOMAIN: &[u8] = b"SUPPLYX_PRIVATE_PAYLOAD_MAC_V1";
+pub const ENCRYPTED_PAYLOAD_DOMAIN: &[u8] = b"SUPPLYX_ENCRYPTED_PAYLOAD_V1";
+pub const PRIVATE_KEY_WRAP_DOMAIN: &[u8] = b"SUPPLYX_PRIVATE_KEY_WRAP_V1";
+pub const PRIVATE_STATE_DOMAIN: &[u8] = b"SUPPLYX_PRIVATE_STATE_V1";
+pub const PRIVATE_PROPOSAL_DOMAIN: &[u8] = b"SUPPLYX_PRIVATE_PROPOSAL_V1";
+pub const ADDRESS_DOMAIN: &[u8] = b"SUPPLYX_ADDRESS_V1";
+pub const SYSTEM_ADDRESS_DOMAIN: &[u8] = b"SUPPLYX_SYSTEM_ADDRESS_V1";
+pub const MULTISIG_DOMAIN: &[u8] = b"SUPPLYX_MULTISIG_V1";
+pub const GOSSIP_DOMAIN: &[u8] = b"SUPPLYX_GOSSIP_V1";
+pub const PROPOSAL_DOMAIN: &[u8] = b"SUPPLYX_PROPOSAL_V1";
+pub const VOTE_DOMAIN: &[u8] = b"SUPPLYX_VOTE_V1";
+pub const GOVERNANCE_TRANSACTION_DOMAIN: &[u8] = b"SUPPLYX_GOVERNANCE_TRANSACTION_V1";
+pub const FREEZE_DOMAIN: &[u8] = b"SUPPLYX_FREEZE_V1";
+pub const CONTRACT_DOMAIN: &[u8] = b"SUPPLYX_CONTRACT_V1";
+pub const BEACON_DOMAIN: &[u8] = b"SUPPLYX_BEACON_V1";
+pub const INCIDENT_DOMAIN: &[u8] = b"SUPPLYX_INCIDENT_V1";
+pub const LIVENESS_DOMAIN: &[u8] = b"SUPPLYX_LIVENESS_V1";
+pub const RULES_FINGERPRINT_DOMAIN: &[u8] = b"SUPPLYX_RULES_FINGERPRINT_V1";
+pub const PROOF_BUNDLE_DOMAIN: &[u8] = b"SUPPLYX_PROOF_BUNDLE_V1";
+pub const SYNC_DOMAIN: &[u8] = b"SUPPLYX_SYNC_V1";
+pub const REMOTE_SIGNER_DOMAIN: &[u8] = b"SUPPLYX_REMOTE_SIGNER_V1";
+pub const KEY_ROTATION_DOMAIN: &[u8] = b"SUPPLYX_KEY_ROTATION_V1";
+pub const STATE_DIFF_DOMAIN: &[u8] = b"SUPPLYX_STATE_DIFF_V1";
+pub const PAIRED_SYNC_DOMAIN: &[u8] = b"SUPPLYX_PAIRED_SYNC_V1";
+pub const STATE_TREE_DOMAIN: &[u8] = b"SUPPLYX_STATE_TREE_V1";
+pub const CONFIDENTIAL_GENERATOR_DOMAIN: &[u8] = b"SUPPLYX_CONFIDENTIAL_GENERATOR_V1";
+pub const CONFIDENTIAL_POOL_DOMAIN: &[u8] = b"SUPPLYX_CONFIDENTIAL_POOL_V1";
+pub const CONFIDENTIAL_TRANSFER_DOMAIN: &[u8] = b"SUPPLYX_CONFIDENTIAL_TRANSFER_V1";
+pub const RANGE_PROOF_DOMAIN: &[u8] = b"SUPPLYX_RANGE_PROOF_V1";
+pub const TRANSACTION_BUNDLE_DOMAIN: &[u8] = b"SUPPLYX_TRANSACTION_BUNDLE_V1";
+pub const REWARDS_DOMAIN: &[u8] = b"SUPPLYX_REWARDS_V1";
+pub const REWARDS_POOL_DOMAIN: &[u8] = b"SUPPLYX_REWARDS_POOL_V1";
+pub const COMMUNITY_POOL_DOMAIN: &[u8] = b"SUPPLYX_COMMUNITY_POOL_V1";
+pub const BACKUP_DOMAIN: &[u8] = b"SUPPLYX_BACKUP_V1";
+pub const EXTERNAL_ANCHOR_DOMAIN: &[u8] = b"SUPPLYX_EXT_ANCHOR_V1";
+
+// Corps de bloc compact : chaque clé publique n'apparaît qu'une fois dans un
+// dictionnaire (ordre de première apparition), les transactions y font
+// référence par index. Le hash du bloc ne dépend que des hashes de transactions,
+// il est donc identique quel que soit l'encodage du corps. Tant que toutes les
+// transactions sont au format 1, le corps garde l'encodage d'origine ; sinon
+// chaque transaction y est précédée de la version de son format.
+pub fn encode_block_body(transactions: &[Transaction]) -> Vec<u8> {
+    let versioned = transactions.iter().any(|tx| tx.version != 1);
+    let domain = if versioned { VERSIONED_BLOCK_BODY_DOMAIN } else { BLOCK_BODY_DOMAIN };
+    encode_body(domain, transactions, |tx| [&tx.sender, &tx.recipient], |tx, encoder, index_of| {
+        if versioned {
+            encoder.put_varint(tx.version as u64);
+        }
+        encoder.put_varint(tx.chain_id);
+        encoder.put_varint(index_of(&tx.sender));
+        encoder.put_varint(tx.nonce);
+        encoder.put_varint(index_of(&tx.recipient));
+        encoder.put_u64(tx.amount);
+        tx.kind.encode_into(encoder);
+        encoder.put_u64(tx.gas_price);
+        encoder.put_u64(tx.timestamp);
+        encoder.put_varint(tx.valid_from);
+        tx.valid_until.encode_into(encoder);
+        match &tx.authorization {
+            Authorization::Signature(signature) => {
+                encoder.put_u8(0);
+                encoder.put_signature(signature);
+            }
+            Authorization::Multisig { policy, signatures } => {
+                encoder.put_u8(1);
+                policy.encode_into(encoder);
+                encoder.put_varint(signatures.len() as u64);
+                for (index, signature) in signatures {
+                    encoder.put_u8(*index);
+                    encoder.put_signature(signature);
+                }
+            }
+        }
+    })
+}
+
+pub fn decode_block_body(data: &[u8]) -> Result<Vec<Transaction>, &'static str> {
+    decode_transactions(data, true)
+}
+
+// Transactions d'un lot atomique, encodées comme un corps de bloc
+pub fn decode_bundle_body(data: &[u8]) -> Result<Vec<Transaction>, &'static str> {
+    decode_transactions(data, false)
+}
+
+fn decode_transactions(data: &[u8], bundles: bool) -> Result<Vec<Transaction>, &'static str> {
+    let (mut decoder, format) = Decoder::versioned(data, &[BLOCK_BODY_DOMAIN, VERSIONED_BLOCK_BODY_DOMAIN])?;
+    let versioned = format == 1;
+
+    let key_count = decoder.get_varint()? as usize;
+    let mut dictionary = Vec::new();
+    for _ in 0..key_count {
+        dictionary.push(decoder.get_public_key()?);
+    }
+
+    let transaction_count = decoder.get_varint()?;
+    let mut transactions = Vec::new();
+    for _ in 0..transaction_count {
+        let version = if versioned { decoder.get_varint()? } else { 1 };
+        if version == 0 || version > u32::MAX as u64 {
+            return Err("Invalid transaction version");
+        }
+        let chain_id = decoder.get_varint()?;
+        let sender = *dictionary.get(decoder.get_varint()? as usize).ok_or("Unknown key index")?;
+        let nonce = decoder.get_varint()?;
+        let recipient = *dictionary.get(decoder.get_varint()? as usize).ok_or("Unknown key index")?;
+        transactions.push(Transaction {
+            version: version as u32,
+            chain_id,
+            sender,
+            nonce,
+            recipient,
+            amount: decoder.get_u64()?,
+            kind: TransactionKind::decode_from(&mut decoder, bundles)?,
+            gas_price: decoder.get_u64()?,
+            timestamp: decoder.get_u64()?,
+            valid_from: decoder.get_varint()?,
+            valid_until: Expiry::decode_from(&mut decoder)?,
+            authorization: decode_authorization(&mut decoder)?,
+        });
+    }
+    decoder.finish()?;
+    if versioned && transactions.iter().all(|tx| tx.version == 1) {
+        return Err("Non-canonical block body");
+    }
+    Ok(transactions)
+}
+
+fn decode_authorization(decoder: &mut Decoder) -> Result<Authorization, &'static str> {
+    match decoder.get_u8()? {
+        0 => Ok(Authorization::Signature(decoder.get_signature()?)),
+        1 => {
+            let policy = MultisigPolicy::decode_from(decoder)?;
+            let count = decoder.get_varint()?;
+            let mut signatures = Vec::new();
+            for _ in 0..count {
+                signatures.push((decoder.get_u8()?, decoder.get_signature()?));
+            }
+            Ok(Authorization::Multisig { policy, signatures })
+        }
+        _ => Err("Unknown transaction authorization"),
+    }
+}
+
+// En-tête seul, dans l'ordre des champs du bloc complet
+pub fn encode_header_into(header: &BlockHeader, encoder: &mut Encoder) {
+    encoder.put_u64(header.chain_id);
+    encoder.put_u64(header.index);
+    encoder.put_u64(header.slot);
+    encoder.put_u64(header.timestamp);
+    encoder.put_u64(header.difficulty);
+    encoder.put_u32(header.protocol_version);
+    encoder.put_bytes(&header.rules_fingerprint);
+    encoder.put_bytes(&header.previous_hash);
+    encoder.put_bytes(&header.transactions_root);
+    encoder.put_bytes(&header.state_root);
+    encoder.put_bytes(&header.snapshot_hash);
+    match &header.checkpoint {
+        Some(checkpoint) => {
+            encoder.put_u8(1);
+            checkpoint.encode_into(encoder);
+        }
+        None => encoder.put_u8(0),
+    }
+    encoder.put_bytes(&header.current_hash);
+    encoder.put_signature(&header.validator_signature);
+    encoder.put_public_key(&header.validator_pubkey);
+}
+
+pub fn decode_header_from(decoder: &mut Decoder) -> Result<BlockHeader, &'static str> {
+    Ok(BlockHeader {
+        chain_id: decoder.get_u64()?,
+        index: decoder.get_u64()?,
+        slot: decoder.get_u64()?,
+        timestamp: decoder.get_u64()?,
+        difficulty: decoder.get_u64()?,
+        protocol_version: decoder.get_u32()?,
+        rules_fingerprint: decoder.get_bytes()?.to_vec(),
+        previous_hash: decoder.get_bytes()?.to_vec(),
+        transactions_root: decoder.get_bytes()?.to_vec(),
+        state_root: decoder.get_bytes()?.to_vec(),
+        snapshot_hash: decoder.get_bytes()?.to_vec(),
+        checkpoint: match decoder.get_u8()? {
+            0 => None,
+            1 => Some(Checkpoint::decode_from(decoder)?),
+            _ => return Err("Invalid checkpoint flag"),
+        },
+        current_hash: decoder.get_bytes()?.to_vec(),
+        validator_signature: decoder.get_signature()?,
+        validator_pubkey: decoder.get_public_key()?,
+    })
+}
+
+// Bloc complet (en-tête et corps compact), pour la réplication et le stockage
+pub fn encode_block(block: &Block) -> Vec<u8> {
+    let mut encoder = Encoder::new(BLOCK_DATA_DOMAIN);
+    encoder.put_u64(block.chain_id);
+    encoder.put_u64(block.index);
+    encoder.put_u64(block.slot);
+    encoder.put_u64(block.timestamp);
+    encoder.put_u64(block.difficulty);
+    encoder.put_u32(block.protocol_version);
+    encoder.put_bytes(&block.rules_fingerprint);
+    encoder.put_bytes(&block.previous_hash);
+    encoder.put_bytes(&block.transactions_root);
+    encoder.put_bytes(&block.state_root);
+    encoder.put_bytes(&block.snapshot_hash);
+    match &block.checkpoint {
+        Some(checkpoint) => {
+            encoder.put_u8(1);
+            checkpoint.encode_into(&mut encoder);
+        }
+        None => encoder.put_u8(0),
+    }
+    encoder.put_bytes(&block.current_hash);
+    encoder.put_signature(&block.validator_signature);
+    encoder.put_public_key(&block.validator_pubkey);
+    encoder.put_bytes(&encode_block_body(&block.transactions));
+    encoder.put_u32(block.private_transactions.len() as u32);
+    for private_transaction in &block.private_transactions {
+        private_transaction.encode_into(&mut encoder);
+    }
+    encoder.put_u32(block.governance_transactions.len() as u32);
+    for governance_transaction in &block.governance_transactions {
+        governance_transaction.encode_into(&mut encoder);
+    }
+    encoder.finish()
+}
+
+pub fn decode_block(data: &[u8]) -> Result<Block, &'static str> {
+    let mut decoder = Decoder::new(data, BLOCK_DATA_DOMAIN)?;
+    let block = Block {
+        chain_id: decoder.get_u64()?,
+        index: decoder.get_u64()?,
+        slot: decoder.get_u64()?,
+        timestamp: decoder.get_u64()?,
+        difficulty: decoder.get_u64()?,
+        protocol_version: decoder.get_u32()?,
+        rules_fingerprint: decoder.get_bytes()?.to_vec(),
+        previous_hash: decoder.get_bytes()?.to_vec(),
+        transactions_root: decoder.get_bytes()?.to_vec(),
+        state_root: decoder.get_bytes()?.to_vec(),
+        snapshot_hash: decoder.get_bytes()?.to_vec(),
+        checkpoint: match decoder.get_u8()? {
+            0 => None,
+            1 => Some(Checkpoint::decode_from(&mut decoder)?),
+            _ => return Err("Invalid checkpoint flag"),
+        },
+        current_hash: decoder.get_bytes()?.to_vec(),
+        validator_signature: decoder.get_signature()?,
+        validator_pubkey: decoder.get_public_key()?,
+        transactions: decode_block_body(decoder.get_bytes()?)?,
+        private_transactions: {
+            let count = decoder.get_u32()?;
+            let mut private_transactions = Vec::new();
+            for _ in 0..count {
+                private_transactions.push(PrivateTransaction::decode_from(&mut decoder)?);
+            }
+            private_transactions
+        },
+        governance_transactions: {
+            let count = decoder.get_u32()?;
+            let mut governance_transactions = Vec::new();
+            for _ in 0..count {
+                governance_transactions.push(GovernanceTransaction::decode_from(&mut decoder)?);
+            }
+            governance_transactions
+        },
+    };
+    decoder.finish()?;
+    Ok(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+
+    use super::{decode_block, decode_block_body, encode_block, encode_block_body, BLOCK_BODY_DOMAIN, VERSIONED_BLOCK_BODY_DOMAIN};
+    use crate::{Authorization, Block, Expiry, Transaction, TransactionKind};
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    // Transferts entre trois comptes : le dictionnaire a trois clés
+    fn transactions() -> Vec<Transaction> {
+        let accounts: Vec<Keypair> = (1..=3).map(keypair).collect();
+        (0..6).map(|nonce| {
+            let (sender, recipient) = (&accounts[nonce % 3], &accounts[(nonce + 1) % 3]);
+            Transaction::signed(1, sender, nonce as u64, &recipient.public, 300 + nonce as u64, TransactionKind::Transfer, 1, 1_700_000_000, Expiry::Never)
+        }).collect()
+    }
+
+    fn versioned_transactions() -> Vec<Transaction> {
+        let mut transactions = transactions();
+        let sender = keypair(1);
+        transactions[0].version = 2;
+        transactions[0].authorization = Authorization::Signature(sender.sign(&transactions[0].hash()));
+        transactions
+    }
+
+    // Position du premier octet après le domaine de `body`
+    fn after_domain(body: &[u8]) -> usize {
+        4 + u32::from_be_bytes(body[..4].try_into().unwrap()) as usize
+    }
+
+    // Position de la première transaction d'un corps de moins de 128 clés et transactions
+    fn first_transaction(body: &[u8]) -> usize {
+        let start = after_domain(body);
+        start + 1 + 32 * body[start] as usize + 1
+    }
+
+    #[test]
+    fn block_bodies_round_trip() {
+        let transactions = transactions();
+        let body = encode_block_body(&transactions);
+        assert_eq!(&body[4..after_domain(&body)], BLOCK_BODY_DOMAIN);
+        assert_eq!(body[after_domain(&body)], 3);
+        // Chaque clé n'apparaît qu'une fois, dans le dictionnaire
+        let key = keypair(1).public.to_bytes();
+        assert_eq!(body.windows(32).filter(|window| *window == key).count(), 1);
+        assert_eq!(decode_block_body(&body), Ok(transactions));
+        assert_eq!(decode_block_body(&encode_block_body(&[])), Ok(Vec::new()));
+
+        // Une transaction au format 2 fait passer tout le corps au format versionné
+        let transactions = versioned_transactions();
+        let body = encode_block_body(&transactions);
+        assert_eq!(&body[4..after_domain(&body)], VERSIONED_BLOCK_BODY_DOMAIN);
+        assert_eq!(decode_block_body(&body), Ok(transactions));
+    }
+
+    #[test]
+    fn blocks_round_trip() {
+        let validator = keypair(9);
+        let current_hash = vec![5; 32];
+        let block = Block {
+            chain_id: 1,
+            index: 3,
+            slot: 4,
+            timestamp: 1_700_000_020,
+            difficulty: 2,
+            protocol_version: 1,
+            rules_fingerprint: vec![1; 32],
+            transactions: versioned_transactions(),
+            private_transactions: Vec::new(),
+            governance_transactions: Vec::new(),
+            previous_hash: vec![2; 32],
+            transactions_root: vec![3; 32],
+            state_root: vec![4; 32],
+            snapshot_hash: Vec::new(),
+            checkpoint: None,
+            validator_signature: validator.sign(&current_hash),
+            current_hash,
+            validator_pubkey: validator.public,
+        };
+        let encoded = encode_block(&block);
+        let decoded = decode_block(&encoded).unwrap();
+        assert_eq!(decoded.transactions, block.transactions);
+        assert_eq!(encode_block(&decoded), encoded);
+
+        let mut trailing = encoded.clone();
+        trailing.push(0);
+        assert_eq!(decode_block(&trailing).err(), Some("Trailing bytes in encoding"));
+        assert_eq!(decode_block(&encoded[..encoded.len() - 1]).err(), Some("Truncated encoding"));
+    }
+
+    #[test]
+    fn rejects_malformed_block_bodies() {
+        let body = encode_block_body(&transactions());
+        let first = first_transaction(&body);
+
+        // Index de clé hors du dictionnaire (émetteur, après l'identifiant de chaîne)
+        let mut index = body.clone();
+        index[first + 1] = 3;
+        assert_eq!(decode_block_body(&index), Err("Unknown key index"));
+
+        let mut trailing = body.clone();
+        trailing.push(0);
+        assert_eq!(decode_block_body(&trailing), Err("Trailing bytes in encoding"));
+        assert_eq!(decode_block_body(&body[..body.len() - 1]), Err("Truncated encoding"));
+
+        // Nombre de transactions encodé sur deux octets au lieu d'un
+        let mut padded = body.clone();
+        padded[first - 1] |= 0x80;
+        padded.insert(first, 0);
+        assert_eq!(decode_block_body(&padded), Err("Non-canonical varint"));
+
+        // Varint au-delà de 64 bits
+        let mut overflow = body[..after_domain(&body)].to_vec();
+        overflow.extend_from_slice(&[0xff; 9]);
+        overflow.push(0x02);
+        assert_eq!(decode_block_body(&overflow), Err("Invalid varint"));
+
+        // Corps versionné dont toutes les transactions sont au format 1
+        let mut versioned = versioned_transactions();
+        let body = encode_block_body(&versioned);
+        let mut downgraded = body.clone();
+        downgraded[first_transaction(&body)] = 1;
+        assert_eq!(decode_block_body(&downgraded), Err("Non-canonical block body"));
+        versioned[0].version = 0;
+        assert_eq!(decode_block_body(&encode_block_body(&versioned)), Err("Invalid transaction version"));
+
+        let mut domain = encode_block_body(&transactions());
+        domain[4] ^= 1;
+        assert_eq!(decode_block_body(&domain), Err("Unexpected encoding domain"));
+    }
+}
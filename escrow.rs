@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use ed25519_dalek::PublicKey;
+
+use crate::address::derive_system_address;
+use crate::encoding::{Decoder, Encoder};
+use crate::{Transaction, TransactionKind};
+
+// Fonds bloqués par une transaction `Escrow`, détenus à une adresse système
+// jusqu'à leur réclamation par le bénéficiaire ou leur remboursement
+#[derive(Clone, Debug, PartialEq)]
+pub struct Escrow {
+    pub depositor: PublicKey,
+    pub beneficiary: PublicKey,
+    pub amount: u64,
+    // Hauteur à partir de laquelle le bénéficiaire peut réclamer seul
+    pub unlock_height: u64,
+    // Clé pouvant libérer les fonds avant terme ou les rembourser
+    pub arbiter: Option<PublicKey>,
+}
+
+impl Escrow {
+    pub fn address(&self, id: &[u8]) -> PublicKey {
+        escrow_address(&self.depositor, id)
+    }
+}
+
+// Adresse d'un séquestre, dérivée du déposant et de l'identifiant (hash de la
+// transaction de dépôt)
+pub fn escrow_address(depositor: &PublicKey, id: &[u8]) -> PublicKey {
+    derive_system_address(depositor, 0, id)
+}
+
+// Séquestres ouverts, par identifiant
+#[derive(Clone, Debug, Default)]
+pub struct Escrows {
+    open: HashMap<Vec<u8>, Escrow>,
+}
+
+impl Escrows {
+    pub fn get(&self, id: &[u8]) -> Option<&Escrow> {
+        self.open.get(id)
+    }
+
+    pub fn is_open(&self, id: &[u8]) -> bool {
+        self.open.contains_key(id)
+    }
+
+    // Règles d'ouverture et de règlement pour une transaction incluse au bloc `height` :
+    // - `Claim` verse les fonds au bénéficiaire, signé par l'arbitre ou, une fois
+    //   `unlock_height` atteinte, par le bénéficiaire
+    // - `Refund` rend les fonds au déposant, signé par l'arbitre ou par le bénéficiaire
+    pub fn check(&self, transaction: &Transaction, height: u64) -> Result<(), &'static str> {
+        match &transaction.kind {
+            TransactionKind::Transfer
+            | TransactionKind::Deploy { .. }
+            | TransactionKind::Call { .. }
+            | TransactionKind::SetRecovery { .. }
+            | TransactionKind::Recover { .. }
+            | TransactionKind::VetoRecovery
+            | TransactionKind::Shield
+            | TransactionKind::ConfidentialTransfer(_)
+            | TransactionKind::Unshield { .. }
+            | TransactionKind::Anchor { .. }
+            | TransactionKind::RegisterAsset { .. }
+            | TransactionKind::GrantAccess { .. }
+            | TransactionKind::RevokeAccess { .. }
+            | TransactionKind::Attest { .. }
+            | TransactionKind::Bundle(_) => Ok(()),
+            TransactionKind::Escrow { .. } => {
+                if self.open.contains_key(&transaction.hash()) {
+                    return Err("Duplicate escrow");
+                }
+                Ok(())
+            }
+            TransactionKind::Claim { escrow } => {
+                let escrow = self.open.get(escrow).ok_or("Unknown or settled escrow")?;
+                if transaction.recipient != escrow.beneficiary || transaction.amount != escrow.amount {
+                    return Err("Claim does not match escrow");
+                }
+                let by_arbiter = escrow.arbiter == Some(transaction.sender);
+                let unlocked = transaction.sender == escrow.beneficiary && height >= escrow.unlock_height;
+                if !by_arbiter && !unlocked {
+                    return Err("Escrow still locked");
+                }
+                Ok(())
+            }
+            TransactionKind::Refund { escrow } => {
+                let escrow = self.open.get(escrow).ok_or("Unknown or settled escrow")?;
+                if transaction.recipient != escrow.depositor || transaction.amount != escrow.amount {
+                    return Err("Refund does not match escrow");
+                }
+                if escrow.arbiter != Some(transaction.sender) && transaction.sender != escrow.beneficiary {
+                    return Err("Refund not authorized");
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // Retourne l'identifiant du séquestre ouvert, le cas échéant
+    pub fn apply(&mut self, transaction: &Transaction, height: u64) -> Result<Option<Vec<u8>>, &'static str> {
+        self.check(transaction, height)?;
+        match &transaction.kind {
+            TransactionKind::Transfer
+            | TransactionKind::Deploy { .. }
+            | TransactionKind::Call { .. }
+            | TransactionKind::SetRecovery { .. }
+            | TransactionKind::Recover { .. }
+            | TransactionKind::VetoRecovery
+            | TransactionKind::Shield
+            | TransactionKind::ConfidentialTransfer(_)
+            | TransactionKind::Unshield { .. }
+            | TransactionKind::Anchor { .. }
+            | TransactionKind::RegisterAsset { .. }
+            | TransactionKind::GrantAccess { .. }
+            | TransactionKind::RevokeAccess { .. }
+            | TransactionKind::Attest { .. }
+            | TransactionKind::Bundle(_) => Ok(None),
+            TransactionKind::Escrow { unlock_height, arbiter } => {
+                let id = transaction.hash();
+                self.open.insert(id.clone(), Escrow {
+                    depositor: transaction.sender,
+                    beneficiary: transaction.recipient,
+                    amount: transaction.amount,
+                    unlock_height: *unlock_height,
+                    arbiter: *arbiter,
+                });
+                Ok(Some(id))
+            }
+            TransactionKind::Claim { escrow } | TransactionKind::Refund { escrow } => {
+                self.open.remove(escrow);
+                Ok(None)
+            }
+        }
+    }
+
+    // Compte récupéré : ses droits de bénéficiaire et d'arbitre passent à sa
+    // nouvelle clé. Le déposant reste inchangé, l'adresse du séquestre en dépend.
+    pub fn rotate(&mut self, account: &PublicKey, new_key: &PublicKey) {
+        for escrow in self.open.values_mut() {
+            if escrow.beneficiary == *account {
+                escrow.beneficiary = *new_key;
+            }
+            if escrow.arbiter == Some(*account) {
+                escrow.arbiter = Some(*new_key);
+            }
+        }
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        let mut open: Vec<(&Vec<u8>, &Escrow)> = self.open.iter().collect();
+        open.sort_by_key(|(id, _)| *id);
+        encoder.put_u32(open.len() as u32);
+        for (id, escrow) in open {
+            encoder.put_bytes(id);
+            encoder.put_public_key(&escrow.depositor);
+            encoder.put_public_key(&escrow.beneficiary);
+            encoder.put_u64(escrow.amount);
+            encoder.put_u64(escrow.unlock_height);
+            match &escrow.arbiter {
+                Some(arbiter) => {
+                    encoder.put_u8(1);
+                    encoder.put_public_key(arbiter);
+                }
+                None => encoder.put_u8(0),
+            }
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Escrows, &'static str> {
+        let mut open = HashMap::new();
+        let mut previous: Option<Vec<u8>> = None;
+        for _ in 0..decoder.get_u32()? {
+            let id = decoder.get_bytes()?.to_vec();
+            if previous.as_ref().is_some_and(|previous| *previous >= id) {
+                return Err("Escrows not in canonical order");
+            }
+            let escrow = Escrow {
+                depositor: decoder.get_public_key()?,
+                beneficiary: decoder.get_public_key()?,
+                amount: decoder.get_u64()?,
+                unlock_height: decoder.get_u64()?,
+                arbiter: match decoder.get_u8()? {
+                    0 => None,
+                    1 => Some(decoder.get_public_key()?),
+                    _ => return Err("Invalid arbiter flag"),
+                },
+            };
+            previous = Some(id.clone());
+            open.insert(id, escrow);
+        }
+        Ok(Escrows { open })
+    }
+}
@@ -0,0 +1,77 @@
+use ed25519_dalek::PublicKey;
+use tokio::sync::broadcast;
+
+use crate::checkpoint::CheckpointVote;
+use crate::compliance::FreezeOrder;
+use crate::governance::{ConsensusParams, GovernanceTransaction};
+use crate::lifecycle::TransactionStatus;
+use crate::privacy::PrivateTransaction;
+use crate::vesting::VestingBalance;
+use crate::{Block, Transaction, Validator};
+
+// Un abonné trop lent perd les événements les plus anciens au-delà de cette capacité
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Clone, Debug)]
+pub enum ChainEvent {
+    BlockCommitted(Block),
+    TransactionAccepted(Transaction),
+    // Transaction retirée du mempool sans être incluse, ou expirée
+    TransactionDropped { transaction: Transaction, status: TransactionStatus },
+    ValidatorRegistered(Validator),
+    ValidatorUpdated(Validator),
+    // Vote de checkpoint nouvellement reçu ou produit localement
+    CheckpointVoteAdded(CheckpointVote),
+    CheckpointFinalized { height: u64, block_hash: Vec<u8> },
+    // Transaction de gouvernance admise dans le mempool local
+    GovernanceTransactionAccepted(GovernanceTransaction),
+    // Transaction multisig incomplète, avec toutes les signatures reçues jusqu'ici
+    MultisigApprovalAdded(Transaction),
+    // Transaction privée admise en attente d'inclusion
+    PrivateTransactionAccepted(PrivateTransaction),
+    // Paramètres de consensus modifiés par la gouvernance, en vigueur à partir du bloc suivant
+    ParametersChanged(ConsensusParams),
+    // Palier d'acquisition atteint par un compte au dernier bloc
+    VestingUnlocked { account: PublicKey, balance: VestingBalance },
+    // Ordre de gel ou de dégel inclus dans le dernier bloc
+    FreezeOrderApplied(FreezeOrder),
+    // Appel de contrat inclus dans le dernier bloc
+    ContractExecuted { contract: PublicKey, caller: PublicKey, output: Vec<u8>, gas_used: u64 },
+    // Version du protocole entrée en vigueur à partir du bloc suivant `height`
+    ProtocolUpgraded { version: u32, height: u64 },
+    // Valeur de la balise d'aléa produite au dernier bloc de l'époque `epoch`
+    BeaconFinalized { epoch: u64, value: Vec<u8> },
+    // Récupération d'un compte proposée par ses gardiens, effective à `execute_at` sauf opposition
+    RecoveryInitiated { account: PublicKey, new_key: PublicKey, execute_at: u64 },
+    // Compte transféré vers sa nouvelle clé au dernier bloc
+    AccountRecovered { account: PublicKey, new_key: PublicKey },
+    // Producteur dont le dernier bloc annonce d'autres règles de consensus que ce nœud
+    RulesFingerprintMismatch { validator: PublicKey, fingerprint: Vec<u8>, height: u64 },
+}
+
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ChainEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        EventBus { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ChainEvent> {
+        self.sender.subscribe()
+    }
+
+    // L'absence d'abonnés n'est pas une erreur
+    pub fn publish(&self, event: ChainEvent) {
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus::new()
+    }
+}
@@ -0,0 +1,513 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use ed25519_dalek::PublicKey;
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+
+use crate::indexer::Page;
+use crate::mempool::Bucket;
+use crate::plugins::PluginHost;
+use crate::ws::{block_json, transaction_json};
+use crate::Blockchain;
+
+// Taille maximale d'une requête (ligne de requête et en-têtes)
+const MAX_REQUEST_SIZE: usize = 8192;
+
+// Préfixe d'adresse minimal accepté par la recherche, en octets
+const MIN_ADDRESS_PREFIX: usize = 2;
+
+// Nombre maximal d'adresses retournées par une recherche par préfixe
+const MAX_SEARCH_ADDRESSES: usize = 20;
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+
+// Réponse JSON avec son code HTTP
+struct Reply {
+    status: u16,
+    body: Value,
+}
+
+impl Reply {
+    fn ok(body: Value) -> Self {
+        Reply { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Reply { status, body: json!({ "error": message }) }
+    }
+}
+
+// API HTTP en lecture pour explorateur de blocs (GET uniquement, réponses JSON) :
+// - /blocks?offset=&limit= : blocs du plus récent au plus ancien
+// - /blocks/<index> : bloc et ses transactions
+// - /addresses/<clé hex>/transactions?offset=&limit= : activité d'une adresse
+// - /addresses/<clé hex>/balance?height= : solde actuel, ou après le bloc `height` (mode archive)
+// - /addresses/<clé hex>/recovery : récupération du compte en cours, contestable par sa clé
+// - /addresses/<clé hex>/freezes : gel en vigueur et ordres de gel ou de dégel visant le compte
+// - /search?q= : index de bloc, hash de bloc ou de transaction, adresse ou préfixe d'adresse (hex)
+// - /stats : nombre de transactions, validateurs actifs, mise totale
+// - /beacon, /beacon/<époque> : dernière valeur de la balise d'aléa, ou celle d'une époque
+// - /mempool : taille, histogrammes de frais et d'âge, types et congestion du mempool
+// - /plugins/<espace de noms>/... : requêtes servies par les plugins du nœud
+pub async fn serve(addr: SocketAddr, blockchain: Arc<Mutex<Blockchain>>, plugins: Arc<PluginHost>) -> Result<(), &'static str> {
+    let listener = TcpListener::bind(addr).await.map_err(|_| "Failed to bind explorer address")?;
+    loop {
+        if let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(handle_connection(stream, blockchain.clone(), plugins.clone()));
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, blockchain: Arc<Mutex<Blockchain>>, plugins: Arc<PluginHost>) {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buffer).await {
+            Ok(0) | Err(_) => return,
+            Ok(read) => request.extend_from_slice(&buffer[..read]),
+        }
+        if request.len() > MAX_REQUEST_SIZE {
+            let _ = respond(&mut stream, Reply::error(431, "Request too large")).await;
+            return;
+        }
+    }
+
+    let line = String::from_utf8_lossy(&request);
+    let mut parts = line.lines().next().unwrap_or_default().split_whitespace();
+    let reply = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => {
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+            let query: HashMap<&str, &str> = query.split('&').filter_map(|pair| pair.split_once('=')).collect();
+            // Les plugins répondent sur leurs propres index, sans le verrou de la chaîne
+            match path.trim_matches('/').split('/').collect::<Vec<_>>().as_slice() {
+                ["plugins", namespace, rest @ ..] => match plugins.query(namespace, rest, &query) {
+                    Some(Ok(body)) => Reply::ok(body),
+                    Some(Err(e)) => Reply::error(400, &e),
+                    None => Reply::error(404, "Unknown plugin namespace"),
+                },
+                _ => {
+                    let chain = blockchain.lock().await;
+                    route(&chain, path, &query)
+                }
+            }
+        }
+        (Some(_), Some(_)) => Reply::error(405, "Method not allowed"),
+        _ => Reply::error(400, "Malformed request"),
+    };
+    let _ = respond(&mut stream, reply).await;
+}
+
+async fn respond(stream: &mut TcpStream, reply: Reply) -> std::io::Result<()> {
+    let body = reply.body.to_string();
+    let reason = match reply.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Request Header Fields Too Large",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n",
+        reply.status, reason, body.len(),
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn route(chain: &Blockchain, path: &str, query: &HashMap<&str, &str>) -> Reply {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let page = || -> Result<(usize, usize), Reply> {
+        let parameter = |name: &str, default: usize| match query.get(name) {
+            Some(value) => value.parse().map_err(|_| Reply::error(400, "Invalid pagination parameter")),
+            None => Ok(default),
+        };
+        Ok((parameter("offset", 0)?, parameter("limit", DEFAULT_PAGE_SIZE)?))
+    };
+    let result = match segments.as_slice() {
+        ["blocks"] => page().map(|(offset, limit)| blocks(chain, offset, limit)),
+        ["blocks", index] => index.parse().map_err(|_| Reply::error(400, "Invalid block index"))
+            .and_then(|index| block(chain, index)),
+        ["addresses", address, "transactions"] => parse_address(address)
+            .and_then(|address| page().map(|(offset, limit)| address_transactions(chain, &address, offset, limit))),
+        ["addresses", address, "balance"] => parse_address(address)
+            .and_then(|address| balance(chain, &address, query.get("height"))),
+        ["addresses", address, "recovery"] => parse_address(address).map(|address| recovery(chain, &address)),
+        ["addresses", address, "freezes"] => parse_address(address).map(|address| freezes(chain, &address)),
+        ["contracts", contract, "storage", key] => parse_address(contract)
+            .and_then(|contract| hex::decode(key).map_err(|_| Reply::error(400, "Invalid storage key"))
+                .map(|key| contract_storage(chain, &contract, &key))),
+        ["search"] => query.get("q").ok_or_else(|| Reply::error(400, "Missing search query"))
+            .and_then(|q| search(chain, q)),
+        ["stats"] => Ok(Reply::ok(stats(chain))),
+        ["mempool"] => Ok(Reply::ok(mempool(chain))),
+        ["beacon"] => chain.latest_beacon()
+            .map(|(epoch, value)| Reply::ok(beacon_json(epoch, value)))
+            .ok_or_else(|| Reply::error(404, "No beacon yet")),
+        ["beacon", epoch] => epoch.parse().map_err(|_| Reply::error(400, "Invalid epoch"))
+            .and_then(|epoch| chain.beacon(epoch)
+                .map(|value| Reply::ok(beacon_json(epoch, value)))
+                .ok_or_else(|| Reply::error(404, "Unknown beacon epoch"))),
+        _ => Err(Reply::error(404, "Unknown endpoint")),
+    };
+    result.unwrap_or_else(|reply| reply)
+}
+
+fn parse_address(address: &str) -> Result<PublicKey, Reply> {
+    hex::decode(address).ok()
+        .and_then(|bytes| PublicKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| Reply::error(400, "Invalid address"))
+}
+
+fn page_json<T>(page: &Page<T>, items: Vec<Value>) -> Value {
+    json!({
+        "items": items,
+        "total": page.total,
+        "next_offset": page.next_offset,
+    })
+}
+
+fn blocks(chain: &Blockchain, offset: usize, limit: usize) -> Reply {
+    let page = chain.blocks_newest_first(offset, limit);
+    Reply::ok(page_json(&page, page.items.iter().map(block_json).collect()))
+}
+
+fn block(chain: &Blockchain, index: u64) -> Result<Reply, Reply> {
+    let block = chain.block_at(index).ok_or_else(|| Reply::error(404, "Unknown block"))?;
+    let mut body = block_json(block);
+    body["transactions"] = block.transactions.iter().map(transaction_json).collect();
+    Ok(Reply::ok(body))
+}
+
+fn address_transactions(chain: &Blockchain, address: &PublicKey, offset: usize, limit: usize) -> Reply {
+    let page = chain.transactions_by_address(address, offset, limit);
+    let items = page.items.iter()
+        .map(|hash| match chain.find_transaction(hash) {
+            Some((block, transaction)) => json!({ "block": block.index, "transaction": transaction_json(transaction) }),
+            None => json!({ "hash": hex::encode(hash) }),
+        })
+        .collect();
+    Reply::ok(page_json(&page, items))
+}
+
+fn balance(chain: &Blockchain, address: &PublicKey, height: Option<&&str>) -> Result<Reply, Reply> {
+    // Parts acquise et bloquée d'un compte en acquisition, pour le solde actuel seulement
+    let (balance, vesting) = match height {
+        Some(height) => {
+            let height = height.parse().map_err(|_| Reply::error(400, "Invalid height"))?;
+            (chain.balance_at(address, height).map_err(|e| Reply::error(404, e))?, None)
+        }
+        None => (chain.balance(address), chain.vesting_balance(address)),
+    };
+    let vesting = vesting.map(|vesting| json!({
+        "vested": vesting.vested,
+        "locked": vesting.locked,
+        "available": vesting.available,
+    }));
+    Ok(Reply::ok(json!({ "address": hex::encode(address.as_bytes()), "balance": balance, "vesting": vesting })))
+}
+
+fn recovery(chain: &Blockchain, address: &PublicKey) -> Reply {
+    let pending = chain.pending_recovery(address).map(|recovery| json!({
+        "new_key": hex::encode(recovery.new_key.as_bytes()),
+        "execute_at": recovery.execute_at,
+    }));
+    Reply::ok(json!({ "address": hex::encode(address.as_bytes()), "pending": pending }))
+}
+
+// Valeur stockée par un contrat, `null` si la clé est absente ou le contrat inconnu
+fn contract_storage(chain: &Blockchain, contract: &PublicKey, key: &[u8]) -> Reply {
+    let value = chain.contract_storage(contract, key).map(hex::encode);
+    Reply::ok(json!({ "contract": hex::encode(contract.as_bytes()), "key": hex::encode(key), "value": value }))
+}
+
+fn freezes(chain: &Blockchain, address: &PublicKey) -> Reply {
+    let frozen = chain.freeze_record(address).map(|record| json!({
+        "issuer": hex::encode(record.issuer.as_bytes()),
+        "reason_hash": hex::encode(&record.reason_hash),
+        "height": record.height,
+    }));
+    let orders: Vec<Value> = chain.freeze_orders(address).iter().map(|(height, order)| json!({
+        "height": height,
+        "issuer": hex::encode(order.issuer.as_bytes()),
+        "freeze": order.freeze,
+        "reason_hash": hex::encode(&order.reason_hash),
+        "timestamp": order.timestamp,
+    })).collect();
+    Reply::ok(json!({ "address": hex::encode(address.as_bytes()), "frozen": frozen, "orders": orders }))
+}
+
+fn search(chain: &Blockchain, q: &str) -> Result<Reply, Reply> {
+    let mut results = Vec::new();
+    if let Some(block) = q.parse().ok().and_then(|index| chain.block_at(index)) {
+        results.push(json!({ "type": "block", "block": block_json(block) }));
+    }
+    if let Ok(bytes) = hex::decode(q) {
+        if let Some(block) = chain.find_block(&bytes) {
+            results.push(json!({ "type": "block", "block": block_json(block) }));
+        }
+        if let Some((block, transaction)) = chain.find_transaction(&bytes) {
+            results.push(json!({ "type": "transaction", "block": block.index, "transaction": transaction_json(transaction) }));
+        }
+        if bytes.len() >= MIN_ADDRESS_PREFIX {
+            for address in chain.indexer.addresses_with_prefix(&bytes, MAX_SEARCH_ADDRESSES) {
+                results.push(json!({
+                    "type": "address",
+                    "address": hex::encode(address.as_bytes()),
+                    "transaction_count": chain.transactions_by_address(&address, 0, 1).total,
+                    "balance": chain.balance(&address),
+                }));
+            }
+        }
+    } else if results.is_empty() {
+        return Err(Reply::error(400, "Query is neither a block index nor hex"));
+    }
+    Ok(Reply::ok(json!({ "query": q, "results": results })))
+}
+
+// Un validateur est actif tant que sa mise n'est pas nulle
+fn stats(chain: &Blockchain) -> Value {
+    let active: Vec<u64> = chain.validators.values().map(|v| v.stake).filter(|stake| *stake > 0).collect();
+    json!({
+        "height": chain.chain.last().map(|block| block.index),
+        "transaction_count": chain.indexer.transaction_count(),
+        "pending_transactions": chain.pending_transactions.len(),
+        "active_validators": active.len(),
+        "total_stake": active.iter().fold(0u64, |total, stake| total.saturating_add(*stake)),
+    })
+}
+
+fn beacon_json(epoch: u64, value: &[u8]) -> Value {
+    json!({ "epoch": epoch, "value": hex::encode(value) })
+}
+
+fn mempool(chain: &Blockchain) -> Value {
+    let status = chain.mempool_status();
+    let buckets = |buckets: &[Bucket]| -> Vec<Value> {
+        buckets.iter().map(|bucket| json!({ "min": bucket.min, "count": bucket.count })).collect()
+    };
+    json!({
+        "size": status.size,
+        "bytes": status.bytes,
+        "gas": status.gas,
+        "fee_histogram": buckets(&status.fee_histogram),
+        "age_histogram": buckets(&status.age_histogram),
+        "kinds": status.kinds,
+        "congestion": status.congestion,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use ed25519_dalek::{Keypair, SecretKey};
+
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::compliance::FreezeOrder;
+    use crate::config::ChainConfig;
+    use crate::contracts::contract_address;
+    use crate::governance::GovernanceTransaction;
+    use crate::txbuilder::TxBuilder;
+    use crate::vesting::VestingSchedule;
+    use crate::TransactionKind;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: (&secret).into(), secret }
+    }
+
+    fn get(chain: &Blockchain, path: &str) -> Reply {
+        route(chain, path, &HashMap::new())
+    }
+
+    fn get_with(chain: &Blockchain, path: &str, query: &[(&str, &str)]) -> Reply {
+        route(chain, path, &query.iter().copied().collect())
+    }
+
+    // Chaîne de quatre blocs dont le premier, d'index 0, porte un transfert
+    fn chain_with_transfer(validator: &Keypair, sender: &Keypair, recipient: &PublicKey) -> (Blockchain, Vec<u8>) {
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain.allocate(&sender.public, 1_000_000);
+        let transaction = TxBuilder::transfer().to(*recipient).amount(250).chain_id(chain.config.chain_id).nonce(chain.next_nonce(&sender.public)).timestamp(chain.now()).sign(sender).unwrap();
+        let hash = transaction.hash();
+        chain.add_transaction(transaction).unwrap();
+        for slot in 1..=4 {
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            chain.validate_and_create_block(validator, slot).unwrap();
+        }
+        (chain, hash)
+    }
+
+    #[test]
+    fn serves_the_beacon_of_each_epoch() {
+        let validator = keypair(1);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        assert_eq!(get(&chain, "/beacon").status, 404);
+
+        // La valeur de l'époque 0 est produite à son dernier bloc
+        for slot in 1..=100 {
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            chain.validate_and_create_block(&validator, slot).unwrap();
+        }
+        let latest = get(&chain, "/beacon");
+        assert_eq!(latest.status, 200);
+        assert_eq!(latest.body["epoch"], 0);
+        assert_eq!(latest.body["value"], hex::encode(chain.beacon(0).unwrap()));
+        assert_eq!(get(&chain, "/beacon/0").body, latest.body);
+        assert_eq!(get(&chain, "/beacon/1").status, 404);
+        assert_eq!(get(&chain, "/beacon/latest").status, 400);
+    }
+
+    #[test]
+    fn reports_no_recovery_for_unprotected_accounts() {
+        let chain = Blockchain::new(ChainConfig::default());
+        let address = hex::encode(keypair(2).public.as_bytes());
+        let reply = get(&chain, &format!("/addresses/{}/recovery", address));
+        assert_eq!(reply.status, 200);
+        assert_eq!(reply.body["address"], address);
+        assert!(reply.body["pending"].is_null());
+        assert_eq!(get(&chain, "/addresses/zz/recovery").status, 400);
+    }
+
+    #[test]
+    fn serves_the_freezes_of_an_account() {
+        let (validator, admin, account) = (keypair(1), keypair(2), keypair(3));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain.add_compliance_admin(admin.public);
+        let path = format!("/addresses/{}/freezes", hex::encode(account.public.as_bytes()));
+        assert!(get(&chain, &path).body["frozen"].is_null());
+
+        let order = FreezeOrder::new(&admin, account.public, true, vec![7; 32], GENESIS_TIME);
+        chain.add_governance_transaction(GovernanceTransaction::Freeze(order)).unwrap();
+        clock.set(Duration::from_secs(GENESIS_TIME + 5));
+        chain.validate_and_create_block(&validator, 1).unwrap();
+        let reply = get(&chain, &path);
+        assert_eq!(reply.body["frozen"]["issuer"], hex::encode(admin.public.as_bytes()));
+        assert_eq!(reply.body["frozen"]["reason_hash"], hex::encode([7; 32]));
+        assert_eq!(reply.body["orders"].as_array().unwrap().len(), 1);
+        assert_eq!(reply.body["orders"][0]["freeze"], true);
+    }
+
+    #[test]
+    fn serves_the_storage_of_a_contract() {
+        let (validator, deployer) = (keypair(1), keypair(2));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain.allocate(&deployer.public, 1_000_000);
+        let submit = |chain: &mut Blockchain, kind: TransactionKind, recipient: PublicKey, slot: u64| {
+            let transaction = TxBuilder::new(kind).to(recipient).chain_id(chain.config.chain_id).nonce(chain.next_nonce(&deployer.public)).timestamp(chain.now()).sign(&deployer).unwrap();
+            chain.add_transaction(transaction).unwrap();
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            chain.validate_and_create_block(&validator, slot).unwrap();
+        };
+
+        // Enregistre son entrée sous la clé "k"
+        let code = vec![0x40, 0x01, 0x01, b'k', 0x31, 0x00];
+        let contract = contract_address(&deployer.public, chain.now(), &code);
+        submit(&mut chain, TransactionKind::Deploy { code }, contract, 1);
+        let path = format!("/contracts/{}/storage/{}", hex::encode(contract.as_bytes()), hex::encode(b"k"));
+        assert!(get(&chain, &path).body["value"].is_null());
+        submit(&mut chain, TransactionKind::Call { input: b"stored".to_vec(), gas_limit: 10_000 }, contract, 2);
+
+        let reply = get(&chain, &path);
+        assert_eq!(reply.status, 200);
+        assert_eq!((&reply.body["contract"], &reply.body["key"]), (&json!(hex::encode(contract.as_bytes())), &json!("6b")));
+        assert_eq!(reply.body["value"], hex::encode(b"stored"));
+        assert_eq!(get(&chain, &format!("/contracts/{}/storage/zz", hex::encode(contract.as_bytes()))).status, 400);
+    }
+
+    #[test]
+    fn reports_the_vested_part_of_a_balance() {
+        let (member, other) = (keypair(2), keypair(3));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.add_vesting_account(member.public, VestingSchedule::new(1000, 0, 10, 100, 10).unwrap()).unwrap();
+        let reply = get(&chain, &format!("/addresses/{}/balance", hex::encode(member.public.as_bytes())));
+        assert_eq!(reply.body["balance"], 1000);
+        assert_eq!(reply.body["vesting"], json!({ "vested": 0, "locked": 1000, "available": 0 }));
+        assert!(get(&chain, &format!("/addresses/{}/balance", hex::encode(other.public.as_bytes()))).body["vesting"].is_null());
+    }
+
+    #[test]
+    fn lists_blocks_from_newest_to_oldest() {
+        let (chain, _) = chain_with_transfer(&keypair(1), &keypair(2), &keypair(3).public);
+        let height = chain.chain.last().unwrap().index;
+        let first = get_with(&chain, "/blocks", &[("limit", "2")]);
+        assert_eq!(first.status, 200);
+        let indices = |reply: &Reply| reply.body["items"].as_array().unwrap().iter().map(|block| block["index"].as_u64().unwrap()).collect::<Vec<_>>();
+        assert_eq!(indices(&first), vec![height, height - 1]);
+        assert_eq!((&first.body["total"], &first.body["next_offset"]), (&json!(chain.chain.len()), &json!(2)));
+
+        let last = get_with(&chain, "/blocks", &[("offset", "2"), ("limit", "100")]);
+        assert_eq!(indices(&last), (0..=height - 2).rev().collect::<Vec<_>>());
+        assert!(last.body["next_offset"].is_null());
+        assert_eq!(indices(&get(&chain, "/blocks")).len(), chain.chain.len().min(DEFAULT_PAGE_SIZE));
+        assert_eq!(get_with(&chain, "/blocks", &[("offset", "-1")]).status, 400);
+        assert_eq!(get_with(&chain, "/blocks", &[("limit", "many")]).status, 400);
+
+        let block = get(&chain, "/blocks/0");
+        assert_eq!((&block.body["index"], block.body["transactions"].as_array().unwrap().len()), (&json!(0), 1));
+        assert_eq!(get(&chain, &format!("/blocks/{}", height + 1)).status, 404);
+        assert_eq!(get(&chain, "/blocks/latest").status, 400);
+    }
+
+    #[test]
+    fn searches_blocks_transactions_and_addresses() {
+        let (sender, recipient) = (keypair(2), keypair(3));
+        let (chain, hash) = chain_with_transfer(&keypair(1), &sender, &recipient.public);
+        let search = |q: &str| get_with(&chain, "/search", &[("q", q)]);
+        let kinds = |reply: &Reply| reply.body["results"].as_array().unwrap().iter().map(|result| result["type"].as_str().unwrap().to_string()).collect::<Vec<_>>();
+
+        let by_index = search("2");
+        assert_eq!((kinds(&by_index), &by_index.body["results"][0]["block"]["index"]), (vec!["block".to_string()], &json!(2)));
+        let block_hash = hex::encode(&chain.block_at(3).unwrap().current_hash);
+        assert_eq!(search(&block_hash).body["results"][0]["block"]["index"], 3);
+
+        let transaction = search(&hex::encode(&hash));
+        assert_eq!(kinds(&transaction), vec!["transaction"]);
+        assert_eq!((&transaction.body["results"][0]["block"], &transaction.body["results"][0]["transaction"]["hash"]), (&json!(0), &json!(hex::encode(&hash))));
+
+        // Une adresse se trouve par un préfixe d'au moins MIN_ADDRESS_PREFIX octets
+        let address = hex::encode(recipient.public.as_bytes());
+        let found = search(&address[..MIN_ADDRESS_PREFIX * 2]);
+        let result = found.body["results"].as_array().unwrap().iter().find(|result| result["address"] == json!(address)).unwrap();
+        assert_eq!((&result["transaction_count"], &result["balance"]), (&json!(1), &json!(250)));
+        assert!(kinds(&search(&address[..MIN_ADDRESS_PREFIX * 2 - 2])).is_empty());
+        assert!(kinds(&search(&hex::encode([0xff; 32]))).is_empty());
+
+        assert_eq!(search("not hex").status, 400);
+        assert_eq!(get(&chain, "/search").status, 400);
+    }
+
+    #[test]
+    fn summarizes_the_chain() {
+        let (validator, sender) = (keypair(1), keypair(2));
+        let (mut chain, _) = chain_with_transfer(&validator, &sender, &keypair(3).public);
+        chain.register_validator(&keypair(4).public, 500).unwrap();
+        chain.register_validator(&keypair(5).public, 500).unwrap();
+        chain.validators.get_mut(&keypair(5).public).unwrap().stake = 0;
+        let pending = TxBuilder::transfer().to(keypair(3).public).amount(1).chain_id(chain.config.chain_id).nonce(chain.next_nonce(&sender.public)).timestamp(chain.now()).sign(&sender).unwrap();
+        chain.add_transaction(pending).unwrap();
+
+        let stats = get(&chain, "/stats").body;
+        assert_eq!(stats["height"], chain.chain.last().unwrap().index);
+        assert_eq!((&stats["transaction_count"], &stats["pending_transactions"]), (&json!(1), &json!(1)));
+        assert_eq!((&stats["active_validators"], &stats["total_stake"]), (&json!(2), &json!(1500)));
+    }
+}
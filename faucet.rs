@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use ed25519_dalek::{Keypair, PublicKey};
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::Blockchain;
+
+// Taille maximale d'une requête (ligne de requête et en-têtes)
+const MAX_REQUEST_SIZE: usize = 8192;
+
+// Dotation de genèse du compte du robinet
+pub const FAUCET_ALLOCATION: u64 = 1_000_000_000;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+// Plafonds du robinet, remis à zéro chaque jour (UTC)
+#[derive(Clone, Debug)]
+pub struct FaucetPolicy {
+    // Montant envoyé par demande
+    pub amount: u64,
+    // Total envoyé à une même adresse
+    pub per_address: u64,
+    // Demandes acceptées d'une même adresse IP
+    pub per_host: u32,
+    // Total envoyé par le robinet
+    pub per_day: u64,
+}
+
+impl Default for FaucetPolicy {
+    fn default() -> Self {
+        FaucetPolicy { amount: 1_000, per_address: 5_000, per_host: 10, per_day: 1_000_000 }
+    }
+}
+
+// Compte du robinet et comptes du jour en cours
+pub struct Faucet {
+    keypair: Keypair,
+    policy: FaucetPolicy,
+    day: u64,
+    sent: u64,
+    addresses: HashMap<PublicKey, u64>,
+    hosts: HashMap<IpAddr, u32>,
+}
+
+impl Faucet {
+    pub fn new(keypair: Keypair, policy: FaucetPolicy) -> Self {
+        Faucet { keypair, policy, day: 0, sent: 0, addresses: HashMap::new(), hosts: HashMap::new() }
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.keypair.public
+    }
+
+    // Montant pouvant être envoyé à `address` pour une demande de `host` ;
+    // décompté par `record` une fois la transaction admise au mempool
+    fn allowance(&mut self, host: IpAddr, address: &PublicKey, now: u64) -> Result<u64, &'static str> {
+        if now / SECONDS_PER_DAY != self.day {
+            self.day = now / SECONDS_PER_DAY;
+            self.sent = 0;
+            self.addresses.clear();
+            self.hosts.clear();
+        }
+        let received = self.addresses.get(address).copied().unwrap_or_default();
+        let amount = self.policy.amount.min(self.policy.per_address.saturating_sub(received));
+        if amount == 0 {
+            return Err("Daily limit reached for this address");
+        }
+        if self.hosts.get(&host).is_some_and(|requests| *requests >= self.policy.per_host) {
+            return Err("Daily limit reached for this host");
+        }
+        if self.sent.saturating_add(amount) > self.policy.per_day {
+            return Err("Faucet daily limit reached");
+        }
+        Ok(amount)
+    }
+
+    fn record(&mut self, host: IpAddr, address: &PublicKey, amount: u64) {
+        self.sent += amount;
+        *self.addresses.entry(*address).or_default() += amount;
+        *self.hosts.entry(host).or_default() += 1;
+    }
+
+    async fn request(&mut self, blockchain: &Mutex<Blockchain>, host: IpAddr, address: &str) -> (u16, Value) {
+        let address = match hex::decode(address).ok().and_then(|bytes| PublicKey::from_bytes(&bytes).ok()) {
+            Some(address) if address != self.keypair.public => address,
+            _ => return (400, json!({ "error": "Invalid address" })),
+        };
+        let mut chain = blockchain.lock().await;
+        let amount = match self.allowance(host, &address, chain.now()) {
+            Ok(amount) => amount,
+            Err(e) => return (429, json!({ "error": e })),
+        };
+        if let Err(e) = chain.create_transaction(&self.keypair, &address, amount) {
+            return (400, json!({ "error": e }));
+        }
+        self.record(host, &address, amount);
+        info!(address = %hex::encode(address.as_bytes()), amount, %host, "Faucet transfer submitted");
+        (200, json!({ "address": hex::encode(address.as_bytes()), "amount": amount }))
+    }
+}
+
+// API HTTP du robinet (réseaux de test uniquement) :
+// - POST /faucet/<clé hex> : envoie `amount` à l'adresse, dans la limite des plafonds du jour
+pub async fn serve(addr: SocketAddr, blockchain: Arc<Mutex<Blockchain>>, faucet: Faucet) -> Result<(), &'static str> {
+    let listener = TcpListener::bind(addr).await.map_err(|_| "Failed to bind faucet address")?;
+    let faucet = Arc::new(Mutex::new(faucet));
+    loop {
+        if let Ok((stream, peer)) = listener.accept().await {
+            tokio::spawn(handle_connection(stream, peer.ip(), blockchain.clone(), faucet.clone()));
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, host: IpAddr, blockchain: Arc<Mutex<Blockchain>>, faucet: Arc<Mutex<Faucet>>) {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buffer).await {
+            Ok(0) | Err(_) => return,
+            Ok(read) => request.extend_from_slice(&buffer[..read]),
+        }
+        if request.len() > MAX_REQUEST_SIZE {
+            let _ = respond(&mut stream, 431, json!({ "error": "Request too large" })).await;
+            return;
+        }
+    }
+
+    let line = String::from_utf8_lossy(&request);
+    let mut parts = line.lines().next().unwrap_or_default().split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("POST"), Some(target)) => match target.trim_matches('/').split('/').collect::<Vec<_>>().as_slice() {
+            ["faucet", address] => faucet.lock().await.request(&blockchain, host, address).await,
+            _ => (404, json!({ "error": "Unknown endpoint" })),
+        },
+        (Some(_), Some(_)) => (405, json!({ "error": "Method not allowed" })),
+        _ => (400, json!({ "error": "Malformed request" })),
+    };
+    let _ = respond(&mut stream, status, body).await;
+}
+
+async fn respond(stream: &mut TcpStream, status: u16, body: Value) -> std::io::Result<()> {
+    let body = body.to_string();
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        429 => "Too Many Requests",
+        _ => "Request Header Fields Too Large",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n",
+        status, reason, body.len(),
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use ed25519_dalek::SecretKey;
+
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    fn host(last: u8) -> IpAddr {
+        IpAddr::from([10, 0, 0, last])
+    }
+
+    #[test]
+    fn daily_limits_cap_each_address_host_and_the_faucet() {
+        let policy = FaucetPolicy { amount: 400, per_address: 1_000, per_host: 3, per_day: 1_500 };
+        let mut faucet = Faucet::new(keypair(1), policy);
+        let (alice, bob, carol) = (keypair(2).public, keypair(3).public, keypair(4).public);
+        let grant = |faucet: &mut Faucet, host, address: &PublicKey, now| {
+            let amount = faucet.allowance(host, address, now)?;
+            faucet.record(host, address, amount);
+            Ok::<u64, &'static str>(amount)
+        };
+
+        // La dernière demande d'une adresse est réduite à ce qu'il lui reste
+        let amounts: Vec<_> = (1..=4).map(|last| grant(&mut faucet, host(last), &alice, GENESIS_TIME)).collect();
+        assert_eq!(amounts, vec![Ok(400), Ok(400), Ok(200), Err("Daily limit reached for this address")]);
+
+        assert_eq!(grant(&mut faucet, host(9), &bob, GENESIS_TIME), Ok(400));
+        assert_eq!(grant(&mut faucet, host(9), &carol, GENESIS_TIME), Err("Faucet daily limit reached"));
+
+        let next_day = GENESIS_TIME + SECONDS_PER_DAY;
+        let amounts: Vec<_> = (0..3).map(|_| grant(&mut faucet, host(9), &bob, next_day)).collect();
+        assert_eq!(amounts, vec![Ok(400), Ok(400), Ok(200)]);
+        assert_eq!(grant(&mut faucet, host(9), &carol, next_day), Err("Daily limit reached for this host"));
+
+        // Tout est remis à zéro au jour suivant
+        assert_eq!(grant(&mut faucet, host(9), &alice, GENESIS_TIME + 2 * SECONDS_PER_DAY), Ok(400));
+    }
+
+    #[tokio::test]
+    async fn requests_submit_a_transfer_from_the_faucet_account() {
+        let (account, recipient) = (keypair(1), keypair(2));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(MockClock::new(Duration::from_secs(GENESIS_TIME))));
+        let blockchain = Mutex::new(chain);
+        let mut faucet = Faucet::new(Keypair::from_bytes(&account.to_bytes()).unwrap(), FaucetPolicy::default());
+        let address = hex::encode(recipient.public.as_bytes());
+
+        // Sans fonds, la transaction est refusée et rien n'est décompté
+        let (status, _) = faucet.request(&blockchain, host(1), &address).await;
+        assert_eq!((status, faucet.sent), (400, 0));
+
+        blockchain.lock().await.allocate(&account.public, FAUCET_ALLOCATION);
+        let (status, body) = faucet.request(&blockchain, host(1), &address).await;
+        assert_eq!(status, 200);
+        assert_eq!(body, json!({ "address": address, "amount": 1_000 }));
+        let chain = blockchain.lock().await;
+        let transaction = chain.pending_transactions.last().unwrap();
+        assert_eq!((transaction.sender, transaction.recipient, transaction.amount), (account.public, recipient.public, 1_000));
+        drop(chain);
+
+        assert_eq!(faucet.request(&blockchain, host(1), "zz").await.0, 400);
+        assert_eq!(faucet.request(&blockchain, host(1), &hex::encode(account.public.as_bytes())).await.0, 400);
+        faucet.policy.per_address = 1_000;
+        assert_eq!(faucet.request(&blockchain, host(2), &address).await, (429, json!({ "error": "Daily limit reached for this address" })));
+    }
+}
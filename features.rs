@@ -0,0 +1,92 @@
+use std::fmt::Write;
+
+use crate::buildinfo::{consensus_fingerprint, CONSENSUS_RULES_VERSION, PACKAGE_VERSION};
+use crate::config::ChainConfig;
+use crate::upgrade::PROTOCOL_VERSION;
+
+// Profils de déploiement (voir cargo.toml) ; le code de consensus est le même
+// dans chacun, ce que build.rs vérifie à la compilation
+const PROFILES: &[(&str, bool)] = &[
+    ("core", cfg!(feature = "core")),
+    ("validator", cfg!(feature = "validator")),
+    ("gateway", cfg!(feature = "gateway")),
+    ("archive", cfg!(feature = "archive")),
+    ("full", cfg!(feature = "full")),
+];
+
+// Sous-systèmes optionnels, chacun derrière sa feature
+const FEATURES: &[(&str, bool)] = &[
+    ("rpc", cfg!(feature = "rpc")),
+    ("admin", cfg!(feature = "admin")),
+    ("ws", cfg!(feature = "ws")),
+    ("explorer", cfg!(feature = "explorer")),
+    ("replication", cfg!(feature = "replication")),
+    ("metrics", cfg!(feature = "metrics")),
+    ("quic", cfg!(feature = "quic")),
+    ("plugin-activity", cfg!(feature = "plugin-activity")),
+    ("confidential", cfg!(feature = "confidential")),
+    ("consensus-sim", cfg!(feature = "consensus-sim")),
+];
+
+fn enabled(flags: &[(&'static str, bool)]) -> Vec<&'static str> {
+    flags.iter().filter(|(_, on)| *on).map(|(name, _)| *name).collect()
+}
+
+// Versions du binaire et empreinte des règles pour `config` ; avec `features`,
+// profils et sous-systèmes compilés. Deux binaires de profils différents
+// annoncent la même empreinte pour la même configuration.
+pub fn report(config: &ChainConfig, features: bool) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "supplyx {}", PACKAGE_VERSION);
+    let _ = writeln!(out, "protocol version: {}", PROTOCOL_VERSION);
+    let _ = writeln!(out, "consensus rules version: {}", CONSENSUS_RULES_VERSION);
+    let _ = writeln!(out, "consensus fingerprint: {}", hex::encode(consensus_fingerprint(config)));
+    if features {
+        let profiles = enabled(PROFILES);
+        let _ = writeln!(out, "profiles: {}", if profiles.is_empty() { "core".to_string() } else { profiles.join(", ") });
+        for (name, on) in FEATURES {
+            let _ = writeln!(out, "  {:<16} {}", name, if *on { "enabled" } else { "disabled" });
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_versions_and_the_rules_fingerprint() {
+        let config = ChainConfig::default();
+        let plain = report(&config, false);
+        assert_eq!(plain.lines().collect::<Vec<_>>(), vec![
+            format!("supplyx {}", PACKAGE_VERSION),
+            format!("protocol version: {}", PROTOCOL_VERSION),
+            format!("consensus rules version: {}", CONSENSUS_RULES_VERSION),
+            format!("consensus fingerprint: {}", hex::encode(consensus_fingerprint(&config))),
+        ]);
+
+        // Seuls les paramètres de consensus changent l'empreinte, pas ceux du nœud
+        let node = ChainConfig::builder().max_clock_drift(60).build().unwrap();
+        assert_eq!(report(&node, false), plain);
+        let consensus = ChainConfig::builder().max_block_gas(1_000_000).build().unwrap();
+        assert_ne!(report(&consensus, false), plain);
+    }
+
+    #[test]
+    fn lists_the_compiled_profiles_and_subsystems() {
+        let config = ChainConfig::default();
+        let detailed = report(&config, true);
+        let lines: Vec<&str> = detailed.lines().collect();
+        assert_eq!(lines[..4], report(&config, false).lines().collect::<Vec<_>>()[..]);
+
+        let profiles = enabled(PROFILES);
+        let expected = if profiles.is_empty() { "core".to_string() } else { profiles.join(", ") };
+        assert_eq!(lines[4], format!("profiles: {}", expected));
+        assert_eq!(lines.len(), 5 + FEATURES.len());
+        for ((name, on), line) in FEATURES.iter().zip(&lines[5..]) {
+            assert_eq!(line.split_whitespace().collect::<Vec<_>>(), vec![*name, if *on { "enabled" } else { "disabled" }]);
+        }
+        assert_eq!(enabled(&[("a", true), ("b", false), ("c", true)]), vec!["a", "c"]);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{Authorization, Transaction, TransactionKind};
+
+// Prix du gaz utilisé par les transactions créées localement
+pub const DEFAULT_GAS_PRICE: u64 = 1;
+
+// Barème : coût fixe par type de transaction, plus la taille signée et chaque signature
+const GAS_TRANSFER: u64 = 1_000;
+const GAS_ESCROW: u64 = 2_000;
+const GAS_SETTLE_ESCROW: u64 = 1_500;
+const GAS_DEPLOY: u64 = 10_000;
+const GAS_CALL: u64 = 2_000;
+const GAS_RECOVERY: u64 = 2_000;
+const GAS_SHIELD: u64 = 1_500;
+const GAS_ANCHOR: u64 = 1_000;
+const GAS_ASSET: u64 = 1_000;
+// Lot atomique, hors frais de ses transactions payés par leurs émetteurs
+const GAS_BUNDLE: u64 = 1_000;
+// Par preuve d'intervalle vérifiée
+const GAS_RANGE_PROOF: u64 = 10_000;
+const GAS_PER_BYTE: u64 = 10;
+const GAS_PER_SIGNATURE: u64 = 500;
+
+// Gaz consommé par une transaction hors exécution de contrat
+pub fn intrinsic_gas(transaction: &Transaction) -> u64 {
+    let base = match transaction.kind {
+        TransactionKind::Transfer => GAS_TRANSFER,
+        TransactionKind::Escrow { .. } => GAS_ESCROW,
+        TransactionKind::Claim { .. } | TransactionKind::Refund { .. } => GAS_SETTLE_ESCROW,
+        TransactionKind::Deploy { .. } => GAS_DEPLOY,
+        TransactionKind::Call { .. } => GAS_CALL,
+        TransactionKind::SetRecovery { .. } | TransactionKind::Recover { .. } | TransactionKind::VetoRecovery => GAS_RECOVERY,
+        TransactionKind::Shield => GAS_SHIELD,
+        TransactionKind::ConfidentialTransfer(_) => GAS_TRANSFER + 2 * GAS_RANGE_PROOF,
+        TransactionKind::Unshield { .. } => GAS_TRANSFER + GAS_RANGE_PROOF,
+        TransactionKind::Anchor { .. } => GAS_ANCHOR,
+        TransactionKind::RegisterAsset { .. } | TransactionKind::GrantAccess { .. } | TransactionKind::RevokeAccess { .. } | TransactionKind::Attest { .. } => GAS_ASSET,
+        TransactionKind::Bundle(_) => GAS_BUNDLE,
+    };
+    let signatures = match &transaction.authorization {
+        Authorization::Signature(_) => 1,
+        Authorization::Multisig { signatures, .. } => signatures.len() as u64,
+    };
+    base + GAS_PER_BYTE * transaction.signing_bytes().len() as u64 + GAS_PER_SIGNATURE * signatures
+}
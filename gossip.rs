@@ -0,0 +1,659 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use sha3::{Digest, Sha3_256};
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex, Notify};
+use tracing::{debug, info, warn};
+
+use crate::checkpoint::CheckpointVote;
+use crate::encoding::{decode_block, decode_block_body, encode_block, encode_block_body, Decoder, Encoder, GOSSIP_DOMAIN};
+use crate::events::ChainEvent;
+use crate::governance::GovernanceTransaction;
+use crate::mempool::RateLimiter;
+use crate::node::{NodeEvent, PendingTraces};
+use crate::privacy::PrivateTransaction;
+use crate::reputation::{host, PeerScore, Reputation, Violation};
+use crate::sync::{SyncPeer, SyncRequest, SyncResponse};
+use crate::telemetry::{self, TraceContext};
+use crate::transport::{Link, Transports};
+use crate::{Block, Blockchain, Transaction};
+
+// Nombre de messages récents mémorisés pour ne pas les relayer deux fois
+const SEEN_CAPACITY: usize = 16_384;
+
+// Transactions relayées acceptées par hôte et par seconde ; le surplus est ignoré
+const MAX_PEER_TRANSACTIONS_PER_SECOND: u32 = 200;
+
+// Délai de réponse d'un pair à une requête de synchronisation
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Intervalle entre deux annonces de l'heure locale à un pair
+const PEER_TIME_INTERVAL: Duration = Duration::from_secs(60);
+
+// Bornes du contexte de trace joint à une transaction (en-têtes W3C)
+const MAX_TRACE_ENTRIES: usize = 4;
+const MAX_TRACE_FIELD: usize = 512;
+
+enum GossipMessage {
+    // Transaction et contexte de trace de son admission par l'émetteur
+    Transaction { transaction: Transaction, trace: TraceContext },
+    Block(Block),
+    CheckpointVote(CheckpointVote),
+    Governance(GovernanceTransaction),
+    PrivateTransaction(PrivateTransaction),
+    // Transaction multisig portant une partie des signatures requises
+    MultisigApproval(Transaction),
+    // Échanges directs avec un pair, jamais relayés
+    Request { id: u64, request: SyncRequest },
+    Response { id: u64, response: SyncResponse },
+    // Heure locale (secondes Unix) de l'émetteur
+    Time(u64),
+}
+
+impl GossipMessage {
+    fn encode(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(GOSSIP_DOMAIN);
+        match self {
+            GossipMessage::Transaction { transaction, trace } => {
+                encoder.put_u8(0);
+                encoder.put_bytes(&encode_block_body(std::slice::from_ref(transaction)));
+                let mut entries: Vec<(&String, &String)> = trace.iter().collect();
+                entries.sort();
+                encoder.put_u32(entries.len() as u32);
+                for (key, value) in entries {
+                    encoder.put_bytes(key.as_bytes());
+                    encoder.put_bytes(value.as_bytes());
+                }
+            }
+            GossipMessage::Block(block) => {
+                encoder.put_u8(1);
+                encoder.put_bytes(&encode_block(block));
+            }
+            GossipMessage::CheckpointVote(vote) => {
+                encoder.put_u8(2);
+                encoder.put_u64(vote.height);
+                encoder.put_bytes(&vote.block_hash);
+                encoder.put_public_key(&vote.validator);
+                encoder.put_signature(&vote.signature);
+            }
+            GossipMessage::Governance(transaction) => {
+                encoder.put_u8(3);
+                transaction.encode_into(&mut encoder);
+            }
+            GossipMessage::Request { id, request } => {
+                encoder.put_u8(4);
+                encoder.put_u64(*id);
+                request.encode_into(&mut encoder);
+            }
+            GossipMessage::Response { id, response } => {
+                encoder.put_u8(5);
+                encoder.put_u64(*id);
+                response.encode_into(&mut encoder);
+            }
+            GossipMessage::Time(time) => {
+                encoder.put_u8(6);
+                encoder.put_u64(*time);
+            }
+            GossipMessage::PrivateTransaction(transaction) => {
+                encoder.put_u8(7);
+                transaction.encode_into(&mut encoder);
+            }
+            GossipMessage::MultisigApproval(transaction) => {
+                encoder.put_u8(8);
+                encoder.put_bytes(&encode_block_body(std::slice::from_ref(transaction)));
+            }
+        }
+        encoder.finish()
+    }
+
+    fn decode(data: &[u8]) -> Result<GossipMessage, &'static str> {
+        let mut decoder = Decoder::new(data, GOSSIP_DOMAIN)?;
+        let single_transaction = |decoder: &mut Decoder| {
+            let mut transactions = decode_block_body(decoder.get_bytes()?)?;
+            if transactions.len() != 1 {
+                return Err("Expected a single transaction");
+            }
+            Ok(transactions.remove(0))
+        };
+        let message = match decoder.get_u8()? {
+            0 => {
+                let transaction = single_transaction(&mut decoder)?;
+                let count = decoder.get_u32()? as usize;
+                if count > MAX_TRACE_ENTRIES {
+                    return Err("Too many trace entries");
+                }
+                let mut trace = TraceContext::new();
+                for _ in 0..count {
+                    let mut field = || match decoder.get_bytes()? {
+                        field if field.len() > MAX_TRACE_FIELD => Err("Trace entry too long"),
+                        field => String::from_utf8(field.to_vec()).map_err(|_| "Invalid trace entry"),
+                    };
+                    let (key, value) = (field()?, field()?);
+                    trace.insert(key, value);
+                }
+                GossipMessage::Transaction { transaction, trace }
+            }
+            1 => GossipMessage::Block(decode_block(decoder.get_bytes()?)?),
+            2 => GossipMessage::CheckpointVote(CheckpointVote {
+                height: decoder.get_u64()?,
+                block_hash: decoder.get_bytes()?.to_vec(),
+                validator: decoder.get_public_key()?,
+                signature: decoder.get_signature()?,
+            }),
+            3 => GossipMessage::Governance(GovernanceTransaction::decode_from(&mut decoder)?),
+            4 => GossipMessage::Request { id: decoder.get_u64()?, request: SyncRequest::decode_from(&mut decoder)? },
+            5 => GossipMessage::Response { id: decoder.get_u64()?, response: SyncResponse::decode_from(&mut decoder)? },
+            6 => GossipMessage::Time(decoder.get_u64()?),
+            7 => GossipMessage::PrivateTransaction(PrivateTransaction::decode_from(&mut decoder)?),
+            8 => GossipMessage::MultisigApproval(single_transaction(&mut decoder)?),
+            _ => return Err("Unknown gossip message"),
+        };
+        decoder.finish()?;
+        Ok(message)
+    }
+
+    fn into_node_event(self) -> Option<NodeEvent> {
+        match self {
+            GossipMessage::Transaction { transaction, trace } => Some(NodeEvent::Transaction { transaction, trace }),
+            GossipMessage::Block(block) => Some(NodeEvent::Block(block)),
+            GossipMessage::CheckpointVote(vote) => Some(NodeEvent::CheckpointVote(vote)),
+            GossipMessage::Governance(transaction) => Some(NodeEvent::Governance(transaction)),
+            GossipMessage::PrivateTransaction(transaction) => Some(NodeEvent::PrivateTransaction(transaction)),
+            GossipMessage::MultisigApproval(transaction) => Some(NodeEvent::MultisigApproval(transaction)),
+            GossipMessage::Request { .. } | GossipMessage::Response { .. } | GossipMessage::Time(_) => None,
+        }
+    }
+}
+
+fn frame_hash(frame: &[u8]) -> Vec<u8> {
+    Sha3_256::digest(frame).to_vec()
+}
+
+#[derive(Default)]
+struct Seen {
+    hashes: HashSet<Vec<u8>>,
+    order: VecDeque<Vec<u8>>,
+}
+
+impl Seen {
+    // `true` si le message n'avait pas encore été vu
+    fn insert(&mut self, hash: Vec<u8>) -> bool {
+        if !self.hashes.insert(hash.clone()) {
+            return false;
+        }
+        self.order.push_back(hash);
+        if self.order.len() > SEEN_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.hashes.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+// Requêtes de synchronisation en attente de réponse, par identifiant
+type PendingRequests = Arc<std::sync::Mutex<HashMap<u64, oneshot::Sender<SyncResponse>>>>;
+
+// Lien ouvert vers un pair
+#[derive(Clone)]
+struct Peer {
+    remote: String,
+    outgoing: mpsc::Sender<Vec<u8>>,
+    pending: PendingRequests,
+    next_request: Arc<AtomicU64>,
+    reputation: Arc<std::sync::Mutex<Reputation>>,
+    // Ferme le lien (bannissement)
+    disconnect: Arc<Notify>,
+}
+
+impl Peer {
+    fn punish(&self, violation: Violation) {
+        if self.reputation.lock().unwrap().record(&self.remote, violation) {
+            warn!(peer = %self.remote, ?violation, "Peer banned");
+            self.disconnect.notify_one();
+        }
+    }
+}
+
+#[async_trait]
+impl SyncPeer for Peer {
+    fn id(&self) -> &str {
+        &self.remote
+    }
+
+    fn report(&self, violation: Violation) {
+        self.punish(violation);
+    }
+
+    async fn request(&self, request: SyncRequest) -> Result<SyncResponse, String> {
+        let id = self.next_request.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, sender);
+        let frame = GossipMessage::Request { id, request }.encode();
+        if self.outgoing.send(frame).await.is_err() {
+            self.pending.lock().unwrap().remove(&id);
+            return Err("Peer disconnected".to_string());
+        }
+        match tokio::time::timeout(REQUEST_TIMEOUT, receiver).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err("Peer disconnected".to_string()),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&id);
+                Err("Sync request timed out".to_string())
+            }
+        }
+    }
+}
+
+// Diffusion des transactions, blocs et votes de checkpoint entre pairs, quel
+// que soit le transport de chaque lien, et réponse aux requêtes de
+// synchronisation à partir de la chaîne locale. Les pairs fautifs sont
+// déconnectés et bannis d'après leur réputation.
+#[derive(Clone)]
+pub struct Gossip {
+    transports: Transports,
+    node_events: mpsc::Sender<NodeEvent>,
+    blockchain: Arc<Mutex<Blockchain>>,
+    peers: Arc<Mutex<Vec<Peer>>>,
+    seen: Arc<std::sync::Mutex<Seen>>,
+    next_request: Arc<AtomicU64>,
+    reputation: Arc<std::sync::Mutex<Reputation>>,
+    transaction_rate: Arc<std::sync::Mutex<RateLimiter<String>>>,
+}
+
+impl Gossip {
+    pub fn new(transports: Transports, node_events: mpsc::Sender<NodeEvent>, blockchain: Arc<Mutex<Blockchain>>, reputation: Reputation) -> Self {
+        Gossip {
+            transports,
+            node_events,
+            blockchain,
+            peers: Arc::default(),
+            seen: Arc::default(),
+            next_request: Arc::default(),
+            reputation: Arc::new(std::sync::Mutex::new(reputation)),
+            transaction_rate: Arc::new(std::sync::Mutex::new(RateLimiter::new(MAX_PEER_TRANSACTIONS_PER_SECOND, Duration::from_secs(1)))),
+        }
+    }
+
+    pub async fn listen(&self, endpoint: &str) -> Result<(), String> {
+        let mut links = self.transports.listen(endpoint).await?;
+        let gossip = self.clone();
+        tokio::spawn(async move {
+            while let Some(link) = links.recv().await {
+                gossip.attach(link).await;
+            }
+        });
+        Ok(())
+    }
+
+    // `endpoints` : adresses de repli d'un même pair, séparées par `|`
+    pub async fn connect(&self, endpoints: &str) -> Result<(), String> {
+        if endpoints.split('|').any(|endpoint| self.reputation.lock().unwrap().is_banned(endpoint)) {
+            return Err("Peer banned".to_string());
+        }
+        let link = self.transports.connect(endpoints).await?;
+        info!(peer = %link.remote, "Connected to peer");
+        self.attach(link).await;
+        Ok(())
+    }
+
+    async fn attach(&self, link: Link) {
+        let Link { remote, outgoing, mut incoming } = link;
+        if self.reputation.lock().unwrap().is_banned(&remote) {
+            info!(peer = %remote, "Rejected banned peer");
+            return;
+        }
+        let peer = Peer {
+            remote,
+            outgoing,
+            pending: PendingRequests::default(),
+            next_request: self.next_request.clone(),
+            reputation: self.reputation.clone(),
+            disconnect: Arc::default(),
+        };
+        self.peers.lock().await.push(peer.clone());
+
+        // Annonce périodique de l'heure locale, tant que le pair reste attaché
+        let blockchain = self.blockchain.clone();
+        let outgoing = peer.outgoing.downgrade();
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(PEER_TIME_INTERVAL);
+            loop {
+                ticks.tick().await;
+                let Some(outgoing) = outgoing.upgrade() else { break };
+                let time = blockchain.lock().await.now();
+                if outgoing.send(GossipMessage::Time(time).encode()).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let gossip = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let frame = tokio::select! {
+                    frame = incoming.recv() => match frame {
+                        Some(frame) => frame,
+                        None => break,
+                    },
+                    _ = peer.disconnect.notified() => break,
+                };
+                if !gossip.reputation.lock().unwrap().allow_message(&peer.remote) {
+                    continue;
+                }
+                let message = match GossipMessage::decode(&frame) {
+                    Ok(message) => message,
+                    Err(e) => {
+                        warn!(peer = %peer.remote, error = e, "Invalid gossip message");
+                        peer.punish(Violation::InvalidMessage);
+                        continue;
+                    }
+                };
+                match message {
+                    GossipMessage::Request { id, request } => {
+                        let response = request.answer(&*gossip.blockchain.lock().await);
+                        let _ = peer.outgoing.send(GossipMessage::Response { id, response }.encode()).await;
+                    }
+                    GossipMessage::Response { id, response } => {
+                        if let Some(sender) = peer.pending.lock().unwrap().remove(&id) {
+                            let _ = sender.send(response);
+                        }
+                    }
+                    GossipMessage::Time(time) => {
+                        if gossip.node_events.send(NodeEvent::PeerTime { peer: peer.remote.clone(), time }).await.is_err() {
+                            break;
+                        }
+                    }
+                    message => {
+                        if matches!(message, GossipMessage::Transaction { .. } | GossipMessage::MultisigApproval(_))
+                            && !gossip.transaction_rate.lock().unwrap().allow(host(&peer.remote).to_string())
+                        {
+                            debug!(peer = %peer.remote, "Peer transaction rate exceeded");
+                            continue;
+                        }
+                        if !gossip.seen.lock().unwrap().insert(frame_hash(&frame)) {
+                            continue;
+                        }
+                        // Un pair honnête ne relaye que des blocs acceptés par sa chaîne
+                        if let GossipMessage::Block(block) = &message {
+                            if let Err(e) = block.header().verify() {
+                                warn!(peer = %peer.remote, error = e, "Invalid gossiped block");
+                                peer.punish(Violation::InvalidBlock);
+                                continue;
+                            }
+                        }
+                        if let Some(event) = message.into_node_event() {
+                            if gossip.node_events.send(event).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            gossip.peers.lock().await.retain(|other| !Arc::ptr_eq(&other.disconnect, &peer.disconnect));
+        });
+    }
+
+    // Pairs connectés et leur réputation
+    pub async fn peers(&self) -> Vec<(String, PeerScore)> {
+        let peers = self.peers.lock().await;
+        let mut reputation = self.reputation.lock().unwrap();
+        peers.iter()
+            .filter(|peer| !peer.outgoing.is_closed())
+            .map(|peer| (peer.remote.clone(), reputation.score(&peer.remote)))
+            .collect()
+    }
+
+    // Bannissements en cours et leur expiration (secondes Unix)
+    pub fn bans(&self) -> Vec<(String, u64)> {
+        self.reputation.lock().unwrap().bans()
+    }
+
+    // Bannit un hôte et ferme ses liens ouverts
+    pub async fn ban(&self, host: &str, duration: Duration) {
+        self.reputation.lock().unwrap().ban(host, duration);
+        for peer in self.peers.lock().await.iter() {
+            if self.reputation.lock().unwrap().is_banned(&peer.remote) {
+                peer.disconnect.notify_one();
+            }
+        }
+    }
+
+    pub fn unban(&self, host: &str) -> bool {
+        self.reputation.lock().unwrap().unban(host)
+    }
+
+    // Pairs dont la liaison est encore ouverte
+    pub async fn peer_count(&self) -> usize {
+        self.peers.lock().await.iter().filter(|peer| !peer.outgoing.is_closed()).count()
+    }
+
+    // Compteurs des transports des liens avec les pairs
+    #[cfg(feature = "metrics")]
+    pub fn transport_metrics(&self) -> std::collections::BTreeMap<&'static str, Arc<crate::transport::TransportMetrics>> {
+        self.transports.metrics().into_iter().collect()
+    }
+
+    // Pairs connectés, interrogeables pour la synchronisation
+    pub async fn sync_peers(&self) -> Vec<Arc<dyn SyncPeer>> {
+        self.peers.lock().await.iter()
+            .filter(|peer| !peer.outgoing.is_closed())
+            .map(|peer| Arc::new(peer.clone()) as Arc<dyn SyncPeer>)
+            .collect()
+    }
+
+    // Relaye vers tous les pairs ce que la chaîne locale accepte, chaque
+    // transaction avec le contexte de trace de son admission (`traces`)
+    pub fn relay(&self, mut events: broadcast::Receiver<ChainEvent>, traces: PendingTraces) {
+        let gossip = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let message = match events.recv().await {
+                    Ok(ChainEvent::TransactionAccepted(transaction)) => {
+                        let trace = traces.lock().unwrap().get(&transaction.hash()).map(telemetry::inject).unwrap_or_default();
+                        GossipMessage::Transaction { transaction, trace }
+                    }
+                    Ok(ChainEvent::BlockCommitted(block)) => GossipMessage::Block(block),
+                    Ok(ChainEvent::CheckpointVoteAdded(vote)) => GossipMessage::CheckpointVote(vote),
+                    Ok(ChainEvent::GovernanceTransactionAccepted(transaction)) => GossipMessage::Governance(transaction),
+                    Ok(ChainEvent::PrivateTransactionAccepted(transaction)) => GossipMessage::PrivateTransaction(transaction),
+                    Ok(ChainEvent::MultisigApprovalAdded(transaction)) => GossipMessage::MultisigApproval(transaction),
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                gossip.broadcast(message.encode()).await;
+            }
+        });
+    }
+
+    async fn broadcast(&self, frame: Vec<u8>) {
+        if !self.seen.lock().unwrap().insert(frame_hash(&frame)) {
+            return;
+        }
+        let mut peers = self.peers.lock().await;
+        // Un pair dont la file est pleine perd le message ; un pair déconnecté est retiré
+        peers.retain(|peer| !matches!(peer.outgoing.try_send(frame.clone()), Err(mpsc::error::TrySendError::Closed(_))));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use ed25519_dalek::Keypair;
+    use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use rand::rngs::OsRng;
+    use tokio::sync::{mpsc, Mutex};
+
+    use super::{Gossip, GossipMessage};
+    use crate::config::ChainConfig;
+    use crate::node::{NodeEvent, PendingTraces};
+    use crate::telemetry::{self, TraceContext};
+    use crate::privacy::{PrivacyGroup, PrivateProposal};
+    use crate::reputation::Reputation;
+    use crate::multisig::MultisigPolicy;
+    use crate::transport::{Link, Transports};
+    use crate::{Blockchain, Expiry, Transaction, TransactionKind, DEFAULT_GAS_PRICE};
+
+    // Liaison en mémoire entre deux nœuds, vue de chaque côté
+    fn link_pair() -> (Link, Link) {
+        let (to_b, from_a) = mpsc::channel(64);
+        let (to_a, from_b) = mpsc::channel(64);
+        (
+            Link { remote: "mem://b".to_string(), outgoing: to_b, incoming: from_b },
+            Link { remote: "mem://a".to_string(), outgoing: to_a, incoming: from_a },
+        )
+    }
+
+    // Nœud d'origine, relié en mémoire à un pair dont on observe les événements ;
+    // l'origine relaye ses transactions avec leur contexte de trace dans `traces`
+    async fn connected(validator: &Keypair, traces: PendingTraces) -> (Arc<Mutex<Blockchain>>, Arc<Mutex<Blockchain>>, mpsc::Receiver<NodeEvent>) {
+        let chain = || {
+            let mut chain = Blockchain::new(ChainConfig::default());
+            chain.register_validator(&validator.public, 1000).unwrap();
+            Arc::new(Mutex::new(chain))
+        };
+        let (origin, peer) = (chain(), chain());
+        let (origin_events, _) = mpsc::channel(16);
+        let (peer_events, peer_received) = mpsc::channel(16);
+        let origin_gossip = Gossip::new(Transports::default(), origin_events, origin.clone(), Reputation::default());
+        let peer_gossip = Gossip::new(Transports::default(), peer_events, peer.clone(), Reputation::default());
+        origin_gossip.relay(origin.lock().await.subscribe(), traces);
+        let (origin_link, peer_link) = link_pair();
+        origin_gossip.attach(origin_link).await;
+        peer_gossip.attach(peer_link).await;
+        (origin, peer, peer_received)
+    }
+
+    // Prochain message relayé au pair, après l'annonce d'heure de l'origine
+    async fn next_relayed(received: &mut mpsc::Receiver<NodeEvent>) -> NodeEvent {
+        loop {
+            match tokio::time::timeout(Duration::from_secs(5), received.recv()).await.unwrap() {
+                Some(NodeEvent::PeerTime { peer, .. }) => assert_eq!(peer, "mem://a"),
+                Some(event) => return event,
+                None => panic!("Gossip stopped"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn private_transaction_is_relayed_and_included_by_a_peer() {
+        let validator = Keypair::generate(&mut OsRng);
+        let members = [Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng)];
+        let (origin, peer, mut peer_received) = connected(&validator, PendingTraces::default()).await;
+
+        // Contenu chiffré pour le groupe, lu puis approuvé par chaque membre
+        let group = PrivacyGroup::new(members.iter().map(|member| member.public).collect()).unwrap();
+        let mut proposal = PrivateProposal::new(&group, b"lot 42 : 12 palettes", &[]).unwrap();
+        assert!(proposal.transaction().is_err());
+        for member in &members {
+            assert_eq!(proposal.endorse(member).unwrap(), b"lot 42 : 12 palettes");
+        }
+        let transaction = PrivateProposal::from_bytes(&proposal.to_bytes()).unwrap().transaction().unwrap();
+        origin.lock().await.add_private_transaction(transaction.clone()).unwrap();
+        assert_eq!(origin.lock().await.add_private_transaction(transaction.clone()), Err("Private transaction already pending"));
+
+        let NodeEvent::PrivateTransaction(relayed) = next_relayed(&mut peer_received).await else {
+            panic!("Expected a private transaction");
+        };
+        assert_eq!(relayed.hash(), transaction.hash());
+        let mut peer = peer.lock().await;
+        peer.add_private_transaction(relayed).unwrap();
+        let block = peer.validate_and_create_block(&validator, 1).unwrap();
+        assert_eq!(block.private_transactions.len(), 1);
+        assert_eq!(peer.private_state(&group.id()), Some(&proposal.new_state()));
+    }
+
+    #[tokio::test]
+    async fn multisig_approvals_are_relayed_until_the_threshold() {
+        let validator = Keypair::generate(&mut OsRng);
+        let members: Vec<Keypair> = (0..3).map(|_| Keypair::generate(&mut OsRng)).collect();
+        let policy = MultisigPolicy::new(2, members.iter().map(|member| member.public).collect()).unwrap();
+        let (origin, peer, mut peer_received) = connected(&validator, PendingTraces::default()).await;
+        let now = origin.lock().await.now();
+        let unsigned = Transaction::multisig(ChainConfig::default().chain_id, policy, 0, &members[0].public, 10, TransactionKind::Transfer, DEFAULT_GAS_PRICE, now, Expiry::Timestamp(now + 60));
+        for chain in [&origin, &peer] {
+            chain.lock().await.allocate(&unsigned.sender, 1_000_000);
+        }
+
+        // Une signature reçue par l'origine parvient au pair, qui complète la transaction
+        let mut first = unsigned.clone();
+        first.cosign(&members[0]).unwrap();
+        assert_eq!(origin.lock().await.add_multisig_approval(first), Ok(false));
+        let NodeEvent::MultisigApproval(relayed) = next_relayed(&mut peer_received).await else {
+            panic!("Expected a multisig approval");
+        };
+        let mut peer = peer.lock().await;
+        assert_eq!(peer.add_multisig_approval(relayed), Ok(false));
+        let mut second = unsigned.clone();
+        second.cosign(&members[2]).unwrap();
+        assert_eq!(peer.add_multisig_approval(second), Ok(true));
+        assert_eq!(peer.pending_transactions.iter().map(Transaction::hash).collect::<Vec<_>>(), vec![unsigned.hash()]);
+    }
+
+    #[tokio::test]
+    async fn transactions_carry_their_trace_context_to_peers() {
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        let validator = Keypair::generate(&mut OsRng);
+        let sender = Keypair::generate(&mut OsRng);
+        let traces = PendingTraces::default();
+        let (origin, _, mut peer_received) = connected(&validator, traces.clone()).await;
+        let now = origin.lock().await.now();
+        let transaction = Transaction::signed(ChainConfig::default().chain_id, &sender, 0, &validator.public, 10, TransactionKind::Transfer, DEFAULT_GAS_PRICE, now, Expiry::Timestamp(now + 60));
+
+        // Contexte du span d'admission de la transaction par l'origine
+        let span = SpanContext::new(
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap(),
+            SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        traces.lock().unwrap().insert(transaction.hash(), opentelemetry::Context::new().with_remote_span_context(span.clone()));
+        let mut chain = origin.lock().await;
+        chain.allocate(&sender.public, 1_000_000);
+        chain.add_transaction(transaction.clone()).unwrap();
+        drop(chain);
+
+        let NodeEvent::Transaction { transaction: relayed, trace } = next_relayed(&mut peer_received).await else {
+            panic!("Expected a transaction");
+        };
+        assert_eq!(relayed.hash(), transaction.hash());
+        assert_eq!(trace.get("traceparent").map(String::as_str), Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"));
+        // Le pair rattache son admission à la trace de l'origine
+        assert_eq!(telemetry::extract(&trace).span().span_context().trace_id(), span.trace_id());
+    }
+
+    #[test]
+    fn transaction_round_trip() {
+        let sender = Keypair::generate(&mut OsRng);
+        let transaction = Transaction::signed(1, &sender, 0, &sender.public, 10, TransactionKind::Transfer, DEFAULT_GAS_PRICE, 0, Expiry::Never);
+        let trace: TraceContext = [("traceparent".to_string(), "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string())].into();
+        let frame = GossipMessage::Transaction { transaction: transaction.clone(), trace: trace.clone() }.encode();
+        let Ok(GossipMessage::Transaction { transaction: decoded, trace: decoded_trace }) = GossipMessage::decode(&frame) else {
+            panic!("Expected a transaction");
+        };
+        assert_eq!((decoded, decoded_trace), (transaction.clone(), trace));
+
+        // Contexte de trace borné
+        let oversized: TraceContext = (0..5).map(|entry| (entry.to_string(), String::new())).collect();
+        let frame = GossipMessage::Transaction { transaction: transaction.clone(), trace: oversized }.encode();
+        assert_eq!(GossipMessage::decode(&frame).err(), Some("Too many trace entries"));
+        let long: TraceContext = [("tracestate".to_string(), "x".repeat(513))].into();
+        let frame = GossipMessage::Transaction { transaction, trace: long }.encode();
+        assert_eq!(GossipMessage::decode(&frame).err(), Some("Trace entry too long"));
+    }
+
+    #[test]
+    fn time_round_trip() {
+        let frame = GossipMessage::Time(1_700_000_000).encode();
+        assert!(matches!(GossipMessage::decode(&frame), Ok(GossipMessage::Time(1_700_000_000))));
+        assert!(GossipMessage::decode(&frame[..frame.len() - 1]).is_err());
+        // Une annonce d'heure n'est jamais relayée au nœud comme un message diffusé
+        assert!(GossipMessage::Time(0).into_node_event().is_none());
+    }
+}
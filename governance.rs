@@ -0,0 +1,621 @@
+use std::collections::{HashMap, HashSet};
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, Verifier};
+
+use crate::beacon::{BeaconCommit, BeaconReveal};
+use crate::checkpoint::has_quorum;
+use crate::compliance::FreezeOrder;
+use crate::encoding::{Decoder, Encoder, GOVERNANCE_TRANSACTION_DOMAIN, PROPOSAL_DOMAIN, VOTE_DOMAIN};
+use crate::incidents::{IncidentClose, IncidentNote, IncidentOpen};
+use crate::liveness::Reactivation;
+use crate::privacy::{EncryptedPayload, PrivacyGroup};
+use crate::rewards::CommissionChange;
+use crate::rotation::KeyRotation;
+use crate::{Validator, EPOCH_LENGTH};
+
+// Durée du vote, en blocs, à partir de l'inclusion de la proposition
+const VOTING_PERIOD: u64 = EPOCH_LENGTH;
+
+// Paramètres de consensus modifiables par la gouvernance
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConsensusParams {
+    pub min_registration_stake: u64,
+    pub min_producer_stake: u64,
+    pub max_block_transactions: u64,
+    // Somme du gaz des transactions d'un bloc
+    pub max_block_gas: u64,
+    // Part de la mise (en pourcentage) devant signaler une version pour l'activer
+    pub upgrade_threshold: u64,
+    // Nombre d'époques consécutives où ce seuil doit être atteint
+    pub upgrade_epochs: u64,
+    // Taille de l'ensemble actif ; les validateurs au-delà attendent en réserve
+    pub max_active_validators: u64,
+}
+
+impl Default for ConsensusParams {
+    fn default() -> Self {
+        ConsensusParams {
+            min_registration_stake: 500,
+            min_producer_stake: 1000,
+            max_block_transactions: 10_000,
+            max_block_gas: 50_000_000,
+            upgrade_threshold: 80,
+            upgrade_epochs: 2,
+            max_active_validators: 100,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParameterChange {
+    MinRegistrationStake(u64),
+    MinProducerStake(u64),
+    MaxBlockTransactions(u64),
+    MaxBlockGas(u64),
+    UpgradeThreshold(u64),
+    UpgradeEpochs(u64),
+    MaxActiveValidators(u64),
+}
+
+impl ParameterChange {
+    fn apply(&self, params: &mut ConsensusParams) {
+        match *self {
+            ParameterChange::MinRegistrationStake(value) => params.min_registration_stake = value,
+            ParameterChange::MinProducerStake(value) => params.min_producer_stake = value,
+            ParameterChange::MaxBlockTransactions(value) => params.max_block_transactions = value,
+            ParameterChange::MaxBlockGas(value) => params.max_block_gas = value,
+            ParameterChange::UpgradeThreshold(value) => params.upgrade_threshold = value,
+            ParameterChange::UpgradeEpochs(value) => params.upgrade_epochs = value,
+            ParameterChange::MaxActiveValidators(value) => params.max_active_validators = value,
+        }
+    }
+
+    fn encode_into(&self, encoder: &mut Encoder) {
+        let (tag, value) = match *self {
+            ParameterChange::MinRegistrationStake(value) => (0, value),
+            ParameterChange::MinProducerStake(value) => (1, value),
+            ParameterChange::MaxBlockTransactions(value) => (2, value),
+            ParameterChange::MaxBlockGas(value) => (3, value),
+            ParameterChange::UpgradeThreshold(value) => (4, value),
+            ParameterChange::UpgradeEpochs(value) => (5, value),
+            ParameterChange::MaxActiveValidators(value) => (6, value),
+        };
+        encoder.put_u8(tag);
+        encoder.put_u64(value);
+    }
+
+    fn decode_from(decoder: &mut Decoder) -> Result<ParameterChange, &'static str> {
+        let tag = decoder.get_u8()?;
+        let value = decoder.get_u64()?;
+        match tag {
+            0 => Ok(ParameterChange::MinRegistrationStake(value)),
+            1 => Ok(ParameterChange::MinProducerStake(value)),
+            2 => Ok(ParameterChange::MaxBlockTransactions(value)),
+            3 => Ok(ParameterChange::MaxBlockGas(value)),
+            4 => Ok(ParameterChange::UpgradeThreshold(value)),
+            5 => Ok(ParameterChange::UpgradeEpochs(value)),
+            6 => Ok(ParameterChange::MaxActiveValidators(value)),
+            _ => Err("Unknown parameter change"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Proposal {
+    pub proposer: PublicKey,
+    pub change: ParameterChange,
+    pub timestamp: u64,
+    pub signature: Signature,
+}
+
+impl Proposal {
+    pub fn new(keypair: &Keypair, change: ParameterChange, timestamp: u64) -> Self {
+        let signature = keypair.sign(&Self::signing_hash(&keypair.public, &change, timestamp));
+        Proposal { proposer: keypair.public, change, timestamp, signature }
+    }
+
+    fn signing_hash(proposer: &PublicKey, change: &ParameterChange, timestamp: u64) -> Vec<u8> {
+        let mut encoder = Encoder::new(PROPOSAL_DOMAIN);
+        encoder.put_public_key(proposer);
+        change.encode_into(&mut encoder);
+        encoder.put_u64(timestamp);
+        encoder.hash()
+    }
+
+    // Identifiant de la proposition, référencé par les votes
+    pub fn id(&self) -> Vec<u8> {
+        Self::signing_hash(&self.proposer, &self.change, self.timestamp)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Vote {
+    pub proposal_id: Vec<u8>,
+    pub voter: PublicKey,
+    pub approve: bool,
+    pub signature: Signature,
+}
+
+impl Vote {
+    pub fn new(keypair: &Keypair, proposal_id: Vec<u8>, approve: bool) -> Self {
+        let signature = keypair.sign(&Self::signing_hash(&proposal_id, &keypair.public, approve));
+        Vote { proposal_id, voter: keypair.public, approve, signature }
+    }
+
+    fn signing_hash(proposal_id: &[u8], voter: &PublicKey, approve: bool) -> Vec<u8> {
+        let mut encoder = Encoder::new(VOTE_DOMAIN);
+        encoder.put_bytes(proposal_id);
+        encoder.put_public_key(voter);
+        encoder.put_u8(approve as u8);
+        encoder.hash()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum GovernanceTransaction {
+    Proposal(Proposal),
+    Vote(Vote),
+    // Gel ou dégel d'un compte par un administrateur de conformité
+    Freeze(FreezeOrder),
+    // Contributions d'un validateur à la balise d'aléa de l'époque
+    BeaconCommit(BeaconCommit),
+    BeaconReveal(BeaconReveal),
+    // Chronologie d'un incident d'exploitation
+    IncidentOpen(IncidentOpen),
+    IncidentNote(IncidentNote),
+    IncidentClose(IncidentClose),
+    // Retour d'un validateur désactivé pour créneaux manqués
+    Reactivation(Reactivation),
+    // Remplacement de la clé d'un validateur
+    RotateKey(KeyRotation),
+    // Commission prélevée par un validateur sur ses récompenses
+    SetCommission(CommissionChange),
+}
+
+impl GovernanceTransaction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GovernanceTransaction::Proposal(_) => "proposal",
+            GovernanceTransaction::Vote(_) => "vote",
+            GovernanceTransaction::Freeze(_) => "freeze",
+            GovernanceTransaction::BeaconCommit(_) => "beacon_commit",
+            GovernanceTransaction::BeaconReveal(_) => "beacon_reveal",
+            GovernanceTransaction::IncidentOpen(_) => "incident_open",
+            GovernanceTransaction::IncidentNote(_) => "incident_note",
+            GovernanceTransaction::IncidentClose(_) => "incident_close",
+            GovernanceTransaction::Reactivation(_) => "reactivation",
+            GovernanceTransaction::RotateKey(_) => "rotate_key",
+            GovernanceTransaction::SetCommission(_) => "set_commission",
+        }
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        match self {
+            GovernanceTransaction::Proposal(proposal) => proposal.id(),
+            GovernanceTransaction::Vote(vote) => Vote::signing_hash(&vote.proposal_id, &vote.voter, vote.approve),
+            GovernanceTransaction::Freeze(order) => order.hash(),
+            GovernanceTransaction::BeaconCommit(commit) => commit.hash(),
+            GovernanceTransaction::BeaconReveal(reveal) => reveal.hash(),
+            GovernanceTransaction::IncidentOpen(open) => open.id(),
+            GovernanceTransaction::IncidentNote(note) => note.hash(),
+            GovernanceTransaction::IncidentClose(close) => close.hash(),
+            GovernanceTransaction::Reactivation(reactivation) => reactivation.hash(),
+            GovernanceTransaction::RotateKey(rotation) => rotation.hash(),
+            GovernanceTransaction::SetCommission(change) => change.hash(),
+        }
+    }
+
+    pub fn verify(&self) -> Result<(), &'static str> {
+        let (key, signature) = match self {
+            GovernanceTransaction::Proposal(proposal) => (&proposal.proposer, &proposal.signature),
+            GovernanceTransaction::Vote(vote) => (&vote.voter, &vote.signature),
+            GovernanceTransaction::Freeze(order) => return order.verify(),
+            GovernanceTransaction::BeaconCommit(commit) => (&commit.validator, &commit.signature),
+            GovernanceTransaction::BeaconReveal(reveal) => (&reveal.validator, &reveal.signature),
+            GovernanceTransaction::IncidentOpen(open) => (&open.opener, &open.signature),
+            GovernanceTransaction::IncidentNote(note) => (&note.author, &note.signature),
+            GovernanceTransaction::IncidentClose(close) => (&close.author, &close.signature),
+            GovernanceTransaction::Reactivation(reactivation) => (&reactivation.validator, &reactivation.signature),
+            GovernanceTransaction::RotateKey(rotation) => (&rotation.validator, &rotation.signature),
+            GovernanceTransaction::SetCommission(change) => (&change.validator, &change.signature),
+        };
+        key.verify(&self.hash(), signature).map_err(|_| "Invalid governance signature")
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        match self {
+            GovernanceTransaction::Proposal(proposal) => {
+                encoder.put_u8(0);
+                encoder.put_public_key(&proposal.proposer);
+                proposal.change.encode_into(encoder);
+                encoder.put_u64(proposal.timestamp);
+                encoder.put_signature(&proposal.signature);
+            }
+            GovernanceTransaction::Vote(vote) => {
+                encoder.put_u8(1);
+                encoder.put_bytes(&vote.proposal_id);
+                encoder.put_public_key(&vote.voter);
+                encoder.put_u8(vote.approve as u8);
+                encoder.put_signature(&vote.signature);
+            }
+            GovernanceTransaction::Freeze(order) => {
+                encoder.put_u8(2);
+                encoder.put_public_key(&order.issuer);
+                encoder.put_public_key(&order.account);
+                encoder.put_u8(order.freeze as u8);
+                encoder.put_bytes(&order.reason_hash);
+                encoder.put_u64(order.timestamp);
+                encoder.put_signature(&order.signature);
+            }
+            GovernanceTransaction::BeaconCommit(commit) => {
+                encoder.put_u8(3);
+                encoder.put_public_key(&commit.validator);
+                encoder.put_u64(commit.epoch);
+                encoder.put_bytes(&commit.commitment);
+                encoder.put_signature(&commit.signature);
+            }
+            GovernanceTransaction::BeaconReveal(reveal) => {
+                encoder.put_u8(4);
+                encoder.put_public_key(&reveal.validator);
+                encoder.put_u64(reveal.epoch);
+                encoder.put_bytes(&reveal.secret);
+                encoder.put_signature(&reveal.signature);
+            }
+            GovernanceTransaction::IncidentOpen(open) => {
+                encoder.put_u8(5);
+                encoder.put_public_key(&open.opener);
+                encoder.put_u32(open.responders.members.len() as u32);
+                for member in &open.responders.members {
+                    encoder.put_public_key(member);
+                }
+                encoder.put_u64(open.timestamp);
+                encoder.put_signature(&open.signature);
+            }
+            GovernanceTransaction::IncidentNote(note) => {
+                encoder.put_u8(6);
+                encoder.put_bytes(&note.incident_id);
+                encoder.put_public_key(&note.author);
+                note.note.encode_into(encoder);
+                encoder.put_u64(note.timestamp);
+                encoder.put_signature(&note.signature);
+            }
+            GovernanceTransaction::IncidentClose(close) => {
+                encoder.put_u8(7);
+                encoder.put_bytes(&close.incident_id);
+                encoder.put_public_key(&close.author);
+                encoder.put_u64(close.timestamp);
+                encoder.put_signature(&close.signature);
+            }
+            GovernanceTransaction::Reactivation(reactivation) => {
+                encoder.put_u8(8);
+                encoder.put_public_key(&reactivation.validator);
+                encoder.put_u64(reactivation.inactive_since);
+                encoder.put_signature(&reactivation.signature);
+            }
+            GovernanceTransaction::RotateKey(rotation) => {
+                encoder.put_u8(9);
+                encoder.put_public_key(&rotation.validator);
+                encoder.put_public_key(&rotation.new_key);
+                encoder.put_signature(&rotation.signature);
+            }
+            GovernanceTransaction::SetCommission(change) => {
+                encoder.put_u8(10);
+                encoder.put_public_key(&change.validator);
+                encoder.put_u64(change.percent);
+                encoder.put_u64(change.epoch);
+                encoder.put_signature(&change.signature);
+            }
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<GovernanceTransaction, &'static str> {
+        match decoder.get_u8()? {
+            0 => Ok(GovernanceTransaction::Proposal(Proposal {
+                proposer: decoder.get_public_key()?,
+                change: ParameterChange::decode_from(decoder)?,
+                timestamp: decoder.get_u64()?,
+                signature: decoder.get_signature()?,
+            })),
+            1 => Ok(GovernanceTransaction::Vote(Vote {
+                proposal_id: decoder.get_bytes()?.to_vec(),
+                voter: decoder.get_public_key()?,
+                approve: decode_flag(decoder)?,
+                signature: decoder.get_signature()?,
+            })),
+            2 => Ok(GovernanceTransaction::Freeze(FreezeOrder {
+                issuer: decoder.get_public_key()?,
+                account: decoder.get_public_key()?,
+                freeze: decode_flag(decoder)?,
+                reason_hash: decoder.get_bytes()?.to_vec(),
+                timestamp: decoder.get_u64()?,
+                signature: decoder.get_signature()?,
+            })),
+            3 => Ok(GovernanceTransaction::BeaconCommit(BeaconCommit {
+                validator: decoder.get_public_key()?,
+                epoch: decoder.get_u64()?,
+                commitment: decoder.get_bytes()?.to_vec(),
+                signature: decoder.get_signature()?,
+            })),
+            4 => Ok(GovernanceTransaction::BeaconReveal(BeaconReveal {
+                validator: decoder.get_public_key()?,
+                epoch: decoder.get_u64()?,
+                secret: decoder.get_bytes()?.to_vec(),
+                signature: decoder.get_signature()?,
+            })),
+            5 => {
+                let opener = decoder.get_public_key()?;
+                let mut members = Vec::new();
+                for _ in 0..decoder.get_u32()? {
+                    members.push(decoder.get_public_key()?);
+                }
+                Ok(GovernanceTransaction::IncidentOpen(IncidentOpen {
+                    opener,
+                    responders: PrivacyGroup { members },
+                    timestamp: decoder.get_u64()?,
+                    signature: decoder.get_signature()?,
+                }))
+            }
+            6 => Ok(GovernanceTransaction::IncidentNote(IncidentNote {
+                incident_id: decoder.get_bytes()?.to_vec(),
+                author: decoder.get_public_key()?,
+                note: EncryptedPayload::decode_from(decoder)?,
+                timestamp: decoder.get_u64()?,
+                signature: decoder.get_signature()?,
+            })),
+            7 => Ok(GovernanceTransaction::IncidentClose(IncidentClose {
+                incident_id: decoder.get_bytes()?.to_vec(),
+                author: decoder.get_public_key()?,
+                timestamp: decoder.get_u64()?,
+                signature: decoder.get_signature()?,
+            })),
+            8 => Ok(GovernanceTransaction::Reactivation(Reactivation {
+                validator: decoder.get_public_key()?,
+                inactive_since: decoder.get_u64()?,
+                signature: decoder.get_signature()?,
+            })),
+            9 => Ok(GovernanceTransaction::RotateKey(KeyRotation {
+                validator: decoder.get_public_key()?,
+                new_key: decoder.get_public_key()?,
+                signature: decoder.get_signature()?,
+            })),
+            10 => Ok(GovernanceTransaction::SetCommission(CommissionChange {
+                validator: decoder.get_public_key()?,
+                percent: decoder.get_u64()?,
+                epoch: decoder.get_u64()?,
+                signature: decoder.get_signature()?,
+            })),
+            _ => Err("Unknown governance transaction"),
+        }
+    }
+
+    // Transaction signée hors du nœud, soumise par `SubmitGovernanceTransaction`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(GOVERNANCE_TRANSACTION_DOMAIN);
+        self.encode_into(&mut encoder);
+        encoder.finish()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<GovernanceTransaction, &'static str> {
+        let mut decoder = Decoder::new(data, GOVERNANCE_TRANSACTION_DOMAIN)?;
+        let transaction = GovernanceTransaction::decode_from(&mut decoder)?;
+        decoder.finish()?;
+        Ok(transaction)
+    }
+}
+
+fn decode_flag(decoder: &mut Decoder) -> Result<bool, &'static str> {
+    match decoder.get_u8()? {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err("Invalid boolean flag"),
+    }
+}
+
+#[derive(Clone, Debug)]
+struct OpenProposal {
+    proposal: Proposal,
+    created_at: u64,
+    // Dans l'ordre d'inclusion
+    votes: Vec<(PublicKey, bool)>,
+}
+
+// État de la gouvernance : paramètres en vigueur, propositions en cours de vote
+// et changements acceptés en attente de la prochaine époque
+#[derive(Clone, Debug, Default)]
+pub struct Governance {
+    pub params: ConsensusParams,
+    proposals: HashMap<Vec<u8>, OpenProposal>,
+    scheduled: Vec<ParameterChange>,
+}
+
+impl Governance {
+    pub fn new(params: ConsensusParams) -> Self {
+        Governance { params, ..Governance::default() }
+    }
+
+    // Propositions et votes de validateurs enregistrés, chaque validateur votant
+    // au plus une fois par proposition ouverte ; réactivation d'un validateur
+    // inactif depuis la hauteur signée, au plus une fois par suite
+    pub fn validate(&self, validators: &HashMap<PublicKey, Validator>, transactions: &[GovernanceTransaction]) -> Result<(), &'static str> {
+        let mut new_proposals = HashSet::new();
+        let mut new_votes = HashSet::new();
+        let mut reactivated = HashSet::new();
+        for transaction in transactions {
+            transaction.verify()?;
+            match transaction {
+                GovernanceTransaction::Proposal(proposal) => {
+                    if !validators.contains_key(&proposal.proposer) {
+                        return Err("Proposal from unknown validator");
+                    }
+                    let id = proposal.id();
+                    if self.proposals.contains_key(&id) || !new_proposals.insert(id) {
+                        return Err("Duplicate proposal");
+                    }
+                }
+                GovernanceTransaction::Vote(vote) => {
+                    if !validators.contains_key(&vote.voter) {
+                        return Err("Vote from unknown validator");
+                    }
+                    let already_voted = match self.proposals.get(&vote.proposal_id) {
+                        Some(open) => open.votes.iter().any(|(voter, _)| *voter == vote.voter),
+                        None if new_proposals.contains(&vote.proposal_id) => false,
+                        None => return Err("Vote for unknown or closed proposal"),
+                    };
+                    if already_voted || !new_votes.insert((vote.proposal_id.clone(), vote.voter.to_bytes())) {
+                        return Err("Duplicate vote");
+                    }
+                }
+                GovernanceTransaction::Reactivation(reactivation) => {
+                    let validator = validators.get(&reactivation.validator).ok_or("Reactivation of unknown validator")?;
+                    if validator.inactive_since != Some(reactivation.inactive_since) {
+                        return Err("Validator not inactive since this height");
+                    }
+                    if !reactivated.insert(reactivation.validator.to_bytes()) {
+                        return Err("Duplicate reactivation");
+                    }
+                }
+                // Validés par `Compliance::validate`, `Beacon::validate`, `Incidents::validate`,
+                // `KeyHistory::validate` et `Rewards::validate`
+                GovernanceTransaction::Freeze(_)
+                | GovernanceTransaction::BeaconCommit(_)
+                | GovernanceTransaction::BeaconReveal(_)
+                | GovernanceTransaction::IncidentOpen(_)
+                | GovernanceTransaction::IncidentNote(_)
+                | GovernanceTransaction::IncidentClose(_)
+                | GovernanceTransaction::RotateKey(_)
+                | GovernanceTransaction::SetCommission(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    // Les votes en cours d'un validateur dont la clé change restent comptés
+    pub fn rekey(&mut self, old_key: &PublicKey, new_key: PublicKey) {
+        for open in self.proposals.values_mut() {
+            if open.votes.iter().any(|(voter, _)| *voter == new_key) {
+                open.votes.retain(|(voter, _)| voter != old_key);
+            } else if let Some((voter, _)) = open.votes.iter_mut().find(|(voter, _)| voter == old_key) {
+                *voter = new_key;
+            }
+        }
+    }
+
+    // Applique les transactions (déjà validées) du bloc `height`, dépouille les
+    // votes arrivés à échéance et, en fin d'époque, active les changements acceptés.
+    // Retourne les nouveaux paramètres s'ils ont changé.
+    pub fn apply(&mut self, validators: &HashMap<PublicKey, Validator>, transactions: &[GovernanceTransaction], height: u64) -> Option<ConsensusParams> {
+        for transaction in transactions {
+            match transaction {
+                GovernanceTransaction::Proposal(proposal) => {
+                    self.proposals.insert(proposal.id(), OpenProposal {
+                        proposal: proposal.clone(),
+                        created_at: height,
+                        votes: Vec::new(),
+                    });
+                }
+                GovernanceTransaction::Vote(vote) => {
+                    if let Some(open) = self.proposals.get_mut(&vote.proposal_id) {
+                        open.votes.push((vote.voter, vote.approve));
+                    }
+                }
+                GovernanceTransaction::Freeze(_)
+                | GovernanceTransaction::BeaconCommit(_)
+                | GovernanceTransaction::BeaconReveal(_)
+                | GovernanceTransaction::IncidentOpen(_)
+                | GovernanceTransaction::IncidentNote(_)
+                | GovernanceTransaction::IncidentClose(_)
+                | GovernanceTransaction::Reactivation(_)
+                | GovernanceTransaction::RotateKey(_)
+                | GovernanceTransaction::SetCommission(_) => {}
+            }
+        }
+
+        let mut closed: Vec<OpenProposal> = Vec::new();
+        self.proposals.retain(|_, open| {
+            if open.created_at + VOTING_PERIOD <= height {
+                closed.push(open.clone());
+                false
+            } else {
+                true
+            }
+        });
+        closed.sort_by_key(|open| (open.created_at, open.proposal.id()));
+        for open in closed {
+            // Pondération par la mise au moment du dépouillement
+            let approving_stake: u128 = open.votes.iter()
+                .filter(|(_, approve)| *approve)
+                .filter_map(|(voter, _)| validators.get(voter))
+                .map(|v| v.stake as u128)
+                .sum();
+            if has_quorum(approving_stake, validators) {
+                self.scheduled.push(open.proposal.change);
+            }
+        }
+
+        if height % EPOCH_LENGTH != 0 || self.scheduled.is_empty() {
+            return None;
+        }
+        for change in std::mem::take(&mut self.scheduled) {
+            change.apply(&mut self.params);
+        }
+        Some(self.params.clone())
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        encoder.put_u64(self.params.min_registration_stake);
+        encoder.put_u64(self.params.min_producer_stake);
+        encoder.put_u64(self.params.max_block_transactions);
+        encoder.put_u64(self.params.max_block_gas);
+        encoder.put_u64(self.params.upgrade_threshold);
+        encoder.put_u64(self.params.upgrade_epochs);
+        encoder.put_u64(self.params.max_active_validators);
+
+        let mut proposals: Vec<&OpenProposal> = self.proposals.values().collect();
+        proposals.sort_by_key(|open| open.proposal.id());
+        encoder.put_u32(proposals.len() as u32);
+        for open in proposals {
+            GovernanceTransaction::Proposal(open.proposal.clone()).encode_into(encoder);
+            encoder.put_u64(open.created_at);
+            encoder.put_u32(open.votes.len() as u32);
+            for (voter, approve) in &open.votes {
+                encoder.put_public_key(voter);
+                encoder.put_u8(*approve as u8);
+            }
+        }
+
+        encoder.put_u32(self.scheduled.len() as u32);
+        for change in &self.scheduled {
+            change.encode_into(encoder);
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Governance, &'static str> {
+        let params = ConsensusParams {
+            min_registration_stake: decoder.get_u64()?,
+            min_producer_stake: decoder.get_u64()?,
+            max_block_transactions: decoder.get_u64()?,
+            max_block_gas: decoder.get_u64()?,
+            upgrade_threshold: decoder.get_u64()?,
+            upgrade_epochs: decoder.get_u64()?,
+            max_active_validators: decoder.get_u64()?,
+        };
+
+        let mut proposals = HashMap::new();
+        for _ in 0..decoder.get_u32()? {
+            let GovernanceTransaction::Proposal(proposal) = GovernanceTransaction::decode_from(decoder)? else {
+                return Err("Expected a proposal");
+            };
+            let created_at = decoder.get_u64()?;
+            let mut votes = Vec::new();
+            for _ in 0..decoder.get_u32()? {
+                votes.push((decoder.get_public_key()?, decode_flag(decoder)?));
+            }
+            proposals.insert(proposal.id(), OpenProposal { proposal, created_at, votes });
+        }
+
+        let mut scheduled = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            scheduled.push(ParameterChange::decode_from(decoder)?);
+        }
+        Ok(Governance { params, proposals, scheduled })
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer};
+
+use crate::encoding::{Decoder, Encoder, INCIDENT_DOMAIN};
+use crate::governance::GovernanceTransaction;
+use crate::privacy::{EncryptedPayload, PrivacyGroup};
+use crate::Validator;
+
+// Taille maximale d'une note chiffrée
+pub const MAX_NOTE_SIZE: usize = 4096;
+
+// Ouverture d'un incident par un validateur : les intervenants forment le
+// groupe auquel les notes sont chiffrées
+#[derive(Clone, Debug)]
+pub struct IncidentOpen {
+    pub opener: PublicKey,
+    pub responders: PrivacyGroup,
+    pub timestamp: u64,
+    pub signature: Signature,
+}
+
+impl IncidentOpen {
+    pub fn new(keypair: &Keypair, responders: PrivacyGroup, timestamp: u64) -> Self {
+        let signature = keypair.sign(&Self::signing_hash(&keypair.public, &responders, timestamp));
+        IncidentOpen { opener: keypair.public, responders, timestamp, signature }
+    }
+
+    pub fn signing_hash(opener: &PublicKey, responders: &PrivacyGroup, timestamp: u64) -> Vec<u8> {
+        let mut encoder = Encoder::new(INCIDENT_DOMAIN);
+        encoder.put_u8(0);
+        encoder.put_public_key(opener);
+        encoder.put_bytes(&responders.id());
+        encoder.put_u64(timestamp);
+        encoder.hash()
+    }
+
+    // Identifiant de l'incident, référencé par les notes et la clôture
+    pub fn id(&self) -> Vec<u8> {
+        Self::signing_hash(&self.opener, &self.responders, self.timestamp)
+    }
+}
+
+// Note chiffrée d'un intervenant, ancrée à la hauteur du bloc qui l'inclut
+#[derive(Clone, Debug)]
+pub struct IncidentNote {
+    pub incident_id: Vec<u8>,
+    pub author: PublicKey,
+    pub note: EncryptedPayload,
+    pub timestamp: u64,
+    pub signature: Signature,
+}
+
+impl IncidentNote {
+    // `note` est chiffrée pour les intervenants de l'incident
+    pub fn new(keypair: &Keypair, incident_id: Vec<u8>, responders: &PrivacyGroup, note: &[u8], timestamp: u64) -> Result<Self, &'static str> {
+        let note = EncryptedPayload::seal(responders, note)?;
+        let signature = keypair.sign(&Self::signing_hash(&incident_id, &keypair.public, &note, timestamp));
+        Ok(IncidentNote { incident_id, author: keypair.public, note, timestamp, signature })
+    }
+
+    pub fn signing_hash(incident_id: &[u8], author: &PublicKey, note: &EncryptedPayload, timestamp: u64) -> Vec<u8> {
+        let mut encoder = Encoder::new(INCIDENT_DOMAIN);
+        encoder.put_u8(1);
+        encoder.put_bytes(incident_id);
+        encoder.put_public_key(author);
+        note.encode_into(&mut encoder);
+        encoder.put_u64(timestamp);
+        encoder.hash()
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Self::signing_hash(&self.incident_id, &self.author, &self.note, self.timestamp)
+    }
+}
+
+// Clôture d'un incident par l'un de ses intervenants ; plus aucune note n'est acceptée
+#[derive(Clone, Debug)]
+pub struct IncidentClose {
+    pub incident_id: Vec<u8>,
+    pub author: PublicKey,
+    pub timestamp: u64,
+    pub signature: Signature,
+}
+
+impl IncidentClose {
+    pub fn new(keypair: &Keypair, incident_id: Vec<u8>, timestamp: u64) -> Self {
+        let signature = keypair.sign(&Self::signing_hash(&incident_id, &keypair.public, timestamp));
+        IncidentClose { incident_id, author: keypair.public, timestamp, signature }
+    }
+
+    pub fn signing_hash(incident_id: &[u8], author: &PublicKey, timestamp: u64) -> Vec<u8> {
+        let mut encoder = Encoder::new(INCIDENT_DOMAIN);
+        encoder.put_u8(2);
+        encoder.put_bytes(incident_id);
+        encoder.put_public_key(author);
+        encoder.put_u64(timestamp);
+        encoder.hash()
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Self::signing_hash(&self.incident_id, &self.author, self.timestamp)
+    }
+}
+
+// Entrée de la chronologie : note (hash de la transaction) et hauteur d'inclusion
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimelineEntry {
+    pub author: PublicKey,
+    pub height: u64,
+    pub note_hash: Vec<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Incident {
+    pub opener: PublicKey,
+    pub responders: PrivacyGroup,
+    pub opened_at: u64,
+    pub timeline: Vec<TimelineEntry>,
+    // Hash chaîné de la chronologie : toute note retirée ou réordonnée le change
+    pub head: Vec<u8>,
+    pub closed_at: Option<u64>,
+}
+
+impl Incident {
+    fn append(&mut self, entry: TimelineEntry) {
+        let mut encoder = Encoder::new(INCIDENT_DOMAIN);
+        encoder.put_u8(3);
+        encoder.put_bytes(&self.head);
+        encoder.put_public_key(&entry.author);
+        encoder.put_u64(entry.height);
+        encoder.put_bytes(&entry.note_hash);
+        self.head = encoder.hash();
+        self.timeline.push(entry);
+    }
+}
+
+// Incidents d'exploitation : chronologies de notes chiffrées entre intervenants,
+// pour des post-mortems infalsifiables rattachés aux hauteurs de la chaîne
+#[derive(Clone, Debug, Default)]
+pub struct Incidents {
+    incidents: HashMap<Vec<u8>, Incident>,
+}
+
+impl Incidents {
+    pub fn get(&self, id: &[u8]) -> Option<&Incident> {
+        self.incidents.get(id)
+    }
+
+    // Règles des incidents, en tenant compte des transactions précédentes de la suite :
+    // - ouverture par un validateur enregistré, membre des intervenants, identifiant inédit
+    // - notes et clôture par un intervenant, sur un incident ouvert
+    // - note chiffrée pour exactement les intervenants de l'incident
+    pub fn validate(&self, validators: &HashMap<PublicKey, Validator>, transactions: &[GovernanceTransaction]) -> Result<(), &'static str> {
+        let mut opened: HashMap<Vec<u8>, &PrivacyGroup> = HashMap::new();
+        let mut closed: Vec<Vec<u8>> = Vec::new();
+        for transaction in transactions {
+            match transaction {
+                GovernanceTransaction::IncidentOpen(open) => {
+                    if !validators.contains_key(&open.opener) {
+                        return Err("Incident opened by unknown validator");
+                    }
+                    if PrivacyGroup::new(open.responders.members.clone())?.members != open.responders.members {
+                        return Err("Incident responders not canonical");
+                    }
+                    if !open.responders.is_member(&open.opener) {
+                        return Err("Incident opener must be a responder");
+                    }
+                    let id = open.id();
+                    if self.incidents.contains_key(&id) || opened.contains_key(&id) {
+                        return Err("Duplicate incident");
+                    }
+                    opened.insert(id, &open.responders);
+                }
+                GovernanceTransaction::IncidentNote(note) => {
+                    let responders = self.open_responders(&note.incident_id, &opened, &closed)?;
+                    if !responders.is_member(&note.author) {
+                        return Err("Incident note from non-responder");
+                    }
+                    if !note.note.wrapped_keys.iter().map(|(member, _)| member).eq(&responders.members) {
+                        return Err("Incident note not sealed to responders");
+                    }
+                    if note.note.ciphertext.len() > MAX_NOTE_SIZE {
+                        return Err("Incident note too large");
+                    }
+                }
+                GovernanceTransaction::IncidentClose(close) => {
+                    let responders = self.open_responders(&close.incident_id, &opened, &closed)?;
+                    if !responders.is_member(&close.author) {
+                        return Err("Incident closed by non-responder");
+                    }
+                    closed.push(close.incident_id.clone());
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn open_responders<'a>(&'a self, id: &[u8], opened: &HashMap<Vec<u8>, &'a PrivacyGroup>, closed: &[Vec<u8>]) -> Result<&'a PrivacyGroup, &'static str> {
+        let responders = match self.incidents.get(id) {
+            Some(incident) if incident.closed_at.is_some() => return Err("Incident already closed"),
+            Some(incident) => &incident.responders,
+            None => *opened.get(id).ok_or("Unknown incident")?,
+        };
+        if closed.iter().any(|closed| closed == id) {
+            return Err("Incident already closed");
+        }
+        Ok(responders)
+    }
+
+    // Applique les transactions (déjà validées) du bloc `height`
+    pub fn apply(&mut self, transactions: &[GovernanceTransaction], height: u64) {
+        for transaction in transactions {
+            match transaction {
+                GovernanceTransaction::IncidentOpen(open) => {
+                    self.incidents.insert(open.id(), Incident {
+                        opener: open.opener,
+                        responders: open.responders.clone(),
+                        opened_at: height,
+                        timeline: Vec::new(),
+                        head: Vec::new(),
+                        closed_at: None,
+                    });
+                }
+                GovernanceTransaction::IncidentNote(note) => {
+                    if let Some(incident) = self.incidents.get_mut(&note.incident_id) {
+                        incident.append(TimelineEntry { author: note.author, height, note_hash: note.hash() });
+                    }
+                }
+                GovernanceTransaction::IncidentClose(close) => {
+                    if let Some(incident) = self.incidents.get_mut(&close.incident_id) {
+                        incident.closed_at = Some(height);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        let mut incidents: Vec<(&Vec<u8>, &Incident)> = self.incidents.iter().collect();
+        incidents.sort_by(|a, b| a.0.cmp(b.0));
+        encoder.put_u32(incidents.len() as u32);
+        for (id, incident) in incidents {
+            encoder.put_bytes(id);
+            encoder.put_public_key(&incident.opener);
+            encoder.put_u32(incident.responders.members.len() as u32);
+            for member in &incident.responders.members {
+                encoder.put_public_key(member);
+            }
+            encoder.put_u64(incident.opened_at);
+            encoder.put_u32(incident.timeline.len() as u32);
+            for entry in &incident.timeline {
+                encoder.put_public_key(&entry.author);
+                encoder.put_u64(entry.height);
+                encoder.put_bytes(&entry.note_hash);
+            }
+            encoder.put_bytes(&incident.head);
+            match incident.closed_at {
+                Some(height) => {
+                    encoder.put_u8(1);
+                    encoder.put_u64(height);
+                }
+                None => encoder.put_u8(0),
+            }
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Incidents, &'static str> {
+        let mut incidents = HashMap::new();
+        let mut previous: Option<Vec<u8>> = None;
+        for _ in 0..decoder.get_u32()? {
+            let id = decoder.get_bytes()?.to_vec();
+            if previous.as_ref().is_some_and(|previous| *previous >= id) {
+                return Err("Incidents not in canonical order");
+            }
+            previous = Some(id.clone());
+            let opener = decoder.get_public_key()?;
+            let mut members = Vec::new();
+            for _ in 0..decoder.get_u32()? {
+                members.push(decoder.get_public_key()?);
+            }
+            let opened_at = decoder.get_u64()?;
+            let mut timeline = Vec::new();
+            for _ in 0..decoder.get_u32()? {
+                timeline.push(TimelineEntry {
+                    author: decoder.get_public_key()?,
+                    height: decoder.get_u64()?,
+                    note_hash: decoder.get_bytes()?.to_vec(),
+                });
+            }
+            let head = decoder.get_bytes()?.to_vec();
+            let closed_at = match decoder.get_u8()? {
+                0 => None,
+                1 => Some(decoder.get_u64()?),
+                _ => return Err("Invalid incident status"),
+            };
+            let incident = Incident { opener, responders: PrivacyGroup { members }, opened_at, timeline, head, closed_at };
+            incidents.insert(id, incident);
+        }
+        Ok(Incidents { incidents })
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::{BTreeMap, HashMap};
+use ed25519_dalek::PublicKey;
+
+use crate::{Block, Transaction, TransactionKind};
+
+// Taille maximale d'une page de résultats
+pub const MAX_PAGE_SIZE: usize = 100;
+
+// Page de résultats, du plus récent au plus ancien
+#[derive(Clone, Debug, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    // Décalage de la page suivante, absent sur la dernière page
+    pub next_offset: Option<usize>,
+}
+
+// Index secondaires des blocs appliqués depuis le démarrage (ou depuis
+// l'instantané de départ) : transactions par adresse, blocs par producteur et
+// ancrages de documents par empreinte et par adresse
+#[derive(Clone, Debug, Default)]
+pub struct Indexer {
+    // Hashes de transaction dans l'ordre de la chaîne, par adresse (ordonnées
+    // pour la recherche par préfixe)
+    transactions: BTreeMap<[u8; 32], Vec<Vec<u8>>>,
+    transaction_count: u64,
+    // Index des blocs produits, croissants
+    blocks: HashMap<PublicKey, Vec<u64>>,
+    // Hashes des transactions d'ancrage dans l'ordre de la chaîne, par empreinte
+    // de document et par émetteur ou destinataire
+    anchors: HashMap<Vec<u8>, Vec<Vec<u8>>>,
+    anchors_by_address: HashMap<PublicKey, Vec<Vec<u8>>>,
+}
+
+impl Indexer {
+    // Une transaction concerne son émetteur, son destinataire et le compte
+    // qu'elle récupère le cas échéant ; les transactions d'un lot sont indexées
+    // à la suite du lot
+    pub fn index_block(&mut self, block: &Block) {
+        let transactions: Vec<&Transaction> = block.transactions.iter()
+            .flat_map(|transaction| std::iter::once(transaction).chain(transaction.inner_transactions()))
+            .collect();
+        for transaction in &transactions {
+            let mut addresses = vec![transaction.sender, transaction.recipient];
+            if let TransactionKind::Recover { account } = transaction.kind {
+                addresses.push(account);
+            }
+            addresses.sort_by_key(|address| address.to_bytes());
+            addresses.dedup();
+            let hash = transaction.hash();
+            if let TransactionKind::Anchor { document, .. } = &transaction.kind {
+                self.anchors.entry(document.clone()).or_default().push(hash.clone());
+                for address in &addresses {
+                    self.anchors_by_address.entry(*address).or_default().push(hash.clone());
+                }
+            }
+            for address in addresses {
+                self.transactions.entry(address.to_bytes()).or_default().push(hash.clone());
+            }
+        }
+        self.transaction_count += transactions.len() as u64;
+        self.blocks.entry(block.validator_pubkey).or_default().push(block.index);
+    }
+
+    pub fn clear(&mut self) {
+        self.transactions.clear();
+        self.transaction_count = 0;
+        self.blocks.clear();
+        self.anchors.clear();
+        self.anchors_by_address.clear();
+    }
+
+    pub fn transaction_count(&self) -> u64 {
+        self.transaction_count
+    }
+
+    pub fn transactions(&self, address: &PublicKey, offset: usize, limit: usize) -> Page<Vec<u8>> {
+        paginate(self.transactions.get(address.as_bytes()).map_or(&[][..], |hashes| hashes.as_slice()), offset, limit)
+    }
+
+    // Adresses ayant une activité indexée commençant par `prefix`, dans l'ordre des clés
+    pub fn addresses_with_prefix(&self, prefix: &[u8], limit: usize) -> Vec<PublicKey> {
+        self.transactions.range(prefix_start(prefix)..)
+            .take_while(|(address, _)| address.starts_with(prefix))
+            .take(limit.clamp(1, MAX_PAGE_SIZE))
+            .filter_map(|(address, _)| PublicKey::from_bytes(address).ok())
+            .collect()
+    }
+
+    pub fn blocks_produced(&self, validator: &PublicKey, offset: usize, limit: usize) -> Page<u64> {
+        paginate(self.blocks.get(validator).map_or(&[][..], |blocks| blocks.as_slice()), offset, limit)
+    }
+
+    // Ancrages d'une empreinte de document, du plus ancien au plus récent
+    pub fn anchors(&self, document: &[u8]) -> &[Vec<u8>] {
+        self.anchors.get(document).map_or(&[][..], |hashes| hashes.as_slice())
+    }
+
+    pub fn anchors_by_address(&self, address: &PublicKey, offset: usize, limit: usize) -> Page<Vec<u8>> {
+        paginate(self.anchors_by_address.get(address).map_or(&[][..], |hashes| hashes.as_slice()), offset, limit)
+    }
+}
+
+fn prefix_start(prefix: &[u8]) -> [u8; 32] {
+    let mut start = [0u8; 32];
+    let len = prefix.len().min(32);
+    start[..len].copy_from_slice(&prefix[..len]);
+    start
+}
+
+// Page de `entries` (dans l'ordre de la chaîne) en partant de la fin ; `limit`
+// est ramenée à [1, MAX_PAGE_SIZE]
+pub fn paginate<T: Clone>(entries: &[T], offset: usize, limit: usize) -> Page<T> {
+    let limit = limit.clamp(1, MAX_PAGE_SIZE);
+    let items: Vec<T> = entries.iter().rev().skip(offset).take(limit).cloned().collect();
+    let end = offset.saturating_add(items.len());
+    Page {
+        items,
+        total: entries.len(),
+        next_offset: (end < entries.len()).then_some(end),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ed25519_dalek::{Keypair, SecretKey};
+
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::gas::DEFAULT_GAS_PRICE;
+    use crate::{Blockchain, Expiry};
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    fn transaction(sender: u8, recipient: u8, kind: TransactionKind) -> Transaction {
+        Transaction::signed(1, &keypair(sender), 0, &keypair(recipient).public, 1, kind, DEFAULT_GAS_PRICE, GENESIS_TIME, Expiry::Never)
+    }
+
+    // Bloc d'index `index` produit par `producer` avec `transactions`, que
+    // l'indexeur ne valide pas
+    fn block(producer: u8, index: u64, transactions: Vec<Transaction>) -> Block {
+        let validator = keypair(producer);
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(std::sync::Arc::new(MockClock::new(Duration::from_secs(GENESIS_TIME + 5))));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        let mut block = chain.validate_and_create_block(&validator, 1).unwrap();
+        block.index = index;
+        block.transactions = transactions;
+        block
+    }
+
+    fn hashes(page: Page<Vec<u8>>) -> Vec<Vec<u8>> {
+        page.items
+    }
+
+    #[test]
+    fn transactions_are_indexed_under_each_address_they_concern() {
+        let transfer = transaction(1, 2, TransactionKind::Transfer);
+        let to_self = transaction(1, 1, TransactionKind::Transfer);
+        let recover = transaction(3, 5, TransactionKind::Recover { account: keypair(4).public });
+        let inner = transaction(2, 3, TransactionKind::Transfer);
+        let bundle = transaction(1, 1, TransactionKind::Bundle(vec![inner.clone()]));
+        let mut indexer = Indexer::default();
+        indexer.index_block(&block(9, 0, vec![transfer.clone(), to_self.clone()]));
+        indexer.index_block(&block(9, 1, vec![recover.clone(), bundle.clone()]));
+        indexer.index_block(&block(8, 2, Vec::new()));
+
+        let of = |seed: u8| hashes(indexer.transactions(&keypair(seed).public, 0, MAX_PAGE_SIZE));
+        assert_eq!(of(1), vec![bundle.hash(), to_self.hash(), transfer.hash()]);
+        assert_eq!(of(2), vec![inner.hash(), transfer.hash()]);
+        assert_eq!(of(3), vec![inner.hash(), recover.hash()]);
+        assert_eq!((of(4), of(5)), (vec![recover.hash()], vec![recover.hash()]));
+        assert_eq!(indexer.transaction_count(), 5);
+
+        assert_eq!(indexer.blocks_produced(&keypair(9).public, 0, 10).items, vec![1, 0]);
+        assert_eq!(indexer.blocks_produced(&keypair(8).public, 0, 10).items, vec![2]);
+        assert_eq!(indexer.blocks_produced(&keypair(7).public, 0, 10), Page { items: Vec::new(), total: 0, next_offset: None });
+
+        indexer.clear();
+        assert!(indexer.transactions(&keypair(1).public, 0, MAX_PAGE_SIZE).items.is_empty());
+        assert_eq!(indexer.transaction_count(), 0);
+    }
+
+    #[test]
+    fn anchors_are_indexed_by_document_and_address() {
+        let anchor = |sender, recipient, document: &[u8]| transaction(sender, recipient, TransactionKind::Anchor { document: document.to_vec(), cid: None });
+        let (first, second, other) = (anchor(1, 2, b"contract"), anchor(3, 3, b"contract"), anchor(1, 3, b"invoice"));
+        let mut indexer = Indexer::default();
+        indexer.index_block(&block(9, 0, vec![first.clone(), transaction(1, 2, TransactionKind::Transfer)]));
+        indexer.index_block(&block(9, 1, vec![second.clone(), other.clone()]));
+
+        assert_eq!(indexer.anchors(b"contract"), &[first.hash(), second.hash()][..]);
+        assert_eq!(indexer.anchors(b"invoice"), &[other.hash()][..]);
+        assert!(indexer.anchors(b"unknown").is_empty());
+        assert_eq!(hashes(indexer.anchors_by_address(&keypair(1).public, 0, 10)), vec![other.hash(), first.hash()]);
+        assert_eq!(hashes(indexer.anchors_by_address(&keypair(3).public, 0, 10)), vec![other.hash(), second.hash()]);
+        assert_eq!(hashes(indexer.anchors_by_address(&keypair(2).public, 0, 10)), vec![first.hash()]);
+    }
+
+    #[test]
+    fn pages_run_from_newest_to_oldest() {
+        let entries: Vec<u32> = (0..250).collect();
+        assert_eq!(paginate(&entries, 0, 3), Page { items: vec![249, 248, 247], total: 250, next_offset: Some(3) });
+        assert_eq!(paginate(&entries, 248, 3), Page { items: vec![1, 0], total: 250, next_offset: None });
+        assert_eq!(paginate(&entries, 250, 3), Page { items: Vec::new(), total: 250, next_offset: None });
+        assert_eq!(paginate(&entries, usize::MAX, 3).next_offset, None);
+
+        // La taille de page est ramenée à [1, MAX_PAGE_SIZE]
+        assert_eq!(paginate(&entries, 0, 0).items, vec![249]);
+        let page = paginate(&entries, 0, 1000);
+        assert_eq!((page.items.len(), page.next_offset), (MAX_PAGE_SIZE, Some(MAX_PAGE_SIZE)));
+    }
+
+    #[test]
+    fn addresses_are_found_by_prefix() {
+        let mut indexer = Indexer::default();
+        let transactions = (1..=6).map(|seed| transaction(seed, seed, TransactionKind::Transfer)).collect();
+        indexer.index_block(&block(9, 0, transactions));
+        let mut keys: Vec<[u8; 32]> = (1..=6).map(|seed| keypair(seed).public.to_bytes()).collect();
+        keys.sort();
+
+        let found = |prefix: &[u8], limit| indexer.addresses_with_prefix(prefix, limit).iter().map(|key| key.to_bytes()).collect::<Vec<_>>();
+        assert_eq!(found(&[], 10), keys);
+        assert_eq!(found(&[], 2), keys[..2].to_vec());
+        assert_eq!(found(&keys[3][..2], 10), vec![keys[3]]);
+        assert_eq!(found(&keys[3], 10), vec![keys[3]]);
+        let mut missing = keys[3];
+        missing[31] ^= 1;
+        assert!(found(&missing, 10).is_empty());
+    }
+}
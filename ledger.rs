@@ -0,0 +1,105 @@
+use std::collections::{HashMap, HashSet};
+use ed25519_dalek::PublicKey;
+
+use crate::encoding::{Decoder, Encoder};
+
+// Soldes des comptes. Un compte absent a un solde nul.
+#[derive(Clone, Debug, Default)]
+pub struct Ledger {
+    balances: HashMap<PublicKey, u64>,
+    // Comptes modifiés depuis le dernier `take_changes` ; hors état, ni encodé ni haché
+    changes: HashSet<PublicKey>,
+}
+
+impl Ledger {
+    pub fn balance(&self, account: &PublicKey) -> u64 {
+        self.balances.get(account).copied().unwrap_or(0)
+    }
+
+    // Allocation ou rémunération, plafonnée à u64::MAX
+    pub fn credit(&mut self, account: &PublicKey, amount: u64) {
+        let balance = self.balance(account).saturating_add(amount);
+        if balance > 0 {
+            self.balances.insert(*account, balance);
+            self.changes.insert(*account);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&PublicKey, u64)> {
+        self.balances.iter().map(|(account, balance)| (account, *balance))
+    }
+
+    // Solde reçu d'un nœud de confiance (synchronisation différentielle)
+    pub fn set_balance(&mut self, account: &PublicKey, balance: u64) {
+        if balance == 0 {
+            self.balances.remove(account);
+        } else {
+            self.balances.insert(*account, balance);
+        }
+        self.changes.insert(*account);
+    }
+
+    pub fn take_changes(&mut self) -> HashSet<PublicKey> {
+        std::mem::take(&mut self.changes)
+    }
+
+    // Mouvements (compte, variation) applicables ensemble : aucun solde ne devient
+    // négatif ni ne dépasse u64::MAX
+    pub fn check(&self, movements: &[(PublicKey, i128)]) -> Result<(), &'static str> {
+        let mut balances: HashMap<PublicKey, i128> = HashMap::new();
+        for (account, delta) in movements {
+            let balance = balances.entry(*account).or_insert_with(|| self.balance(account) as i128);
+            *balance += delta;
+        }
+        for balance in balances.values() {
+            if *balance < 0 {
+                return Err("Insufficient balance");
+            }
+            if *balance > u64::MAX as i128 {
+                return Err("Balance overflow");
+            }
+        }
+        Ok(())
+    }
+
+    // Mouvements déjà vérifiés par `check`
+    pub fn apply(&mut self, movements: &[(PublicKey, i128)]) {
+        for (account, delta) in movements {
+            let balance = self.balance(account) as i128 + delta;
+            self.changes.insert(*account);
+            if balance == 0 {
+                self.balances.remove(account);
+            } else {
+                self.balances.insert(*account, balance as u64);
+            }
+        }
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        let mut balances: Vec<(&PublicKey, &u64)> = self.balances.iter().collect();
+        balances.sort_by_key(|(account, _)| account.to_bytes());
+        encoder.put_u32(balances.len() as u32);
+        for (account, balance) in balances {
+            encoder.put_public_key(account);
+            encoder.put_u64(*balance);
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Ledger, &'static str> {
+        let mut balances = HashMap::new();
+        let mut previous: Option<PublicKey> = None;
+        for _ in 0..decoder.get_u32()? {
+            let account = decoder.get_public_key()?;
+            if previous.is_some_and(|previous| previous.to_bytes() >= account.to_bytes()) {
+                return Err("Balances not in canonical order");
+            }
+            let balance = decoder.get_u64()?;
+            if balance == 0 {
+                return Err("Zero balance in ledger");
+            }
+            previous = Some(account);
+            balances.insert(account, balance);
+        }
+        Ok(Ledger { balances, changes: HashSet::new() })
+    }
+}
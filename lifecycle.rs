@@ -0,0 +1,282 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use async_trait::async_trait;
+use ed25519_dalek::Keypair;
+
+use crate::clock::Clock;
+use crate::{Authorization, Blockchain, Expiry, Transaction};
+
+// Transactions retirées du mempool dont le nœud garde la trace
+const MAX_DEAD_LETTERS: usize = 10_000;
+
+// Cycle de vie d'une transaction connue du nœud
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionStatus {
+    // En attente dans le mempool ou parmi les transactions programmées
+    Pending,
+    Included { height: u64 },
+    // Retirée du mempool sans être incluse : remplacée, devenue inexécutable ou
+    // écartée lors d'une resynchronisation
+    Dropped { reason: &'static str },
+    // Échéance passée avant son inclusion
+    Expired,
+}
+
+impl TransactionStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransactionStatus::Pending => "pending",
+            TransactionStatus::Included { .. } => "included",
+            TransactionStatus::Dropped { .. } => "dropped",
+            TransactionStatus::Expired => "expired",
+        }
+    }
+}
+
+// Transactions retirées du mempool sans être incluses, avec leur statut ; les
+// plus anciennes sont oubliées au-delà de MAX_DEAD_LETTERS
+#[derive(Default)]
+pub struct DeadLetters {
+    transactions: HashMap<Vec<u8>, (Transaction, TransactionStatus)>,
+    order: VecDeque<Vec<u8>>,
+}
+
+impl DeadLetters {
+    pub fn record(&mut self, transaction: Transaction, status: TransactionStatus) {
+        let hash = transaction.hash();
+        if self.transactions.insert(hash.clone(), (transaction, status)).is_none() {
+            self.order.push_back(hash);
+        }
+        while self.order.len() > MAX_DEAD_LETTERS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.transactions.remove(&oldest);
+            }
+        }
+    }
+
+    // Transaction de nouveau admise
+    pub fn remove(&mut self, hash: &[u8]) {
+        if self.transactions.remove(hash).is_some() {
+            self.order.retain(|recorded| recorded != hash);
+        }
+    }
+
+    pub fn get(&self, hash: &[u8]) -> Option<&(Transaction, TransactionStatus)> {
+        self.transactions.get(hash)
+    }
+}
+
+// Ce qu'un nœud fournit au suivi des transactions d'un client : la chaîne
+// locale, ou un nœud distant interrogé par le RPC (`rpc::RpcRelay`)
+#[async_trait]
+pub trait TransactionRelay: Send {
+    // Aucun statut pour une transaction inconnue ou oubliée du nœud
+    async fn transaction_status(&mut self, hash: &[u8]) -> Result<Option<TransactionStatus>, String>;
+    async fn submit(&mut self, transaction: Transaction) -> Result<(), String>;
+}
+
+#[async_trait]
+impl TransactionRelay for Blockchain {
+    async fn transaction_status(&mut self, hash: &[u8]) -> Result<Option<TransactionStatus>, String> {
+        Ok(Blockchain::transaction_status(self, hash))
+    }
+
+    async fn submit(&mut self, transaction: Transaction) -> Result<(), String> {
+        Ok(self.add_transaction(transaction)?)
+    }
+}
+
+// Hausse par défaut du prix du gaz à chaque resoumission, au-dessus du seuil de
+// remplacement par défaut du mempool
+pub const DEFAULT_BUMP_PERCENT: u64 = 20;
+
+pub const DEFAULT_MAX_RESUBMISSIONS: u32 = 5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrackerEvent {
+    Included { hash: Vec<u8>, height: u64 },
+    // Transaction retirée ou expirée, signée de nouveau à un prix du gaz relevé
+    Resubmitted { previous: Vec<u8>, hash: Vec<u8>, gas_price: u64 },
+    // Suivi abandonné : resoumission refusée (notamment lorsqu'une autre
+    // transaction de même nonce a été incluse) ou trop de resoumissions
+    Abandoned { hash: Vec<u8>, reason: String },
+}
+
+struct Tracked {
+    transaction: Transaction,
+    resubmissions: u32,
+}
+
+// Suivi côté client des transactions signées par `keypair` : celles que le nœud
+// retire de son mempool ou laisse expirer sont signées de nouveau avec le même
+// nonce, un prix du gaz relevé (et une nouvelle échéance de même durée si elle
+// est horodatée) puis resoumises. La nouvelle version remplace l'ancienne
+// partout où celle-ci serait encore en attente, et une seule des deux peut
+// être incluse.
+pub struct TxTracker {
+    keypair: Keypair,
+    tracked: HashMap<Vec<u8>, Tracked>,
+    bump_percent: u64,
+    max_resubmissions: u32,
+    // Horodatage des transactions signées de nouveau
+    clock: Arc<dyn Clock>,
+}
+
+impl TxTracker {
+    pub fn new(keypair: Keypair, clock: Arc<dyn Clock>) -> Self {
+        TxTracker {
+            keypair,
+            tracked: HashMap::new(),
+            bump_percent: DEFAULT_BUMP_PERCENT,
+            max_resubmissions: DEFAULT_MAX_RESUBMISSIONS,
+            clock,
+        }
+    }
+
+    pub fn with_policy(mut self, bump_percent: u64, max_resubmissions: u32) -> Self {
+        self.bump_percent = bump_percent;
+        self.max_resubmissions = max_resubmissions;
+        self
+    }
+
+    // Soumet une transaction signée par la clé du suivi et retourne son hash
+    pub async fn send(&mut self, node: &mut impl TransactionRelay, transaction: Transaction) -> Result<Vec<u8>, String> {
+        if transaction.sender != self.keypair.public || !matches!(transaction.authorization, Authorization::Signature(_)) {
+            return Err("Transaction not signed by the tracker key".to_string());
+        }
+        let hash = transaction.hash();
+        node.submit(transaction.clone()).await?;
+        self.tracked.insert(hash.clone(), Tracked { transaction, resubmissions: 0 });
+        Ok(hash)
+    }
+
+    pub fn pending(&self) -> usize {
+        self.tracked.len()
+    }
+
+    // Interroge le nœud sur chaque transaction suivie ; les transactions incluses
+    // ou abandonnées ne sont plus suivies. Rien n'est modifié si le nœud ne
+    // répond pas pour l'une d'elles.
+    pub async fn poll(&mut self, node: &mut impl TransactionRelay) -> Result<Vec<TrackerEvent>, String> {
+        let mut statuses = Vec::with_capacity(self.tracked.len());
+        for hash in self.tracked.keys() {
+            statuses.push((hash.clone(), node.transaction_status(hash).await?));
+        }
+        let mut events = Vec::new();
+        for (hash, status) in statuses {
+            match status {
+                Some(TransactionStatus::Pending) => {}
+                Some(TransactionStatus::Included { height }) => {
+                    self.tracked.remove(&hash);
+                    events.push(TrackerEvent::Included { hash, height });
+                }
+                Some(TransactionStatus::Dropped { .. } | TransactionStatus::Expired) | None => {
+                    let Some(tracked) = self.tracked.remove(&hash) else { continue };
+                    events.push(self.resubmit(node, hash, tracked).await);
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    async fn resubmit(&mut self, node: &mut impl TransactionRelay, previous: Vec<u8>, tracked: Tracked) -> TrackerEvent {
+        if tracked.resubmissions >= self.max_resubmissions {
+            return TrackerEvent::Abandoned { hash: previous, reason: "Too many resubmissions".to_string() };
+        }
+        let transaction = self.bumped(&tracked.transaction);
+        let (hash, gas_price) = (transaction.hash(), transaction.gas_price);
+        if let Err(reason) = node.submit(transaction.clone()).await {
+            return TrackerEvent::Abandoned { hash: previous, reason };
+        }
+        self.tracked.insert(hash.clone(), Tracked { transaction, resubmissions: tracked.resubmissions + 1 });
+        TrackerEvent::Resubmitted { previous, hash, gas_price }
+    }
+
+    // Même transaction, même nonce : la nouvelle version remplace l'ancienne
+    fn bumped(&self, transaction: &Transaction) -> Transaction {
+        let gas_price = transaction.gas_price.saturating_mul(100 + self.bump_percent).div_ceil(100).max(transaction.gas_price.saturating_add(1));
+        let timestamp = self.clock.unix_secs();
+        let valid_until = match transaction.valid_until {
+            Expiry::Timestamp(last) => Expiry::Timestamp(timestamp + last.saturating_sub(transaction.timestamp)),
+            expiry => expiry,
+        };
+        Transaction::scheduled(transaction.chain_id, &self.keypair, transaction.nonce, &transaction.recipient, transaction.amount, transaction.kind.clone(), gas_price, timestamp, transaction.valid_from, valid_until)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use rand::rngs::OsRng;
+
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::gas::DEFAULT_GAS_PRICE;
+    use crate::TransactionKind;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn test_chain(validator: &Keypair, sender: &Keypair, clock: &MockClock) -> Blockchain {
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain.allocate(&sender.public, 1_000_000);
+        chain
+    }
+
+    fn produce(chain: &mut Blockchain, clock: &MockClock, validator: &Keypair, slot: u64) {
+        clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+        chain.validate_and_create_block(validator, slot).unwrap();
+    }
+
+    fn transfer(sender: &Keypair, recipient: &Keypair, amount: u64, gas_price: u64, valid_until: Expiry) -> Transaction {
+        Transaction::signed(1, sender, 0, &recipient.public, amount, TransactionKind::Transfer, gas_price, GENESIS_TIME, valid_until)
+    }
+
+    #[tokio::test]
+    async fn resubmits_expired_transaction_with_the_same_nonce() {
+        let (validator, alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &alice, &clock);
+        let alice_copy = Keypair::from_bytes(&alice.to_bytes()).unwrap();
+        let mut tracker = TxTracker::new(alice_copy, Arc::new(clock.clone()));
+
+        let original = tracker.send(&mut chain, transfer(&alice, &bob, 100, 10, Expiry::Timestamp(GENESIS_TIME + 10))).await.unwrap();
+        produce(&mut chain, &clock, &validator, 20);
+        assert_eq!(chain.transaction_status(&original), Some(TransactionStatus::Expired));
+
+        let events = tracker.poll(&mut chain).await.unwrap();
+        let [TrackerEvent::Resubmitted { previous, hash, gas_price }] = events.as_slice() else { panic!("{:?}", events) };
+        assert_eq!((previous, *gas_price), (&original, 12));
+        let resubmitted = chain.pending_transactions[0].clone();
+        assert_eq!((resubmitted.hash(), resubmitted.nonce), (hash.clone(), 0));
+        assert_eq!(resubmitted.valid_until, Expiry::Timestamp(GENESIS_TIME + 110));
+
+        produce(&mut chain, &clock, &validator, 21);
+        assert_eq!(tracker.poll(&mut chain).await.unwrap(), vec![TrackerEvent::Included { hash: hash.clone(), height: 1 }]);
+        assert_eq!(tracker.pending(), 0);
+        assert_eq!(chain.balance(&bob.public), 100);
+    }
+
+    #[tokio::test]
+    async fn abandons_when_another_version_is_included() {
+        let (validator, alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &alice, &clock);
+        let alice_copy = Keypair::from_bytes(&alice.to_bytes()).unwrap();
+        let mut tracker = TxTracker::new(alice_copy, Arc::new(clock.clone()));
+        let valid_until = Expiry::Timestamp(GENESIS_TIME + 3600);
+
+        let tracked = tracker.send(&mut chain, transfer(&alice, &bob, 100, DEFAULT_GAS_PRICE, valid_until)).await.unwrap();
+        // Autre paiement signé avec le même nonce, à un prix du gaz supérieur
+        chain.add_transaction(transfer(&alice, &bob, 500, DEFAULT_GAS_PRICE * 2, valid_until)).unwrap();
+        produce(&mut chain, &clock, &validator, 1);
+        assert_eq!(chain.balance(&bob.public), 500);
+
+        let events = tracker.poll(&mut chain).await.unwrap();
+        assert_eq!(events, vec![TrackerEvent::Abandoned { hash: tracked, reason: "Nonce already used".to_string() }]);
+        assert_eq!(tracker.pending(), 0);
+        assert_eq!(chain.balance(&bob.public), 500);
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use ed25519_dalek::PublicKey;
+
+use crate::config::ChainConfig;
+use crate::difficulty::retarget;
+use crate::merkle::{merkle_proof, MerkleProof};
+use crate::statetree::StateProof;
+use crate::{select_leader, Block, BlockHeader, Blockchain, Validator, EPOCH_LENGTH};
+
+// En-têtes qu'un nœud complet (ou un pair, voir `PeerSnapshot`) fournit à un client léger
+pub trait HeaderSource {
+    fn headers(&self, from: u64, count: usize) -> Vec<BlockHeader>;
+}
+
+impl HeaderSource for Blockchain {
+    // Les blocs antérieurs à un instantané de départ ne sont pas disponibles
+    fn headers(&self, from: u64, count: usize) -> Vec<BlockHeader> {
+        let Some(offset) = from.checked_sub(self.first_index()) else {
+            return Vec::new();
+        };
+        self.chain.iter().skip(offset as usize).take(count).map(|block| block.header()).collect()
+    }
+}
+
+// Preuves vérifiables par un client léger contre ses en-têtes
+impl Blockchain {
+    // Hauteur du bloc contenant la transaction et preuve d'inclusion
+    pub fn transaction_proof(&self, transaction_hash: &[u8]) -> Option<(u64, MerkleProof)> {
+        self.chain.iter().find_map(|block| {
+            let hashes = Block::leaf_hashes(&block.transactions, &block.private_transactions, &block.governance_transactions);
+            let position = hashes.iter().position(|hash| hash == transaction_hash)?;
+            Some((block.index, merkle_proof(&hashes, position)?))
+        })
+    }
+
+    // Preuve du solde actuel d'un compte et hauteur du bloc dont l'en-tête en
+    // engage la racine (le prochain bloc)
+    pub fn balance_proof(&self, account: &PublicKey) -> (u64, StateProof) {
+        (self.next_index(), self.state_tree.prove(account))
+    }
+}
+
+// Nombre d'en-têtes demandés par requête lors de la synchronisation
+const HEADER_BATCH_SIZE: usize = 512;
+
+// Client ne conservant que les en-têtes, à partir d'un ensemble de validateurs de confiance
+pub struct LightClient {
+    headers: Vec<BlockHeader>,
+    validators: HashMap<PublicKey, Validator>,
+    config: ChainConfig,
+    // Difficulté du prochain en-tête, recalculée en fin d'époque comme la chaîne
+    difficulty: u64,
+}
+
+impl LightClient {
+    pub fn new(validators: HashMap<PublicKey, Validator>, config: ChainConfig) -> Self {
+        LightClient {
+            headers: Vec::new(),
+            validators,
+            difficulty: config.initial_difficulty,
+            config,
+        }
+    }
+
+    pub fn header(&self, height: u64) -> Option<&BlockHeader> {
+        self.headers.get(height as usize)
+    }
+
+    pub fn add_header(&mut self, header: BlockHeader) -> Result<(), &'static str> {
+        if header.chain_id != self.config.chain_id {
+            return Err("Block for another chain");
+        }
+        if header.index != self.headers.len() as u64 {
+            return Err("Invalid block index");
+        }
+
+        let previous_hash = match self.headers.last() {
+            Some(parent) => {
+                if header.slot <= parent.slot {
+                    return Err("Invalid block slot");
+                }
+                parent.current_hash.clone()
+            }
+            None => vec![0; 32],
+        };
+        if header.previous_hash != previous_hash {
+            return Err("Invalid previous hash");
+        }
+
+        if header.difficulty != self.difficulty {
+            return Err("Invalid block difficulty");
+        }
+        let (min_stake, min_score) = (self.config.consensus.min_producer_stake, self.config.min_contribution_score);
+        if select_leader(&self.validators, &previous_hash, header.slot, self.difficulty, min_stake, min_score) != Some(header.validator_pubkey) {
+            return Err("Validator not selected for this slot");
+        }
+
+        header.verify()?;
+        self.headers.push(header);
+        let next = self.headers.len() as u64;
+        if next % EPOCH_LENGTH == 0 {
+            let span = self.headers[next as usize - 1].timestamp.saturating_sub(self.headers[(next - EPOCH_LENGTH) as usize].timestamp);
+            self.difficulty = retarget(self.difficulty, span, EPOCH_LENGTH - 1, self.config.target_block_interval);
+        }
+        Ok(())
+    }
+
+    // Télécharge et vérifie les en-têtes manquants ; retourne le nombre ajouté
+    pub fn sync(&mut self, source: &impl HeaderSource) -> Result<usize, &'static str> {
+        let mut added = 0;
+        loop {
+            let headers = source.headers(self.headers.len() as u64, HEADER_BATCH_SIZE);
+            if headers.is_empty() {
+                return Ok(added);
+            }
+            for header in headers {
+                self.add_header(header)?;
+                added += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+
+    use super::{HeaderSource, LightClient};
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::Blockchain;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    #[test]
+    fn syncs_headers_and_rejects_forged_ones() {
+        let validator = keypair(1);
+        let clock = MockClock::new(Duration::from_secs(1_700_000_000));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        for slot in 1..=20 {
+            clock.set(Duration::from_secs(1_700_000_000 + slot * 5));
+            chain.validate_and_create_block(&validator, slot).unwrap();
+        }
+
+        let mut light_client = LightClient::new(chain.validators.clone(), chain.config.clone());
+        assert_eq!(light_client.sync(&chain), Ok(chain.next_index() as usize));
+        assert_eq!(light_client.sync(&chain), Ok(0));
+        assert_eq!(light_client.header(chain.next_index() - 1).map(|header| header.current_hash.clone()), Some(chain.last_hash()));
+
+        // En-têtes de la même chaîne, altérés ou signés par un inconnu
+        let mut light_client = LightClient::new(chain.validators.clone(), chain.config.clone());
+        let headers = chain.headers(0, 3);
+        light_client.add_header(headers[0].clone()).unwrap();
+        let mut forged = headers[1].clone();
+        forged.transactions_root = vec![1; 32];
+        assert_eq!(light_client.add_header(forged), Err("Invalid block hash"));
+        let mut foreign = headers[1].clone();
+        foreign.validator_pubkey = keypair(2).public;
+        assert_eq!(light_client.add_header(foreign), Err("Validator not selected for this slot"));
+        assert_eq!(light_client.add_header(headers[2].clone()), Err("Invalid block index"));
+        light_client.add_header(headers[1].clone()).unwrap();
+    }
+}
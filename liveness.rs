@@ -0,0 +1,39 @@
+use ed25519_dalek::{PublicKey, Signature};
+
+use crate::encoding::{Encoder, LIVENESS_DOMAIN};
+use crate::signer::Signer;
+
+// Créneaux attribués manqués consécutivement au-delà desquels un validateur
+// est désactivé : il n'est plus choisi comme producteur jusqu'à sa réactivation
+pub const MAX_MISSED_SLOTS: u64 = 16;
+
+// Facteur appliqué en fin d'époque au score des validateurs inactifs
+pub const INACTIVE_SCORE_DECAY: f64 = 0.9;
+
+// Retour d'un validateur désactivé au bloc `inactive_since` : la hauteur signée
+// empêche de rejouer la transaction après une désactivation ultérieure
+#[derive(Clone, Debug)]
+pub struct Reactivation {
+    pub validator: PublicKey,
+    pub inactive_since: u64,
+    pub signature: Signature,
+}
+
+impl Reactivation {
+    pub fn new(signer: &dyn Signer, inactive_since: u64) -> Result<Self, &'static str> {
+        let validator = signer.public_key();
+        let signature = signer.sign(&Self::signing_hash(&validator, inactive_since))?;
+        Ok(Reactivation { validator, inactive_since, signature })
+    }
+
+    pub fn signing_hash(validator: &PublicKey, inactive_since: u64) -> Vec<u8> {
+        let mut encoder = Encoder::new(LIVENESS_DOMAIN);
+        encoder.put_public_key(validator);
+        encoder.put_u64(inactive_since);
+        encoder.hash()
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Self::signing_hash(&self.validator, self.inactive_since)
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+use ed25519_dalek::{Keypair, PublicKey};
+use rand::rngs::OsRng;
+use tokio::time::Instant;
+use tokio_stream::StreamExt;
+
+use crate::clock::{Clock, SystemClock};
+use crate::encoding::encode_block_body;
+use crate::gas::intrinsic_gas;
+use crate::rpc::proto::query_client::QueryClient;
+use crate::rpc::proto::submission_client::SubmissionClient;
+use crate::rpc::proto::{self, NextNonceRequest, StateProofRequest, SubmitBatchRequest, SubscribeBlocksRequest};
+use crate::txbuilder::TxBuilder;
+use crate::Transaction;
+
+// Intervalle entre deux lots soumis : le débit visé est réparti en lots de
+// `rate / 10` transactions
+const TICK: Duration = Duration::from_millis(100);
+
+// Charge synthétique envoyée à un nœud en service (`supplyx bench`) : des
+// comptes éphémères, dotés par la clé fournie, s'envoient des transferts en
+// anneau à débit constant par `SubmitBatch`. L'inclusion est observée par
+// `SubscribeBlocks` ; la latence va de la soumission du lot à la réception du
+// bloc qui inclut la transaction.
+pub struct LoadOptions {
+    // Adresse du RPC, `http://127.0.0.1:50051` par exemple
+    pub endpoint: String,
+    pub chain_id: u64,
+    pub accounts: usize,
+    pub transactions: usize,
+    // Transactions soumises par seconde
+    pub rate: u64,
+    // Attente des inclusions après la dernière soumission
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    pub submitted: usize,
+    pub accepted: usize,
+    // Refus du mempool par motif
+    pub rejected: HashMap<String, usize>,
+    // Latence de chaque transaction incluse
+    pub latencies: Vec<Duration>,
+    // De la première soumission à la dernière inclusion
+    pub elapsed: Duration,
+}
+
+impl LoadReport {
+    // Transactions incluses par seconde
+    pub fn tps(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return 0.0;
+        }
+        self.latencies.len() as f64 / self.elapsed.as_secs_f64()
+    }
+
+    // Percentile `percent` des latences (rang le plus proche), aucun sans inclusion
+    pub fn percentile(&self, percent: u32) -> Option<Duration> {
+        let mut latencies = self.latencies.clone();
+        latencies.sort();
+        let rank = (latencies.len() * percent as usize).div_ceil(100);
+        latencies.get(rank.max(1) - 1).copied()
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Submitted {}, accepted {}, included {} in {:.1} s: {:.1} TPS", self.submitted, self.accepted, self.latencies.len(), self.elapsed.as_secs_f64(), self.tps())?;
+        let millis = |percent| self.percentile(percent).map_or("-".to_string(), |latency| format!("{} ms", latency.as_millis()));
+        writeln!(f, "Latency p50 {}, p90 {}, p99 {}, max {}", millis(50), millis(90), millis(99), millis(100))?;
+        let mut rejected: Vec<(&String, &usize)> = self.rejected.iter().collect();
+        rejected.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        for (reason, count) in rejected {
+            writeln!(f, "Rejected {}: {}", count, reason)?;
+        }
+        Ok(())
+    }
+}
+
+fn transfer(chain_id: u64, sender: &Keypair, nonce: u64, recipient: &PublicKey, amount: u64, timestamp: u64) -> Result<Transaction, String> {
+    Ok(TxBuilder::transfer().chain_id(chain_id).to(*recipient).amount(amount).nonce(nonce).timestamp(timestamp).sign(sender)?)
+}
+
+async fn submit(client: &mut SubmissionClient<tonic::transport::Channel>, transactions: &[Transaction]) -> Result<Vec<proto::SubmissionResult>, String> {
+    let request = SubmitBatchRequest { transactions: encode_block_body(transactions) };
+    let response = client.submit_batch(request).await.map_err(|status| status.message().to_string())?;
+    Ok(response.into_inner().results)
+}
+
+// Attend l'inclusion de toutes les transactions `hashes`
+async fn wait_included(blocks: &mut tonic::Streaming<proto::Block>, mut hashes: Vec<Vec<u8>>, timeout: Duration) -> Result<(), String> {
+    let deadline = Instant::now() + timeout;
+    while !hashes.is_empty() {
+        let block = tokio::time::timeout_at(deadline, blocks.next()).await
+            .map_err(|_| format!("{} funding transactions not included in time", hashes.len()))?
+            .ok_or("Block subscription closed")?
+            .map_err(|status| status.message().to_string())?;
+        hashes.retain(|hash| !block.transactions.iter().any(|transaction| transaction.hash == *hash));
+    }
+    Ok(())
+}
+
+pub async fn run(funder: &Keypair, options: &LoadOptions) -> Result<LoadReport, String> {
+    if options.accounts < 2 || options.transactions == 0 || options.rate == 0 {
+        return Err("At least 2 accounts, 1 transaction and a rate of 1 per second".to_string());
+    }
+    let connect_error = |e: tonic::transport::Error| format!("{}: {}", options.endpoint, e);
+    let mut query = QueryClient::connect(options.endpoint.clone()).await.map_err(connect_error)?;
+    let mut submission = SubmissionClient::connect(options.endpoint.clone()).await.map_err(connect_error)?;
+    // La preuve d'état donne le solde du compte payeur et la hauteur du prochain bloc
+    let proof = query.get_state_proof(StateProofRequest { account: funder.public.to_bytes().to_vec() }).await
+        .map_err(|status| status.message().to_string())?
+        .into_inner();
+    let mut blocks = query.subscribe_blocks(SubscribeBlocksRequest { from_height: proof.height }).await
+        .map_err(|status| status.message().to_string())?
+        .into_inner();
+
+    // Transferts signés d'avance, chaque compte payant le suivant avec des
+    // nonces successifs
+    let timestamp = SystemClock.unix_secs();
+    let accounts: Vec<Keypair> = (0..options.accounts).map(|_| Keypair::generate(&mut OsRng)).collect();
+    let mut load = Vec::with_capacity(options.transactions);
+    let mut costs: HashMap<PublicKey, u64> = HashMap::new();
+    for position in 0..options.transactions {
+        let (index, sequence) = (position % accounts.len(), position / accounts.len());
+        let recipient = accounts[(index + 1) % accounts.len()].public;
+        let transaction = transfer(options.chain_id, &accounts[index], sequence as u64, &recipient, sequence as u64 + 1, timestamp)?;
+        *costs.entry(transaction.sender).or_default() += intrinsic_gas(&transaction) * transaction.gas_price + transaction.amount;
+        load.push(transaction);
+    }
+
+    let first_nonce = query.get_next_nonce(NextNonceRequest { sender: funder.public.to_bytes().to_vec() }).await
+        .map_err(|status| status.message().to_string())?
+        .into_inner()
+        .nonce;
+    let funding = accounts.iter().zip(first_nonce..)
+        .map(|(account, nonce)| transfer(options.chain_id, funder, nonce, &account.public, costs.get(&account.public).copied().unwrap_or_default(), timestamp))
+        .collect::<Result<Vec<_>, _>>()?;
+    let required: u64 = funding.iter().map(|transaction| intrinsic_gas(transaction) * transaction.gas_price + transaction.amount).sum();
+    if proof.balance < required {
+        return Err(format!("Insufficient balance for {}: {} required, {} available", hex::encode(funder.public.as_bytes()), required, proof.balance));
+    }
+    let results = submit(&mut submission, &funding).await?;
+    if let Some(refused) = results.iter().find(|result| !result.accepted) {
+        return Err(format!("Funding transaction refused: {}", refused.error));
+    }
+    wait_included(&mut blocks, results.into_iter().map(|result| result.transaction_hash).collect(), options.timeout).await?;
+
+    let per_tick = (options.rate as usize * TICK.as_millis() as usize).div_ceil(1000);
+    let mut batches = load.chunks(per_tick);
+    let mut ticker = tokio::time::interval(TICK);
+    let mut report = LoadReport { submitted: load.len(), ..LoadReport::default() };
+    let mut in_flight: HashMap<Vec<u8>, Instant> = HashMap::new();
+    let (start, mut last_inclusion) = (Instant::now(), Instant::now());
+    let mut deadline: Option<Instant> = None;
+    loop {
+        if deadline.is_some() && in_flight.is_empty() {
+            break;
+        }
+        tokio::select! {
+            _ = ticker.tick(), if deadline.is_none() => match batches.next() {
+                Some(batch) => {
+                    let sent = Instant::now();
+                    for result in submit(&mut submission, batch).await? {
+                        if result.accepted {
+                            in_flight.insert(result.transaction_hash, sent);
+                        } else {
+                            *report.rejected.entry(result.error).or_default() += 1;
+                        }
+                    }
+                }
+                None => deadline = Some(Instant::now() + options.timeout),
+            },
+            block = blocks.next() => {
+                let block = block.ok_or("Block subscription closed")?.map_err(|status| status.message().to_string())?;
+                let received = Instant::now();
+                for transaction in &block.transactions {
+                    if let Some(sent) = in_flight.remove(&transaction.hash) {
+                        report.latencies.push(received - sent);
+                        last_inclusion = received;
+                    }
+                }
+            }
+            _ = tokio::time::sleep_until(deadline.unwrap_or_else(|| Instant::now() + options.timeout)), if deadline.is_some() => break,
+        }
+    }
+    report.accepted = report.submitted - report.rejected.values().sum::<usize>();
+    report.elapsed = last_inclusion - start;
+    Ok(report)
+}
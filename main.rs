@@ -1,8 +1,177 @@
-use std::collections::HashMap;
-use std::time::{SystemTime};
-use ed25519_dalek::{Keypair, PublicKey, Signature};
-use sha3::{Sha3_256, Digest};
-use rand::{rngs::OsRng, Rng}; // Ajout de Rng
+mod accounts;
+mod address;
+#[cfg(feature = "admin")]
+mod admin;
+mod admission;
+mod analytics;
+mod anchoring;
+mod anchors;
+mod archive;
+mod assets;
+mod audit;
+mod backup;
+mod beacon;
+mod blockstore;
+mod buildinfo;
+mod bundle;
+mod checkpoint;
+mod clock;
+mod compliance;
+mod confidential;
+mod config;
+mod conformance;
+#[cfg(feature = "consensus-sim")]
+mod consensus_sim;
+mod contracts;
+mod devnet;
+mod difficulty;
+mod dryrun;
+mod encoding;
+mod escrow;
+mod events;
+#[cfg(feature = "explorer")]
+mod explorer;
+mod faucet;
+mod features;
+mod gas;
+mod governance;
+mod gossip;
+mod incidents;
+mod indexer;
+mod ledger;
+mod lifecycle;
+mod light;
+mod liveness;
+#[cfg(feature = "rpc")]
+mod loadtest;
+mod mempool;
+mod metrics;
+mod multisig;
+mod node;
+mod nonces;
+mod orphans;
+mod plugins;
+mod privacy;
+mod receipts;
+mod recovery;
+mod reputation;
+mod rewards;
+mod rotation;
+#[cfg(feature = "replication")]
+mod replication;
+#[cfg(feature = "rpc")]
+mod rpc;
+mod scheduler;
+mod scoring;
+#[cfg(feature = "confidential")]
+mod shielded;
+mod signer;
+mod simulation;
+mod snapshot;
+mod standby;
+mod statetree;
+mod sync;
+mod telemetry;
+mod transport;
+mod txbuilder;
+mod upgrade;
+mod vesting;
+#[cfg(feature = "ws")]
+mod ws;
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use ed25519_dalek::{verify_batch, Keypair, PublicKey, SecretKey, Signature, Signer, Verifier};
+use rand::rngs::OsRng;
+use rayon::prelude::*;
+use tracing::{debug, debug_span, error, info, warn};
+use accounts::{AccountState, Outcome};
+use admission::{AdmissionGate, FailureMode, HttpAllowlist};
+use analytics::Aggregates;
+use anchoring::{Anchorer, BitcoinAnchorer, EthereumAnchorer};
+use archive::BalanceArchive;
+use assets::{Asset, MAX_ASSET_READERS};
+use audit::DumpFormat;
+use txbuilder::TxBuilder;
+use backup::Backup;
+use beacon::{Beacon, WITHHOLDING_PENALTY_PERCENT};
+use blockstore::ChainStore;
+use buildinfo::{consensus_fingerprint, RulesMonitor};
+use bundle::{Fact, ProofBundle};
+use checkpoint::{has_quorum, Checkpoint, CheckpointVote};
+use clock::{Clock, SystemClock};
+use compliance::{Compliance, FreezeOrder, FreezeRecord};
+use confidential::{ConfidentialTransfer, RangeProof};
+use config::ChainConfig;
+use contracts::{contract_address, Execution};
+use devnet::{Devnet, DEV_ACCOUNTS, DEV_SLOT_MILLIS};
+use encoding::{decode_bundle_body, encode_block_body, Decoder, Encoder, BLOCK_DOMAIN, LEADER_DOMAIN, TRANSACTION_BUNDLE_DOMAIN};
+use escrow::escrow_address;
+use events::{ChainEvent, EventBus};
+use faucet::{Faucet, FaucetPolicy, FAUCET_ALLOCATION};
+use gas::DEFAULT_GAS_PRICE;
+use gossip::Gossip;
+use governance::{Governance, GovernanceTransaction};
+use incidents::{Incident, IncidentNote, Incidents};
+use indexer::{paginate, Indexer, Page};
+use lifecycle::{DeadLetters, TransactionStatus};
+use liveness::{INACTIVE_SCORE_DECAY, MAX_MISSED_SLOTS};
+use mempool::{MempoolGuard, MempoolPolicy, MempoolStatus};
+use supplyx_core::merkle::{self, merkle_root};
+use supplyx_core::transaction::{encode_anchor, encode_transfer, signing_encoder, Expiry, TRANSACTION_VERSION};
+use multisig::{merge_signatures, MultisigPolicy};
+use node::{Node, NodeConfig};
+use privacy::{EncryptedPayload, PrivateTransaction};
+use receipts::{Receipt, ReceiptStatus};
+use recovery::PendingRecovery;
+use reputation::Reputation;
+use rewards::{RewardCredit, RewardHistory, RewardKind, Rewards};
+use rotation::KeyHistory;
+use signer::RemoteSigner;
+use simulation::{Profile, Traffic};
+use snapshot::StateSnapshot;
+use standby::{ChangeJournal, StateDiff};
+use statetree::StateTree;
+use sync::{PeerSnapshot, SyncJournal, Synchronizer};
+use telemetry::LogFormat;
+use upgrade::{Upgrades, PROTOCOL_VERSION};
+use vesting::{VestingBalance, VestingSchedule};
+
+// Nombre de signatures vérifiées ensemble par `verify_batch`
+const SIGNATURE_BATCH_SIZE: usize = 256;
+
+// Les blocs dont l'index est multiple de EPOCH_LENGTH sont des checkpoints
+const EPOCH_LENGTH: u64 = 100;
+
+// Un instantané d'état est engagé dans l'en-tête tous les SNAPSHOT_INTERVAL blocs
+const SNAPSHOT_INTERVAL: u64 = 1000;
+
+// Nombre de pairs dont la dernière heure rapportée est conservée pour la médiane
+const MAX_PEER_TIME_SAMPLES: usize = 64;
+
+// Un bloc doit être horodaté après la médiane des MEDIAN_TIME_SPAN blocs précédents
+const MEDIAN_TIME_SPAN: usize = 11;
+
+// Borne supérieure du score de contribution d'un validateur
+const MAX_CONTRIBUTION_SCORE: f64 = 10.0;
+
+// Durée de validité, en secondes, des transactions créées localement
+const TRANSACTION_VALIDITY: u64 = 3600;
+
+// Échéance maximale d'une transaction, en secondes ou en blocs après le bloc
+// qui l'inclut : ses nonces ne sont conservés que jusque-là
+const MAX_TRANSACTION_LIFETIME: u64 = 86_400;
+const MAX_TRANSACTION_LIFETIME_BLOCKS: u64 = 17_280;
+
+// Nombre maximal de transactions d'un lot atomique
+const MAX_BUNDLE_TRANSACTIONS: usize = 64;
+
+// Nombre maximal de transactions programmées conservées par le nœud
+const MAX_SCHEDULED_TRANSACTIONS: usize = 10_000;
+
+// Nombre maximal de transactions multisig en cours de signature conservées par
+// le nœud ; les plus anciennes sont oubliées au-delà
+const MAX_PARTIAL_MULTISIG: usize = 1024;
 
 // Structures principales
 #[derive(Clone, Debug)]
@@ -11,176 +180,4690 @@ struct Validator {
     stake: u64,
     contribution_score: f64,
     last_validated_block: Option<u64>,
+    // Créneaux attribués manqués depuis le dernier bloc produit
+    missed_slots: u64,
+    // Hauteur de la désactivation pour créneaux manqués, jusqu'à la réactivation
+    inactive_since: Option<u64>,
+    // En réserve : enregistré hors de l'ensemble actif, promu en fin d'époque
+    // lorsqu'une place se libère
+    standby: bool,
 }
 
 #[derive(Clone, Debug)]
 struct Block {
+    // Réseau de la chaîne (`ChainConfig::chain_id`), couvert par la signature du producteur
+    chain_id: u64,
     index: u64,
+    slot: u64,
     timestamp: u64,
+    // Un créneau sur `difficulty` en moyenne a un leader (voir `difficulty.rs`)
+    difficulty: u64,
+    // Version du protocole implémentée par le producteur
+    protocol_version: u32,
+    // Empreinte des règles de consensus du producteur (voir `buildinfo`), vide si inconnue
+    rules_fingerprint: Vec<u8>,
     transactions: Vec<Transaction>,
+    // Traces on-chain des transactions privées (hash du contenu et transition d'état)
+    private_transactions: Vec<PrivateTransaction>,
+    // Propositions et votes de gouvernance
+    governance_transactions: Vec<GovernanceTransaction>,
     previous_hash: Vec<u8>,
+    transactions_root: Vec<u8>,
+    // Racine de l'arbre des soldes après le bloc précédent (voir `StateTree`)
+    state_root: Vec<u8>,
+    // Hash de l'instantané d'état (vide hors des hauteurs d'instantané)
+    snapshot_hash: Vec<u8>,
+    // Votes agrégés finalisant un checkpoint antérieur
+    checkpoint: Option<Checkpoint>,
     current_hash: Vec<u8>,
     validator_signature: Signature,
     validator_pubkey: PublicKey,
 }
 
+// En-tête seul, suffisant pour suivre la chaîne sans les transactions (client léger)
 #[derive(Clone, Debug)]
+struct BlockHeader {
+    chain_id: u64,
+    index: u64,
+    slot: u64,
+    timestamp: u64,
+    difficulty: u64,
+    protocol_version: u32,
+    rules_fingerprint: Vec<u8>,
+    previous_hash: Vec<u8>,
+    transactions_root: Vec<u8>,
+    // Racine de l'arbre des soldes après le bloc précédent (voir `StateTree`)
+    state_root: Vec<u8>,
+    // Hash de l'instantané d'état (vide hors des hauteurs d'instantané)
+    snapshot_hash: Vec<u8>,
+    // Votes agrégés finalisant un checkpoint antérieur
+    checkpoint: Option<Checkpoint>,
+    current_hash: Vec<u8>,
+    validator_signature: Signature,
+    validator_pubkey: PublicKey,
+}
+
+// Bloc construit par son producteur, en attente de sa signature (voir `Blockchain::build_block`)
+struct BlockTemplate {
+    chain_id: u64,
+    index: u64,
+    slot: u64,
+    timestamp: u64,
+    difficulty: u64,
+    rules_fingerprint: Vec<u8>,
+    transactions: Vec<Transaction>,
+    private_transactions: Vec<PrivateTransaction>,
+    governance_transactions: Vec<GovernanceTransaction>,
+    previous_hash: Vec<u8>,
+    transactions_root: Vec<u8>,
+    state_root: Vec<u8>,
+    snapshot_hash: Vec<u8>,
+    checkpoint: Option<Checkpoint>,
+    current_hash: Vec<u8>,
+    validator_pubkey: PublicKey,
+}
+
+impl BlockTemplate {
+    fn sign(self, validator_signature: Signature) -> Block {
+        Block {
+            chain_id: self.chain_id,
+            index: self.index,
+            slot: self.slot,
+            timestamp: self.timestamp,
+            difficulty: self.difficulty,
+            protocol_version: PROTOCOL_VERSION,
+            rules_fingerprint: self.rules_fingerprint,
+            transactions: self.transactions,
+            private_transactions: self.private_transactions,
+            governance_transactions: self.governance_transactions,
+            previous_hash: self.previous_hash,
+            transactions_root: self.transactions_root,
+            state_root: self.state_root,
+            snapshot_hash: self.snapshot_hash,
+            checkpoint: self.checkpoint,
+            current_hash: self.current_hash,
+            validator_signature,
+            validator_pubkey: self.validator_pubkey,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 struct Transaction {
+    // Format de la transaction, signé dans son propre domaine ; un format n'est
+    // valide qu'à partir de l'activation de la version du protocole qui l'introduit
+    version: u32,
+    // Réseau pour lequel la transaction est signée : elle n'est valide sur aucun autre
+    chain_id: u64,
     sender: PublicKey,
+    // Choisi par l'émetteur, utilisable une seule fois tant que la transaction
+    // n'a pas expiré (voir `Nonces`) : deux transactions de même nonce sont
+    // deux versions d'un même paiement, une seule peut être incluse
+    nonce: u64,
     recipient: PublicKey,
     amount: u64,
-    signature: Signature,
+    kind: TransactionKind,
+    // Prix payé par unité de gaz consommée
+    gas_price: u64,
+    authorization: Authorization,
     timestamp: u64,
+    // Premier bloc pouvant inclure la transaction (0 : dès sa signature) ; avec
+    // `valid_until`, fenêtre d'exécution d'une transaction programmée
+    valid_from: u64,
+    valid_until: Expiry,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TransactionKind {
+    Transfer,
+    // Bloque `amount` pour `recipient` jusqu'à `unlock_height` ou jusqu'à libération par l'arbitre
+    Escrow { unlock_height: u64, arbiter: Option<PublicKey> },
+    // Versement d'un séquestre (identifié par le hash de son dépôt) au bénéficiaire
+    Claim { escrow: Vec<u8> },
+    // Remboursement d'un séquestre au déposant
+    Refund { escrow: Vec<u8> },
+    // Déploiement d'un contrat à l'adresse `recipient` (voir `contract_address`)
+    Deploy { code: Vec<u8> },
+    // Appel du contrat `recipient`, exécution limitée à `gas_limit`
+    Call { input: Vec<u8>, gas_limit: u64 },
+    // Désignation des gardiens du compte émetteur (voir `Recoveries`)
+    SetRecovery { guardians: MultisigPolicy, challenge_period: u64 },
+    // Rotation de `account` vers la clé `recipient`, émise par ses gardiens
+    Recover { account: PublicKey },
+    // Opposition du compte émetteur à sa récupération en cours
+    VetoRecovery,
+    // Dépôt de `amount` public dans le solde confidentiel de `recipient`
+    Shield,
+    // Transfert d'un montant caché entre soldes confidentiels (voir `confidential`)
+    ConfidentialTransfer(Box<ConfidentialTransfer>),
+    // Retrait de `amount` du solde confidentiel de l'émetteur vers le solde public de `recipient`
+    Unshield { balance_proof: Box<RangeProof> },
+    // Ancrage d'un document hors chaîne concernant `recipient` : empreinte
+    // SHA3-256 du contenu et, le cas échéant, CID IPFS où le trouver
+    Anchor { document: Vec<u8>, cid: Option<String> },
+    // Lot atomique : les transactions, signées par leurs émetteurs pour ce lot
+    // (voir `Transaction::bundle_id`), sont appliquées dans l'ordre, toutes ou aucune
+    Bundle(Vec<Transaction>),
+    // Enregistrement d'un actif dont l'émetteur est le propriétaire, avec ses
+    // lecteurs initiaux (voir `Assets`) ; identifié par le hash de la transaction
+    RegisterAsset { readers: Vec<PublicKey> },
+    // Accès du lecteur `recipient` aux attestations suivantes de l'actif, accordé par son propriétaire
+    GrantAccess { asset: Vec<u8> },
+    // Retrait de l'accès du lecteur `recipient`, par le propriétaire
+    RevokeAccess { asset: Vec<u8> },
+    // Attestation sur un actif, chiffrée pour ses participants et conservée
+    // chiffrée dans la chaîne
+    Attest { asset: Vec<u8>, payload: Box<EncryptedPayload> },
+}
+
+// Signature de l'émetteur, ou signatures des membres lorsque l'émetteur est un compte multisig
+#[derive(Clone, Debug, PartialEq)]
+enum Authorization {
+    Signature(Signature),
+    Multisig { policy: MultisigPolicy, signatures: Vec<(u8, Signature)> },
+}
+
+// Origine de l'horodatage des blocs produits localement, propre au nœud
+// (`SUPPLYX_TIMESTAMP_SOURCE`) ; l'horodatage reste borné par les règles de
+// consensus (`ChainConfig::max_timestamp_step` notamment)
+#[derive(Clone, Debug, PartialEq)]
+enum TimestampSource {
+    Local,
+    // Heure locale corrigée de la médiane des décalages rapportés par les pairs
+    MedianOfPeers,
+}
+
+impl TimestampSource {
+    fn parse(value: &str) -> Result<TimestampSource, String> {
+        match value {
+            "local" => Ok(TimestampSource::Local),
+            "median-of-peers" => Ok(TimestampSource::MedianOfPeers),
+            _ => Err(format!("Invalid timestamp source: {} (expected local or median-of-peers)", value)),
+        }
+    }
+}
+
+impl Block {
+    fn header(&self) -> BlockHeader {
+        BlockHeader {
+            chain_id: self.chain_id,
+            index: self.index,
+            slot: self.slot,
+            timestamp: self.timestamp,
+            difficulty: self.difficulty,
+            protocol_version: self.protocol_version,
+            rules_fingerprint: self.rules_fingerprint.clone(),
+            previous_hash: self.previous_hash.clone(),
+            transactions_root: self.transactions_root.clone(),
+            state_root: self.state_root.clone(),
+            snapshot_hash: self.snapshot_hash.clone(),
+            checkpoint: self.checkpoint.clone(),
+            current_hash: self.current_hash.clone(),
+            validator_signature: self.validator_signature,
+            validator_pubkey: self.validator_pubkey,
+        }
+    }
+
+    // Feuilles de l'arbre de Merkle : transactions publiques, privées puis de gouvernance
+    fn leaf_hashes(transactions: &[Transaction], private_transactions: &[PrivateTransaction], governance_transactions: &[GovernanceTransaction]) -> Vec<Vec<u8>> {
+        transactions.iter().map(|tx| tx.hash())
+            .chain(private_transactions.iter().map(|tx| tx.hash()))
+            .chain(governance_transactions.iter().map(|tx| tx.hash()))
+            .collect()
+    }
+
+    fn transactions_root(transactions: &[Transaction], private_transactions: &[PrivateTransaction], governance_transactions: &[GovernanceTransaction]) -> Vec<u8> {
+        merkle_root(&Self::leaf_hashes(transactions, private_transactions, governance_transactions))
+    }
+}
+
+impl BlockHeader {
+    #[allow(clippy::too_many_arguments)]
+    fn compute_hash(chain_id: u64, index: u64, slot: u64, timestamp: u64, difficulty: u64, protocol_version: u32, rules_fingerprint: &[u8], previous_hash: &[u8], transactions_root: &[u8], state_root: &[u8], snapshot_hash: &[u8], checkpoint: Option<&Checkpoint>, validator_pubkey: &PublicKey) -> Vec<u8> {
+        Self::encoder(chain_id, index, slot, timestamp, difficulty, protocol_version, rules_fingerprint, previous_hash, transactions_root, state_root, snapshot_hash, checkpoint, validator_pubkey).hash()
+    }
+
+    // Encodage canonique haché pour obtenir `current_hash`
+    #[allow(clippy::too_many_arguments)]
+    fn encoder(chain_id: u64, index: u64, slot: u64, timestamp: u64, difficulty: u64, protocol_version: u32, rules_fingerprint: &[u8], previous_hash: &[u8], transactions_root: &[u8], state_root: &[u8], snapshot_hash: &[u8], checkpoint: Option<&Checkpoint>, validator_pubkey: &PublicKey) -> Encoder {
+        let mut encoder = Encoder::new(BLOCK_DOMAIN);
+        encoder.put_u64(chain_id);
+        encoder.put_u64(index);
+        encoder.put_u64(slot);
+        encoder.put_u64(timestamp);
+        encoder.put_u64(difficulty);
+        encoder.put_u32(protocol_version);
+        encoder.put_bytes(rules_fingerprint);
+        encoder.put_bytes(previous_hash);
+        encoder.put_bytes(transactions_root);
+        encoder.put_bytes(state_root);
+        encoder.put_bytes(snapshot_hash);
+        encoder.put_bytes(&checkpoint.map(|c| c.hash()).unwrap_or_default());
+        encoder.put_public_key(validator_pubkey);
+        encoder
+    }
+
+    fn signing_bytes(&self) -> Vec<u8> {
+        Self::encoder(self.chain_id, self.index, self.slot, self.timestamp, self.difficulty, self.protocol_version, &self.rules_fingerprint, &self.previous_hash, &self.transactions_root, &self.state_root, &self.snapshot_hash, self.checkpoint.as_ref(), &self.validator_pubkey).finish()
+    }
+
+    // Hash et signature du validateur, vérifiables sans le corps du bloc
+    fn verify(&self) -> Result<(), &'static str> {
+        let expected_hash = Self::compute_hash(self.chain_id, self.index, self.slot, self.timestamp, self.difficulty, self.protocol_version, &self.rules_fingerprint, &self.previous_hash, &self.transactions_root, &self.state_root, &self.snapshot_hash, self.checkpoint.as_ref(), &self.validator_pubkey);
+        if self.current_hash != expected_hash {
+            return Err("Invalid block hash");
+        }
+
+        self.validator_pubkey.verify(&self.current_hash, &self.validator_signature)
+            .map_err(|_| "Invalid validator signature")
+    }
+}
+
+impl TransactionKind {
+    fn encode_into(&self, encoder: &mut Encoder) {
+        match self {
+            TransactionKind::Transfer => encode_transfer(encoder),
+            TransactionKind::Escrow { unlock_height, arbiter } => {
+                encoder.put_u8(1);
+                encoder.put_u64(*unlock_height);
+                match arbiter {
+                    Some(arbiter) => {
+                        encoder.put_u8(1);
+                        encoder.put_public_key(arbiter);
+                    }
+                    None => encoder.put_u8(0),
+                }
+            }
+            TransactionKind::Claim { escrow } => {
+                encoder.put_u8(2);
+                encoder.put_bytes(escrow);
+            }
+            TransactionKind::Refund { escrow } => {
+                encoder.put_u8(3);
+                encoder.put_bytes(escrow);
+            }
+            TransactionKind::Deploy { code } => {
+                encoder.put_u8(4);
+                encoder.put_bytes(code);
+            }
+            TransactionKind::Call { input, gas_limit } => {
+                encoder.put_u8(5);
+                encoder.put_bytes(input);
+                encoder.put_u64(*gas_limit);
+            }
+            TransactionKind::SetRecovery { guardians, challenge_period } => {
+                encoder.put_u8(6);
+                guardians.encode_into(encoder);
+                encoder.put_u64(*challenge_period);
+            }
+            TransactionKind::Recover { account } => {
+                encoder.put_u8(7);
+                encoder.put_public_key(account);
+            }
+            TransactionKind::VetoRecovery => encoder.put_u8(8),
+            TransactionKind::Shield => encoder.put_u8(9),
+            TransactionKind::ConfidentialTransfer(transfer) => {
+                encoder.put_u8(10);
+                transfer.encode_into(encoder);
+            }
+            TransactionKind::Unshield { balance_proof } => {
+                encoder.put_u8(11);
+                balance_proof.encode_into(encoder);
+            }
+            TransactionKind::Anchor { document, cid } => encode_anchor(encoder, document, cid.as_deref()),
+            TransactionKind::Bundle(transactions) => {
+                encoder.put_u8(13);
+                encoder.put_bytes(&encode_block_body(transactions));
+            }
+            TransactionKind::RegisterAsset { readers } => {
+                encoder.put_u8(14);
+                encoder.put_u32(readers.len() as u32);
+                for reader in readers {
+                    encoder.put_public_key(reader);
+                }
+            }
+            TransactionKind::GrantAccess { asset } => {
+                encoder.put_u8(15);
+                encoder.put_bytes(asset);
+            }
+            TransactionKind::RevokeAccess { asset } => {
+                encoder.put_u8(16);
+                encoder.put_bytes(asset);
+            }
+            TransactionKind::Attest { asset, payload } => {
+                encoder.put_u8(17);
+                encoder.put_bytes(asset);
+                payload.encode_into(encoder);
+            }
+        }
+    }
+
+    // `bundles` est faux pour les transactions d'un lot, qui ne peut en contenir d'autre
+    fn decode_from(decoder: &mut Decoder, bundles: bool) -> Result<TransactionKind, &'static str> {
+        match decoder.get_u8()? {
+            0 => Ok(TransactionKind::Transfer),
+            1 => Ok(TransactionKind::Escrow {
+                unlock_height: decoder.get_u64()?,
+                arbiter: match decoder.get_u8()? {
+                    0 => None,
+                    1 => Some(decoder.get_public_key()?),
+                    _ => return Err("Invalid arbiter flag"),
+                },
+            }),
+            2 => Ok(TransactionKind::Claim { escrow: decoder.get_bytes()?.to_vec() }),
+            3 => Ok(TransactionKind::Refund { escrow: decoder.get_bytes()?.to_vec() }),
+            4 => Ok(TransactionKind::Deploy { code: decoder.get_bytes()?.to_vec() }),
+            5 => Ok(TransactionKind::Call { input: decoder.get_bytes()?.to_vec(), gas_limit: decoder.get_u64()? }),
+            6 => Ok(TransactionKind::SetRecovery { guardians: MultisigPolicy::decode_from(decoder)?, challenge_period: decoder.get_u64()? }),
+            7 => Ok(TransactionKind::Recover { account: decoder.get_public_key()? }),
+            8 => Ok(TransactionKind::VetoRecovery),
+            9 => Ok(TransactionKind::Shield),
+            10 => Ok(TransactionKind::ConfidentialTransfer(Box::new(ConfidentialTransfer::decode_from(decoder)?))),
+            11 => Ok(TransactionKind::Unshield { balance_proof: Box::new(RangeProof::decode_from(decoder)?) }),
+            12 => Ok(TransactionKind::Anchor {
+                document: decoder.get_bytes()?.to_vec(),
+                cid: match decoder.get_u8()? {
+                    0 => None,
+                    1 => Some(String::from_utf8(decoder.get_bytes()?.to_vec()).map_err(|_| "Invalid document CID")?),
+                    _ => return Err("Invalid CID flag"),
+                },
+            }),
+            13 if bundles => Ok(TransactionKind::Bundle(decode_bundle_body(decoder.get_bytes()?)?)),
+            13 => Err("Bundles cannot be nested"),
+            14 => {
+                let count = decoder.get_u32()? as usize;
+                if count > MAX_ASSET_READERS {
+                    return Err("Too many asset readers");
+                }
+                let readers = (0..count).map(|_| decoder.get_public_key()).collect::<Result<_, _>>()?;
+                Ok(TransactionKind::RegisterAsset { readers })
+            }
+            15 => Ok(TransactionKind::GrantAccess { asset: decoder.get_bytes()?.to_vec() }),
+            16 => Ok(TransactionKind::RevokeAccess { asset: decoder.get_bytes()?.to_vec() }),
+            17 => Ok(TransactionKind::Attest { asset: decoder.get_bytes()?.to_vec(), payload: Box::new(EncryptedPayload::decode_from(decoder)?) }),
+            _ => Err("Unknown transaction kind"),
+        }
+    }
+}
+
+impl Transaction {
+    #[allow(clippy::too_many_arguments)]
+    fn signing_hash(version: u32, chain_id: u64, sender: &PublicKey, nonce: u64, recipient: &PublicKey, amount: u64, kind: &TransactionKind, gas_price: u64, timestamp: u64, valid_from: u64, valid_until: Expiry) -> Vec<u8> {
+        Self::encoder(version, chain_id, sender, nonce, recipient, amount, kind, gas_price, timestamp, valid_from, valid_until).hash()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn encoder(version: u32, chain_id: u64, sender: &PublicKey, nonce: u64, recipient: &PublicKey, amount: u64, kind: &TransactionKind, gas_price: u64, timestamp: u64, valid_from: u64, valid_until: Expiry) -> Encoder {
+        signing_encoder(version, chain_id, sender, nonce, recipient, amount, |encoder| kind.encode_into(encoder), gas_price, timestamp, valid_from, valid_until)
+    }
+
+    fn signing_bytes(&self) -> Vec<u8> {
+        Self::encoder(self.version, self.chain_id, &self.sender, self.nonce, &self.recipient, self.amount, &self.kind, self.gas_price, self.timestamp, self.valid_from, self.valid_until).finish()
+    }
+
+    fn hash(&self) -> Vec<u8> {
+        Self::signing_hash(self.version, self.chain_id, &self.sender, self.nonce, &self.recipient, self.amount, &self.kind, self.gas_price, self.timestamp, self.valid_from, self.valid_until)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn signed(chain_id: u64, sender: &Keypair, nonce: u64, recipient: &PublicKey, amount: u64, kind: TransactionKind, gas_price: u64, timestamp: u64, valid_until: Expiry) -> Transaction {
+        Self::scheduled(chain_id, sender, nonce, recipient, amount, kind, gas_price, timestamp, 0, valid_until)
+    }
+
+    // Transaction programmée : incluable à partir du bloc `valid_from` seulement
+    #[allow(clippy::too_many_arguments)]
+    fn scheduled(chain_id: u64, sender: &Keypair, nonce: u64, recipient: &PublicKey, amount: u64, kind: TransactionKind, gas_price: u64, timestamp: u64, valid_from: u64, valid_until: Expiry) -> Transaction {
+        let signature = sender.sign(&Self::signing_hash(TRANSACTION_VERSION, chain_id, &sender.public, nonce, recipient, amount, &kind, gas_price, timestamp, valid_from, valid_until));
+        Transaction {
+            version: TRANSACTION_VERSION,
+            chain_id,
+            sender: sender.public,
+            nonce,
+            recipient: *recipient,
+            amount,
+            kind,
+            gas_price,
+            authorization: Authorization::Signature(signature),
+            timestamp,
+            valid_from,
+            valid_until,
+        }
+    }
+
+    // Transaction d'un compte multisig, sans signature, à faire circuler entre les membres
+    #[allow(clippy::too_many_arguments)]
+    fn multisig(chain_id: u64, policy: MultisigPolicy, nonce: u64, recipient: &PublicKey, amount: u64, kind: TransactionKind, gas_price: u64, timestamp: u64, valid_until: Expiry) -> Transaction {
+        Transaction {
+            version: TRANSACTION_VERSION,
+            chain_id,
+            sender: policy.address(),
+            nonce,
+            recipient: *recipient,
+            amount,
+            kind,
+            gas_price,
+            authorization: Authorization::Multisig { policy, signatures: Vec::new() },
+            timestamp,
+            valid_from: 0,
+            valid_until,
+        }
+    }
+
+    // Ajoute la signature partielle d'un membre du compte multisig émetteur
+    fn cosign(&mut self, keypair: &Keypair) -> Result<(), &'static str> {
+        let hash = self.hash();
+        let Authorization::Multisig { policy, signatures } = &mut self.authorization else {
+            return Err("Not a multisig transaction");
+        };
+        let approval = policy.approve(keypair, &hash)?;
+        merge_signatures(signatures, &[approval]);
+        Ok(())
+    }
+
+    // Identifiant d'un lot atomique, signé par l'émetteur de chacune de ses
+    // transactions à la place de leur hash : une transaction du lot n'est
+    // valide ni seule ni dans un autre lot
+    fn bundle_id(transactions: &[Transaction]) -> Vec<u8> {
+        let mut encoder = Encoder::new(TRANSACTION_BUNDLE_DOMAIN);
+        encoder.put_varint(transactions.len() as u64);
+        for transaction in transactions {
+            encoder.put_bytes(&transaction.hash());
+        }
+        encoder.hash()
+    }
+
+    // Signature pour le lot `bundle_id` d'une transaction créée par `Transaction::signed`
+    fn sign_for_bundle(&mut self, keypair: &Keypair, bundle_id: &[u8]) -> Result<(), &'static str> {
+        if keypair.public != self.sender {
+            return Err("Not the transaction sender");
+        }
+        self.authorization = Authorization::Signature(keypair.sign(bundle_id));
+        Ok(())
+    }
+
+    // Transactions contenues dans un lot atomique
+    fn inner_transactions(&self) -> &[Transaction] {
+        match &self.kind {
+            TransactionKind::Bundle(transactions) => transactions,
+            _ => &[],
+        }
+    }
+
+    // Premier bloc pouvant l'inclure avec toutes les transactions de son lot
+    fn executable_from(&self) -> u64 {
+        std::iter::once(self).chain(self.inner_transactions()).map(|transaction| transaction.valid_from).max().unwrap_or_default()
+    }
+
+    // Vrai si un bloc de hauteur `height` et d'horodatage `timestamp` ne peut plus
+    // l'inclure, ou plus inclure l'une des transactions de son lot
+    fn is_expired(&self, height: u64, timestamp: u64) -> bool {
+        self.valid_until.is_expired(height, timestamp)
+            || self.inner_transactions().iter().any(|transaction| transaction.valid_until.is_expired(height, timestamp))
+    }
+
+    // Un bloc de hauteur `height` et d'horodatage `timestamp` ne peut l'inclure
+    // que si elle, et chaque transaction de son lot, expire au plus tard
+    // MAX_TRANSACTION_LIFETIME plus loin
+    fn check_lifetime(&self, height: u64, timestamp: u64) -> Result<(), &'static str> {
+        std::iter::once(self).chain(self.inner_transactions()).try_for_each(|transaction| match transaction.valid_until {
+            Expiry::Height(last) if last <= height.saturating_add(MAX_TRANSACTION_LIFETIME_BLOCKS) => Ok(()),
+            Expiry::Timestamp(last) if last <= timestamp.saturating_add(MAX_TRANSACTION_LIFETIME) => Ok(()),
+            _ => Err("Transaction expiry too far"),
+        })
+    }
+
+    // Vérifications sans état, pouvant se faire hors du verrou de la chaîne
+    fn verify(&self) -> Result<(), &'static str> {
+        self.verify_signed(&self.hash())
+    }
+
+    // `verify` pour une transaction dont l'autorisation porte sur `message` :
+    // son propre hash, ou l'identifiant du lot qui la contient
+    fn verify_signed(&self, message: &[u8]) -> Result<(), &'static str> {
+        self.verify_content()?;
+        self.verify_authorization(message)
+    }
+
+    // Vérifications sans état hors autorisation de l'émetteur : montant, preuves,
+    // ancrage, adresse de contrat et transactions d'un lot avec leurs signatures
+    fn verify_content(&self) -> Result<(), &'static str> {
+        // Déploiements et appels de contrat peuvent ne transférer aucun montant,
+        // les transactions de récupération n'en transfèrent aucun
+        let contract = matches!(self.kind, TransactionKind::Deploy { .. } | TransactionKind::Call { .. });
+        let recovery = matches!(self.kind, TransactionKind::SetRecovery { .. } | TransactionKind::Recover { .. } | TransactionKind::VetoRecovery);
+        if recovery && self.amount != 0 {
+            return Err("Recovery transactions carry no amount");
+        }
+        let anchor = matches!(self.kind, TransactionKind::Anchor { .. });
+        if anchor && self.amount != 0 {
+            return Err("Anchors carry no amount");
+        }
+        let bundle = matches!(self.kind, TransactionKind::Bundle(_));
+        if bundle && self.amount != 0 {
+            return Err("Bundles carry no amount");
+        }
+        let asset = matches!(self.kind, TransactionKind::RegisterAsset { .. } | TransactionKind::GrantAccess { .. } | TransactionKind::RevokeAccess { .. } | TransactionKind::Attest { .. });
+        if asset && self.amount != 0 {
+            return Err("Asset transactions carry no amount");
+        }
+        // Le montant d'un transfert confidentiel n'est porté que par son engagement
+        if let TransactionKind::ConfidentialTransfer(transfer) = &self.kind {
+            if self.amount != 0 {
+                return Err("Confidential transfers carry no public amount");
+            }
+            transfer.verify()?;
+        } else if self.amount == 0 && !contract && !recovery && !anchor && !bundle && !asset {
+            return Err("Invalid transaction amount");
+        }
+        if let TransactionKind::Anchor { document, cid } = &self.kind {
+            anchors::check(document, cid.as_deref())?;
+        }
+        if let TransactionKind::RegisterAsset { readers } = &self.kind {
+            assets::check_readers(&self.sender, readers)?;
+        }
+        if let Expiry::Height(last) = self.valid_until {
+            if self.valid_from > last {
+                return Err("Empty execution window");
+            }
+        }
+        if let TransactionKind::Bundle(transactions) = &self.kind {
+            if transactions.is_empty() || transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+                return Err("Invalid bundle size");
+            }
+            let id = Transaction::bundle_id(transactions);
+            for transaction in transactions {
+                if matches!(transaction.kind, TransactionKind::Bundle(_)) {
+                    return Err("Bundles cannot be nested");
+                }
+                if transaction.chain_id != self.chain_id {
+                    return Err("Bundled transaction signed for another chain");
+                }
+                transaction.verify_signed(&id)?;
+            }
+        }
+        if let TransactionKind::Deploy { code } = &self.kind {
+            if self.recipient != contract_address(&self.sender, self.timestamp, code) {
+                return Err("Recipient is not the contract address");
+            }
+        }
+        Ok(())
+    }
+
+    fn verify_authorization(&self, message: &[u8]) -> Result<(), &'static str> {
+        match &self.authorization {
+            Authorization::Signature(signature) => self.sender.verify(message, signature)
+                .map_err(|_| "Invalid transaction signature"),
+            Authorization::Multisig { policy, signatures } => {
+                if policy.address() != self.sender {
+                    return Err("Sender is not the multisig address");
+                }
+                policy.verify(message, signatures, true)
+            }
+        }
+    }
 }
 
 struct Blockchain {
     chain: Vec<Block>,
     validators: HashMap<PublicKey, Validator>,
     pending_transactions: Vec<Transaction>,
+    mempool_guard: MempoolGuard,
+    // Transactions multisig en cours de signature, par hash, et leur ordre d'arrivée
+    partial_multisig: HashMap<Vec<u8>, Transaction>,
+    partial_multisig_order: VecDeque<Vec<u8>>,
+    // Transactions programmées conservées par le nœud jusqu'à leur premier bloc
+    // d'inclusion (`valid_from`), puis admises au mempool
+    scheduled_transactions: BTreeMap<u64, Vec<Transaction>>,
+    // Transactions retirées du mempool sans être incluses, pour le suivi par les clients
+    dead_letters: DeadLetters,
+    pending_private_transactions: Vec<PrivateTransaction>,
+    // Hash de l'état privé courant de chaque groupe de confidentialité, par identifiant de groupe
+    private_states: HashMap<Vec<u8>, Vec<u8>>,
+    // Comptes à adresse dérivée (séquestres, contrats), dépensables uniquement par leurs règles
+    system_accounts: HashSet<PublicKey>,
+    accounts: AccountState,
+    // Reçus des transactions incluses, par hash de transaction
+    receipts: HashMap<Vec<u8>, Receipt>,
+    indexer: Indexer,
+    compliance: Compliance,
+    beacon: Beacon,
+    incidents: Incidents,
+    upgrades: Upgrades,
+    governance: Governance,
+    key_history: KeyHistory,
+    rewards: Rewards,
+    // Crédits de récompense reçus par chaque adresse
+    reward_history: RewardHistory,
+    // Arbre des soldes, dont la racine est engagée dans l'en-tête du bloc suivant
+    state_tree: StateTree,
+    // Comptes modifiés par les derniers blocs, pour la synchronisation différentielle
+    state_changes: ChangeJournal,
+    // Historique des soldes, en mode archive uniquement
+    archive: Option<BalanceArchive>,
+    pending_governance_transactions: Vec<GovernanceTransaction>,
+    // Configuration de départ ; les paramètres en vigueur sont dans `governance.params`
+    config: ChainConfig,
+    // Empreinte locale des règles de consensus et producteurs qui en annoncent une autre
+    rules: RulesMonitor,
+    // Difficulté du prochain bloc, réajustée en fin d'époque
     current_difficulty: u64,
+    timestamp_source: TimestampSource,
+    // Dernier décalage rapporté par chaque pair, du plus ancien au plus récent
+    peer_time_offsets: VecDeque<(String, i64)>,
+    clock: Arc<dyn Clock>,
+    events: EventBus,
+    // Dernier en-tête antérieur à `chain` lorsque la chaîne a démarré d'un instantané
+    base: Option<BlockHeader>,
+    // Horodatages des derniers blocs jusqu'à `base`, repris de l'instantané
+    base_timestamps: Vec<u64>,
+    latest_snapshot: Option<StateSnapshot>,
+    // Votes reçus pour chaque checkpoint non encore finalisé
+    checkpoint_votes: HashMap<u64, Vec<CheckpointVote>>,
+    finalized_height: Option<u64>,
 }
 
 impl Blockchain {
-    fn new() -> Self {
+    fn new(config: ChainConfig) -> Self {
         Blockchain {
             chain: Vec::new(),
             validators: HashMap::new(),
             pending_transactions: Vec::new(),
-            current_difficulty: 4,
+            mempool_guard: MempoolGuard::default(),
+            partial_multisig: HashMap::new(),
+            partial_multisig_order: VecDeque::new(),
+            scheduled_transactions: BTreeMap::new(),
+            dead_letters: DeadLetters::default(),
+            pending_private_transactions: Vec::new(),
+            private_states: HashMap::new(),
+            system_accounts: HashSet::new(),
+            accounts: AccountState::default(),
+            receipts: HashMap::new(),
+            indexer: Indexer::default(),
+            compliance: Compliance::default(),
+            beacon: Beacon::default(),
+            incidents: Incidents::default(),
+            upgrades: Upgrades::default(),
+            governance: Governance::new(config.consensus.clone()),
+            key_history: KeyHistory::default(),
+            rewards: Rewards::default(),
+            reward_history: RewardHistory::default(),
+            state_tree: StateTree::default(),
+            state_changes: ChangeJournal::default(),
+            archive: None,
+            pending_governance_transactions: Vec::new(),
+            current_difficulty: config.initial_difficulty,
+            config,
+            rules: RulesMonitor::default(),
+            timestamp_source: TimestampSource::Local,
+            peer_time_offsets: VecDeque::new(),
+            clock: Arc::new(SystemClock),
+            events: EventBus::new(),
+            base: None,
+            base_timestamps: Vec::new(),
+            latest_snapshot: None,
+            checkpoint_votes: HashMap::new(),
+            finalized_height: None,
         }
     }
 
-    fn select_validator(&self) -> Option<PublicKey> {
-        let total_weighted_stake: f64 = self.validators.values()
-            .map(|v| (v.stake as f64) * v.contribution_score)
-            .sum();
+    // Blocs à cette hauteur et en dessous sont irréversibles
+    fn finalized_height(&self) -> Option<u64> {
+        self.finalized_height
+    }
+
+    fn is_checkpoint_height(&self, index: u64) -> bool {
+        index > 0 && index % EPOCH_LENGTH == 0
+    }
 
-        let mut rng = OsRng;
-        let random_point: f64 = rng.gen::<f64>() * total_weighted_stake;
+    fn block_at(&self, index: u64) -> Option<&Block> {
+        let offset = index.checked_sub(self.first_index())?;
+        self.chain.get(offset as usize)
+    }
 
-        let mut cumulative_weight = 0.0;
-        for (pubkey, validator) in &self.validators {
-            cumulative_weight += (validator.stake as f64) * validator.contribution_score;
-            if cumulative_weight >= random_point {
-                return Some(*pubkey);
-            }
+    fn add_checkpoint_vote(&mut self, vote: CheckpointVote) -> Result<(), &'static str> {
+        if !self.is_checkpoint_height(vote.height) || self.finalized_height.is_some_and(|h| vote.height <= h) {
+            return Err("Vote for a non-pending checkpoint");
+        }
+        let block = self.block_at(vote.height).ok_or("Unknown checkpoint block")?;
+        if block.current_hash != vote.block_hash {
+            return Err("Checkpoint vote for another block");
         }
-        None
+        if !self.validators.contains_key(&vote.validator) {
+            return Err("Checkpoint vote from unknown validator");
+        }
+        vote.verify()?;
+
+        let votes = self.checkpoint_votes.entry(vote.height).or_default();
+        if !votes.iter().any(|v| v.validator == vote.validator) {
+            votes.push(vote.clone());
+            self.events.publish(ChainEvent::CheckpointVoteAdded(vote));
+        }
+        Ok(())
     }
 
-    fn create_transaction(&mut self, sender: &Keypair, recipient: &PublicKey, amount: u64) -> Result<(), &'static str> {
-        if amount == 0 {
-            return Err("Invalid transaction amount");
+    // Checkpoint le plus récent ayant atteint le quorum, à inclure dans le prochain
+    // bloc ; une époque plus récente encore incomplète ne masque pas les précédentes
+    fn checkpoint_ready(&self) -> Option<Checkpoint> {
+        let mut pending: Vec<(&u64, &Vec<CheckpointVote>)> = self.checkpoint_votes.iter()
+            .filter(|(&height, _)| self.finalized_height.is_none_or(|h| height > h))
+            .collect();
+        pending.sort_by_key(|(&height, _)| std::cmp::Reverse(height));
+        let (&height, votes) = pending.into_iter().find(|(_, votes)| {
+            let signed_stake: u128 = votes.iter()
+                .filter_map(|vote| self.validators.get(&vote.validator))
+                .map(|v| v.stake as u128)
+                .sum();
+            has_quorum(signed_stake, &self.validators)
+        })?;
+
+        let mut votes = votes.clone();
+        votes.sort_by_key(|vote| vote.validator.to_bytes());
+        Some(Checkpoint {
+            height,
+            block_hash: votes[0].block_hash.clone(),
+            votes,
+        })
+    }
+
+    fn finalize_checkpoint(&mut self, checkpoint: &Checkpoint) {
+        self.finalized_height = Some(checkpoint.height);
+        self.checkpoint_votes.retain(|&height, _| height > checkpoint.height);
+        self.events.publish(ChainEvent::CheckpointFinalized { height: checkpoint.height, block_hash: checkpoint.block_hash.clone() });
+    }
+
+    fn subscribe(&self) -> tokio::sync::broadcast::Receiver<ChainEvent> {
+        self.events.subscribe()
+    }
+
+    fn set_timestamp_source(&mut self, source: TimestampSource) {
+        self.timestamp_source = source;
+    }
+
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    // Heure locale, en secondes Unix
+    fn now(&self) -> u64 {
+        self.clock.unix_secs()
+    }
+
+    // Heure rapportée par un pair, conservée sous forme de décalage par rapport à
+    // l'heure locale ; seule la dernière de chaque pair compte
+    fn record_peer_time(&mut self, peer: &str, peer_time: u64) {
+        let local_time = self.now();
+        self.peer_time_offsets.retain(|(other, _)| other != peer);
+        if self.peer_time_offsets.len() == MAX_PEER_TIME_SAMPLES {
+            self.peer_time_offsets.pop_front();
         }
+        self.peer_time_offsets.push_back((peer.to_string(), peer_time as i64 - local_time as i64));
+    }
 
-        let timestamp = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+    fn block_timestamp(&self) -> u64 {
+        let local_time = self.now();
 
-        let transaction = Transaction {
-            sender: sender.public,
-            recipient: *recipient,
-            amount,
-            signature: sender.sign(&self.hash_transaction_data(sender.public, *recipient, amount, timestamp)),
-            timestamp,
+        let timestamp = match self.timestamp_source {
+            TimestampSource::Local => local_time,
+            TimestampSource::MedianOfPeers => {
+                // L'heure locale compte comme un échantillon de décalage nul
+                let mut offsets: Vec<i64> = self.peer_time_offsets.iter().map(|(_, offset)| *offset).collect();
+                offsets.push(0);
+                offsets.sort_unstable();
+                let median = offsets[offsets.len() / 2];
+                (local_time as i64 + median).max(0) as u64
+            }
         };
+        // Les horodatages ne reculent pas, même si l'horloge du parent avançait
+        let timestamp = self.last_header().map_or(timestamp, |parent| timestamp.max(parent.timestamp));
+        let timestamp = self.median_time_past().map_or(timestamp, |median| timestamp.max(median + 1));
+        match (self.config.max_timestamp_step, self.last_header()) {
+            (Some(max_step), Some(parent)) => timestamp.min(parent.timestamp.saturating_add(max_step)),
+            _ => timestamp,
+        }
+    }
 
-        self.pending_transactions.push(transaction);
+    // Horodatage admissible pour le prochain bloc : ni avant le parent ni au plus
+    // tard la médiane des blocs récents, ni trop loin après l'heure locale ou le parent
+    fn check_block_timestamp(&self, timestamp: u64) -> Result<(), &'static str> {
+        if self.last_header().is_some_and(|parent| timestamp < parent.timestamp) {
+            return Err("Block timestamp before its parent");
+        }
+        if self.median_time_past().is_some_and(|median| timestamp <= median) {
+            return Err("Block timestamp not after the median time past");
+        }
+        if timestamp > self.now().saturating_add(self.config.max_clock_drift) {
+            return Err("Block timestamp too far in the future");
+        }
+        if let (Some(max_step), Some(parent)) = (self.config.max_timestamp_step, self.last_header()) {
+            if timestamp > parent.timestamp.saturating_add(max_step) {
+                return Err("Block timestamp too far after its parent");
+            }
+        }
         Ok(())
     }
 
-    fn hash_transaction_data(&self, sender: PublicKey, recipient: PublicKey, amount: u64, timestamp: u64) -> Vec<u8> {
-        let mut hasher = Sha3_256::new();
-        hasher.update(sender.as_bytes());
-        hasher.update(recipient.as_bytes());
-        hasher.update(amount.to_be_bytes());
-        hasher.update(timestamp.to_be_bytes());
-        hasher.finalize().to_vec()
+    fn timestamp_at(&self, index: u64) -> Option<u64> {
+        self.block_at(index).map(|block| block.timestamp)
+            .or_else(|| self.base.as_ref().filter(|header| header.index == index).map(|header| header.timestamp))
     }
 
-    fn validate_and_create_block(&mut self, validator_keypair: &Keypair) -> Result<Block, &'static str> {
-        let validator_pubkey = validator_keypair.public;
+    // En fin d'époque, difficulté de l'époque suivante d'après la durée de celle
+    // qui s'achève
+    fn retarget_difficulty(&mut self) {
+        let next = self.next_index();
+        if next % EPOCH_LENGTH != 0 {
+            return;
+        }
+        if let (Some(first), Some(last)) = (self.timestamp_at(next - EPOCH_LENGTH), self.timestamp_at(next - 1)) {
+            self.current_difficulty = difficulty::retarget(self.current_difficulty, last.saturating_sub(first), EPOCH_LENGTH - 1, self.config.target_block_interval);
+        }
+    }
 
-        if let Some(validator) = self.validators.get(&validator_pubkey) {
-            if validator.stake < 1000 || validator.contribution_score < 0.5 {
-                return Err("Validator not qualified");
-            }
-        } else {
-            return Err("Validator not registered");
+    // Horodatages des MEDIAN_TIME_SPAN derniers blocs au plus, du plus ancien au plus récent
+    fn recent_timestamps(&self) -> Vec<u64> {
+        let timestamps: Vec<u64> = self.base_timestamps.iter().copied()
+            .chain(self.chain.iter().map(|block| block.timestamp))
+            .collect();
+        timestamps[timestamps.len().saturating_sub(MEDIAN_TIME_SPAN)..].to_vec()
+    }
+
+    // Médiane des horodatages des MEDIAN_TIME_SPAN derniers blocs (des blocs
+    // existants au début de la chaîne)
+    fn median_time_past(&self) -> Option<u64> {
+        let mut timestamps = self.recent_timestamps();
+        if timestamps.is_empty() {
+            return None;
         }
+        timestamps.sort_unstable();
+        Some(timestamps[timestamps.len() / 2])
+    }
+
+    fn select_leader(&self, slot: u64) -> Option<PublicKey> {
+        let _entered = debug_span!("leader.select", slot).entered();
+        let leader = select_leader(
+            &self.validators,
+            &self.last_hash(),
+            slot,
+            self.current_difficulty,
+            self.governance.params.min_producer_stake,
+            self.config.min_contribution_score,
+        );
+        debug!(leader = ?leader.map(|key| hex::encode(key.as_bytes())), "Leader selected");
+        leader
+    }
 
-        let previous_hash = if let Some(last_block) = self.chain.last() {
-            last_block.current_hash.clone()
+    fn last_hash(&self) -> Vec<u8> {
+        if let Some(last_header) = self.last_header() {
+            last_header.current_hash
         } else {
             vec![0; 32]
-        };
+        }
+    }
 
-        let index = self.chain.len() as u64;
-        let timestamp = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+    fn last_header(&self) -> Option<BlockHeader> {
+        self.chain.last().map(|block| block.header()).or_else(|| self.base.clone())
+    }
 
-        let current_hash = self.calculate_block_hash(&previous_hash, &self.pending_transactions);
+    // Index du premier bloc conservé dans `chain`
+    fn first_index(&self) -> u64 {
+        self.base.as_ref().map_or(0, |header| header.index + 1)
+    }
 
-        let block = Block {
-            index,
-            timestamp,
-            transactions: self.pending_transactions.clone(),
-            previous_hash,
-            current_hash: current_hash.clone(),
-            validator_signature: validator_keypair.sign(&current_hash),
-            validator_pubkey,
-        };
+    fn next_index(&self) -> u64 {
+        self.first_index() + self.chain.len() as u64
+    }
 
-        self.chain.push(block.clone());
-        self.pending_transactions.clear();
+    fn is_snapshot_height(&self, index: u64) -> bool {
+        index > 0 && index % SNAPSHOT_INTERVAL == 0
+    }
 
-        Ok(block)
+    // État après le dernier bloc appliqué
+    fn snapshot(&self) -> StateSnapshot {
+        let mut validators: Vec<Validator> = self.validators.values().cloned().collect();
+        validators.sort_by_key(|v| v.public_key.to_bytes());
+        let mut system_accounts: Vec<PublicKey> = self.system_accounts.iter().copied().collect();
+        system_accounts.sort_by_key(|key| key.to_bytes());
+        let mut private_states: Vec<(Vec<u8>, Vec<u8>)> = self.private_states.clone().into_iter().collect();
+        private_states.sort();
+        StateSnapshot {
+            height: self.next_index().saturating_sub(1),
+            block_hash: self.last_hash(),
+            validators,
+            private_states,
+            system_accounts,
+            ledger: self.accounts.ledger.clone(),
+            escrows: self.accounts.escrows.clone(),
+            vesting: self.accounts.vesting.clone(),
+            contracts: self.accounts.contracts.clone(),
+            recoveries: self.accounts.recoveries.clone(),
+            confidential: self.accounts.confidential.clone(),
+            assets: self.accounts.assets.clone(),
+            nonces: self.accounts.nonces.clone(),
+            compliance: self.compliance.clone(),
+            beacon: self.beacon.clone(),
+            incidents: self.incidents.clone(),
+            upgrades: self.upgrades.clone(),
+            governance: self.governance.clone(),
+            key_history: self.key_history.clone(),
+            rewards: self.rewards.clone(),
+            recent_timestamps: self.recent_timestamps(),
+            difficulty: self.current_difficulty,
+        }
     }
 
-    fn calculate_block_hash(&self, previous_hash: &[u8], transactions: &[Transaction]) -> Vec<u8> {
-        let mut hasher = Sha3_256::new();
-        hasher.update(previous_hash);
-        for tx in transactions {
-            hasher.update(self.hash_transaction_data(tx.sender, tx.recipient, tx.amount, tx.timestamp));
+    // Redémarre la chaîne à partir d'un instantané ; `header` est l'en-tête
+    // (déjà vérifié) du bloc auquel correspond l'instantané
+    fn restore_snapshot(&mut self, snapshot: StateSnapshot, header: BlockHeader) -> Result<(), &'static str> {
+        if header.chain_id != self.config.chain_id {
+            return Err("Snapshot of another chain");
+        }
+        if header.index != snapshot.height || header.current_hash != snapshot.block_hash || snapshot.recent_timestamps.last() != Some(&header.timestamp) {
+            return Err("Snapshot does not match header");
         }
-        hasher.finalize().to_vec()
+
+        self.load_state(&snapshot);
+        self.chain.clear();
+        self.indexer.clear();
+        self.reward_history.clear();
+        self.state_changes.clear();
+        self.drop_all_pending("Mempool cleared by a snapshot restore");
+        self.pending_private_transactions.clear();
+        self.pending_governance_transactions.clear();
+        self.base = Some(header);
+        self.base_timestamps = snapshot.recent_timestamps.clone();
+        self.latest_snapshot = Some(snapshot);
+        self.restart_archive();
+        Ok(())
     }
 
-    fn register_validator(&mut self, validator_keypair: &Keypair, initial_stake: u64) -> Result<(), &'static str> {
-        let pubkey = validator_keypair.public;
+    // Remplace l'état par celui d'un instantané, sans toucher aux blocs
+    fn load_state(&mut self, snapshot: &StateSnapshot) {
+        self.validators = snapshot.validators.iter().map(|v| (v.public_key, v.clone())).collect();
+        self.private_states = snapshot.private_states.iter().cloned().collect();
+        self.system_accounts = snapshot.system_accounts.iter().copied().collect();
+        self.accounts = AccountState {
+            ledger: snapshot.ledger.clone(),
+            escrows: snapshot.escrows.clone(),
+            vesting: snapshot.vesting.clone(),
+            contracts: snapshot.contracts.clone(),
+            recoveries: snapshot.recoveries.clone(),
+            confidential: snapshot.confidential.clone(),
+            assets: snapshot.assets.clone(),
+            nonces: snapshot.nonces.clone(),
+        };
+        self.compliance = snapshot.compliance.clone();
+        self.beacon = snapshot.beacon.clone();
+        self.incidents = snapshot.incidents.clone();
+        self.upgrades = snapshot.upgrades.clone();
+        self.governance = snapshot.governance.clone();
+        self.key_history = snapshot.key_history.clone();
+        self.rewards = snapshot.rewards.clone();
+        self.current_difficulty = snapshot.difficulty;
+        // Les soldes chargés ne sont pas des modifications d'un bloc
+        self.accounts.ledger.take_changes();
+        self.state_tree = StateTree::from_ledger(&self.accounts.ledger);
+    }
 
-        if self.validators.contains_key(&pubkey) {
-            return Err("Validator already registered");
+    fn create_transaction(&mut self, sender: &Keypair, recipient: &PublicKey, amount: u64) -> Result<(), &'static str> {
+        if amount == 0 {
+            return Err("Invalid transaction amount");
         }
 
-        if initial_stake < 500 {
-            return Err("Insufficient stake to become a validator");
-        }
+        let timestamp = self.now();
 
-        self.validators.insert(pubkey, Validator {
-            public_key: pubkey,
-            stake: initial_stake,
-            contribution_score: 1.0,
-            last_validated_block: None,
-        });
+        let transaction = Transaction::signed(self.config.chain_id, sender, self.next_nonce(&sender.public), recipient, amount, TransactionKind::Transfer, DEFAULT_GAS_PRICE, timestamp, Expiry::Timestamp(timestamp + TRANSACTION_VALIDITY));
+        self.admit_transaction(transaction)
+    }
 
-        Ok(())
+    fn asset(&self, id: &[u8]) -> Option<&Asset> {
+        self.accounts.assets.get(id)
     }
 
-    // Correction du nom de la méthode et de l'argument
-    fn adjust_contribution_score(&mut self, pubkey: &PublicKey, adjustment: f64) {
-        if let Some(validator) = self.validators.get_mut(pubkey) {
-            validator.contribution_score = (validator.contribution_score + adjustment).clamp(0.0, 10.0);
-        }
+    // Nonce suivant le plus grand utilisé par `sender` dans les transactions
+    // incluses, en attente ou programmées (lots compris)
+    fn next_nonce(&self, sender: &PublicKey) -> u64 {
+        self.pending_transactions.iter().chain(self.scheduled_transactions.values().flatten())
+            .flat_map(|transaction| std::iter::once(transaction).chain(transaction.inner_transactions()))
+            .filter(|transaction| transaction.sender == *sender)
+            .map(|transaction| transaction.nonce.saturating_add(1))
+            .fold(self.accounts.nonces.next(sender), u64::max)
     }
-}
 
-fn main() {
-    let mut blockchain = Blockchain::new();
-    let validator_keypair = Keypair::generate(&mut OsRng);
+    // Transaction déjà signée, reçue du réseau ou du RPC
+    fn add_transaction(&mut self, transaction: Transaction) -> Result<(), &'static str> {
+        transaction.verify()?;
+        self.admit_transaction(transaction)
+    }
+
+    // Lot de transactions signées, vérifiées en parallèle puis admises dans
+    // l'ordre ; chacune est admise ou refusée indépendamment des autres.
+    // Retourne le verdict de chaque transaction, dans l'ordre du lot.
+    fn submit_batch(&mut self, transactions: Vec<Transaction>) -> Vec<Result<(), &'static str>> {
+        let verified: Vec<(Transaction, Result<(), &'static str>)> = transactions.into_par_iter()
+            .map(|transaction| {
+                let verdict = transaction.verify();
+                (transaction, verdict)
+            })
+            .collect();
+        // Les transactions multisig peuvent n'apporter qu'une partie des signatures
+        verified.into_iter()
+            .map(|(transaction, verdict)| match transaction.authorization {
+                Authorization::Multisig { .. } => self.add_multisig_approval(transaction).map(|_| ()),
+                _ => verdict.and_then(|()| self.admit_transaction(transaction)),
+            })
+            .collect()
+    }
 
-    blockchain.register_validator(&validator_keypair, 1000).unwrap();
+    // Transaction dont `Transaction::verify` a déjà été appelé
+    fn admit_transaction(&mut self, transaction: Transaction) -> Result<(), &'static str> {
+        if transaction.chain_id != self.config.chain_id {
+            return Err("Transaction signed for another chain");
+        }
+        self.check_transaction_version(&transaction)?;
+        if transaction.is_expired(self.next_index(), self.block_timestamp()) {
+            return Err("Transaction expired");
+        }
+        self.check_spendable(&transaction)?;
+        if transaction.executable_from() > self.next_index() {
+            return self.hold_transaction(transaction);
+        }
+        transaction.check_lifetime(self.next_index(), self.block_timestamp())?;
+        let replaced = self.mempool_guard.check(&self.pending_transactions, &transaction)?;
+        self.accounts.prepare(&transaction, self.next_index())?;
+        if let Some(position) = replaced {
+            let previous = self.pending_transactions.remove(position);
+            self.drop_transaction(previous, TransactionStatus::Dropped { reason: "Replaced by a higher fee" });
+        }
+        self.dead_letters.remove(&transaction.hash());
+        self.events.publish(ChainEvent::TransactionAccepted(transaction.clone()));
+        self.pending_transactions.push(transaction);
+        Ok(())
+    }
 
-    let recipient_keypair = Keypair::generate(&mut OsRng);
-    blockchain.create_transaction(&validator_keypair, &recipient_keypair.public, 50).unwrap();
+    // Vide le mempool et les transactions programmées après un changement d'état
+    // hors de l'application des blocs
+    fn drop_all_pending(&mut self, reason: &'static str) {
+        let scheduled = std::mem::take(&mut self.scheduled_transactions);
+        for transaction in std::mem::take(&mut self.pending_transactions).into_iter().chain(scheduled.into_values().flatten()) {
+            self.drop_transaction(transaction, TransactionStatus::Dropped { reason });
+        }
+    }
 
-    let new_block = blockchain.validate_and_create_block(&validator_keypair).unwrap();
-    println!("Block created: {:?}", new_block);
+    // Transaction retirée du mempool ou des transactions programmées sans être incluse
+    fn drop_transaction(&mut self, transaction: Transaction, status: TransactionStatus) {
+        self.events.publish(ChainEvent::TransactionDropped { transaction: transaction.clone(), status });
+        self.dead_letters.record(transaction, status);
+    }
+
+    // Statut d'une transaction : incluse, en attente ou retirée du mempool ;
+    // aucun pour une transaction inconnue du nœud ou retirée depuis trop longtemps
+    fn transaction_status(&self, hash: &[u8]) -> Option<TransactionStatus> {
+        if let Some(receipt) = self.receipts.get(hash) {
+            return Some(TransactionStatus::Included { height: receipt.block_index });
+        }
+        if self.pending_transactions.iter().chain(self.scheduled_transactions.values().flatten()).any(|tx| tx.hash() == hash) {
+            return Some(TransactionStatus::Pending);
+        }
+        self.dead_letters.get(hash).map(|(_, status)| *status)
+    }
+
+    // Transaction programmée, conservée hors du mempool jusqu'à son premier bloc
+    // d'inclusion ; le solde de l'émetteur n'est vérifié qu'à ce moment
+    fn hold_transaction(&mut self, transaction: Transaction) -> Result<(), &'static str> {
+        if self.scheduled_transactions.values().map(Vec::len).sum::<usize>() >= MAX_SCHEDULED_TRANSACTIONS {
+            return Err("Too many scheduled transactions");
+        }
+        self.dead_letters.remove(&transaction.hash());
+        self.scheduled_transactions.entry(transaction.executable_from()).or_default().push(transaction);
+        Ok(())
+    }
+
+    // Admet au mempool les transactions programmées que le bloc `height` peut inclure
+    fn release_scheduled_transactions(&mut self, height: u64) {
+        let later = self.scheduled_transactions.split_off(&(height + 1));
+        let due = std::mem::replace(&mut self.scheduled_transactions, later);
+        for transaction in due.into_values().flatten() {
+            if let Err(e) = self.admit_transaction(transaction.clone()) {
+                debug!(error = e, height, "Scheduled transaction not admitted");
+                self.drop_transaction(transaction, TransactionStatus::Dropped { reason: e });
+            }
+        }
+    }
+
+    // Retire du mempool et des transactions programmées celles qu'un bloc de
+    // hauteur `height` et d'horodatage `timestamp` ne peut plus inclure
+    fn drop_expired_transactions(&mut self, height: u64, timestamp: u64) {
+        let pending = std::mem::take(&mut self.pending_transactions);
+        let (expired, pending): (Vec<Transaction>, Vec<Transaction>) = pending.into_iter()
+            .partition(|tx| tx.is_expired(height, timestamp));
+        self.pending_transactions = pending;
+        if !expired.is_empty() {
+            debug!(count = expired.len(), height, "Expired transactions dropped from the mempool");
+        }
+        let scheduled = std::mem::take(&mut self.scheduled_transactions);
+        let mut expired_scheduled = Vec::new();
+        for (valid_from, transactions) in scheduled {
+            let (expired, transactions): (Vec<Transaction>, Vec<Transaction>) = transactions.into_iter()
+                .partition(|tx| tx.is_expired(height, timestamp));
+            expired_scheduled.extend(expired);
+            if !transactions.is_empty() {
+                self.scheduled_transactions.insert(valid_from, transactions);
+            }
+        }
+        for transaction in expired.into_iter().chain(expired_scheduled) {
+            self.drop_transaction(transaction, TransactionStatus::Expired);
+        }
+        self.partial_multisig.retain(|_, transaction| !transaction.is_expired(height, timestamp));
+        let partial_multisig = &self.partial_multisig;
+        self.partial_multisig_order.retain(|hash| partial_multisig.contains_key(hash));
+    }
+
+    // Règles d'admission au mempool propres au nœud
+    fn set_mempool_policy(&mut self, policy: MempoolPolicy) {
+        self.mempool_guard = MempoolGuard::new(policy);
+    }
+
+    // Signatures partielles d'une transaction multisig : fusionnées avec celles
+    // déjà reçues, la transaction est admise dès que le seuil est atteint. Les
+    // signatures nouvelles d'une transaction incomplète sont relayées aux pairs
+    // (`ChainEvent::MultisigApprovalAdded`). Retourne `true` si la transaction a été admise.
+    fn add_multisig_approval(&mut self, transaction: Transaction) -> Result<bool, &'static str> {
+        if transaction.chain_id != self.config.chain_id {
+            return Err("Transaction signed for another chain");
+        }
+        let hash = transaction.hash();
+        let Authorization::Multisig { policy, signatures } = &transaction.authorization else {
+            return Err("Not a multisig transaction");
+        };
+        if policy.address() != transaction.sender {
+            return Err("Sender is not the multisig address");
+        }
+        policy.verify(&hash, signatures, false)?;
+        let (height, timestamp) = (self.next_index(), self.block_timestamp());
+        if transaction.is_expired(height, timestamp) {
+            return Err("Transaction expired");
+        }
+        transaction.check_lifetime(height, timestamp)?;
+
+        if !self.partial_multisig.contains_key(&hash) {
+            if self.partial_multisig_order.len() == MAX_PARTIAL_MULTISIG {
+                if let Some(oldest) = self.partial_multisig_order.pop_front() {
+                    self.partial_multisig.remove(&oldest);
+                }
+            }
+            self.partial_multisig_order.push_back(hash.clone());
+            let mut unsigned = transaction.clone();
+            unsigned.authorization = Authorization::Multisig { policy: policy.clone(), signatures: Vec::new() };
+            self.partial_multisig.insert(hash.clone(), unsigned);
+        }
+        let partial = self.partial_multisig.get_mut(&hash).unwrap();
+        let Authorization::Multisig { signatures: collected, .. } = &mut partial.authorization else {
+            unreachable!("partial multisig transactions keep their multisig authorization");
+        };
+        let known = collected.len();
+        merge_signatures(collected, signatures);
+        let added = collected.len() > known;
+        if partial.verify().is_err() {
+            if added {
+                let partial = partial.clone();
+                self.events.publish(ChainEvent::MultisigApprovalAdded(partial));
+            }
+            return Ok(false);
+        }
+
+        let complete = self.partial_multisig.remove(&hash).unwrap();
+        self.partial_multisig_order.retain(|pending| *pending != hash);
+        self.admit_transaction(complete)?;
+        Ok(true)
+    }
+
+    // Les comptes système ne sont débités que par les règles qui les gouvernent,
+    // jamais par une transaction signée ; les comptes gelés sont bloqués. Les
+    // transactions d'un lot sont soumises aux mêmes règles.
+    fn check_spendable(&self, transaction: &Transaction) -> Result<(), &'static str> {
+        if self.system_accounts.contains(&transaction.sender) {
+            return Err("Cannot spend from a system account");
+        }
+        // Un compte gelé ne peut pas être transféré vers une nouvelle clé
+        if let TransactionKind::Recover { account } = &transaction.kind {
+            self.compliance.check_transfer(account, &transaction.recipient)?;
+        }
+        for inner in transaction.inner_transactions() {
+            self.check_spendable(inner)?;
+        }
+        self.compliance.check_transfer(&transaction.sender, &transaction.recipient)
+    }
+
+    // Mode permissionné : clé autorisée à geler et dégeler des comptes
+    fn add_compliance_admin(&mut self, admin: PublicKey) {
+        self.compliance.add_admin(admin);
+    }
+
+    fn freeze_record(&self, account: &PublicKey) -> Option<&FreezeRecord> {
+        self.compliance.freeze_record(account)
+    }
+
+    // Historique des ordres de gel visant `account` dans les blocs conservés, avec leur hauteur
+    fn freeze_orders(&self, account: &PublicKey) -> Vec<(u64, FreezeOrder)> {
+        self.chain.iter()
+            .flat_map(|block| block.governance_transactions.iter().map(move |tx| (block.index, tx)))
+            .filter_map(|(index, tx)| match tx {
+                GovernanceTransaction::Freeze(order) if order.account == *account => Some((index, order.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn incident(&self, id: &[u8]) -> Option<&Incident> {
+        self.incidents.get(id)
+    }
+
+    // Notes chiffrées d'un incident dans les blocs conservés, avec leur hauteur ;
+    // chacune se retrouve dans la chronologie par son hash
+    fn incident_notes(&self, id: &[u8]) -> Vec<(u64, IncidentNote)> {
+        self.chain.iter()
+            .flat_map(|block| block.governance_transactions.iter().map(move |tx| (block.index, tx)))
+            .filter_map(|(index, tx)| match tx {
+                GovernanceTransaction::IncidentNote(note) if note.incident_id == id => Some((index, note.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Allocation de genèse, immédiatement disponible
+    fn allocate(&mut self, account: &PublicKey, amount: u64) {
+        self.accounts.ledger.credit(account, amount);
+        self.state_tree.update(&self.accounts.ledger, [account]);
+    }
+
+    fn balance(&self, account: &PublicKey) -> u64 {
+        self.accounts.ledger.balance(account)
+    }
+
+    // Solde d'un compte après le bloc `height` (mode archive)
+    fn balance_at(&self, account: &PublicKey, height: u64) -> Result<u64, &'static str> {
+        let archive = self.archive.as_ref().ok_or("Archive mode disabled")?;
+        if height >= self.next_index() {
+            return Err("Height not reached");
+        }
+        archive.balance_at(account, height)
+    }
+
+    // Allocation de genèse d'un membre, acquise selon `schedule`
+    fn add_vesting_account(&mut self, account: PublicKey, schedule: VestingSchedule) -> Result<(), &'static str> {
+        let total = schedule.total;
+        self.accounts.vesting.add(account, schedule)?;
+        self.accounts.ledger.credit(&account, total);
+        self.state_tree.update(&self.accounts.ledger, [&account]);
+        Ok(())
+    }
+
+    // Soldes acquis, bloqués et disponibles d'un compte en acquisition, pour le prochain bloc
+    fn vesting_balance(&self, account: &PublicKey) -> Option<VestingBalance> {
+        self.accounts.vesting.balance(account, self.balance(account), self.next_index())
+    }
+
+    // Récupération du compte en cours de contestation, le cas échéant
+    fn pending_recovery(&self, account: &PublicKey) -> Option<&PendingRecovery> {
+        self.accounts.recoveries.pending(account)
+    }
+
+    // Taille, frais, âge et types des transactions publiques en attente, et
+    // nombre de blocs pleins nécessaires pour les inclure
+    fn mempool_status(&self) -> MempoolStatus {
+        let now = self.now();
+        let params = &self.governance.params;
+        mempool::status(&self.pending_transactions, now, params.max_block_gas, params.max_block_transactions)
+    }
+
+    // Crédits de récompense reçus par `address`, les plus récents d'abord
+    fn reward_history(&self, address: &PublicKey, offset: usize, limit: usize) -> Page<RewardCredit> {
+        paginate(self.reward_history.credits(address), offset, limit)
+    }
+
+    // Commission en vigueur d'un validateur et frais accumulés depuis la dernière distribution
+    fn validator_rewards(&self, validator: &PublicKey) -> (u64, u64) {
+        (self.rewards.commission(validator), self.rewards.accrued(validator))
+    }
+
+    // Hashes des transactions émises ou reçues par `address`, les plus récentes d'abord
+    fn transactions_by_address(&self, address: &PublicKey, offset: usize, limit: usize) -> Page<Vec<u8>> {
+        self.indexer.transactions(address, offset, limit)
+    }
+
+    // Blocs conservés, du plus récent au plus ancien
+    fn blocks_newest_first(&self, offset: usize, limit: usize) -> Page<Block> {
+        paginate(&self.chain, offset, limit)
+    }
+
+    fn find_block(&self, hash: &[u8]) -> Option<&Block> {
+        self.chain.iter().find(|block| block.current_hash == hash)
+    }
+
+    // Transaction incluse et appliquée, avec son bloc
+    fn find_transaction(&self, hash: &[u8]) -> Option<(&Block, &Transaction)> {
+        let receipt = self.receipts.get(hash)?;
+        let block = self.block_at(receipt.block_index)?;
+        let transaction = block.transactions.get(receipt.position as usize)?;
+        // Une transaction d'un lot a la position du lot
+        match transaction.inner_transactions().iter().find(|inner| inner.hash() == hash) {
+            Some(inner) => Some((block, inner)),
+            None => Some((block, transaction)),
+        }
+    }
+
+    // Ancrages d'un document encore présents dans les blocs conservés, du plus ancien au plus récent
+    fn document_anchors(&self, document: &[u8]) -> Vec<(&Block, &Transaction)> {
+        self.indexer.anchors(document).iter().filter_map(|hash| self.find_transaction(hash)).collect()
+    }
+
+    // Hashes des ancrages émis par `address` ou la concernant, les plus récents d'abord
+    fn documents_by_address(&self, address: &PublicKey, offset: usize, limit: usize) -> Page<Vec<u8>> {
+        self.indexer.anchors_by_address(address, offset, limit)
+    }
+
+    // Index des blocs produits par `validator`, les plus récents d'abord
+    fn blocks_by_validator(&self, validator: &PublicKey, offset: usize, limit: usize) -> Page<u64> {
+        self.indexer.blocks_produced(validator, offset, limit)
+    }
+
+    // Sort d'une transaction incluse : statut, bloc et rang, gaz et événements émis
+    fn get_receipt(&self, transaction_hash: &[u8]) -> Option<&Receipt> {
+        self.receipts.get(transaction_hash)
+    }
+
+    // Transaction privée approuvée par tous les membres du groupe ; le contenu
+    // chiffré reste hors chaîne
+    fn add_private_transaction(&mut self, transaction: PrivateTransaction) -> Result<(), &'static str> {
+        transaction.verify()?;
+        let hash = transaction.hash();
+        if self.pending_private_transactions.iter().any(|pending| pending.hash() == hash) {
+            return Err("Private transaction already pending");
+        }
+        self.events.publish(ChainEvent::PrivateTransactionAccepted(transaction.clone()));
+        self.pending_private_transactions.push(transaction);
+        Ok(())
+    }
+
+    fn add_governance_transaction(&mut self, transaction: GovernanceTransaction) -> Result<(), &'static str> {
+        self.governance.validate(&self.validators, std::slice::from_ref(&transaction))?;
+        self.compliance.validate(std::slice::from_ref(&transaction))?;
+        self.beacon.validate(&self.validators, std::slice::from_ref(&transaction), self.next_index())?;
+        self.incidents.validate(&self.validators, std::slice::from_ref(&transaction))?;
+        self.key_history.validate(&self.validators, &self.beacon, std::slice::from_ref(&transaction))?;
+        self.rewards.validate(&self.validators, std::slice::from_ref(&transaction), self.next_index())?;
+        self.events.publish(ChainEvent::GovernanceTransactionAccepted(transaction.clone()));
+        self.pending_governance_transactions.push(transaction);
+        Ok(())
+    }
+
+    // Valeur aléatoire produite à la fin de l'époque `epoch`
+    fn beacon(&self, epoch: u64) -> Option<&Vec<u8>> {
+        self.beacon.value(epoch)
+    }
+
+    fn latest_beacon(&self) -> Option<(u64, &Vec<u8>)> {
+        self.beacon.latest()
+    }
+
+    fn private_state(&self, group_id: &[u8]) -> Option<&Vec<u8>> {
+        self.private_states.get(group_id)
+    }
+
+    // États privés modifiés par une suite de transactions privées ; chacune doit
+    // partir de l'état courant de son groupe
+    fn next_private_states(&self, transactions: &[PrivateTransaction]) -> Result<HashMap<Vec<u8>, Vec<u8>>, &'static str> {
+        let mut updated: HashMap<Vec<u8>, Vec<u8>> = HashMap::new();
+        for transaction in transactions {
+            let group_id = transaction.group.id();
+            let current = updated.get(&group_id).or_else(|| self.private_states.get(&group_id));
+            if current.map_or(&[][..], |state| state.as_slice()) != transaction.previous_state.as_slice() {
+                return Err("Private transaction does not extend the group state");
+            }
+            updated.insert(group_id, transaction.new_state.clone());
+        }
+        Ok(updated)
+    }
+
+    // Bloc du créneau `slot` pour `validator_pubkey`, sans sa signature : ses
+    // transactions sont retirées de l'attente jusqu'à `commit_block` ou `abandon_block`
+    fn build_block(&mut self, validator_pubkey: PublicKey, slot: u64) -> Result<BlockTemplate, &'static str> {
+
+        if let Some(validator) = self.validators.get(&validator_pubkey) {
+            // Le score minimal est vérifié par la sélection du leader, qui le lève
+            // lorsqu'aucun validateur ne l'atteint
+            if validator.stake < self.governance.params.min_producer_stake {
+                return Err("Validator not qualified");
+            }
+            if validator.inactive_since.is_some() {
+                return Err("Validator inactive");
+            }
+            if validator.standby {
+                return Err("Validator on standby");
+            }
+        } else {
+            return Err("Validator not registered");
+        }
+
+        if self.last_header().is_some_and(|parent| slot <= parent.slot) {
+            return Err("Slot already used");
+        }
+        self.upgrades.check(PROTOCOL_VERSION)?;
+        if self.select_leader(slot) != Some(validator_pubkey) {
+            return Err("Validator not selected for this slot");
+        }
+
+        let previous_hash = self.last_hash();
+
+        let index = self.next_index();
+        // Créneau sauté lorsqu'aucun horodatage ne respecte toutes les bornes (heure
+        // locale en retard sur la médiane des blocs récents, par exemple)
+        let timestamp = self.block_timestamp();
+        self.check_block_timestamp(timestamp)?;
+        self.drop_expired_transactions(index, timestamp);
+        // Transactions privées en attente qui prolongent l'état de leur groupe, dans l'ordre d'arrivée
+        let mut private_transactions: Vec<PrivateTransaction> = Vec::new();
+        for transaction in &self.pending_private_transactions {
+            let mut candidate = private_transactions.clone();
+            candidate.push(transaction.clone());
+            if self.next_private_states(&candidate).is_ok() {
+                private_transactions = candidate;
+            }
+        }
+
+        let mut governance_transactions: Vec<GovernanceTransaction> = Vec::new();
+        for transaction in &self.pending_governance_transactions {
+            let mut candidate = governance_transactions.clone();
+            candidate.push(transaction.clone());
+            if self.governance.validate(&self.validators, &candidate).is_ok()
+                && self.compliance.validate(&candidate).is_ok()
+                && self.beacon.validate(&self.validators, &candidate, index).is_ok()
+                && self.incidents.validate(&self.validators, &candidate).is_ok()
+                && self.key_history.validate(&self.validators, &self.beacon, &candidate).is_ok()
+                && self.rewards.validate(&self.validators, &candidate, index).is_ok()
+            {
+                governance_transactions = candidate;
+            }
+        }
+
+        // Transactions en attente dans l'ordre d'arrivée, dans la limite de gaz du
+        // bloc ; celles qui ne respectent plus les règles de séquestre (déjà réglé,
+        // encore bloqué), dont le solde (disponible ou acquis) ne couvre plus le
+        // montant et les frais ou dont l'appel de contrat échoue restent en attente
+        let mut accounts = self.accounts.clone();
+        let mut block_gas: u64 = 0;
+        let mut transactions: Vec<Transaction> = Vec::new();
+        let mut deferred: Vec<Transaction> = Vec::new();
+        for transaction in std::mem::take(&mut self.pending_transactions) {
+            if transactions.len() >= self.governance.params.max_block_transactions as usize || self.check_spendable(&transaction).is_err() {
+                deferred.push(transaction);
+                continue;
+            }
+            match accounts.prepare(&transaction, index) {
+                Ok(outcome) if block_gas + outcome.block_gas() <= self.governance.params.max_block_gas => {
+                    block_gas += outcome.block_gas();
+                    accounts.commit(&transaction, &outcome, index, &rewards::pool_address());
+                    transactions.push(transaction);
+                }
+                _ => deferred.push(transaction),
+            }
+        }
+        self.pending_transactions = deferred;
+        let transactions_root = Block::transactions_root(&transactions, &private_transactions, &governance_transactions);
+        let state_root = self.state_tree.root().to_vec();
+        let snapshot_hash = if self.is_snapshot_height(index) {
+            let snapshot = self.snapshot();
+            let hash = snapshot.hash();
+            self.latest_snapshot = Some(snapshot);
+            hash
+        } else {
+            Vec::new()
+        };
+
+        let checkpoint = self.checkpoint_ready();
+
+        let rules_fingerprint = self.rules.fingerprint().to_vec();
+        let (chain_id, difficulty) = (self.config.chain_id, self.current_difficulty);
+        let current_hash = BlockHeader::compute_hash(chain_id, index, slot, timestamp, difficulty, PROTOCOL_VERSION, &rules_fingerprint, &previous_hash, &transactions_root, &state_root, &snapshot_hash, checkpoint.as_ref(), &validator_pubkey);
+        Ok(BlockTemplate {
+            chain_id,
+            index,
+            slot,
+            timestamp,
+            difficulty,
+            rules_fingerprint,
+            transactions,
+            private_transactions,
+            governance_transactions,
+            previous_hash,
+            transactions_root,
+            state_root,
+            snapshot_hash,
+            checkpoint,
+            current_hash,
+            validator_pubkey,
+        })
+    }
+
+    // Applique un bloc construit par `build_block` une fois signé ; il est abandonné
+    // si un autre bloc a prolongé la chaîne pendant la signature
+    fn commit_block(&mut self, template: BlockTemplate, validator_signature: Signature) -> Result<Block, &'static str> {
+        if template.previous_hash != self.last_hash() {
+            self.abandon_block(template);
+            return Err("Chain advanced while signing");
+        }
+        let outcomes = match self.accounts.apply_block(&template.transactions, template.index, &rewards::pool_address()) {
+            Ok(outcomes) => outcomes,
+            Err(e) => {
+                self.abandon_block(template);
+                return Err(e);
+            }
+        };
+        let block = template.sign(validator_signature);
+        self.apply_liveness(&block);
+        if let Some(checkpoint) = &block.checkpoint {
+            self.finalize_checkpoint(checkpoint);
+        }
+        self.apply_transactions(block.index, block.timestamp, &block.validator_pubkey, &block.transactions, outcomes);
+        self.distribute_rewards(block.index);
+        self.apply_private_transactions(&block.private_transactions);
+        self.apply_governance_transactions(block.index, &block.governance_transactions);
+        self.apply_version_signal(block.index, &block.validator_pubkey, block.protocol_version);
+        self.renew_active_set(block.index);
+        self.record_changes(block.index);
+        self.chain.push(block.clone());
+        self.retarget_difficulty();
+        self.indexer.index_block(&block);
+        self.events.publish(ChainEvent::BlockCommitted(block.clone()));
+        self.release_scheduled_transactions(self.next_index());
+
+        Ok(block)
+    }
+
+    fn verify_transaction_signatures(&self, transactions: &[Transaction]) -> Result<(), &'static str> {
+        let mut signed: Vec<(&Transaction, Signature)> = Vec::new();
+        let mut multisig: Vec<&Transaction> = Vec::new();
+        for tx in transactions {
+            match tx.authorization {
+                Authorization::Signature(signature) => signed.push((tx, signature)),
+                Authorization::Multisig { .. } => multisig.push(tx),
+            }
+        }
+        multisig.par_iter().try_for_each(|tx| tx.verify())?;
+        // Signatures simples vérifiées par lots ci-dessous, le reste de la transaction ici
+        signed.par_iter().try_for_each(|(tx, _)| tx.verify_content())?;
+
+        // Vérification par lots, chaque lot étant traité sur un thread différent
+        signed.par_chunks(SIGNATURE_BATCH_SIZE).try_for_each(|chunk| {
+            let hashes: Vec<Vec<u8>> = chunk.iter()
+                .map(|(tx, _)| tx.hash())
+                .collect();
+            let messages: Vec<&[u8]> = hashes.iter().map(|hash| hash.as_slice()).collect();
+            let signatures: Vec<Signature> = chunk.iter().map(|(_, signature)| *signature).collect();
+            let public_keys: Vec<PublicKey> = chunk.iter().map(|(tx, _)| tx.sender).collect();
+
+            verify_batch(&messages, &signatures, &public_keys).map_err(|_| "Invalid transaction signature")
+        })
+    }
+
+    fn validate_block(&self, block: &Block) -> Result<(), &'static str> {
+        let _entered = debug_span!("block.validate", index = block.index).entered();
+        if block.chain_id != self.config.chain_id {
+            return Err("Block for another chain");
+        }
+        if block.index != self.next_index() {
+            return Err("Invalid block index");
+        }
+
+        if block.previous_hash != self.last_hash() {
+            return Err("Invalid previous hash");
+        }
+
+        self.check_block_timestamp(block.timestamp)?;
+
+        if !self.validators.contains_key(&block.validator_pubkey) {
+            return Err("Validator not registered");
+        }
+
+        if self.last_header().is_some_and(|parent| block.slot <= parent.slot) {
+            return Err("Invalid block slot");
+        }
+        if block.difficulty != self.current_difficulty {
+            return Err("Invalid block difficulty");
+        }
+        if self.select_leader(block.slot) != Some(block.validator_pubkey) {
+            return Err("Validator not selected for this slot");
+        }
+
+        block.header().verify()?;
+        self.upgrades.check(block.protocol_version)?;
+
+        if block.transactions_root != Block::transactions_root(&block.transactions, &block.private_transactions, &block.governance_transactions) {
+            return Err("Invalid transactions root");
+        }
+        if block.transactions.len() as u64 > self.governance.params.max_block_transactions {
+            return Err("Too many transactions in block");
+        }
+
+        for transaction in &block.transactions {
+            if transaction.chain_id != block.chain_id {
+                return Err("Transaction signed for another chain");
+            }
+            self.check_transaction_version(transaction)?;
+            if transaction.is_expired(block.index, block.timestamp) {
+                return Err("Transaction expired");
+            }
+            transaction.check_lifetime(block.index, block.timestamp)?;
+            if transaction.executable_from() > block.index {
+                return Err("Transaction not yet executable");
+            }
+            self.check_spendable(transaction)?;
+        }
+        let outcomes = self.accounts.clone().apply_block(&block.transactions, block.index, &rewards::pool_address())?;
+        let block_gas = outcomes.iter().fold(0u64, |total, outcome| total.saturating_add(outcome.block_gas()));
+        if block_gas > self.governance.params.max_block_gas {
+            return Err("Block gas limit exceeded");
+        }
+        for transaction in &block.private_transactions {
+            transaction.verify()?;
+        }
+        self.next_private_states(&block.private_transactions)?;
+        self.governance.validate(&self.validators, &block.governance_transactions)?;
+        self.compliance.validate(&block.governance_transactions)?;
+        self.beacon.validate(&self.validators, &block.governance_transactions, block.index)?;
+        self.incidents.validate(&self.validators, &block.governance_transactions)?;
+        self.key_history.validate(&self.validators, &self.beacon, &block.governance_transactions)?;
+        self.rewards.validate(&self.validators, &block.governance_transactions, block.index)?;
+
+        if block.state_root != self.state_tree.root() {
+            return Err("Invalid state root");
+        }
+        let expected_snapshot_hash = if self.is_snapshot_height(block.index) {
+            self.snapshot().hash()
+        } else {
+            Vec::new()
+        };
+        if block.snapshot_hash != expected_snapshot_hash {
+            return Err("Invalid snapshot hash");
+        }
+
+        if let Some(checkpoint) = &block.checkpoint {
+            if !self.is_checkpoint_height(checkpoint.height) || self.finalized_height.is_some_and(|h| checkpoint.height <= h) {
+                return Err("Checkpoint already finalized");
+            }
+            let checkpoint_block = self.block_at(checkpoint.height).ok_or("Unknown checkpoint block")?;
+            if checkpoint_block.current_hash != checkpoint.block_hash {
+                return Err("Checkpoint for another block");
+            }
+            checkpoint.verify(&self.validators)?;
+        }
+
+        self.verify_transaction_signatures(&block.transactions)
+    }
+
+    // Ajout d'un bloc produit par un autre validateur
+    fn add_block(&mut self, block: Block) -> Result<(), &'static str> {
+        self.validate_block(&block)?;
+
+        // Les comptes sont mis à jour atomiquement : un échec rejette le bloc
+        // avant toute autre modification de l'état
+        let snapshot = self.is_snapshot_height(block.index).then(|| self.snapshot());
+        let outcomes = self.accounts.apply_block(&block.transactions, block.index, &rewards::pool_address())?;
+        if snapshot.is_some() {
+            self.latest_snapshot = snapshot;
+        }
+        self.apply_liveness(&block);
+        if let Some(checkpoint) = &block.checkpoint {
+            self.finalize_checkpoint(checkpoint);
+        }
+
+        let included: HashSet<Vec<u8>> = block.transactions.iter()
+            .map(|tx| tx.hash())
+            .collect();
+        let pending = std::mem::take(&mut self.pending_transactions);
+        self.pending_transactions = pending.into_iter()
+            .filter(|tx| !included.contains(&tx.hash()))
+            .collect();
+        self.apply_transactions(block.index, block.timestamp, &block.validator_pubkey, &block.transactions, outcomes);
+        self.distribute_rewards(block.index);
+        self.apply_private_transactions(&block.private_transactions);
+        self.apply_governance_transactions(block.index, &block.governance_transactions);
+        self.apply_version_signal(block.index, &block.validator_pubkey, block.protocol_version);
+        self.renew_active_set(block.index);
+        self.check_rules_fingerprint(&block);
+        self.record_changes(block.index);
+        self.drop_expired_transactions(block.index + 1, block.timestamp);
+
+        self.indexer.index_block(&block);
+        self.events.publish(ChainEvent::BlockCommitted(block.clone()));
+        self.chain.push(block);
+        self.retarget_difficulty();
+        self.release_scheduled_transactions(self.next_index());
+        Ok(())
+    }
+
+    // Bloc reçu d'un primaire de confiance (réplica en lecture) : seuls le
+    // réseau, le chaînage, le hash, la signature, la racine des transactions et
+    // leur exécution sont vérifiés
+    fn apply_replicated_block(&mut self, block: Block) -> Result<(), &'static str> {
+        if block.chain_id != self.config.chain_id {
+            return Err("Block for another chain");
+        }
+        if block.index != self.next_index() {
+            return Err("Invalid block index");
+        }
+        if block.previous_hash != self.last_hash() {
+            return Err("Invalid previous hash");
+        }
+        block.header().verify()?;
+        if block.transactions_root != Block::transactions_root(&block.transactions, &block.private_transactions, &block.governance_transactions) {
+            return Err("Invalid transactions root");
+        }
+        let outcomes = self.accounts.apply_block(&block.transactions, block.index, &rewards::pool_address())?;
+
+        self.apply_liveness(&block);
+        if let Some(checkpoint) = &block.checkpoint {
+            self.finalize_checkpoint(checkpoint);
+        }
+        self.apply_transactions(block.index, block.timestamp, &block.validator_pubkey, &block.transactions, outcomes);
+        self.distribute_rewards(block.index);
+        self.apply_private_transactions(&block.private_transactions);
+        self.apply_governance_transactions(block.index, &block.governance_transactions);
+        self.apply_version_signal(block.index, &block.validator_pubkey, block.protocol_version);
+        self.renew_active_set(block.index);
+        self.check_rules_fingerprint(&block);
+        self.record_changes(block.index);
+        self.indexer.index_block(&block);
+        self.events.publish(ChainEvent::BlockCommitted(block.clone()));
+        self.chain.push(block);
+        self.retarget_difficulty();
+        Ok(())
+    }
+
+    // Rattrapage différentiel auprès du primaire apparié : les blocs sont
+    // vérifiés (réseau, chaînage, signature, racine des transactions) mais pas
+    // réexécutés ; l'état reconstitué doit correspondre au hash annoncé
+    fn apply_state_diff(&mut self, diff: StateDiff) -> Result<(), &'static str> {
+        let mut previous_hash = self.last_hash();
+        let mut index = self.next_index();
+        for block in diff.blocks() {
+            if block.chain_id != self.config.chain_id {
+                return Err("Block for another chain");
+            }
+            if block.index != index || block.previous_hash != previous_hash {
+                return Err("State diff does not extend the local chain");
+            }
+            block.header().verify()?;
+            if block.transactions_root != Block::transactions_root(&block.transactions, &block.private_transactions, &block.governance_transactions) {
+                return Err("Invalid transactions root");
+            }
+            previous_hash = block.current_hash.clone();
+            index += 1;
+        }
+        let state = diff.state(&self.accounts.ledger)?;
+        if state.height + 1 != index || state.block_hash != previous_hash {
+            return Err("State diff does not match its blocks");
+        }
+
+        self.load_state(&state);
+        let (blocks, receipts) = diff.into_parts();
+        for block in blocks {
+            if let Some(checkpoint) = &block.checkpoint {
+                self.finalize_checkpoint(checkpoint);
+            }
+            self.check_rules_fingerprint(&block);
+            self.indexer.index_block(&block);
+            self.events.publish(ChainEvent::BlockCommitted(block.clone()));
+            self.chain.push(block);
+        }
+        for receipt in receipts {
+            self.receipts.insert(receipt.transaction_hash.clone(), receipt);
+        }
+        // Le journal et l'archive ne couvrent pas les blocs rattrapés
+        self.state_changes.clear();
+        self.restart_archive();
+        self.drop_all_pending("Mempool cleared by a state diff catch-up");
+        self.pending_private_transactions.clear();
+        self.pending_governance_transactions.clear();
+        Ok(())
+    }
+
+    // Comptes modifiés par le bloc `height`, pour l'arbre des soldes, la
+    // synchronisation différentielle et l'archive des soldes
+    fn record_changes(&mut self, height: u64) {
+        let changes = self.accounts.ledger.take_changes();
+        self.state_tree.update(&self.accounts.ledger, &changes);
+        if let Some(archive) = &mut self.archive {
+            archive.record(height, &self.accounts.ledger, &changes);
+        }
+        self.state_changes.record(height, changes);
+    }
+
+    // Mode archive : conserve le solde de chaque compte après chaque bloc à
+    // partir de l'état actuel
+    fn enable_archive(&mut self) {
+        self.archive = Some(BalanceArchive::new(&self.accounts.ledger, self.next_index().saturating_sub(1)));
+    }
+
+    // L'état a été remplacé sans appliquer les blocs : l'archive repart de lui
+    fn restart_archive(&mut self) {
+        if self.archive.is_some() {
+            self.enable_archive();
+        }
+    }
+
+    // Empreinte annoncée par chaque producteur dans ses en-têtes : une
+    // divergence est signalée sans rejeter le bloc
+    fn check_rules_fingerprint(&mut self, block: &Block) {
+        if self.rules.record(block.validator_pubkey, &block.rules_fingerprint, block.index) {
+            warn!(
+                validator = %hex::encode(block.validator_pubkey.as_bytes()),
+                fingerprint = %hex::encode(&block.rules_fingerprint),
+                height = block.index,
+                "Producer announces different consensus rules",
+            );
+            self.events.publish(ChainEvent::RulesFingerprintMismatch {
+                validator: block.validator_pubkey,
+                fingerprint: block.rules_fingerprint.clone(),
+                height: block.index,
+            });
+        }
+    }
+
+    // Empreinte annoncée dans les blocs produits localement
+    fn set_rules_fingerprint(&mut self, fingerprint: Vec<u8>) {
+        self.rules = RulesMonitor::new(fingerprint);
+    }
+
+    // Enregistre les reçus des transactions d'un bloc dont les comptes ont déjà été
+    // mis à jour par `AccountState::apply_block` : frais versés à la réserve des
+    // récompenses au nom du producteur, acquisitions débloquées, récupérations
+    // arrivées à échéance. Les nonces que les blocs suivants ne peuvent plus
+    // inclure sont oubliés et les transactions devenues inexécutables (compte
+    // récupéré, séquestre déjà réglé) sont retirées du mempool.
+    fn apply_transactions(&mut self, index: u64, timestamp: u64, producer: &PublicKey, transactions: &[Transaction], outcomes: Vec<Outcome>) {
+        let fees = outcomes.iter().fold(0u64, |total, outcome| total.saturating_add(outcome.fees()));
+        if fees > 0 {
+            self.rewards.accrue(producer, fees);
+            self.system_accounts.insert(rewards::pool_address());
+        }
+        for (position, (transaction, outcome)) in transactions.iter().zip(outcomes).enumerate() {
+            self.record_outcome(index, position as u32, transaction, outcome);
+        }
+        for account in self.accounts.vesting.unlocks(index) {
+            if let Some(balance) = self.accounts.vesting.balance(&account, self.balance(&account), index) {
+                self.events.publish(ChainEvent::VestingUnlocked { account, balance });
+            }
+        }
+        for (account, new_key) in self.accounts.complete_recoveries(index) {
+            self.rotate_validator(index, &account, new_key);
+            self.events.publish(ChainEvent::AccountRecovered { account, new_key });
+        }
+        self.accounts.nonces.expire(index + 1, timestamp);
+        self.prune_pending_transactions();
+    }
+
+    // Bloc non signé ou devancé : ses transactions retournent en tête des
+    // transactions en attente, sauf celles qu'un autre bloc a rendues inexécutables
+    fn abandon_block(&mut self, template: BlockTemplate) {
+        self.pending_transactions.splice(0..0, template.transactions);
+        self.prune_pending_transactions();
+    }
+
+    fn prune_pending_transactions(&mut self) {
+        for transaction in std::mem::take(&mut self.pending_transactions) {
+            match self.unexecutable_reason(&transaction) {
+                Some(reason) => self.drop_transaction(transaction, TransactionStatus::Dropped { reason }),
+                None => self.pending_transactions.push(transaction),
+            }
+        }
+    }
+
+    // Motif pour lequel une transaction en attente ne pourra plus être incluse
+    fn unexecutable_reason(&self, transaction: &Transaction) -> Option<&'static str> {
+        std::iter::once(transaction).chain(transaction.inner_transactions()).find_map(|tx| {
+            if self.accounts.recoveries.is_recovered(&tx.sender) {
+                return Some("Sender account recovered");
+            }
+            if self.accounts.nonces.is_used(&tx.sender, tx.nonce) {
+                return Some("Nonce already used");
+            }
+            match &tx.kind {
+                TransactionKind::Claim { escrow } | TransactionKind::Refund { escrow } if !self.accounts.escrows.is_open(escrow) => Some("Escrow already settled"),
+                TransactionKind::Attest { .. } if self.accounts.assets.check(tx).is_err() => Some("Asset access changed"),
+                _ => None,
+            }
+        })
+    }
+
+    // Effets d'une transaction appliquée puis, dans l'ordre, de celles de son lot,
+    // qui partagent sa position dans le bloc
+    fn record_outcome(&mut self, index: u64, position: u32, transaction: &Transaction, mut outcome: Outcome) {
+        let bundle = std::mem::take(&mut outcome.bundle);
+        if let Some(id) = &outcome.escrow {
+            self.system_accounts.insert(escrow_address(&transaction.sender, id));
+        }
+        match transaction.kind {
+            TransactionKind::Deploy { .. } => {
+                self.system_accounts.insert(transaction.recipient);
+            }
+            TransactionKind::Shield => {
+                self.system_accounts.insert(confidential::pool_address());
+            }
+            _ => {}
+        }
+        let mut status = ReceiptStatus::Success;
+        let mut events = Vec::new();
+        match outcome.execution {
+            Some(Execution { failure: Some(reason), .. }) => status = ReceiptStatus::Failed(reason),
+            Some(execution) => events.push(ChainEvent::ContractExecuted {
+                contract: transaction.recipient,
+                caller: transaction.sender,
+                output: execution.output,
+                gas_used: execution.gas_used,
+            }),
+            None => {}
+        }
+        if let (TransactionKind::Recover { account }, Some(recovery)) = (&transaction.kind, outcome.recovery) {
+            events.push(ChainEvent::RecoveryInitiated { account: *account, new_key: recovery.new_key, execute_at: recovery.execute_at });
+        }
+        for event in &events {
+            self.events.publish(event.clone());
+        }
+        let hash = transaction.hash();
+        self.receipts.insert(hash.clone(), Receipt {
+            transaction_hash: hash,
+            block_index: index,
+            position,
+            status,
+            gas_used: outcome.gas_used,
+            gas_price: transaction.gas_price,
+            fee: outcome.fee,
+            events,
+        });
+        for (inner, outcome) in transaction.inner_transactions().iter().zip(bundle) {
+            self.record_outcome(index, position, inner, outcome);
+        }
+    }
+
+    // Valeur stockée par un contrat déployé
+    fn contract_storage(&self, contract: &PublicKey, key: &[u8]) -> Option<&Vec<u8>> {
+        self.accounts.contracts.storage(contract, key)
+    }
+
+    // Met à jour l'état des groupes et retire du mempool les transactions privées
+    // partant d'un état consommé (incluses ou concurrentes)
+    fn apply_private_transactions(&mut self, transactions: &[PrivateTransaction]) {
+        let mut consumed = HashSet::new();
+        for transaction in transactions {
+            let group_id = transaction.group.id();
+            consumed.insert((group_id.clone(), transaction.previous_state.clone()));
+            self.private_states.insert(group_id, transaction.new_state.clone());
+        }
+        let pending = std::mem::take(&mut self.pending_private_transactions);
+        self.pending_private_transactions = pending.into_iter()
+            .filter(|tx| !consumed.contains(&(tx.group.id(), tx.previous_state.clone())))
+            .collect();
+    }
+
+    // Enregistre propositions, votes, ordres de gel, contributions à la balise,
+    // chronologies d'incident et changements de commission,
+    // applique les changements de paramètres en fin d'époque, produit la valeur de
+    // la balise (en pénalisant les révélations retenues), puis retire du mempool
+    // ce qui n'est plus valide
+    fn apply_governance_transactions(&mut self, index: u64, transactions: &[GovernanceTransaction]) {
+        if let Some(params) = self.governance.apply(&self.validators, transactions, index) {
+            self.events.publish(ChainEvent::ParametersChanged(params));
+        }
+        for order in self.compliance.apply(transactions, index) {
+            self.events.publish(ChainEvent::FreezeOrderApplied(order));
+        }
+        if let Some(output) = self.beacon.apply(transactions, index) {
+            for validator in &output.withheld {
+                self.slash(validator, WITHHOLDING_PENALTY_PERCENT);
+            }
+            self.events.publish(ChainEvent::BeaconFinalized { epoch: output.epoch, value: output.value });
+        }
+        self.incidents.apply(transactions, index);
+        self.rewards.apply(transactions);
+        for transaction in transactions {
+            match transaction {
+                GovernanceTransaction::Reactivation(reactivation) => self.reactivate_validator(&reactivation.validator),
+                GovernanceTransaction::RotateKey(rotation) => self.rotate_validator(index, &rotation.validator, rotation.new_key),
+                _ => {}
+            }
+        }
+        let pending = std::mem::take(&mut self.pending_governance_transactions);
+        self.pending_governance_transactions = pending.into_iter()
+            .filter(|tx| self.governance.validate(&self.validators, std::slice::from_ref(tx)).is_ok())
+            .filter(|tx| self.compliance.validate(std::slice::from_ref(tx)).is_ok())
+            .filter(|tx| self.beacon.validate(&self.validators, std::slice::from_ref(tx), index + 1).is_ok())
+            .filter(|tx| self.incidents.validate(&self.validators, std::slice::from_ref(tx)).is_ok())
+            .filter(|tx| self.key_history.validate(&self.validators, &self.beacon, std::slice::from_ref(tx)).is_ok())
+            .filter(|tx| self.rewards.validate(&self.validators, std::slice::from_ref(tx), index + 1).is_ok())
+            .collect();
+    }
+
+    // Formats de la transaction et de celles de son lot, en vigueur à la hauteur courante
+    fn check_transaction_version(&self, transaction: &Transaction) -> Result<(), &'static str> {
+        self.upgrades.check_transaction(transaction.version)?;
+        transaction.inner_transactions().iter().try_for_each(|inner| self.upgrades.check_transaction(inner.version))
+    }
+
+    // Enregistre la version signalée par le producteur ; en fin d'époque, active
+    // la version candidate si elle a été signalée assez longtemps
+    fn apply_version_signal(&mut self, index: u64, producer: &PublicKey, protocol_version: u32) {
+        self.upgrades.record(*producer, protocol_version);
+        if index % EPOCH_LENGTH != 0 {
+            return;
+        }
+        if let Some(version) = self.upgrades.end_epoch(&self.validators, &self.governance.params) {
+            self.events.publish(ChainEvent::ProtocolUpgraded { version, height: index });
+        }
+    }
+
+    // En fin d'époque, les frais accumulés dans la réserve des récompenses sont
+    // répartis entre la réserve communautaire et les validateurs (voir `rewards.rs`)
+    fn distribute_rewards(&mut self, index: u64) {
+        if index % EPOCH_LENGTH != 0 {
+            return;
+        }
+        let credits = self.rewards.distribute(index, self.config.community_pool_percent);
+        let total: u64 = credits.iter().map(|credit| credit.amount).sum();
+        let mut movements: Vec<(PublicKey, i128)> = vec![(rewards::pool_address(), -(total as i128))];
+        movements.extend(credits.iter().map(|credit| (credit.recipient(), credit.amount as i128)));
+        if let Err(e) = self.accounts.ledger.check(&movements) {
+            error!(error = e, height = index, "Rewards not distributed");
+            return;
+        }
+        self.accounts.ledger.apply(&movements);
+        for credit in credits {
+            if credit.kind == RewardKind::CommunityPool {
+                self.system_accounts.insert(credit.recipient());
+            }
+            self.reward_history.record(credit);
+        }
+    }
+
+    // Retire `percent` % de la mise d'un validateur et pénalise son score
+    fn slash(&mut self, pubkey: &PublicKey, percent: u64) {
+        if let Some(validator) = self.validators.get_mut(pubkey) {
+            validator.stake -= (validator.stake as u128 * percent as u128 / 100) as u64;
+            validator.contribution_score = (validator.contribution_score - scoring::SLASHING_PENALTY).max(0.0);
+            self.events.publish(ChainEvent::ValidatorUpdated(validator.clone()));
+        }
+    }
+
+    // Validateur dont la clé change (rotation ou récupération du compte) après le
+    // bloc `index` : sa mise, ses votes et son signal de version passent à la
+    // nouvelle clé ; ses votes de checkpoint en attente, signés par l'ancienne, sont abandonnés
+    fn rotate_validator(&mut self, index: u64, account: &PublicKey, new_key: PublicKey) {
+        let Some(validator) = self.validators.remove(account) else {
+            return;
+        };
+        self.key_history.record(index, *account, new_key);
+        self.governance.rekey(account, new_key);
+        self.upgrades.rekey(account, new_key);
+        self.rewards.rekey(account, new_key);
+        for votes in self.checkpoint_votes.values_mut() {
+            votes.retain(|vote| vote.validator != *account);
+        }
+        let validator = self.validators.entry(new_key)
+            .and_modify(|existing| existing.stake = existing.stake.saturating_add(validator.stake))
+            .or_insert(Validator { public_key: new_key, ..validator })
+            .clone();
+        self.events.publish(ChainEvent::ValidatorUpdated(validator));
+    }
+
+    fn apply_validator_update(&mut self, validator: Validator) {
+        self.validators.insert(validator.public_key, validator.clone());
+        self.events.publish(ChainEvent::ValidatorUpdated(validator));
+    }
+
+    fn register_validator(&mut self, validator: &PublicKey, initial_stake: u64) -> Result<(), &'static str> {
+        let pubkey = *validator;
+
+        if self.validators.contains_key(&pubkey) {
+            return Err("Validator already registered");
+        }
+
+        if initial_stake < self.governance.params.min_registration_stake {
+            return Err("Insufficient stake to become a validator");
+        }
+
+        let validator = Validator {
+            public_key: pubkey,
+            stake: initial_stake,
+            contribution_score: scoring::INITIAL_CONTRIBUTION_SCORE,
+            last_validated_block: None,
+            missed_slots: 0,
+            inactive_since: None,
+            standby: self.active_validators() >= self.governance.params.max_active_validators,
+        };
+        self.validators.insert(pubkey, validator.clone());
+        self.events.publish(ChainEvent::ValidatorRegistered(validator));
+
+        Ok(())
+    }
+
+    // Scores de contribution et activité des validateurs mis à jour à partir du
+    // bloc, avant son application : créneaux manqués depuis le parent, production,
+    // participation au checkpoint et, en fin d'époque, décroissance des inactifs
+    fn apply_liveness(&mut self, block: &Block) {
+        let parent_slot = self.last_header().map(|parent| parent.slot);
+        let missed = scoring::missed_leaders(
+            &self.validators,
+            parent_slot,
+            block,
+            self.current_difficulty,
+            self.governance.params.min_producer_stake,
+            self.config.min_contribution_score,
+        );
+        let changes = scoring::score_changes(&self.validators, &missed, block);
+
+        let mut updated: HashSet<PublicKey> = HashSet::new();
+        for (key, delta) in changes {
+            let Ok(pubkey) = PublicKey::from_bytes(&key) else {
+                continue;
+            };
+            if let Some(validator) = self.validators.get_mut(&pubkey) {
+                validator.contribution_score = (validator.contribution_score + delta).clamp(0.0, MAX_CONTRIBUTION_SCORE);
+                updated.insert(pubkey);
+            }
+        }
+
+        for leader in &missed {
+            if let Some(validator) = self.validators.get_mut(leader) {
+                validator.missed_slots += 1;
+            }
+        }
+        if let Some(producer) = self.validators.get_mut(&block.validator_pubkey) {
+            producer.missed_slots = 0;
+            producer.last_validated_block = Some(block.index);
+        }
+        for leader in &missed {
+            if let Some(validator) = self.validators.get_mut(leader) {
+                if validator.missed_slots >= MAX_MISSED_SLOTS && validator.inactive_since.is_none() {
+                    validator.inactive_since = Some(block.index);
+                    warn!(validator = %hex::encode(leader.as_bytes()), height = block.index, "Validator deactivated for missed slots");
+                }
+            }
+        }
+
+        if block.index % EPOCH_LENGTH == 0 {
+            for validator in self.validators.values_mut().filter(|v| v.inactive_since.is_some()) {
+                validator.contribution_score *= INACTIVE_SCORE_DECAY;
+                updated.insert(validator.public_key);
+            }
+        }
+        for pubkey in updated {
+            self.events.publish(ChainEvent::ValidatorUpdated(self.validators[&pubkey].clone()));
+        }
+    }
+
+    fn active_validators(&self) -> u64 {
+        self.validators.values().filter(|v| !v.standby).count() as u64
+    }
+
+    // En fin d'époque, les validateurs actifs désactivés pour créneaux manqués ou
+    // dont la mise est passée sous `min_producer_stake` rejoignent la réserve,
+    // ainsi que les moins bien classés si l'ensemble actif dépasse sa taille ; les
+    // validateurs éligibles de la réserve les mieux classés comblent les places
+    // libres. Classement : mise, puis score de contribution, puis clé publique.
+    fn renew_active_set(&mut self, index: u64) {
+        if index % EPOCH_LENGTH != 0 {
+            return;
+        }
+        let params = &self.governance.params;
+        let eligible = |v: &Validator| v.inactive_since.is_none() && v.stake >= params.min_producer_stake;
+        let mut ranked: Vec<&Validator> = self.validators.values().collect();
+        ranked.sort_by(|a, b| {
+            b.stake.cmp(&a.stake)
+                .then(b.contribution_score.total_cmp(&a.contribution_score))
+                .then(a.public_key.to_bytes().cmp(&b.public_key.to_bytes()))
+        });
+        let (active, standby): (Vec<&Validator>, Vec<&Validator>) = ranked.into_iter().partition(|v| !v.standby);
+        let (kept, mut demoted): (Vec<&Validator>, Vec<&Validator>) = active.into_iter().partition(|v| eligible(v));
+        let seats = params.max_active_validators as usize;
+        demoted.extend(kept.iter().skip(seats));
+        let promoted: Vec<&Validator> = standby.into_iter()
+            .filter(|v| eligible(v))
+            .take(seats.saturating_sub(kept.len()))
+            .collect();
+        let changes: Vec<(PublicKey, bool)> = demoted.iter().map(|v| (v.public_key, true))
+            .chain(promoted.iter().map(|v| (v.public_key, false)))
+            .collect();
+
+        for (pubkey, standby) in changes {
+            let validator = self.validators.get_mut(&pubkey).unwrap();
+            validator.standby = standby;
+            if standby {
+                info!(validator = %hex::encode(pubkey.as_bytes()), height = index, "Validator moved to standby");
+            } else {
+                info!(validator = %hex::encode(pubkey.as_bytes()), height = index, "Validator promoted from standby");
+            }
+            self.events.publish(ChainEvent::ValidatorUpdated(validator.clone()));
+        }
+    }
+
+    // Validateur de nouveau éligible à la production après une désactivation
+    fn reactivate_validator(&mut self, pubkey: &PublicKey) {
+        if let Some(validator) = self.validators.get_mut(pubkey) {
+            validator.inactive_since = None;
+            validator.missed_slots = 0;
+            self.events.publish(ChainEvent::ValidatorUpdated(validator.clone()));
+        }
+    }
+}
+
+// Sélection déterministe du validateur pour un créneau parmi les actifs (hors
+// réserve et non désactivés) atteignant `min_stake` et `min_score` : tous les
+// nœuds partageant la même chaîne et le même ensemble de validateurs obtiennent
+// le même résultat. Aucun leader pour les créneaux écartés par la difficulté.
+fn select_leader(validators: &HashMap<PublicKey, Validator>, previous_hash: &[u8], slot: u64, difficulty: u64, min_stake: u64, min_score: f64) -> Option<PublicKey> {
+    let eligible: Vec<&Validator> = validators.values()
+        .filter(|v| !v.standby && v.inactive_since.is_none() && v.stake >= min_stake)
+        .collect();
+    let mut candidates: Vec<(&Validator, f64)> = eligible.iter()
+        .filter(|v| v.contribution_score >= min_score)
+        .map(|v| (*v, (v.stake as f64) * v.contribution_score))
+        .collect();
+    // Aucun validateur actif n'atteint le score minimal (ou tous ont un score nul) :
+    // le seuil est levé et seule la mise compte, pour que la chaîne ne s'arrête pas
+    if candidates.iter().all(|(_, weight)| *weight <= 0.0) {
+        candidates = eligible.iter().map(|v| (*v, v.stake as f64)).collect();
+    }
+    candidates.sort_by_key(|(v, _)| v.public_key.to_bytes());
+
+    let total_weighted_stake: f64 = candidates.iter().map(|(_, weight)| weight).sum();
+    if total_weighted_stake <= 0.0 {
+        return None;
+    }
+
+    let mut encoder = Encoder::new(LEADER_DOMAIN);
+    encoder.put_bytes(previous_hash);
+    encoder.put_u64(slot);
+    let seed = encoder.hash();
+    if !difficulty::is_production_slot(&seed, difficulty) {
+        return None;
+    }
+    let random = u64::from_be_bytes(seed[..8].try_into().unwrap());
+    let random_point = (random as f64 / u64::MAX as f64) * total_weighted_stake;
+
+    let mut cumulative_weight = 0.0;
+    for (validator, weight) in &candidates {
+        cumulative_weight += weight;
+        if cumulative_weight >= random_point {
+            return Some(validator.public_key);
+        }
+    }
+    candidates.last().map(|(v, _)| v.public_key)
+}
+
+// `supplyx conformance generate [fichier]` ou `supplyx conformance verify <fichier>`
+// Fin du code de consensus : les points d'entrée ci-dessous démarrent les
+// services compilés dans le profil
+
+fn run_conformance(args: &[String]) -> Result<(), String> {
+    match args {
+        [command] if command == "generate" => {
+            println!("{}", serde_json::to_string_pretty(&conformance::generate()).unwrap());
+            Ok(())
+        }
+        [command, path] if command == "generate" => {
+            let vectors = serde_json::to_string_pretty(&conformance::generate()).unwrap();
+            std::fs::write(path, vectors + "\n").map_err(|e| e.to_string())
+        }
+        [command, path] if command == "verify" => {
+            let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+            let vectors = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+            let failures = conformance::verify(&vectors)?;
+            for failure in &failures {
+                println!("{}", failure);
+            }
+            if !failures.is_empty() {
+                return Err(format!("{} conformance failures", failures.len()));
+            }
+            println!("All conformance vectors passed");
+            Ok(())
+        }
+        _ => Err("Usage: supplyx conformance generate [path] | verify <path>".to_string()),
+    }
+}
+
+// `supplyx version [--features]`, pour la configuration `SUPPLYX_CONFIG`
+fn run_version(args: &[String]) -> Result<(), String> {
+    let features = match args {
+        [] => false,
+        [flag] if flag == "--features" => true,
+        _ => return Err("Usage: supplyx version [--features]".to_string()),
+    };
+    let config = match std::env::var("SUPPLYX_CONFIG") {
+        Ok(path) => ChainConfig::load(&path)?,
+        Err(_) => ChainConfig::default(),
+    };
+    print!("{}", features::report(&config, features));
+    Ok(())
+}
+
+// `supplyx verify-bundle <fichier> [hash de l'ensemble de validateurs]`, sans accès au réseau
+fn run_verify_bundle(args: &[String]) -> Result<(), String> {
+    let (path, trusted) = match args {
+        [path] => (path, None),
+        [path, trusted] => (path, Some(hex::decode(trusted).map_err(|e| e.to_string())?)),
+        _ => return Err("Usage: supplyx verify-bundle <path> [validator-set-hash]".to_string()),
+    };
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    let bundle = ProofBundle::decode(&data)?;
+    let validator_set = bundle.validator_set_hash();
+    if trusted.is_some_and(|trusted| trusted != validator_set) {
+        return Err("Bundle validator set differs from the trusted one".to_string());
+    }
+    bundle.verify()?;
+    match &bundle.fact {
+        Fact::TransactionIncluded { transaction_hash, height, .. } => {
+            println!("Transaction {} included at height {}", hex::encode(transaction_hash), height);
+        }
+        Fact::Balance { account, balance, .. } => {
+            let height = bundle.headers[0].index - 1;
+            println!("Account {} held {} at height {}", hex::encode(account.as_bytes()), balance, height);
+        }
+    }
+    println!("Finalized by checkpoint at height {} on chain {}", bundle.checkpoint.height, bundle.headers[0].chain_id);
+    println!("Validator set {}", hex::encode(&validator_set));
+    Ok(())
+}
+
+// Détenteur de la clé de validation pour un nœud configuré avec
+// `SUPPLYX_REMOTE_SIGNER` ; la clé (hex, 32 octets) est créée au premier lancement
+fn run_signer(args: &[String]) -> Result<(), String> {
+    let [addr, key_file] = args else {
+        return Err("Usage: supplyx signer <listen-addr> <key-file>".to_string());
+    };
+    let auth_key = std::env::var("SUPPLYX_REMOTE_SIGNER_KEY").map_err(|_| "SUPPLYX_REMOTE_SIGNER_KEY is required".to_string())?;
+    let keypair = load_or_create_key(key_file)?;
+    println!("{}", hex::encode(keypair.public.as_bytes()));
+    signer::serve(addr, &keypair, &auth_key)
+}
+
+// Clé secrète (hex) lue dans `key_file`, créée au premier lancement
+fn load_or_create_key(key_file: &str) -> Result<Keypair, String> {
+    let secret = match std::fs::read_to_string(key_file) {
+        Ok(text) => {
+            let bytes = hex::decode(text.trim()).map_err(|_| format!("{}: invalid key", key_file))?;
+            SecretKey::from_bytes(&bytes).map_err(|_| format!("{}: invalid key", key_file))?
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let secret = Keypair::generate(&mut OsRng).secret;
+            std::fs::write(key_file, hex::encode(secret.as_bytes())).map_err(|e| format!("{}: {}", key_file, e))?;
+            secret
+        }
+        Err(e) => return Err(format!("{}: {}", key_file, e)),
+    };
+    Ok(Keypair { public: PublicKey::from(&secret), secret })
+}
+
+// `supplyx tx <type> <fichier de clé> <destinataire> <opérandes>... --nonce <nonce> [options]` :
+// signature hors ligne (machine isolée) d'une transaction, affichée encodée
+// comme un corps de bloc à soumettre avec `SubmitBatch` ; réseau de `SUPPLYX_CONFIG`,
+// nonce obtenu au préalable avec `GetNextNonce`
+fn run_tx(args: &[String]) -> Result<(), String> {
+    let transaction = offline_transaction(args, SystemClock.unix_secs())?;
+    eprintln!("Transaction {}", hex::encode(transaction.hash()));
+    println!("{}", hex::encode(encode_block_body(&[transaction])));
+    Ok(())
+}
+
+// Opérandes selon le type : `transfer <montant>`, `anchor <document> [--cid <cid>]`,
+// `escrow <montant> <hauteur de déblocage> [--arbiter <clé>]`, `claim|refund <séquestre> <montant>`,
+// `set-recovery <période de contestation> <seuil> <gardien>...`, `veto-recovery`,
+// `register-asset [<lecteur>...]`, `grant|revoke <actif>` et
+// `attest <actif> <fichier> <participant>...`, le contenu étant chiffré pour les
+// participants donnés (propriétaire et lecteurs de l'actif). Horodatage `now` par défaut.
+fn offline_transaction(args: &[String], now: u64) -> Result<Transaction, String> {
+    const USAGE: &str = "Usage: supplyx tx <type> <key-file> <recipient> [<operand>...] --nonce <nonce> [--fee <gas-price>] [--timestamp <secs>] [--valid-from <height>] [--valid-until <height>] [--tx-version <version>], with types transfer <amount> | anchor <document-file> [--cid <cid>] | escrow <amount> <unlock-height> [--arbiter <key>] | claim <escrow> <amount> | refund <escrow> <amount> | set-recovery <challenge-period> <threshold> <guardian>... | veto-recovery | register-asset [<reader>...] | grant <asset> | revoke <asset> | attest <asset> <payload-file> <participant>... | shield <amount>, and with the confidential feature confidential-transfer <amount> <sync-journal-dir> | unshield <amount> <sync-journal-dir>";
+    let [kind, key_file, recipient, rest @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    let mut operands = Vec::new();
+    let mut options: HashMap<&str, &str> = HashMap::new();
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        let name = match arg.as_str() {
+            "--cid" if kind != "anchor" => return Err(USAGE.to_string()),
+            "--arbiter" if kind != "escrow" => return Err(USAGE.to_string()),
+            name @ ("--cid" | "--arbiter" | "--nonce" | "--fee" | "--timestamp" | "--valid-from" | "--valid-until" | "--tx-version") => name,
+            name if name.starts_with("--") => return Err(USAGE.to_string()),
+            operand => {
+                operands.push(operand);
+                continue;
+            }
+        };
+        options.insert(name, rest.next().ok_or(USAGE)?);
+    }
+    let number = |value: &str| value.parse::<u64>().map_err(|_| format!("Invalid number: {}", value));
+    let option = |name: &str| options.get(name).map(|value| number(value)).transpose();
+    let key = |key: &str| hex::decode(key).ok().and_then(|bytes| PublicKey::from_bytes(&bytes).ok()).ok_or_else(|| format!("Invalid public key: {}", key));
+    // Clés dans l'ordre canonique, comme les lecteurs d'un actif et ses participants
+    let keys = |keys: &[&str]| -> Result<Vec<PublicKey>, String> {
+        let mut keys = keys.iter().map(|k| key(k)).collect::<Result<Vec<_>, _>>()?;
+        keys.sort_by_key(|key| key.to_bytes());
+        keys.dedup();
+        Ok(keys)
+    };
+    let id = |id: &str| hex::decode(id).map_err(|_| format!("Invalid identifier: {}", id));
+    let read = |path: &str| std::fs::read(path).map_err(|e| format!("{}: {}", path, e));
+    let keypair = load_or_create_key(key_file)?;
+
+    let mut builder = match (kind.as_str(), operands.as_slice()) {
+        ("transfer", [amount]) => TxBuilder::transfer().amount(number(amount)?),
+        ("shield", [amount]) => TxBuilder::shield().amount(number(amount)?),
+        // Preuves sur le solde confidentiel retrouvé dans le journal de synchronisation
+        #[cfg(feature = "confidential")]
+        ("confidential-transfer", [amount, dir]) => shielded::transfer(&keypair.public, &confidential_opening(&keypair, dir)?, &key(recipient)?, number(amount)?)?,
+        #[cfg(feature = "confidential")]
+        ("unshield", [amount, dir]) => shielded::unshield(&confidential_opening(&keypair, dir)?, &key(recipient)?, number(amount)?)?,
+        ("anchor", [document]) => TxBuilder::anchor(anchors::document_hash(&read(document)?), options.get("--cid").map(|cid| cid.to_string())),
+        ("escrow", [amount, unlock_height]) => {
+            let arbiter = options.get("--arbiter").map(|arbiter| key(arbiter)).transpose()?;
+            TxBuilder::escrow(number(unlock_height)?, arbiter).amount(number(amount)?)
+        }
+        ("claim", [escrow, amount]) => TxBuilder::claim(id(escrow)?).amount(number(amount)?),
+        ("refund", [escrow, amount]) => TxBuilder::refund(id(escrow)?).amount(number(amount)?),
+        ("set-recovery", [challenge_period, threshold, guardians @ ..]) => {
+            let threshold = threshold.parse().map_err(|_| format!("Invalid threshold: {}", threshold))?;
+            TxBuilder::set_recovery(MultisigPolicy::new(threshold, keys(guardians)?)?, number(challenge_period)?)
+        }
+        ("veto-recovery", []) => TxBuilder::veto_recovery(),
+        ("register-asset", readers) => TxBuilder::register_asset(keys(readers)?),
+        ("grant", [asset]) => TxBuilder::grant_access(id(asset)?),
+        ("revoke", [asset]) => TxBuilder::revoke_access(id(asset)?),
+        ("attest", [asset, payload_file, participants @ ..]) => {
+            let payload = EncryptedPayload::seal_to(&keys(participants)?, &read(payload_file)?)?;
+            TxBuilder::attest(id(asset)?, payload)
+        }
+        _ => return Err(USAGE.to_string()),
+    };
+    let config = match std::env::var("SUPPLYX_CONFIG") {
+        Ok(path) => ChainConfig::load(&path)?,
+        Err(_) => ChainConfig::default(),
+    };
+    builder = builder.chain_id(config.chain_id).to(key(recipient)?).timestamp(option("--timestamp")?.unwrap_or(now));
+    if let Some(nonce) = option("--nonce")? {
+        builder = builder.nonce(nonce);
+    }
+    if let Some(fee) = option("--fee")? {
+        builder = builder.fee(fee);
+    }
+    if let Some(height) = option("--valid-from")? {
+        builder = builder.valid_from(height);
+    }
+    if let Some(height) = option("--valid-until")? {
+        builder = builder.valid_until(Expiry::Height(height));
+    }
+    if let Some(version) = options.get("--tx-version") {
+        builder = builder.version(version.parse().map_err(|_| format!("Invalid transaction version: {}", version))?);
+    }
+    Ok(builder.sign(&keypair)?)
+}
+
+// `supplyx backup <répertoire de données> <fichier> <fichier de clé>` : sauvegarde
+// du répertoire (journal de synchronisation, blocs) avec un manifeste signé par
+// la clé de sauvegarde, créée au premier lancement ; à faire nœud arrêté
+fn run_backup(args: &[String]) -> Result<(), String> {
+    let [dir, path, key_file] = args else {
+        return Err("Usage: supplyx backup <data-dir> <backup-file> <key-file>".to_string());
+    };
+    let keypair = load_or_create_key(key_file)?;
+    let references = backup::KEY_REFERENCES.iter()
+        .filter_map(|name| std::env::var(name).ok().map(|value| (name.to_string(), value)))
+        .collect();
+    let created = SystemClock.unix_secs();
+    let backup = Backup::create(std::path::Path::new(dir), references, created, &keypair)?;
+    std::fs::write(path, backup.encode()).map_err(|e| format!("{}: {}", path, e))?;
+    let size: u64 = backup.manifest.entries.iter().map(|entry| entry.size).sum();
+    println!("{} files ({} bytes) saved to {}", backup.manifest.entries.len(), size, path);
+    println!("Manifest {} signed by {}", hex::encode(backup.manifest.hash()), hex::encode(keypair.public.as_bytes()));
+    Ok(())
+}
+
+// `supplyx restore <fichier> <répertoire de données> <clé publique>` : vérifie
+// la signature du manifeste par la clé de sauvegarde attendue et le contenu de
+// chaque fichier avant d'en écrire aucun dans le répertoire, absent ou vide
+fn run_restore(args: &[String]) -> Result<(), String> {
+    let [path, dir, signer] = args else {
+        return Err("Usage: supplyx restore <backup-file> <data-dir> <signer-public-key>".to_string());
+    };
+    let signer = hex::decode(signer).ok().and_then(|bytes| PublicKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| format!("Invalid public key: {}", signer))?;
+    let data = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+    let backup = Backup::decode(&data).map_err(|e| format!("{}: {}", path, e))?;
+    backup.verify(&signer).map_err(|e| format!("{}: {}", path, e))?;
+    backup.unpack(std::path::Path::new(dir))?;
+    println!("{} files restored to {} (manifest {})", backup.manifest.entries.len(), dir, hex::encode(backup.manifest.hash()));
+    for (name, value) in &backup.manifest.references {
+        println!("{}={}", name, value);
+    }
+    Ok(())
+}
+
+// `supplyx analytics export <journal de synchronisation> [fichier]` : agrégats par
+// jour et par époque, en base SQLite ou DuckDB ou en script SQL
+fn run_analytics(args: &[String]) -> Result<(), String> {
+    let (dir, output) = match args {
+        [command, dir] if command == "export" => (dir, None),
+        [command, dir, path] if command == "export" => (dir, Some(path)),
+        _ => return Err("Usage: supplyx analytics export <sync-journal-dir> [path]".to_string()),
+    };
+    let journal = SyncJournal::open(dir.as_str())?;
+    let mut aggregates = Aggregates::default();
+    for block in journal.blocks().blocks(journal.blocks().range()) {
+        aggregates.add(&block?);
+    }
+    match output {
+        Some(path) => aggregates.export(std::path::Path::new(path)),
+        None => {
+            print!("{}", aggregates.to_sql());
+            Ok(())
+        }
+    }
+}
+
+// État de départ d'un nœud : son validateur, doté, les administrateurs de
+// conformité de SUPPLYX_COMPLIANCE_ADMINS et les allocations en acquisition de
+// SUPPLYX_VESTING_ACCOUNTS
+fn init_genesis(blockchain: &mut Blockchain, validator: &PublicKey) {
+    blockchain.register_validator(validator, blockchain.config.consensus.min_producer_stake).unwrap();
+    blockchain.allocate(validator, 1_000_000);
+    if let Ok(admins) = std::env::var("SUPPLYX_COMPLIANCE_ADMINS") {
+        for admin in admins.split(',') {
+            let key = hex::decode(admin.trim()).ok().and_then(|bytes| PublicKey::from_bytes(&bytes).ok());
+            blockchain.add_compliance_admin(key.expect("Invalid SUPPLYX_COMPLIANCE_ADMINS key"));
+        }
+    }
+    if let Ok(accounts) = std::env::var("SUPPLYX_VESTING_ACCOUNTS") {
+        for account in accounts.split(',') {
+            let (account, schedule) = vesting_account(account.trim()).unwrap_or_else(|e| panic!("Invalid SUPPLYX_VESTING_ACCOUNTS: {}", e));
+            blockchain.add_vesting_account(account, schedule).unwrap_or_else(|e| panic!("Invalid SUPPLYX_VESTING_ACCOUNTS: {}", e));
+        }
+    }
+}
+
+// `<clé>:<total>:<début>:<cliff>:<durée>:<intervalle>`, hauteurs en blocs
+fn vesting_account(spec: &str) -> Result<(PublicKey, VestingSchedule), String> {
+    let [key, total, start, cliff, duration, interval] = spec.split(':').collect::<Vec<_>>()[..] else {
+        return Err(format!("Invalid vesting account: {}", spec));
+    };
+    let account = hex::decode(key).ok().and_then(|bytes| PublicKey::from_bytes(&bytes).ok()).ok_or_else(|| format!("Invalid public key: {}", key))?;
+    let number = |value: &str| value.parse::<u64>().map_err(|_| format!("Invalid number: {}", value));
+    let schedule = VestingSchedule::new(number(total)?, number(start)?, number(cliff)?, number(duration)?, number(interval)?)?;
+    Ok((account, schedule))
+}
+
+// `supplyx export <journal> [--from <hauteur>] [--to <hauteur>] [--format jsonl|csv] [fichier]` :
+// vidage des blocs du journal de synchronisation pour un audit hors ligne
+fn run_export(args: &[String]) -> Result<(), String> {
+    const USAGE: &str = "Usage: supplyx export <sync-journal-dir> [--from <height>] [--to <height>] [--format jsonl|csv] [path]";
+    let (dir, options) = args.split_first().ok_or(USAGE)?;
+    let (mut from, mut to, mut format, mut output) = (None, None, DumpFormat::Jsonl, None);
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let height = |value: Option<&String>| value.and_then(|value| value.parse::<u64>().ok()).ok_or(USAGE.to_string());
+        match option.as_str() {
+            "--from" => from = Some(height(options.next())?),
+            "--to" => to = Some(height(options.next())?),
+            "--format" => format = options.next().ok_or(USAGE)?.parse()?,
+            path if output.is_none() && !path.starts_with("--") => output = Some(path),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let journal = SyncJournal::open(dir.as_str())?;
+    let stored = journal.blocks().range();
+    let range = from.unwrap_or(stored.start).max(stored.start)..to.map_or(stored.end, |to| to.saturating_add(1)).min(stored.end);
+    if range.is_empty() {
+        return Err(format!("{}: no blocks in the requested range", dir));
+    }
+    let blocks = journal.blocks().blocks(range);
+    let count = match output {
+        Some(path) => {
+            let file = std::fs::File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+            let mut out = std::io::BufWriter::new(file);
+            let count = audit::export(blocks, format, &mut out)?;
+            std::io::Write::flush(&mut out).map_err(|e| format!("{}: {}", path, e))?;
+            count
+        }
+        None => audit::export(blocks, format, &mut std::io::stdout().lock())?,
+    };
+    eprintln!("{} blocks exported", count);
+    Ok(())
+}
+
+// `supplyx import <vidage JSONL> [journal] [--anchors <journal des ancrages>]` :
+// rejoue un vidage commençant au bloc de genèse sur un nœud neuf (configuration
+// de `SUPPLYX_CONFIG`, genèse d'un nœud dont le validateur est le producteur du
+// premier bloc), avec la validation complète de chaque bloc ; les blocs rejoués
+// sont écrits dans le journal s'il est indiqué, qui doit être vide. Les
+// ancrages externes doivent désigner des blocs finalisés de la chaîne rejouée,
+// et sont relus sur la chaîne externe si `SUPPLYX_ANCHOR_NETWORK` est configuré.
+async fn run_import(args: &[String]) -> Result<(), String> {
+    const USAGE: &str = "Usage: supplyx import <dump.jsonl> [sync-journal-dir] [--anchors <anchor-log>]";
+    let (mut positional, mut anchors) = (Vec::new(), None);
+    let mut options = args.iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--anchors" => anchors = Some(options.next().ok_or(USAGE)?),
+            _ => positional.push(option),
+        }
+    }
+    let (path, dir) = match positional[..] {
+        [path] => (path, None),
+        [path, dir] => (path, Some(dir)),
+        _ => return Err(USAGE.to_string()),
+    };
+    let file = std::fs::File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    let blocks = audit::read_blocks(std::io::BufReader::new(file)).map_err(|e| format!("{}: {}", path, e))?;
+    let genesis = blocks.first().ok_or_else(|| format!("{}: no blocks to import", path))?;
+    if genesis.index != 0 {
+        return Err(format!("{}: dump does not start at the genesis block", path));
+    }
+    let mut journal = match dir {
+        Some(dir) => {
+            let journal = SyncJournal::open(dir.as_str())?;
+            if !journal.blocks().range().is_empty() {
+                return Err(format!("{}: sync journal is not empty", dir));
+            }
+            Some(journal)
+        }
+        None => None,
+    };
+
+    let config = match std::env::var("SUPPLYX_CONFIG") {
+        Ok(path) => ChainConfig::load(&path)?,
+        Err(_) => ChainConfig::default(),
+    };
+    let mut blockchain = Blockchain::new(config.clone());
+    blockchain.set_rules_fingerprint(consensus_fingerprint(&config));
+    init_genesis(&mut blockchain, &genesis.validator_pubkey);
+    for block in &blocks {
+        blockchain.add_block(block.clone()).map_err(|e| format!("Block {} rejected: {}", block.index, e))?;
+    }
+    if let Some(journal) = &mut journal {
+        journal.append_blocks(&blocks)?;
+    }
+    println!("{} blocks replayed", blocks.len());
+    println!("tip {}", hex::encode(blockchain.last_hash()));
+    println!("state root {}", hex::encode(blockchain.state_tree.root()));
+
+    if let Some(anchors) = anchors {
+        let file = std::fs::File::open(anchors).map_err(|e| format!("{}: {}", anchors, e))?;
+        let records = anchoring::read_records(std::io::BufReader::new(file)).map_err(|e| format!("{}: {}", anchors, e))?;
+        let anchorer = configured_anchorer()?;
+        for record in &records {
+            let context = format!("Anchor of block {} ({} {})", record.payload.height, record.network, record.reference);
+            record.payload.verify(config.chain_id, &blocks, blockchain.finalized_height()).map_err(|e| format!("{}: {}", context, e))?;
+            if let Some(anchorer) = &anchorer {
+                record.check_published(anchorer.as_ref()).await.map_err(|e| format!("{}: {}", context, e))?;
+            }
+        }
+        let source = if anchorer.is_some() { "checked on the external chain" } else { "not checked on the external chain" };
+        println!("{} anchors verified ({})", records.len(), source);
+    }
+    Ok(())
+}
+
+// Chaîne externe d'ancrage des checkpoints (`SUPPLYX_ANCHOR_NETWORK` : `bitcoin`
+// ou `ethereum`), jointe par JSON-RPC à `SUPPLYX_ANCHOR_URL`
+fn configured_anchorer() -> Result<Option<Arc<dyn Anchorer>>, String> {
+    let Ok(network) = std::env::var("SUPPLYX_ANCHOR_NETWORK") else {
+        return Ok(None);
+    };
+    let url = std::env::var("SUPPLYX_ANCHOR_URL").map_err(|_| "SUPPLYX_ANCHOR_URL is required with SUPPLYX_ANCHOR_NETWORK".to_string())?;
+    let anchorer: Arc<dyn Anchorer> = match network.as_str() {
+        "bitcoin" => Arc::new(BitcoinAnchorer::new(&url)?),
+        "ethereum" => {
+            let account = std::env::var("SUPPLYX_ANCHOR_ACCOUNT").map_err(|_| "SUPPLYX_ANCHOR_ACCOUNT is required for Ethereum anchoring".to_string())?;
+            Arc::new(EthereumAnchorer::new(&url, &account)?)
+        }
+        _ => return Err(format!("Unknown anchor network: {}", network)),
+    };
+    Ok(Some(anchorer))
+}
+
+// `supplyx confidential balance <fichier de clé> <journal de synchronisation>` :
+// solde confidentiel du compte, retrouvé en ouvrant ses notes depuis le début du journal
+#[cfg(feature = "confidential")]
+fn run_confidential(args: &[String]) -> Result<(), String> {
+    let [command, key_file, dir] = args else {
+        return Err("Usage: supplyx confidential balance <key-file> <sync-journal-dir>".to_string());
+    };
+    if command != "balance" {
+        return Err("Usage: supplyx confidential balance <key-file> <sync-journal-dir>".to_string());
+    }
+    let keypair = load_or_create_key(key_file)?;
+    let opening = confidential_opening(&keypair, dir)?;
+    println!("{} {}", hex::encode(keypair.public.as_bytes()), opening.value);
+    Ok(())
+}
+
+// Ouverture du solde confidentiel de `keypair` à la fin du journal `dir`
+#[cfg(feature = "confidential")]
+fn confidential_opening(keypair: &Keypair, dir: &str) -> Result<shielded::Opening, String> {
+    let journal = SyncJournal::open(dir)?;
+    let mut opening = shielded::Opening::default();
+    for block in journal.blocks().blocks(journal.blocks().range()) {
+        shielded::scan(&mut opening, keypair, &block?)?;
+    }
+    Ok(opening)
+}
+
+// `supplyx simulate replay <journal de synchronisation> <blocs> <configuration candidate>`
+// ou `supplyx simulate synthetic <steady|burst>:<transactions par bloc> <blocs> <configuration candidate>` :
+// frais, revenus des producteurs et attente du mempool sous les paramètres en
+// vigueur (`SUPPLYX_CONFIG`) et candidats
+fn run_simulate(args: &[String]) -> Result<(), String> {
+    #[cfg(feature = "consensus-sim")]
+    if args.first().map(String::as_str) == Some("consensus") {
+        return run_consensus_simulation(&args[1..]);
+    }
+    let (source, input, blocks, candidate) = match args {
+        [source, input, blocks, candidate] if source == "replay" || source == "synthetic" => (source, input, blocks, candidate),
+        _ => return Err("Usage: supplyx simulate replay <sync-journal-dir> <blocks> <candidate-config> | synthetic <steady|burst>:<n> <blocks> <candidate-config>".to_string()),
+    };
+    let blocks: u64 = blocks.parse().map_err(|_| format!("Invalid block count: {}", blocks))?;
+    let current = match std::env::var("SUPPLYX_CONFIG") {
+        Ok(path) => ChainConfig::load(&path)?,
+        Err(_) => ChainConfig::default(),
+    };
+    let candidate = ChainConfig::load(candidate)?;
+    let traffic = if source == "replay" {
+        let journal = SyncJournal::open(input.as_str())?;
+        let stored = journal.blocks().range();
+        if stored.is_empty() {
+            return Err(format!("{}: no blocks to replay", input));
+        }
+        let replayed = journal.blocks().blocks(stored.end.saturating_sub(blocks).max(stored.start)..stored.end)
+            .collect::<Result<Vec<Block>, String>>()?;
+        Traffic::replay(&replayed, replayed.len())
+    } else {
+        Traffic::synthetic(input.parse::<Profile>()?, blocks)
+    };
+    print!("{}", simulation::compare(&traffic, &current.consensus, &candidate.consensus));
+    Ok(())
+}
+
+// `supplyx simulate consensus [scénarios]` : réseaux de validateurs en mémoire
+// (pertes, délais, partitions, transferts aléatoires) ; en cas de violation
+// d'un invariant de sûreté, affiche le scénario minimal qui la reproduit
+#[cfg(feature = "consensus-sim")]
+fn run_consensus_simulation(args: &[String]) -> Result<(), String> {
+    let cases = match args {
+        [] => 32,
+        [cases] => cases.parse().map_err(|_| format!("Invalid scenario count: {}", cases))?,
+        _ => return Err("Usage: supplyx simulate consensus [scenarios]".to_string()),
+    };
+    let reports = consensus_sim::check(cases)?;
+    for report in &reports {
+        let finalized = report.finalized.map_or("none".to_string(), |height| height.to_string());
+        println!("height {:>4}  finalized {:>4}  heads {}  delivered {:>6}  lost {:>6}", report.height, finalized, report.heads, report.delivered, report.lost);
+    }
+    println!("{} scenarios passed", reports.len());
+    Ok(())
+}
+
+// `supplyx bench <fichier de clé> [--rpc <url>] [--accounts <nombre>]
+// [--transactions <nombre>] [--rate <tps>] [--timeout <secondes>]` : charge
+// synthétique contre un nœud en service (voir `loadtest.rs`), payée par la clé
+#[cfg(feature = "rpc")]
+async fn run_bench(args: &[String]) -> Result<(), String> {
+    const USAGE: &str = "Usage: supplyx bench <key-file> [--rpc <url>] [--accounts <count>] [--transactions <count>] [--rate <tps>] [--timeout <secs>]";
+    let [key_file, options @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    let config = match std::env::var("SUPPLYX_CONFIG") {
+        Ok(path) => ChainConfig::load(&path)?,
+        Err(_) => ChainConfig::default(),
+    };
+    let addr = std::env::var("SUPPLYX_RPC_ADDR").unwrap_or_else(|_| "127.0.0.1:50051".to_string());
+    let mut load = loadtest::LoadOptions {
+        endpoint: format!("http://{}", addr),
+        chain_id: config.chain_id,
+        accounts: 50,
+        transactions: 1_000,
+        rate: 100,
+        timeout: std::time::Duration::from_secs(60),
+    };
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let number = |value: Option<&String>| value.and_then(|value| value.parse::<u64>().ok()).ok_or(USAGE.to_string());
+        match option.as_str() {
+            "--rpc" => load.endpoint = options.next().ok_or(USAGE)?.clone(),
+            "--accounts" => load.accounts = number(options.next())? as usize,
+            "--transactions" => load.transactions = number(options.next())? as usize,
+            "--rate" => load.rate = number(options.next())?,
+            "--timeout" => load.timeout = std::time::Duration::from_secs(number(options.next())?),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let report = loadtest::run(&load_or_create_key(key_file)?, &load).await?;
+    print!("{}", report);
+    Ok(())
+}
+
+// `supplyx send <fichier de clé> <destinataire> <montant> [--rpc <url>] [--fee <prix du gaz>]
+// [--bump <pourcentage>] [--resubmissions <nombre>]` : transfert signé avec le
+// nonce suivant connu du nœud, puis suivi jusqu'à son inclusion et resoumis à
+// un prix du gaz relevé s'il est retiré ou expire (voir `TxTracker`) ; réseau
+// de `SUPPLYX_CONFIG`
+#[cfg(feature = "rpc")]
+async fn run_send(args: &[String]) -> Result<(), String> {
+    const USAGE: &str = "Usage: supplyx send <key-file> <recipient> <amount> [--rpc <url>] [--fee <gas-price>] [--bump <percent>] [--resubmissions <count>]";
+    // Intervalle entre deux interrogations du nœud
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+    let [key_file, recipient, amount, options @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    let recipient = hex::decode(recipient).ok().and_then(|bytes| PublicKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| format!("Invalid public key: {}", recipient))?;
+    let amount: u64 = amount.parse().map_err(|_| format!("Invalid amount: {}", amount))?;
+    let addr = std::env::var("SUPPLYX_RPC_ADDR").unwrap_or_else(|_| "127.0.0.1:50051".to_string());
+    let (mut endpoint, mut fee) = (format!("http://{}", addr), None);
+    let (mut bump_percent, mut resubmissions) = (lifecycle::DEFAULT_BUMP_PERCENT, lifecycle::DEFAULT_MAX_RESUBMISSIONS);
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let number = |value: Option<&String>| value.and_then(|value| value.parse::<u64>().ok()).ok_or(USAGE.to_string());
+        match option.as_str() {
+            "--rpc" => endpoint = options.next().ok_or(USAGE)?.clone(),
+            "--fee" => fee = Some(number(options.next())?),
+            "--bump" => bump_percent = number(options.next())?,
+            "--resubmissions" => resubmissions = u32::try_from(number(options.next())?).map_err(|_| USAGE.to_string())?,
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let config = match std::env::var("SUPPLYX_CONFIG") {
+        Ok(path) => ChainConfig::load(&path)?,
+        Err(_) => ChainConfig::default(),
+    };
+    let keypair = load_or_create_key(key_file)?;
+    let mut node = rpc::RpcRelay::connect(&endpoint).await?;
+    let nonce = node.next_nonce(&keypair.public).await?;
+    let mut builder = TxBuilder::transfer().chain_id(config.chain_id).to(recipient).amount(amount).nonce(nonce).timestamp(SystemClock.unix_secs());
+    if let Some(fee) = fee {
+        builder = builder.fee(fee);
+    }
+    let transaction = builder.sign(&keypair)?;
+    let mut tracker = lifecycle::TxTracker::new(keypair, Arc::new(SystemClock)).with_policy(bump_percent, resubmissions);
+    eprintln!("Transaction {}", hex::encode(tracker.send(&mut node, transaction).await?));
+    while tracker.pending() > 0 {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        for event in tracker.poll(&mut node).await? {
+            match event {
+                lifecycle::TrackerEvent::Included { hash, height } => println!("Transaction {} included at height {}", hex::encode(hash), height),
+                lifecycle::TrackerEvent::Resubmitted { previous, hash, gas_price } => eprintln!("Transaction {} resubmitted as {} at gas price {}", hex::encode(previous), hex::encode(hash), gas_price),
+                lifecycle::TrackerEvent::Abandoned { hash, reason } => return Err(format!("Transaction {} abandoned: {}", hex::encode(hash), reason)),
+            }
+        }
+    }
+    Ok(())
+}
+
+// `supplyx private propose <fichier de clé> <contenu> <proposition> <membre>... [--rpc <url>]`,
+// `supplyx private endorse <fichier de clé> <proposition>` puis `supplyx private
+// submit <proposition> [--rpc <url>]` : contenu chiffré pour un groupe de
+// confidentialité (l'auteur et les membres donnés), approuvé tour à tour par
+// chaque membre après lecture, puis soumis au nœud qui le relaie à ses pairs
+#[cfg(feature = "rpc")]
+async fn run_private(args: &[String]) -> Result<(), String> {
+    const USAGE: &str = "Usage: supplyx private propose <key-file> <payload-file> <proposal-file> <member>... [--rpc <url>] | endorse <key-file> <proposal-file> | submit <proposal-file> [--rpc <url>]";
+    let addr = std::env::var("SUPPLYX_RPC_ADDR").unwrap_or_else(|_| "127.0.0.1:50051".to_string());
+    let mut endpoint = format!("http://{}", addr);
+    let mut operands = Vec::new();
+    let mut options = args.iter();
+    while let Some(arg) = options.next() {
+        match arg.as_str() {
+            "--rpc" => endpoint = options.next().ok_or(USAGE)?.clone(),
+            _ => operands.push(arg.as_str()),
+        }
+    }
+    let read_proposal = |path: &str| -> Result<privacy::PrivateProposal, String> {
+        let data = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+        privacy::PrivateProposal::from_bytes(&data).map_err(|e| format!("{}: {}", path, e))
+    };
+    let write_proposal = |path: &str, proposal: &privacy::PrivateProposal| std::fs::write(path, proposal.to_bytes()).map_err(|e| format!("{}: {}", path, e));
+    match operands.as_slice() {
+        ["propose", key_file, payload_file, proposal_file, members @ ..] if !members.is_empty() => {
+            let keypair = load_or_create_key(key_file)?;
+            let mut group = vec![keypair.public];
+            for member in members {
+                group.push(hex::decode(member).ok().and_then(|bytes| PublicKey::from_bytes(&bytes).ok()).ok_or_else(|| format!("Invalid public key: {}", member))?);
+            }
+            let group = privacy::PrivacyGroup::new(group)?;
+            let payload = std::fs::read(payload_file).map_err(|e| format!("{}: {}", payload_file, e))?;
+            let previous_state = rpc::RpcRelay::connect(&endpoint).await?.private_state(&group).await?;
+            let mut proposal = privacy::PrivateProposal::new(&group, &payload, &previous_state)?;
+            proposal.endorse(&keypair)?;
+            write_proposal(proposal_file, &proposal)?;
+            println!("Group {} proposal {}", hex::encode(group.id()), hex::encode(&proposal.payload_hash));
+        }
+        ["endorse", key_file, proposal_file] => {
+            let keypair = load_or_create_key(key_file)?;
+            let mut proposal = read_proposal(proposal_file)?;
+            let payload = proposal.endorse(&keypair)?;
+            write_proposal(proposal_file, &proposal)?;
+            std::io::Write::write_all(&mut std::io::stdout(), &payload).map_err(|e| e.to_string())?;
+            let endorsed = proposal.endorsements.iter().filter(|endorsement| endorsement.is_some()).count();
+            eprintln!("Endorsed by {}/{} members", endorsed, proposal.members.len());
+        }
+        ["submit", proposal_file] => {
+            let transaction = read_proposal(proposal_file)?.transaction()?;
+            let hash = rpc::RpcRelay::connect(&endpoint).await?.submit_private(&transaction).await?;
+            println!("Private transaction {}", hex::encode(hash));
+        }
+        _ => return Err(USAGE.to_string()),
+    }
+    Ok(())
+}
+
+// `supplyx governance propose <fichier de clé> <paramètre> <valeur> [--rpc <url>]`
+// ou `supplyx governance vote <fichier de clé> <proposition> <yes|no> [--rpc <url>]` :
+// transaction de gouvernance signée par la clé d'un validateur, soumise au nœud
+// (`SubmitGovernanceTransaction`) qui la relaie à ses pairs.
+// `supplyx governance incident-read <fichier de clé> <incident>` déchiffre les notes
+// d'un incident servies par le nœud (`GetIncident`)
+#[cfg(feature = "rpc")]
+async fn run_governance(args: &[String]) -> Result<(), String> {
+    let addr = std::env::var("SUPPLYX_RPC_ADDR").unwrap_or_else(|_| "127.0.0.1:50051".to_string());
+    let mut endpoint = format!("http://{}", addr);
+    let mut operands = Vec::new();
+    let mut options = args.iter();
+    while let Some(arg) = options.next() {
+        match arg.as_str() {
+            "--rpc" => endpoint = options.next().ok_or(GOVERNANCE_USAGE)?.clone(),
+            _ => operands.push(arg.as_str()),
+        }
+    }
+    if let ["incident-read", key_file, incident] = operands.as_slice() {
+        let keypair = load_or_create_key(key_file)?;
+        let incident_id = hex::decode(incident).map_err(|_| format!("Invalid incident id: {}", incident))?;
+        for (height, author, note) in rpc::RpcRelay::connect(&endpoint).await?.incident_notes(&incident_id).await? {
+            let note = note.open(&keypair)?;
+            println!("{} {} {}", height, hex::encode(author.as_bytes()), String::from_utf8_lossy(&note));
+        }
+        return Ok(());
+    }
+    let transaction = governance_transaction(&operands, SystemClock.unix_secs())?;
+    let hash = rpc::RpcRelay::connect(&endpoint).await?.submit_governance(&transaction).await?;
+    println!("{} {}", transaction.name(), hex::encode(hash));
+    Ok(())
+}
+
+#[cfg(feature = "rpc")]
+const GOVERNANCE_USAGE: &str = "Usage: supplyx governance propose <key-file> <parameter> <value> | vote <key-file> <proposal-id> <yes|no> | freeze <key-file> <account> <reason-file> | unfreeze <key-file> <account> <reason-file> | incident-open <key-file> <responder>... | incident-note <key-file> <incident-id> <note-file> <responder>... | incident-close <key-file> <incident-id> | incident-read <key-file> <incident-id> | rotate-key <key-file> <new-key> | commission <key-file> <percent> <epoch> [--rpc <url>]";
+
+// Paramètres désignés comme les champs de `ConsensusParams` ; les intervenants d'un
+// incident comprennent toujours le signataire
+#[cfg(feature = "rpc")]
+fn governance_transaction(operands: &[&str], now: u64) -> Result<GovernanceTransaction, String> {
+    let responders = |keypair: &Keypair, responders: &[&str]| -> Result<privacy::PrivacyGroup, String> {
+        let mut group = vec![keypair.public];
+        for responder in responders {
+            group.push(hex::decode(responder).ok().and_then(|bytes| PublicKey::from_bytes(&bytes).ok()).ok_or_else(|| format!("Invalid public key: {}", responder))?);
+        }
+        Ok(privacy::PrivacyGroup::new(group)?)
+    };
+    let incident_id = |incident: &str| hex::decode(incident).map_err(|_| format!("Invalid incident id: {}", incident));
+    match operands {
+        ["propose", key_file, parameter, value] => {
+            let value = value.parse().map_err(|_| format!("Invalid parameter value: {}", value))?;
+            let change = match *parameter {
+                "min_registration_stake" => governance::ParameterChange::MinRegistrationStake(value),
+                "min_producer_stake" => governance::ParameterChange::MinProducerStake(value),
+                "max_block_transactions" => governance::ParameterChange::MaxBlockTransactions(value),
+                "max_block_gas" => governance::ParameterChange::MaxBlockGas(value),
+                "upgrade_threshold" => governance::ParameterChange::UpgradeThreshold(value),
+                "upgrade_epochs" => governance::ParameterChange::UpgradeEpochs(value),
+                "max_active_validators" => governance::ParameterChange::MaxActiveValidators(value),
+                _ => return Err(format!("Unknown consensus parameter: {}", parameter)),
+            };
+            Ok(GovernanceTransaction::Proposal(governance::Proposal::new(&load_or_create_key(key_file)?, change, now)))
+        }
+        ["vote", key_file, proposal, approve] => {
+            let proposal_id = hex::decode(proposal).map_err(|_| format!("Invalid proposal id: {}", proposal))?;
+            let approve = match *approve {
+                "yes" => true,
+                "no" => false,
+                _ => return Err(GOVERNANCE_USAGE.to_string()),
+            };
+            Ok(GovernanceTransaction::Vote(governance::Vote::new(&load_or_create_key(key_file)?, proposal_id, approve)))
+        }
+        // Le motif reste hors chaîne, seule son empreinte est publiée
+        [command @ ("freeze" | "unfreeze"), key_file, account, reason_file] => {
+            let account = hex::decode(account).ok().and_then(|bytes| PublicKey::from_bytes(&bytes).ok()).ok_or_else(|| format!("Invalid public key: {}", account))?;
+            let reason = std::fs::read(reason_file).map_err(|e| format!("{}: {}", reason_file, e))?;
+            let order = FreezeOrder::new(&load_or_create_key(key_file)?, account, *command == "freeze", anchors::document_hash(&reason), now);
+            Ok(GovernanceTransaction::Freeze(order))
+        }
+        ["incident-open", key_file, others @ ..] if !others.is_empty() => {
+            let keypair = load_or_create_key(key_file)?;
+            let open = incidents::IncidentOpen::new(&keypair, responders(&keypair, others)?, now);
+            Ok(GovernanceTransaction::IncidentOpen(open))
+        }
+        // La note est chiffrée pour tous les intervenants de l'incident
+        ["incident-note", key_file, incident, note_file, others @ ..] if !others.is_empty() => {
+            let keypair = load_or_create_key(key_file)?;
+            let note = std::fs::read(note_file).map_err(|e| format!("{}: {}", note_file, e))?;
+            let note = IncidentNote::new(&keypair, incident_id(incident)?, &responders(&keypair, others)?, &note, now)?;
+            Ok(GovernanceTransaction::IncidentNote(note))
+        }
+        ["incident-close", key_file, incident] => {
+            let close = incidents::IncidentClose::new(&load_or_create_key(key_file)?, incident_id(incident)?, now);
+            Ok(GovernanceTransaction::IncidentClose(close))
+        }
+        ["rotate-key", key_file, new_key] => {
+            let new_key = hex::decode(new_key).ok().and_then(|bytes| PublicKey::from_bytes(&bytes).ok()).ok_or_else(|| format!("Invalid public key: {}", new_key))?;
+            Ok(GovernanceTransaction::RotateKey(rotation::KeyRotation::new(&load_or_create_key(key_file)?, new_key)?))
+        }
+        // Signée pour l'époque en cours, la commission s'applique à la suivante
+        ["commission", key_file, percent, epoch] => {
+            let percent = percent.parse().map_err(|_| format!("Invalid percentage: {}", percent))?;
+            let epoch = epoch.parse().map_err(|_| format!("Invalid epoch: {}", epoch))?;
+            Ok(GovernanceTransaction::SetCommission(rewards::CommissionChange::new(&load_or_create_key(key_file)?, percent, epoch)?))
+        }
+        _ => Err(GOVERNANCE_USAGE.to_string()),
+    }
+}
+
+// `supplyx --dev [--accounts <nombre>] [--faucet <adresse>]` : réseau de
+// développement local à un seul validateur (voir `devnet.rs`), sans pairs ni
+// journal ; un bloc est produit dès qu'une transaction est en attente
+async fn run_dev(args: &[String]) -> Result<(), String> {
+    const USAGE: &str = "Usage: supplyx --dev [--accounts <count>] [--faucet <addr>]";
+    let (mut accounts, mut faucet_addr) = (DEV_ACCOUNTS, "127.0.0.1:8080".to_string());
+    let mut options = args.iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--accounts" => accounts = options.next().and_then(|count| count.parse().ok()).ok_or(USAGE)?,
+            "--faucet" => faucet_addr = options.next().ok_or(USAGE)?.clone(),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let faucet_addr: std::net::SocketAddr = faucet_addr.parse().map_err(|_| format!("Invalid faucet address: {}", faucet_addr))?;
+    telemetry::init(LogFormat::Pretty, None)?;
+
+    let Devnet { mut blockchain, validator, faucet, accounts } = Devnet::new(accounts);
+    blockchain.set_rules_fingerprint(consensus_fingerprint(&blockchain.config));
+    println!("Chain id: {}", blockchain.config.chain_id);
+    println!("Validator: {}", hex::encode(validator.public.as_bytes()));
+    for (index, account) in accounts.iter().enumerate() {
+        println!("Account {}: {} (secret key {})", index, hex::encode(account.public.as_bytes()), hex::encode(account.secret.as_bytes()));
+    }
+    println!("Faucet: POST http://{}/faucet/<public-key>", faucet_addr);
+
+    #[cfg(feature = "ws")]
+    serve_websocket(blockchain.events.clone());
+    let node_config = NodeConfig {
+        slot_interval: std::time::Duration::from_millis(DEV_SLOT_MILLIS),
+        empty_blocks: false,
+        ..NodeConfig::default()
+    };
+    let (node, _events) = Node::new(blockchain, Arc::new(validator), node_config);
+
+    #[cfg(feature = "rpc")]
+    {
+        let addr = std::env::var("SUPPLYX_RPC_ADDR").unwrap_or_else(|_| "127.0.0.1:50051".to_string());
+        let addr = addr.parse().map_err(|_| format!("Invalid SUPPLYX_RPC_ADDR: {}", addr))?;
+        println!("RPC: {}", addr);
+        let blockchain = node.blockchain();
+        tokio::spawn(async move {
+            if let Err(e) = rpc::serve(addr, blockchain).await {
+                error!(error = e, "RPC server stopped");
+            }
+        });
+    }
+
+    let blockchain = node.blockchain();
+    let faucet = Faucet::new(faucet, FaucetPolicy::default());
+    tokio::spawn(async move {
+        if let Err(e) = faucet::serve(faucet_addr, blockchain, faucet).await {
+            error!(error = e, "Faucet server stopped");
+        }
+    });
+
+    node.run().await;
+    Ok(())
+}
+
+#[cfg(feature = "ws")]
+fn serve_websocket(events: EventBus) {
+    if let Ok(addr) = std::env::var("SUPPLYX_WS_ADDR") {
+        let addr = addr.parse().expect("Invalid SUPPLYX_WS_ADDR");
+        tokio::spawn(async move {
+            if let Err(e) = ws::serve(addr, events).await {
+                error!(error = e, "WebSocket server stopped");
+            }
+        });
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("conformance") {
+        if let Err(e) = run_conformance(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("--dev") {
+        if let Err(e) = run_dev(&args[2..]).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("tx") {
+        if let Err(e) = run_tx(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("backup") {
+        if let Err(e) = run_backup(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("restore") {
+        if let Err(e) = run_restore(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("export") {
+        if let Err(e) = run_export(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("import") {
+        if let Err(e) = run_import(&args[2..]).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("analytics") {
+        if let Err(e) = run_analytics(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    #[cfg(feature = "confidential")]
+    if args.get(1).map(String::as_str) == Some("confidential") {
+        if let Err(e) = run_confidential(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    #[cfg(feature = "rpc")]
+    if args.get(1).map(String::as_str) == Some("send") {
+        if let Err(e) = run_send(&args[2..]).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    #[cfg(feature = "rpc")]
+    if args.get(1).map(String::as_str) == Some("private") {
+        if let Err(e) = run_private(&args[2..]).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    #[cfg(feature = "rpc")]
+    if args.get(1).map(String::as_str) == Some("governance") {
+        if let Err(e) = run_governance(&args[2..]).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    #[cfg(feature = "rpc")]
+    if args.get(1).map(String::as_str) == Some("bench") {
+        if let Err(e) = run_bench(&args[2..]).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("simulate") {
+        if let Err(e) = run_simulate(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("signer") {
+        if let Err(e) = run_signer(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("version") {
+        if let Err(e) = run_version(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("verify-bundle") {
+        if let Err(e) = run_verify_bundle(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let log_format = match std::env::var("SUPPLYX_LOG_FORMAT") {
+        Ok(format) => format.parse().expect("Invalid SUPPLYX_LOG_FORMAT"),
+        Err(_) => LogFormat::Pretty,
+    };
+    telemetry::init(log_format, std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok()).unwrap();
+
+    // Paramètres de consensus de départ, vérifiés avant le démarrage
+    let config = match std::env::var("SUPPLYX_CONFIG") {
+        Ok(path) => ChainConfig::load(&path).unwrap_or_else(|e| panic!("Invalid SUPPLYX_CONFIG: {}", e)),
+        Err(_) => ChainConfig::default(),
+    };
+
+    // Réplica en lecture : suit le flux de blocs d'un primaire au lieu de produire
+    #[cfg(feature = "replication")]
+    if let Ok(primary) = std::env::var("SUPPLYX_REPLICATE_FROM") {
+        let mut blockchain = Blockchain::new(config.clone());
+        blockchain.set_rules_fingerprint(consensus_fingerprint(&config));
+        if std::env::var("SUPPLYX_ARCHIVE").is_ok() {
+            blockchain.enable_archive();
+        }
+        #[cfg(feature = "ws")]
+        serve_websocket(blockchain.events.clone());
+        if let Err(e) = replication::follow(primary, Arc::new(tokio::sync::Mutex::new(blockchain))).await {
+            error!(error = e, "Replication stopped");
+        }
+        return;
+    }
+
+    let fingerprint = consensus_fingerprint(&config);
+    info!(fingerprint = %hex::encode(&fingerprint), "Consensus rules fingerprint");
+    let mut blockchain = Blockchain::new(config);
+    blockchain.set_rules_fingerprint(fingerprint);
+    if std::env::var("SUPPLYX_ARCHIVE").is_ok() {
+        blockchain.enable_archive();
+    }
+    let mut mempool_policy = MempoolPolicy::default();
+    if let Ok(fee) = std::env::var("SUPPLYX_MEMPOOL_MIN_FEE_PER_BYTE") {
+        mempool_policy.min_fee_per_byte = fee.parse().expect("Invalid SUPPLYX_MEMPOOL_MIN_FEE_PER_BYTE");
+    }
+    if let Ok(count) = std::env::var("SUPPLYX_MEMPOOL_MAX_PER_SENDER") {
+        mempool_policy.max_pending_per_sender = count.parse().expect("Invalid SUPPLYX_MEMPOOL_MAX_PER_SENDER");
+    }
+    if let Ok(rate) = std::env::var("SUPPLYX_MEMPOOL_SENDER_RATE") {
+        mempool_policy.sender_rate_per_minute = rate.parse().expect("Invalid SUPPLYX_MEMPOOL_SENDER_RATE");
+    }
+    if let Ok(percent) = std::env::var("SUPPLYX_MEMPOOL_REPLACEMENT_BUMP") {
+        mempool_policy.replacement_bump_percent = percent.parse().expect("Invalid SUPPLYX_MEMPOOL_REPLACEMENT_BUMP");
+    }
+    blockchain.set_mempool_policy(mempool_policy);
+    if let Ok(source) = std::env::var("SUPPLYX_TIMESTAMP_SOURCE") {
+        blockchain.set_timestamp_source(TimestampSource::parse(&source).unwrap_or_else(|e| panic!("Invalid SUPPLYX_TIMESTAMP_SOURCE: {}", e)));
+    }
+    // Clé de validation hors du processus si un signataire distant est configuré
+    let (validator_signer, validator_keypair): (Arc<dyn signer::Signer>, Option<Arc<Keypair>>) = match std::env::var("SUPPLYX_REMOTE_SIGNER") {
+        Ok(addr) => {
+            let auth_key = std::env::var("SUPPLYX_REMOTE_SIGNER_KEY").expect("SUPPLYX_REMOTE_SIGNER_KEY is required with SUPPLYX_REMOTE_SIGNER");
+            (Arc::new(RemoteSigner::connect(&addr, &auth_key).expect("Invalid SUPPLYX_REMOTE_SIGNER")), None)
+        }
+        Err(_) => {
+            let keypair = Arc::new(Keypair::generate(&mut OsRng));
+            (keypair.clone(), Some(keypair))
+        }
+    };
+    let validator = validator_signer.public_key();
+
+    init_genesis(&mut blockchain, &validator);
+
+    // Robinet des réseaux de test : compte doté à la genèse, servi en HTTP
+    let faucet = std::env::var("SUPPLYX_FAUCET_ADDR").ok().map(|addr| {
+        assert!(blockchain.config.testnet, "SUPPLYX_FAUCET_ADDR requires a testnet configuration");
+        let addr: std::net::SocketAddr = addr.parse().expect("Invalid SUPPLYX_FAUCET_ADDR");
+        let key_file = std::env::var("SUPPLYX_FAUCET_KEY").expect("SUPPLYX_FAUCET_KEY is required with SUPPLYX_FAUCET_ADDR");
+        let keypair = load_or_create_key(&key_file).unwrap_or_else(|e| panic!("Invalid SUPPLYX_FAUCET_KEY: {}", e));
+        let mut policy = FaucetPolicy::default();
+        if let Ok(amount) = std::env::var("SUPPLYX_FAUCET_AMOUNT") {
+            policy.amount = amount.parse().expect("Invalid SUPPLYX_FAUCET_AMOUNT");
+        }
+        if let Ok(amount) = std::env::var("SUPPLYX_FAUCET_PER_ADDRESS") {
+            policy.per_address = amount.parse().expect("Invalid SUPPLYX_FAUCET_PER_ADDRESS");
+        }
+        if let Ok(count) = std::env::var("SUPPLYX_FAUCET_PER_HOST") {
+            policy.per_host = count.parse().expect("Invalid SUPPLYX_FAUCET_PER_HOST");
+        }
+        if let Ok(amount) = std::env::var("SUPPLYX_FAUCET_PER_DAY") {
+            policy.per_day = amount.parse().expect("Invalid SUPPLYX_FAUCET_PER_DAY");
+        }
+        let faucet = Faucet::new(keypair, policy);
+        blockchain.allocate(&faucet.public_key(), FAUCET_ALLOCATION);
+        info!(account = %hex::encode(faucet.public_key().as_bytes()), "Faucet enabled");
+        (addr, faucet)
+    });
+
+    if let Some(keypair) = &validator_keypair {
+        let recipient_keypair = Keypair::generate(&mut OsRng);
+        blockchain.create_transaction(keypair, &recipient_keypair.public, 50).unwrap();
+    }
+
+    #[cfg(feature = "ws")]
+    serve_websocket(blockchain.events.clone());
+    let chain_events = blockchain.subscribe();
+
+    // Contrôle externe des émetteurs à l'entrée du mempool (hors consensus)
+    let mut node_config = NodeConfig::default();
+    if let Ok(url) = std::env::var("SUPPLYX_ADMISSION_URL") {
+        let hook = HttpAllowlist::new(&url).expect("Invalid SUPPLYX_ADMISSION_URL");
+        let failure_mode = match std::env::var("SUPPLYX_ADMISSION_FAILURE") {
+            Ok(mode) => mode.parse().expect("Invalid SUPPLYX_ADMISSION_FAILURE"),
+            Err(_) => FailureMode::Closed,
+        };
+        let cache_secs = match std::env::var("SUPPLYX_ADMISSION_CACHE_SECS") {
+            Ok(secs) => secs.parse().expect("Invalid SUPPLYX_ADMISSION_CACHE_SECS"),
+            Err(_) => 60,
+        };
+        let gate = AdmissionGate::new(Arc::new(hook), failure_mode, std::time::Duration::from_secs(cache_secs));
+        node_config.admission = Some(Arc::new(gate));
+    }
+    if let Ok(blocks) = std::env::var("SUPPLYX_ORPHAN_MAX_BLOCKS") {
+        node_config.orphan_limits.max_blocks = blocks.parse().expect("Invalid SUPPLYX_ORPHAN_MAX_BLOCKS");
+    }
+    if let Ok(bytes) = std::env::var("SUPPLYX_ORPHAN_MAX_BYTES") {
+        node_config.orphan_limits.max_bytes = bytes.parse().expect("Invalid SUPPLYX_ORPHAN_MAX_BYTES");
+    }
+    if let Ok(secs) = std::env::var("SUPPLYX_ORPHAN_MAX_AGE_SECS") {
+        node_config.orphan_limits.max_age = std::time::Duration::from_secs(secs.parse().expect("Invalid SUPPLYX_ORPHAN_MAX_AGE_SECS"));
+    }
+
+    // Ancrage périodique des checkpoints finalisés sur une chaîne publique
+    if let Some(anchorer) = configured_anchorer().unwrap_or_else(|e| panic!("Invalid anchoring configuration: {}", e)) {
+        let log = std::env::var("SUPPLYX_ANCHOR_LOG").expect("SUPPLYX_ANCHOR_LOG is required with SUPPLYX_ANCHOR_NETWORK");
+        let interval = match std::env::var("SUPPLYX_ANCHOR_INTERVAL_SECS") {
+            Ok(secs) => secs.parse().expect("Invalid SUPPLYX_ANCHOR_INTERVAL_SECS"),
+            Err(_) => 3600,
+        };
+        anchoring::follow(anchorer, blockchain.config.chain_id, std::time::Duration::from_secs(interval), log.into(), blockchain.subscribe());
+    }
+
+    // Plugins compilés dans le nœud : hooks de blocs et d'admission, requêtes de l'explorateur
+    let plugins = Arc::new(plugins::registered());
+    if !plugins.is_empty() {
+        plugins.clone().follow(blockchain.subscribe());
+    }
+    node_config.plugins = plugins.clone();
+
+    let (node, events) = Node::new(blockchain, validator_signer, node_config);
+
+    let reputation = match std::env::var("SUPPLYX_BAN_LIST") {
+        Ok(path) => Reputation::load(path).expect("Invalid SUPPLYX_BAN_LIST"),
+        Err(_) => Reputation::default(),
+    };
+    let gossip = Gossip::new(transport::default_transports(), events, node.blockchain(), reputation);
+    gossip.relay(chain_events, node.traces());
+    if let Ok(endpoints) = std::env::var("SUPPLYX_GOSSIP_LISTEN") {
+        for endpoint in endpoints.split(',') {
+            if let Err(e) = gossip.listen(endpoint.trim()).await {
+                error!(endpoint, error = %e, "Gossip listener failed");
+            }
+        }
+    }
+    if let Ok(peers) = std::env::var("SUPPLYX_PEERS") {
+        for peer in peers.split(',') {
+            if let Err(e) = gossip.connect(peer.trim()).await {
+                warn!(peer, error = %e, "Peer unreachable");
+            }
+        }
+    }
+
+    // Rattrapage de la chaîne auprès des pairs avant de produire
+    let journal = std::env::var("SUPPLYX_SYNC_DIR").ok()
+        .map(|dir| SyncJournal::open(dir).expect("Invalid SUPPLYX_SYNC_DIR"));
+    // Départ du dernier instantané d'un pair plutôt que de la genèse ; un
+    // journal déjà rempli est rejoué depuis la genèse à la place
+    if std::env::var_os("SUPPLYX_FAST_SYNC").is_some() {
+        if journal.as_ref().is_some_and(|journal| !journal.blocks().range().is_empty()) {
+            info!("Sync journal not empty, fast sync skipped");
+        } else {
+            let next_index = node.blockchain().lock().await.next_index();
+            match PeerSnapshot::fetch(&gossip.sync_peers().await, next_index).await {
+                Some(source) => match node.fast_sync(&source).await {
+                    Ok(next_index) => info!(snapshot = source.height(), next_index, "Chain restored from a peer snapshot"),
+                    Err(e) => warn!(error = e, "Fast sync failed"),
+                },
+                None => info!("No peer snapshot ahead of the local chain"),
+            }
+        }
+    }
+    let mut synchronizer = Synchronizer::new(node.blockchain(), journal);
+    if let Err(e) = synchronizer.resume().await {
+        warn!(error = %e, "Sync journal unavailable");
+    }
+    // Nœud de secours apparié : rattrapage différentiel auprès du primaire,
+    // les pairs ne fournissant ensuite que les blocs produits depuis
+    if let Ok(primary) = std::env::var("SUPPLYX_PAIRED_SYNC_PRIMARY") {
+        let auth_key = std::env::var("SUPPLYX_PAIRED_SYNC_KEY").expect("SUPPLYX_PAIRED_SYNC_KEY is required with SUPPLYX_PAIRED_SYNC_PRIMARY");
+        match standby::catch_up(&primary, &node.blockchain(), &auth_key).await {
+            Ok(next_index) => info!(next_index, "Chain synchronized with the primary"),
+            Err(e) => warn!(error = %e, "Differential sync with the primary failed"),
+        }
+    }
+    match synchronizer.run(&gossip.sync_peers().await).await {
+        Ok(next_index) => info!(next_index, "Chain synchronized with peers"),
+        Err(e) => warn!(error = %e, "Sync with peers failed"),
+    }
+
+    #[cfg(feature = "rpc")]
+    if let Ok(addr) = std::env::var("SUPPLYX_RPC_ADDR") {
+        let addr = addr.parse().expect("Invalid SUPPLYX_RPC_ADDR");
+        let blockchain = node.blockchain();
+        tokio::spawn(async move {
+            if let Err(e) = rpc::serve(addr, blockchain).await {
+                error!(error = e, "RPC server stopped");
+            }
+        });
+    }
+
+    #[cfg(feature = "admin")]
+    if let Ok(addr) = std::env::var("SUPPLYX_ADMIN_ADDR") {
+        let addr = addr.parse().expect("Invalid SUPPLYX_ADMIN_ADDR");
+        let gossip = gossip.clone();
+        let blockchain = node.blockchain();
+        tokio::spawn(async move {
+            if let Err(e) = admin::serve(addr, gossip, blockchain).await {
+                error!(error = e, "Admin server stopped");
+            }
+        });
+    }
+
+    #[cfg(feature = "metrics")]
+    if let Ok(addr) = std::env::var("SUPPLYX_METRICS_ADDR") {
+        let addr = addr.parse().expect("Invalid SUPPLYX_METRICS_ADDR");
+        let (blockchain, gossip, ingestion) = (node.blockchain(), gossip.clone(), node.ingestion_metrics());
+        tokio::spawn(async move {
+            if let Err(e) = metrics::serve(addr, blockchain, gossip, ingestion).await {
+                error!(error = e, "Metrics server stopped");
+            }
+        });
+    }
+
+    #[cfg(feature = "explorer")]
+    if let Ok(addr) = std::env::var("SUPPLYX_EXPLORER_ADDR") {
+        let addr = addr.parse().expect("Invalid SUPPLYX_EXPLORER_ADDR");
+        let (blockchain, plugins) = (node.blockchain(), plugins.clone());
+        tokio::spawn(async move {
+            if let Err(e) = explorer::serve(addr, blockchain, plugins).await {
+                error!(error = e, "Explorer server stopped");
+            }
+        });
+    }
+
+    if let Some((addr, faucet)) = faucet {
+        let blockchain = node.blockchain();
+        tokio::spawn(async move {
+            if let Err(e) = faucet::serve(addr, blockchain, faucet).await {
+                error!(error = e, "Faucet server stopped");
+            }
+        });
+    }
+
+    #[cfg(feature = "replication")]
+    if let Ok(addr) = std::env::var("SUPPLYX_REPLICATION_ADDR") {
+        let addr = addr.parse().expect("Invalid SUPPLYX_REPLICATION_ADDR");
+        let blockchain = node.blockchain();
+        tokio::spawn(async move {
+            if let Err(e) = replication::serve(addr, blockchain).await {
+                error!(error = e, "Replication server stopped");
+            }
+        });
+    }
+
+    if let Ok(addr) = std::env::var("SUPPLYX_PAIRED_SYNC_ADDR") {
+        let auth_key = std::env::var("SUPPLYX_PAIRED_SYNC_KEY").expect("SUPPLYX_PAIRED_SYNC_KEY is required with SUPPLYX_PAIRED_SYNC_ADDR");
+        let blockchain = node.blockchain();
+        tokio::spawn(async move {
+            if let Err(e) = standby::serve(&addr, blockchain, &auth_key).await {
+                error!(error = e, "Paired sync server stopped");
+            }
+        });
+    }
+
+    node.run().await;
+}
+
+// Production en une étape, sans relâcher la chaîne pendant la signature
+#[cfg(test)]
+impl Blockchain {
+    // Bloc du créneau `slot`, construit, signé par `signer` puis appliqué
+    pub fn validate_and_create_block(&mut self, signer: &dyn signer::Signer, slot: u64) -> Result<Block, &'static str> {
+        let template = self.build_block(signer.public_key(), slot)?;
+        match signer.sign(&template.current_hash) {
+            Ok(signature) => self.commit_block(template, signature),
+            Err(e) => {
+                self.abandon_block(template);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::clock::MockClock;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    // Chaîne à un seul validateur, à l'heure virtuelle de `clock`
+    fn test_chain(validator: &Keypair, clock: &MockClock) -> Blockchain {
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain
+    }
+
+    // Bloc du créneau `slot`, produit cinq secondes après le précédent
+    fn produce(chain: &mut Blockchain, clock: &MockClock, validator: &Keypair, slot: u64) -> Block {
+        clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+        chain.validate_and_create_block(validator, slot).unwrap()
+    }
+
+    // Bloc modifié, re-haché et re-signé par son producteur
+    fn resigned(mut block: Block, validator: &Keypair) -> Block {
+        block.current_hash = BlockHeader::compute_hash(block.chain_id, block.index, block.slot, block.timestamp, block.difficulty, block.protocol_version, &block.rules_fingerprint, &block.previous_hash, &block.transactions_root, &block.state_root, &block.snapshot_hash, block.checkpoint.as_ref(), &block.validator_pubkey);
+        block.validator_signature = validator.sign(&block.current_hash);
+        block
+    }
+
+    // Le même bloc avec d'autres transactions, re-signé par son producteur
+    fn with_transactions(mut block: Block, transactions: Vec<Transaction>, validator: &Keypair) -> Block {
+        block.transactions_root = Block::transactions_root(&transactions, &block.private_transactions, &block.governance_transactions);
+        block.transactions = transactions;
+        resigned(block, validator)
+    }
+
+    // Le même bloc avec un autre checkpoint, re-signé par son producteur
+    fn with_checkpoint(mut block: Block, checkpoint: Checkpoint, validator: &Keypair) -> Block {
+        block.checkpoint = Some(checkpoint);
+        resigned(block, validator)
+    }
+
+    #[test]
+    fn checkpoints_need_two_thirds_of_stake_from_distinct_validators() {
+        let validator = Keypair::generate(&mut OsRng);
+        let others = [Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng)];
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut producer = test_chain(&validator, &clock);
+        let mut follower = test_chain(&validator, &clock);
+        for slot in 1..=2 * EPOCH_LENGTH + 1 {
+            let block = produce(&mut producer, &clock, &validator, slot);
+            follower.add_block(block).unwrap();
+        }
+        for chain in [&mut producer, &mut follower] {
+            for other in &others {
+                chain.register_validator(&other.public, 1000).unwrap();
+            }
+        }
+        assert_eq!(producer.finalized_height(), None);
+
+        let vote = |signer: &Keypair, height: u64| {
+            let hash = producer.block_at(height).unwrap().current_hash.clone();
+            CheckpointVote::new(signer, height, hash).unwrap()
+        };
+        let (first, first_again, second) = (vote(&validator, EPOCH_LENGTH), vote(&others[0], EPOCH_LENGTH), vote(&validator, 2 * EPOCH_LENGTH));
+
+        // Un tiers de la mise ne suffit pas ; deux tiers pour la première époque,
+        // même si la seconde, plus récente, n'a pas encore son quorum
+        producer.add_checkpoint_vote(first.clone()).unwrap();
+        producer.add_checkpoint_vote(first.clone()).unwrap();
+        producer.add_checkpoint_vote(second).unwrap();
+        assert!(producer.checkpoint_ready().is_none());
+        producer.add_checkpoint_vote(first_again.clone()).unwrap();
+        let checkpoint = producer.checkpoint_ready().unwrap();
+        assert_eq!((checkpoint.height, checkpoint.votes.len()), (EPOCH_LENGTH, 2));
+
+        let mut slot = 2 * EPOCH_LENGTH + 2;
+        while producer.select_leader(slot) != Some(validator.public) {
+            slot += 1;
+        }
+        let block = produce(&mut producer, &clock, &validator, slot);
+        assert_eq!(block.checkpoint.as_ref().map(|c| c.height), Some(EPOCH_LENGTH));
+        assert_eq!(producer.finalized_height(), Some(EPOCH_LENGTH));
+        assert_eq!(producer.add_checkpoint_vote(first_again.clone()), Err("Vote for a non-pending checkpoint"));
+
+        // Votes insuffisants, ou dont un validateur compte deux fois
+        let insufficient = Checkpoint { votes: vec![first.clone()], ..checkpoint.clone() };
+        let duplicate = Checkpoint { votes: vec![first.clone(), first], ..checkpoint };
+        assert_eq!(follower.validate_block(&with_checkpoint(block.clone(), insufficient, &validator)), Err("Checkpoint lacks two-thirds of stake"));
+        assert_eq!(follower.validate_block(&with_checkpoint(block.clone(), duplicate, &validator)), Err("Duplicate checkpoint vote"));
+        assert_eq!(follower.finalized_height(), None);
+        follower.add_block(block).unwrap();
+        assert_eq!(follower.finalized_height(), Some(EPOCH_LENGTH));
+    }
+
+    #[test]
+    fn included_transfer_cannot_be_replayed() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut producer = test_chain(&validator, &clock);
+        let mut follower = test_chain(&validator, &clock);
+        producer.allocate(&alice.public, 1_000_000);
+        follower.allocate(&alice.public, 1_000_000);
+
+        producer.create_transaction(&alice, &bob.public, 100).unwrap();
+        let transfer = producer.pending_transactions[0].clone();
+        let block = produce(&mut producer, &clock, &validator, 1);
+        assert_eq!(block.transactions, vec![transfer.clone()]);
+        follower.add_block(block).unwrap();
+        assert_eq!(follower.balance(&bob.public), 100);
+
+        // Rejouée à l'admission, sur le producteur comme sur le suiveur
+        assert_eq!(producer.add_transaction(transfer.clone()), Err("Nonce already used"));
+        assert_eq!(follower.add_transaction(transfer.clone()), Err("Nonce already used"));
+
+        // Rejouée dans un bloc
+        let next = produce(&mut producer, &clock, &validator, 2);
+        assert!(next.transactions.is_empty());
+        let replay = with_transactions(next, vec![transfer], &validator);
+        assert_eq!(follower.validate_block(&replay), Err("Nonce already used"));
+        assert_eq!(follower.add_block(replay), Err("Nonce already used"));
+        assert_eq!(follower.balance(&bob.public), 100);
+    }
+
+    #[test]
+    fn replicated_blocks_that_fail_to_execute_are_not_appended() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut primary = test_chain(&validator, &clock);
+        let mut replica = test_chain(&validator, &clock);
+        primary.allocate(&alice.public, 1_000_000);
+
+        primary.create_transaction(&alice, &bob.public, 100).unwrap();
+        let block = produce(&mut primary, &clock, &validator, 1);
+        assert_eq!(block.transactions.len(), 1);
+
+        // Le réplica ne connaît pas l'allocation d'alice : le bloc est rejeté
+        // avant d'être ajouté et aucun état n'est modifié
+        assert_eq!(replica.apply_replicated_block(block.clone()), Err("Insufficient balance"));
+        assert_eq!(replica.next_index(), 0);
+        assert_eq!(replica.balance(&bob.public), 0);
+        assert_eq!(replica.validators[&validator.public].last_validated_block, None);
+        assert!(replica.get_receipt(&block.transactions[0].hash()).is_none());
+
+        replica.allocate(&alice.public, 1_000_000);
+        replica.apply_replicated_block(block).unwrap();
+        assert_eq!(replica.next_index(), 1);
+        assert_eq!(replica.balance(&bob.public), 100);
+    }
+
+    #[test]
+    fn signatures_for_another_chain_are_rejected() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        let mut other = Blockchain::new(ChainConfig::builder().chain_id(2).build().unwrap());
+        other.set_clock(Arc::new(clock.clone()));
+        other.register_validator(&validator.public, 1000).unwrap();
+        for chain in [&mut chain, &mut other] {
+            chain.allocate(&alice.public, 1_000_000);
+        }
+
+        // Transaction signée pour le réseau 2, telle quelle ou ré-étiquetée
+        let foreign = TxBuilder::transfer().to(bob.public).amount(100).chain_id(2).nonce(0).timestamp(chain.now()).sign(&alice).unwrap();
+        assert_eq!(chain.add_transaction(foreign.clone()), Err("Transaction signed for another chain"));
+        let relabelled = Transaction { chain_id: 1, ..foreign.clone() };
+        assert!(relabelled.verify().is_err());
+        assert!(chain.add_transaction(relabelled).is_err());
+
+        // En-tête signé pour le réseau 2, tel quel ou ré-étiqueté
+        let block = produce(&mut other, &clock, &validator, 1);
+        assert_eq!(chain.validate_block(&block), Err("Block for another chain"));
+        let relabelled = Block { chain_id: 1, ..block.clone() };
+        assert!(relabelled.header().verify().is_err());
+        assert!(chain.add_block(relabelled).is_err());
+
+        // Bloc du réseau 1 portant une transaction du réseau 2
+        let block = produce(&mut chain, &clock, &validator, 1);
+        let mut follower = test_chain(&validator, &clock);
+        follower.allocate(&alice.public, 1_000_000);
+        assert_eq!(follower.validate_block(&with_transactions(block.clone(), vec![foreign], &validator)), Err("Transaction signed for another chain"));
+        follower.add_block(block).unwrap();
+        assert_eq!(follower.balance(&bob.public), 0);
+    }
+
+    #[test]
+    fn block_gas_limit_defers_transactions_and_rejects_heavy_blocks() {
+        let validator = Keypair::generate(&mut OsRng);
+        let senders: Vec<Keypair> = (0..3).map(|_| Keypair::generate(&mut OsRng)).collect();
+        let bob = Keypair::generate(&mut OsRng);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut producer = test_chain(&validator, &clock);
+        let mut follower = test_chain(&validator, &clock);
+        for sender in &senders {
+            producer.allocate(&sender.public, 1_000_000);
+            follower.allocate(&sender.public, 1_000_000);
+            submit(&mut producer, sender, TxBuilder::transfer().to(bob.public).amount(10)).unwrap();
+        }
+        let transfer_gas = gas::intrinsic_gas(&producer.pending_transactions[0]);
+        assert!(producer.pending_transactions.iter().all(|tx| gas::intrinsic_gas(tx) == transfer_gas));
+
+        // Deux transferts tiennent dans la limite du suiveur, pas trois
+        follower.governance.params.max_block_gas = 2 * transfer_gas;
+        let heavy = produce(&mut producer, &clock, &validator, 1);
+        assert_eq!(heavy.transactions.len(), 3);
+        assert_eq!(follower.validate_block(&heavy), Err("Block gas limit exceeded"));
+
+        // Un producteur soumis à la même limite reporte le troisième au bloc suivant
+        let mut bounded = test_chain(&validator, &clock);
+        bounded.governance.params.max_block_gas = 2 * transfer_gas;
+        for sender in &senders {
+            bounded.allocate(&sender.public, 1_000_000);
+            bounded.add_transaction(heavy.transactions.iter().find(|tx| tx.sender == sender.public).unwrap().clone()).unwrap();
+        }
+        let first = produce(&mut bounded, &clock, &validator, 1);
+        assert_eq!((first.transactions.len(), bounded.pending_transactions.len()), (2, 1));
+        follower.add_block(first).unwrap();
+        let second = produce(&mut bounded, &clock, &validator, 2);
+        assert_eq!(second.transactions.len(), 1);
+        follower.add_block(second).unwrap();
+        assert_eq!(follower.balance(&bob.public), 30);
+    }
+
+    #[test]
+    fn calls_out_of_gas_pay_their_whole_gas_limit() {
+        let validator = Keypair::generate(&mut OsRng);
+        let alice = Keypair::generate(&mut OsRng);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        chain.allocate(&alice.public, 1_000_000);
+
+        // Boucle sans fin
+        let code = vec![0x20, 0x00, 0x00];
+        let contract = contract_address(&alice.public, chain.now(), &code);
+        submit(&mut chain, &alice, TxBuilder::new(TransactionKind::Deploy { code }).to(contract)).unwrap();
+        produce(&mut chain, &clock, &validator, 1);
+
+        let before = chain.balance(&alice.public);
+        let hash = submit(&mut chain, &alice, TxBuilder::new(TransactionKind::Call { input: Vec::new(), gas_limit: 5_000 }).to(contract).amount(100)).unwrap();
+        let call = chain.pending_transactions[0].clone();
+        let block = produce(&mut chain, &clock, &validator, 2);
+        assert_eq!(block.transactions, vec![call.clone()]);
+
+        let receipt = chain.get_receipt(&hash).unwrap();
+        assert_eq!(receipt.status, ReceiptStatus::Failed("Out of gas"));
+        assert_eq!(receipt.gas_used, gas::intrinsic_gas(&call) + 5_000);
+        assert_eq!(receipt.fee, receipt.gas_used * DEFAULT_GAS_PRICE);
+        assert_eq!(chain.balance(&alice.public), before - receipt.fee);
+        assert_eq!(chain.balance(&contract), 0);
+    }
+
+    #[test]
+    fn transactions_in_a_format_not_yet_introduced_are_rejected() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut producer = test_chain(&validator, &clock);
+        let mut follower = test_chain(&validator, &clock);
+        for chain in [&mut producer, &mut follower] {
+            chain.allocate(&alice.public, 1_000_000);
+        }
+        let mut future = TxBuilder::transfer().to(bob.public).amount(10).chain_id(producer.config.chain_id)
+            .nonce(producer.next_nonce(&alice.public)).timestamp(producer.now()).sign(&alice).unwrap();
+        future.version = 2;
+        future.authorization = Authorization::Signature(alice.sign(&future.hash()));
+        assert_eq!(future.verify(), Ok(()));
+        assert_eq!(producer.add_transaction(future.clone()), Err("Unsupported transaction version"));
+
+        // Ni dans un bloc, ni dans un lot
+        let block = produce(&mut producer, &clock, &validator, 1);
+        assert_eq!(follower.validate_block(&with_transactions(block.clone(), vec![future.clone()], &validator)), Err("Unsupported transaction version"));
+        let mut bundled = vec![future];
+        let id = Transaction::bundle_id(&bundled);
+        bundled[0].sign_for_bundle(&alice, &id).unwrap();
+        let bundle = TxBuilder::new(TransactionKind::Bundle(bundled)).to(alice.public).chain_id(producer.config.chain_id)
+            .nonce(producer.next_nonce(&alice.public)).timestamp(producer.now()).sign(&alice).unwrap();
+        assert_eq!(follower.validate_block(&with_transactions(block.clone(), vec![bundle], &validator)), Err("Unsupported transaction version"));
+        follower.add_block(block).unwrap();
+    }
+
+    #[test]
+    fn batched_signature_verification_matches_verifying_each_transaction() {
+        let validator = Keypair::generate(&mut OsRng);
+        let senders: Vec<Keypair> = (0..8).map(|_| Keypair::generate(&mut OsRng)).collect();
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let chain = test_chain(&validator, &clock);
+        let expiry = Expiry::Timestamp(GENESIS_TIME + TRANSACTION_VALIDITY);
+        // Deux lots complets et un lot partiel
+        let transactions: Vec<Transaction> = (0..2 * SIGNATURE_BATCH_SIZE + 3).map(|i| {
+            let sender = &senders[i % senders.len()];
+            Transaction::signed(chain.config.chain_id, sender, i as u64, &validator.public, 1 + i as u64, TransactionKind::Transfer, DEFAULT_GAS_PRICE, GENESIS_TIME, expiry)
+        }).collect();
+        assert!(transactions.iter().all(|tx| tx.verify().is_ok()));
+        assert_eq!(chain.verify_transaction_signatures(&transactions), Ok(()));
+
+        // Une seule signature invalide, où qu'elle soit, fait rejeter l'ensemble
+        for position in [0, SIGNATURE_BATCH_SIZE - 1, SIGNATURE_BATCH_SIZE, transactions.len() - 1] {
+            let mut forged = transactions.clone();
+            forged[position].amount += 1;
+            assert!(forged[position].verify().is_err());
+            assert_eq!(chain.verify_transaction_signatures(&forged), Err("Invalid transaction signature"));
+        }
+        let mut swapped = transactions.clone();
+        let signature = swapped[1].authorization.clone();
+        swapped[1].authorization = std::mem::replace(&mut swapped[2 * SIGNATURE_BATCH_SIZE].authorization, signature);
+        assert_eq!(chain.verify_transaction_signatures(&swapped), Err("Invalid transaction signature"));
+    }
+
+    #[test]
+    fn standby_validators_fill_freed_seats_at_epoch_boundaries() {
+        let keys = [Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng)];
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let config = ChainConfig::builder().max_active_validators(2).build().unwrap();
+        let mut chains = [Blockchain::new(config.clone()), Blockchain::new(config)];
+        for chain in &mut chains {
+            chain.set_clock(Arc::new(clock.clone()));
+            for (key, stake) in keys.iter().zip([3000, 2000, 1000]) {
+                chain.register_validator(&key.public, stake).unwrap();
+            }
+        }
+        let standby = |chain: &Blockchain| keys.iter().map(|key| chain.validators[&key.public].standby).collect::<Vec<_>>();
+        assert_eq!(standby(&chains[0]), vec![false, false, true]);
+        assert!(matches!(chains[0].build_block(keys[2].public, 1), Err("Validator on standby")));
+
+        // Blocs produits par le leader de chaque créneau jusqu'à l'index `end` exclu,
+        // appliqués par le second nœud
+        let mut slot = 0;
+        let mut advance = |chains: &mut [Blockchain; 2], end: u64| {
+            while chains[0].next_index() < end {
+                slot += 1;
+                clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+                let Some(leader) = chains[0].select_leader(slot) else { continue };
+                let producer = keys.iter().find(|key| key.public == leader).unwrap();
+                let block = chains[0].validate_and_create_block(producer, slot).unwrap();
+                chains[1].add_block(block).unwrap();
+            }
+        };
+        advance(&mut chains, EPOCH_LENGTH);
+        assert!(chains[0].chain.iter().all(|block| block.validator_pubkey != keys[2].public));
+
+        // Un siège de plus : la réserve est promue au bloc de fin d'époque
+        for chain in &mut chains {
+            chain.governance.params.max_active_validators = 3;
+        }
+        advance(&mut chains, EPOCH_LENGTH + 1);
+        assert_eq!(standby(&chains[0]), vec![false, false, false]);
+
+        // Un seul siège : les moins dotés passent en réserve
+        for chain in &mut chains {
+            chain.governance.params.max_active_validators = 1;
+        }
+        advance(&mut chains, 2 * EPOCH_LENGTH + 1);
+        assert_eq!(standby(&chains[0]), vec![false, true, true]);
+
+        // Deux sièges : un validateur en réserve désactivé n'est pas promu
+        for chain in &mut chains {
+            chain.governance.params.max_active_validators = 2;
+            chain.validators.get_mut(&keys[1].public).unwrap().inactive_since = Some(2 * EPOCH_LENGTH);
+        }
+        advance(&mut chains, 3 * EPOCH_LENGTH + 1);
+        assert_eq!(standby(&chains[0]), vec![false, true, false]);
+        assert_eq!(standby(&chains[1]), standby(&chains[0]));
+        assert_eq!(chains[1].snapshot().hash(), chains[0].snapshot().hash());
+    }
+
+    #[test]
+    fn receipts_record_the_outcome_of_each_included_transaction() {
+        let validator = Keypair::generate(&mut OsRng);
+        let [alice, bob, carol, dave, erin] = [0; 5].map(|_| Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        for account in [&alice, &bob, &carol, &dave, &erin] {
+            chain.allocate(&account.public, 1_000_000);
+        }
+        let counter = vec![0x01, 1, b'c', 0x30, 0x01, 1, 1, 0x10, 0x03, 0, 0x01, 1, b'c', 0x31, 0x50];
+        let endless = vec![0x20, 0x00, 0x00];
+        let contracts = [&counter, &endless].map(|code| contract_address(&alice.public, chain.now(), code));
+        for (code, contract) in [counter, endless].into_iter().zip(contracts) {
+            submit(&mut chain, &alice, TxBuilder::new(TransactionKind::Deploy { code }).to(contract)).unwrap();
+        }
+        produce(&mut chain, &clock, &validator, 1);
+
+        let transfer = submit(&mut chain, &bob, TxBuilder::transfer().to(carol.public).amount(100)).unwrap();
+        let call = |contract| TxBuilder::new(TransactionKind::Call { input: Vec::new(), gas_limit: 5_000 }).to(contract);
+        let counted = submit(&mut chain, &carol, call(contracts[0])).unwrap();
+        let exhausted = submit(&mut chain, &dave, call(contracts[1])).unwrap();
+        let expiry = Expiry::Timestamp(chain.now() + TRANSACTION_VALIDITY);
+        let mut bundled = vec![Transaction::signed(chain.config.chain_id, &erin, 0, &bob.public, 50, TransactionKind::Transfer, DEFAULT_GAS_PRICE, chain.now(), expiry)];
+        let id = Transaction::bundle_id(&bundled);
+        bundled[0].sign_for_bundle(&erin, &id).unwrap();
+        let bundle = submit(&mut chain, &alice, TxBuilder::new(TransactionKind::Bundle(bundled.clone())).to(alice.public)).unwrap();
+        assert!(chain.get_receipt(&transfer).is_none());
+        assert_eq!(chain.transaction_status(&transfer), Some(TransactionStatus::Pending));
+
+        let block = produce(&mut chain, &clock, &validator, 2);
+        assert_eq!(block.transactions.len(), 4);
+        let position = |hash: &[u8]| block.transactions.iter().position(|tx| tx.hash() == hash).unwrap() as u32;
+        for hash in [&transfer, &counted, &exhausted, &bundle] {
+            let receipt = chain.get_receipt(hash).unwrap();
+            assert_eq!((&receipt.transaction_hash, receipt.block_index, receipt.position), (hash, block.index, position(hash)));
+            assert_eq!((receipt.gas_price, receipt.fee), (DEFAULT_GAS_PRICE, receipt.gas_used * DEFAULT_GAS_PRICE));
+            assert_eq!(chain.transaction_status(hash), Some(TransactionStatus::Included { height: block.index }));
+        }
+
+        let receipt = chain.get_receipt(&transfer).unwrap();
+        assert_eq!((&receipt.status, receipt.events.len()), (&ReceiptStatus::Success, 0));
+        assert_eq!(receipt.gas_used, gas::intrinsic_gas(&block.transactions[position(&transfer) as usize]));
+        let receipt = chain.get_receipt(&counted).unwrap();
+        assert_eq!(receipt.status, ReceiptStatus::Success);
+        assert!(matches!(&receipt.events[..], [ChainEvent::ContractExecuted { contract, caller, gas_used, .. }]
+            if *contract == contracts[0] && *caller == carol.public && *gas_used < receipt.gas_used));
+        let receipt = chain.get_receipt(&exhausted).unwrap();
+        assert_eq!((&receipt.status, receipt.events.len()), (&ReceiptStatus::Failed("Out of gas"), 0));
+
+        // La transaction d'un lot a son propre reçu, à la position du lot
+        let inner = chain.get_receipt(&bundled[0].hash()).unwrap();
+        assert_eq!((inner.position, &inner.status), (position(&bundle), &ReceiptStatus::Success));
+        assert_eq!(chain.find_transaction(&bundled[0].hash()).map(|(_, tx)| tx.hash()), Some(bundled[0].hash()));
+    }
+
+    #[test]
+    fn archive_nodes_serve_the_balance_after_each_block() {
+        let (validator, alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        chain.allocate(&alice.public, 1_000_000);
+        assert_eq!(chain.balance_at(&alice.public, 0), Err("Archive mode disabled"));
+        chain.enable_archive();
+
+        let mut history = Vec::new();
+        for (slot, transfer) in [(1, Some((&alice, &bob, 10_000))), (2, None), (3, Some((&bob, &alice, 3_000)))] {
+            if let Some((sender, recipient, amount)) = transfer {
+                submit(&mut chain, sender, TxBuilder::transfer().to(recipient.public).amount(amount)).unwrap();
+            }
+            produce(&mut chain, &clock, &validator, slot);
+            history.push((chain.balance(&alice.public), chain.balance(&bob.public)));
+        }
+        for (height, balances) in history.iter().enumerate() {
+            assert_eq!((chain.balance_at(&alice.public, height as u64), chain.balance_at(&bob.public, height as u64)), (Ok(balances.0), Ok(balances.1)));
+        }
+        assert_eq!(history[0].1, 10_000);
+        assert_eq!(chain.balance_at(&validator.public, 1), Ok(chain.balance(&validator.public)));
+        assert_eq!(chain.balance_at(&Keypair::generate(&mut OsRng).public, 2), Ok(0));
+        assert_eq!(chain.balance_at(&alice.public, 3), Err("Height not reached"));
+
+        // Une archive activée en cours de route ne couvre que les blocs suivants
+        chain.enable_archive();
+        assert_eq!(chain.balance_at(&alice.public, 1), Err("Height before the start of the archive"));
+        assert_eq!(chain.balance_at(&alice.public, 2), Ok(history[2].0));
+    }
+
+    #[test]
+    fn system_accounts_cannot_be_spent_from() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (holder, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        chain.allocate(&holder.public, 1_000_000);
+        assert_eq!(chain.create_transaction(&holder, &bob.public, 100), Ok(()));
+
+        // Les adresses système (séquestres, contrats) sont dérivées sans clé ; une
+        // clé connue enregistrée comme telle ne peut plus rien signer qui la débite
+        chain.system_accounts.insert(holder.public);
+        assert_eq!(chain.create_transaction(&holder, &bob.public, 100), Err("Cannot spend from a system account"));
+    }
+
+    #[test]
+    fn block_cannot_repeat_a_sender_nonce() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut producer = test_chain(&validator, &clock);
+        let mut follower = test_chain(&validator, &clock);
+        producer.allocate(&alice.public, 1_000_000);
+        follower.allocate(&alice.public, 1_000_000);
+
+        // Deux transferts différents signés avec le même nonce
+        let timestamp = GENESIS_TIME;
+        let valid_until = Expiry::Timestamp(timestamp + TRANSACTION_VALIDITY);
+        let first = Transaction::signed(1, &alice, 7, &bob.public, 100, TransactionKind::Transfer, DEFAULT_GAS_PRICE, timestamp, valid_until);
+        let second = Transaction::signed(1, &alice, 7, &bob.public, 200, TransactionKind::Transfer, DEFAULT_GAS_PRICE, timestamp, valid_until);
+        producer.add_transaction(first.clone()).unwrap();
+        assert_eq!(producer.next_nonce(&alice.public), 8);
+
+        let block = produce(&mut producer, &clock, &validator, 1);
+        let forged = with_transactions(block.clone(), vec![first, second], &validator);
+        assert_eq!(follower.validate_block(&forged), Err("Nonce already used"));
+        follower.add_block(block).unwrap();
+        assert_eq!(follower.balance(&bob.public), 100);
+    }
+
+    #[test]
+    fn replacement_conflicts_with_the_included_version() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, bob, carol) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut producer = test_chain(&validator, &clock);
+        let mut follower = test_chain(&validator, &clock);
+        producer.allocate(&alice.public, 1_000_000);
+        follower.allocate(&alice.public, 1_000_000);
+
+        // Deux paiements différents avec le même nonce, chacun connu d'un seul nœud
+        let valid_until = Expiry::Timestamp(GENESIS_TIME + TRANSACTION_VALIDITY);
+        let original = Transaction::signed(1, &alice, 0, &bob.public, 100, TransactionKind::Transfer, DEFAULT_GAS_PRICE, GENESIS_TIME, valid_until);
+        let replacement = Transaction::signed(1, &alice, 0, &carol.public, 100, TransactionKind::Transfer, DEFAULT_GAS_PRICE * 2, GENESIS_TIME, valid_until);
+        producer.add_transaction(original.clone()).unwrap();
+        follower.add_transaction(original.clone()).unwrap();
+        follower.add_transaction(replacement.clone()).unwrap();
+        assert_eq!(follower.pending_transactions, vec![replacement.clone()]);
+        assert_eq!(follower.transaction_status(&original.hash()), Some(TransactionStatus::Dropped { reason: "Replaced by a higher fee" }));
+
+        // Le nœud qui a inclus l'original l'emporte : le remplacement devient inexécutable
+        let block = produce(&mut producer, &clock, &validator, 1);
+        assert_eq!(block.transactions, vec![original]);
+        follower.add_block(block).unwrap();
+        assert!(follower.pending_transactions.is_empty());
+        assert_eq!(follower.transaction_status(&replacement.hash()), Some(TransactionStatus::Dropped { reason: "Nonce already used" }));
+        assert_eq!((follower.balance(&bob.public), follower.balance(&carol.public)), (100, 0));
+    }
+
+    #[test]
+    fn used_nonces_expire_with_their_transactions() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        chain.allocate(&alice.public, 1_000_000);
+
+        let transfer = Transaction::signed(1, &alice, 0, &bob.public, 100, TransactionKind::Transfer, DEFAULT_GAS_PRICE, GENESIS_TIME, Expiry::Height(1));
+        chain.add_transaction(transfer.clone()).unwrap();
+        produce(&mut chain, &clock, &validator, 1);
+        assert!(chain.accounts.nonces.is_used(&alice.public, 0));
+        produce(&mut chain, &clock, &validator, 2);
+        // Le bloc 2 ne peut plus inclure la transaction : son nonce est oublié
+        assert!(!chain.accounts.nonces.is_used(&alice.public, 0));
+        assert_eq!(chain.add_transaction(transfer), Err("Transaction expired"));
+    }
+
+    #[test]
+    fn transaction_lifetime_is_bounded() {
+        let alice = Keypair::generate(&mut OsRng);
+        let transfer = |valid_until| Transaction::signed(1, &alice, 0, &alice.public, 1, TransactionKind::Transfer, DEFAULT_GAS_PRICE, GENESIS_TIME, valid_until);
+        assert_eq!(transfer(Expiry::Never).check_lifetime(1, GENESIS_TIME), Err("Transaction expiry too far"));
+        assert_eq!(transfer(Expiry::Height(1 + MAX_TRANSACTION_LIFETIME_BLOCKS)).check_lifetime(1, GENESIS_TIME), Ok(()));
+        assert_eq!(transfer(Expiry::Height(2 + MAX_TRANSACTION_LIFETIME_BLOCKS)).check_lifetime(1, GENESIS_TIME), Err("Transaction expiry too far"));
+        assert_eq!(transfer(Expiry::Timestamp(GENESIS_TIME + MAX_TRANSACTION_LIFETIME)).check_lifetime(1, GENESIS_TIME), Ok(()));
+        assert_eq!(transfer(Expiry::Timestamp(GENESIS_TIME + MAX_TRANSACTION_LIFETIME + 1)).check_lifetime(1, GENESIS_TIME), Err("Transaction expiry too far"));
+    }
+
+    #[test]
+    fn block_timestamps_follow_the_clock_within_the_drift() {
+        let validator = Keypair::generate(&mut OsRng);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let follower_clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut producer = test_chain(&validator, &clock);
+        let mut follower = test_chain(&validator, &follower_clock);
+        let drift = follower.config.max_clock_drift;
+        let first = produce(&mut producer, &clock, &validator, 1);
+        assert_eq!(first.timestamp, GENESIS_TIME + 5);
+        follower_clock.set(Duration::from_secs(first.timestamp));
+        follower.add_block(first.clone()).unwrap();
+
+        let second = produce(&mut producer, &clock, &validator, 2);
+        assert_eq!(second.timestamp, GENESIS_TIME + 10);
+        let retimed = |timestamp| resigned(Block { timestamp, ..second.clone() }, &validator);
+        assert_eq!(follower.validate_block(&retimed(first.timestamp - 1)), Err("Block timestamp before its parent"));
+        assert_eq!(follower.validate_block(&retimed(first.timestamp + drift + 1)), Err("Block timestamp too far in the future"));
+        assert_eq!(follower.validate_block(&retimed(first.timestamp + drift)), Ok(()));
+
+        // Le même bloc devient acceptable quand l'horloge locale le rattrape
+        follower_clock.set(Duration::from_secs(second.timestamp - drift - 1));
+        assert_eq!(follower.validate_block(&second), Err("Block timestamp too far in the future"));
+        follower_clock.set(Duration::from_secs(second.timestamp - drift));
+        follower.add_block(second).unwrap();
+    }
+
+    #[test]
+    fn block_timestamps_must_exceed_the_median_time_past() {
+        let validator = Keypair::generate(&mut OsRng);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut producer = test_chain(&validator, &clock);
+        let mut follower = test_chain(&validator, &clock);
+        assert_eq!(producer.median_time_past(), None);
+        for slot in 1..=MEDIAN_TIME_SPAN as u64 {
+            follower.add_block(produce(&mut producer, &clock, &validator, slot)).unwrap();
+        }
+        let last = GENESIS_TIME + 5 * MEDIAN_TIME_SPAN as u64;
+        assert_eq!(producer.median_time_past(), Some(last - 25));
+
+        // Horloge arrêtée : le parent est repris tant que la médiane des onze
+        // derniers blocs le précède, puis l'horodatage passe à médiane + 1
+        let mut slot = MEDIAN_TIME_SPAN as u64;
+        while producer.median_time_past() < Some(last) {
+            slot += 1;
+            let block = producer.validate_and_create_block(&validator, slot).unwrap();
+            assert_eq!(block.timestamp, last);
+            follower.add_block(block).unwrap();
+        }
+        assert_eq!(slot, MEDIAN_TIME_SPAN as u64 + 5);
+
+        let block = producer.validate_and_create_block(&validator, slot + 1).unwrap();
+        assert_eq!(block.timestamp, last + 1);
+        let stale = resigned(Block { timestamp: last, ..block.clone() }, &validator);
+        assert_eq!(follower.validate_block(&stale), Err("Block timestamp not after the median time past"));
+        follower.add_block(block).unwrap();
+    }
+
+    #[test]
+    fn timestamp_step_is_a_chain_parameter() {
+        let validator = Keypair::generate(&mut OsRng);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let config = ChainConfig::from_toml("[consensus]\nmax_timestamp_step = 10\n").unwrap();
+        assert_eq!(config.max_timestamp_step, Some(10));
+        assert!(ChainConfig::from_toml("[consensus]\nmax_timestamp_step = 0\n").is_err());
+        let bounded = |clock: &MockClock| {
+            let mut chain = Blockchain::new(config.clone());
+            chain.set_clock(Arc::new(clock.clone()));
+            chain.register_validator(&validator.public, 1000).unwrap();
+            chain
+        };
+
+        // Un producteur sans borne horodate son bloc à l'heure locale, bien après son parent
+        let mut unbounded = test_chain(&validator, &clock);
+        let mut follower = bounded(&clock);
+        let first = produce(&mut unbounded, &clock, &validator, 1);
+        follower.add_block(first.clone()).unwrap();
+        clock.set(Duration::from_secs(first.timestamp + 60));
+        let late = unbounded.validate_and_create_block(&validator, 2).unwrap();
+        assert_eq!(follower.add_block(late), Err("Block timestamp too far after its parent"));
+
+        // Un producteur soumis à la borne ramène son horodatage à parent + 10
+        let mut producer = bounded(&clock);
+        producer.add_block(first.clone()).unwrap();
+        let clamped = producer.validate_and_create_block(&validator, 2).unwrap();
+        assert_eq!(clamped.timestamp, first.timestamp + 10);
+        follower.add_block(clamped).unwrap();
+    }
+
+    #[test]
+    fn slot_is_skipped_when_no_timestamp_is_valid() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        chain.allocate(&alice.public, 1_000_000);
+        for slot in 1..=3 {
+            produce(&mut chain, &clock, &validator, slot);
+        }
+        chain.create_transaction(&alice, &bob.public, 100).unwrap();
+
+        // L'heure locale recule de plus que la dérive tolérée : le parent impose un
+        // horodatage que les pairs jugeraient trop loin dans le futur
+        clock.set(Duration::from_secs(GENESIS_TIME - 100));
+        assert_eq!(chain.validate_and_create_block(&validator, 4).map(|_| ()), Err("Block timestamp too far in the future"));
+        assert_eq!((chain.next_index(), chain.pending_transactions.len()), (3, 1));
+
+        let block = produce(&mut chain, &clock, &validator, 5);
+        assert_eq!(block.transactions.len(), 1);
+    }
+
+    #[test]
+    fn headers_must_carry_the_current_difficulty() {
+        let validator = Keypair::generate(&mut OsRng);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut producer = test_chain(&validator, &clock);
+        let mut follower = test_chain(&validator, &clock);
+        let block = produce(&mut producer, &clock, &validator, 1);
+        assert_eq!(block.difficulty, follower.current_difficulty);
+
+        for difficulty in [0, block.difficulty + 1] {
+            let tampered = resigned(Block { difficulty, ..block.clone() }, &validator);
+            assert_eq!(tampered.header().verify(), Ok(()));
+            assert_eq!(follower.validate_block(&tampered), Err("Invalid block difficulty"));
+        }
+        follower.add_block(block).unwrap();
+    }
+
+    #[test]
+    fn median_of_peers_counts_each_peer_once() {
+        let validator = Keypair::generate(&mut OsRng);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        assert_eq!(TimestampSource::parse("median-of-peers"), Ok(TimestampSource::MedianOfPeers));
+        assert_eq!(TimestampSource::parse("local"), Ok(TimestampSource::Local));
+        assert!(TimestampSource::parse("bounded-by-parent").is_err());
+        chain.set_timestamp_source(TimestampSource::MedianOfPeers);
+
+        // Un pair qui répète une heure avancée ne déplace pas la médiane à lui seul
+        for _ in 0..3 {
+            chain.record_peer_time("tcp://a", GENESIS_TIME + 100);
+        }
+        chain.record_peer_time("tcp://b", GENESIS_TIME);
+        chain.record_peer_time("tcp://c", GENESIS_TIME);
+        assert_eq!(chain.block_timestamp(), GENESIS_TIME);
+
+        // Seule la dernière heure de chaque pair est retenue
+        chain.record_peer_time("tcp://b", GENESIS_TIME + 100);
+        chain.record_peer_time("tcp://c", GENESIS_TIME + 100);
+        assert_eq!(chain.block_timestamp(), GENESIS_TIME + 100);
+    }
+
+    // Transfert depuis un compte 2-parmi-3, signé par les membres `signers`
+    fn multisig_transfer(members: &[Keypair], signers: &[usize], nonce: u64, valid_until: Expiry) -> Transaction {
+        let policy = MultisigPolicy::new(2, members.iter().map(|member| member.public).collect()).unwrap();
+        let mut transaction = Transaction::multisig(ChainConfig::default().chain_id, policy, nonce, &members[0].public, 10, TransactionKind::Transfer, DEFAULT_GAS_PRICE, GENESIS_TIME, valid_until);
+        for &signer in signers {
+            transaction.cosign(&members[signer]).unwrap();
+        }
+        transaction
+    }
+
+    #[test]
+    fn multisig_approvals_are_collected_until_the_threshold() {
+        let validator = Keypair::generate(&mut OsRng);
+        let members: Vec<Keypair> = (0..3).map(|_| Keypair::generate(&mut OsRng)).collect();
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        let valid_until = Expiry::Timestamp(GENESIS_TIME + 60);
+        chain.allocate(&multisig_transfer(&members, &[], 0, valid_until).sender, 1_000_000);
+        let mut events = chain.subscribe();
+        let approvals_relayed = |events: &mut tokio::sync::broadcast::Receiver<ChainEvent>| {
+            std::iter::from_fn(|| events.try_recv().ok()).filter(|event| matches!(event, ChainEvent::MultisigApprovalAdded(_))).count()
+        };
+
+        // Une première signature est conservée et relayée, pas admise
+        assert_eq!(chain.submit_batch(vec![multisig_transfer(&members, &[1], 0, valid_until)]), vec![Ok(())]);
+        assert!(chain.pending_transactions.is_empty());
+        assert_eq!(approvals_relayed(&mut events), 1);
+        // La même signature reçue d'un autre pair n'est pas relayée de nouveau
+        assert_eq!(chain.add_multisig_approval(multisig_transfer(&members, &[1], 0, valid_until)), Ok(false));
+        assert_eq!(approvals_relayed(&mut events), 0);
+
+        assert_eq!(chain.add_multisig_approval(multisig_transfer(&members, &[2], 0, valid_until)), Ok(true));
+        assert_eq!(chain.pending_transactions.len(), 1);
+        assert!(chain.partial_multisig.is_empty() && chain.partial_multisig_order.is_empty());
+        assert_eq!(chain.add_multisig_approval(multisig_transfer(&members, &[0], 0, Expiry::Timestamp(GENESIS_TIME - 1))), Err("Transaction expired"));
+    }
+
+    #[test]
+    fn partial_multisig_transactions_are_bounded_and_expire() {
+        let validator = Keypair::generate(&mut OsRng);
+        let members: Vec<Keypair> = (0..3).map(|_| Keypair::generate(&mut OsRng)).collect();
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+
+        let oldest = multisig_transfer(&members, &[0], 0, Expiry::Timestamp(GENESIS_TIME + 60));
+        chain.add_multisig_approval(oldest.clone()).unwrap();
+        for nonce in 1..=MAX_PARTIAL_MULTISIG as u64 {
+            chain.add_multisig_approval(multisig_transfer(&members, &[0], nonce, Expiry::Timestamp(GENESIS_TIME + 1))).unwrap();
+        }
+        assert_eq!(chain.partial_multisig.len(), MAX_PARTIAL_MULTISIG);
+        assert!(!chain.partial_multisig.contains_key(&oldest.hash()));
+
+        // Les transactions expirées sont oubliées à la production du bloc suivant
+        let kept = multisig_transfer(&members, &[0], 0, Expiry::Timestamp(GENESIS_TIME + 60));
+        chain.add_multisig_approval(kept.clone()).unwrap();
+        produce(&mut chain, &clock, &validator, 1);
+        assert_eq!(chain.partial_multisig.keys().collect::<Vec<_>>(), vec![&kept.hash()]);
+        assert_eq!(chain.partial_multisig_order, VecDeque::from([kept.hash()]));
+    }
+
+    // Arguments de `supplyx tx` : type, fichier de clé de `sender` (écrit dans `dir`) puis le reste
+    fn tx_args(dir: &std::path::Path, sender: &Keypair, args: &[&str]) -> Vec<String> {
+        let key_file = dir.join(hex::encode(sender.public.as_bytes()));
+        std::fs::write(&key_file, hex::encode(sender.secret.as_bytes())).unwrap();
+        let mut full = vec![args[0].to_string(), key_file.to_str().unwrap().to_string()];
+        full.extend(args[1..].iter().map(|arg| arg.to_string()));
+        full
+    }
+
+    #[test]
+    fn signs_offline_transactions_from_the_command_line() {
+        let (sender, arbiter, reader) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let dir = std::env::temp_dir().join(format!("supplyx-tx-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key = |keypair: &Keypair| hex::encode(keypair.public.as_bytes());
+        let tx = |args: &[&str]| offline_transaction(&tx_args(&dir, &sender, args), GENESIS_TIME);
+
+        let escrow = tx(&["escrow", &key(&reader), "500", "120", "--arbiter", &key(&arbiter), "--nonce", "3", "--valid-from", "10"]).unwrap();
+        assert_eq!(escrow.kind, TransactionKind::Escrow { unlock_height: 120, arbiter: Some(arbiter.public) });
+        assert_eq!((escrow.sender, escrow.recipient, escrow.amount), (sender.public, reader.public, 500));
+        assert_eq!((escrow.nonce, escrow.valid_from, escrow.timestamp), (3, 10, GENESIS_TIME));
+
+        let recovery = tx(&["set-recovery", &key(&sender), "50", "1", &key(&arbiter), &key(&reader), "--nonce", "4"]).unwrap();
+        let TransactionKind::SetRecovery { guardians, challenge_period: 50 } = recovery.kind else {
+            panic!("not a recovery policy");
+        };
+        assert_eq!((guardians.threshold, guardians.members.len()), (1, 2));
+
+        // Lecteurs dans l'ordre canonique, sans doublon
+        let asset = tx(&["register-asset", &key(&sender), &key(&reader), &key(&reader), "--nonce", "5", "--tx-version", "1"]).unwrap();
+        assert_eq!(asset.kind, TransactionKind::RegisterAsset { readers: vec![reader.public] });
+        assert_eq!(asset.version, 1);
+
+        let shield = tx(&["shield", &key(&sender), "70", "--nonce", "6"]).unwrap();
+        assert_eq!((shield.kind, shield.amount), (TransactionKind::Shield, 70));
+
+        assert_eq!(tx(&["transfer", &key(&reader), "10"]).err().as_deref(), Some("Missing transaction nonce"));
+        assert!(tx(&["transfer", &key(&reader), "10", "--cid", "x", "--nonce", "1"]).unwrap_err().starts_with("Usage"));
+        assert!(tx(&["burn", &key(&reader), "10", "--nonce", "1"]).unwrap_err().starts_with("Usage"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // Transaction signée avec le prochain nonce de `signer`, admise au mempool ; retourne son hash
+    fn submit(chain: &mut Blockchain, signer: &Keypair, builder: TxBuilder) -> Result<Vec<u8>, &'static str> {
+        let transaction = builder.chain_id(chain.config.chain_id).nonce(chain.next_nonce(&signer.public)).timestamp(chain.now()).sign(signer)?;
+        let hash = transaction.hash();
+        chain.add_transaction(transaction)?;
+        Ok(hash)
+    }
+
+    #[test]
+    fn vesting_accounts_spend_only_their_vested_allocation() {
+        let (validator, member, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        let spec = format!("{}:1000:0:10:100:10", hex::encode(member.public.as_bytes()));
+        let (account, schedule) = vesting_account(&spec).unwrap();
+        assert_eq!((account, &schedule), (member.public, &VestingSchedule::new(1000, 0, 10, 100, 10).unwrap()));
+        assert!(vesting_account(&spec.replace(":10:100:", ":200:100:")).unwrap_err().contains("Invalid vesting schedule"));
+        chain.add_vesting_account(account, schedule).unwrap();
+        chain.allocate(&member.public, 10_000);
+        assert_eq!(chain.vesting_balance(&member.public), Some(VestingBalance { vested: 0, locked: 1000, available: 10_000 }));
+        assert_eq!(chain.vesting_balance(&bob.public), None);
+
+        assert_eq!(submit(&mut chain, &member, TxBuilder::transfer().to(bob.public).amount(7_500)), Err("Amount exceeds vested balance"));
+        for slot in 1..=11 {
+            produce(&mut chain, &clock, &validator, slot);
+        }
+        assert_eq!(chain.vesting_balance(&member.public), Some(VestingBalance { vested: 100, locked: 900, available: 10_100 }));
+    }
+
+    #[test]
+    fn escrow_is_released_by_its_arbiter_or_refunded() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, bob, carol) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        for keypair in [&alice, &bob, &carol] {
+            chain.allocate(&keypair.public, 1_000_000);
+        }
+
+        let escrow = submit(&mut chain, &alice, TxBuilder::escrow(100, Some(carol.public)).to(bob.public).amount(500)).unwrap();
+        let refunded = submit(&mut chain, &alice, TxBuilder::escrow(100, Some(carol.public)).to(bob.public).amount(300)).unwrap();
+        produce(&mut chain, &clock, &validator, 1);
+        assert!(chain.accounts.escrows.is_open(&escrow) && chain.accounts.escrows.is_open(&refunded));
+
+        // Avant la hauteur de déblocage, seul l'arbitre peut verser les fonds
+        assert_eq!(submit(&mut chain, &bob, TxBuilder::claim(escrow.clone()).to(bob.public).amount(500)), Err("Escrow still locked"));
+        assert_eq!(submit(&mut chain, &carol, TxBuilder::claim(escrow.clone()).to(bob.public).amount(400)), Err("Claim does not match escrow"));
+        submit(&mut chain, &carol, TxBuilder::claim(escrow.clone()).to(bob.public).amount(500)).unwrap();
+        // Le bénéficiaire peut renoncer au second séquestre
+        let refund = submit(&mut chain, &bob, TxBuilder::refund(refunded.clone()).to(alice.public).amount(300)).unwrap();
+        let (alice_before, bob_before) = (chain.balance(&alice.public), chain.balance(&bob.public));
+        produce(&mut chain, &clock, &validator, 2);
+
+        assert!(!chain.accounts.escrows.is_open(&escrow) && !chain.accounts.escrows.is_open(&refunded));
+        assert_eq!(chain.balance(&alice.public), alice_before + 300);
+        assert_eq!(chain.balance(&bob.public), bob_before + 500 - chain.get_receipt(&refund).unwrap().fee);
+        assert_eq!(submit(&mut chain, &carol, TxBuilder::claim(escrow).to(bob.public).amount(500)), Err("Unknown or settled escrow"));
+    }
+
+    #[test]
+    fn account_owner_vetoes_a_guardian_recovery() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, new_key) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let guardians = [Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng)];
+        let policy = MultisigPolicy::new(2, guardians.iter().map(|guardian| guardian.public).collect()).unwrap();
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        chain.allocate(&alice.public, 1_000_000);
+        chain.allocate(&policy.address(), 1_000_000);
+
+        submit(&mut chain, &alice, TxBuilder::set_recovery(policy.clone(), recovery::MIN_CHALLENGE_PERIOD).to(alice.public)).unwrap();
+        produce(&mut chain, &clock, &validator, 1);
+
+        // Transfert du compte vers `new_key`, signé par les deux gardiens
+        let timestamp = chain.now();
+        let nonce = chain.next_nonce(&policy.address());
+        let mut recover = Transaction::multisig(chain.config.chain_id, policy, nonce, &new_key.public, 0, TransactionKind::Recover { account: alice.public }, DEFAULT_GAS_PRICE, timestamp, Expiry::Timestamp(timestamp + TRANSACTION_VALIDITY));
+        for guardian in &guardians {
+            recover.cosign(guardian).unwrap();
+        }
+        chain.add_transaction(recover).unwrap();
+        produce(&mut chain, &clock, &validator, 2);
+        let pending = chain.pending_recovery(&alice.public).unwrap();
+        assert_eq!((pending.new_key, pending.execute_at), (new_key.public, 1 + recovery::MIN_CHALLENGE_PERIOD));
+
+        submit(&mut chain, &alice, TxBuilder::veto_recovery().to(alice.public)).unwrap();
+        produce(&mut chain, &clock, &validator, 3);
+        assert!(chain.pending_recovery(&alice.public).is_none());
+        assert!(!chain.accounts.recoveries.is_recovered(&alice.public));
+        assert_eq!(submit(&mut chain, &alice, TxBuilder::veto_recovery().to(alice.public)), Err("No recovery in progress"));
+    }
+
+    #[test]
+    fn anchored_documents_are_indexed_by_hash_and_address() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        chain.allocate(&alice.public, 1_000_000);
+
+        let document = anchors::document_hash(b"bill of lading #42");
+        let anchor = submit(&mut chain, &alice, TxBuilder::anchor(document.clone(), Some("bafybeigdyrzt".to_string())).to(bob.public)).unwrap();
+        assert_eq!(submit(&mut chain, &alice, TxBuilder::anchor(vec![0; 31], None).to(bob.public)), Err("Invalid document hash"));
+        produce(&mut chain, &clock, &validator, 1);
+
+        let anchors: Vec<Vec<u8>> = chain.document_anchors(&document).iter().map(|(_, transaction)| transaction.hash()).collect();
+        assert_eq!(anchors, vec![anchor]);
+        for address in [&alice.public, &bob.public] {
+            assert_eq!(chain.documents_by_address(address, 0, 10).items, anchors);
+        }
+    }
+
+    #[test]
+    fn bundled_transfers_are_applied_together() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, bob, carol) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        chain.allocate(&alice.public, 1_000_000);
+        chain.allocate(&carol.public, 1_000_000);
+
+        // bob ne peut payer carol qu'avec le paiement d'alice qui le précède dans le lot
+        let timestamp = chain.now();
+        let expiry = Expiry::Timestamp(timestamp + TRANSACTION_VALIDITY);
+        let mut bundled = vec![
+            Transaction::signed(chain.config.chain_id, &alice, 0, &bob.public, 5_000, TransactionKind::Transfer, DEFAULT_GAS_PRICE, timestamp, expiry),
+            Transaction::signed(chain.config.chain_id, &bob, 0, &carol.public, 1_000, TransactionKind::Transfer, DEFAULT_GAS_PRICE, timestamp, expiry),
+        ];
+        let id = Transaction::bundle_id(&bundled);
+        bundled[0].sign_for_bundle(&alice, &id).unwrap();
+        bundled[1].sign_for_bundle(&bob, &id).unwrap();
+        let bundle = Transaction::signed(chain.config.chain_id, &carol, 0, &carol.public, 0, TransactionKind::Bundle(bundled.clone()), DEFAULT_GAS_PRICE, timestamp, expiry);
+        chain.add_transaction(bundle).unwrap();
+        produce(&mut chain, &clock, &validator, 1);
+        assert_eq!(chain.balance(&bob.public), 5_000 - 1_000 - chain.get_receipt(&bundled[1].hash()).unwrap().fee);
+
+        // Une transaction signée seule ne peut pas être glissée dans un lot
+        let unbundled = Transaction::signed(chain.config.chain_id, &alice, 1, &bob.public, 500, TransactionKind::Transfer, DEFAULT_GAS_PRICE, timestamp, expiry);
+        let forged = Transaction::signed(chain.config.chain_id, &carol, 1, &carol.public, 0, TransactionKind::Bundle(vec![unbundled]), DEFAULT_GAS_PRICE, timestamp, expiry);
+        assert!(chain.add_transaction(forged).is_err());
+    }
+
+    #[test]
+    fn scheduled_transfers_wait_for_their_height() {
+        let validator = Keypair::generate(&mut OsRng);
+        let (alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        chain.allocate(&alice.public, 1_000_000);
+
+        let scheduled = submit(&mut chain, &alice, TxBuilder::transfer().to(bob.public).amount(100).valid_from(3).valid_until(Expiry::Height(4))).unwrap();
+        // Le nonce suivant tient compte de la transaction programmée
+        submit(&mut chain, &alice, TxBuilder::transfer().to(bob.public).amount(50)).unwrap();
+        for slot in 1..=3 {
+            assert!(produce(&mut chain, &clock, &validator, slot).transactions.iter().all(|transaction| transaction.hash() != scheduled));
+        }
+        assert_eq!(chain.balance(&bob.public), 50);
+        let block = produce(&mut chain, &clock, &validator, 4);
+        assert_eq!(block.index, 3);
+        assert_eq!(block.transactions.iter().map(Transaction::hash).collect::<Vec<_>>(), vec![scheduled]);
+        assert_eq!(chain.balance(&bob.public), 150);
+    }
+
+    #[test]
+    fn blocks_overtaken_while_signing_are_abandoned() {
+        let (validator, alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        chain.allocate(&alice.public, 1_000_000);
+        let transfer = submit(&mut chain, &alice, TxBuilder::transfer().to(bob.public).amount(100)).unwrap();
+
+        // Un bloc en cours de signature, devancé par un autre bloc au même parent
+        clock.set(Duration::from_secs(GENESIS_TIME + 5));
+        let slow = chain.build_block(validator.public, 1).unwrap();
+        assert!(chain.pending_transactions.is_empty());
+        clock.set(Duration::from_secs(GENESIS_TIME + 10));
+        let fast = chain.build_block(validator.public, 2).unwrap();
+        let signature = validator.sign(&fast.current_hash);
+        chain.commit_block(fast, signature).unwrap();
+        let signature = validator.sign(&slow.current_hash);
+        assert_eq!(chain.commit_block(slow, signature).err(), Some("Chain advanced while signing"));
+        assert_eq!(chain.next_index(), 1);
+
+        let pending: Vec<Vec<u8>> = chain.pending_transactions.iter().map(Transaction::hash).collect();
+        assert_eq!(pending, vec![transfer.clone()]);
+        let block = produce(&mut chain, &clock, &validator, 3);
+        assert_eq!(block.transactions.iter().map(Transaction::hash).collect::<Vec<_>>(), vec![transfer]);
+        assert_eq!(chain.balance(&bob.public), 100);
+    }
+
+    #[test]
+    fn rotated_validators_produce_with_their_new_key() {
+        let (validator, rotated) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        let rotation = rotation::KeyRotation::new(&validator, rotated.public).unwrap();
+        chain.add_governance_transaction(GovernanceTransaction::RotateKey(rotation.clone())).unwrap();
+        produce(&mut chain, &clock, &validator, 1);
+
+        assert!(!chain.validators.contains_key(&validator.public));
+        assert_eq!(chain.validators[&rotated.public].stake, 1000);
+        assert_eq!(chain.add_governance_transaction(GovernanceTransaction::RotateKey(rotation)), Err("Key rotation of unknown validator"));
+        let back = rotation::KeyRotation::new(&rotated, validator.public).unwrap();
+        assert_eq!(chain.add_governance_transaction(GovernanceTransaction::RotateKey(back)), Err("Key already used by a validator"));
+        assert_eq!(produce(&mut chain, &clock, &rotated, 2).validator_pubkey, rotated.public);
+    }
+
+    #[test]
+    fn commission_changes_apply_after_the_epoch() {
+        let validator = Keypair::generate(&mut OsRng);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = test_chain(&validator, &clock);
+        let stale = rewards::CommissionChange::new(&validator, 25, 1).unwrap();
+        assert_eq!(chain.add_governance_transaction(GovernanceTransaction::SetCommission(stale)), Err("Commission change for another epoch"));
+        let change = rewards::CommissionChange::new(&validator, 25, 0).unwrap();
+        chain.add_governance_transaction(GovernanceTransaction::SetCommission(change)).unwrap();
+        produce(&mut chain, &clock, &validator, 1);
+        assert_eq!(chain.validator_rewards(&validator.public).0, rewards::DEFAULT_COMMISSION_PERCENT);
+
+        for slot in 2..=EPOCH_LENGTH + 1 {
+            produce(&mut chain, &clock, &validator, slot);
+        }
+        assert_eq!(chain.validator_rewards(&validator.public).0, 25);
+    }
+
+    #[cfg(feature = "rpc")]
+    #[test]
+    fn signs_governance_transactions_from_the_command_line() {
+        let validator = Keypair::generate(&mut OsRng);
+        let dir = std::env::temp_dir().join(format!("supplyx-governance-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key_file = dir.join("validator.key");
+        std::fs::write(&key_file, hex::encode(validator.secret.as_bytes())).unwrap();
+        let key_file = key_file.to_str().unwrap();
+
+        let proposal = governance_transaction(&["propose", key_file, "max_block_gas", "60000000"], GENESIS_TIME).unwrap();
+        let GovernanceTransaction::Proposal(ref signed) = proposal else {
+            panic!("not a proposal");
+        };
+        assert_eq!((signed.proposer, &signed.change, signed.timestamp), (validator.public, &governance::ParameterChange::MaxBlockGas(60_000_000), GENESIS_TIME));
+        let id = hex::encode(proposal.hash());
+        let vote = governance_transaction(&["vote", key_file, &id, "no"], GENESIS_TIME).unwrap();
+        let GovernanceTransaction::Vote(ref signed) = vote else {
+            panic!("not a vote");
+        };
+        assert_eq!((&signed.proposal_id, signed.voter, signed.approve), (&proposal.hash(), validator.public, false));
+        assert_eq!((proposal.verify(), vote.verify()), (Ok(()), Ok(())));
+
+        let reason_file = dir.join("reason.txt");
+        std::fs::write(&reason_file, b"court order 2026-114").unwrap();
+        let account = Keypair::generate(&mut OsRng).public;
+        let unfreeze = governance_transaction(&["unfreeze", key_file, &hex::encode(account.as_bytes()), reason_file.to_str().unwrap()], GENESIS_TIME).unwrap();
+        let GovernanceTransaction::Freeze(ref order) = unfreeze else {
+            panic!("not a freeze order");
+        };
+        assert_eq!((order.issuer, order.account, order.freeze), (validator.public, account, false));
+        assert_eq!(order.reason_hash, anchors::document_hash(b"court order 2026-114"));
+        assert_eq!(unfreeze.verify(), Ok(()));
+
+        let responder = Keypair::generate(&mut OsRng);
+        let responder_key = hex::encode(responder.public.as_bytes());
+        let open = governance_transaction(&["incident-open", key_file, &responder_key], GENESIS_TIME).unwrap();
+        let GovernanceTransaction::IncidentOpen(ref opened) = open else {
+            panic!("not an incident");
+        };
+        assert!(opened.responders.is_member(&validator.public) && opened.responders.is_member(&responder.public));
+        let incident = hex::encode(opened.id());
+        let note_file = dir.join("note.txt");
+        std::fs::write(&note_file, b"validators restarted").unwrap();
+        let note = governance_transaction(&["incident-note", key_file, &incident, note_file.to_str().unwrap(), &responder_key], GENESIS_TIME).unwrap();
+        let GovernanceTransaction::IncidentNote(ref written) = note else {
+            panic!("not an incident note");
+        };
+        assert_eq!((&written.incident_id, written.note.open(&responder).unwrap()), (&opened.id(), b"validators restarted".to_vec()));
+        let close = governance_transaction(&["incident-close", key_file, &incident], GENESIS_TIME).unwrap();
+        assert!(matches!(close, GovernanceTransaction::IncidentClose(ref close) if close.incident_id == opened.id()));
+        assert_eq!((open.verify(), note.verify(), close.verify()), (Ok(()), Ok(()), Ok(())));
+        assert!(governance_transaction(&["incident-open", key_file], GENESIS_TIME).unwrap_err().starts_with("Usage"));
+
+        let rotate = governance_transaction(&["rotate-key", key_file, &responder_key], GENESIS_TIME).unwrap();
+        assert!(matches!(rotate, GovernanceTransaction::RotateKey(ref rotation) if (rotation.validator, rotation.new_key) == (validator.public, responder.public)));
+        let commission = governance_transaction(&["commission", key_file, "15", "3"], GENESIS_TIME).unwrap();
+        assert!(matches!(commission, GovernanceTransaction::SetCommission(ref change) if (change.validator, change.percent, change.epoch) == (validator.public, 15, 3)));
+        assert_eq!((rotate.verify(), commission.verify()), (Ok(()), Ok(())));
+        assert_eq!(governance_transaction(&["commission", key_file, "15%", "3"], GENESIS_TIME).err().as_deref(), Some("Invalid percentage: 15%"));
+
+        assert_eq!(governance_transaction(&["propose", key_file, "block_reward", "1"], GENESIS_TIME).err().as_deref(), Some("Unknown consensus parameter: block_reward"));
+        assert!(governance_transaction(&["vote", key_file, &id, "maybe"], GENESIS_TIME).unwrap_err().starts_with("Usage"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,278 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+use ed25519_dalek::PublicKey;
+
+use crate::gas::intrinsic_gas;
+use crate::{Transaction, TransactionKind};
+
+// Bornes inférieures des tranches de prix du gaz de l'histogramme
+const FEE_BUCKETS: [u64; 8] = [0, 1, 2, 5, 10, 20, 50, 100];
+
+// Bornes inférieures des tranches d'âge, en secondes
+const AGE_BUCKETS: [u64; 6] = [0, 10, 60, 300, 1800, 3600];
+
+// Nombre de transactions en attente dans une tranche [min, tranche suivante)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bucket {
+    pub min: u64,
+    pub count: usize,
+}
+
+// Vue du mempool public pour les portefeuilles et la supervision
+#[derive(Clone, Debug, PartialEq)]
+pub struct MempoolStatus {
+    pub size: usize,
+    // Taille signée cumulée des transactions
+    pub bytes: usize,
+    // Gaz maximal consommé par les transactions, exécution de contrat comprise
+    pub gas: u64,
+    pub fee_histogram: Vec<Bucket>,
+    // Âge d'après l'horodatage signé des transactions
+    pub age_histogram: Vec<Bucket>,
+    pub kinds: BTreeMap<&'static str, usize>,
+    // Nombre de blocs pleins nécessaires pour vider le mempool
+    pub congestion: f64,
+}
+
+pub fn kind_name(kind: &TransactionKind) -> &'static str {
+    match kind {
+        TransactionKind::Transfer => "transfer",
+        TransactionKind::Escrow { .. } => "escrow",
+        TransactionKind::Claim { .. } => "claim",
+        TransactionKind::Refund { .. } => "refund",
+        TransactionKind::Deploy { .. } => "deploy",
+        TransactionKind::Call { .. } => "call",
+        TransactionKind::SetRecovery { .. } => "set_recovery",
+        TransactionKind::Recover { .. } => "recover",
+        TransactionKind::VetoRecovery => "veto_recovery",
+        TransactionKind::Shield => "shield",
+        TransactionKind::ConfidentialTransfer(_) => "confidential_transfer",
+        TransactionKind::Unshield { .. } => "unshield",
+        TransactionKind::Anchor { .. } => "anchor",
+        TransactionKind::RegisterAsset { .. } => "register_asset",
+        TransactionKind::GrantAccess { .. } => "grant_access",
+        TransactionKind::RevokeAccess { .. } => "revoke_access",
+        TransactionKind::Attest { .. } => "attest",
+        TransactionKind::Bundle(_) => "bundle",
+    }
+}
+
+// Gaz maximal d'une transaction : l'exécution d'un appel est bornée par `gas_limit`,
+// un lot compte celui de ses transactions
+pub fn max_gas(transaction: &Transaction) -> u64 {
+    let execution = match &transaction.kind {
+        TransactionKind::Call { gas_limit, .. } => *gas_limit,
+        TransactionKind::Bundle(transactions) => transactions.iter().fold(0u64, |total, tx| total.saturating_add(max_gas(tx))),
+        _ => 0,
+    };
+    intrinsic_gas(transaction).saturating_add(execution)
+}
+
+fn histogram(bounds: &[u64], values: impl Iterator<Item = u64>) -> Vec<Bucket> {
+    let mut buckets: Vec<Bucket> = bounds.iter().map(|min| Bucket { min: *min, count: 0 }).collect();
+    for value in values {
+        if let Some(bucket) = buckets.iter_mut().rev().find(|bucket| value >= bucket.min) {
+            bucket.count += 1;
+        }
+    }
+    buckets
+}
+
+// État des transactions en attente à l'instant `now` (secondes), pour des blocs
+// limités à `max_block_gas` et `max_block_transactions`
+pub fn status(transactions: &[Transaction], now: u64, max_block_gas: u64, max_block_transactions: u64) -> MempoolStatus {
+    let gas = transactions.iter().fold(0u64, |total, tx| total.saturating_add(max_gas(tx)));
+    let mut kinds = BTreeMap::new();
+    for transaction in transactions {
+        *kinds.entry(kind_name(&transaction.kind)).or_insert(0) += 1;
+    }
+    // Le mempool est limité soit par le gaz, soit par le nombre de transactions par bloc
+    let by_gas = gas as f64 / max_block_gas.max(1) as f64;
+    let by_count = transactions.len() as f64 / max_block_transactions.max(1) as f64;
+    MempoolStatus {
+        size: transactions.len(),
+        bytes: transactions.iter().map(|tx| tx.signing_bytes().len()).sum(),
+        gas,
+        fee_histogram: histogram(&FEE_BUCKETS, transactions.iter().map(|tx| tx.gas_price)),
+        age_histogram: histogram(&AGE_BUCKETS, transactions.iter().map(|tx| now.saturating_sub(tx.timestamp))),
+        kinds,
+        congestion: by_gas.max(by_count),
+    }
+}
+
+// Règles d'admission au mempool propres au nœud, hors consensus
+#[derive(Clone, Debug)]
+pub struct MempoolPolicy {
+    // Frais minimaux (gaz intrinsèque × prix du gaz) par octet signé
+    pub min_fee_per_byte: u64,
+    pub max_pending_per_sender: usize,
+    // Admissions par émetteur et par minute, remplacements compris
+    pub sender_rate_per_minute: u32,
+    // Hausse minimale du prix du gaz, en pourcentage, pour remplacer une
+    // transaction en attente
+    pub replacement_bump_percent: u64,
+}
+
+impl Default for MempoolPolicy {
+    fn default() -> Self {
+        MempoolPolicy {
+            min_fee_per_byte: 1,
+            max_pending_per_sender: 256,
+            sender_rate_per_minute: 120,
+            replacement_bump_percent: 10,
+        }
+    }
+}
+
+// Nombre d'événements par clé sur une fenêtre fixe
+pub struct RateLimiter<K> {
+    limit: u32,
+    window: Duration,
+    counters: HashMap<K, (Instant, u32)>,
+}
+
+// Au-delà, les compteurs des fenêtres écoulées sont purgés
+const MAX_TRACKED_KEYS: usize = 65_536;
+
+impl<K: Hash + Eq> RateLimiter<K> {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        RateLimiter { limit, window, counters: HashMap::new() }
+    }
+
+    // Comptabilise un événement ; faux si la limite de la fenêtre est atteinte
+    pub fn allow(&mut self, key: K) -> bool {
+        if self.counters.len() >= MAX_TRACKED_KEYS {
+            let window = self.window;
+            self.counters.retain(|_, (start, _)| start.elapsed() < window);
+        }
+        let (start, count) = self.counters.entry(key).or_insert((Instant::now(), 0));
+        if start.elapsed() >= self.window {
+            *start = Instant::now();
+            *count = 0;
+        }
+        if *count >= self.limit {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+}
+
+// Garde du mempool : plancher de frais, limites par émetteur et remplacement
+// d'une transaction en attente par une transaction du même émetteur avec le
+// même nonce à un prix du gaz supérieur ; les deux ne pouvant être incluses
+// toutes les deux, seule la plus récente est conservée
+pub struct MempoolGuard {
+    policy: MempoolPolicy,
+    senders: RateLimiter<PublicKey>,
+}
+
+impl Default for MempoolGuard {
+    fn default() -> Self {
+        MempoolGuard::new(MempoolPolicy::default())
+    }
+}
+
+impl MempoolGuard {
+    pub fn new(policy: MempoolPolicy) -> Self {
+        let senders = RateLimiter::new(policy.sender_rate_per_minute, Duration::from_secs(60));
+        MempoolGuard { policy, senders }
+    }
+
+    // Retourne la position de la transaction en attente remplacée, le cas échéant
+    pub fn check(&mut self, pending: &[Transaction], transaction: &Transaction) -> Result<Option<usize>, &'static str> {
+        let fee = intrinsic_gas(transaction).saturating_mul(transaction.gas_price);
+        let bytes = transaction.signing_bytes().len() as u64;
+        if fee < self.policy.min_fee_per_byte.saturating_mul(bytes) {
+            return Err("Fee below the mempool minimum");
+        }
+
+        let replaced = pending.iter().position(|tx| tx.sender == transaction.sender && tx.nonce == transaction.nonce);
+        match replaced {
+            Some(position) => {
+                let previous = pending[position].gas_price;
+                let bumped = previous.saturating_mul(100 + self.policy.replacement_bump_percent).div_ceil(100);
+                if transaction.gas_price < bumped.max(previous.saturating_add(1)) {
+                    return Err("Replacement fee too low");
+                }
+            }
+            None => {
+                if pending.iter().filter(|tx| tx.sender == transaction.sender).count() >= self.policy.max_pending_per_sender {
+                    return Err("Too many pending transactions from sender");
+                }
+            }
+        }
+
+        if !self.senders.allow(transaction.sender) {
+            return Err("Sender rate limit exceeded");
+        }
+        Ok(replaced)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::Keypair;
+    use rand::rngs::OsRng;
+
+    use super::*;
+    use crate::Expiry;
+
+    fn transfer(sender: &Keypair, nonce: u64, amount: u64, gas_price: u64) -> Transaction {
+        Transaction::signed(1, sender, nonce, &sender.public, amount, TransactionKind::Transfer, gas_price, 1_000, Expiry::Timestamp(2_000))
+    }
+
+    #[test]
+    fn replaces_by_sender_nonce() {
+        let (alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let mut guard = MempoolGuard::default();
+        let pending = vec![transfer(&alice, 0, 100, 10), transfer(&alice, 1, 100, 10)];
+
+        // Autre paiement signé avec le même nonce : remplace le premier
+        assert_eq!(guard.check(&pending, &transfer(&alice, 0, 500, 11)), Ok(Some(0)));
+        assert_eq!(guard.check(&pending, &transfer(&alice, 1, 100, 11)), Ok(Some(1)));
+        assert_eq!(guard.check(&pending, &transfer(&alice, 0, 500, 10)), Err("Replacement fee too low"));
+        // Nonce libre, ou même nonce d'un autre émetteur : aucun remplacement
+        assert_eq!(guard.check(&pending, &transfer(&alice, 2, 100, 10)), Ok(None));
+        assert_eq!(guard.check(&pending, &transfer(&bob, 0, 100, 10)), Ok(None));
+    }
+
+    #[test]
+    fn summarizes_pending_transactions() {
+        let alice = Keypair::generate(&mut OsRng);
+        let signed = |nonce, kind, gas_price, timestamp| Transaction::signed(1, &alice, nonce, &alice.public, 1, kind, gas_price, timestamp, Expiry::Never);
+        let call = signed(2, TransactionKind::Call { input: Vec::new(), gas_limit: 5_000 }, 100, 400);
+        let pending = vec![transfer(&alice, 0, 100, 1), signed(1, TransactionKind::Transfer, 7, 1_055), call.clone()];
+
+        let status = status(&pending, 1_060, 1_000_000, 100);
+        assert_eq!(status.size, 3);
+        assert_eq!(status.bytes, pending.iter().map(|tx| tx.signing_bytes().len()).sum::<usize>());
+        // Le gaz d'un appel compte sa limite d'exécution
+        assert_eq!(max_gas(&call), intrinsic_gas(&call) + 5_000);
+        assert_eq!(status.gas, pending.iter().map(intrinsic_gas).sum::<u64>() + 5_000);
+        let counts = |buckets: &[Bucket]| buckets.iter().map(|bucket| bucket.count).collect::<Vec<_>>();
+        assert_eq!(status.fee_histogram.iter().map(|bucket| bucket.min).collect::<Vec<_>>(), FEE_BUCKETS);
+        assert_eq!(counts(&status.fee_histogram), vec![0, 1, 0, 1, 0, 0, 0, 1]);
+        // Âges : 60, 5 et 660 secondes
+        assert_eq!(counts(&status.age_histogram), vec![1, 0, 1, 1, 0, 0]);
+        assert_eq!(status.kinds, BTreeMap::from([("call", 1), ("transfer", 2)]));
+    }
+
+    #[test]
+    fn congestion_follows_the_tighter_block_limit() {
+        let alice = Keypair::generate(&mut OsRng);
+        let pending: Vec<Transaction> = (0..4).map(|nonce| transfer(&alice, nonce, 100, 1)).collect();
+        let gas = status(&pending, 1_000, u64::MAX, 1).gas;
+
+        assert_eq!(status(&pending, 1_000, gas * 2, 100).congestion, 0.5);
+        assert_eq!(status(&pending, 1_000, gas * 2, 2).congestion, 2.0);
+        assert_eq!(status(&pending, 1_000, gas / 8, 100).congestion, 8.0);
+        // Limites nulles ramenées à 1
+        assert_eq!(status(&pending, 1_000, 0, 0).congestion, gas as f64);
+
+        let empty = status(&[], 1_000, 0, 0);
+        assert_eq!((empty.size, empty.gas, empty.congestion), (0, 0, 0.0));
+        assert!(empty.kinds.is_empty() && empty.age_histogram.iter().all(|bucket| bucket.count == 0));
+    }
+}
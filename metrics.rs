@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+// Bornes supérieures des tranches des histogrammes de durée, en secondes
+const LATENCY_BUCKETS: [f64; 10] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0];
+
+pub struct Histogram {
+    // Nombre d'observations par tranche (non cumulé)
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    sum_micros: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    const fn new() -> Self {
+        Histogram {
+            buckets: [const { AtomicU64::new(0) }; LATENCY_BUCKETS.len()],
+            sum_micros: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        }
+        self.sum_micros.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// Mesures du nœud, relevées par les composants concernés ; l'état de la chaîne
+// (hauteur, mempool) et les pairs sont lus au moment de l'export
+pub struct Metrics {
+    pub block_production: Histogram,
+    pub rejected_blocks: AtomicU64,
+    pub rejected_transactions: AtomicU64,
+    pub orphan_blocks: AtomicU64,
+    pub orphan_bytes: AtomicU64,
+    pub orphans_connected: AtomicU64,
+    pub orphans_evicted: AtomicU64,
+    pub orphans_dropped: AtomicU64,
+    rpc: std::sync::Mutex<BTreeMap<&'static str, &'static Histogram>>,
+}
+
+pub static METRICS: Metrics = Metrics {
+    block_production: Histogram::new(),
+    rejected_blocks: AtomicU64::new(0),
+    rejected_transactions: AtomicU64::new(0),
+    orphan_blocks: AtomicU64::new(0),
+    orphan_bytes: AtomicU64::new(0),
+    orphans_connected: AtomicU64::new(0),
+    orphans_evicted: AtomicU64::new(0),
+    orphans_dropped: AtomicU64::new(0),
+    rpc: std::sync::Mutex::new(BTreeMap::new()),
+};
+
+impl Metrics {
+    // Durée d'un appel RPC, enregistrée à la fin de la portée du minuteur
+    pub fn rpc_timer(&self, method: &'static str) -> RpcTimer {
+        RpcTimer { method, started: Instant::now() }
+    }
+
+    fn rpc_histogram(&self, method: &'static str) -> &'static Histogram {
+        // Un histogramme par méthode, créé au premier appel et conservé
+        self.rpc.lock().unwrap().entry(method).or_insert_with(|| Box::leak(Box::new(Histogram::new())))
+    }
+}
+
+pub struct RpcTimer {
+    method: &'static str,
+    started: Instant,
+}
+
+impl Drop for RpcTimer {
+    fn drop(&mut self) {
+        METRICS.rpc_histogram(self.method).observe(self.started.elapsed());
+    }
+}
+
+#[cfg(feature = "metrics")]
+pub use exporter::serve;
+
+// Export au format texte Prometheus sur `GET /metrics`
+#[cfg(feature = "metrics")]
+mod exporter {
+    use std::fmt::Write;
+    use std::net::SocketAddr;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::Mutex;
+
+    use super::{Histogram, LATENCY_BUCKETS, METRICS};
+    use crate::gossip::Gossip;
+    use crate::node::IngestionMetrics;
+    use crate::transport::TransportMetrics;
+    use crate::Blockchain;
+
+    pub async fn serve(addr: SocketAddr, blockchain: Arc<Mutex<Blockchain>>, gossip: Gossip, ingestion: Arc<IngestionMetrics>) -> Result<(), &'static str> {
+        let listener = TcpListener::bind(addr).await.map_err(|_| "Failed to bind metrics address")?;
+        loop {
+            if let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle_connection(stream, blockchain.clone(), gossip.clone(), ingestion.clone()));
+            }
+        }
+    }
+
+    async fn handle_connection(mut stream: TcpStream, blockchain: Arc<Mutex<Blockchain>>, gossip: Gossip, ingestion: Arc<IngestionMetrics>) {
+        let mut request = [0u8; 1024];
+        let Ok(read) = stream.read(&mut request).await else {
+            return;
+        };
+        let (status, body) = if request[..read].starts_with(b"GET /metrics ") {
+            ("200 OK", render(&blockchain, &gossip, &ingestion).await)
+        } else {
+            ("404 Not Found", String::new())
+        };
+        let head = format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            status, body.len(),
+        );
+        let _ = stream.write_all(head.as_bytes()).await;
+        let _ = stream.write_all(body.as_bytes()).await;
+        let _ = stream.shutdown().await;
+    }
+
+    pub(super) async fn render(blockchain: &Mutex<Blockchain>, gossip: &Gossip, ingestion: &IngestionMetrics) -> String {
+        let (height, mempool) = {
+            let chain = blockchain.lock().await;
+            (chain.next_index().saturating_sub(1), chain.pending_transactions.len())
+        };
+        let mut out = String::new();
+        gauge(&mut out, "supplyx_chain_height", "Index of the last applied block", height);
+        gauge(&mut out, "supplyx_mempool_transactions", "Public transactions waiting for inclusion", mempool as u64);
+        gauge(&mut out, "supplyx_peers_connected", "Gossip peers currently connected", gossip.peer_count().await as u64);
+
+        let _ = writeln!(out, "# HELP supplyx_validation_failures_total Blocks and transactions rejected by validation");
+        let _ = writeln!(out, "# TYPE supplyx_validation_failures_total counter");
+        let _ = writeln!(out, "supplyx_validation_failures_total{{kind=\"block\"}} {}", METRICS.rejected_blocks.load(Ordering::Relaxed));
+        let _ = writeln!(out, "supplyx_validation_failures_total{{kind=\"transaction\"}} {}", METRICS.rejected_transactions.load(Ordering::Relaxed));
+
+        gauge(&mut out, "supplyx_orphan_blocks", "Blocks received ahead of their parent", METRICS.orphan_blocks.load(Ordering::Relaxed));
+        gauge(&mut out, "supplyx_orphan_bytes", "Encoded size of the orphan block pool", METRICS.orphan_bytes.load(Ordering::Relaxed));
+        let _ = writeln!(out, "# HELP supplyx_orphan_blocks_removed_total Blocks leaving the orphan pool by outcome");
+        let _ = writeln!(out, "# TYPE supplyx_orphan_blocks_removed_total counter");
+        let _ = writeln!(out, "supplyx_orphan_blocks_removed_total{{outcome=\"connected\"}} {}", METRICS.orphans_connected.load(Ordering::Relaxed));
+        let _ = writeln!(out, "supplyx_orphan_blocks_removed_total{{outcome=\"evicted\"}} {}", METRICS.orphans_evicted.load(Ordering::Relaxed));
+        let _ = writeln!(out, "supplyx_orphan_blocks_removed_total{{outcome=\"dropped\"}} {}", METRICS.orphans_dropped.load(Ordering::Relaxed));
+
+        let transports = gossip.transport_metrics();
+        let mut counter = |name: &str, help: &str, value: fn(&TransportMetrics) -> &AtomicU64| {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} counter", name);
+            for (scheme, metrics) in &transports {
+                let _ = writeln!(out, "{}{{transport=\"{}\"}} {}", name, scheme, value(metrics).load(Ordering::Relaxed));
+            }
+        };
+        counter("supplyx_transport_connections_total", "Gossip connections established by transport", |metrics| &metrics.connections);
+        counter("supplyx_transport_connect_failures_total", "Failed outgoing gossip connections by transport", |metrics| &metrics.connect_failures);
+        counter("supplyx_transport_frames_sent_total", "Gossip frames sent by transport", |metrics| &metrics.frames_sent);
+        counter("supplyx_transport_frames_received_total", "Gossip frames received by transport", |metrics| &metrics.frames_received);
+        counter("supplyx_transport_bytes_sent_total", "Gossip bytes sent by transport", |metrics| &metrics.bytes_sent);
+        counter("supplyx_transport_bytes_received_total", "Gossip bytes received by transport", |metrics| &metrics.bytes_received);
+
+        let _ = writeln!(out, "# HELP supplyx_ingested_transactions_total Transactions verified for mempool admission by outcome");
+        let _ = writeln!(out, "# TYPE supplyx_ingested_transactions_total counter");
+        let _ = writeln!(out, "supplyx_ingested_transactions_total{{outcome=\"admitted\"}} {}", ingestion.admitted.load(Ordering::Relaxed));
+        let _ = writeln!(out, "supplyx_ingested_transactions_total{{outcome=\"rejected\"}} {}", ingestion.rejected.load(Ordering::Relaxed));
+        let _ = writeln!(out, "# HELP supplyx_ingestion_batches_total Admission batches processed");
+        let _ = writeln!(out, "# TYPE supplyx_ingestion_batches_total counter");
+        let _ = writeln!(out, "supplyx_ingestion_batches_total {}", ingestion.batches.load(Ordering::Relaxed));
+        let _ = writeln!(out, "# HELP supplyx_ingestion_throughput Transactions processed per second of admission work");
+        let _ = writeln!(out, "# TYPE supplyx_ingestion_throughput gauge");
+        let _ = writeln!(out, "supplyx_ingestion_throughput {}", ingestion.throughput());
+
+        let _ = writeln!(out, "# HELP supplyx_block_production_seconds Time to build, validate and apply a locally produced block");
+        let _ = writeln!(out, "# TYPE supplyx_block_production_seconds histogram");
+        histogram(&mut out, "supplyx_block_production_seconds", "", &METRICS.block_production);
+
+        let _ = writeln!(out, "# HELP supplyx_rpc_duration_seconds Query RPC latency by method");
+        let _ = writeln!(out, "# TYPE supplyx_rpc_duration_seconds histogram");
+        for (method, rpc) in METRICS.rpc.lock().unwrap().iter() {
+            histogram(&mut out, "supplyx_rpc_duration_seconds", &format!("method=\"{}\",", method), rpc);
+        }
+        out
+    }
+
+    fn gauge(out: &mut String, name: &str, help: &str, value: u64) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} gauge", name);
+        let _ = writeln!(out, "{} {}", name, value);
+    }
+
+    // `labels` : étiquettes supplémentaires, chacune suivie d'une virgule
+    fn histogram(out: &mut String, name: &str, labels: &str, histogram: &Histogram) {
+        let mut cumulative = 0;
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&histogram.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{}_bucket{{{}le=\"{}\"}} {}", name, labels, bound, cumulative);
+        }
+        let count = histogram.count.load(Ordering::Relaxed);
+        let _ = writeln!(out, "{}_bucket{{{}le=\"+Inf\"}} {}", name, labels, count);
+        let labels = labels.trim_end_matches(',');
+        let sum = histogram.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, sum);
+        let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, count);
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use tokio::sync::{mpsc, Mutex};
+
+    use super::exporter::render;
+    use crate::config::ChainConfig;
+    use crate::gossip::Gossip;
+    use crate::node::IngestionMetrics;
+    use crate::reputation::Reputation;
+    use crate::transport::{default_transports, Transports};
+    use crate::Blockchain;
+
+    #[tokio::test]
+    async fn renders_ingestion_metrics() {
+        let blockchain = Arc::new(Mutex::new(Blockchain::new(ChainConfig::default())));
+        let gossip = Gossip::new(Transports::default(), mpsc::channel(1).0, blockchain.clone(), Reputation::default());
+        let ingestion = IngestionMetrics::default();
+        ingestion.batches.store(2, Ordering::Relaxed);
+        ingestion.admitted.store(30, Ordering::Relaxed);
+        ingestion.rejected.store(10, Ordering::Relaxed);
+        ingestion.busy_micros.store(500_000, Ordering::Relaxed);
+
+        let out = render(&blockchain, &gossip, &ingestion).await;
+        for line in [
+            "supplyx_ingested_transactions_total{outcome=\"admitted\"} 30",
+            "supplyx_ingested_transactions_total{outcome=\"rejected\"} 10",
+            "supplyx_ingestion_batches_total 2",
+            "supplyx_ingestion_throughput 80",
+        ] {
+            assert!(out.lines().any(|rendered| rendered == line), "{} missing", line);
+        }
+    }
+
+    #[tokio::test]
+    async fn renders_transport_metrics() {
+        let blockchain = Arc::new(Mutex::new(Blockchain::new(ChainConfig::default())));
+        let transports = default_transports();
+        let gossip = Gossip::new(transports.clone(), mpsc::channel(1).0, blockchain.clone(), Reputation::default());
+
+        // Connexion refusée, comptée comme un échec du transport essayé
+        assert!(gossip.connect("tcp://127.0.0.1:1").await.is_err());
+        transports.metrics()["ws"].bytes_sent.fetch_add(512, Ordering::Relaxed);
+
+        let out = render(&blockchain, &gossip, &IngestionMetrics::default()).await;
+        for line in [
+            "supplyx_transport_connect_failures_total{transport=\"tcp\"} 1",
+            "supplyx_transport_connections_total{transport=\"tcp\"} 0",
+            "supplyx_transport_bytes_sent_total{transport=\"ws\"} 512",
+        ] {
+            assert!(out.lines().any(|rendered| rendered == line), "{} missing", line);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, Verifier};
+
+use crate::address::derive_address;
+use crate::encoding::{Decoder, Encoder, MULTISIG_DOMAIN};
+
+// Compte M-parmi-N : son adresse est dérivée du seuil et des clés membres,
+// ses transactions exigent `threshold` signatures distinctes de membres
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultisigPolicy {
+    pub threshold: u8,
+    // Triés par clé publique, sans doublon
+    pub members: Vec<PublicKey>,
+}
+
+impl MultisigPolicy {
+    pub fn new(threshold: u8, mut members: Vec<PublicKey>) -> Result<Self, &'static str> {
+        members.sort_by_key(|key| key.to_bytes());
+        members.dedup();
+        let policy = MultisigPolicy { threshold, members };
+        policy.check()?;
+        Ok(policy)
+    }
+
+    fn check(&self) -> Result<(), &'static str> {
+        if self.members.len() > u8::MAX as usize {
+            return Err("Too many multisig members");
+        }
+        if self.threshold == 0 || self.threshold as usize > self.members.len() {
+            return Err("Invalid multisig threshold");
+        }
+        if self.members.windows(2).any(|pair| pair[0].to_bytes() >= pair[1].to_bytes()) {
+            return Err("Multisig members not in canonical order");
+        }
+        Ok(())
+    }
+
+    pub fn address(&self) -> PublicKey {
+        let mut encoder = Encoder::new(MULTISIG_DOMAIN);
+        self.encode_into(&mut encoder);
+        derive_address(&encoder.finish())
+    }
+
+    // Signature partielle d'un membre : (index du membre, signature)
+    pub fn approve(&self, keypair: &Keypair, message: &[u8]) -> Result<(u8, Signature), &'static str> {
+        let index = self.members.iter().position(|member| *member == keypair.public).ok_or("Not a multisig member")?;
+        Ok((index as u8, keypair.sign(message)))
+    }
+
+    // Signatures valides de membres distincts ; le seuil n'est exigé que si `complete`
+    pub fn verify(&self, message: &[u8], signatures: &[(u8, Signature)], complete: bool) -> Result<(), &'static str> {
+        self.check()?;
+        let mut signers = HashSet::new();
+        for (index, signature) in signatures {
+            let member = self.members.get(*index as usize).ok_or("Unknown multisig member")?;
+            if !signers.insert(*index) {
+                return Err("Duplicate multisig signature");
+            }
+            member.verify(message, signature).map_err(|_| "Invalid multisig signature")?;
+        }
+        if complete && signers.len() < self.threshold as usize {
+            return Err("Multisig threshold not reached");
+        }
+        Ok(())
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        encoder.put_u8(self.threshold);
+        encoder.put_u8(self.members.len() as u8);
+        for member in &self.members {
+            encoder.put_public_key(member);
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<MultisigPolicy, &'static str> {
+        let threshold = decoder.get_u8()?;
+        let count = decoder.get_u8()?;
+        let mut members = Vec::new();
+        for _ in 0..count {
+            members.push(decoder.get_public_key()?);
+        }
+        let policy = MultisigPolicy { threshold, members };
+        policy.check()?;
+        Ok(policy)
+    }
+}
+
+// Ajoute à `signatures` celles de `other` qui n'y figurent pas encore
+pub fn merge_signatures(signatures: &mut Vec<(u8, Signature)>, other: &[(u8, Signature)]) {
+    for (index, signature) in other {
+        if !signatures.iter().any(|(existing, _)| existing == index) {
+            signatures.push((*index, *signature));
+        }
+    }
+    signatures.sort_by_key(|(index, _)| *index);
+}
@@ -0,0 +1,700 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use rayon::prelude::*;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::Instant;
+use tracing::{debug, info, info_span, warn};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::admission::AdmissionGate;
+use crate::beacon::{epoch_of, BeaconCommit, BeaconReveal, REVEAL_OFFSET};
+use crate::checkpoint::CheckpointVote;
+use crate::clock::{Clock, SystemClock};
+use crate::governance::GovernanceTransaction;
+use crate::light::LightClient;
+use crate::liveness::Reactivation;
+use crate::metrics::METRICS;
+use crate::orphans::{OrphanLimits, OrphanPool};
+use crate::plugins::PluginHost;
+use crate::privacy::PrivateTransaction;
+use crate::signer::Signer;
+use crate::snapshot::SnapshotSource;
+use crate::telemetry::{self, TraceContext};
+use crate::{Block, Blockchain, Transaction, EPOCH_LENGTH};
+
+// Taille de la file des événements entrants (réseau / RPC)
+const EVENT_QUEUE_SIZE: usize = 1024;
+
+// Nombre de blocs demandés par requête lors de la synchronisation rapide
+const SYNC_BATCH_SIZE: usize = 128;
+
+pub enum NodeEvent {
+    Transaction { transaction: Transaction, trace: TraceContext },
+    Block(Block),
+    // Heure (secondes Unix) annoncée par un pair, identifié par son adresse
+    PeerTime { peer: String, time: u64 },
+    CheckpointVote(CheckpointVote),
+    PrivateTransaction(PrivateTransaction),
+    Governance(GovernanceTransaction),
+    // Transaction multisig portant une partie des signatures requises
+    MultisigApproval(Transaction),
+}
+
+#[derive(Clone)]
+pub struct NodeConfig {
+    pub slot_interval: Duration,
+    // Un lot de transactions est admis dès qu'il atteint cette taille...
+    pub max_batch_size: usize,
+    // ...ou dès que sa première transaction a attendu ce délai
+    pub max_batch_latency: Duration,
+    // Contrôle externe des émetteurs avant l'admission dans le mempool
+    pub admission: Option<Arc<AdmissionGate>>,
+    // Bornes des blocs reçus avant leur parent
+    pub orphan_limits: OrphanLimits,
+    // Filtres d'admission des plugins, appliqués après la vérification des signatures
+    pub plugins: Arc<PluginHost>,
+    // Horloge des créneaux, installée aussi dans la chaîne du nœud
+    pub clock: Arc<dyn Clock>,
+    // Production de blocs sans transaction (désactivée sur un réseau de développement)
+    pub empty_blocks: bool,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        NodeConfig {
+            slot_interval: Duration::from_secs(5),
+            max_batch_size: 512,
+            max_batch_latency: Duration::from_millis(20),
+            admission: None,
+            orphan_limits: OrphanLimits::default(),
+            plugins: Arc::new(PluginHost::default()),
+            clock: Arc::new(SystemClock),
+            empty_blocks: true,
+        }
+    }
+}
+
+// Compteurs de la file d'admission dans le mempool
+#[derive(Default)]
+pub struct IngestionMetrics {
+    pub batches: AtomicU64,
+    pub admitted: AtomicU64,
+    pub rejected: AtomicU64,
+    pub busy_micros: AtomicU64,
+}
+
+impl IngestionMetrics {
+    // Transactions traitées par seconde de travail d'admission
+    #[cfg(feature = "metrics")]
+    pub fn throughput(&self) -> f64 {
+        let processed = self.admitted.load(Ordering::Relaxed) + self.rejected.load(Ordering::Relaxed);
+        let busy_secs = self.busy_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        if busy_secs == 0.0 {
+            0.0
+        } else {
+            processed as f64 / busy_secs
+        }
+    }
+}
+
+// Contexte de trace de chaque transaction admise, par hash, jusqu'à son
+// inclusion ; la diffusion le transmet aux pairs avec la transaction
+pub type PendingTraces = Arc<std::sync::Mutex<HashMap<Vec<u8>, opentelemetry::Context>>>;
+
+pub struct Node {
+    blockchain: Arc<Mutex<Blockchain>>,
+    signer: Arc<dyn Signer>,
+    config: NodeConfig,
+    events: mpsc::Receiver<NodeEvent>,
+    traces: PendingTraces,
+    metrics: Arc<IngestionMetrics>,
+}
+
+impl Node {
+    // Retourne le nœud et l'émetteur à utiliser par le réseau et le RPC
+    pub fn new(mut blockchain: Blockchain, signer: Arc<dyn Signer>, config: NodeConfig) -> (Self, mpsc::Sender<NodeEvent>) {
+        let (sender, events) = mpsc::channel(EVENT_QUEUE_SIZE);
+        blockchain.set_clock(config.clock.clone());
+        let node = Node {
+            blockchain: Arc::new(Mutex::new(blockchain)),
+            signer,
+            config,
+            events,
+            traces: PendingTraces::default(),
+            metrics: Arc::new(IngestionMetrics::default()),
+        };
+        (node, sender)
+    }
+
+    pub fn blockchain(&self) -> Arc<Mutex<Blockchain>> {
+        self.blockchain.clone()
+    }
+
+    pub fn traces(&self) -> PendingTraces {
+        self.traces.clone()
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn ingestion_metrics(&self) -> Arc<IngestionMetrics> {
+        self.metrics.clone()
+    }
+
+    // Synchronisation à partir du dernier instantané d'un pair : les en-têtes sont
+    // vérifiés depuis la genèse avec l'ensemble de validateurs local, puis seuls
+    // les blocs postérieurs à l'instantané sont téléchargés et rejoués.
+    // Retourne l'index du prochain bloc attendu.
+    pub async fn fast_sync(&self, source: &impl SnapshotSource) -> Result<u64, &'static str> {
+        let mut chain = self.blockchain.lock().await;
+        let snapshot = source.latest_snapshot().ok_or("No snapshot available")?;
+
+        let mut light_client = LightClient::new(chain.validators.clone(), chain.config.clone());
+        light_client.sync(source)?;
+
+        let committing_header = light_client.header(snapshot.height + 1).ok_or("Snapshot not committed in a header")?;
+        if committing_header.snapshot_hash != snapshot.hash() {
+            return Err("Snapshot hash mismatch");
+        }
+        let base = light_client.header(snapshot.height).ok_or("Missing snapshot header")?.clone();
+        chain.restore_snapshot(snapshot, base)?;
+
+        loop {
+            let blocks = source.blocks(chain.next_index(), SYNC_BATCH_SIZE);
+            if blocks.is_empty() {
+                return Ok(chain.next_index());
+            }
+            for block in blocks {
+                chain.add_block(block)?;
+            }
+        }
+    }
+
+    pub async fn run(self) {
+        let Node { blockchain, signer, config, events, traces, metrics } = self;
+
+        // Traitement des événements entrants en parallèle de la production
+        tokio::spawn(Self::process_events(
+            blockchain.clone(),
+            signer.clone(),
+            traces.clone(),
+            metrics,
+            config.clone(),
+            events,
+        ));
+
+        let mut ticker = tokio::time::interval(config.slot_interval);
+        loop {
+            ticker.tick().await;
+            let slot = current_slot(&*config.clock, config.slot_interval);
+            Self::produce_block(&blockchain, &traces, &signer, slot, config.empty_blocks).await;
+        }
+    }
+
+    // Regroupe les transactions entrantes en lots ; les autres événements
+    // vident d'abord le lot en cours pour conserver l'ordre d'arrivée
+    async fn process_events(
+        blockchain: Arc<Mutex<Blockchain>>,
+        signer: Arc<dyn Signer>,
+        traces: PendingTraces,
+        metrics: Arc<IngestionMetrics>,
+        config: NodeConfig,
+        mut events: mpsc::Receiver<NodeEvent>,
+    ) {
+        let NodeConfig { max_batch_size, max_batch_latency, admission, orphan_limits, plugins, .. } = config;
+        let mut orphans = OrphanPool::new(orphan_limits);
+        let mut batch: Vec<(Transaction, TraceContext)> = Vec::new();
+        let mut batch_deadline = Instant::now();
+
+        loop {
+            let event = if batch.is_empty() {
+                events.recv().await
+            } else {
+                match tokio::time::timeout_at(batch_deadline, events.recv()).await {
+                    Ok(event) => event,
+                    Err(_) => {
+                        Self::admit_batch(&blockchain, &traces, &metrics, admission.as_deref(), &plugins, std::mem::take(&mut batch)).await;
+                        continue;
+                    }
+                }
+            };
+
+            let Some(event) = event else {
+                break;
+            };
+            match event {
+                NodeEvent::Transaction { transaction, trace } => {
+                    if batch.is_empty() {
+                        batch_deadline = Instant::now() + max_batch_latency;
+                    }
+                    batch.push((transaction, trace));
+                    if batch.len() >= max_batch_size {
+                        Self::admit_batch(&blockchain, &traces, &metrics, admission.as_deref(), &plugins, std::mem::take(&mut batch)).await;
+                    }
+                }
+                NodeEvent::Block(block) => {
+                    if !batch.is_empty() {
+                        Self::admit_batch(&blockchain, &traces, &metrics, admission.as_deref(), &plugins, std::mem::take(&mut batch)).await;
+                    }
+                    Self::apply_block(&blockchain, &*signer, &traces, &mut orphans, block).await;
+                }
+                NodeEvent::PeerTime { peer, time } => {
+                    blockchain.lock().await.record_peer_time(&peer, time);
+                }
+                NodeEvent::CheckpointVote(vote) => {
+                    if let Err(e) = blockchain.lock().await.add_checkpoint_vote(vote) {
+                        warn!(error = e, "Rejected checkpoint vote");
+                    }
+                }
+                NodeEvent::PrivateTransaction(transaction) => {
+                    if let Err(e) = blockchain.lock().await.add_private_transaction(transaction) {
+                        warn!(error = e, "Rejected private transaction");
+                    }
+                }
+                NodeEvent::Governance(transaction) => {
+                    if let Err(e) = blockchain.lock().await.add_governance_transaction(transaction) {
+                        warn!(error = e, "Rejected governance transaction");
+                    }
+                }
+                NodeEvent::MultisigApproval(transaction) => {
+                    if let Some(gate) = &admission {
+                        if let Err(e) = gate.check(&transaction.sender).await {
+                            warn!(error = e, "Rejected multisig approval");
+                            continue;
+                        }
+                    }
+                    if let Err(e) = blockchain.lock().await.add_multisig_approval(transaction) {
+                        warn!(error = e, "Rejected multisig approval");
+                    }
+                }
+            }
+        }
+
+        if !batch.is_empty() {
+            Self::admit_batch(&blockchain, &traces, &metrics, admission.as_deref(), &plugins, batch).await;
+        }
+    }
+
+    // Vérification parallèle et contrôle d'admission hors verrou, puis insertion
+    // sous une seule prise du verrou
+    async fn admit_batch(
+        blockchain: &Mutex<Blockchain>,
+        traces: &PendingTraces,
+        metrics: &IngestionMetrics,
+        admission: Option<&AdmissionGate>,
+        plugins: &Arc<PluginHost>,
+        batch: Vec<(Transaction, TraceContext)>,
+    ) {
+        let started = Instant::now();
+        let plugins = plugins.clone();
+        let verified = tokio::task::spawn_blocking(move || {
+            batch.into_par_iter()
+                .map(|(transaction, trace)| {
+                    let verdict = transaction.verify().and_then(|()| plugins.admit(&transaction));
+                    (transaction, trace, verdict)
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap();
+
+        let admitted = match admission {
+            Some(gate) => {
+                let senders = verified.iter().filter(|(_, _, verdict)| verdict.is_ok()).map(|(tx, _, _)| tx.sender);
+                gate.check_senders(senders).await
+            }
+            None => HashMap::new(),
+        };
+
+        let mut chain = blockchain.lock().await;
+        let mut traces = traces.lock().unwrap();
+        for (transaction, trace, verdict) in verified {
+            let hash = transaction.hash();
+            let span = info_span!("mempool.admit", hash = %hex::encode(&hash));
+            span.set_parent(telemetry::extract(&trace));
+            let _entered = span.enter();
+
+            let verdict = verdict.and_then(|()| admitted.get(&transaction.sender).copied().unwrap_or(Ok(())));
+            match verdict.and_then(|()| chain.admit_transaction(transaction)) {
+                Ok(()) => {
+                    debug!("Transaction admitted");
+                    traces.insert(hash, span.context());
+                    metrics.admitted.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    warn!(error = e, "Rejected transaction");
+                    metrics.rejected.fetch_add(1, Ordering::Relaxed);
+                    METRICS.rejected_transactions.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+
+        metrics.batches.fetch_add(1, Ordering::Relaxed);
+        metrics.busy_micros.fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+    }
+
+    // Un bloc en avance sur la tête attend son parent dans le pool des orphelins ;
+    // chaque bloc appliqué y rattache ses descendants
+    async fn apply_block(blockchain: &Mutex<Blockchain>, signer: &dyn Signer, traces: &PendingTraces, orphans: &mut OrphanPool, block: Block) {
+        let mut chain = blockchain.lock().await;
+        if block.index > chain.next_index() {
+            // Seuls les blocs signés par un validateur connu sont conservés
+            let verdict = match chain.validators.contains_key(&block.validator_pubkey) {
+                true => block.header().verify(),
+                false => Err("Unknown validator"),
+            };
+            match verdict {
+                Ok(()) => {
+                    if orphans.insert(block) {
+                        debug!(orphans = orphans.len(), "Orphan block buffered");
+                    }
+                }
+                Err(e) => {
+                    warn!(error = e, "Rejected orphan block");
+                    METRICS.rejected_blocks.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            return;
+        }
+
+        let mut pending = vec![block];
+        while let Some(block) = pending.pop() {
+            let _entered = info_span!("block.apply", index = block.index).entered();
+            let included = block.transactions.clone();
+            let (index, hash) = (block.index, block.current_hash.clone());
+            match chain.add_block(block) {
+                Ok(()) => {
+                    info!(hash = %hex::encode(&hash), transactions = included.len(), "Block applied");
+                    Self::record_inclusion(&chain, traces, &included, "block.apply");
+                    Self::vote_checkpoint(&mut chain, signer, index, hash.clone());
+                    Self::contribute_beacon(&mut chain, signer);
+                    Self::reactivate(&mut chain, signer);
+                    pending.extend(orphans.take_children(&hash));
+                }
+                Err(e) => {
+                    warn!(error = e, "Rejected block");
+                    METRICS.rejected_blocks.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        orphans.prune(chain.next_index());
+    }
+
+    // Un validateur local signe chaque bloc de checkpoint dès qu'il est appliqué
+    pub fn vote_checkpoint(chain: &mut Blockchain, signer: &dyn Signer, index: u64, block_hash: Vec<u8>) {
+        if !chain.is_checkpoint_height(index) || !chain.validators.contains_key(&signer.public_key()) {
+            return;
+        }
+        let vote = CheckpointVote::new(signer, index, block_hash);
+        if let Err(e) = vote.and_then(|vote| chain.add_checkpoint_vote(vote)) {
+            warn!(error = e, "Checkpoint vote failed");
+        }
+    }
+
+    // Un validateur local s'engage au début de chaque époque et révèle son secret
+    // au début de la phase de révélation
+    pub fn contribute_beacon(chain: &mut Blockchain, signer: &dyn Signer) {
+        let next = chain.next_index();
+        if !chain.validators.contains_key(&signer.public_key()) {
+            return;
+        }
+        let transaction = match next % EPOCH_LENGTH {
+            0 => BeaconCommit::new(signer, epoch_of(next)).map(GovernanceTransaction::BeaconCommit),
+            REVEAL_OFFSET if chain.beacon.has_committed(&signer.public_key()) => {
+                BeaconReveal::new(signer, epoch_of(next)).map(GovernanceTransaction::BeaconReveal)
+            }
+            _ => return,
+        };
+        if let Err(e) = transaction.and_then(|transaction| chain.add_governance_transaction(transaction)) {
+            warn!(error = e, "Beacon contribution failed");
+        }
+    }
+
+    // Un validateur local désactivé pour créneaux manqués demande sa réactivation
+    // dès qu'il applique de nouveau des blocs
+    pub fn reactivate(chain: &mut Blockchain, signer: &dyn Signer) {
+        let Some(inactive_since) = chain.validators.get(&signer.public_key()).and_then(|v| v.inactive_since) else {
+            return;
+        };
+        let pending = chain.pending_governance_transactions.iter()
+            .any(|tx| matches!(tx, GovernanceTransaction::Reactivation(r) if r.validator == signer.public_key()));
+        if pending {
+            return;
+        }
+        let transaction = Reactivation::new(signer, inactive_since).map(GovernanceTransaction::Reactivation);
+        match transaction.and_then(|transaction| chain.add_governance_transaction(transaction)) {
+            Ok(()) => info!(inactive_since, "Reactivation requested"),
+            Err(e) => warn!(error = e, "Reactivation failed"),
+        }
+    }
+
+    // Production uniquement si la clé locale est le validateur choisi pour ce créneau.
+    // Le bloc est signé hors du verrou de la chaîne : un signataire distant lent ne
+    // bloque ni l'admission, ni la diffusion, ni le RPC
+    async fn produce_block(blockchain: &Mutex<Blockchain>, traces: &PendingTraces, signer: &Arc<dyn Signer>, slot: u64, empty_blocks: bool) {
+        let started = Instant::now();
+        let template = {
+            let mut chain = blockchain.lock().await;
+            if chain.select_leader(slot) != Some(signer.public_key()) {
+                return;
+            }
+            if !empty_blocks && chain.pending_transactions.is_empty() {
+                return;
+            }
+            let _entered = info_span!("block.build", slot).entered();
+            match chain.build_block(signer.public_key(), slot) {
+                Ok(template) => template,
+                Err(e) => {
+                    warn!(error = e, "Block production failed");
+                    return;
+                }
+            }
+        };
+
+        let (block_signer, hash) = (signer.clone(), template.current_hash.clone());
+        let signature = tokio::task::spawn_blocking(move || block_signer.sign(&hash)).await
+            .unwrap_or(Err("Signer task failed"));
+        let mut chain = blockchain.lock().await;
+        let produced = match signature {
+            Ok(signature) => chain.commit_block(template, signature),
+            Err(e) => {
+                chain.abandon_block(template);
+                Err(e)
+            }
+        };
+        match produced {
+            Ok(block) => {
+                METRICS.block_production.observe(started.elapsed());
+                Self::record_inclusion(&chain, traces, &block.transactions, "block.build");
+                Self::vote_checkpoint(&mut chain, &**signer, block.index, block.current_hash.clone());
+                Self::contribute_beacon(&mut chain, &**signer);
+                info!(
+                    index = block.index,
+                    hash = %hex::encode(&block.current_hash),
+                    transactions = block.transactions.len(),
+                    "Block produced",
+                );
+            }
+            Err(e) => warn!(error = e, "Block production failed"),
+        }
+    }
+
+    // Ajoute un span d'inclusion dans la trace de chaque transaction
+    fn record_inclusion(chain: &Blockchain, traces: &PendingTraces, transactions: &[Transaction], stage: &'static str) {
+        let mut traces = traces.lock().unwrap();
+        for tx in transactions {
+            if let Some(context) = traces.remove(&tx.hash()) {
+                let span = info_span!("transaction.included", stage, height = chain.next_index() - 1);
+                span.set_parent(context);
+                span.in_scope(|| {});
+            }
+        }
+    }
+}
+
+fn current_slot(clock: &dyn Clock, slot_interval: Duration) -> u64 {
+    (clock.now().as_millis() / slot_interval.as_millis().max(1)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::devnet::{Devnet, DEV_SLOT_MILLIS};
+    use crate::liveness::MAX_MISSED_SLOTS;
+    use crate::txbuilder::TxBuilder;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        let public = PublicKey::from(&secret);
+        Keypair { secret, public }
+    }
+
+    // Chaîne d'un nœud, à l'heure de `clock`, avec les validateurs `seeds`
+    fn test_chain(seeds: &[u8], clock: &MockClock) -> Arc<Mutex<Blockchain>> {
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        for &seed in seeds {
+            chain.register_validator(&keypair(seed).public, 1000).unwrap();
+        }
+        Arc::new(Mutex::new(chain))
+    }
+
+    #[tokio::test]
+    async fn each_slot_is_produced_by_the_leader_every_node_selects() {
+        let seeds = [1, 2];
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let chains: Vec<Arc<Mutex<Blockchain>>> = seeds.iter().map(|_| test_chain(&seeds, &clock)).collect();
+        let signers: Vec<Arc<dyn Signer>> = seeds.iter().map(|&seed| Arc::new(keypair(seed)) as Arc<dyn Signer>).collect();
+        let interval = Duration::from_secs(5);
+        let traces = PendingTraces::default();
+
+        let mut produced = [0; 2];
+        for step in 1..=20 {
+            clock.set(Duration::from_secs(GENESIS_TIME) + interval * step);
+            let slot = current_slot(&clock, interval);
+            assert_eq!(slot, GENESIS_TIME / 5 + step as u64);
+
+            let mut leaders = Vec::new();
+            for chain in &chains {
+                leaders.push(chain.lock().await.select_leader(slot));
+            }
+            assert_eq!(leaders[0], leaders[1]);
+
+            for (chain, signer) in chains.iter().zip(&signers) {
+                Node::produce_block(chain, &traces, signer, slot, true).await;
+            }
+            let heights = [chains[0].lock().await.next_index(), chains[1].lock().await.next_index()];
+            let Some(leader) = leaders[0] else {
+                assert_eq!(heights[0], heights[1]);
+                continue;
+            };
+            let producer = seeds.iter().position(|&seed| keypair(seed).public == leader).unwrap();
+            let follower = 1 - producer;
+            assert_eq!(heights[producer], heights[follower] + 1);
+            produced[producer] += 1;
+
+            let block = chains[producer].lock().await.chain.last().unwrap().clone();
+            assert_eq!((block.slot, block.validator_pubkey), (slot, leader));
+            chains[follower].lock().await.add_block(block).unwrap();
+        }
+        assert!(produced.iter().all(|&count| count > 0), "{:?}", produced);
+        assert_eq!(chains[0].lock().await.last_hash(), chains[1].lock().await.last_hash());
+    }
+
+    #[tokio::test]
+    async fn slots_are_not_reused() {
+        let validator = keypair(1);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME + 10));
+        let chain = test_chain(&[1], &clock);
+        let signer: Arc<dyn Signer> = Arc::new(keypair(1));
+        Node::produce_block(&chain, &PendingTraces::default(), &signer, 2, true).await;
+
+        let mut chain = chain.lock().await;
+        assert_eq!(chain.next_index(), 1);
+        for slot in [1, 2] {
+            assert_eq!(chain.build_block(validator.public, slot).map(|_| ()), Err("Slot already used"));
+        }
+        assert_eq!(chain.next_index(), 1);
+        assert!(chain.build_block(validator.public, 3).is_ok());
+    }
+
+    #[test]
+    fn missed_slots_deactivate_a_validator_until_it_reactivates() {
+        let (producer, absent) = (keypair(1), keypair(2));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        // Score minimal abaissé : le validateur absent reste choisi, et pénalisé,
+        // jusqu'à sa désactivation
+        let mut chain = Blockchain::new(ChainConfig::builder().min_contribution_score(0.1).build().unwrap());
+        chain.set_clock(Arc::new(clock.clone()));
+        for validator in [&producer, &absent] {
+            chain.register_validator(&validator.public, 1000).unwrap();
+        }
+        let produce_next = |chain: &mut Blockchain, slot: &mut u64| loop {
+            *slot += 1;
+            clock.set(Duration::from_secs(GENESIS_TIME + *slot * 5));
+            if chain.select_leader(*slot) == Some(producer.public) {
+                return chain.validate_and_create_block(&producer, *slot).unwrap();
+            }
+        };
+
+        // Les créneaux attribués au validateur absent sont comptés à chaque bloc
+        let mut slot = 0;
+        while chain.validators[&absent.public].inactive_since.is_none() {
+            assert!(slot < 500);
+            produce_next(&mut chain, &mut slot);
+        }
+        let inactive_since = chain.next_index() - 1;
+        assert_eq!(chain.validators[&absent.public].inactive_since, Some(inactive_since));
+        assert!(chain.validators[&absent.public].missed_slots >= MAX_MISSED_SLOTS);
+        assert!((slot + 1..slot + 50).all(|slot| chain.select_leader(slot) != Some(absent.public)));
+
+        // Une seule demande en attente, pour la hauteur de la désactivation
+        Node::reactivate(&mut chain, &producer);
+        Node::reactivate(&mut chain, &absent);
+        Node::reactivate(&mut chain, &absent);
+        assert_eq!(chain.pending_governance_transactions.len(), 1);
+        let stale = Reactivation::new(&absent, inactive_since - 1).map(GovernanceTransaction::Reactivation).unwrap();
+        assert_eq!(chain.add_governance_transaction(stale), Err("Validator not inactive since this height"));
+
+        let block = produce_next(&mut chain, &mut slot);
+        assert_eq!(block.governance_transactions.len(), 1);
+        let validator = &chain.validators[&absent.public];
+        assert_eq!((validator.inactive_since, validator.missed_slots), (None, 0));
+        let replay = Reactivation::new(&absent, inactive_since).map(GovernanceTransaction::Reactivation).unwrap();
+        assert_eq!(chain.add_governance_transaction(replay), Err("Validator not inactive since this height"));
+        Node::reactivate(&mut chain, &absent);
+        assert!(chain.pending_governance_transactions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn orphans_are_connected_once_their_parent_arrives() {
+        let validator = keypair(1);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let producer = test_chain(&[1], &clock);
+        let follower = test_chain(&[1], &clock);
+        let mut blocks = Vec::new();
+        for slot in 1..=4 {
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            blocks.push(producer.lock().await.validate_and_create_block(&validator, slot).unwrap());
+        }
+
+        let (traces, mut orphans) = (PendingTraces::default(), OrphanPool::new(OrphanLimits::default()));
+        for index in [3, 1, 2] {
+            Node::apply_block(&follower, &validator, &traces, &mut orphans, blocks[index].clone()).await;
+        }
+        assert_eq!((follower.lock().await.next_index(), orphans.len()), (0, 3));
+
+        Node::apply_block(&follower, &validator, &traces, &mut orphans, blocks[0].clone()).await;
+        assert_eq!((follower.lock().await.next_index(), orphans.len()), (4, 0));
+        assert_eq!(follower.lock().await.last_hash(), blocks[3].current_hash);
+
+        // Un bloc en avance d'un validateur inconnu n'entre pas dans le pool
+        let stranger = test_chain(&[2], &clock);
+        let mut ahead = None;
+        for slot in 5..=10 {
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            ahead = Some(stranger.lock().await.validate_and_create_block(&keypair(2), slot).unwrap());
+        }
+        let ahead = ahead.unwrap();
+        assert!(ahead.index > follower.lock().await.next_index());
+        Node::apply_block(&follower, &validator, &traces, &mut orphans, ahead).await;
+        assert_eq!(orphans.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn devnet_produces_blocks_only_for_pending_transactions() {
+        let Devnet { mut blockchain, validator, accounts, .. } = Devnet::new(2);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        blockchain.set_clock(Arc::new(clock.clone()));
+        let chain = Arc::new(Mutex::new(blockchain));
+        let signer: Arc<dyn Signer> = Arc::new(validator);
+        let traces = PendingTraces::default();
+        let interval = Duration::from_millis(DEV_SLOT_MILLIS);
+
+        let mut slots = (1..).map(|step| {
+            clock.set(Duration::from_secs(GENESIS_TIME) + interval * step);
+            current_slot(&clock, interval)
+        });
+        for slot in slots.by_ref().take(5) {
+            Node::produce_block(&chain, &traces, &signer, slot, false).await;
+        }
+        assert_eq!(chain.lock().await.next_index(), 0);
+
+        let transfer = {
+            let chain = chain.lock().await;
+            TxBuilder::transfer().to(accounts[1].public).amount(10).chain_id(chain.config.chain_id)
+                .nonce(chain.next_nonce(&accounts[0].public)).timestamp(chain.now()).sign(&accounts[0]).unwrap()
+        };
+        chain.lock().await.add_transaction(transfer.clone()).unwrap();
+        Node::produce_block(&chain, &traces, &signer, slots.next().unwrap(), false).await;
+        let chain = chain.lock().await;
+        assert_eq!(chain.next_index(), 1);
+        assert_eq!(chain.chain[0].transactions, vec![transfer]);
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::{BTreeMap, HashMap};
+use ed25519_dalek::PublicKey;
+
+use crate::encoding::{Decoder, Encoder};
+use crate::{Expiry, Transaction};
+
+// Nonces utilisés par les transactions incluses, par émetteur, avec l'échéance
+// de la transaction qui les a utilisés : une transaction (ou une autre version
+// signée avec le même nonce) ne peut pas être incluse une seconde fois. Une
+// fois l'échéance passée la transaction n'est plus incluable et le nonce est
+// oublié, ce qui borne l'état à la durée de vie maximale des transactions.
+#[derive(Clone, Debug, Default)]
+pub struct Nonces {
+    used: HashMap<PublicKey, BTreeMap<u64, Expiry>>,
+}
+
+impl Nonces {
+    pub fn is_used(&self, sender: &PublicKey, nonce: u64) -> bool {
+        self.used.get(sender).is_some_and(|nonces| nonces.contains_key(&nonce))
+    }
+
+    // Nonce suivant le plus grand encore retenu pour `sender`
+    pub fn next(&self, sender: &PublicKey) -> u64 {
+        self.used.get(sender)
+            .and_then(|nonces| nonces.keys().next_back())
+            .map_or(0, |nonce| nonce.saturating_add(1))
+    }
+
+    pub fn check(&self, transaction: &Transaction) -> Result<(), &'static str> {
+        if self.is_used(&transaction.sender, transaction.nonce) {
+            return Err("Nonce already used");
+        }
+        Ok(())
+    }
+
+    pub fn apply(&mut self, transaction: &Transaction) {
+        self.used.entry(transaction.sender).or_default().insert(transaction.nonce, transaction.valid_until);
+    }
+
+    // Oublie les nonces des transactions qu'un bloc de hauteur `height` et
+    // d'horodatage `timestamp` ne peut plus inclure
+    pub fn expire(&mut self, height: u64, timestamp: u64) {
+        for nonces in self.used.values_mut() {
+            nonces.retain(|_, valid_until| !valid_until.is_expired(height, timestamp));
+        }
+        self.used.retain(|_, nonces| !nonces.is_empty());
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        let mut senders: Vec<(&PublicKey, &BTreeMap<u64, Expiry>)> = self.used.iter().collect();
+        senders.sort_by_key(|(sender, _)| sender.to_bytes());
+        encoder.put_u32(senders.len() as u32);
+        for (sender, nonces) in senders {
+            encoder.put_public_key(sender);
+            encoder.put_u32(nonces.len() as u32);
+            for (nonce, valid_until) in nonces {
+                encoder.put_u64(*nonce);
+                valid_until.encode_into(encoder);
+            }
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Nonces, &'static str> {
+        let mut used = HashMap::new();
+        let mut previous: Option<[u8; 32]> = None;
+        for _ in 0..decoder.get_u32()? {
+            let sender = decoder.get_public_key()?;
+            if previous.is_some_and(|previous| previous >= sender.to_bytes()) {
+                return Err("Nonces not in canonical order");
+            }
+            previous = Some(sender.to_bytes());
+            let mut nonces = BTreeMap::new();
+            let mut last: Option<u64> = None;
+            for _ in 0..decoder.get_u32()? {
+                let nonce = decoder.get_u64()?;
+                if last.is_some_and(|last| last >= nonce) {
+                    return Err("Nonces not in canonical order");
+                }
+                last = Some(nonce);
+                nonces.insert(nonce, Expiry::decode_from(decoder)?);
+            }
+            if nonces.is_empty() {
+                return Err("Nonces not in canonical order");
+            }
+            used.insert(sender, nonces);
+        }
+        Ok(Nonces { used })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::Keypair;
+    use rand::rngs::OsRng;
+
+    use super::*;
+
+    fn encode(nonces: &Nonces) -> Vec<u8> {
+        let mut encoder = Encoder::new(b"nonces");
+        nonces.encode_into(&mut encoder);
+        encoder.finish()
+    }
+
+    fn decode(data: &[u8]) -> Result<Nonces, &'static str> {
+        let mut decoder = Decoder::new(data, b"nonces")?;
+        let nonces = Nonces::decode_from(&mut decoder)?;
+        decoder.finish()?;
+        Ok(nonces)
+    }
+
+    #[test]
+    fn round_trip_and_expiry() {
+        let (alice, bob) = (Keypair::generate(&mut OsRng).public, Keypair::generate(&mut OsRng).public);
+        let mut nonces = Nonces::default();
+        nonces.used.entry(alice).or_default().extend([(0, Expiry::Height(10)), (5, Expiry::Timestamp(1_000))]);
+        nonces.used.entry(bob).or_default().insert(3, Expiry::Height(20));
+        assert_eq!(nonces.next(&alice), 6);
+        assert_eq!(nonces.next(&bob), 4);
+
+        let decoded = decode(&encode(&nonces)).unwrap();
+        assert_eq!(decoded.used, nonces.used);
+        assert_eq!(encode(&decoded), encode(&nonces));
+
+        nonces.expire(11, 999);
+        assert!(!nonces.is_used(&alice, 0));
+        assert!(nonces.is_used(&alice, 5));
+        nonces.expire(21, 1_001);
+        assert!(nonces.used.is_empty());
+        assert_eq!(nonces.next(&alice), 0);
+    }
+
+    #[test]
+    fn rejects_non_canonical_encoding() {
+        let alice = Keypair::generate(&mut OsRng).public;
+        let unordered = |first: u64, second: u64| {
+            let mut encoder = Encoder::new(b"nonces");
+            encoder.put_u32(1);
+            encoder.put_public_key(&alice);
+            encoder.put_u32(2);
+            for nonce in [first, second] {
+                encoder.put_u64(nonce);
+                Expiry::Height(1).encode_into(&mut encoder);
+            }
+            encoder.finish()
+        };
+        assert!(decode(&unordered(1, 2)).is_ok());
+        assert_eq!(decode(&unordered(2, 1)).err(), Some("Nonces not in canonical order"));
+        assert_eq!(decode(&unordered(1, 1)).err(), Some("Nonces not in canonical order"));
+
+        let mut encoder = Encoder::new(b"nonces");
+        encoder.put_u32(1);
+        encoder.put_public_key(&alice);
+        encoder.put_u32(0);
+        assert_eq!(decode(&encoder.finish()).err(), Some("Nonces not in canonical order"));
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use crate::encoding::encode_block;
+use crate::metrics::METRICS;
+use crate::Block;
+
+// Bornes du pool des blocs reçus avant leur parent
+#[derive(Clone, Debug)]
+pub struct OrphanLimits {
+    pub max_blocks: usize,
+    // Taille encodée cumulée
+    pub max_bytes: usize,
+    pub max_age: Duration,
+}
+
+impl Default for OrphanLimits {
+    fn default() -> Self {
+        OrphanLimits {
+            max_blocks: 256,
+            max_bytes: 32 * 1024 * 1024,
+            max_age: Duration::from_secs(600),
+        }
+    }
+}
+
+struct Orphan {
+    block: Block,
+    size: usize,
+    received: Instant,
+}
+
+// Blocs en avance sur la tête, rattachés dès que leur parent est appliqué.
+// Au-delà des bornes, les blocs les plus éloignés de la tête (le plus de
+// parents manquants, donc les moins susceptibles d'être rattachés) sont évincés
+// en premier, puis les plus anciens.
+pub struct OrphanPool {
+    limits: OrphanLimits,
+    // Par hash de bloc
+    blocks: HashMap<Vec<u8>, Orphan>,
+    bytes: usize,
+}
+
+impl OrphanPool {
+    pub fn new(limits: OrphanLimits) -> Self {
+        OrphanPool { limits, blocks: HashMap::new(), bytes: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    // Retourne faux si le bloc est déjà présent, trop gros ou évincé aussitôt
+    pub fn insert(&mut self, block: Block) -> bool {
+        if self.blocks.contains_key(&block.current_hash) {
+            return false;
+        }
+        let size = encode_block(&block).len();
+        if size > self.limits.max_bytes {
+            return false;
+        }
+        let hash = block.current_hash.clone();
+        self.bytes += size;
+        self.blocks.insert(hash.clone(), Orphan { block, size, received: Instant::now() });
+        while self.blocks.len() > self.limits.max_blocks || self.bytes > self.limits.max_bytes {
+            self.evict();
+        }
+        self.publish();
+        self.blocks.contains_key(&hash)
+    }
+
+    // Retire et retourne les blocs dont le parent est `parent_hash`
+    pub fn take_children(&mut self, parent_hash: &[u8]) -> Vec<Block> {
+        let hashes: Vec<Vec<u8>> = self.blocks.iter()
+            .filter(|(_, orphan)| orphan.block.previous_hash == parent_hash)
+            .map(|(hash, _)| hash.clone())
+            .collect();
+        let children: Vec<Block> = hashes.iter().filter_map(|hash| self.remove(hash)).collect();
+        METRICS.orphans_connected.fetch_add(children.len() as u64, Ordering::Relaxed);
+        self.publish();
+        children
+    }
+
+    // Écarte les blocs expirés et ceux qui ne peuvent plus prolonger la tête
+    pub fn prune(&mut self, next_index: u64) {
+        let max_age = self.limits.max_age;
+        let hashes: Vec<Vec<u8>> = self.blocks.iter()
+            .filter(|(_, orphan)| orphan.block.index < next_index || orphan.received.elapsed() > max_age)
+            .map(|(hash, _)| hash.clone())
+            .collect();
+        for hash in &hashes {
+            self.remove(hash);
+        }
+        METRICS.orphans_dropped.fetch_add(hashes.len() as u64, Ordering::Relaxed);
+        self.publish();
+    }
+
+    fn evict(&mut self) {
+        let victim = self.blocks.iter()
+            .max_by(|(_, a), (_, b)| a.block.index.cmp(&b.block.index).then(b.received.cmp(&a.received)))
+            .map(|(hash, _)| hash.clone());
+        if let Some(hash) = victim {
+            self.remove(&hash);
+            METRICS.orphans_evicted.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn remove(&mut self, hash: &[u8]) -> Option<Block> {
+        let orphan = self.blocks.remove(hash)?;
+        self.bytes -= orphan.size;
+        Some(orphan.block)
+    }
+
+    fn publish(&self) {
+        METRICS.orphan_blocks.store(self.blocks.len() as u64, Ordering::Relaxed);
+        METRICS.orphan_bytes.store(self.bytes as u64, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::Blockchain;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    // `count` blocs successifs d'un validateur unique
+    fn blocks(count: u64) -> Vec<Block> {
+        let secret = SecretKey::from_bytes(&[1; 32]).unwrap();
+        let validator = Keypair { public: PublicKey::from(&secret), secret };
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(std::sync::Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        (1..=count).map(|slot| {
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            chain.validate_and_create_block(&validator, slot).unwrap()
+        }).collect()
+    }
+
+    fn indexes(pool: &OrphanPool) -> Vec<u64> {
+        let mut indexes: Vec<u64> = pool.blocks.values().map(|orphan| orphan.block.index).collect();
+        indexes.sort_unstable();
+        indexes
+    }
+
+    #[test]
+    fn farthest_blocks_are_evicted_at_the_count_limit() {
+        let blocks = blocks(5);
+        let mut pool = OrphanPool::new(OrphanLimits { max_blocks: 3, ..OrphanLimits::default() });
+        for index in [2, 4, 1] {
+            assert!(pool.insert(blocks[index].clone()));
+        }
+        assert!(!pool.insert(blocks[1].clone()));
+
+        // Le plus éloigné de la tête cède sa place ; un bloc plus éloigné que tous
+        // les autres est évincé aussitôt
+        assert!(pool.insert(blocks[3].clone()));
+        assert_eq!(indexes(&pool), vec![1, 2, 3]);
+        assert!(!pool.insert(blocks[4].clone()));
+        assert_eq!((pool.len(), indexes(&pool)), (3, vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn blocks_are_evicted_at_the_byte_limit() {
+        let blocks = blocks(4);
+        let size = encode_block(&blocks[1]).len();
+        assert!(blocks.iter().all(|block| encode_block(block).len() == size));
+        let mut pool = OrphanPool::new(OrphanLimits { max_bytes: size * 2 + size / 2, ..OrphanLimits::default() });
+        for index in [3, 1] {
+            assert!(pool.insert(blocks[index].clone()));
+        }
+        assert!(pool.insert(blocks[2].clone()));
+        assert_eq!(indexes(&pool), vec![1, 2]);
+        assert_eq!(pool.bytes, size * 2);
+
+        let mut tiny = OrphanPool::new(OrphanLimits { max_bytes: size - 1, ..OrphanLimits::default() });
+        assert!(!tiny.insert(blocks[1].clone()));
+        assert_eq!((tiny.len(), tiny.bytes), (0, 0));
+    }
+
+    #[test]
+    fn children_are_taken_once_and_stale_blocks_pruned() {
+        let blocks = blocks(4);
+        let mut pool = OrphanPool::new(OrphanLimits::default());
+        for block in &blocks[1..] {
+            assert!(pool.insert(block.clone()));
+        }
+        let children = pool.take_children(&blocks[0].current_hash);
+        assert_eq!(children.iter().map(|block| block.index).collect::<Vec<_>>(), vec![1]);
+        assert!(pool.take_children(&blocks[0].current_hash).is_empty());
+
+        // La tête a dépassé le bloc 2 par un autre chemin
+        pool.prune(3);
+        assert_eq!(indexes(&pool), vec![3]);
+        let mut expiring = OrphanPool::new(OrphanLimits { max_age: Duration::ZERO, ..OrphanLimits::default() });
+        expiring.insert(blocks[3].clone());
+        std::thread::sleep(Duration::from_millis(2));
+        expiring.prune(0);
+        assert_eq!((expiring.len(), expiring.bytes), (0, 0));
+    }
+}
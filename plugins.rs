@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use serde_json::Value;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{error, warn};
+
+use crate::events::ChainEvent;
+use crate::{Block, Transaction};
+
+// Extension du nœud chargée au démarrage. Les plugins ne reçoivent que des copies
+// des blocs et transactions, jamais la chaîne : ils ne peuvent ni modifier l'état
+// de consensus ni retenir son verrou. Un plugin qui panique est désactivé.
+pub trait Plugin: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // Appelé dans l'ordre pour chaque bloc appliqué, hors du verrou de la chaîne ;
+    // c'est là qu'un plugin alimente ses propres index
+    fn block_applied(&self, _block: &Block) {}
+
+    // Filtre local à l'entrée du mempool (hors consensus) : `Err` refuse la transaction
+    fn admit(&self, _transaction: &Transaction) -> Result<(), String> {
+        Ok(())
+    }
+
+    // Espace de noms servi par l'explorateur sous `/plugins/<namespace>/...`
+    fn namespace(&self) -> Option<&'static str> {
+        None
+    }
+
+    // Requête sur l'espace de noms, `path` étant la suite du chemin
+    fn query(&self, _path: &[&str], _query: &HashMap<&str, &str>) -> Result<Value, String> {
+        Err("Unknown endpoint".to_string())
+    }
+}
+
+struct Entry {
+    plugin: Arc<dyn Plugin>,
+    disabled: AtomicBool,
+}
+
+#[derive(Default)]
+pub struct PluginHost {
+    entries: Vec<Entry>,
+}
+
+impl PluginHost {
+    pub fn register(&mut self, plugin: Arc<dyn Plugin>) -> Result<(), &'static str> {
+        if self.entries.iter().any(|entry| entry.plugin.name() == plugin.name()) {
+            return Err("Duplicate plugin name");
+        }
+        if plugin.namespace().is_some() && self.entries.iter().any(|entry| entry.plugin.namespace() == plugin.namespace()) {
+            return Err("Duplicate plugin namespace");
+        }
+        self.entries.push(Entry { plugin, disabled: AtomicBool::new(false) });
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn block_applied(&self, block: &Block) {
+        for entry in &self.entries {
+            self.guard(entry, || entry.plugin.block_applied(block));
+        }
+    }
+
+    // Les refus sont journalisés avec leur motif ; un plugin désactivé n'est plus consulté
+    pub fn admit(&self, transaction: &Transaction) -> Result<(), &'static str> {
+        for entry in &self.entries {
+            if let Some(Err(reason)) = self.guard(entry, || entry.plugin.admit(transaction)) {
+                warn!(plugin = entry.plugin.name(), reason, "Transaction refused by plugin");
+                return Err("Refused by plugin");
+            }
+        }
+        Ok(())
+    }
+
+    // `None` si aucun plugin actif ne sert cet espace de noms
+    pub fn query(&self, namespace: &str, path: &[&str], query: &HashMap<&str, &str>) -> Option<Result<Value, String>> {
+        let entry = self.entries.iter().find(|entry| entry.plugin.namespace() == Some(namespace))?;
+        self.guard(entry, || entry.plugin.query(path, query))
+    }
+
+    // Suit les blocs appliqués par la chaîne ; les hooks s'exécutent sur un fil
+    // bloquant pour ne pas ralentir l'exécuteur
+    pub fn follow(self: Arc<Self>, mut events: broadcast::Receiver<ChainEvent>) {
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(ChainEvent::BlockCommitted(block)) => {
+                        let host = self.clone();
+                        let _ = tokio::task::spawn_blocking(move || host.block_applied(&block)).await;
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(missed)) => warn!(missed, "Plugins fell behind chain events"),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    fn guard<T>(&self, entry: &Entry, call: impl FnOnce() -> T) -> Option<T> {
+        if entry.disabled.load(Ordering::Relaxed) {
+            return None;
+        }
+        match catch_unwind(AssertUnwindSafe(call)) {
+            Ok(result) => Some(result),
+            Err(_) => {
+                entry.disabled.store(true, Ordering::Relaxed);
+                error!(plugin = entry.plugin.name(), "Plugin panicked, disabled");
+                None
+            }
+        }
+    }
+}
+
+// Plugins compilés dans le nœud, chacun derrière sa feature Cargo
+pub fn registered() -> PluginHost {
+    let plugins: Vec<Arc<dyn Plugin>> = vec![
+        #[cfg(feature = "plugin-activity")]
+        Arc::new(activity::Activity::default()),
+    ];
+    let mut host = PluginHost::default();
+    for plugin in plugins {
+        host.register(plugin).unwrap();
+    }
+    host
+}
+
+// Exemple d'index propre à un plugin : nombre de transactions envoyées et reçues
+// par adresse, servi sous `/plugins/activity/<clé hex>`
+#[cfg(feature = "plugin-activity")]
+mod activity {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use serde_json::{json, Value};
+
+    use super::Plugin;
+    use crate::Block;
+
+    #[derive(Default)]
+    pub struct Activity {
+        // Clé publique -> (envoyées, reçues)
+        counts: Mutex<HashMap<[u8; 32], (u64, u64)>>,
+    }
+
+    impl Plugin for Activity {
+        fn name(&self) -> &'static str {
+            "activity"
+        }
+
+        fn block_applied(&self, block: &Block) {
+            let mut counts = self.counts.lock().unwrap();
+            for transaction in &block.transactions {
+                counts.entry(transaction.sender.to_bytes()).or_default().0 += 1;
+                counts.entry(transaction.recipient.to_bytes()).or_default().1 += 1;
+            }
+        }
+
+        fn namespace(&self) -> Option<&'static str> {
+            Some("activity")
+        }
+
+        fn query(&self, path: &[&str], _query: &HashMap<&str, &str>) -> Result<Value, String> {
+            let [address] = path else {
+                return Err("Expected /plugins/activity/<address>".to_string());
+            };
+            let key: [u8; 32] = hex::decode(address).ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or("Invalid address")?;
+            let (sent, received) = self.counts.lock().unwrap().get(&key).copied().unwrap_or_default();
+            Ok(json!({ "address": address, "sent": sent, "received": received }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Plugin servant `namespace`, dont les requêtes paniquent sur `/panic`
+    struct Probe {
+        name: &'static str,
+        namespace: &'static str,
+    }
+
+    impl Plugin for Probe {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn namespace(&self) -> Option<&'static str> {
+            Some(self.namespace)
+        }
+
+        fn query(&self, path: &[&str], _query: &HashMap<&str, &str>) -> Result<Value, String> {
+            match path {
+                ["panic"] => panic!("probe"),
+                _ => Ok(Value::from(self.name)),
+            }
+        }
+    }
+
+    #[test]
+    fn registers_unique_plugins_and_disables_panicking_ones() {
+        let mut host = PluginHost::default();
+        host.register(Arc::new(Probe { name: "probe", namespace: "probe" })).unwrap();
+        assert_eq!(host.register(Arc::new(Probe { name: "probe", namespace: "other" })), Err("Duplicate plugin name"));
+        assert_eq!(host.register(Arc::new(Probe { name: "other", namespace: "probe" })), Err("Duplicate plugin namespace"));
+
+        let query = HashMap::new();
+        assert_eq!(host.query("probe", &[], &query), Some(Ok(Value::from("probe"))));
+        assert!(host.query("unknown", &[], &query).is_none());
+        assert!(host.query("probe", &["panic"], &query).is_none());
+        assert!(host.query("probe", &[], &query).is_none());
+    }
+}
@@ -0,0 +1,416 @@
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use ed25519_dalek::{ExpandedSecretKey, Keypair, PublicKey, Signature, Signer, Verifier};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Digest, Sha3_256, Shake256};
+
+use crate::encoding::{
+    Decoder, Encoder, ENCRYPTED_PAYLOAD_DOMAIN, PRIVACY_GROUP_DOMAIN, PRIVATE_KEY_WRAP_DOMAIN, PRIVATE_PAYLOAD_DOMAIN,
+    PRIVATE_PAYLOAD_MAC_DOMAIN, PRIVATE_PROPOSAL_DOMAIN, PRIVATE_STATE_DOMAIN, PRIVATE_TRANSACTION_DOMAIN,
+};
+
+// Groupe de confidentialité : ensemble de membres partageant des transactions
+// privées. L'identifiant ne dépend que des membres (triés, sans doublon).
+#[derive(Clone, Debug)]
+pub struct PrivacyGroup {
+    pub members: Vec<PublicKey>,
+}
+
+impl PrivacyGroup {
+    pub fn new(mut members: Vec<PublicKey>) -> Result<Self, &'static str> {
+        members.sort_by_key(|key| key.to_bytes());
+        members.dedup();
+        if members.len() < 2 {
+            return Err("Privacy group needs at least two members");
+        }
+        Ok(PrivacyGroup { members })
+    }
+
+    pub fn id(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(PRIVACY_GROUP_DOMAIN);
+        encoder.put_u32(self.members.len() as u32);
+        for member in &self.members {
+            encoder.put_public_key(member);
+        }
+        encoder.hash()
+    }
+
+    pub fn is_member(&self, key: &PublicKey) -> bool {
+        self.members.contains(key)
+    }
+}
+
+// Trace on-chain d'une transaction privée : seuls le hash du contenu et la
+// transition d'état du groupe sont publiés, approuvés par tous les membres
+#[derive(Clone, Debug)]
+pub struct PrivateTransaction {
+    pub group: PrivacyGroup,
+    pub payload_hash: Vec<u8>,
+    // Hash de l'état privé du groupe avant et après la transaction (vide avant la première)
+    pub previous_state: Vec<u8>,
+    pub new_state: Vec<u8>,
+    // Une signature par membre, dans l'ordre de `group.members`
+    pub endorsements: Vec<Signature>,
+}
+
+impl PrivateTransaction {
+    pub fn signing_hash(group_id: &[u8], payload_hash: &[u8], previous_state: &[u8], new_state: &[u8]) -> Vec<u8> {
+        let mut encoder = Encoder::new(PRIVATE_TRANSACTION_DOMAIN);
+        encoder.put_bytes(group_id);
+        encoder.put_bytes(payload_hash);
+        encoder.put_bytes(previous_state);
+        encoder.put_bytes(new_state);
+        encoder.hash()
+    }
+
+    // Approbation d'un membre, à collecter auprès de chacun avant soumission
+    pub fn endorse(keypair: &Keypair, group: &PrivacyGroup, payload_hash: &[u8], previous_state: &[u8], new_state: &[u8]) -> Signature {
+        keypair.sign(&Self::signing_hash(&group.id(), payload_hash, previous_state, new_state))
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Self::signing_hash(&self.group.id(), &self.payload_hash, &self.previous_state, &self.new_state)
+    }
+
+    pub fn verify(&self) -> Result<(), &'static str> {
+        if PrivacyGroup::new(self.group.members.clone())?.members != self.group.members {
+            return Err("Privacy group members not canonical");
+        }
+        if self.new_state.is_empty() {
+            return Err("Missing private state hash");
+        }
+        if self.endorsements.len() != self.group.members.len() {
+            return Err("Private transaction not endorsed by all members");
+        }
+
+        let message = self.hash();
+        for (member, signature) in self.group.members.iter().zip(&self.endorsements) {
+            member.verify(&message, signature).map_err(|_| "Invalid private transaction endorsement")?;
+        }
+        Ok(())
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        encoder.put_u32(self.group.members.len() as u32);
+        for (member, signature) in self.group.members.iter().zip(&self.endorsements) {
+            encoder.put_public_key(member);
+            encoder.put_signature(signature);
+        }
+        encoder.put_bytes(&self.payload_hash);
+        encoder.put_bytes(&self.previous_state);
+        encoder.put_bytes(&self.new_state);
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<PrivateTransaction, &'static str> {
+        let count = decoder.get_u32()?;
+        let mut members = Vec::new();
+        let mut endorsements = Vec::new();
+        for _ in 0..count {
+            members.push(decoder.get_public_key()?);
+            endorsements.push(decoder.get_signature()?);
+        }
+        Ok(PrivateTransaction {
+            group: PrivacyGroup { members },
+            payload_hash: decoder.get_bytes()?.to_vec(),
+            previous_state: decoder.get_bytes()?.to_vec(),
+            new_state: decoder.get_bytes()?.to_vec(),
+            endorsements,
+        })
+    }
+}
+
+// Transaction privée en cours d'approbation, échangée hors chaîne entre les
+// membres : le contenu chiffré pour le groupe et les approbations recueillies.
+// L'état privé qu'elle produit enchaîne l'état précédent et le hash du contenu.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrivateProposal {
+    pub members: Vec<PublicKey>,
+    pub payload: EncryptedPayload,
+    pub payload_hash: Vec<u8>,
+    pub previous_state: Vec<u8>,
+    // Une approbation par membre, dans l'ordre de `members`, absente tant que le membre n'a pas approuvé
+    pub endorsements: Vec<Option<Signature>>,
+}
+
+impl PrivateProposal {
+    pub fn new(group: &PrivacyGroup, payload: &[u8], previous_state: &[u8]) -> Result<Self, &'static str> {
+        Ok(PrivateProposal {
+            members: group.members.clone(),
+            payload: EncryptedPayload::seal(group, payload)?,
+            payload_hash: Sha3_256::digest(payload).to_vec(),
+            previous_state: previous_state.to_vec(),
+            endorsements: vec![None; group.members.len()],
+        })
+    }
+
+    pub fn new_state(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(PRIVATE_STATE_DOMAIN);
+        encoder.put_bytes(&self.previous_state);
+        encoder.put_bytes(&self.payload_hash);
+        encoder.hash()
+    }
+
+    // Contenu déchiffré par un membre, vérifié contre le hash approuvé
+    pub fn open(&self, keypair: &Keypair) -> Result<Vec<u8>, &'static str> {
+        let payload = self.payload.open(keypair)?;
+        if Sha3_256::digest(&payload)[..] != self.payload_hash[..] {
+            return Err("Private payload does not match its hash");
+        }
+        Ok(payload)
+    }
+
+    // Approbation du membre `keypair`, après lecture du contenu
+    pub fn endorse(&mut self, keypair: &Keypair) -> Result<Vec<u8>, &'static str> {
+        let payload = self.open(keypair)?;
+        let position = self.members.iter().position(|member| *member == keypair.public).ok_or("Not a member of this privacy group")?;
+        let group = PrivacyGroup::new(self.members.clone())?;
+        self.endorsements[position] = Some(PrivateTransaction::endorse(keypair, &group, &self.payload_hash, &self.previous_state, &self.new_state()));
+        Ok(payload)
+    }
+
+    // Transaction à soumettre, une fois approuvée par tous les membres
+    pub fn transaction(&self) -> Result<PrivateTransaction, &'static str> {
+        let transaction = PrivateTransaction {
+            group: PrivacyGroup { members: self.members.clone() },
+            payload_hash: self.payload_hash.clone(),
+            previous_state: self.previous_state.clone(),
+            new_state: self.new_state(),
+            endorsements: self.endorsements.iter().copied().collect::<Option<Vec<_>>>().ok_or("Private transaction not endorsed by all members")?,
+        };
+        transaction.verify()?;
+        Ok(transaction)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(PRIVATE_PROPOSAL_DOMAIN);
+        encoder.put_u32(self.members.len() as u32);
+        for (member, endorsement) in self.members.iter().zip(&self.endorsements) {
+            encoder.put_public_key(member);
+            match endorsement {
+                Some(signature) => {
+                    encoder.put_u8(1);
+                    encoder.put_signature(signature);
+                }
+                None => encoder.put_u8(0),
+            }
+        }
+        self.payload.encode_into(&mut encoder);
+        encoder.put_bytes(&self.payload_hash);
+        encoder.put_bytes(&self.previous_state);
+        encoder.finish()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<PrivateProposal, &'static str> {
+        let mut decoder = Decoder::new(data, PRIVATE_PROPOSAL_DOMAIN)?;
+        let mut members = Vec::new();
+        let mut endorsements = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            members.push(decoder.get_public_key()?);
+            endorsements.push(match decoder.get_u8()? {
+                0 => None,
+                1 => Some(decoder.get_signature()?),
+                _ => return Err("Invalid endorsement flag"),
+            });
+        }
+        let proposal = PrivateProposal {
+            members,
+            payload: EncryptedPayload::decode_from(&mut decoder)?,
+            payload_hash: decoder.get_bytes()?.to_vec(),
+            previous_state: decoder.get_bytes()?.to_vec(),
+            endorsements,
+        };
+        decoder.finish()?;
+        if PrivacyGroup::new(proposal.members.clone())?.members != proposal.members {
+            return Err("Privacy group members not canonical");
+        }
+        Ok(proposal)
+    }
+}
+
+// Contenu chiffré, échangé hors chaîne entre les membres du groupe (ou publié
+// dans les notes d'incident).
+// Le contenu est chiffré par une clé aléatoire (flux SHAKE256, authentifié par
+// un MAC SHA3), elle-même enveloppée pour chaque membre par un échange
+// Diffie-Hellman entre une clé éphémère et sa clé publique ed25519.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncryptedPayload {
+    pub ephemeral: [u8; 32],
+    pub nonce: [u8; 16],
+    pub wrapped_keys: Vec<(PublicKey, [u8; 32])>,
+    pub ciphertext: Vec<u8>,
+    pub tag: Vec<u8>,
+}
+
+impl EncryptedPayload {
+    pub fn seal(group: &PrivacyGroup, payload: &[u8]) -> Result<Self, &'static str> {
+        Self::seal_to(&group.members, payload)
+    }
+
+    // Contenu chiffré pour `members`, dans cet ordre
+    pub fn seal_to(members: &[PublicKey], payload: &[u8]) -> Result<Self, &'static str> {
+        let content_key: [u8; 32] = rand::random();
+        let nonce: [u8; 16] = rand::random();
+        let ephemeral_secret = Scalar::from_bytes_mod_order(rand::random());
+        let ephemeral = (ephemeral_secret * ED25519_BASEPOINT_POINT).compress().to_bytes();
+
+        let mut wrapped_keys = Vec::new();
+        for member in members {
+            let shared = (ephemeral_secret * member_point(member)?).compress();
+            let wrapping_key = wrapping_key(shared.as_bytes(), &ephemeral, member);
+            wrapped_keys.push((*member, xor32(&content_key, &wrapping_key)));
+        }
+
+        let mut ciphertext = payload.to_vec();
+        apply_keystream(&content_key, &nonce, &mut ciphertext);
+        let tag = mac(&content_key, &nonce, &ciphertext);
+        Ok(EncryptedPayload { ephemeral, nonce, wrapped_keys, ciphertext, tag })
+    }
+
+    pub fn open(&self, keypair: &Keypair) -> Result<Vec<u8>, &'static str> {
+        let (_, wrapped) = self.wrapped_keys.iter()
+            .find(|(member, _)| *member == keypair.public)
+            .ok_or("Not a member of this privacy group")?;
+
+        // Scalaire secret ed25519 (32 premiers octets de la clé étendue, déjà bornés)
+        let expanded = ExpandedSecretKey::from(&keypair.secret).to_bytes();
+        let secret = Scalar::from_bits(expanded[..32].try_into().unwrap());
+        let ephemeral = CompressedEdwardsY(self.ephemeral).decompress().ok_or("Invalid ephemeral key")?;
+        let shared = (secret * ephemeral).compress();
+        let content_key = xor32(wrapped, &wrapping_key(shared.as_bytes(), &self.ephemeral, &keypair.public));
+
+        if mac(&content_key, &self.nonce, &self.ciphertext) != self.tag {
+            return Err("Private payload authentication failed");
+        }
+        let mut payload = self.ciphertext.clone();
+        apply_keystream(&content_key, &self.nonce, &mut payload);
+        Ok(payload)
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        encoder.put_bytes(&self.ephemeral);
+        encoder.put_bytes(&self.nonce);
+        encoder.put_u32(self.wrapped_keys.len() as u32);
+        for (member, wrapped) in &self.wrapped_keys {
+            encoder.put_public_key(member);
+            encoder.put_bytes(wrapped);
+        }
+        encoder.put_bytes(&self.ciphertext);
+        encoder.put_bytes(&self.tag);
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<EncryptedPayload, &'static str> {
+        let ephemeral = decoder.get_bytes()?.try_into().map_err(|_| "Invalid ephemeral key")?;
+        let nonce = decoder.get_bytes()?.try_into().map_err(|_| "Invalid payload nonce")?;
+        let mut wrapped_keys = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            let member = decoder.get_public_key()?;
+            wrapped_keys.push((member, decoder.get_bytes()?.try_into().map_err(|_| "Invalid wrapped key")?));
+        }
+        Ok(EncryptedPayload {
+            ephemeral,
+            nonce,
+            wrapped_keys,
+            ciphertext: decoder.get_bytes()?.to_vec(),
+            tag: decoder.get_bytes()?.to_vec(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(ENCRYPTED_PAYLOAD_DOMAIN);
+        self.encode_into(&mut encoder);
+        encoder.finish()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<EncryptedPayload, &'static str> {
+        let mut decoder = Decoder::new(data, ENCRYPTED_PAYLOAD_DOMAIN)?;
+        let payload = EncryptedPayload::decode_from(&mut decoder)?;
+        decoder.finish()?;
+        Ok(payload)
+    }
+}
+
+fn member_point(member: &PublicKey) -> Result<EdwardsPoint, &'static str> {
+    CompressedEdwardsY(member.to_bytes()).decompress().ok_or("Invalid member key")
+}
+
+fn wrapping_key(shared: &[u8], ephemeral: &[u8], member: &PublicKey) -> [u8; 32] {
+    let mut encoder = Encoder::new(PRIVATE_KEY_WRAP_DOMAIN);
+    encoder.put_bytes(shared);
+    encoder.put_bytes(ephemeral);
+    encoder.put_public_key(member);
+    encoder.hash().try_into().unwrap()
+}
+
+fn apply_keystream(key: &[u8], nonce: &[u8], data: &mut [u8]) {
+    let mut encoder = Encoder::new(PRIVATE_PAYLOAD_DOMAIN);
+    encoder.put_bytes(key);
+    encoder.put_bytes(nonce);
+    let mut shake = Shake256::default();
+    shake.update(&encoder.finish());
+    let mut keystream = vec![0u8; data.len()];
+    shake.finalize_xof().read(&mut keystream);
+    for (byte, key_byte) in data.iter_mut().zip(keystream) {
+        *byte ^= key_byte;
+    }
+}
+
+fn mac(key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let mut encoder = Encoder::new(PRIVATE_PAYLOAD_MAC_DOMAIN);
+    encoder.put_bytes(key);
+    encoder.put_bytes(nonce);
+    encoder.put_bytes(ciphertext);
+    encoder.hash()
+}
+
+fn xor32(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    for i in 0..32 {
+        out[i] = a[i] ^ b[i];
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::Keypair;
+    use rand::rngs::OsRng;
+
+    use super::{EncryptedPayload, PrivacyGroup, PrivateProposal};
+
+    #[test]
+    fn sealed_payload_opens_only_for_members() {
+        let (alice, bob, eve) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let group = PrivacyGroup::new(vec![alice.public, bob.public]).unwrap();
+        let sealed = EncryptedPayload::from_bytes(&EncryptedPayload::seal(&group, b"facture 17").unwrap().to_bytes()).unwrap();
+        assert_eq!(sealed.open(&alice).unwrap(), b"facture 17");
+        assert_eq!(sealed.open(&bob).unwrap(), b"facture 17");
+        assert_eq!(sealed.open(&eve), Err("Not a member of this privacy group"));
+
+        let mut tampered = sealed.clone();
+        tampered.ciphertext[0] ^= 1;
+        assert_eq!(tampered.open(&alice), Err("Private payload authentication failed"));
+    }
+
+    #[test]
+    fn proposal_needs_every_member_and_the_announced_payload() {
+        let (alice, bob) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let group = PrivacyGroup::new(vec![alice.public, bob.public]).unwrap();
+        let mut proposal = PrivateProposal::new(&group, b"facture 17", b"etat 1").unwrap();
+        proposal.endorse(&alice).unwrap();
+        assert_eq!(proposal.transaction().unwrap_err(), "Private transaction not endorsed by all members");
+        proposal.endorse(&bob).unwrap();
+        let transaction = proposal.transaction().unwrap();
+        assert_eq!(transaction.previous_state, b"etat 1");
+        assert_eq!(transaction.new_state, proposal.new_state());
+
+        // Un membre n'approuve pas un contenu différent du hash annoncé
+        let mut substituted = PrivateProposal::new(&group, b"facture 18", b"etat 1").unwrap();
+        substituted.payload_hash = proposal.payload_hash.clone();
+        assert_eq!(substituted.endorse(&alice), Err("Private payload does not match its hash"));
+
+        let mut reordered = proposal.clone();
+        reordered.members.reverse();
+        assert_eq!(PrivateProposal::from_bytes(&reordered.to_bytes()), Err("Privacy group members not canonical"));
+    }
+}
@@ -0,0 +1,24 @@
+use crate::events::ChainEvent;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReceiptStatus {
+    Success,
+    // Appel de contrat inclus mais dont l'exécution a échoué : seuls les frais sont prélevés
+    Failed(&'static str),
+}
+
+// Résultat de l'exécution d'une transaction incluse dans un bloc
+#[derive(Clone, Debug)]
+pub struct Receipt {
+    pub transaction_hash: Vec<u8>,
+    pub block_index: u64,
+    // Rang de la transaction dans le bloc
+    pub position: u32,
+    pub status: ReceiptStatus,
+    pub gas_used: u64,
+    pub gas_price: u64,
+    // `gas_used * gas_price`, débité de l'émetteur et versé au producteur du bloc
+    pub fee: u64,
+    // Événements émis par l'exécution, dans l'ordre
+    pub events: Vec<ChainEvent>,
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use ed25519_dalek::PublicKey;
+
+use crate::encoding::{Decoder, Encoder};
+use crate::multisig::MultisigPolicy;
+use crate::{Transaction, TransactionKind};
+
+// Délai de contestation minimal, en blocs, laissé à la clé d'origine pour s'opposer
+pub const MIN_CHALLENGE_PERIOD: u64 = 100;
+
+// Gardiens désignés par un compte : une transaction `Recover` émise par leur
+// adresse multisig propose une nouvelle clé, effective après `challenge_period` blocs
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecoveryPolicy {
+    pub guardians: MultisigPolicy,
+    pub challenge_period: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PendingRecovery {
+    pub new_key: PublicKey,
+    // Hauteur à partir de laquelle la rotation est effectuée, sauf opposition
+    pub execute_at: u64,
+}
+
+// Récupération sociale des comptes : politiques des comptes, récupérations en
+// cours de contestation, et comptes déjà transférés vers leur nouvelle clé
+#[derive(Clone, Debug, Default)]
+pub struct Recoveries {
+    policies: HashMap<PublicKey, RecoveryPolicy>,
+    pending: HashMap<PublicKey, PendingRecovery>,
+    recovered: HashMap<PublicKey, PublicKey>,
+}
+
+impl Recoveries {
+    pub fn pending(&self, account: &PublicKey) -> Option<&PendingRecovery> {
+        self.pending.get(account)
+    }
+
+    pub fn is_recovered(&self, account: &PublicKey) -> bool {
+        self.recovered.contains_key(account)
+    }
+
+    // Clé actuelle d'un compte, en suivant les rotations successives
+    pub fn resolve(&self, account: &PublicKey) -> PublicKey {
+        let mut account = *account;
+        while let Some(new_key) = self.recovered.get(&account) {
+            account = *new_key;
+        }
+        account
+    }
+
+    // Règles de récupération pour une transaction incluse au bloc `height` :
+    // - un compte récupéré ne peut plus émettre
+    // - `SetRecovery` et `VetoRecovery` sont émises par le compte, à son adresse
+    // - `Recover` est émise par l'adresse des gardiens du compte, vers la nouvelle clé
+    // Retourne la récupération que `Recover` ouvrirait.
+    pub fn check(&self, transaction: &Transaction, height: u64) -> Result<Option<PendingRecovery>, &'static str> {
+        if self.recovered.contains_key(&transaction.sender) {
+            return Err("Account has been recovered");
+        }
+        match &transaction.kind {
+            TransactionKind::SetRecovery { guardians, challenge_period } => {
+                if transaction.recipient != transaction.sender {
+                    return Err("Recovery policy must be addressed to its account");
+                }
+                if guardians.address() == transaction.sender {
+                    return Err("Account cannot be its own guardian");
+                }
+                if *challenge_period < MIN_CHALLENGE_PERIOD {
+                    return Err("Challenge period too short");
+                }
+                if self.pending.contains_key(&transaction.sender) {
+                    return Err("Recovery already in progress");
+                }
+                Ok(None)
+            }
+            TransactionKind::Recover { account } => {
+                let policy = self.policies.get(account).ok_or("No recovery policy for account")?;
+                if transaction.sender != policy.guardians.address() {
+                    return Err("Recovery not authorized by guardians");
+                }
+                if self.pending.contains_key(account) {
+                    return Err("Recovery already in progress");
+                }
+                if transaction.recipient == *account || self.recovered.contains_key(&transaction.recipient) {
+                    return Err("Invalid recovery key");
+                }
+                Ok(Some(PendingRecovery {
+                    new_key: transaction.recipient,
+                    execute_at: height.saturating_add(policy.challenge_period),
+                }))
+            }
+            TransactionKind::VetoRecovery => {
+                if transaction.recipient != transaction.sender {
+                    return Err("Recovery veto must be addressed to its account");
+                }
+                if !self.pending.contains_key(&transaction.sender) {
+                    return Err("No recovery in progress");
+                }
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    pub fn apply(&mut self, transaction: &Transaction, height: u64) -> Result<(), &'static str> {
+        let recovery = self.check(transaction, height)?;
+        match &transaction.kind {
+            TransactionKind::SetRecovery { guardians, challenge_period } => {
+                self.policies.insert(transaction.sender, RecoveryPolicy {
+                    guardians: guardians.clone(),
+                    challenge_period: *challenge_period,
+                });
+            }
+            TransactionKind::Recover { account } => {
+                if let Some(recovery) = recovery {
+                    self.pending.insert(*account, recovery);
+                }
+            }
+            TransactionKind::VetoRecovery => {
+                self.pending.remove(&transaction.sender);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Effectue les récupérations dont le délai de contestation expire au bloc
+    // `height` : la politique suit le compte vers sa nouvelle clé. Retourne les
+    // paires (ancienne clé, nouvelle clé), triées par ancienne clé.
+    pub fn complete(&mut self, height: u64) -> Vec<(PublicKey, PublicKey)> {
+        let mut due: Vec<(PublicKey, PublicKey)> = self.pending.iter()
+            .filter(|(_, recovery)| recovery.execute_at <= height)
+            .map(|(account, recovery)| (*account, recovery.new_key))
+            .collect();
+        due.sort_by_key(|(account, _)| account.to_bytes());
+        for (account, new_key) in &due {
+            self.pending.remove(account);
+            if let Some(policy) = self.policies.remove(account) {
+                self.policies.entry(*new_key).or_insert(policy);
+            }
+            self.recovered.insert(*account, *new_key);
+        }
+        due
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        let mut policies: Vec<(&PublicKey, &RecoveryPolicy)> = self.policies.iter().collect();
+        policies.sort_by_key(|(account, _)| account.to_bytes());
+        encoder.put_u32(policies.len() as u32);
+        for (account, policy) in policies {
+            encoder.put_public_key(account);
+            policy.guardians.encode_into(encoder);
+            encoder.put_u64(policy.challenge_period);
+        }
+        let mut pending: Vec<(&PublicKey, &PendingRecovery)> = self.pending.iter().collect();
+        pending.sort_by_key(|(account, _)| account.to_bytes());
+        encoder.put_u32(pending.len() as u32);
+        for (account, recovery) in pending {
+            encoder.put_public_key(account);
+            encoder.put_public_key(&recovery.new_key);
+            encoder.put_u64(recovery.execute_at);
+        }
+        let mut recovered: Vec<(&PublicKey, &PublicKey)> = self.recovered.iter().collect();
+        recovered.sort_by_key(|(account, _)| account.to_bytes());
+        encoder.put_u32(recovered.len() as u32);
+        for (account, new_key) in recovered {
+            encoder.put_public_key(account);
+            encoder.put_public_key(new_key);
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Recoveries, &'static str> {
+        let mut policies = HashMap::new();
+        let mut previous: Option<PublicKey> = None;
+        for _ in 0..decoder.get_u32()? {
+            let account = decode_account(decoder, &mut previous)?;
+            let guardians = MultisigPolicy::decode_from(decoder)?;
+            policies.insert(account, RecoveryPolicy { guardians, challenge_period: decoder.get_u64()? });
+        }
+        let mut pending = HashMap::new();
+        let mut previous: Option<PublicKey> = None;
+        for _ in 0..decoder.get_u32()? {
+            let account = decode_account(decoder, &mut previous)?;
+            pending.insert(account, PendingRecovery { new_key: decoder.get_public_key()?, execute_at: decoder.get_u64()? });
+        }
+        let mut recovered = HashMap::new();
+        let mut previous: Option<PublicKey> = None;
+        for _ in 0..decoder.get_u32()? {
+            let account = decode_account(decoder, &mut previous)?;
+            recovered.insert(account, decoder.get_public_key()?);
+        }
+        Ok(Recoveries { policies, pending, recovered })
+    }
+}
+
+fn decode_account(decoder: &mut Decoder, previous: &mut Option<PublicKey>) -> Result<PublicKey, &'static str> {
+    let account = decoder.get_public_key()?;
+    if previous.is_some_and(|previous| previous.to_bytes() >= account.to_bytes()) {
+        return Err("Recoveries not in canonical order");
+    }
+    *previous = Some(account);
+    Ok(account)
+}
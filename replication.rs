@@ -0,0 +1,274 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use ed25519_dalek::PublicKey;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{mpsc, Mutex};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{transport::Server, Request, Response, Status};
+
+use crate::encoding::{decode_block, encode_block};
+use crate::events::ChainEvent;
+use crate::{Block, Blockchain, Validator};
+
+pub mod proto {
+    tonic::include_proto!("supplyx.replication");
+}
+
+use proto::replication_client::ReplicationClient;
+use proto::replication_record::Record;
+use proto::replication_server::{Replication, ReplicationServer};
+use proto::{ReplicationRecord, StreamRequest, ValidatorUpdate};
+
+// Enregistrements en attente d'envoi par réplica
+const REPLICATION_BUFFER: usize = 256;
+
+fn block_record(block: &Block) -> ReplicationRecord {
+    ReplicationRecord {
+        record: Some(Record::Block(encode_block(block))),
+    }
+}
+
+fn validator_record(validator: &Validator) -> ReplicationRecord {
+    ReplicationRecord {
+        record: Some(Record::Validator(ValidatorUpdate {
+            public_key: validator.public_key.to_bytes().to_vec(),
+            stake: validator.stake,
+            contribution_score: validator.contribution_score,
+            last_validated_block: validator.last_validated_block,
+            missed_slots: validator.missed_slots,
+            inactive_since: validator.inactive_since,
+            standby: validator.standby,
+        })),
+    }
+}
+
+fn validator_from_update(update: ValidatorUpdate) -> Result<Validator, &'static str> {
+    Ok(Validator {
+        public_key: PublicKey::from_bytes(&update.public_key).map_err(|_| "Invalid validator key")?,
+        stake: update.stake,
+        contribution_score: update.contribution_score,
+        last_validated_block: update.last_validated_block,
+        missed_slots: update.missed_slots,
+        inactive_since: update.inactive_since,
+        standby: update.standby,
+    })
+}
+
+pub struct ReplicationService {
+    blockchain: Arc<Mutex<Blockchain>>,
+}
+
+#[tonic::async_trait]
+impl Replication for ReplicationService {
+    type StreamBlocksStream = ReceiverStream<Result<ReplicationRecord, Status>>;
+
+    // Ensemble des validateurs, blocs déjà produits depuis `from_height`, puis
+    // les nouveaux blocs et mises à jour de validateurs au fil de l'eau
+    async fn stream_blocks(&self, request: Request<StreamRequest>) -> Result<Response<Self::StreamBlocksStream>, Status> {
+        let from_height = request.into_inner().from_height;
+        let (sender, receiver) = mpsc::channel(REPLICATION_BUFFER);
+
+        // Abonnement pris sous le verrou : aucun bloc ne peut être manqué entre
+        // l'historique et le flux en direct
+        let chain = self.blockchain.lock().await;
+        if from_height < chain.first_index() {
+            return Err(Status::out_of_range("Blocks before the local snapshot are not available"));
+        }
+        let mut events = chain.subscribe();
+        let mut validators: Vec<&Validator> = chain.validators.values().collect();
+        validators.sort_by_key(|v| v.public_key.to_bytes());
+        let mut history: Vec<ReplicationRecord> = validators.into_iter().map(validator_record).collect();
+        history.extend(chain.chain.iter().filter(|block| block.index >= from_height).map(block_record));
+        let mut next_index = chain.next_index().max(from_height);
+        drop(chain);
+
+        tokio::spawn(async move {
+            for record in history {
+                if sender.send(Ok(record)).await.is_err() {
+                    return;
+                }
+            }
+
+            loop {
+                let record = match events.recv().await {
+                    Ok(ChainEvent::BlockCommitted(block)) if block.index >= next_index => {
+                        next_index = block.index + 1;
+                        block_record(&block)
+                    }
+                    Ok(ChainEvent::ValidatorRegistered(validator)) | Ok(ChainEvent::ValidatorUpdated(validator)) => {
+                        validator_record(&validator)
+                    }
+                    Ok(_) => continue,
+                    // Un réplica trop lent doit se reconnecter depuis sa hauteur courante
+                    Err(RecvError::Lagged(_)) => {
+                        let _ = sender.send(Err(Status::data_loss("Replica fell behind, reconnect"))).await;
+                        return;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+                if sender.send(Ok(record)).await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+pub async fn serve(addr: SocketAddr, blockchain: Arc<Mutex<Blockchain>>) -> Result<(), &'static str> {
+    Server::builder()
+        .add_service(ReplicationServer::new(ReplicationService { blockchain }))
+        .serve(addr)
+        .await
+        .map_err(|_| "Replication server failed")
+}
+
+// Applique le flux du primaire jusqu'à sa fermeture
+pub async fn follow(primary: String, blockchain: Arc<Mutex<Blockchain>>) -> Result<(), &'static str> {
+    let mut client = ReplicationClient::connect(primary).await.map_err(|_| "Failed to connect to primary")?;
+    let from_height = blockchain.lock().await.next_index();
+    let mut stream = client.stream_blocks(StreamRequest { from_height }).await
+        .map_err(|_| "Failed to open replication stream")?
+        .into_inner();
+
+    while let Some(record) = stream.message().await.map_err(|_| "Replication stream failed")? {
+        let mut chain = blockchain.lock().await;
+        match record.record {
+            Some(Record::Block(data)) => chain.apply_replicated_block(decode_block(&data)?)?,
+            Some(Record::Validator(update)) => chain.apply_validator_update(validator_from_update(update)?),
+            None => return Err("Empty replication record"),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ed25519_dalek::{Keypair, SecretKey};
+
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::txbuilder::TxBuilder;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    // Chaîne dotant alice ; le réplica reçoit les validateurs du primaire par le flux
+    fn test_chain(clock: &MockClock, validator: bool) -> Blockchain {
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        if validator {
+            chain.register_validator(&keypair(1).public, 1000).unwrap();
+        }
+        chain.allocate(&keypair(2).public, 1_000_000);
+        chain
+    }
+
+    fn produce(primary: &mut Blockchain, clock: &MockClock, slot: u64) {
+        clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+        let alice = keypair(2);
+        let transfer = TxBuilder::transfer().to(keypair(3).public).amount(100).chain_id(primary.config.chain_id)
+            .nonce(primary.next_nonce(&alice.public)).timestamp(primary.now()).sign(&alice).unwrap();
+        primary.add_transaction(transfer).unwrap();
+        primary.validate_and_create_block(&keypair(1), slot).unwrap();
+    }
+
+    // Primaire servi sur un port local libre ; `follow` est relancé tant que le
+    // serveur n'écoute pas encore
+    async fn replicate(primary: Arc<Mutex<Blockchain>>, replica: Arc<Mutex<Blockchain>>) -> tokio::task::JoinHandle<Result<(), &'static str>> {
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        tokio::spawn(serve(addr, primary));
+        tokio::spawn(async move {
+            loop {
+                match follow(format!("http://{}", addr), replica.clone()).await {
+                    Err("Failed to connect to primary") => tokio::time::sleep(Duration::from_millis(20)).await,
+                    result => return result,
+                }
+            }
+        })
+    }
+
+    async fn wait_for(replica: &Mutex<Blockchain>, height: u64) {
+        for _ in 0..250 {
+            if replica.lock().await.next_index() >= height {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("replica stuck at {}", replica.lock().await.next_index());
+    }
+
+    #[tokio::test]
+    async fn replica_follows_history_then_live_blocks_and_validators() {
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let primary = Arc::new(Mutex::new(test_chain(&clock, true)));
+        let replica = Arc::new(Mutex::new(test_chain(&clock, false)));
+        for slot in 1..=2 {
+            produce(&mut *primary.lock().await, &clock, slot);
+        }
+        let follower = replicate(primary.clone(), replica.clone()).await;
+        wait_for(&replica, 2).await;
+
+        {
+            let mut primary = primary.lock().await;
+            primary.register_validator(&keypair(4).public, 1000).unwrap();
+            produce(&mut primary, &clock, 3);
+        }
+        wait_for(&replica, 3).await;
+        let (primary, replica) = (primary.lock().await, replica.lock().await);
+        assert_eq!(replica.last_hash(), primary.last_hash());
+        assert_eq!(replica.balance(&keypair(3).public), 300);
+        assert_eq!(replica.state_tree.root(), primary.state_tree.root());
+        let keys = |chain: &Blockchain| {
+            let mut keys: Vec<[u8; 32]> = chain.validators.keys().map(|key| key.to_bytes()).collect();
+            keys.sort();
+            keys
+        };
+        assert_eq!(keys(&replica), keys(&primary));
+        assert_eq!(replica.validators[&keypair(1).public].last_validated_block, Some(2));
+        follower.abort();
+    }
+
+    #[tokio::test]
+    async fn replication_stops_at_a_block_the_replica_cannot_execute() {
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let primary = Arc::new(Mutex::new(test_chain(&clock, true)));
+        for slot in 1..=2 {
+            produce(&mut *primary.lock().await, &clock, slot);
+        }
+        // Sans la dotation d'alice, le premier transfert échoue
+        let mut replica = Blockchain::new(ChainConfig::default());
+        replica.set_clock(Arc::new(clock.clone()));
+        let replica = Arc::new(Mutex::new(replica));
+
+        let result = tokio::time::timeout(Duration::from_secs(5), replicate(primary, replica.clone()).await).await;
+        assert_eq!(result.unwrap().unwrap(), Err("Insufficient balance"));
+        assert_eq!(replica.lock().await.next_index(), 0);
+    }
+
+    #[test]
+    fn validator_updates_round_trip() {
+        let validator = Validator {
+            public_key: keypair(1).public,
+            stake: 1000,
+            contribution_score: 0.75,
+            last_validated_block: Some(12),
+            missed_slots: 3,
+            inactive_since: Some(40),
+            standby: true,
+        };
+        let Some(Record::Validator(update)) = validator_record(&validator).record else { panic!("not a validator record") };
+        let decoded = validator_from_update(update.clone()).unwrap();
+        let fields = |v: &Validator| (v.public_key, v.stake, v.contribution_score, v.last_validated_block, v.missed_slots, v.inactive_since, v.standby);
+        assert_eq!(fields(&decoded), fields(&validator));
+        assert_eq!(validator_from_update(ValidatorUpdate { public_key: vec![0; 31], ..update }).err(), Some("Invalid validator key"));
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+// Pénalité cumulée au-delà de laquelle un pair est déconnecté et banni
+const BAN_THRESHOLD: u32 = 100;
+
+// Durée d'un bannissement automatique ou sans durée explicite
+pub const DEFAULT_BAN_DURATION: Duration = Duration::from_secs(24 * 3600);
+
+// Pénalité effacée par minute sans nouvelle infraction
+const RECOVERY_PER_MINUTE: u32 = 2;
+
+// Messages acceptés par pair et par seconde ; au-delà, le surplus est ignoré et pénalisé
+const MAX_MESSAGES_PER_SECOND: u32 = 500;
+
+// Infractions au protocole constatées sur un lien
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Violation {
+    // Message illisible
+    InvalidMessage,
+    // Bloc dont le hash ou la signature est invalide
+    InvalidBlock,
+    // Réponse de synchronisation incohérente avec les en-têtes vérifiés
+    InvalidSyncResponse,
+    // Débit de messages excessif
+    Flooding,
+}
+
+impl Violation {
+    fn penalty(&self) -> u32 {
+        match self {
+            Violation::InvalidMessage => 20,
+            Violation::InvalidBlock => 50,
+            Violation::InvalidSyncResponse => 50,
+            Violation::Flooding => 10,
+        }
+    }
+}
+
+struct PeerRecord {
+    penalty: u32,
+    updated: Instant,
+    violations: u64,
+    window_start: Instant,
+    window_messages: u32,
+}
+
+impl PeerRecord {
+    fn new() -> Self {
+        let now = Instant::now();
+        PeerRecord { penalty: 0, updated: now, violations: 0, window_start: now, window_messages: 0 }
+    }
+
+    fn recover(&mut self) {
+        let minutes = self.updated.elapsed().as_secs() / 60;
+        if minutes > 0 {
+            self.penalty = self.penalty.saturating_sub((minutes as u32).saturating_mul(RECOVERY_PER_MINUTE));
+            self.updated += Duration::from_secs(minutes * 60);
+        }
+    }
+}
+
+// État d'un pair connu
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeerScore {
+    pub penalty: u32,
+    pub violations: u64,
+}
+
+// Réputation des pairs par hôte (adresse sans schéma ni port, pour couvrir les
+// reconnexions) et liste de bannissement, conservée dans un fichier texte
+// d'une ligne `hôte expiration` (secondes Unix) par entrée
+#[derive(Default)]
+pub struct Reputation {
+    peers: HashMap<String, PeerRecord>,
+    bans: BTreeMap<String, u64>,
+    path: Option<PathBuf>,
+}
+
+impl Reputation {
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let mut reputation = Reputation { path: Some(path.clone()), ..Reputation::default() };
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(reputation),
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        };
+        let now = unix_now();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let (host, expires) = line.split_once(' ').ok_or_else(|| format!("{}: invalid line `{}`", path.display(), line))?;
+            let expires: u64 = expires.trim().parse().map_err(|_| format!("{}: invalid line `{}`", path.display(), line))?;
+            if expires > now {
+                reputation.bans.insert(host.to_string(), expires);
+            }
+        }
+        Ok(reputation)
+    }
+
+    pub fn is_banned(&self, remote: &str) -> bool {
+        self.bans.get(host(remote)).is_some_and(|expires| *expires > unix_now())
+    }
+
+    // Retourne vrai si l'infraction fait basculer le pair dans la liste de bannissement
+    pub fn record(&mut self, remote: &str, violation: Violation) -> bool {
+        let record = self.peers.entry(host(remote).to_string()).or_insert_with(PeerRecord::new);
+        record.recover();
+        record.penalty = record.penalty.saturating_add(violation.penalty());
+        record.violations += 1;
+        if record.penalty < BAN_THRESHOLD || self.is_banned(remote) {
+            return false;
+        }
+        self.ban(remote, DEFAULT_BAN_DURATION);
+        true
+    }
+
+    // Comptabilise un message reçu ; faux s'il dépasse le débit autorisé
+    pub fn allow_message(&mut self, remote: &str) -> bool {
+        let record = self.peers.entry(host(remote).to_string()).or_insert_with(PeerRecord::new);
+        if record.window_start.elapsed() >= Duration::from_secs(1) {
+            record.window_start = Instant::now();
+            record.window_messages = 0;
+        }
+        record.window_messages += 1;
+        let messages = record.window_messages;
+        // Une seule pénalité par fenêtre dépassée
+        if messages == MAX_MESSAGES_PER_SECOND + 1 {
+            self.record(remote, Violation::Flooding);
+        }
+        messages <= MAX_MESSAGES_PER_SECOND
+    }
+
+    pub fn ban(&mut self, remote: &str, duration: Duration) {
+        self.bans.insert(host(remote).to_string(), unix_now() + duration.as_secs());
+        self.peers.remove(host(remote));
+        self.save();
+    }
+
+    pub fn unban(&mut self, remote: &str) -> bool {
+        let removed = self.bans.remove(host(remote)).is_some();
+        if removed {
+            self.save();
+        }
+        removed
+    }
+
+    pub fn score(&mut self, remote: &str) -> PeerScore {
+        let (penalty, violations) = match self.peers.get_mut(host(remote)) {
+            Some(record) => {
+                record.recover();
+                (record.penalty, record.violations)
+            }
+            None => (0, 0),
+        };
+        PeerScore { penalty, violations }
+    }
+
+    // Bannissements en cours et leur expiration (secondes Unix)
+    pub fn bans(&self) -> Vec<(String, u64)> {
+        let now = unix_now();
+        self.bans.iter().filter(|(_, expires)| **expires > now).map(|(host, expires)| (host.clone(), *expires)).collect()
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let text: String = self.bans().iter().map(|(host, expires)| format!("{} {}\n", host, expires)).collect();
+        if let Err(e) = std::fs::write(path, text) {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to persist the ban list");
+        }
+    }
+}
+
+// `tcp://10.0.0.1:7000` -> `10.0.0.1`, `ws://[::1]:443` -> `[::1]`
+pub fn host(remote: &str) -> &str {
+    let address = remote.split_once("://").map_or(remote, |(_, address)| address);
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => address,
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bans_a_host_across_reconnections() {
+        let mut reputation = Reputation::default();
+        assert!(!reputation.record("tcp://10.0.0.1:7000", Violation::InvalidBlock));
+        // Même hôte, autre port : la pénalité est cumulée
+        assert_eq!(reputation.score("tcp://10.0.0.1:7001"), PeerScore { penalty: 50, violations: 1 });
+        assert!(reputation.record("tcp://10.0.0.1:7001", Violation::InvalidSyncResponse));
+        assert!(reputation.is_banned("tcp://10.0.0.1:7002"));
+        assert!(!reputation.is_banned("tcp://10.0.0.2:7000"));
+
+        assert!(reputation.unban("10.0.0.1"));
+        assert!(!reputation.is_banned("tcp://10.0.0.1:7000"));
+        assert_eq!(reputation.score("tcp://10.0.0.1:7000"), PeerScore { penalty: 0, violations: 0 });
+    }
+
+    #[test]
+    fn extracts_the_host() {
+        assert_eq!(host("tcp://10.0.0.1:7000"), "10.0.0.1");
+        assert_eq!(host("ws://[::1]:443"), "[::1]");
+        assert_eq!(host("peer.example"), "peer.example");
+    }
+}
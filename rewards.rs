@@ -0,0 +1,242 @@
+use std::collections::{HashMap, HashSet};
+use ed25519_dalek::{PublicKey, Signature};
+
+use crate::address::derive_address;
+use crate::beacon::epoch_of;
+use crate::encoding::{Decoder, Encoder, COMMUNITY_POOL_DOMAIN, REWARDS_DOMAIN, REWARDS_POOL_DOMAIN};
+use crate::governance::GovernanceTransaction;
+use crate::signer::Signer;
+use crate::Validator;
+
+// Commission d'un validateur qui n'en a jamais fixé
+pub const DEFAULT_COMMISSION_PERCENT: u64 = 10;
+
+// Adresse système où les frais des transactions s'accumulent jusqu'à leur
+// distribution en fin d'époque
+pub fn pool_address() -> PublicKey {
+    derive_address(&Encoder::new(REWARDS_POOL_DOMAIN).finish())
+}
+
+// Adresse système recevant la part communautaire des récompenses
+pub fn community_pool_address() -> PublicKey {
+    derive_address(&Encoder::new(COMMUNITY_POOL_DOMAIN).finish())
+}
+
+// Nouvelle commission d'un validateur, signée pour l'époque `epoch` : en vigueur
+// après la prochaine distribution, elle ne peut pas être rejouée à une autre époque
+#[derive(Clone, Debug)]
+pub struct CommissionChange {
+    pub validator: PublicKey,
+    pub percent: u64,
+    pub epoch: u64,
+    pub signature: Signature,
+}
+
+impl CommissionChange {
+    pub fn new(signer: &dyn Signer, percent: u64, epoch: u64) -> Result<Self, &'static str> {
+        let validator = signer.public_key();
+        let signature = signer.sign(&Self::signing_hash(&validator, percent, epoch))?;
+        Ok(CommissionChange { validator, percent, epoch, signature })
+    }
+
+    pub fn signing_hash(validator: &PublicKey, percent: u64, epoch: u64) -> Vec<u8> {
+        let mut encoder = Encoder::new(REWARDS_DOMAIN);
+        encoder.put_public_key(validator);
+        encoder.put_u64(percent);
+        encoder.put_u64(epoch);
+        encoder.hash()
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Self::signing_hash(&self.validator, self.percent, self.epoch)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RewardKind {
+    // Commission prélevée par le validateur
+    Commission,
+    // Part revenant à la mise ; sans délégation, toute la mise est celle du validateur
+    Stake,
+    // Part versée à la réserve communautaire
+    CommunityPool,
+}
+
+impl RewardKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RewardKind::Commission => "commission",
+            RewardKind::Stake => "stake",
+            RewardKind::CommunityPool => "community_pool",
+        }
+    }
+}
+
+// Crédit versé à la distribution du bloc `height`, sur les frais produits par `validator`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RewardCredit {
+    pub height: u64,
+    pub validator: PublicKey,
+    pub kind: RewardKind,
+    pub amount: u64,
+}
+
+impl RewardCredit {
+    pub fn recipient(&self) -> PublicKey {
+        match self.kind {
+            RewardKind::Commission | RewardKind::Stake => self.validator,
+            RewardKind::CommunityPool => community_pool_address(),
+        }
+    }
+}
+
+// Récompenses des validateurs : frais accumulés depuis la dernière distribution
+// par producteur, commissions en vigueur et changements de l'époque en cours
+#[derive(Clone, Debug, Default)]
+pub struct Rewards {
+    commissions: HashMap<PublicKey, u64>,
+    scheduled: HashMap<PublicKey, u64>,
+    accrued: HashMap<PublicKey, u64>,
+}
+
+impl Rewards {
+    pub fn commission(&self, validator: &PublicKey) -> u64 {
+        self.commissions.get(validator).copied().unwrap_or(DEFAULT_COMMISSION_PERCENT)
+    }
+
+    pub fn accrued(&self, validator: &PublicKey) -> u64 {
+        self.accrued.get(validator).copied().unwrap_or(0)
+    }
+
+    // Frais des transactions d'un bloc, versés à la réserve des récompenses
+    pub fn accrue(&mut self, producer: &PublicKey, fees: u64) {
+        if fees > 0 {
+            let accrued = self.accrued.entry(*producer).or_default();
+            *accrued = accrued.saturating_add(fees);
+        }
+    }
+
+    // Changements de commission de validateurs enregistrés, d'au plus 100 %,
+    // signés pour l'époque de `height`, au plus un par validateur et par époque
+    pub fn validate(&self, validators: &HashMap<PublicKey, Validator>, transactions: &[GovernanceTransaction], height: u64) -> Result<(), &'static str> {
+        let mut changed = HashSet::new();
+        for transaction in transactions {
+            let GovernanceTransaction::SetCommission(change) = transaction else {
+                continue;
+            };
+            if !validators.contains_key(&change.validator) {
+                return Err("Commission change of unknown validator");
+            }
+            if change.percent > 100 {
+                return Err("Commission must be a percentage");
+            }
+            if change.epoch != epoch_of(height) {
+                return Err("Commission change for another epoch");
+            }
+            if self.scheduled.contains_key(&change.validator) || !changed.insert(change.validator.to_bytes()) {
+                return Err("Commission already changed this epoch");
+            }
+        }
+        Ok(())
+    }
+
+    pub fn apply(&mut self, transactions: &[GovernanceTransaction]) {
+        for transaction in transactions {
+            if let GovernanceTransaction::SetCommission(change) = transaction {
+                self.scheduled.insert(change.validator, change.percent);
+            }
+        }
+    }
+
+    // Les frais accumulés et la commission d'un validateur passent à sa nouvelle clé
+    pub fn rekey(&mut self, old_key: &PublicKey, new_key: PublicKey) {
+        if let Some(fees) = self.accrued.remove(old_key) {
+            self.accrue(&new_key, fees);
+        }
+        if let Some(percent) = self.commissions.remove(old_key) {
+            self.commissions.entry(new_key).or_insert(percent);
+        }
+        if let Some(percent) = self.scheduled.remove(old_key) {
+            self.scheduled.entry(new_key).or_insert(percent);
+        }
+    }
+
+    // Répartit les frais accumulés au bloc `height` : `community_percent` % vont
+    // à la réserve communautaire, la commission du validateur est prélevée sur
+    // le reste, qui revient à sa mise. Les changements de commission de l'époque
+    // prennent ensuite effet. Crédits par validateur dans l'ordre des clés.
+    pub fn distribute(&mut self, height: u64, community_percent: u64) -> Vec<RewardCredit> {
+        let mut accrued: Vec<(PublicKey, u64)> = self.accrued.drain().collect();
+        accrued.sort_by_key(|(validator, _)| validator.to_bytes());
+        let mut credits = Vec::new();
+        for (validator, fees) in accrued {
+            let community = (fees as u128 * community_percent as u128 / 100) as u64;
+            let commission = ((fees - community) as u128 * self.commission(&validator) as u128 / 100) as u64;
+            let stake = fees - community - commission;
+            for (kind, amount) in [(RewardKind::Commission, commission), (RewardKind::Stake, stake), (RewardKind::CommunityPool, community)] {
+                if amount > 0 {
+                    credits.push(RewardCredit { height, validator, kind, amount });
+                }
+            }
+        }
+        self.commissions.extend(self.scheduled.drain());
+        credits
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        for entries in [&self.commissions, &self.scheduled, &self.accrued] {
+            let mut entries: Vec<(&PublicKey, &u64)> = entries.iter().collect();
+            entries.sort_by_key(|(validator, _)| validator.to_bytes());
+            encoder.put_u32(entries.len() as u32);
+            for (validator, value) in entries {
+                encoder.put_public_key(validator);
+                encoder.put_u64(*value);
+            }
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Rewards, &'static str> {
+        let mut maps = Vec::with_capacity(3);
+        for _ in 0..3 {
+            let mut entries = HashMap::new();
+            let mut previous: Option<[u8; 32]> = None;
+            for _ in 0..decoder.get_u32()? {
+                let validator = decoder.get_public_key()?;
+                if previous.is_some_and(|previous| previous >= validator.to_bytes()) {
+                    return Err("Rewards not in canonical order");
+                }
+                previous = Some(validator.to_bytes());
+                entries.insert(validator, decoder.get_u64()?);
+            }
+            maps.push(entries);
+        }
+        let accrued = maps.pop().unwrap();
+        let scheduled = maps.pop().unwrap();
+        let commissions = maps.pop().unwrap();
+        if commissions.values().chain(scheduled.values()).any(|percent| *percent > 100) {
+            return Err("Commission must be a percentage");
+        }
+        Ok(Rewards { commissions, scheduled, accrued })
+    }
+}
+
+// Crédits de récompense reçus par chaque adresse, dans l'ordre de la chaîne,
+// depuis le démarrage (ou depuis l'instantané de départ) ; hors état
+#[derive(Clone, Debug, Default)]
+pub struct RewardHistory {
+    credits: HashMap<PublicKey, Vec<RewardCredit>>,
+}
+
+impl RewardHistory {
+    pub fn record(&mut self, credit: RewardCredit) {
+        self.credits.entry(credit.recipient()).or_default().push(credit);
+    }
+
+    pub fn credits(&self, address: &PublicKey) -> &[RewardCredit] {
+        self.credits.get(address).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn clear(&mut self) {
+        self.credits.clear();
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::{HashMap, HashSet};
+use ed25519_dalek::{PublicKey, Signature};
+
+use crate::beacon::Beacon;
+use crate::encoding::{Decoder, Encoder, KEY_ROTATION_DOMAIN};
+use crate::governance::GovernanceTransaction;
+use crate::signer::Signer;
+use crate::Validator;
+
+// Remplacement de la clé d'un validateur, signé par la clé en vigueur : mise,
+// score et activité passent à `new_key` après le bloc qui l'inclut
+#[derive(Clone, Debug)]
+pub struct KeyRotation {
+    pub validator: PublicKey,
+    pub new_key: PublicKey,
+    pub signature: Signature,
+}
+
+impl KeyRotation {
+    pub fn new(signer: &dyn Signer, new_key: PublicKey) -> Result<Self, &'static str> {
+        let validator = signer.public_key();
+        let signature = signer.sign(&Self::signing_hash(&validator, &new_key))?;
+        Ok(KeyRotation { validator, new_key, signature })
+    }
+
+    pub fn signing_hash(validator: &PublicKey, new_key: &PublicKey) -> Vec<u8> {
+        let mut encoder = Encoder::new(KEY_ROTATION_DOMAIN);
+        encoder.put_public_key(validator);
+        encoder.put_public_key(new_key);
+        encoder.hash()
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Self::signing_hash(&self.validator, &self.new_key)
+    }
+}
+
+// Historique des clés des validateurs, pour vérifier les blocs et certificats
+// signés avant une rotation. Une clé retirée ne peut plus être attribuée, ce qui
+// empêche aussi de rejouer une rotation.
+#[derive(Clone, Debug, Default)]
+pub struct KeyHistory {
+    // (hauteur du bloc appliquant la rotation, ancienne clé, nouvelle clé), dans l'ordre
+    rotations: Vec<(u64, PublicKey, PublicKey)>,
+}
+
+impl KeyHistory {
+    pub fn record(&mut self, height: u64, old_key: PublicKey, new_key: PublicKey) {
+        self.rotations.push((height, old_key, new_key));
+    }
+
+    pub fn is_retired(&self, key: &PublicKey) -> bool {
+        self.rotations.iter().any(|(_, old_key, _)| old_key == key)
+    }
+
+    // Clé avec laquelle le validateur dont la clé actuelle est `key` a signé le
+    // bloc `height` ; une rotation appliquée au bloc `h` prend effet en `h + 1`
+    pub fn key_at(&self, key: &PublicKey, height: u64) -> PublicKey {
+        let mut key = *key;
+        for (rotated_at, old_key, new_key) in self.rotations.iter().rev() {
+            if *rotated_at < height {
+                break;
+            }
+            if *new_key == key {
+                key = *old_key;
+            }
+        }
+        key
+    }
+
+    // Rotations émises par des validateurs enregistrés, au plus une par validateur
+    // et par bloc, vers une clé jamais utilisée par un validateur. Un validateur
+    // engagé dans la balise de l'époque doit d'abord révéler son secret, dérivé de
+    // sa clé.
+    pub fn validate(&self, validators: &HashMap<PublicKey, Validator>, beacon: &Beacon, transactions: &[GovernanceTransaction]) -> Result<(), &'static str> {
+        let mut rotated = HashSet::new();
+        let mut new_keys = HashSet::new();
+        for transaction in transactions {
+            let GovernanceTransaction::RotateKey(rotation) = transaction else {
+                continue;
+            };
+            if !validators.contains_key(&rotation.validator) {
+                return Err("Key rotation of unknown validator");
+            }
+            if validators.contains_key(&rotation.new_key) || self.is_retired(&rotation.new_key) {
+                return Err("Key already used by a validator");
+            }
+            if beacon.awaits_reveal(&rotation.validator) {
+                return Err("Key rotation before beacon reveal");
+            }
+            if !rotated.insert(rotation.validator.to_bytes()) || !new_keys.insert(rotation.new_key.to_bytes()) {
+                return Err("Duplicate key rotation");
+            }
+        }
+        Ok(())
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        encoder.put_u32(self.rotations.len() as u32);
+        for (height, old_key, new_key) in &self.rotations {
+            encoder.put_u64(*height);
+            encoder.put_public_key(old_key);
+            encoder.put_public_key(new_key);
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<KeyHistory, &'static str> {
+        let mut rotations: Vec<(u64, PublicKey, PublicKey)> = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            let height = decoder.get_u64()?;
+            if rotations.last().is_some_and(|(previous, _, _)| *previous > height) {
+                return Err("Key rotations not in canonical order");
+            }
+            rotations.push((height, decoder.get_public_key()?, decoder.get_public_key()?));
+        }
+        Ok(KeyHistory { rotations })
+    }
+}
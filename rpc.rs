@@ -0,0 +1,846 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use ed25519_dalek::{PublicKey, Signature};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{mpsc, Mutex};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::{Channel, Server};
+use tonic::{Request, Response, Status};
+
+use crate::buildinfo::{CONSENSUS_RULES_VERSION, PACKAGE_VERSION};
+use crate::anchors::document_hash;
+use crate::assets::{asset_events, AssetEvent};
+use crate::bundle::ProofBundle;
+use crate::encoding::{decode_block_body, encode_block, encode_block_body};
+use crate::events::ChainEvent;
+use crate::governance::GovernanceTransaction;
+use crate::indexer::Page;
+use crate::lifecycle;
+use crate::mempool;
+use crate::metrics::METRICS;
+use crate::privacy::{EncryptedPayload, PrivacyGroup, PrivateTransaction};
+use crate::receipts::{Receipt, ReceiptStatus};
+use crate::upgrade::PROTOCOL_VERSION;
+use crate::{Block, Blockchain, Expiry, Transaction, TransactionKind};
+
+pub mod proto {
+    tonic::include_proto!("supplyx.query");
+}
+
+use proto::query_client::QueryClient;
+use proto::query_server::{Query, QueryServer};
+use proto::submission_client::SubmissionClient;
+use proto::submission_server::{Submission, SubmissionServer};
+use proto::proof_bundle_request::Fact;
+use proto::receipt_event::Event;
+use proto::{
+    verify_document_request, AddressDocuments, AssetRequest, AddressDocumentsRequest, AddressTransactions, AddressTransactionsRequest, BalanceAt, BalanceAtRequest, BlockRequest, BuildInfo, BuildInfoRequest, Bucket,
+    ContractExecuted, FingerprintMismatch, IncidentRequest, IncludedTransaction, KindCount, MempoolStatus, MempoolStatusRequest, NextNonce, NextNonceRequest, PageInfo, PrivateState, PrivateStateRequest, PrivateTransactionRequest, GovernanceTransactionRequest,
+    DocumentAnchor, DocumentAnchors, PageRequest, ProofBundleRequest,
+    ReceiptEvent, ReceiptRequest, RewardHistory, RewardHistoryRequest, StateProofRequest, SubmissionResult, SubmitBatchRequest, SubmitBatchResponse, SubscribeAssetEventsRequest, SubscribeBlocksRequest, TimelineEntry, TransactionRequest, TransactionStatus, UpgradeStatus,
+    UpgradeStatusRequest, ValidatorBlocks, ValidatorBlocksRequest, VerifyDocumentRequest, VersionSignal,
+};
+
+// Blocs en attente d'envoi par abonné
+const SUBSCRIPTION_BUFFER: usize = 256;
+
+// Nombre maximal de transactions d'un lot soumis
+const MAX_BATCH_TRANSACTIONS: usize = 10_000;
+
+fn transaction_message(transaction: &Transaction) -> proto::Transaction {
+    let (valid_until_height, valid_until_timestamp) = match transaction.valid_until {
+        Expiry::Never => (None, None),
+        Expiry::Height(height) => (Some(height), None),
+        Expiry::Timestamp(timestamp) => (None, Some(timestamp)),
+    };
+    proto::Transaction {
+        hash: transaction.hash(),
+        sender: transaction.sender.to_bytes().to_vec(),
+        recipient: transaction.recipient.to_bytes().to_vec(),
+        amount: transaction.amount,
+        kind: mempool::kind_name(&transaction.kind).to_string(),
+        gas_price: transaction.gas_price,
+        timestamp: transaction.timestamp,
+        valid_until_height,
+        valid_until_timestamp,
+        bundle: transaction.inner_transactions().iter().map(transaction_message).collect(),
+        valid_from_height: transaction.valid_from,
+        nonce: transaction.nonce,
+    }
+}
+
+fn anchor_message(block: &Block, transaction: &Transaction) -> Option<DocumentAnchor> {
+    let TransactionKind::Anchor { document, cid } = &transaction.kind else {
+        return None;
+    };
+    Some(DocumentAnchor {
+        transaction_hash: transaction.hash(),
+        block_index: block.index,
+        block_timestamp: block.timestamp,
+        sender: transaction.sender.to_bytes().to_vec(),
+        recipient: transaction.recipient.to_bytes().to_vec(),
+        document_hash: document.clone(),
+        cid: cid.clone(),
+    })
+}
+
+fn block_message(block: &Block) -> proto::Block {
+    proto::Block {
+        index: block.index,
+        slot: block.slot,
+        timestamp: block.timestamp,
+        hash: block.current_hash.clone(),
+        previous_hash: block.previous_hash.clone(),
+        validator: block.validator_pubkey.to_bytes().to_vec(),
+        transactions_root: block.transactions_root.clone(),
+        state_root: block.state_root.clone(),
+        transactions: block.transactions.iter().map(transaction_message).collect(),
+        private_transaction_count: block.private_transactions.len() as u32,
+        governance_transaction_count: block.governance_transactions.len() as u32,
+        encoded: encode_block(block),
+        difficulty: block.difficulty,
+    }
+}
+
+fn asset_event_message(event: AssetEvent) -> proto::AssetEvent {
+    proto::AssetEvent {
+        asset: event.asset,
+        kind: event.kind.name().to_string(),
+        account: event.account.to_bytes().to_vec(),
+        block_index: event.block_index,
+        transaction_hash: event.transaction_hash,
+    }
+}
+
+fn receipt_message(receipt: &Receipt) -> proto::Receipt {
+    let (success, failure_reason) = match receipt.status {
+        ReceiptStatus::Success => (true, String::new()),
+        ReceiptStatus::Failed(reason) => (false, reason.to_string()),
+    };
+    proto::Receipt {
+        transaction_hash: receipt.transaction_hash.clone(),
+        block_index: receipt.block_index,
+        position: receipt.position,
+        success,
+        failure_reason,
+        gas_used: receipt.gas_used,
+        gas_price: receipt.gas_price,
+        fee: receipt.fee,
+        events: receipt.events.iter().filter_map(event_message).collect(),
+    }
+}
+
+fn event_message(event: &ChainEvent) -> Option<ReceiptEvent> {
+    let event = match event {
+        ChainEvent::ContractExecuted { contract, caller, output, gas_used } => Event::ContractExecuted(ContractExecuted {
+            contract: contract.to_bytes().to_vec(),
+            caller: caller.to_bytes().to_vec(),
+            output: output.clone(),
+            gas_used: *gas_used,
+        }),
+        _ => return None,
+    };
+    Some(ReceiptEvent { event: Some(event) })
+}
+
+fn page_bounds(page: Option<PageRequest>) -> (usize, usize) {
+    let page = page.unwrap_or_default();
+    (page.offset.try_into().unwrap_or(usize::MAX), page.limit.try_into().unwrap_or(usize::MAX))
+}
+
+fn page_info<T>(page: &Page<T>) -> PageInfo {
+    PageInfo { total: page.total as u64, next_offset: page.next_offset.map(|offset| offset as u64) }
+}
+
+fn buckets(buckets: &[mempool::Bucket]) -> Vec<Bucket> {
+    buckets.iter().map(|bucket| Bucket { min: bucket.min, count: bucket.count as u64 }).collect()
+}
+
+pub struct QueryService {
+    blockchain: Arc<Mutex<Blockchain>>,
+}
+
+#[tonic::async_trait]
+impl Query for QueryService {
+    type SubscribeBlocksStream = ReceiverStream<Result<proto::Block, Status>>;
+    type SubscribeAssetEventsStream = ReceiverStream<Result<proto::AssetEvent, Status>>;
+
+    async fn get_receipt(&self, request: Request<ReceiptRequest>) -> Result<Response<proto::Receipt>, Status> {
+        let _timer = METRICS.rpc_timer("GetReceipt");
+        let hash = request.into_inner().transaction_hash;
+        let chain = self.blockchain.lock().await;
+        let receipt = chain.get_receipt(&hash).ok_or_else(|| Status::not_found("Unknown or pending transaction"))?;
+        Ok(Response::new(receipt_message(receipt)))
+    }
+
+    async fn get_upgrade_status(&self, _request: Request<UpgradeStatusRequest>) -> Result<Response<UpgradeStatus>, Status> {
+        let _timer = METRICS.rpc_timer("GetUpgradeStatus");
+        let chain = self.blockchain.lock().await;
+        let (candidate_version, ready_epochs) = chain.upgrades.candidate().unwrap_or((0, 0));
+        Ok(Response::new(UpgradeStatus {
+            active_version: chain.upgrades.active_version(),
+            candidate_version,
+            ready_epochs,
+            threshold_percent: chain.governance.params.upgrade_threshold,
+            required_epochs: chain.governance.params.upgrade_epochs,
+            signals: chain.upgrades.summary(&chain.validators).into_iter()
+                .map(|signal| VersionSignal { version: signal.version, stake: signal.stake, share: signal.share })
+                .collect(),
+        }))
+    }
+
+    async fn get_address_transactions(&self, request: Request<AddressTransactionsRequest>) -> Result<Response<AddressTransactions>, Status> {
+        let _timer = METRICS.rpc_timer("GetAddressTransactions");
+        let request = request.into_inner();
+        let address = PublicKey::from_bytes(&request.address).map_err(|_| Status::invalid_argument("Invalid address"))?;
+        let (offset, limit) = page_bounds(request.page);
+        let chain = self.blockchain.lock().await;
+        let page = chain.transactions_by_address(&address, offset, limit);
+        Ok(Response::new(AddressTransactions { page: Some(page_info(&page)), transaction_hashes: page.items }))
+    }
+
+    async fn get_validator_blocks(&self, request: Request<ValidatorBlocksRequest>) -> Result<Response<ValidatorBlocks>, Status> {
+        let _timer = METRICS.rpc_timer("GetValidatorBlocks");
+        let request = request.into_inner();
+        let validator = PublicKey::from_bytes(&request.validator).map_err(|_| Status::invalid_argument("Invalid validator key"))?;
+        let (offset, limit) = page_bounds(request.page);
+        let chain = self.blockchain.lock().await;
+        let page = chain.blocks_by_validator(&validator, offset, limit);
+        Ok(Response::new(ValidatorBlocks { page: Some(page_info(&page)), block_indexes: page.items }))
+    }
+
+    async fn get_mempool_status(&self, _request: Request<MempoolStatusRequest>) -> Result<Response<MempoolStatus>, Status> {
+        let _timer = METRICS.rpc_timer("GetMempoolStatus");
+        let status = self.blockchain.lock().await.mempool_status();
+        Ok(Response::new(MempoolStatus {
+            size: status.size as u64,
+            bytes: status.bytes as u64,
+            gas: status.gas,
+            fee_histogram: buckets(&status.fee_histogram),
+            age_histogram: buckets(&status.age_histogram),
+            kinds: status.kinds.iter().map(|(kind, count)| KindCount { kind: kind.to_string(), count: *count as u64 }).collect(),
+            congestion: status.congestion,
+        }))
+    }
+
+    async fn get_incident(&self, request: Request<IncidentRequest>) -> Result<Response<proto::Incident>, Status> {
+        let _timer = METRICS.rpc_timer("GetIncident");
+        let incident_id = request.into_inner().incident_id;
+        let chain = self.blockchain.lock().await;
+        let incident = chain.incident(&incident_id).ok_or_else(|| Status::not_found("Unknown incident"))?;
+        let notes = chain.incident_notes(&incident_id);
+        Ok(Response::new(proto::Incident {
+            incident_id,
+            opener: incident.opener.to_bytes().to_vec(),
+            responders: incident.responders.members.iter().map(|member| member.to_bytes().to_vec()).collect(),
+            opened_at: incident.opened_at,
+            timeline: incident.timeline.iter()
+                .map(|entry| TimelineEntry {
+                    author: entry.author.to_bytes().to_vec(),
+                    height: entry.height,
+                    note_hash: entry.note_hash.clone(),
+                    note: notes.iter()
+                        .find(|(_, note)| note.hash() == entry.note_hash)
+                        .map(|(_, note)| note.note.to_bytes())
+                        .unwrap_or_default(),
+                })
+                .collect(),
+            head: incident.head.clone(),
+            closed_at: incident.closed_at,
+        }))
+    }
+
+    async fn get_build_info(&self, _request: Request<BuildInfoRequest>) -> Result<Response<BuildInfo>, Status> {
+        let _timer = METRICS.rpc_timer("GetBuildInfo");
+        let chain = self.blockchain.lock().await;
+        let mut mismatches: Vec<FingerprintMismatch> = chain.rules.mismatches().iter()
+            .map(|(validator, mismatch)| FingerprintMismatch {
+                validator: validator.to_bytes().to_vec(),
+                fingerprint: mismatch.fingerprint.clone(),
+                height: mismatch.height,
+            })
+            .collect();
+        mismatches.sort_by(|a, b| a.validator.cmp(&b.validator));
+        Ok(Response::new(BuildInfo {
+            package_version: PACKAGE_VERSION.to_string(),
+            rules_version: CONSENSUS_RULES_VERSION,
+            protocol_version: PROTOCOL_VERSION,
+            rules_fingerprint: chain.rules.fingerprint().to_vec(),
+            mismatches,
+        }))
+    }
+
+    async fn get_proof_bundle(&self, request: Request<ProofBundleRequest>) -> Result<Response<proto::ProofBundle>, Status> {
+        let _timer = METRICS.rpc_timer("GetProofBundle");
+        let chain = self.blockchain.lock().await;
+        let bundle = match request.into_inner().fact {
+            Some(Fact::TransactionHash(hash)) => ProofBundle::transaction(&chain, &hash),
+            Some(Fact::Account(account)) => {
+                let account = PublicKey::from_bytes(&account).map_err(|_| Status::invalid_argument("Invalid account"))?;
+                ProofBundle::balance(&chain, &account)
+            }
+            None => return Err(Status::invalid_argument("Missing fact")),
+        };
+        let bundle = bundle.map_err(Status::failed_precondition)?;
+        Ok(Response::new(proto::ProofBundle {
+            bundle: bundle.encode(),
+            validator_set_hash: bundle.validator_set_hash(),
+        }))
+    }
+
+    async fn get_balance_at(&self, request: Request<BalanceAtRequest>) -> Result<Response<BalanceAt>, Status> {
+        let _timer = METRICS.rpc_timer("GetBalanceAt");
+        let request = request.into_inner();
+        let account = PublicKey::from_bytes(&request.account).map_err(|_| Status::invalid_argument("Invalid account"))?;
+        let balance = self.blockchain.lock().await.balance_at(&account, request.height).map_err(Status::failed_precondition)?;
+        Ok(Response::new(BalanceAt { balance }))
+    }
+
+    async fn get_state_proof(&self, request: Request<StateProofRequest>) -> Result<Response<proto::StateProof>, Status> {
+        let _timer = METRICS.rpc_timer("GetStateProof");
+        let account = PublicKey::from_bytes(&request.into_inner().account).map_err(|_| Status::invalid_argument("Invalid account"))?;
+        let chain = self.blockchain.lock().await;
+        let (height, proof) = chain.balance_proof(&account);
+        Ok(Response::new(proto::StateProof {
+            height,
+            balance: chain.balance(&account),
+            state_root: chain.state_tree.root().to_vec(),
+            siblings: proof.siblings,
+            leaf_account: proof.leaf.map(|(leaf, _)| leaf.to_vec()),
+            leaf_balance: proof.leaf.map_or(0, |(_, balance)| balance),
+        }))
+    }
+
+    async fn get_next_nonce(&self, request: Request<NextNonceRequest>) -> Result<Response<NextNonce>, Status> {
+        let _timer = METRICS.rpc_timer("GetNextNonce");
+        let sender = PublicKey::from_bytes(&request.into_inner().sender).map_err(|_| Status::invalid_argument("Invalid sender"))?;
+        let nonce = self.blockchain.lock().await.next_nonce(&sender);
+        Ok(Response::new(NextNonce { nonce }))
+    }
+
+    async fn get_private_state(&self, request: Request<PrivateStateRequest>) -> Result<Response<PrivateState>, Status> {
+        let _timer = METRICS.rpc_timer("GetPrivateState");
+        let group_id = request.into_inner().group_id;
+        let state = self.blockchain.lock().await.private_state(&group_id).cloned().unwrap_or_default();
+        Ok(Response::new(PrivateState { state }))
+    }
+
+    async fn get_block(&self, request: Request<BlockRequest>) -> Result<Response<proto::Block>, Status> {
+        let _timer = METRICS.rpc_timer("GetBlock");
+        let index = request.into_inner().index;
+        let chain = self.blockchain.lock().await;
+        let block = chain.block_at(index).ok_or_else(|| Status::not_found("Unknown block"))?;
+        Ok(Response::new(block_message(block)))
+    }
+
+    async fn get_transaction(&self, request: Request<TransactionRequest>) -> Result<Response<IncludedTransaction>, Status> {
+        let _timer = METRICS.rpc_timer("GetTransaction");
+        let hash = request.into_inner().transaction_hash;
+        let chain = self.blockchain.lock().await;
+        let receipt = chain.get_receipt(&hash).ok_or_else(|| Status::not_found("Unknown or pending transaction"))?;
+        let (_, transaction) = chain.find_transaction(&hash).ok_or_else(|| Status::not_found("Block no longer stored"))?;
+        Ok(Response::new(IncludedTransaction {
+            transaction: Some(transaction_message(transaction)),
+            block_index: receipt.block_index,
+            position: receipt.position,
+        }))
+    }
+
+    async fn get_transaction_status(&self, request: Request<TransactionRequest>) -> Result<Response<TransactionStatus>, Status> {
+        let _timer = METRICS.rpc_timer("GetTransactionStatus");
+        let hash = request.into_inner().transaction_hash;
+        let chain = self.blockchain.lock().await;
+        let status = chain.transaction_status(&hash).ok_or_else(|| Status::not_found("Unknown transaction"))?;
+        let (block_index, reason) = match status {
+            lifecycle::TransactionStatus::Included { height } => (Some(height), ""),
+            lifecycle::TransactionStatus::Dropped { reason } => (None, reason),
+            lifecycle::TransactionStatus::Pending | lifecycle::TransactionStatus::Expired => (None, ""),
+        };
+        let encoded = chain.dead_letters.get(&hash)
+            .map(|(transaction, _)| encode_block_body(std::slice::from_ref(transaction)))
+            .unwrap_or_default();
+        Ok(Response::new(TransactionStatus {
+            status: status.name().to_string(),
+            block_index,
+            reason: reason.to_string(),
+            encoded,
+        }))
+    }
+
+    async fn verify_document(&self, request: Request<VerifyDocumentRequest>) -> Result<Response<DocumentAnchors>, Status> {
+        let _timer = METRICS.rpc_timer("VerifyDocument");
+        let document_hash = match request.into_inner().document {
+            Some(verify_document_request::Document::Content(content)) => document_hash(&content),
+            Some(verify_document_request::Document::Hash(hash)) if hash.len() == 32 => hash,
+            _ => return Err(Status::invalid_argument("Document content or 32-byte SHA3-256 hash required")),
+        };
+        let chain = self.blockchain.lock().await;
+        let anchors = chain.document_anchors(&document_hash).into_iter()
+            .filter_map(|(block, transaction)| anchor_message(block, transaction))
+            .collect();
+        Ok(Response::new(DocumentAnchors { document_hash, anchors }))
+    }
+
+    async fn get_address_documents(&self, request: Request<AddressDocumentsRequest>) -> Result<Response<AddressDocuments>, Status> {
+        let _timer = METRICS.rpc_timer("GetAddressDocuments");
+        let request = request.into_inner();
+        let address = PublicKey::from_bytes(&request.address).map_err(|_| Status::invalid_argument("Invalid address"))?;
+        let (offset, limit) = page_bounds(request.page);
+        let chain = self.blockchain.lock().await;
+        let page = chain.documents_by_address(&address, offset, limit);
+        let anchors = page.items.iter()
+            .filter_map(|hash| chain.find_transaction(hash))
+            .filter_map(|(block, transaction)| anchor_message(block, transaction))
+            .collect();
+        Ok(Response::new(AddressDocuments { page: Some(page_info(&page)), anchors }))
+    }
+
+    async fn get_reward_history(&self, request: Request<RewardHistoryRequest>) -> Result<Response<RewardHistory>, Status> {
+        let _timer = METRICS.rpc_timer("GetRewardHistory");
+        let request = request.into_inner();
+        let address = PublicKey::from_bytes(&request.address).map_err(|_| Status::invalid_argument("Invalid address"))?;
+        let (offset, limit) = page_bounds(request.page);
+        let chain = self.blockchain.lock().await;
+        let page = chain.reward_history(&address, offset, limit);
+        let (commission_percent, accrued) = if chain.validators.contains_key(&address) {
+            let (commission, accrued) = chain.validator_rewards(&address);
+            (Some(commission), Some(accrued))
+        } else {
+            (None, None)
+        };
+        let credits = page.items.iter()
+            .map(|credit| proto::RewardCredit {
+                height: credit.height,
+                validator: credit.validator.to_bytes().to_vec(),
+                kind: credit.kind.name().to_string(),
+                amount: credit.amount,
+            })
+            .collect();
+        Ok(Response::new(RewardHistory { page: Some(page_info(&page)), credits, commission_percent, accrued }))
+    }
+
+    async fn subscribe_blocks(&self, request: Request<SubscribeBlocksRequest>) -> Result<Response<Self::SubscribeBlocksStream>, Status> {
+        let _timer = METRICS.rpc_timer("SubscribeBlocks");
+        let from_height = request.into_inner().from_height;
+        let (sender, receiver) = mpsc::channel(SUBSCRIPTION_BUFFER);
+
+        // Abonnement pris sous le verrou : aucun bloc ne peut être manqué entre
+        // l'historique et le flux en direct
+        let chain = self.blockchain.lock().await;
+        if from_height < chain.first_index() {
+            return Err(Status::out_of_range("Blocks before the local snapshot are not available"));
+        }
+        let mut events = chain.subscribe();
+        let history: Vec<proto::Block> = chain.chain.iter().filter(|block| block.index >= from_height).map(block_message).collect();
+        let mut next_index = chain.next_index().max(from_height);
+        drop(chain);
+
+        tokio::spawn(async move {
+            for block in history {
+                if sender.send(Ok(block)).await.is_err() {
+                    return;
+                }
+            }
+
+            loop {
+                let block = match events.recv().await {
+                    Ok(ChainEvent::BlockCommitted(block)) if block.index >= next_index => {
+                        next_index = block.index + 1;
+                        block_message(&block)
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(_)) => {
+                        let _ = sender.send(Err(Status::data_loss("Subscriber fell behind, resubscribe"))).await;
+                        return;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+                if sender.send(Ok(block)).await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    async fn get_asset(&self, request: Request<AssetRequest>) -> Result<Response<proto::Asset>, Status> {
+        let _timer = METRICS.rpc_timer("GetAsset");
+        let id = request.into_inner().asset;
+        let chain = self.blockchain.lock().await;
+        let asset = chain.asset(&id).ok_or_else(|| Status::not_found("Unknown asset"))?;
+        Ok(Response::new(proto::Asset {
+            owner: asset.owner.to_bytes().to_vec(),
+            readers: asset.readers.iter().map(|reader| reader.to_bytes().to_vec()).collect(),
+            asset: id,
+        }))
+    }
+
+    async fn subscribe_asset_events(&self, request: Request<SubscribeAssetEventsRequest>) -> Result<Response<Self::SubscribeAssetEventsStream>, Status> {
+        let _timer = METRICS.rpc_timer("SubscribeAssetEvents");
+        let SubscribeAssetEventsRequest { from_height, asset } = request.into_inner();
+        let (sender, receiver) = mpsc::channel(SUBSCRIPTION_BUFFER);
+        let selected = move |event: &AssetEvent| asset.is_empty() || event.asset == asset;
+
+        // Même garantie que SubscribeBlocks : abonnement pris sous le verrou
+        let chain = self.blockchain.lock().await;
+        if from_height < chain.first_index() {
+            return Err(Status::out_of_range("Blocks before the local snapshot are not available"));
+        }
+        let mut events = chain.subscribe();
+        let history: Vec<AssetEvent> = chain.chain.iter().filter(|block| block.index >= from_height).flat_map(asset_events).filter(&selected).collect();
+        let mut next_index = chain.next_index().max(from_height);
+        drop(chain);
+
+        tokio::spawn(async move {
+            for event in history {
+                if sender.send(Ok(asset_event_message(event))).await.is_err() {
+                    return;
+                }
+            }
+
+            loop {
+                let block = match events.recv().await {
+                    Ok(ChainEvent::BlockCommitted(block)) if block.index >= next_index => block,
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(_)) => {
+                        let _ = sender.send(Err(Status::data_loss("Subscriber fell behind, resubscribe"))).await;
+                        return;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+                next_index = block.index + 1;
+                for event in asset_events(&block).into_iter().filter(&selected) {
+                    if sender.send(Ok(asset_event_message(event))).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+pub struct SubmissionService {
+    blockchain: Arc<Mutex<Blockchain>>,
+}
+
+#[tonic::async_trait]
+impl Submission for SubmissionService {
+    async fn submit_batch(&self, request: Request<SubmitBatchRequest>) -> Result<Response<SubmitBatchResponse>, Status> {
+        let _timer = METRICS.rpc_timer("SubmitBatch");
+        let transactions = decode_block_body(&request.into_inner().transactions).map_err(Status::invalid_argument)?;
+        if transactions.len() > MAX_BATCH_TRANSACTIONS {
+            return Err(Status::invalid_argument(format!("At most {} transactions per batch", MAX_BATCH_TRANSACTIONS)));
+        }
+        let hashes: Vec<Vec<u8>> = transactions.iter().map(Transaction::hash).collect();
+        let verdicts = self.blockchain.lock().await.submit_batch(transactions);
+        let results: Vec<SubmissionResult> = hashes.into_iter().zip(verdicts)
+            .map(|(transaction_hash, verdict)| SubmissionResult {
+                transaction_hash,
+                accepted: verdict.is_ok(),
+                error: verdict.err().unwrap_or_default().to_string(),
+            })
+            .collect();
+        Ok(Response::new(SubmitBatchResponse {
+            accepted: results.iter().filter(|result| result.accepted).count() as u32,
+            results,
+        }))
+    }
+
+    async fn submit_private_transaction(&self, request: Request<PrivateTransactionRequest>) -> Result<Response<SubmissionResult>, Status> {
+        let _timer = METRICS.rpc_timer("SubmitPrivateTransaction");
+        let transaction = private_transaction(request.into_inner()).map_err(Status::invalid_argument)?;
+        let transaction_hash = transaction.hash();
+        let verdict = self.blockchain.lock().await.add_private_transaction(transaction);
+        Ok(Response::new(SubmissionResult {
+            transaction_hash,
+            accepted: verdict.is_ok(),
+            error: verdict.err().unwrap_or_default().to_string(),
+        }))
+    }
+
+    async fn submit_governance_transaction(&self, request: Request<GovernanceTransactionRequest>) -> Result<Response<SubmissionResult>, Status> {
+        let _timer = METRICS.rpc_timer("SubmitGovernanceTransaction");
+        let transaction = GovernanceTransaction::from_bytes(&request.into_inner().transaction).map_err(Status::invalid_argument)?;
+        let transaction_hash = transaction.hash();
+        let verdict = self.blockchain.lock().await.add_governance_transaction(transaction);
+        Ok(Response::new(SubmissionResult {
+            transaction_hash,
+            accepted: verdict.is_ok(),
+            error: verdict.err().unwrap_or_default().to_string(),
+        }))
+    }
+}
+
+fn private_transaction(request: PrivateTransactionRequest) -> Result<PrivateTransaction, &'static str> {
+    if request.members.len() != request.endorsements.len() {
+        return Err("Private transaction not endorsed by all members");
+    }
+    let members = request.members.iter()
+        .map(|member| PublicKey::from_bytes(member).map_err(|_| "Invalid privacy group member"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let endorsements = request.endorsements.iter()
+        .map(|signature| Signature::from_bytes(signature).map_err(|_| "Invalid private transaction endorsement"))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(PrivateTransaction {
+        group: PrivacyGroup { members },
+        payload_hash: request.payload_hash,
+        previous_state: request.previous_state,
+        new_state: request.new_state,
+        endorsements,
+    })
+}
+
+pub async fn serve(addr: SocketAddr, blockchain: Arc<Mutex<Blockchain>>) -> Result<(), &'static str> {
+    Server::builder()
+        .add_service(QueryServer::new(QueryService { blockchain: blockchain.clone() }))
+        .add_service(SubmissionServer::new(SubmissionService { blockchain }))
+        .serve(addr)
+        .await
+        .map_err(|_| "RPC server failed")
+}
+
+// Nœud distant : statuts par `GetTransactionStatus`, soumissions par `SubmitBatch`
+pub struct RpcRelay {
+    query: QueryClient<Channel>,
+    submission: SubmissionClient<Channel>,
+}
+
+impl RpcRelay {
+    pub async fn connect(endpoint: &str) -> Result<Self, String> {
+        let connect_error = |e: tonic::transport::Error| format!("{}: {}", endpoint, e);
+        Ok(RpcRelay {
+            query: QueryClient::connect(endpoint.to_string()).await.map_err(connect_error)?,
+            submission: SubmissionClient::connect(endpoint.to_string()).await.map_err(connect_error)?,
+        })
+    }
+
+    // Nonce suivant le plus grand connu du nœud pour `sender`
+    pub async fn next_nonce(&mut self, sender: &PublicKey) -> Result<u64, String> {
+        let request = NextNonceRequest { sender: sender.to_bytes().to_vec() };
+        let response = self.query.get_next_nonce(request).await.map_err(|status| status.message().to_string())?;
+        Ok(response.into_inner().nonce)
+    }
+
+    // État privé actuel d'un groupe, vide avant sa première transaction privée
+    pub async fn private_state(&mut self, group: &PrivacyGroup) -> Result<Vec<u8>, String> {
+        let request = PrivateStateRequest { group_id: group.id() };
+        let response = self.query.get_private_state(request).await.map_err(|status| status.message().to_string())?;
+        Ok(response.into_inner().state)
+    }
+
+    pub async fn submit_private(&mut self, transaction: &PrivateTransaction) -> Result<Vec<u8>, String> {
+        let request = PrivateTransactionRequest {
+            members: transaction.group.members.iter().map(|member| member.to_bytes().to_vec()).collect(),
+            endorsements: transaction.endorsements.iter().map(|signature| signature.to_bytes().to_vec()).collect(),
+            payload_hash: transaction.payload_hash.clone(),
+            previous_state: transaction.previous_state.clone(),
+            new_state: transaction.new_state.clone(),
+        };
+        let result = self.submission.submit_private_transaction(request).await.map_err(|status| status.message().to_string())?.into_inner();
+        if !result.accepted {
+            return Err(result.error);
+        }
+        Ok(result.transaction_hash)
+    }
+
+    pub async fn submit_governance(&mut self, transaction: &GovernanceTransaction) -> Result<Vec<u8>, String> {
+        let request = GovernanceTransactionRequest { transaction: transaction.to_bytes() };
+        let result = self.submission.submit_governance_transaction(request).await.map_err(|status| status.message().to_string())?.into_inner();
+        if !result.accepted {
+            return Err(result.error);
+        }
+        Ok(result.transaction_hash)
+    }
+
+    // Notes d'un incident encore disponibles, dans l'ordre de sa chronologie
+    pub async fn incident_notes(&mut self, incident_id: &[u8]) -> Result<Vec<(u64, PublicKey, EncryptedPayload)>, String> {
+        let request = IncidentRequest { incident_id: incident_id.to_vec() };
+        let incident = self.query.get_incident(request).await.map_err(|status| status.message().to_string())?.into_inner();
+        let mut notes = Vec::new();
+        for entry in incident.timeline.iter().filter(|entry| !entry.note.is_empty()) {
+            let author = PublicKey::from_bytes(&entry.author).map_err(|e| e.to_string())?;
+            notes.push((entry.height, author, EncryptedPayload::from_bytes(&entry.note)?));
+        }
+        Ok(notes)
+    }
+}
+
+#[tonic::async_trait]
+impl lifecycle::TransactionRelay for RpcRelay {
+    async fn transaction_status(&mut self, hash: &[u8]) -> Result<Option<lifecycle::TransactionStatus>, String> {
+        let status = match self.query.get_transaction_status(TransactionRequest { transaction_hash: hash.to_vec() }).await {
+            Ok(response) => response.into_inner(),
+            Err(status) if status.code() == tonic::Code::NotFound => return Ok(None),
+            Err(status) => return Err(status.message().to_string()),
+        };
+        // Le motif d'un retrait, libre côté RPC, n'est pas repris
+        match (status.status.as_str(), status.block_index) {
+            ("pending", _) => Ok(Some(lifecycle::TransactionStatus::Pending)),
+            ("included", Some(height)) => Ok(Some(lifecycle::TransactionStatus::Included { height })),
+            ("dropped", _) => Ok(Some(lifecycle::TransactionStatus::Dropped { reason: "Dropped by the remote node" })),
+            ("expired", _) => Ok(Some(lifecycle::TransactionStatus::Expired)),
+            _ => Err(format!("Invalid transaction status: {}", status.status)),
+        }
+    }
+
+    async fn submit(&mut self, transaction: Transaction) -> Result<(), String> {
+        let request = SubmitBatchRequest { transactions: encode_block_body(&[transaction]) };
+        let response = self.submission.submit_batch(request).await.map_err(|status| status.message().to_string())?;
+        match response.into_inner().results.into_iter().next() {
+            Some(result) if result.accepted => Ok(()),
+            Some(result) => Err(result.error),
+            None => Err("Empty submission response".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+    use tokio::sync::Mutex;
+    use tokio_stream::StreamExt;
+    use tonic::Request;
+
+    use super::proto::query_server::Query;
+    use super::proto::submission_server::Submission;
+    use super::proto::{AssetRequest, GovernanceTransactionRequest, IncidentRequest, SubscribeAssetEventsRequest};
+    use super::{QueryService, SubmissionService};
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::governance::{GovernanceTransaction, ParameterChange, Proposal, Vote};
+    use crate::incidents::{IncidentNote, IncidentOpen};
+    use crate::privacy::{EncryptedPayload, PrivacyGroup};
+    use crate::txbuilder::TxBuilder;
+    use crate::Blockchain;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    fn submit(chain: &mut Blockchain, signer: &Keypair, builder: TxBuilder) -> Vec<u8> {
+        let transaction = builder.chain_id(chain.config.chain_id).nonce(chain.next_nonce(&signer.public)).timestamp(chain.now()).sign(signer).unwrap();
+        let hash = transaction.hash();
+        chain.add_transaction(transaction).unwrap();
+        hash
+    }
+
+    #[tokio::test]
+    async fn streams_the_events_of_an_asset() {
+        let (validator, owner, reader) = (keypair(1), keypair(2), keypair(3));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain.allocate(&owner.public, 1_000_000);
+        chain.allocate(&reader.public, 1_000_000);
+        let produce = |chain: &mut Blockchain, slot: u64| {
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            chain.validate_and_create_block(&validator, slot).unwrap();
+        };
+
+        let asset = submit(&mut chain, &owner, TxBuilder::register_asset(Vec::new()).to(owner.public));
+        submit(&mut chain, &owner, TxBuilder::register_asset(vec![reader.public]).to(owner.public));
+        produce(&mut chain, 1);
+        submit(&mut chain, &owner, TxBuilder::grant_access(asset.clone()).to(reader.public));
+        produce(&mut chain, 2);
+        let service = QueryService { blockchain: Arc::new(Mutex::new(chain)) };
+
+        let found = service.get_asset(Request::new(AssetRequest { asset: asset.clone() })).await.unwrap().into_inner();
+        assert_eq!((found.owner, found.readers), (owner.public.to_bytes().to_vec(), vec![reader.public.to_bytes().to_vec()]));
+        assert!(service.get_asset(Request::new(AssetRequest { asset: vec![0; 32] })).await.is_err());
+
+        // Historique de l'actif seul, puis une attestation au fil de l'eau
+        let request = SubscribeAssetEventsRequest { from_height: 0, asset: asset.clone() };
+        let mut events = service.subscribe_asset_events(Request::new(request)).await.unwrap().into_inner();
+        let attestation = {
+            let mut chain = service.blockchain.lock().await;
+            let payload = EncryptedPayload::seal_to(&chain.asset(&asset).unwrap().participants(), b"temperature within range").unwrap();
+            let attestation = submit(&mut chain, &reader, TxBuilder::attest(asset.clone(), payload).to(reader.public));
+            produce(&mut chain, 3);
+            attestation
+        };
+        let mut received = Vec::new();
+        for _ in 0..3 {
+            let event = tokio::time::timeout(Duration::from_secs(5), events.next()).await.unwrap().unwrap().unwrap();
+            assert_eq!(event.asset, asset);
+            received.push((event.kind, event.account, event.block_index));
+        }
+        let (owner, reader) = (owner.public.to_bytes().to_vec(), reader.public.to_bytes().to_vec());
+        assert_eq!(received, vec![
+            ("registered".to_string(), owner, 0),
+            ("access_granted".to_string(), reader.clone(), 1),
+            ("attested".to_string(), reader, 2),
+        ]);
+
+        // Toutes les attestations à partir de la dernière hauteur
+        let request = SubscribeAssetEventsRequest { from_height: 2, asset: Vec::new() };
+        let mut events = service.subscribe_asset_events(Request::new(request)).await.unwrap().into_inner();
+        assert_eq!(events.next().await.unwrap().unwrap().transaction_hash, attestation);
+    }
+
+    #[tokio::test]
+    async fn submits_signed_governance_transactions() {
+        let validator = keypair(1);
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.register_validator(&validator.public, 1000).unwrap();
+        let service = SubmissionService { blockchain: Arc::new(Mutex::new(chain)) };
+        let request = |transaction: &GovernanceTransaction| Request::new(GovernanceTransactionRequest { transaction: transaction.to_bytes() });
+
+        let proposal = GovernanceTransaction::Proposal(Proposal::new(&validator, ParameterChange::MaxBlockGas(60_000_000), GENESIS_TIME));
+        let result = service.submit_governance_transaction(request(&proposal)).await.unwrap().into_inner();
+        assert!(result.accepted);
+        assert_eq!(result.transaction_hash, proposal.hash());
+        // Les votes ne portent que sur une proposition incluse
+        let vote = GovernanceTransaction::Vote(Vote::new(&validator, proposal.hash(), true));
+        let result = service.submit_governance_transaction(request(&vote)).await.unwrap().into_inner();
+        assert_eq!((result.accepted, result.error.as_str()), (false, "Vote for unknown or closed proposal"));
+        let vote = GovernanceTransaction::Vote(Vote::new(&keypair(2), proposal.hash(), true));
+        let result = service.submit_governance_transaction(request(&vote)).await.unwrap().into_inner();
+        assert_eq!((result.accepted, result.error.as_str()), (false, "Vote from unknown validator"));
+
+        let malformed = GovernanceTransactionRequest { transaction: proposal.to_bytes()[1..].to_vec() };
+        let status = service.submit_governance_transaction(Request::new(malformed)).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert_eq!(service.blockchain.lock().await.pending_governance_transactions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn serves_incident_notes_with_their_timeline() {
+        let (validator, responder) = (keypair(1), keypair(2));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        let produce = |chain: &mut Blockchain, slot: u64| {
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            chain.validate_and_create_block(&validator, slot).unwrap();
+        };
+
+        let responders = PrivacyGroup::new(vec![validator.public, responder.public]).unwrap();
+        let open = IncidentOpen::new(&validator, responders.clone(), GENESIS_TIME);
+        chain.add_governance_transaction(GovernanceTransaction::IncidentOpen(open.clone())).unwrap();
+        produce(&mut chain, 1);
+        let note = IncidentNote::new(&responder, open.id(), &responders, b"halted at height 0", GENESIS_TIME).unwrap();
+        chain.add_governance_transaction(GovernanceTransaction::IncidentNote(note.clone())).unwrap();
+        produce(&mut chain, 2);
+        let service = QueryService { blockchain: Arc::new(Mutex::new(chain)) };
+
+        let incident = service.get_incident(Request::new(IncidentRequest { incident_id: open.id() })).await.unwrap().into_inner();
+        assert_eq!(incident.timeline.len(), 1);
+        let entry = &incident.timeline[0];
+        assert_eq!((entry.height, &entry.note_hash), (1, &note.hash()));
+        let sealed = EncryptedPayload::from_bytes(&entry.note).unwrap();
+        assert_eq!(sealed.open(&validator).unwrap(), b"halted at height 0");
+        assert!(sealed.open(&keypair(3)).is_err());
+        let status = service.get_incident(Request::new(IncidentRequest { incident_id: vec![0; 32] })).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+}
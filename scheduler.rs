@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use ed25519_dalek::PublicKey;
+
+use crate::recovery::Recoveries;
+use crate::{Transaction, TransactionKind};
+
+// En deçà, une suite de transferts est exécutée en série : le coût de la
+// répartition dépasse le gain
+const MIN_PARALLEL_RUN: usize = 32;
+
+// Portion d'un bloc, dans l'ordre du bloc
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    // Transactions exécutées en série
+    Serial(Range<usize>),
+    // Groupes de transferts sans compte commun, exécutables en parallèle ;
+    // chaque groupe liste ses positions dans le bloc par ordre croissant
+    Parallel(Vec<Vec<usize>>),
+}
+
+// Comptes touchés par un transfert indépendant du reste du bloc, ou `None` si
+// la transaction doit être exécutée en série : autre type que le transfert
+// (séquestres, contrats et récupérations modifient un état partagé), ou compte
+// du producteur, crédité des frais de chaque transaction
+fn transfer_accounts(transaction: &Transaction, producer: &PublicKey, recoveries: &Recoveries) -> Option<[PublicKey; 2]> {
+    if transaction.kind != TransactionKind::Transfer {
+        return None;
+    }
+    let recipient = recoveries.resolve(&transaction.recipient);
+    if transaction.sender == *producer || recipient == *producer {
+        return None;
+    }
+    Some([transaction.sender, recipient])
+}
+
+// Découpe les transactions d'un bloc en suites exécutées en série et en suites
+// de transferts réparties en groupes indépendants. Deux transferts touchant un
+// même compte sont dans le même groupe, exécuté dans l'ordre du bloc : le
+// résultat est celui de l'exécution en série.
+pub fn schedule(transactions: &[Transaction], producer: &PublicKey, recoveries: &Recoveries) -> Vec<Segment> {
+    let accounts: Vec<Option<[PublicKey; 2]>> = transactions.iter()
+        .map(|transaction| transfer_accounts(transaction, producer, recoveries))
+        .collect();
+    let mut segments = Vec::new();
+    let mut serial_start = 0;
+    let mut position = 0;
+    while position < transactions.len() {
+        let run_end = (position..transactions.len()).find(|&i| accounts[i].is_none()).unwrap_or(transactions.len());
+        if run_end - position >= MIN_PARALLEL_RUN {
+            if serial_start < position {
+                segments.push(Segment::Serial(serial_start..position));
+            }
+            segments.push(Segment::Parallel(groups(&accounts, position..run_end)));
+            serial_start = run_end;
+        }
+        position = run_end + 1;
+    }
+    if serial_start < transactions.len() {
+        segments.push(Segment::Serial(serial_start..transactions.len()));
+    }
+    segments
+}
+
+// Composantes connexes des transferts de `run` reliés par un compte commun
+fn groups(accounts: &[Option<[PublicKey; 2]>], run: Range<usize>) -> Vec<Vec<usize>> {
+    // Forêt d'union-find sur les positions relatives à `run.start`
+    let mut parents: Vec<usize> = (0..run.len()).collect();
+    let mut owners: HashMap<PublicKey, usize> = HashMap::new();
+    for local in 0..run.len() {
+        for account in accounts[run.start + local].iter().flatten() {
+            let owner = *owners.entry(*account).or_insert(local);
+            let (a, b) = (find(&mut parents, owner), find(&mut parents, local));
+            parents[a.max(b)] = a.min(b);
+        }
+    }
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut index_of: HashMap<usize, usize> = HashMap::new();
+    for local in 0..run.len() {
+        let root = find(&mut parents, local);
+        let index = *index_of.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(run.start + local);
+    }
+    groups
+}
+
+fn find(parents: &mut [usize], node: usize) -> usize {
+    let mut root = node;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    let mut node = node;
+    while node != root {
+        let next = parents[node];
+        parents[node] = root;
+        node = next;
+    }
+    root
+}
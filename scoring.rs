@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, HashMap};
+use ed25519_dalek::PublicKey;
+
+use crate::{select_leader, Block, Validator};
+
+// Score de contribution d'un validateur à son enregistrement
+pub const INITIAL_CONTRIBUTION_SCORE: f64 = 1.0;
+
+// Variations du score, appliquées à chaque bloc à partir de faits inscrits dans
+// la chaîne uniquement, afin que tous les nœuds obtiennent les mêmes scores
+pub const BLOCK_PRODUCED_REWARD: f64 = 0.01;
+pub const MISSED_SLOT_PENALTY: f64 = 0.05;
+pub const ATTESTATION_REWARD: f64 = 0.02;
+pub const MISSED_ATTESTATION_PENALTY: f64 = 0.05;
+pub const SLASHING_PENALTY: f64 = 0.5;
+
+// Nombre maximal de créneaux manqués imputés entre deux blocs : au-delà
+// (réseau arrêté), l'absence n'est plus attribuable aux seuls leaders
+pub const MAX_ATTRIBUTED_MISSED_SLOTS: u64 = 32;
+
+// Leaders des créneaux sautés entre le bloc de créneau `parent_slot` (absent
+// pour le premier bloc) et `block`, avec l'ensemble des validateurs d'avant le
+// bloc ; un leader apparaît une fois par créneau manqué, les créneaux sans
+// leader (voir `difficulty.rs`) ne comptent pas
+pub fn missed_leaders(
+    validators: &HashMap<PublicKey, Validator>,
+    parent_slot: Option<u64>,
+    block: &Block,
+    difficulty: u64,
+    min_stake: u64,
+    min_score: f64,
+) -> Vec<PublicKey> {
+    let Some(parent_slot) = parent_slot else {
+        return Vec::new();
+    };
+    (parent_slot + 1..block.slot)
+        .take(MAX_ATTRIBUTED_MISSED_SLOTS as usize)
+        .filter_map(|slot| select_leader(validators, &block.previous_hash, slot, difficulty, min_stake, min_score))
+        .collect()
+}
+
+// Variations du score induites par `block` :
+// - chaque leader d'un créneau manqué (`missed`) est pénalisé
+// - le producteur est récompensé
+// - les signataires du checkpoint inclus sont récompensés, les autres validateurs
+//   dotés d'une mise pénalisés
+// Les variations sont cumulées par validateur, dans cet ordre.
+pub fn score_changes(validators: &HashMap<PublicKey, Validator>, missed: &[PublicKey], block: &Block) -> BTreeMap<[u8; 32], f64> {
+    let mut changes: BTreeMap<[u8; 32], f64> = BTreeMap::new();
+    let mut change = |validator: &PublicKey, delta: f64| {
+        *changes.entry(validator.to_bytes()).or_insert(0.0) += delta;
+    };
+
+    for leader in missed {
+        change(leader, -MISSED_SLOT_PENALTY);
+    }
+
+    change(&block.validator_pubkey, BLOCK_PRODUCED_REWARD);
+
+    if let Some(checkpoint) = &block.checkpoint {
+        let mut attesters: Vec<&Validator> = validators.values().filter(|v| v.stake > 0).collect();
+        attesters.sort_by_key(|v| v.public_key.to_bytes());
+        for validator in attesters {
+            if checkpoint.votes.iter().any(|vote| vote.validator == validator.public_key) {
+                change(&validator.public_key, ATTESTATION_REWARD);
+            } else {
+                change(&validator.public_key, -MISSED_ATTESTATION_PENALTY);
+            }
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ed25519_dalek::{Keypair, SecretKey};
+
+    use super::*;
+    use crate::checkpoint::{Checkpoint, CheckpointVote};
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::Blockchain;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    fn validator(public_key: PublicKey, stake: u64) -> Validator {
+        Validator {
+            public_key,
+            stake,
+            contribution_score: INITIAL_CONTRIBUTION_SCORE,
+            last_validated_block: None,
+            missed_slots: 0,
+            inactive_since: None,
+            standby: false,
+        }
+    }
+
+    // Un bloc du créneau `slot` produit par `producer` ; les fonctions de score ne
+    // vérifient pas la signature
+    fn block(producer: &Keypair, slot: u64) -> Block {
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME + 5));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(std::sync::Arc::new(clock));
+        chain.register_validator(&producer.public, 1000).unwrap();
+        let mut block = chain.validate_and_create_block(producer, 1).unwrap();
+        block.slot = slot;
+        block
+    }
+
+    #[test]
+    fn missed_leaders_are_counted_once_per_skipped_slot_up_to_the_cap() {
+        let leader = keypair(1);
+        let validators = HashMap::from([(leader.public, validator(leader.public, 1000))]);
+        let missed = |parent_slot, slot| missed_leaders(&validators, parent_slot, &block(&leader, slot), 1, 1, 0.5);
+
+        assert!(missed(None, 10).is_empty());
+        assert!(missed(Some(9), 10).is_empty());
+        assert_eq!(missed(Some(6), 10), vec![leader.public; 3]);
+        assert_eq!(missed(Some(0), 1000).len() as u64, MAX_ATTRIBUTED_MISSED_SLOTS);
+
+        // Sans validateur éligible, aucun créneau n'a de leader à pénaliser
+        assert!(missed_leaders(&validators, Some(0), &block(&leader, 10), 1, 2000, 0.5).is_empty());
+    }
+
+    #[test]
+    fn producer_is_rewarded_and_missed_leaders_penalized_cumulatively() {
+        let (producer, absent) = (keypair(1), keypair(2));
+        let validators = HashMap::from([
+            (producer.public, validator(producer.public, 1000)),
+            (absent.public, validator(absent.public, 1000)),
+        ]);
+        let changes = score_changes(&validators, &[absent.public, producer.public, absent.public], &block(&producer, 4));
+
+        assert_eq!(changes.len(), 2);
+        assert!((changes[&absent.public.to_bytes()] + 2.0 * MISSED_SLOT_PENALTY).abs() < 1e-12);
+        assert!((changes[&producer.public.to_bytes()] - (BLOCK_PRODUCED_REWARD - MISSED_SLOT_PENALTY)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn checkpoint_rewards_signers_and_penalizes_staked_absentees() {
+        let (producer, absent, unstaked) = (keypair(1), keypair(2), keypair(3));
+        let validators = HashMap::from([
+            (producer.public, validator(producer.public, 1000)),
+            (absent.public, validator(absent.public, 1000)),
+            (unstaked.public, validator(unstaked.public, 0)),
+        ]);
+        let mut block = block(&producer, 1);
+        block.checkpoint = Some(Checkpoint {
+            height: 0,
+            block_hash: block.previous_hash.clone(),
+            votes: vec![CheckpointVote::new(&producer, 0, block.previous_hash.clone()).unwrap()],
+        });
+        let changes = score_changes(&validators, &[], &block);
+
+        assert!((changes[&producer.public.to_bytes()] - (BLOCK_PRODUCED_REWARD + ATTESTATION_REWARD)).abs() < 1e-12);
+        assert!((changes[&absent.public.to_bytes()] + MISSED_ATTESTATION_PENALTY).abs() < 1e-12);
+        assert!(!changes.contains_key(&unstaked.public.to_bytes()));
+
+        // Sans checkpoint, seul le producteur change de score
+        block.checkpoint = None;
+        assert_eq!(score_changes(&validators, &[], &block).into_keys().collect::<Vec<_>>(), vec![producer.public.to_bytes()]);
+    }
+}
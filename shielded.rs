@@ -0,0 +1,299 @@
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use ed25519_dalek::{Keypair, PublicKey};
+use rand::RngCore;
+
+use crate::confidential::{combine, commit, generators, powers, ConfidentialTransfer, RangeProof, Transcript, BITS, ROUNDS};
+use crate::privacy::{EncryptedPayload, PrivacyGroup};
+use crate::txbuilder::TxBuilder;
+use crate::{Block, TransactionKind};
+
+// Portefeuille confidentiel : création des preuves d'intervalle et des
+// transactions confidentielles, hors du code de consensus qui ne fait que les vérifier
+
+fn random_scalar() -> Scalar {
+    let mut bytes = [0u8; 64];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+fn inner_product(a: &[Scalar], b: &[Scalar]) -> Scalar {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+// Ouverture d'un engagement : montant et facteur d'aveuglement. Celle d'un
+// transfert est transmise chiffrée dans `ConfidentialTransfer::note`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Opening {
+    pub value: u64,
+    pub blinding: Scalar,
+}
+
+impl Opening {
+    pub fn commitment(&self) -> RistrettoPoint {
+        commit(self.value, &self.blinding)
+    }
+
+    pub fn to_bytes(self) -> Vec<u8> {
+        let mut bytes = self.value.to_be_bytes().to_vec();
+        bytes.extend_from_slice(self.blinding.as_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Opening, &'static str> {
+        if bytes.len() != 40 {
+            return Err("Invalid confidential note");
+        }
+        let blinding = Scalar::from_canonical_bytes(bytes[8..].try_into().unwrap()).ok_or("Invalid confidential note")?;
+        Ok(Opening { value: u64::from_be_bytes(bytes[..8].try_into().unwrap()), blinding })
+    }
+}
+
+impl RangeProof {
+    // Preuve pour l'engagement `commit(value, blinding)`
+    pub fn prove(value: u64, blinding: &Scalar) -> RangeProof {
+        let bits = (0..BITS).map(|i| Scalar::from((value >> i) & 1)).collect();
+        Self::prove_digits(&commit(value, blinding), bits, blinding)
+    }
+
+    // Preuve construite à partir des chiffres `a_l` de la valeur engagée par
+    // `commitment` : elle n'est valide que s'il s'agit de ses bits
+    fn prove_digits(commitment: &RistrettoPoint, a_l: Vec<Scalar>, blinding: &Scalar) -> RangeProof {
+        let generators = generators();
+        let mut transcript = Transcript::new(&commitment.compress());
+
+        // Bits de la valeur (a_l) et leur complément à un (a_r = a_l - 1)
+        let a_r: Vec<Scalar> = a_l.iter().map(|bit| bit - Scalar::one()).collect();
+        let alpha = random_scalar();
+        let a = alpha * generators.blinding + combine(&a_l, &generators.g) + combine(&a_r, &generators.h);
+        let s_l: Vec<Scalar> = (0..BITS).map(|_| random_scalar()).collect();
+        let s_r: Vec<Scalar> = (0..BITS).map(|_| random_scalar()).collect();
+        let rho = random_scalar();
+        let s = rho * generators.blinding + combine(&s_l, &generators.g) + combine(&s_r, &generators.h);
+        let (a, s) = (a.compress().to_bytes(), s.compress().to_bytes());
+        transcript.append(&a);
+        transcript.append(&s);
+        let y = transcript.challenge();
+        let z = transcript.challenge();
+
+        // l(X) = l0 + l1 X et r(X) = r0 + r1 X, dont le produit scalaire t(X) vaut
+        // z² v + δ(y, z) en X = 0 exactement quand les bits sont corrects
+        let y_powers = powers(y);
+        let two_powers = powers(Scalar::from(2u64));
+        let z2 = z * z;
+        let l0: Vec<Scalar> = a_l.iter().map(|bit| bit - z).collect();
+        let r0: Vec<Scalar> = (0..BITS).map(|i| y_powers[i] * (a_r[i] + z) + z2 * two_powers[i]).collect();
+        let r1: Vec<Scalar> = (0..BITS).map(|i| y_powers[i] * s_r[i]).collect();
+        let t1 = inner_product(&l0, &r1) + inner_product(&s_l, &r0);
+        let t2 = inner_product(&s_l, &r1);
+        let (tau1, tau2) = (random_scalar(), random_scalar());
+        let t1_point = (t1 * generators.value + tau1 * generators.blinding).compress().to_bytes();
+        let t2_point = (t2 * generators.value + tau2 * generators.blinding).compress().to_bytes();
+        transcript.append(&t1_point);
+        transcript.append(&t2_point);
+        let x = transcript.challenge();
+
+        let tau_x = tau2 * x * x + tau1 * x + z2 * blinding;
+        let mu = alpha + rho * x;
+        let l: Vec<Scalar> = (0..BITS).map(|i| l0[i] + s_l[i] * x).collect();
+        let r: Vec<Scalar> = (0..BITS).map(|i| r0[i] + r1[i] * x).collect();
+        let t_hat = inner_product(&l, &r);
+        transcript.append(tau_x.as_bytes());
+        transcript.append(mu.as_bytes());
+        transcript.append(t_hat.as_bytes());
+        let q = transcript.challenge() * generators.value;
+
+        // Argument de produit scalaire sur h' = y^-i h : connaissance de l et r
+        // tels que P = <l, g> + <r, h'> + <l, r> Q
+        let y_inverse = y.invert();
+        let mut g = generators.g.clone();
+        let mut h: Vec<RistrettoPoint> = generators.h.iter().zip(powers(y_inverse)).map(|(h, power)| power * h).collect();
+        let (mut a_vector, mut b_vector) = (l, r);
+        let (mut l_points, mut r_points) = (Vec::with_capacity(ROUNDS), Vec::with_capacity(ROUNDS));
+        while a_vector.len() > 1 {
+            let half = a_vector.len() / 2;
+            let (a_lo, a_hi) = a_vector.split_at(half);
+            let (b_lo, b_hi) = b_vector.split_at(half);
+            let (g_lo, g_hi) = g.split_at(half);
+            let (h_lo, h_hi) = h.split_at(half);
+            let c_l = inner_product(a_lo, b_hi);
+            let c_r = inner_product(a_hi, b_lo);
+            let l_point = (combine(a_lo, g_hi) + combine(b_hi, h_lo) + c_l * q).compress().to_bytes();
+            let r_point = (combine(a_hi, g_lo) + combine(b_lo, h_hi) + c_r * q).compress().to_bytes();
+            transcript.append(&l_point);
+            transcript.append(&r_point);
+            l_points.push(l_point);
+            r_points.push(r_point);
+            let u = transcript.challenge();
+            let u_inverse = u.invert();
+            let next_a = (0..half).map(|i| a_lo[i] * u + a_hi[i] * u_inverse).collect();
+            let next_b = (0..half).map(|i| b_lo[i] * u_inverse + b_hi[i] * u).collect();
+            let next_g = (0..half).map(|i| g_lo[i] * u_inverse + g_hi[i] * u).collect();
+            let next_h = (0..half).map(|i| h_lo[i] * u + h_hi[i] * u_inverse).collect();
+            (a_vector, b_vector, g, h) = (next_a, next_b, next_g, next_h);
+        }
+
+        RangeProof {
+            a,
+            s,
+            t1: t1_point,
+            t2: t2_point,
+            tau_x: tau_x.to_bytes(),
+            mu: mu.to_bytes(),
+            t_hat: t_hat.to_bytes(),
+            l: l_points,
+            r: r_points,
+            a_final: a_vector[0].to_bytes(),
+            b_final: b_vector[0].to_bytes(),
+        }
+    }
+}
+
+// Transfert confidentiel de `value` depuis le solde de `sender` ouvert par
+// `opening` (voir `scan`), à signer par `sender` : les preuves portent sur ce
+// solde, qui doit encore être celui de l'état au moment de l'inclusion
+pub fn transfer(sender: &PublicKey, opening: &Opening, recipient: &PublicKey, value: u64) -> Result<TxBuilder, &'static str> {
+    let amount = Opening { value, blinding: random_scalar() };
+    let remaining = Opening {
+        value: opening.value.checked_sub(value).ok_or("Insufficient confidential balance")?,
+        blinding: opening.blinding - amount.blinding,
+    };
+    let note = EncryptedPayload::seal(&PrivacyGroup::new(vec![*sender, *recipient])?, &amount.to_bytes())?;
+    let transfer = ConfidentialTransfer {
+        commitment: amount.commitment().compress().to_bytes(),
+        amount_proof: RangeProof::prove(amount.value, &amount.blinding),
+        balance_proof: RangeProof::prove(remaining.value, &remaining.blinding),
+        note,
+    };
+    Ok(TxBuilder::new(TransactionKind::ConfidentialTransfer(Box::new(transfer))).to(*recipient))
+}
+
+// Retrait de `amount` du solde ouvert par `opening` vers le solde public de `recipient`
+pub fn unshield(opening: &Opening, recipient: &PublicKey, amount: u64) -> Result<TxBuilder, &'static str> {
+    let remaining = Opening {
+        value: opening.value.checked_sub(amount).ok_or("Insufficient confidential balance")?,
+        blinding: opening.blinding,
+    };
+    let balance_proof = Box::new(RangeProof::prove(remaining.value, &remaining.blinding));
+    Ok(TxBuilder::new(TransactionKind::Unshield { balance_proof }).to(*recipient).amount(amount))
+}
+
+// Le détenteur d'un compte conserve l'ouverture de son solde confidentiel, que
+// `scan` met à jour avec les transactions de `block`. Les blocs doivent être parcourus dans l'ordre depuis
+// celui où l'ouverture était connue (la genèse pour un solde nul) ; les soldes
+// transférés par récupération de compte ne sont pas suivis.
+pub fn scan(opening: &mut Opening, keypair: &Keypair, block: &Block) -> Result<(), &'static str> {
+    let account = keypair.public;
+    for transaction in &block.transactions {
+        let (sent, received) = (transaction.sender == account, transaction.recipient == account);
+        let (debit, credit) = match &transaction.kind {
+            TransactionKind::Shield if received => (None, Some(Opening { value: transaction.amount, blinding: Default::default() })),
+            TransactionKind::ConfidentialTransfer(transfer) if sent || received => {
+                let note = Opening::from_bytes(&transfer.note.open(keypair)?)?;
+                if note.commitment().compress().to_bytes() != transfer.commitment {
+                    return Err("Confidential note does not open its commitment");
+                }
+                (sent.then_some(note), received.then_some(note))
+            }
+            TransactionKind::Unshield { .. } if sent => (Some(Opening { value: transaction.amount, blinding: Default::default() }), None),
+            _ => (None, None),
+        };
+        if let Some(debit) = debit {
+            opening.value = opening.value.checked_sub(debit.value).ok_or("Confidential balance underflow")?;
+            opening.blinding -= debit.blinding;
+        }
+        if let Some(credit) = credit {
+            opening.value = opening.value.checked_add(credit.value).ok_or("Confidential balance overflow")?;
+            opening.blinding += credit.blinding;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use ed25519_dalek::SecretKey;
+
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::Blockchain;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: (&secret).into(), secret }
+    }
+
+    // Engagement d'une valeur quelconque, y compris hors de [0, 2^64)
+    fn commit_scalar(value: Scalar, blinding: &Scalar) -> RistrettoPoint {
+        value * generators().value + blinding * generators().blinding
+    }
+
+    #[test]
+    fn proves_values_across_the_range() {
+        for value in [0, 1, 1_000, u64::MAX - 1, u64::MAX] {
+            let blinding = random_scalar();
+            let proof = RangeProof::prove(value, &blinding);
+            assert_eq!(proof.verify(&commit(value, &blinding)), Ok(()));
+            assert_eq!(RangeProof::from_bytes(&proof.to_bytes()), Ok(proof));
+        }
+    }
+
+    #[test]
+    fn rejects_values_outside_the_range() {
+        let blinding = random_scalar();
+        // 2^64 écrit avec un chiffre non binaire : 2 + 2 + 4 + ... + 2^63
+        let two_pow_64 = Scalar::from(u64::MAX) + Scalar::one();
+        let mut digits = vec![Scalar::one(); BITS];
+        digits[0] = Scalar::from(2u64);
+        let commitment = commit_scalar(two_pow_64, &blinding);
+        assert!(RangeProof::prove_digits(&commitment, digits, &blinding).verify(&commitment).is_err());
+
+        // -1 : aucune décomposition en 64 bits, pas même celle de 2^64 - 1
+        let commitment = commit_scalar(-Scalar::one(), &blinding);
+        assert!(RangeProof::prove_digits(&commitment, vec![Scalar::one(); BITS], &blinding).verify(&commitment).is_err());
+        // Une preuve valide ne vaut que pour l'engagement prouvé
+        assert!(RangeProof::prove(u64::MAX, &blinding).verify(&commitment).is_err());
+    }
+
+    #[test]
+    fn wallets_follow_their_confidential_balances() {
+        let (validator, alice, bob) = (keypair(1), keypair(2), keypair(3));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain.allocate(&alice.public, 1_000_000);
+        chain.allocate(&bob.public, 1_000_000);
+        let include = |chain: &mut Blockchain, signer: &Keypair, builder: TxBuilder, slot: u64| {
+            let transaction = builder.chain_id(chain.config.chain_id).nonce(chain.next_nonce(&signer.public)).timestamp(chain.now()).sign(signer).unwrap();
+            chain.add_transaction(transaction).unwrap();
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            chain.validate_and_create_block(&validator, slot).unwrap()
+        };
+        let (mut alice_opening, mut bob_opening) = (Opening::default(), Opening::default());
+
+        let block = include(&mut chain, &alice, TxBuilder::shield().to(alice.public).amount(1_000), 1);
+        scan(&mut alice_opening, &alice, &block).unwrap();
+        let stale = alice_opening;
+        let block = include(&mut chain, &alice, transfer(&alice.public, &alice_opening, &bob.public, 300).unwrap(), 2);
+        scan(&mut alice_opening, &alice, &block).unwrap();
+        scan(&mut bob_opening, &bob, &block).unwrap();
+        let block = include(&mut chain, &bob, unshield(&bob_opening, &bob.public, 100).unwrap(), 3);
+        scan(&mut bob_opening, &bob, &block).unwrap();
+        assert_eq!((alice_opening.value, bob_opening.value), (700, 200));
+        for (account, opening) in [(&alice.public, alice_opening), (&bob.public, bob_opening)] {
+            assert_eq!(chain.accounts.confidential.balance(account), opening.commitment());
+        }
+
+        // Preuve sur un solde qui n'est plus celui de l'état, ou qu'elle dépasse
+        let outdated = transfer(&alice.public, &stale, &bob.public, 100).unwrap()
+            .chain_id(chain.config.chain_id).nonce(chain.next_nonce(&alice.public)).timestamp(chain.now()).sign(&alice).unwrap();
+        assert_eq!(chain.add_transaction(outdated), Err("Insufficient confidential balance"));
+        assert_eq!(unshield(&bob_opening, &bob.public, 201).err(), Some("Insufficient confidential balance"));
+    }
+}
@@ -0,0 +1,242 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use ed25519_dalek::{Keypair, PublicKey, Signature};
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha3::Sha3_256;
+
+use crate::encoding::{Decoder, Encoder, REMOTE_SIGNER_DOMAIN};
+
+// Délai accordé au signataire distant pour chaque requête
+const SIGNER_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Taille maximale d'une trame : les messages signés sont des hash
+const MAX_FRAME_SIZE: usize = 4096;
+
+const REQUEST_PUBLIC_KEY: u8 = 0;
+const REQUEST_SIGNATURE: u8 = 1;
+
+// Clé de validation du nœud : en-têtes de blocs, votes de checkpoint,
+// contributions à la balise et demandes de réactivation
+pub trait Signer: Send + Sync {
+    fn public_key(&self) -> PublicKey;
+
+    // `Err` si la clé n'est pas joignable ; la signature doit être déterministe
+    // (le secret de la balise en est dérivé)
+    fn sign(&self, message: &[u8]) -> Result<Signature, &'static str>;
+}
+
+// Clé en mémoire du processus
+impl Signer for Keypair {
+    fn public_key(&self) -> PublicKey {
+        self.public
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature, &'static str> {
+        Ok(ed25519_dalek::Signer::sign(self, message))
+    }
+}
+
+// Clé détenue par un processus distinct (`supplyx signer`), joint en TCP. Chaque
+// requête porte un nonce aléatoire et un HMAC-SHA3-256 calculé avec la clé
+// partagée ; la réponse est authentifiée sur le même nonce. Les appels sont
+// bloquants (SIGNER_TIMEOUT au plus par opération) : le nœud signe ses blocs
+// par `spawn_blocking`, hors du verrou de la chaîne.
+pub struct RemoteSigner {
+    addr: String,
+    auth_key: Vec<u8>,
+    public_key: PublicKey,
+}
+
+impl RemoteSigner {
+    // `auth_key` : clé partagée (hex, 32 octets)
+    pub fn connect(addr: &str, auth_key: &str) -> Result<Self, String> {
+        let auth_key = parse_auth_key(auth_key)?;
+        let bytes = request(addr, &auth_key, REQUEST_PUBLIC_KEY, &[])?;
+        let public_key = PublicKey::from_bytes(&bytes).map_err(|_| "Invalid signer public key".to_string())?;
+        Ok(RemoteSigner { addr: addr.to_string(), auth_key, public_key })
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature, &'static str> {
+        let bytes = request(&self.addr, &self.auth_key, REQUEST_SIGNATURE, message).map_err(|e| {
+            tracing::warn!(addr = %self.addr, error = %e, "Remote signer request failed");
+            "Remote signer unavailable"
+        })?;
+        let signature = Signature::from_bytes(&bytes).map_err(|_| "Invalid remote signature")?;
+        self.public_key.verify_strict(message, &signature).map_err(|_| "Invalid remote signature")?;
+        Ok(signature)
+    }
+}
+
+fn request(addr: &str, auth_key: &[u8], kind: u8, message: &[u8]) -> Result<Vec<u8>, String> {
+    let mut nonce = [0u8; 32];
+    OsRng.fill_bytes(&mut nonce);
+    let socket_addr = addr.to_socket_addrs().map_err(|e| e.to_string())?.next().ok_or("Unresolved signer address")?;
+    let mut stream = TcpStream::connect_timeout(&socket_addr, SIGNER_TIMEOUT).map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(SIGNER_TIMEOUT)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(SIGNER_TIMEOUT)).map_err(|e| e.to_string())?;
+    write_frame(&mut stream, &seal(auth_key, kind, &nonce, message)).map_err(|e| e.to_string())?;
+    let response = read_frame(&mut stream).map_err(|e| e.to_string())?;
+    let (response_kind, response_nonce, body) = open(auth_key, &response)?;
+    if response_kind != kind || response_nonce != nonce {
+        return Err("Unexpected signer response".to_string());
+    }
+    Ok(body)
+}
+
+// Côté détenteur de la clé : répond aux requêtes authentifiées, chaque connexion
+// sur son propre thread ; les requêtes dont l'authentification échoue restent sans réponse
+pub fn serve(addr: &str, keypair: &Keypair, auth_key: &str) -> Result<(), String> {
+    let auth_key = parse_auth_key(auth_key)?;
+    let listener = TcpListener::bind(addr).map_err(|e| format!("{}: {}", addr, e))?;
+    serve_connections(listener, keypair, &auth_key);
+    Ok(())
+}
+
+fn serve_connections(listener: TcpListener, keypair: &Keypair, auth_key: &[u8]) {
+    std::thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            scope.spawn(move || {
+                if let Err(e) = answer(&mut stream, keypair, auth_key) {
+                    tracing::warn!(error = %e, "Signer request rejected");
+                }
+            });
+        }
+    });
+}
+
+fn answer(stream: &mut TcpStream, keypair: &Keypair, auth_key: &[u8]) -> Result<(), String> {
+    stream.set_read_timeout(Some(SIGNER_TIMEOUT)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(SIGNER_TIMEOUT)).map_err(|e| e.to_string())?;
+    let request = read_frame(stream).map_err(|e| e.to_string())?;
+    let (kind, nonce, message) = open(auth_key, &request)?;
+    let body = match kind {
+        REQUEST_PUBLIC_KEY => keypair.public.to_bytes().to_vec(),
+        REQUEST_SIGNATURE => ed25519_dalek::Signer::sign(keypair, &message).to_bytes().to_vec(),
+        _ => return Err("Unknown signer request".to_string()),
+    };
+    write_frame(stream, &seal(auth_key, kind, &nonce, &body)).map_err(|e| e.to_string())
+}
+
+fn parse_auth_key(auth_key: &str) -> Result<Vec<u8>, String> {
+    match hex::decode(auth_key.trim()) {
+        Ok(key) if key.len() == 32 => Ok(key),
+        _ => Err("Signer authentication key must be 32 bytes of hex".to_string()),
+    }
+}
+
+fn mac(auth_key: &[u8], payload: &[u8]) -> Hmac<Sha3_256> {
+    let mut mac = Hmac::<Sha3_256>::new_from_slice(auth_key).expect("HMAC accepts keys of any size");
+    mac.update(payload);
+    mac
+}
+
+fn seal(auth_key: &[u8], kind: u8, nonce: &[u8], body: &[u8]) -> Vec<u8> {
+    let mut payload = Encoder::new(REMOTE_SIGNER_DOMAIN);
+    payload.put_u8(kind);
+    payload.put_bytes(nonce);
+    payload.put_bytes(body);
+    let payload = payload.finish();
+    let mut encoder = Encoder::new(REMOTE_SIGNER_DOMAIN);
+    encoder.put_bytes(&payload);
+    encoder.put_bytes(&mac(auth_key, &payload).finalize().into_bytes());
+    encoder.finish()
+}
+
+fn open(auth_key: &[u8], frame: &[u8]) -> Result<(u8, Vec<u8>, Vec<u8>), String> {
+    let mut decoder = Decoder::new(frame, REMOTE_SIGNER_DOMAIN)?;
+    let payload = decoder.get_bytes()?;
+    let tag = decoder.get_bytes()?;
+    decoder.finish()?;
+    // Comparaison en temps constant
+    mac(auth_key, payload).verify_slice(tag).map_err(|_| "Signer authentication failed".to_string())?;
+    let mut decoder = Decoder::new(payload, REMOTE_SIGNER_DOMAIN)?;
+    let kind = decoder.get_u8()?;
+    let nonce = decoder.get_bytes()?.to_vec();
+    let body = decoder.get_bytes()?.to_vec();
+    decoder.finish()?;
+    Ok((kind, nonce, body))
+}
+
+fn write_frame(stream: &mut TcpStream, frame: &[u8]) -> std::io::Result<()> {
+    stream.write_all(&(frame.len() as u32).to_be_bytes())?;
+    stream.write_all(frame)
+}
+
+fn read_frame(stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut length = [0u8; 4];
+    stream.read_exact(&mut length)?;
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_FRAME_SIZE {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Signer frame too large"));
+    }
+    let mut frame = vec![0u8; length];
+    stream.read_exact(&mut frame)?;
+    Ok(frame)
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{SecretKey, Verifier};
+
+    use super::*;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    // Signataire servi sur un port local libre, avec la clé partagée `[7; 32]`
+    fn spawn_signer(keypair: Keypair) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || serve_connections(listener, &keypair, &[7; 32]));
+        addr
+    }
+
+    #[test]
+    fn remote_signatures_come_from_the_served_key() {
+        let key = keypair(1);
+        let addr = spawn_signer(keypair(1));
+        let signer = RemoteSigner::connect(&addr, &hex::encode([7; 32])).unwrap();
+        assert_eq!(signer.public_key(), key.public);
+
+        // Requêtes concurrentes, chacune sur sa connexion
+        let signer = std::sync::Arc::new(signer);
+        let threads: Vec<_> = (0..4u8).map(|i| {
+            let signer = signer.clone();
+            std::thread::spawn(move || (i, signer.sign(&[i; 32]).unwrap()))
+        }).collect();
+        for thread in threads {
+            let (i, signature) = thread.join().unwrap();
+            assert!(key.public.verify(&[i; 32], &signature).is_ok());
+            assert_eq!(signature, ed25519_dalek::Signer::sign(&key, &[i; 32]));
+        }
+    }
+
+    #[test]
+    fn rejects_requests_with_another_authentication_key() {
+        let addr = spawn_signer(keypair(1));
+        assert!(RemoteSigner::connect(&addr, &hex::encode([8; 32])).is_err());
+        assert!(RemoteSigner::connect(&addr, "not hex").is_err());
+
+        // Code d'authentification altéré
+        let frame = seal(&[7; 32], REQUEST_PUBLIC_KEY, &[0; 32], &[]);
+        assert!(open(&[7; 32], &frame).is_ok());
+        let mut tampered = frame.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(open(&[7; 32], &tampered).unwrap_err(), "Signer authentication failed");
+        assert!(open(&[8; 32], &frame).is_err());
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+use std::str::FromStr;
+use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+
+use crate::governance::ConsensusParams;
+use crate::mempool::max_gas;
+use crate::{Block, Expiry, Transaction, TransactionKind};
+
+// Prix du gaz des transactions synthétiques, tiré entre 1 et ce maximum
+const MAX_SYNTHETIC_GAS_PRICE: u64 = 20;
+
+// Un bloc sur BURST_PERIOD reçoit BURST_FACTOR fois la charge du profil
+const BURST_PERIOD: u64 = 10;
+const BURST_FACTOR: u64 = 5;
+
+// Transaction à inclure : bloc d'arrivée (à partir de 0), gaz consommé et prix du gaz
+#[derive(Clone, Debug)]
+pub struct Demand {
+    pub arrival: u64,
+    pub gas: u64,
+    pub gas_price: u64,
+}
+
+// Profil de trafic synthétique, en transactions de transfert par bloc
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Profile {
+    Steady { transactions: u64 },
+    // Charge multipliée par BURST_FACTOR un bloc sur BURST_PERIOD
+    Burst { transactions: u64 },
+}
+
+impl FromStr for Profile {
+    type Err = &'static str;
+
+    // `steady:<transactions par bloc>` ou `burst:<transactions par bloc>`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (name, transactions) = value.split_once(':').ok_or("Invalid profile (expected steady:<n> or burst:<n>)")?;
+        let transactions = transactions.parse().map_err(|_| "Invalid profile load")?;
+        match name {
+            "steady" => Ok(Profile::Steady { transactions }),
+            "burst" => Ok(Profile::Burst { transactions }),
+            _ => Err("Unknown profile (expected steady or burst)"),
+        }
+    }
+}
+
+// Trafic soumis, identique pour chaque jeu de paramètres comparé
+#[derive(Clone, Debug)]
+pub struct Traffic {
+    // Par bloc d'arrivée croissant
+    pub demand: Vec<Demand>,
+    // Producteur de chaque bloc simulé, inconnu pour un profil synthétique
+    pub producers: Vec<Option<PublicKey>>,
+}
+
+impl Traffic {
+    // Transactions publiques des `count` derniers blocs, arrivées au bloc qui les
+    // a incluses et produites par le même validateur. Le gaz d'un appel de
+    // contrat est majoré par sa limite. La demande restée dans le mempool
+    // d'origine n'est pas connue : des limites plus larges ne la révèlent pas.
+    pub fn replay(blocks: &[Block], count: usize) -> Self {
+        let blocks = &blocks[blocks.len().saturating_sub(count)..];
+        let demand = blocks.iter().enumerate()
+            .flat_map(|(arrival, block)| block.transactions.iter().map(move |tx| Demand {
+                arrival: arrival as u64,
+                gas: max_gas(tx),
+                gas_price: tx.gas_price,
+            }))
+            .collect();
+        let producers = blocks.iter().map(|block| Some(block.validator_pubkey)).collect();
+        Traffic { demand, producers }
+    }
+
+    // Transferts signés de taille réelle sur `blocks` blocs, prix du gaz pseudo-aléatoire
+    // de graine fixe : deux exécutions produisent le même trafic
+    pub fn synthetic(profile: Profile, blocks: u64) -> Self {
+        let secret = SecretKey::from_bytes(&[1; 32]).unwrap();
+        let sender = Keypair { public: PublicKey::from(&secret), secret };
+        let mut random = SplitMix64(0);
+        let mut demand = Vec::new();
+        for arrival in 0..blocks {
+            let transactions = match profile {
+                Profile::Steady { transactions } => transactions,
+                Profile::Burst { transactions } if arrival % BURST_PERIOD == BURST_PERIOD - 1 => transactions.saturating_mul(BURST_FACTOR),
+                Profile::Burst { transactions } => transactions,
+            };
+            for _ in 0..transactions {
+                let gas_price = 1 + random.next() % MAX_SYNTHETIC_GAS_PRICE;
+                let transaction = Transaction::signed(0, &sender, 0, &sender.public, 1, TransactionKind::Transfer, gas_price, arrival, Expiry::Never);
+                demand.push(Demand { arrival, gas: max_gas(&transaction), gas_price });
+            }
+        }
+        Traffic { demand, producers: vec![None; blocks as usize] }
+    }
+}
+
+// Générateur pseudo-aléatoire déterministe (trafic synthétique, réseau simulé)
+pub struct SplitMix64(pub u64);
+
+impl SplitMix64 {
+    pub fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+// Résultat de la simulation d'un jeu de paramètres
+#[derive(Clone, Debug, Default)]
+pub struct SimulationReport {
+    pub blocks: u64,
+    pub included: u64,
+    // Frais payés par les transactions incluses, versés aux producteurs
+    pub fees_paid: u64,
+    // Frais reçus par producteur (trafic rejoué)
+    pub revenue: BTreeMap<[u8; 32], u64>,
+    // Blocs ayant laissé en attente des transactions arrivées
+    pub full_blocks: u64,
+    // Transactions en attente après chaque bloc
+    pub max_backlog: usize,
+    pub mean_backlog: f64,
+    pub final_backlog: usize,
+    // Attente moyenne entre l'arrivée et l'inclusion, en blocs
+    pub mean_wait: f64,
+}
+
+// Production des blocs sous `params` comme le fait le producteur : transactions
+// en attente dans l'ordre d'arrivée, celles qui dépassent le gaz restant du bloc
+// attendant le suivant
+pub fn simulate(traffic: &Traffic, params: &ConsensusParams) -> SimulationReport {
+    let mut report = SimulationReport { blocks: traffic.producers.len() as u64, ..SimulationReport::default() };
+    let mut arrivals = traffic.demand.iter().peekable();
+    let mut pending: VecDeque<&Demand> = VecDeque::new();
+    let (mut total_backlog, mut total_wait) = (0u64, 0u64);
+    for (height, producer) in traffic.producers.iter().enumerate() {
+        let height = height as u64;
+        while let Some(demand) = arrivals.next_if(|demand| demand.arrival <= height) {
+            pending.push_back(demand);
+        }
+        let (mut count, mut gas, mut fees) = (0u64, 0u64, 0u64);
+        let mut deferred = VecDeque::new();
+        for demand in pending.drain(..) {
+            if count >= params.max_block_transactions || gas.saturating_add(demand.gas) > params.max_block_gas {
+                deferred.push_back(demand);
+                continue;
+            }
+            count += 1;
+            gas += demand.gas;
+            fees = fees.saturating_add(demand.gas.saturating_mul(demand.gas_price));
+            total_wait += height - demand.arrival;
+        }
+        pending = deferred;
+        report.included += count;
+        report.fees_paid = report.fees_paid.saturating_add(fees);
+        if let Some(producer) = producer {
+            let revenue = report.revenue.entry(producer.to_bytes()).or_default();
+            *revenue = revenue.saturating_add(fees);
+        }
+        if !pending.is_empty() {
+            report.full_blocks += 1;
+        }
+        report.max_backlog = report.max_backlog.max(pending.len());
+        total_backlog += pending.len() as u64;
+    }
+    report.final_backlog = pending.len() + arrivals.count();
+    report.mean_backlog = total_backlog as f64 / report.blocks.max(1) as f64;
+    report.mean_wait = total_wait as f64 / report.included.max(1) as f64;
+    report
+}
+
+// Tableau comparant les paramètres en vigueur (`current`) et candidats
+pub fn compare(traffic: &Traffic, current: &ConsensusParams, candidate: &ConsensusParams) -> String {
+    let (before, after) = (simulate(traffic, current), simulate(traffic, candidate));
+    let mut out = String::new();
+    let mut row = |label: &str, before: String, after: String| {
+        let _ = writeln!(out, "{:<24} {:>20} {:>20}", label, before, after);
+    };
+    row("", "current".to_string(), "candidate".to_string());
+    row("max_block_transactions", current.max_block_transactions.to_string(), candidate.max_block_transactions.to_string());
+    row("max_block_gas", current.max_block_gas.to_string(), candidate.max_block_gas.to_string());
+    row("blocks", before.blocks.to_string(), after.blocks.to_string());
+    row("transactions included", before.included.to_string(), after.included.to_string());
+    row("fees paid", before.fees_paid.to_string(), after.fees_paid.to_string());
+    row("full blocks", before.full_blocks.to_string(), after.full_blocks.to_string());
+    row("max backlog", before.max_backlog.to_string(), after.max_backlog.to_string());
+    row("mean backlog", format!("{:.2}", before.mean_backlog), format!("{:.2}", after.mean_backlog));
+    row("final backlog", before.final_backlog.to_string(), after.final_backlog.to_string());
+    row("mean wait (blocks)", format!("{:.2}", before.mean_wait), format!("{:.2}", after.mean_wait));
+    for producer in before.revenue.keys() {
+        let revenue = |report: &SimulationReport| report.revenue.get(producer).copied().unwrap_or_default().to_string();
+        row(&format!("revenue {}", &hex::encode(producer)[..16]), revenue(&before), revenue(&after));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::Blockchain;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    fn demand(arrival: u64, gas: u64, gas_price: u64) -> Demand {
+        Demand { arrival, gas, gas_price }
+    }
+
+    fn params(max_block_transactions: u64, max_block_gas: u64) -> ConsensusParams {
+        ConsensusParams { max_block_transactions, max_block_gas, ..ConsensusParams::default() }
+    }
+
+    #[test]
+    fn parses_profiles() {
+        assert_eq!("steady:12".parse(), Ok(Profile::Steady { transactions: 12 }));
+        assert_eq!("burst:3".parse(), Ok(Profile::Burst { transactions: 3 }));
+        assert_eq!("steady".parse::<Profile>(), Err("Invalid profile (expected steady:<n> or burst:<n>)"));
+        assert_eq!("steady:many".parse::<Profile>(), Err("Invalid profile load"));
+        assert_eq!("spiky:3".parse::<Profile>(), Err("Unknown profile (expected steady or burst)"));
+    }
+
+    #[test]
+    fn synthetic_traffic_is_reproducible() {
+        let traffic = Traffic::synthetic(Profile::Burst { transactions: 3 }, 20);
+        assert_eq!(traffic.producers, vec![None; 20]);
+        // Les blocs 9 et 19 reçoivent BURST_FACTOR fois la charge
+        let per_block: Vec<usize> = (0..20).map(|arrival| traffic.demand.iter().filter(|demand| demand.arrival == arrival).count()).collect();
+        let expected: Vec<usize> = (0..20).map(|arrival| if arrival % BURST_PERIOD == BURST_PERIOD - 1 { 15 } else { 3 }).collect();
+        assert_eq!(per_block, expected);
+        assert!(traffic.demand.iter().all(|demand| (1..=MAX_SYNTHETIC_GAS_PRICE).contains(&demand.gas_price)));
+
+        let prices = |traffic: &Traffic| traffic.demand.iter().map(|demand| (demand.arrival, demand.gas, demand.gas_price)).collect::<Vec<_>>();
+        assert_eq!(prices(&Traffic::synthetic(Profile::Burst { transactions: 3 }, 20)), prices(&traffic));
+        assert_eq!(Traffic::synthetic(Profile::Steady { transactions: 4 }, 5).demand.len(), 20);
+    }
+
+    #[test]
+    fn blocks_include_pending_demand_within_their_limits() {
+        let (first, second) = (keypair(1).public, keypair(2).public);
+        let traffic = Traffic {
+            demand: vec![demand(0, 100, 2), demand(0, 100, 2), demand(0, 100, 2), demand(1, 300, 1), demand(5, 100, 1)],
+            producers: vec![Some(first), Some(second), Some(first)],
+        };
+
+        // Limité par le nombre de transactions : la troisième attend le bloc suivant
+        let report = simulate(&traffic, &params(2, 1_000));
+        assert_eq!((report.blocks, report.included, report.fees_paid), (3, 4, 900));
+        assert_eq!(report.revenue, BTreeMap::from([(first.to_bytes(), 400), (second.to_bytes(), 500)]));
+        assert_eq!((report.full_blocks, report.max_backlog, report.mean_backlog), (1, 1, 1.0 / 3.0));
+        // La demande arrivée après le dernier bloc reste en attente
+        assert_eq!((report.final_backlog, report.mean_wait), (1, 0.25));
+
+        // Limité par le gaz : une transaction plus lourde que le bloc n'est jamais incluse
+        let report = simulate(&traffic, &params(10, 250));
+        assert_eq!((report.included, report.fees_paid), (3, 600));
+        assert_eq!((report.full_blocks, report.max_backlog, report.final_backlog), (3, 1, 2));
+        assert_eq!(report.mean_wait, 1.0 / 3.0);
+    }
+
+    #[test]
+    fn replays_the_last_blocks_of_a_chain() {
+        let (validator, alice) = (keypair(1), keypair(2));
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain.allocate(&alice.public, 1_000_000);
+        for slot in 1..=3 {
+            for _ in 0..slot {
+                let transaction = Transaction::signed(chain.config.chain_id, &alice, chain.next_nonce(&alice.public), &validator.public, 10, TransactionKind::Transfer, slot, chain.now(), Expiry::Timestamp(chain.now() + 60));
+                chain.add_transaction(transaction).unwrap();
+            }
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            chain.validate_and_create_block(&validator, slot).unwrap();
+        }
+
+        let traffic = Traffic::replay(&chain.chain, 2);
+        assert_eq!(traffic.producers, vec![Some(validator.public); 2]);
+        let arrivals: Vec<(u64, u64)> = traffic.demand.iter().map(|demand| (demand.arrival, demand.gas_price)).collect();
+        assert_eq!(arrivals, vec![(0, 2), (0, 2), (1, 3), (1, 3), (1, 3)]);
+        assert!(traffic.demand.iter().all(|demand| demand.gas == max_gas(&chain.chain[2].transactions[0])));
+        assert_eq!(Traffic::replay(&chain.chain, 10).demand.len(), 6);
+
+        // Les mêmes blocs sous une limite plus stricte laissent une transaction en attente
+        let table = compare(&traffic, &ConsensusParams::default(), &params(2, 50_000_000));
+        let row = |label: &str| table.lines().find(|line| line.starts_with(label)).unwrap().split_whitespace().rev().take(2).collect::<Vec<_>>();
+        assert_eq!(row("transactions included"), vec!["4", "5"]);
+        assert_eq!(row("final backlog"), vec!["1", "0"]);
+        assert!(table.contains(&format!("revenue {}", &hex::encode(validator.public.as_bytes())[..16])));
+    }
+}
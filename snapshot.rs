@@ -0,0 +1,235 @@
+use ed25519_dalek::PublicKey;
+
+use crate::assets::Assets;
+use crate::beacon::Beacon;
+use crate::compliance::Compliance;
+use crate::confidential::ConfidentialBalances;
+use crate::contracts::Contracts;
+use crate::encoding::{Decoder, Encoder, SNAPSHOT_DOMAIN};
+use crate::escrow::Escrows;
+use crate::governance::Governance;
+use crate::incidents::Incidents;
+use crate::ledger::Ledger;
+use crate::nonces::Nonces;
+use crate::light::HeaderSource;
+use crate::recovery::Recoveries;
+use crate::rewards::Rewards;
+use crate::rotation::KeyHistory;
+use crate::upgrade::Upgrades;
+use crate::vesting::Vesting;
+use crate::{Block, Blockchain, Validator, MEDIAN_TIME_SPAN};
+
+// État complet de la chaîne après le bloc `height`. Seul l'ensemble des
+// validateurs, le hash d'état de chaque groupe de confidentialité, les comptes
+// système, les soldes, les séquestres ouverts, les comptes en acquisition, les
+// contrats et leur stockage, les soldes confidentiels, les actifs et leurs
+// listes d'accès, les nonces utilisés, les gels de conformité, la balise
+// d'aléa, les incidents, les signaux de version, la gouvernance, l'historique
+// des clés de validateurs, les récompenses en cours, les horodatages des
+// derniers blocs et la difficulté font aujourd'hui partie de l'état.
+#[derive(Clone, Debug)]
+pub struct StateSnapshot {
+    pub height: u64,
+    pub block_hash: Vec<u8>,
+    // Triés par clé publique pour un encodage déterministe
+    pub validators: Vec<Validator>,
+    // (identifiant de groupe, hash d'état privé), triés par identifiant
+    pub private_states: Vec<(Vec<u8>, Vec<u8>)>,
+    // Triés par adresse
+    pub system_accounts: Vec<PublicKey>,
+    pub ledger: Ledger,
+    pub escrows: Escrows,
+    pub vesting: Vesting,
+    pub contracts: Contracts,
+    pub recoveries: Recoveries,
+    pub confidential: ConfidentialBalances,
+    pub assets: Assets,
+    pub nonces: Nonces,
+    pub compliance: Compliance,
+    pub beacon: Beacon,
+    pub incidents: Incidents,
+    pub upgrades: Upgrades,
+    pub governance: Governance,
+    pub key_history: KeyHistory,
+    pub rewards: Rewards,
+    // Horodatages des MEDIAN_TIME_SPAN derniers blocs au plus, jusqu'à `height`
+    // inclus, du plus ancien au plus récent
+    pub recent_timestamps: Vec<u64>,
+    // Difficulté du bloc suivant `height`
+    pub difficulty: u64,
+}
+
+impl StateSnapshot {
+    pub fn encode(&self) -> Vec<u8> {
+        self.encoder().finish()
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        self.encoder().hash()
+    }
+
+    fn encoder(&self) -> Encoder {
+        let mut encoder = Encoder::new(SNAPSHOT_DOMAIN);
+        encoder.put_u64(self.height);
+        encoder.put_bytes(&self.block_hash);
+        encoder.put_u32(self.validators.len() as u32);
+        for validator in &self.validators {
+            encoder.put_public_key(&validator.public_key);
+            encoder.put_u64(validator.stake);
+            encoder.put_u64(validator.contribution_score.to_bits());
+            match validator.last_validated_block {
+                Some(index) => {
+                    encoder.put_u8(1);
+                    encoder.put_u64(index);
+                }
+                None => encoder.put_u8(0),
+            }
+            encoder.put_u64(validator.missed_slots);
+            match validator.inactive_since {
+                Some(height) => {
+                    encoder.put_u8(1);
+                    encoder.put_u64(height);
+                }
+                None => encoder.put_u8(0),
+            }
+            encoder.put_u8(validator.standby as u8);
+        }
+        encoder.put_u32(self.private_states.len() as u32);
+        for (group_id, state) in &self.private_states {
+            encoder.put_bytes(group_id);
+            encoder.put_bytes(state);
+        }
+        encoder.put_u32(self.system_accounts.len() as u32);
+        for address in &self.system_accounts {
+            encoder.put_public_key(address);
+        }
+        self.ledger.encode_into(&mut encoder);
+        self.escrows.encode_into(&mut encoder);
+        self.vesting.encode_into(&mut encoder);
+        self.contracts.encode_into(&mut encoder);
+        self.recoveries.encode_into(&mut encoder);
+        self.confidential.encode_into(&mut encoder);
+        self.assets.encode_into(&mut encoder);
+        self.nonces.encode_into(&mut encoder);
+        self.compliance.encode_into(&mut encoder);
+        self.beacon.encode_into(&mut encoder);
+        self.incidents.encode_into(&mut encoder);
+        self.upgrades.encode_into(&mut encoder);
+        self.governance.encode_into(&mut encoder);
+        self.key_history.encode_into(&mut encoder);
+        self.rewards.encode_into(&mut encoder);
+        encoder.put_u32(self.recent_timestamps.len() as u32);
+        for timestamp in &self.recent_timestamps {
+            encoder.put_u64(*timestamp);
+        }
+        encoder.put_u64(self.difficulty);
+        encoder
+    }
+
+    pub fn decode(data: &[u8]) -> Result<StateSnapshot, &'static str> {
+        let mut decoder = Decoder::new(data, SNAPSHOT_DOMAIN)?;
+        let height = decoder.get_u64()?;
+        let block_hash = decoder.get_bytes()?.to_vec();
+
+        let count = decoder.get_u32()?;
+        let mut validators = Vec::new();
+        for _ in 0..count {
+            let public_key = decoder.get_public_key()?;
+            let stake = decoder.get_u64()?;
+            let contribution_score = f64::from_bits(decoder.get_u64()?);
+            let last_validated_block = match decoder.get_u8()? {
+                0 => None,
+                1 => Some(decoder.get_u64()?),
+                _ => return Err("Invalid snapshot encoding"),
+            };
+            let missed_slots = decoder.get_u64()?;
+            let inactive_since = match decoder.get_u8()? {
+                0 => None,
+                1 => Some(decoder.get_u64()?),
+                _ => return Err("Invalid snapshot encoding"),
+            };
+            let standby = match decoder.get_u8()? {
+                0 => false,
+                1 => true,
+                _ => return Err("Invalid snapshot encoding"),
+            };
+            validators.push(Validator {
+                public_key,
+                stake,
+                contribution_score,
+                last_validated_block,
+                missed_slots,
+                inactive_since,
+                standby,
+            });
+        }
+
+        let count = decoder.get_u32()?;
+        let mut private_states = Vec::new();
+        for _ in 0..count {
+            private_states.push((decoder.get_bytes()?.to_vec(), decoder.get_bytes()?.to_vec()));
+        }
+
+        let count = decoder.get_u32()?;
+        let mut system_accounts = Vec::new();
+        for _ in 0..count {
+            system_accounts.push(decoder.get_public_key()?);
+        }
+        let ledger = Ledger::decode_from(&mut decoder)?;
+        let escrows = Escrows::decode_from(&mut decoder)?;
+        let vesting = Vesting::decode_from(&mut decoder)?;
+        let contracts = Contracts::decode_from(&mut decoder)?;
+        let recoveries = Recoveries::decode_from(&mut decoder)?;
+        let confidential = ConfidentialBalances::decode_from(&mut decoder)?;
+        let assets = Assets::decode_from(&mut decoder)?;
+        let nonces = Nonces::decode_from(&mut decoder)?;
+        let compliance = Compliance::decode_from(&mut decoder)?;
+        let beacon = Beacon::decode_from(&mut decoder)?;
+        let incidents = Incidents::decode_from(&mut decoder)?;
+        let upgrades = Upgrades::decode_from(&mut decoder)?;
+        let governance = Governance::decode_from(&mut decoder)?;
+        let key_history = KeyHistory::decode_from(&mut decoder)?;
+        let rewards = Rewards::decode_from(&mut decoder)?;
+        let count = decoder.get_u32()?;
+        if count as usize > MEDIAN_TIME_SPAN {
+            return Err("Invalid snapshot encoding");
+        }
+        let mut recent_timestamps = Vec::new();
+        for _ in 0..count {
+            recent_timestamps.push(decoder.get_u64()?);
+        }
+        let difficulty = decoder.get_u64()?;
+        decoder.finish()?;
+
+        let snapshot = StateSnapshot { height, block_hash, validators, private_states, system_accounts, ledger, escrows, vesting, contracts, recoveries, confidential, assets, nonces, compliance, beacon, incidents, upgrades, governance, key_history, rewards, recent_timestamps, difficulty };
+        if snapshot.validators.windows(2).any(|pair| pair[0].public_key.to_bytes() >= pair[1].public_key.to_bytes()) {
+            return Err("Snapshot validators not in canonical order");
+        }
+        if snapshot.private_states.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+            return Err("Snapshot private states not in canonical order");
+        }
+        if snapshot.system_accounts.windows(2).any(|pair| pair[0].to_bytes() >= pair[1].to_bytes()) {
+            return Err("Snapshot system accounts not in canonical order");
+        }
+        Ok(snapshot)
+    }
+}
+
+// Ce qu'un nœud complet fournit à un nœud en synchronisation rapide
+pub trait SnapshotSource: HeaderSource {
+    fn latest_snapshot(&self) -> Option<StateSnapshot>;
+    fn blocks(&self, from: u64, count: usize) -> Vec<Block>;
+}
+
+impl SnapshotSource for Blockchain {
+    fn latest_snapshot(&self) -> Option<StateSnapshot> {
+        self.latest_snapshot.clone()
+    }
+
+    fn blocks(&self, from: u64, count: usize) -> Vec<Block> {
+        let Some(offset) = from.checked_sub(self.first_index()) else {
+            return Vec::new();
+        };
+        self.chain.iter().skip(offset as usize).take(count).cloned().collect()
+    }
+}
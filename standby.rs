@@ -0,0 +1,486 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use ed25519_dalek::PublicKey;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::encoding::{decode_block, encode_block, Decoder, Encoder, PAIRED_SYNC_DOMAIN, STATE_DIFF_DOMAIN};
+use crate::ledger::Ledger;
+use crate::receipts::{Receipt, ReceiptStatus};
+use crate::snapshot::StateSnapshot;
+use crate::{Block, Blockchain};
+
+// Blocs couverts par le journal des comptes modifiés : au-delà, le nœud de
+// secours repasse par la synchronisation entre pairs
+const JOURNAL_BLOCKS: usize = 10_000;
+
+// Délai accordé à chaque échange entre les deux nœuds
+const EXCHANGE_TIMEOUT: Duration = Duration::from_secs(60);
+
+const MAX_REQUEST_SIZE: usize = 4096;
+const MAX_RESPONSE_SIZE: usize = 256 * 1024 * 1024;
+
+const REQUEST_DIFF: u8 = 0;
+const RESPONSE_DIFF: u8 = 1;
+const RESPONSE_ERROR: u8 = 2;
+
+// Motifs d'échec d'exécution de contrat ; un motif inconnu est rapporté génériquement
+const EXECUTION_FAILURES: [&str; 10] = [
+    "Out of gas",
+    "Stack underflow",
+    "Stack overflow",
+    "Invalid jump target",
+    "Invalid contract opcode",
+    "Contract reverted",
+    "Value too large",
+    "Integer operand too long",
+    "Truncated contract instruction",
+    "Invalid contract code size",
+];
+
+// Comptes dont le solde a changé, par bloc, pour les derniers blocs appliqués
+#[derive(Debug, Default)]
+pub struct ChangeJournal {
+    blocks: VecDeque<(u64, HashSet<PublicKey>)>,
+}
+
+impl ChangeJournal {
+    pub fn record(&mut self, height: u64, accounts: HashSet<PublicKey>) {
+        self.blocks.push_back((height, accounts));
+        if self.blocks.len() > JOURNAL_BLOCKS {
+            self.blocks.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    // Comptes modifiés par les blocs postérieurs à `height` ; `None` si le
+    // journal ne remonte pas jusqu'au bloc suivant
+    pub fn changed_since(&self, height: u64) -> Option<HashSet<PublicKey>> {
+        let mut changed = HashSet::new();
+        let mut covered = false;
+        for (block, accounts) in self.blocks.iter().rev() {
+            if *block <= height {
+                covered = true;
+                break;
+            }
+            changed.extend(accounts.iter().copied());
+        }
+        let first = self.blocks.front().map(|(block, _)| *block);
+        (covered || first == Some(height + 1) || self.blocks.is_empty()).then_some(changed)
+    }
+}
+
+// Ce qu'il manque au nœud de secours arrêté à une hauteur connue du primaire :
+// les blocs suivants, les soldes modifiés depuis et le reste de l'état entier
+// (ensemble petit devant les soldes). Les blocs ne sont pas réexécutés ; l'état
+// obtenu est vérifié contre le hash de l'état du primaire.
+#[derive(Debug)]
+pub struct StateDiff {
+    // État du primaire après son dernier bloc, sans les soldes
+    state: StateSnapshot,
+    // Soldes modifiés (0 pour un compte vidé), triés par compte
+    balances: Vec<(PublicKey, u64)>,
+    state_hash: Vec<u8>,
+    blocks: Vec<Block>,
+    // Reçus des transactions de ces blocs, sans leurs événements
+    receipts: Vec<Receipt>,
+}
+
+impl StateDiff {
+    pub fn since(chain: &Blockchain, height: u64, block_hash: &[u8]) -> Result<StateDiff, &'static str> {
+        let block = chain.block_at(height).ok_or("Standby height not available on the primary")?;
+        if block.current_hash != block_hash {
+            return Err("Standby chain diverges from the primary");
+        }
+        let changed = chain.state_changes.changed_since(height).ok_or("Standby too far behind for a differential sync")?;
+        let mut balances: Vec<(PublicKey, u64)> = changed.into_iter()
+            .map(|account| (account, chain.accounts.ledger.balance(&account)))
+            .collect();
+        balances.sort_by_key(|(account, _)| account.to_bytes());
+
+        let mut state = chain.snapshot();
+        let state_hash = state.hash();
+        state.ledger = Ledger::default();
+        let blocks: Vec<Block> = chain.chain.iter().filter(|block| block.index > height).cloned().collect();
+        let receipts = blocks.iter()
+            .flat_map(|block| &block.transactions)
+            .filter_map(|tx| chain.receipts.get(&tx.hash()).cloned())
+            .collect();
+        Ok(StateDiff { state, balances, state_hash, blocks, receipts })
+    }
+
+    pub fn height(&self) -> u64 {
+        self.state.height
+    }
+
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    // État complet du primaire, reconstitué à partir des soldes de `ledger`
+    pub fn state(&self, ledger: &Ledger) -> Result<StateSnapshot, &'static str> {
+        let mut state = self.state.clone();
+        state.ledger = ledger.clone();
+        for (account, balance) in &self.balances {
+            state.ledger.set_balance(account, *balance);
+        }
+        if state.hash() != self.state_hash {
+            return Err("State diff does not match the primary state");
+        }
+        Ok(state)
+    }
+
+    pub fn into_parts(self) -> (Vec<Block>, Vec<Receipt>) {
+        (self.blocks, self.receipts)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new(STATE_DIFF_DOMAIN);
+        encoder.put_bytes(&self.state.encode());
+        encoder.put_u32(self.balances.len() as u32);
+        for (account, balance) in &self.balances {
+            encoder.put_public_key(account);
+            encoder.put_u64(*balance);
+        }
+        encoder.put_bytes(&self.state_hash);
+        encoder.put_u32(self.blocks.len() as u32);
+        for block in &self.blocks {
+            encoder.put_bytes(&encode_block(block));
+        }
+        encoder.put_u32(self.receipts.len() as u32);
+        for receipt in &self.receipts {
+            encoder.put_bytes(&receipt.transaction_hash);
+            encoder.put_u64(receipt.block_index);
+            encoder.put_u32(receipt.position);
+            match receipt.status {
+                ReceiptStatus::Success => encoder.put_u8(0),
+                ReceiptStatus::Failed(reason) => {
+                    encoder.put_u8(1);
+                    encoder.put_bytes(reason.as_bytes());
+                }
+            }
+            encoder.put_u64(receipt.gas_used);
+            encoder.put_u64(receipt.gas_price);
+            encoder.put_u64(receipt.fee);
+        }
+        encoder.finish()
+    }
+
+    pub fn decode(data: &[u8]) -> Result<StateDiff, &'static str> {
+        let mut decoder = Decoder::new(data, STATE_DIFF_DOMAIN)?;
+        let state = StateSnapshot::decode(decoder.get_bytes()?)?;
+        let mut balances = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            balances.push((decoder.get_public_key()?, decoder.get_u64()?));
+        }
+        let state_hash = decoder.get_bytes()?.to_vec();
+        let mut blocks = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            blocks.push(decode_block(decoder.get_bytes()?)?);
+        }
+        let mut receipts = Vec::new();
+        for _ in 0..decoder.get_u32()? {
+            let transaction_hash = decoder.get_bytes()?.to_vec();
+            let block_index = decoder.get_u64()?;
+            let position = decoder.get_u32()?;
+            let status = match decoder.get_u8()? {
+                0 => ReceiptStatus::Success,
+                1 => {
+                    let reason = decoder.get_bytes()?;
+                    let known = EXECUTION_FAILURES.iter().find(|known| known.as_bytes() == reason);
+                    ReceiptStatus::Failed(known.copied().unwrap_or("Contract execution failed"))
+                }
+                _ => return Err("Invalid receipt status"),
+            };
+            receipts.push(Receipt {
+                transaction_hash,
+                block_index,
+                position,
+                status,
+                gas_used: decoder.get_u64()?,
+                gas_price: decoder.get_u64()?,
+                fee: decoder.get_u64()?,
+                events: Vec::new(),
+            });
+        }
+        decoder.finish()?;
+        Ok(StateDiff { state, balances, state_hash, blocks, receipts })
+    }
+}
+
+// Côté primaire : répond aux demandes authentifiées du nœud de secours apparié
+pub async fn serve(addr: &str, blockchain: Arc<Mutex<Blockchain>>, auth_key: &str) -> Result<(), String> {
+    let auth_key = parse_auth_key(auth_key)?;
+    let listener = TcpListener::bind(addr).await.map_err(|e| format!("{}: {}", addr, e))?;
+    loop {
+        let Ok((mut stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let (blockchain, auth_key) = (blockchain.clone(), auth_key.clone());
+        tokio::spawn(async move {
+            let answer = tokio::time::timeout(EXCHANGE_TIMEOUT, answer(&mut stream, &blockchain, &auth_key)).await;
+            if let Err(e) = answer.unwrap_or_else(|_| Err("Standby request timed out".to_string())) {
+                warn!(error = %e, "Standby request rejected");
+            }
+        });
+    }
+}
+
+async fn answer(stream: &mut TcpStream, blockchain: &Mutex<Blockchain>, auth_key: &[u8]) -> Result<(), String> {
+    let request = read_frame(stream, MAX_REQUEST_SIZE).await.map_err(|e| e.to_string())?;
+    let (kind, nonce, body) = open(auth_key, &request)?;
+    if kind != REQUEST_DIFF {
+        return Err("Unknown standby request".to_string());
+    }
+    let mut decoder = Decoder::new(&body, PAIRED_SYNC_DOMAIN)?;
+    let height = decoder.get_u64()?;
+    let block_hash = decoder.get_bytes()?.to_vec();
+    decoder.finish()?;
+
+    let diff = StateDiff::since(&*blockchain.lock().await, height, &block_hash);
+    let response = match &diff {
+        Ok(diff) => seal(auth_key, RESPONSE_DIFF, &nonce, &diff.encode()),
+        Err(e) => seal(auth_key, RESPONSE_ERROR, &nonce, e.as_bytes()),
+    };
+    write_frame(stream, &response).await.map_err(|e| e.to_string())?;
+    diff.map(|_| ()).map_err(str::to_string)
+}
+
+// Côté nœud de secours : récupère auprès du primaire ce qui manque depuis le
+// dernier bloc local et l'applique ; retourne l'index du prochain bloc attendu
+pub async fn catch_up(primary: &str, blockchain: &Mutex<Blockchain>, auth_key: &str) -> Result<u64, String> {
+    let auth_key = parse_auth_key(auth_key)?;
+    let (height, block_hash) = {
+        let chain = blockchain.lock().await;
+        let header = chain.last_header().ok_or("No local block to resume from")?;
+        (header.index, header.current_hash)
+    };
+    let mut nonce = [0u8; 32];
+    OsRng.fill_bytes(&mut nonce);
+    let mut body = Encoder::new(PAIRED_SYNC_DOMAIN);
+    body.put_u64(height);
+    body.put_bytes(&block_hash);
+
+    let exchange = async {
+        let mut stream = TcpStream::connect(primary).await.map_err(|e| e.to_string())?;
+        write_frame(&mut stream, &seal(&auth_key, REQUEST_DIFF, &nonce, &body.finish())).await.map_err(|e| e.to_string())?;
+        read_frame(&mut stream, MAX_RESPONSE_SIZE).await.map_err(|e| e.to_string())
+    };
+    let response = tokio::time::timeout(EXCHANGE_TIMEOUT, exchange).await.map_err(|_| "Primary timed out".to_string())??;
+    let (kind, response_nonce, body) = open(&auth_key, &response)?;
+    if response_nonce != nonce {
+        return Err("Unexpected primary response".to_string());
+    }
+    match kind {
+        RESPONSE_DIFF => {}
+        RESPONSE_ERROR => return Err(String::from_utf8_lossy(&body).into_owned()),
+        _ => return Err("Unexpected primary response".to_string()),
+    }
+
+    // Décodage et vérification des en-têtes hors du verrou
+    let diff = tokio::task::spawn_blocking(move || -> Result<StateDiff, &'static str> {
+        let diff = StateDiff::decode(&body)?;
+        for block in diff.blocks() {
+            block.header().verify()?;
+        }
+        Ok(diff)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    let (applied, target) = (diff.blocks().len(), diff.height());
+    let next_index = {
+        let mut chain = blockchain.lock().await;
+        chain.apply_state_diff(diff)?;
+        chain.next_index()
+    };
+    info!(applied, height = target, "Caught up with the primary");
+    Ok(next_index)
+}
+
+fn parse_auth_key(auth_key: &str) -> Result<Vec<u8>, String> {
+    match hex::decode(auth_key.trim()) {
+        Ok(key) if key.len() == 32 => Ok(key),
+        _ => Err("Pairing key must be 32 bytes of hex".to_string()),
+    }
+}
+
+fn authenticate(auth_key: &[u8], payload: &[u8]) -> Vec<u8> {
+    let mut encoder = Encoder::new(PAIRED_SYNC_DOMAIN);
+    encoder.put_bytes(auth_key);
+    encoder.put_bytes(payload);
+    encoder.hash()
+}
+
+// Trame authentifiée avec la clé d'appariement, liée à la requête par son nonce
+fn seal(auth_key: &[u8], kind: u8, nonce: &[u8], body: &[u8]) -> Vec<u8> {
+    let mut payload = Encoder::new(PAIRED_SYNC_DOMAIN);
+    payload.put_u8(kind);
+    payload.put_bytes(nonce);
+    payload.put_bytes(body);
+    let payload = payload.finish();
+    let mut encoder = Encoder::new(PAIRED_SYNC_DOMAIN);
+    encoder.put_bytes(&payload);
+    encoder.put_bytes(&authenticate(auth_key, &payload));
+    encoder.finish()
+}
+
+fn open(auth_key: &[u8], frame: &[u8]) -> Result<(u8, Vec<u8>, Vec<u8>), String> {
+    let mut decoder = Decoder::new(frame, PAIRED_SYNC_DOMAIN)?;
+    let payload = decoder.get_bytes()?;
+    let tag = decoder.get_bytes()?;
+    decoder.finish()?;
+    if authenticate(auth_key, payload) != tag {
+        return Err("Pairing authentication failed".to_string());
+    }
+    let mut decoder = Decoder::new(payload, PAIRED_SYNC_DOMAIN)?;
+    let kind = decoder.get_u8()?;
+    let nonce = decoder.get_bytes()?.to_vec();
+    let body = decoder.get_bytes()?.to_vec();
+    decoder.finish()?;
+    Ok((kind, nonce, body))
+}
+
+async fn write_frame(stream: &mut TcpStream, frame: &[u8]) -> std::io::Result<()> {
+    stream.write_all(&(frame.len() as u32).to_be_bytes()).await?;
+    stream.write_all(frame).await
+}
+
+async fn read_frame(stream: &mut TcpStream, max_size: usize) -> std::io::Result<Vec<u8>> {
+    let length = stream.read_u32().await? as usize;
+    if length > max_size {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Pairing frame too large"));
+    }
+    let mut frame = vec![0u8; length];
+    stream.read_exact(&mut frame).await?;
+    Ok(frame)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ed25519_dalek::{Keypair, SecretKey};
+
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::txbuilder::TxBuilder;
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+    const PAIRING_KEY: &str = "0707070707070707070707070707070707070707070707070707070707070707";
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    fn genesis(clock: &MockClock) -> Blockchain {
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&keypair(1).public, 1000).unwrap();
+        chain.allocate(&keypair(2).public, 1_000_000);
+        chain
+    }
+
+    // Bloc du créneau `slot` avec un transfert d'alice vers un nouveau compte
+    fn produce(primary: &mut Blockchain, clock: &MockClock, slot: u64) -> Block {
+        clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+        let alice = keypair(2);
+        let transfer = TxBuilder::transfer().to(keypair(10 + slot as u8).public).amount(100 * slot).chain_id(primary.config.chain_id)
+            .nonce(primary.next_nonce(&alice.public)).timestamp(primary.now()).sign(&alice).unwrap();
+        primary.add_transaction(transfer).unwrap();
+        primary.validate_and_create_block(&keypair(1), slot).unwrap()
+    }
+
+    fn assert_same_state(synced: &Blockchain, replayed: &Blockchain) {
+        assert_eq!(synced.snapshot().hash(), replayed.snapshot().hash());
+        assert_eq!(synced.state_tree.root(), replayed.state_tree.root());
+        assert_eq!(synced.last_hash(), replayed.last_hash());
+        for block in &replayed.chain {
+            for tx in &block.transactions {
+                let (synced, replayed) = (&synced.receipts[&tx.hash()], &replayed.receipts[&tx.hash()]);
+                assert_eq!((&synced.status, synced.gas_used, synced.fee, synced.position), (&replayed.status, replayed.gas_used, replayed.fee, replayed.position));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn standby_caught_up_from_a_diff_matches_a_full_replay() {
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let (mut primary, mut standby, mut replayed) = (genesis(&clock), genesis(&clock), genesis(&clock));
+        for slot in 1..=6 {
+            let block = produce(&mut primary, &clock, slot);
+            if slot <= 2 {
+                standby.add_block(block.clone()).unwrap();
+            }
+            replayed.add_block(block).unwrap();
+        }
+        let primary = Arc::new(Mutex::new(primary));
+        let standby = Mutex::new(standby);
+
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+        tokio::spawn({
+            let (addr, primary) = (addr.clone(), primary.clone());
+            async move { serve(&addr, primary, PAIRING_KEY).await }
+        });
+        let mut next_index = Err(String::new());
+        for _ in 0..50 {
+            next_index = catch_up(&addr, &standby, PAIRING_KEY).await;
+            if next_index.is_ok() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(next_index, Ok(6));
+        assert_same_state(&*standby.lock().await, &replayed);
+
+        // Le nœud rattrapé suit ensuite la chaîne en appliquant les blocs
+        let block = produce(&mut *primary.lock().await, &clock, 7);
+        standby.lock().await.add_block(block.clone()).unwrap();
+        replayed.add_block(block).unwrap();
+        assert_same_state(&*standby.lock().await, &replayed);
+
+        let other_key = "08".repeat(32);
+        assert!(catch_up(&addr, &standby, &other_key).await.is_err());
+        assert_eq!(standby.lock().await.next_index(), 7);
+    }
+
+    #[test]
+    fn diffs_that_do_not_match_the_primary_are_rejected() {
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let (mut primary, mut standby) = (genesis(&clock), genesis(&clock));
+        for slot in 1..=4 {
+            let block = produce(&mut primary, &clock, slot);
+            if slot <= 2 {
+                standby.add_block(block).unwrap();
+            }
+        }
+        let height = standby.next_index() - 1;
+        assert_eq!(StateDiff::since(&primary, height, &[0; 32]).err(), Some("Standby chain diverges from the primary"));
+        let diff = StateDiff::since(&primary, height, &standby.last_hash()).unwrap();
+        assert_eq!(diff.height(), 3);
+        let before = standby.snapshot().hash();
+
+        let mut balance = StateDiff::decode(&diff.encode()).unwrap();
+        balance.balances[0].1 += 1;
+        assert_eq!(standby.apply_state_diff(balance), Err("State diff does not match the primary state"));
+        let mut gap = StateDiff::decode(&diff.encode()).unwrap();
+        gap.blocks.remove(0);
+        assert_eq!(standby.apply_state_diff(gap), Err("State diff does not extend the local chain"));
+        let mut truncated = StateDiff::decode(&diff.encode()).unwrap();
+        truncated.blocks.pop();
+        assert_eq!(standby.apply_state_diff(truncated), Err("State diff does not match its blocks"));
+        assert_eq!(standby.snapshot().hash(), before);
+
+        standby.apply_state_diff(diff).unwrap();
+        assert_eq!(standby.snapshot().hash(), primary.snapshot().hash());
+    }
+}
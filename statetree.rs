@@ -0,0 +1,285 @@
+use std::collections::{BTreeMap, HashMap};
+use ed25519_dalek::PublicKey;
+
+use crate::encoding::{Encoder, STATE_TREE_DOMAIN};
+use crate::ledger::Ledger;
+
+const LEAF: u8 = 0;
+const NODE: u8 = 1;
+
+// Profondeur maximale : une feuille par clé publique de 256 bits
+const KEY_BITS: usize = 256;
+
+// Sous-arbre : (profondeur, préfixe complété par des 0)
+type NodeKey = (usize, [u8; 32]);
+
+fn empty_hash() -> Vec<u8> {
+    vec![0; 32]
+}
+
+fn leaf_hash(account: &[u8; 32], balance: u64) -> Vec<u8> {
+    let mut encoder = Encoder::new(STATE_TREE_DOMAIN);
+    encoder.put_u8(LEAF);
+    encoder.put_bytes(account);
+    encoder.put_u64(balance);
+    encoder.hash()
+}
+
+fn node_hash(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut encoder = Encoder::new(STATE_TREE_DOMAIN);
+    encoder.put_u8(NODE);
+    encoder.put_bytes(left);
+    encoder.put_bytes(right);
+    encoder.hash()
+}
+
+fn bit(key: &[u8; 32], depth: usize) -> bool {
+    key[depth / 8] >> (7 - depth % 8) & 1 == 1
+}
+
+// Préfixe des `depth` premiers bits de `key`, complété par des 0 (ou des 1)
+fn prefix(key: &[u8; 32], depth: usize, fill: bool) -> [u8; 32] {
+    let mut prefix = *key;
+    for position in depth..KEY_BITS {
+        let mask = 1 << (7 - position % 8);
+        if fill {
+            prefix[position / 8] |= mask;
+        } else {
+            prefix[position / 8] &= !mask;
+        }
+    }
+    prefix
+}
+
+// Arbre de Merkle creux des soldes, indexé par les bits de la clé publique. Un
+// sous-arbre vide a pour hash 32 octets nuls, un sous-arbre d'une seule feuille
+// le hash de cette feuille ; les autres combinent leurs deux moitiés. Les hash
+// des sous-arbres d'au moins deux feuilles sont conservés et seuls les chemins
+// des comptes modifiés sont recalculés.
+#[derive(Clone, Debug)]
+pub struct StateTree {
+    balances: BTreeMap<[u8; 32], u64>,
+    nodes: HashMap<NodeKey, Vec<u8>>,
+    root: Vec<u8>,
+}
+
+impl Default for StateTree {
+    fn default() -> Self {
+        StateTree { balances: BTreeMap::new(), nodes: HashMap::new(), root: empty_hash() }
+    }
+}
+
+impl StateTree {
+    pub fn from_ledger(ledger: &Ledger) -> Self {
+        let mut tree = StateTree {
+            balances: ledger.iter().map(|(account, balance)| (account.to_bytes(), balance)).collect(),
+            ..StateTree::default()
+        };
+        tree.refresh();
+        tree
+    }
+
+    pub fn root(&self) -> &[u8] {
+        &self.root
+    }
+
+    // Reporte les soldes des comptes modifiés puis recalcule la racine
+    pub fn update<'a>(&mut self, ledger: &Ledger, accounts: impl IntoIterator<Item = &'a PublicKey>) {
+        for account in accounts {
+            let key = account.to_bytes();
+            match ledger.balance(account) {
+                0 => self.balances.remove(&key),
+                balance => self.balances.insert(key, balance),
+            };
+            for depth in 0..KEY_BITS {
+                self.nodes.remove(&(depth, prefix(&key, depth, false)));
+            }
+        }
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        let mut computed = Vec::new();
+        self.root = self.subtree(0, [0; 32], &mut computed);
+        self.nodes.extend(computed);
+    }
+
+    // Hash du sous-arbre ; les hash absents du cache sont ajoutés à `computed`
+    fn subtree(&self, depth: usize, key: [u8; 32], computed: &mut Vec<(NodeKey, Vec<u8>)>) -> Vec<u8> {
+        if let Some(hash) = self.nodes.get(&(depth, key)) {
+            return hash.clone();
+        }
+        let mut leaves = self.balances.range(prefix(&key, depth, false)..=prefix(&key, depth, true));
+        match (leaves.next(), leaves.next()) {
+            (None, _) => empty_hash(),
+            (Some((account, balance)), None) => leaf_hash(account, *balance),
+            _ => {
+                let mut right = key;
+                right[depth / 8] |= 1 << (7 - depth % 8);
+                let left = self.subtree(depth + 1, key, computed);
+                let right = self.subtree(depth + 1, right, computed);
+                let hash = node_hash(&left, &right);
+                computed.push(((depth, key), hash.clone()));
+                hash
+            }
+        }
+    }
+
+    // Preuve du solde de `account` (inclusion, ou absence pour un solde nul)
+    pub fn prove(&self, account: &PublicKey) -> StateProof {
+        let key = account.to_bytes();
+        let mut siblings = Vec::new();
+        let mut depth = 0;
+        loop {
+            let mut leaves = self.balances.range(prefix(&key, depth, false)..=prefix(&key, depth, true));
+            match (leaves.next(), leaves.next()) {
+                (None, _) => return StateProof { siblings, leaf: None },
+                (Some((account, balance)), None) => return StateProof { siblings, leaf: Some((*account, *balance)) },
+                _ => {
+                    let mut sibling = prefix(&key, depth + 1, false);
+                    sibling[depth / 8] ^= 1 << (7 - depth % 8);
+                    siblings.push(self.subtree(depth + 1, sibling, &mut Vec::new()));
+                    depth += 1;
+                }
+            }
+        }
+    }
+}
+
+// Chemin d'un compte dans l'arbre des soldes : hash des sous-arbres voisins,
+// de la racine vers la feuille, et feuille atteinte (vide, ou un autre compte
+// de même préfixe pour une preuve d'absence)
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateProof {
+    pub siblings: Vec<Vec<u8>>,
+    pub leaf: Option<([u8; 32], u64)>,
+}
+
+impl StateProof {
+    // Solde de `account` attesté par la preuve sous la racine `root`
+    pub fn verify(&self, root: &[u8], account: &PublicKey) -> Result<u64, &'static str> {
+        let key = account.to_bytes();
+        let depth = self.siblings.len();
+        if depth >= KEY_BITS {
+            return Err("Invalid state proof");
+        }
+        let (mut hash, balance) = match &self.leaf {
+            None => (empty_hash(), 0),
+            Some((leaf, balance)) => {
+                if *balance == 0 || prefix(leaf, depth, false) != prefix(&key, depth, false) {
+                    return Err("Invalid state proof");
+                }
+                (leaf_hash(leaf, *balance), if *leaf == key { *balance } else { 0 })
+            }
+        };
+        for (depth, sibling) in self.siblings.iter().enumerate().rev() {
+            hash = if bit(&key, depth) { node_hash(sibling, &hash) } else { node_hash(&hash, sibling) };
+        }
+        if hash != root {
+            return Err("Invalid state proof");
+        }
+        Ok(balance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{PublicKey, SecretKey};
+
+    use super::{StateProof, StateTree};
+    use crate::ledger::Ledger;
+
+    // Racine de l'arbre de `ledger()`, à ne changer qu'avec les règles de consensus
+    const ROOT_VECTOR: &str = "bc3ffa72d7b0299da21b25d35e1d3ab9e9c345126dec90618a1c8ec8153d67ac";
+
+    fn account(seed: u8) -> PublicKey {
+        PublicKey::from(&SecretKey::from_bytes(&[seed; 32]).unwrap())
+    }
+
+    // Soldes 100, 200… des comptes de graine 1 à 8
+    fn ledger() -> Ledger {
+        let mut ledger = Ledger::default();
+        for seed in 1..=8 {
+            ledger.credit(&account(seed), seed as u64 * 100);
+        }
+        ledger
+    }
+
+    #[test]
+    fn matches_the_root_vector() {
+        assert_eq!(StateTree::default().root(), &[0; 32][..]);
+        let tree = StateTree::from_ledger(&ledger());
+        assert_eq!(hex::encode(tree.root()), ROOT_VECTOR);
+
+        // La mise à jour incrémentale donne la racine d'un arbre reconstruit
+        let mut ledger = ledger();
+        let mut updated = tree.clone();
+        ledger.set_balance(&account(3), 0);
+        ledger.credit(&account(5), 1);
+        ledger.credit(&account(42), 7);
+        updated.update(&ledger, &[account(3), account(5), account(42)]);
+        assert_eq!(updated.root(), StateTree::from_ledger(&ledger).root());
+        assert_ne!(updated.root(), tree.root());
+    }
+
+    #[test]
+    fn proves_inclusion_and_absence() {
+        let tree = StateTree::from_ledger(&ledger());
+        for seed in 1..=8 {
+            let proof = tree.prove(&account(seed));
+            assert_eq!(proof.leaf, Some((account(seed).to_bytes(), seed as u64 * 100)));
+            assert_eq!(proof.verify(tree.root(), &account(seed)), Ok(seed as u64 * 100));
+        }
+        // Un compte absent aboutit à un sous-arbre vide ou à la feuille d'un
+        // autre compte de même préfixe ; les deux cas sont couverts
+        let (mut empty, mut other) = (0, 0);
+        for seed in 9..=64 {
+            let proof = tree.prove(&account(seed));
+            match proof.leaf {
+                None => empty += 1,
+                Some(_) => other += 1,
+            }
+            assert_eq!(proof.verify(tree.root(), &account(seed)), Ok(0));
+        }
+        assert!(empty > 0 && other > 0);
+        assert_eq!(StateTree::default().prove(&account(1)).verify(&[0; 32], &account(1)), Ok(0));
+    }
+
+    #[test]
+    fn rejects_invalid_proofs() {
+        let tree = StateTree::from_ledger(&ledger());
+        let proof = tree.prove(&account(1));
+        let invalid = |proof: &StateProof, root: &[u8], account: &PublicKey| proof.verify(root, account) == Err("Invalid state proof");
+
+        assert!(invalid(&proof, &[0; 32], &account(1)));
+        assert!(invalid(&proof, tree.root(), &account(2)));
+        let mut balance = proof.clone();
+        balance.leaf = Some((account(1).to_bytes(), 101));
+        assert!(invalid(&balance, tree.root(), &account(1)));
+        let mut absent = proof.clone();
+        absent.leaf = None;
+        assert!(invalid(&absent, tree.root(), &account(1)));
+        let mut zero = proof.clone();
+        zero.leaf = Some((account(1).to_bytes(), 0));
+        assert!(invalid(&zero, tree.root(), &account(1)));
+        let mut sibling = proof.clone();
+        sibling.siblings[0][0] ^= 1;
+        assert!(invalid(&sibling, tree.root(), &account(1)));
+        let mut shortened = proof.clone();
+        shortened.siblings.pop();
+        assert!(invalid(&shortened, tree.root(), &account(1)));
+        let mut deep = proof.clone();
+        deep.siblings = vec![vec![0; 32]; 256];
+        assert!(invalid(&deep, tree.root(), &account(1)));
+
+        // La feuille d'une preuve d'absence n'atteste que le solde de son propre
+        // compte, pas celui du compte absent
+        let (_, absent) = (9..=64).map(|seed| (seed, tree.prove(&account(seed)))).find(|(_, proof)| proof.leaf.is_some()).unwrap();
+        let (leaf, balance) = absent.leaf.unwrap();
+        let owner = (1..=8).find(|seed| account(*seed).to_bytes() == leaf).unwrap();
+        assert_eq!(absent.verify(tree.root(), &account(owner)), Ok(balance));
+        let mut claimed = absent.clone();
+        claimed.leaf = Some((account(9).to_bytes(), balance));
+        assert!(invalid(&claimed, tree.root(), &account(9)));
+    }
+}
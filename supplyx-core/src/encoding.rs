@@ -0,0 +1,286 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::vec::Vec;
+use ed25519_dalek::{PublicKey, Signature, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use sha3::{Digest, Sha3_256};
+
+// Encodage binaire canonique utilisé pour tous les hachages et signatures.
+// Chaque message commence par une étiquette de domaine (transaction, bloc...)
+// afin qu'une signature produite pour un type ne soit jamais valide pour un autre,
+// et chaque champ de taille variable est préfixé par sa longueur.
+pub const TRANSACTION_DOMAIN: &[u8] = b"SUPPLYX_TRANSACTION_V1";
+pub const BLOCK_BODY_DOMAIN: &[u8] = b"SUPPLYX_BLOCK_BODY_V1";
+// Corps dont chaque transaction indique la version de son format
+pub const VERSIONED_BLOCK_BODY_DOMAIN: &[u8] = b"SUPPLYX_BLOCK_BODY_V2";
+
+// Domaine des signatures d'une transaction au format `version` : un format
+// nouveau ne peut pas être confondu avec un autre, même s'il n'ajoute qu'un champ
+pub fn transaction_domain(version: u32) -> Vec<u8> {
+    match version {
+        1 => TRANSACTION_DOMAIN.to_vec(),
+        _ => format!("SUPPLYX_TRANSACTION_V{}", version).into_bytes(),
+    }
+}
+
+pub struct Encoder {
+    buffer: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new(domain: &[u8]) -> Self {
+        let mut encoder = Encoder { buffer: Vec::new() };
+        encoder.put_bytes(domain);
+        encoder
+    }
+
+    pub fn put_u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    pub fn put_u32(&mut self, value: u32) {
+        self.buffer.extend_from_slice(&value.to_be_bytes());
+    }
+
+    pub fn put_u64(&mut self, value: u64) {
+        self.buffer.extend_from_slice(&value.to_be_bytes());
+    }
+
+    // Entier LEB128, utilisé pour les compteurs et index du corps de bloc
+    pub fn put_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buffer.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        self.buffer.push(value as u8);
+    }
+
+    // Champ de taille variable : longueur (u32 big-endian) puis contenu
+    pub fn put_bytes(&mut self, value: &[u8]) {
+        self.put_u32(value.len() as u32);
+        self.buffer.extend_from_slice(value);
+    }
+
+    // Les clés publiques ont une taille fixe, pas de préfixe nécessaire
+    pub fn put_public_key(&mut self, key: &PublicKey) {
+        self.buffer.extend_from_slice(key.as_bytes());
+    }
+
+    pub fn put_signature(&mut self, signature: &Signature) {
+        self.buffer.extend_from_slice(&signature.to_bytes());
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    pub fn hash(self) -> Vec<u8> {
+        Sha3_256::digest(&self.buffer).to_vec()
+    }
+}
+
+pub struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(data: &'a [u8], domain: &[u8]) -> Result<Self, &'static str> {
+        let mut decoder = Decoder { data, position: 0 };
+        if decoder.get_bytes()? != domain {
+            return Err("Unexpected encoding domain");
+        }
+        Ok(decoder)
+    }
+
+    // Décodeur d'un encodage dont le domaine est l'un de `domains` (versions
+    // successives d'un format) ; retourne aussi la position du domaine trouvé
+    pub fn versioned(data: &'a [u8], domains: &[&[u8]]) -> Result<(Self, usize), &'static str> {
+        let mut decoder = Decoder { data, position: 0 };
+        let domain = decoder.get_bytes()?;
+        let index = domains.iter().position(|candidate| *candidate == domain).ok_or("Unexpected encoding domain")?;
+        Ok((decoder, index))
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], &'static str> {
+        let end = self.position.checked_add(length).ok_or("Truncated encoding")?;
+        let bytes = self.data.get(self.position..end).ok_or("Truncated encoding")?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    pub fn get_u8(&mut self) -> Result<u8, &'static str> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn get_u32(&mut self) -> Result<u32, &'static str> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub fn get_u64(&mut self) -> Result<u64, &'static str> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    // Seul l'encodage minimal est accepté : un même corps de bloc n'a qu'un encodage
+    pub fn get_varint(&mut self) -> Result<u64, &'static str> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.get_u8()?;
+            if shift == 63 && byte > 1 {
+                return Err("Invalid varint");
+            }
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                if byte == 0 && shift > 0 {
+                    return Err("Non-canonical varint");
+                }
+                return Ok(value);
+            }
+        }
+        Err("Invalid varint")
+    }
+
+    pub fn get_bytes(&mut self) -> Result<&'a [u8], &'static str> {
+        let length = self.get_u32()? as usize;
+        self.take(length)
+    }
+
+    pub fn get_public_key(&mut self) -> Result<PublicKey, &'static str> {
+        PublicKey::from_bytes(self.take(PUBLIC_KEY_LENGTH)?).map_err(|_| "Invalid public key")
+    }
+
+    pub fn get_signature(&mut self) -> Result<Signature, &'static str> {
+        Signature::try_from(self.take(SIGNATURE_LENGTH)?).map_err(|_| "Invalid signature")
+    }
+
+    // Tout l'encodage doit avoir été consommé
+    pub fn finish(self) -> Result<(), &'static str> {
+        if self.position != self.data.len() {
+            return Err("Trailing bytes in encoding");
+        }
+        Ok(())
+    }
+}
+
+// Corps de transactions compact, dans le domaine `domain` : chaque clé
+// publique n'apparaît qu'une fois dans un dictionnaire (ordre de première
+// apparition), puis `write` encode chaque transaction en désignant ses clés
+// par leur index (`index_of`)
+pub fn encode_body<T>(domain: &[u8], transactions: &[T], keys: impl Fn(&T) -> [&PublicKey; 2], write: impl Fn(&T, &mut Encoder, &dyn Fn(&PublicKey) -> u64)) -> Vec<u8> {
+    let mut dictionary: Vec<PublicKey> = Vec::new();
+    let mut indices: BTreeMap<[u8; PUBLIC_KEY_LENGTH], u64> = BTreeMap::new();
+    for transaction in transactions {
+        for key in keys(transaction) {
+            indices.entry(key.to_bytes()).or_insert_with(|| {
+                dictionary.push(*key);
+                (dictionary.len() - 1) as u64
+            });
+        }
+    }
+    let index_of = |key: &PublicKey| indices[&key.to_bytes()];
+
+    let mut encoder = Encoder::new(domain);
+    encoder.put_varint(dictionary.len() as u64);
+    for key in &dictionary {
+        encoder.put_public_key(key);
+    }
+    encoder.put_varint(transactions.len() as u64);
+    for transaction in transactions {
+        write(transaction, &mut encoder, &index_of);
+    }
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use ed25519_dalek::{Keypair, SecretKey, Signer};
+
+    use super::*;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        let public = PublicKey::from(&secret);
+        Keypair { secret, public }
+    }
+
+    #[test]
+    fn decoder_reads_back_what_the_encoder_wrote() {
+        let key = keypair(1);
+        let signature = key.sign(b"bloc");
+        let mut encoder = Encoder::new(TRANSACTION_DOMAIN);
+        encoder.put_u8(7);
+        encoder.put_u32(70_000);
+        encoder.put_u64(u64::MAX);
+        for value in [0, 127, 128, 300, u64::MAX] {
+            encoder.put_varint(value);
+        }
+        encoder.put_bytes(b"");
+        encoder.put_bytes(b"lot 42");
+        encoder.put_public_key(&key.public);
+        encoder.put_signature(&signature);
+        let bytes = encoder.finish();
+
+        let mut decoder = Decoder::new(&bytes, TRANSACTION_DOMAIN).unwrap();
+        assert_eq!(decoder.get_u8(), Ok(7));
+        assert_eq!(decoder.get_u32(), Ok(70_000));
+        assert_eq!(decoder.get_u64(), Ok(u64::MAX));
+        for value in [0, 127, 128, 300, u64::MAX] {
+            assert_eq!(decoder.get_varint(), Ok(value));
+        }
+        assert_eq!(decoder.get_bytes(), Ok(&b""[..]));
+        assert_eq!(decoder.get_bytes(), Ok(&b"lot 42"[..]));
+        assert_eq!(decoder.get_public_key(), Ok(key.public));
+        assert_eq!(decoder.get_signature(), Ok(signature));
+        assert_eq!(decoder.finish(), Ok(()));
+
+        assert!(matches!(Decoder::new(&bytes, BLOCK_BODY_DOMAIN), Err("Unexpected encoding domain")));
+        let (_, version) = Decoder::versioned(&bytes, &[BLOCK_BODY_DOMAIN, TRANSACTION_DOMAIN]).unwrap();
+        assert_eq!(version, 1);
+    }
+
+    #[test]
+    fn decoder_rejects_truncated_trailing_and_non_canonical_input() {
+        let mut encoder = Encoder::new(TRANSACTION_DOMAIN);
+        encoder.put_u64(42);
+        let bytes = encoder.finish();
+        let mut decoder = Decoder::new(&bytes[..bytes.len() - 1], TRANSACTION_DOMAIN).unwrap();
+        assert_eq!(decoder.get_u64(), Err("Truncated encoding"));
+
+        let mut padded = bytes.clone();
+        padded.push(0);
+        let mut decoder = Decoder::new(&padded, TRANSACTION_DOMAIN).unwrap();
+        decoder.get_u64().unwrap();
+        assert_eq!(decoder.finish(), Err("Trailing bytes in encoding"));
+
+        // 1 encodé sur deux octets, puis une valeur dépassant 64 bits
+        for (varint, error) in [(vec![0x81, 0x00], "Non-canonical varint"), (vec![0xff; 10], "Invalid varint")] {
+            let mut encoded = Encoder::new(TRANSACTION_DOMAIN).finish();
+            encoded.extend(varint);
+            let mut decoder = Decoder::new(&encoded, TRANSACTION_DOMAIN).unwrap();
+            assert_eq!(decoder.get_varint(), Err(error));
+        }
+    }
+
+    #[test]
+    fn body_keys_are_listed_once_in_order_of_appearance() {
+        let (a, b, c) = (keypair(1).public, keypair(2).public, keypair(3).public);
+        let transfers = [(a, b, 5u64), (b, a, 6), (c, a, 7)];
+        let body = encode_body(BLOCK_BODY_DOMAIN, &transfers, |(from, to, _)| [from, to], |(from, to, amount), encoder, index_of| {
+            encoder.put_varint(index_of(from));
+            encoder.put_varint(index_of(to));
+            encoder.put_varint(*amount);
+        });
+
+        let mut decoder = Decoder::new(&body, BLOCK_BODY_DOMAIN).unwrap();
+        assert_eq!(decoder.get_varint(), Ok(3));
+        let keys: Vec<PublicKey> = (0..3).map(|_| decoder.get_public_key().unwrap()).collect();
+        assert_eq!(keys, vec![a, b, c]);
+        assert_eq!(decoder.get_varint(), Ok(3));
+        for (from, to, amount) in transfers {
+            assert_eq!(keys[decoder.get_varint().unwrap() as usize], from);
+            assert_eq!(keys[decoder.get_varint().unwrap() as usize], to);
+            assert_eq!(decoder.get_varint(), Ok(amount));
+        }
+        assert_eq!(decoder.finish(), Ok(()));
+    }
+}
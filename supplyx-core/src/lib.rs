@@ -0,0 +1,12 @@
+// Primitives des clients embarqués (scanners d'entrepôt...) : encodage
+// canonique, hachage, construction et signature des transactions simples,
+// vérification des preuves de Merkle. Sans `std` (seulement `alloc`) : les
+// entrées-sorties et le réseau restent dans le nœud, qui s'appuie sur ces
+// mêmes primitives.
+#![no_std]
+
+extern crate alloc;
+
+pub mod encoding;
+pub mod merkle;
+pub mod transaction;
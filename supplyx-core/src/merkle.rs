@@ -0,0 +1,176 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use sha3::{Digest, Sha3_256};
+
+// Préfixes distincts pour les feuilles et les nœuds internes, afin qu'un nœud
+// interne ne puisse pas être présenté comme une feuille
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+// La racine engage aussi le nombre de feuilles : un nœud sans voisin remonte tel
+// quel, et sans ce nombre la dernière feuille d'un arbre de taille impaire se
+// prouverait aussi à une autre position d'un arbre plus petit
+const ROOT_PREFIX: u8 = 0x02;
+
+#[derive(Clone, Debug)]
+pub struct MerkleProof {
+    pub index: u64,
+    pub leaf_count: u64,
+    pub siblings: Vec<Vec<u8>>,
+}
+
+fn hash_leaf(leaf: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha3_256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(leaf);
+    hasher.finalize().to_vec()
+}
+
+fn hash_node(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha3_256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().to_vec()
+}
+
+fn hash_root(leaf_count: u64, tree_root: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha3_256::new();
+    hasher.update([ROOT_PREFIX]);
+    hasher.update(leaf_count.to_be_bytes());
+    hasher.update(tree_root);
+    hasher.finalize().to_vec()
+}
+
+// Un nœud sans voisin (niveau de taille impaire) remonte tel quel
+fn next_level(level: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    level.chunks(2)
+        .map(|pair| match pair {
+            [left, right] => hash_node(left, right),
+            [single] => single.clone(),
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+pub fn merkle_root(leaves: &[Vec<u8>]) -> Vec<u8> {
+    if leaves.is_empty() {
+        return vec![0; 32];
+    }
+
+    let mut level: Vec<Vec<u8>> = leaves.iter().map(|leaf| hash_leaf(leaf)).collect();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    hash_root(leaves.len() as u64, &level[0])
+}
+
+pub fn merkle_proof(leaves: &[Vec<u8>], index: usize) -> Option<MerkleProof> {
+    if index >= leaves.len() {
+        return None;
+    }
+
+    let mut siblings = Vec::new();
+    let mut position = index;
+    let mut level: Vec<Vec<u8>> = leaves.iter().map(|leaf| hash_leaf(leaf)).collect();
+    while level.len() > 1 {
+        if let Some(sibling) = level.get(position ^ 1) {
+            siblings.push(sibling.clone());
+        }
+        level = next_level(&level);
+        position /= 2;
+    }
+
+    Some(MerkleProof {
+        index: index as u64,
+        leaf_count: leaves.len() as u64,
+        siblings,
+    })
+}
+
+pub fn verify_proof(root: &[u8], leaf: &[u8], proof: &MerkleProof) -> bool {
+    if proof.index >= proof.leaf_count {
+        return false;
+    }
+
+    let mut hash = hash_leaf(leaf);
+    let mut siblings = proof.siblings.iter();
+    let mut position = proof.index;
+    let mut level_size = proof.leaf_count;
+    while level_size > 1 {
+        let has_sibling = (position ^ 1) < level_size;
+        if has_sibling {
+            let Some(sibling) = siblings.next() else {
+                return false;
+            };
+            hash = if position & 1 == 0 {
+                hash_node(&hash, sibling)
+            } else {
+                hash_node(sibling, &hash)
+            };
+        }
+        position /= 2;
+        level_size = level_size.div_ceil(2);
+    }
+
+    siblings.next().is_none() && hash_root(proof.leaf_count, &hash) == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: u8) -> Vec<Vec<u8>> {
+        (0..count).map(|i| vec![i; 4]).collect()
+    }
+
+    #[test]
+    fn root_commits_to_every_leaf_and_their_count() {
+        assert_eq!(merkle_root(&[]), vec![0; 32]);
+        let three = leaves(3);
+        assert_eq!(merkle_root(&three), merkle_root(&leaves(3)));
+        assert_ne!(merkle_root(&three), merkle_root(&[three[1].clone(), three[0].clone(), three[2].clone()]));
+        assert_ne!(merkle_root(&three), merkle_root(&leaves(4)));
+
+        // Arbre de trois feuilles : la troisième remonte telle quelle, seul le
+        // nombre de feuilles distingue sa racine de celle de l'arbre interne
+        let inner = hash_node(&hash_leaf(&three[0]), &hash_leaf(&three[1]));
+        let tree = hash_node(&inner, &hash_leaf(&three[2]));
+        assert_eq!(merkle_root(&three), hash_root(3, &tree));
+        assert_ne!(merkle_root(&three), hash_root(2, &tree));
+    }
+
+    #[test]
+    fn every_leaf_is_proven_at_its_position_only() {
+        for count in 1..=9u8 {
+            let leaves = leaves(count);
+            let root = merkle_root(&leaves);
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = merkle_proof(&leaves, index).unwrap();
+                assert!(verify_proof(&root, leaf, &proof));
+                assert!(!verify_proof(&root, b"autre", &proof));
+                for other in (0..count as u64).filter(|&other| other != index as u64) {
+                    assert!(!verify_proof(&root, leaf, &MerkleProof { index: other, ..proof.clone() }));
+                }
+            }
+            assert!(merkle_proof(&leaves, count as usize).is_none());
+        }
+    }
+
+    #[test]
+    fn odd_last_leaf_cannot_be_proven_in_a_smaller_tree() {
+        let leaves = leaves(3);
+        let root = merkle_root(&leaves);
+        let proof = merkle_proof(&leaves, 2).unwrap();
+        assert_eq!(proof.siblings.len(), 1);
+        assert!(verify_proof(&root, &leaves[2], &proof));
+        assert!(!verify_proof(&root, &leaves[2], &MerkleProof { index: 1, leaf_count: 2, ..proof.clone() }));
+        assert!(!verify_proof(&root, &leaves[2], &MerkleProof { index: 3, leaf_count: 3, ..proof.clone() }));
+
+        let mut truncated = merkle_proof(&leaves, 0).unwrap();
+        truncated.siblings.pop();
+        assert!(!verify_proof(&root, &leaves[0], &truncated));
+        let mut extended = merkle_proof(&leaves, 0).unwrap();
+        extended.siblings.push(vec![0; 32]);
+        assert!(!verify_proof(&root, &leaves[0], &extended));
+    }
+}
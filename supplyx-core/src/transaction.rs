@@ -0,0 +1,166 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, Verifier};
+
+use crate::encoding::{encode_body, transaction_domain, Decoder, Encoder, BLOCK_BODY_DOMAIN};
+
+// Format des transactions construites par ces primitives ; les formats
+// suivants sont signés dans leur propre domaine (`transaction_domain`)
+pub const TRANSACTION_VERSION: u32 = 1;
+
+// Dernier bloc pouvant inclure une transaction, désigné par sa hauteur ou son horodatage
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Expiry {
+    Never,
+    Height(u64),
+    Timestamp(u64),
+}
+
+impl Expiry {
+    // Vrai si un bloc de hauteur `height` et d'horodatage `timestamp` ne peut plus l'inclure
+    pub fn is_expired(&self, height: u64, timestamp: u64) -> bool {
+        match *self {
+            Expiry::Never => false,
+            Expiry::Height(last) => height > last,
+            Expiry::Timestamp(last) => timestamp > last,
+        }
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        let (tag, value) = match *self {
+            Expiry::Never => (0, 0),
+            Expiry::Height(height) => (1, height),
+            Expiry::Timestamp(timestamp) => (2, timestamp),
+        };
+        encoder.put_u8(tag);
+        encoder.put_u64(value);
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Expiry, &'static str> {
+        let tag = decoder.get_u8()?;
+        let value = decoder.get_u64()?;
+        match (tag, value) {
+            (0, 0) => Ok(Expiry::Never),
+            (1, height) => Ok(Expiry::Height(height)),
+            (2, timestamp) => Ok(Expiry::Timestamp(timestamp)),
+            _ => Err("Invalid transaction expiry"),
+        }
+    }
+}
+
+// Types de transaction qu'un appareil peut construire seul ; les autres
+// (contrats, séquestres, transferts confidentiels...) restent dans le nœud
+#[derive(Clone, Debug, PartialEq)]
+pub enum Kind {
+    Transfer,
+    // Ancrage d'un document hors chaîne concernant le destinataire : empreinte
+    // SHA3-256 du contenu et, le cas échéant, CID IPFS où le trouver
+    Anchor { document: Vec<u8>, cid: Option<String> },
+}
+
+impl Kind {
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        match self {
+            Kind::Transfer => encode_transfer(encoder),
+            Kind::Anchor { document, cid } => encode_anchor(encoder, document, cid.as_deref()),
+        }
+    }
+}
+
+// Encodages des types partagés avec le nœud, qui les utilise pour les siens
+pub fn encode_transfer(encoder: &mut Encoder) {
+    encoder.put_u8(0);
+}
+
+pub fn encode_anchor(encoder: &mut Encoder, document: &[u8], cid: Option<&str>) {
+    encoder.put_u8(12);
+    encoder.put_bytes(document);
+    match cid {
+        Some(cid) => {
+            encoder.put_u8(1);
+            encoder.put_bytes(cid.as_bytes());
+        }
+        None => encoder.put_u8(0),
+    }
+}
+
+// Champs signés d'une transaction au format `version`, dans l'ordre de
+// l'encodage ; `kind` encode son type
+#[allow(clippy::too_many_arguments)]
+pub fn signing_encoder(version: u32, chain_id: u64, sender: &PublicKey, nonce: u64, recipient: &PublicKey, amount: u64, kind: impl FnOnce(&mut Encoder), gas_price: u64, timestamp: u64, valid_from: u64, valid_until: Expiry) -> Encoder {
+    let mut encoder = Encoder::new(&transaction_domain(version));
+    encoder.put_u64(chain_id);
+    encoder.put_public_key(sender);
+    encoder.put_u64(nonce);
+    encoder.put_public_key(recipient);
+    encoder.put_u64(amount);
+    kind(&mut encoder);
+    encoder.put_u64(gas_price);
+    encoder.put_u64(timestamp);
+    encoder.put_u64(valid_from);
+    valid_until.encode_into(&mut encoder);
+    encoder
+}
+
+// Transaction signée par une clé unique, incluable dès sa signature
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transaction {
+    pub chain_id: u64,
+    pub sender: PublicKey,
+    // Un émetteur n'utilise chaque nonce qu'une fois tant que la transaction
+    // n'a pas expiré : le nœud refuse de l'inclure une seconde fois
+    pub nonce: u64,
+    pub recipient: PublicKey,
+    pub amount: u64,
+    pub kind: Kind,
+    pub gas_price: u64,
+    pub timestamp: u64,
+    pub valid_until: Expiry,
+    pub signature: Signature,
+}
+
+impl Transaction {
+    #[allow(clippy::too_many_arguments)]
+    pub fn signed(chain_id: u64, sender: &Keypair, nonce: u64, recipient: &PublicKey, amount: u64, kind: Kind, gas_price: u64, timestamp: u64, valid_until: Expiry) -> Transaction {
+        let hash = signing_encoder(TRANSACTION_VERSION, chain_id, &sender.public, nonce, recipient, amount, |encoder| kind.encode_into(encoder), gas_price, timestamp, 0, valid_until).hash();
+        Transaction {
+            chain_id,
+            sender: sender.public,
+            nonce,
+            recipient: *recipient,
+            amount,
+            kind,
+            gas_price,
+            timestamp,
+            valid_until,
+            signature: sender.sign(&hash),
+        }
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        signing_encoder(TRANSACTION_VERSION, self.chain_id, &self.sender, self.nonce, &self.recipient, self.amount, |encoder| self.kind.encode_into(encoder), self.gas_price, self.timestamp, 0, self.valid_until).hash()
+    }
+
+    pub fn verify(&self) -> bool {
+        self.sender.verify(&self.hash(), &self.signature).is_ok()
+    }
+}
+
+// Transactions encodées comme un corps de bloc, à soumettre au nœud
+// (`SubmitBatch`)
+pub fn encode_transactions(transactions: &[Transaction]) -> Vec<u8> {
+    encode_body(BLOCK_BODY_DOMAIN, transactions, |transaction| [&transaction.sender, &transaction.recipient], |transaction, encoder, index_of| {
+        encoder.put_varint(transaction.chain_id);
+        encoder.put_varint(index_of(&transaction.sender));
+        encoder.put_varint(transaction.nonce);
+        encoder.put_varint(index_of(&transaction.recipient));
+        encoder.put_u64(transaction.amount);
+        transaction.kind.encode_into(encoder);
+        encoder.put_u64(transaction.gas_price);
+        encoder.put_u64(transaction.timestamp);
+        encoder.put_varint(0);
+        transaction.valid_until.encode_into(encoder);
+        encoder.put_u8(0);
+        encoder.put_signature(&transaction.signature);
+    })
+}
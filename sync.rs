@@ -0,0 +1,627 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use futures_util::{stream, StreamExt};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::blockstore::{BlockFile, ChainStore};
+use crate::encoding::{decode_block, decode_header_from, encode_block, encode_header_into, Decoder, Encoder, SYNC_DOMAIN};
+use crate::light::HeaderSource;
+use crate::reputation::Violation;
+use crate::snapshot::{SnapshotSource, StateSnapshot};
+use crate::{Block, BlockHeader, Blockchain};
+
+// Nombre d'en-têtes par requête
+const HEADER_BATCH_SIZE: usize = 512;
+
+// Nombre de corps de blocs par requête
+const BODY_BATCH_SIZE: usize = 64;
+
+// Requêtes de corps de blocs en vol, réparties entre les pairs
+const MAX_BODY_REQUESTS: usize = 8;
+
+// Tête annoncée par un pair
+#[derive(Clone, Debug)]
+pub struct PeerStatus {
+    pub height: u64,
+    pub tip_hash: Vec<u8>,
+}
+
+// Requêtes du protocole de synchronisation entre pairs
+#[derive(Clone, Debug)]
+pub enum SyncRequest {
+    Status,
+    Headers { from: u64, count: u32 },
+    Blocks { from: u64, count: u32 },
+    // Dernier instantané d'état, pour une synchronisation rapide
+    Snapshot,
+}
+
+#[derive(Clone, Debug)]
+pub enum SyncResponse {
+    Status(PeerStatus),
+    Headers(Vec<BlockHeader>),
+    Blocks(Vec<Block>),
+    Snapshot(Option<Box<StateSnapshot>>),
+}
+
+impl SyncRequest {
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        match self {
+            SyncRequest::Status => encoder.put_u8(0),
+            SyncRequest::Headers { from, count } => {
+                encoder.put_u8(1);
+                encoder.put_u64(*from);
+                encoder.put_u32(*count);
+            }
+            SyncRequest::Blocks { from, count } => {
+                encoder.put_u8(2);
+                encoder.put_u64(*from);
+                encoder.put_u32(*count);
+            }
+            SyncRequest::Snapshot => encoder.put_u8(3),
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<SyncRequest, &'static str> {
+        match decoder.get_u8()? {
+            0 => Ok(SyncRequest::Status),
+            1 => Ok(SyncRequest::Headers { from: decoder.get_u64()?, count: decoder.get_u32()? }),
+            2 => Ok(SyncRequest::Blocks { from: decoder.get_u64()?, count: decoder.get_u32()? }),
+            3 => Ok(SyncRequest::Snapshot),
+            _ => Err("Unknown sync request"),
+        }
+    }
+
+    // Réponse du nœud local, bornée aux tailles de lot
+    pub fn answer(&self, chain: &Blockchain) -> SyncResponse {
+        match self {
+            SyncRequest::Status => SyncResponse::Status(PeerStatus {
+                height: chain.next_index().saturating_sub(1),
+                tip_hash: chain.last_hash(),
+            }),
+            SyncRequest::Headers { from, count } => {
+                SyncResponse::Headers(chain.headers(*from, (*count as usize).min(HEADER_BATCH_SIZE)))
+            }
+            SyncRequest::Blocks { from, count } => {
+                SyncResponse::Blocks(chain.blocks(*from, (*count as usize).min(BODY_BATCH_SIZE)))
+            }
+            SyncRequest::Snapshot => SyncResponse::Snapshot(chain.latest_snapshot().map(Box::new)),
+        }
+    }
+}
+
+impl SyncResponse {
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        match self {
+            SyncResponse::Status(status) => {
+                encoder.put_u8(0);
+                encoder.put_u64(status.height);
+                encoder.put_bytes(&status.tip_hash);
+            }
+            SyncResponse::Headers(headers) => {
+                encoder.put_u8(1);
+                encoder.put_u32(headers.len() as u32);
+                for header in headers {
+                    encode_header_into(header, encoder);
+                }
+            }
+            SyncResponse::Blocks(blocks) => {
+                encoder.put_u8(2);
+                encoder.put_u32(blocks.len() as u32);
+                for block in blocks {
+                    encoder.put_bytes(&encode_block(block));
+                }
+            }
+            SyncResponse::Snapshot(snapshot) => {
+                encoder.put_u8(3);
+                match snapshot {
+                    Some(snapshot) => {
+                        encoder.put_u8(1);
+                        encoder.put_bytes(&snapshot.encode());
+                    }
+                    None => encoder.put_u8(0),
+                }
+            }
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<SyncResponse, &'static str> {
+        match decoder.get_u8()? {
+            0 => Ok(SyncResponse::Status(PeerStatus { height: decoder.get_u64()?, tip_hash: decoder.get_bytes()?.to_vec() })),
+            1 => {
+                let mut headers = Vec::new();
+                for _ in 0..decoder.get_u32()? {
+                    headers.push(decode_header_from(decoder)?);
+                }
+                Ok(SyncResponse::Headers(headers))
+            }
+            2 => {
+                let mut blocks = Vec::new();
+                for _ in 0..decoder.get_u32()? {
+                    blocks.push(decode_block(decoder.get_bytes()?)?);
+                }
+                Ok(SyncResponse::Blocks(blocks))
+            }
+            3 => match decoder.get_u8()? {
+                0 => Ok(SyncResponse::Snapshot(None)),
+                1 => Ok(SyncResponse::Snapshot(Some(Box::new(StateSnapshot::decode(decoder.get_bytes()?)?)))),
+                _ => Err("Invalid snapshot flag"),
+            },
+            _ => Err("Unknown sync response"),
+        }
+    }
+}
+
+// Pair interrogeable pendant la synchronisation, quel que soit le transport
+#[async_trait]
+pub trait SyncPeer: Send + Sync {
+    fn id(&self) -> &str;
+    // Signale une réponse contraire au protocole
+    fn report(&self, _violation: Violation) {}
+    async fn request(&self, request: SyncRequest) -> Result<SyncResponse, String>;
+
+    async fn status(&self) -> Result<PeerStatus, String> {
+        match self.request(SyncRequest::Status).await? {
+            SyncResponse::Status(status) => Ok(status),
+            _ => Err("Unexpected sync response".to_string()),
+        }
+    }
+
+    async fn headers(&self, from: u64, count: usize) -> Result<Vec<BlockHeader>, String> {
+        match self.request(SyncRequest::Headers { from, count: count as u32 }).await? {
+            SyncResponse::Headers(headers) => Ok(headers),
+            _ => Err("Unexpected sync response".to_string()),
+        }
+    }
+
+    async fn blocks(&self, from: u64, count: usize) -> Result<Vec<Block>, String> {
+        match self.request(SyncRequest::Blocks { from, count: count as u32 }).await? {
+            SyncResponse::Blocks(blocks) => Ok(blocks),
+            _ => Err("Unexpected sync response".to_string()),
+        }
+    }
+
+    async fn snapshot(&self) -> Result<Option<StateSnapshot>, String> {
+        match self.request(SyncRequest::Snapshot).await? {
+            SyncResponse::Snapshot(snapshot) => Ok(snapshot.map(|snapshot| *snapshot)),
+            _ => Err("Unexpected sync response".to_string()),
+        }
+    }
+}
+
+// Dernier instantané d'un pair, avec les en-têtes depuis la genèse et les blocs
+// qui le suivent, téléchargés pour `Node::fast_sync` qui les vérifie
+pub struct PeerSnapshot {
+    snapshot: StateSnapshot,
+    headers: Vec<BlockHeader>,
+    blocks: Vec<Block>,
+}
+
+impl PeerSnapshot {
+    // Instantané du pair le plus avancé qui en a un au-delà de `next_index`
+    // (prochain bloc attendu localement) ; `None` si aucun pair n'en a
+    pub async fn fetch(peers: &[Arc<dyn SyncPeer>], next_index: u64) -> Option<PeerSnapshot> {
+        let statuses = join_all(peers.iter().map(|peer| peer.status())).await;
+        let mut peers: Vec<(&Arc<dyn SyncPeer>, u64)> = peers.iter()
+            .zip(statuses)
+            .filter_map(|(peer, status)| Some((peer, status.ok()?.height)))
+            .collect();
+        peers.sort_by_key(|(_, height)| std::cmp::Reverse(*height));
+        for (peer, height) in peers {
+            match Self::download(peer.as_ref(), height, next_index).await {
+                Ok(Some(snapshot)) => return Some(snapshot),
+                Ok(None) => {}
+                Err(e) => warn!(peer = peer.id(), error = %e, "Snapshot download failed"),
+            }
+        }
+        None
+    }
+
+    async fn download(peer: &dyn SyncPeer, height: u64, next_index: u64) -> Result<Option<PeerSnapshot>, String> {
+        let Some(snapshot) = peer.snapshot().await?.filter(|snapshot| snapshot.height >= next_index) else {
+            return Ok(None);
+        };
+        let mut headers = Vec::new();
+        while headers.len() as u64 <= height {
+            let batch = peer.headers(headers.len() as u64, HEADER_BATCH_SIZE).await?;
+            if batch.is_empty() {
+                return Err("Peer has no headers up to its announced height".to_string());
+            }
+            headers.extend(batch);
+        }
+        let mut blocks: Vec<Block> = Vec::new();
+        while snapshot.height + 1 + (blocks.len() as u64) <= height {
+            let batch = peer.blocks(snapshot.height + 1 + blocks.len() as u64, BODY_BATCH_SIZE).await?;
+            if batch.is_empty() {
+                return Err("Peer has no blocks up to its announced height".to_string());
+            }
+            blocks.extend(batch);
+        }
+        Ok(Some(PeerSnapshot { snapshot, headers, blocks }))
+    }
+
+    pub fn height(&self) -> u64 {
+        self.snapshot.height
+    }
+}
+
+impl HeaderSource for PeerSnapshot {
+    fn headers(&self, from: u64, count: usize) -> Vec<BlockHeader> {
+        self.headers.iter().skip(from as usize).take(count).cloned().collect()
+    }
+}
+
+impl SnapshotSource for PeerSnapshot {
+    fn latest_snapshot(&self) -> Option<StateSnapshot> {
+        Some(self.snapshot.clone())
+    }
+
+    fn blocks(&self, from: u64, count: usize) -> Vec<Block> {
+        self.blocks.iter().filter(|block| block.index >= from).take(count).cloned().collect()
+    }
+}
+
+// Journal de la synchronisation sur disque : en-têtes validés, chacun préfixé
+// par sa longueur, et blocs appliqués, dans un stockage de blocs (`store/`). Un
+// enregistrement tronqué par un arrêt brutal est ignoré à la relecture.
+pub struct SyncJournal {
+    dir: PathBuf,
+    blocks: BlockFile,
+}
+
+impl SyncJournal {
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, String> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let blocks = BlockFile::open(dir.join("store"))?;
+        Ok(SyncJournal { dir, blocks })
+    }
+
+    fn headers(&self) -> Vec<BlockHeader> {
+        self.read("headers").iter()
+            .map_while(|record| {
+                let mut decoder = Decoder::new(record, SYNC_DOMAIN).ok()?;
+                let header = decode_header_from(&mut decoder).ok()?;
+                decoder.finish().ok()?;
+                Some(header)
+            })
+            .collect()
+    }
+
+    pub fn blocks(&self) -> &BlockFile {
+        &self.blocks
+    }
+
+    fn append_headers(&self, headers: &[BlockHeader]) -> Result<(), String> {
+        let records = headers.iter().map(|header| {
+            let mut encoder = Encoder::new(SYNC_DOMAIN);
+            encode_header_into(header, &mut encoder);
+            encoder.finish()
+        });
+        self.append("headers", records)
+    }
+
+    // Des blocs qui ne suivent pas ceux du journal (branche abandonnée, chaîne
+    // repartie d'ailleurs) remplacent les blocs journalisés à partir de leur index
+    pub fn append_blocks(&mut self, blocks: &[Block]) -> Result<(), String> {
+        let (Some(first), stored) = (blocks.first(), self.blocks.range()) else {
+            return Ok(());
+        };
+        if first.index < stored.end {
+            self.blocks.truncate(first.index)?;
+        } else if first.index > stored.end {
+            self.blocks.truncate(stored.start)?;
+        }
+        self.blocks.append(blocks)
+    }
+
+    // Tous les en-têtes en attente ont été appliqués
+    fn reset_headers(&self) -> Result<(), String> {
+        let path = self.dir.join("headers");
+        std::fs::write(&path, []).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    fn read(&self, name: &str) -> Vec<Vec<u8>> {
+        let data = std::fs::read(self.dir.join(name)).unwrap_or_default();
+        let mut records = Vec::new();
+        let mut rest = data.as_slice();
+        while let Some((length, tail)) = rest.split_first_chunk::<4>() {
+            let length = u32::from_be_bytes(*length) as usize;
+            let Some(record) = tail.get(..length) else {
+                break;
+            };
+            records.push(record.to_vec());
+            rest = &tail[length..];
+        }
+        records
+    }
+
+    fn append(&self, name: &str, records: impl Iterator<Item = Vec<u8>>) -> Result<(), String> {
+        let path = self.dir.join(name);
+        let mut buffer = Vec::new();
+        for record in records {
+            buffer.extend_from_slice(&(record.len() as u32).to_be_bytes());
+            buffer.extend_from_slice(&record);
+        }
+        OpenOptions::new().create(true).append(true).open(&path)
+            .and_then(|mut file| file.write_all(&buffer))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+// Rattrapage de la chaîne auprès des pairs : les en-têtes sont téléchargés et
+// vérifiés d'abord auprès du pair le plus avancé, puis les corps sont demandés
+// en parallèle à tous les pairs qui les ont et appliqués dans l'ordre. Avec un
+// journal, une synchronisation interrompue reprend là où elle s'était arrêtée.
+pub struct Synchronizer {
+    blockchain: Arc<Mutex<Blockchain>>,
+    journal: Option<SyncJournal>,
+    // En-têtes vérifiés au-delà de la tête locale
+    headers: Vec<BlockHeader>,
+}
+
+impl Synchronizer {
+    pub fn new(blockchain: Arc<Mutex<Blockchain>>, journal: Option<SyncJournal>) -> Self {
+        Synchronizer { blockchain, journal, headers: Vec::new() }
+    }
+
+    // Rejoue les blocs journalisés puis reprend les en-têtes déjà vérifiés ;
+    // retourne l'index du prochain bloc attendu
+    pub async fn resume(&mut self) -> Result<u64, String> {
+        let Some(journal) = &self.journal else {
+            return Ok(self.blockchain.lock().await.next_index());
+        };
+        let mut chain = self.blockchain.lock().await;
+        let mut replayed = 0;
+        let stored = journal.blocks().range();
+        for block in journal.blocks().blocks(chain.next_index().max(stored.start)..stored.end) {
+            let block = match block {
+                Ok(block) => block,
+                Err(e) => {
+                    warn!(error = %e, "Journaled block unreadable, resuming from peers");
+                    break;
+                }
+            };
+            if let Err(e) = chain.add_block(block) {
+                warn!(error = e, "Journaled block rejected, resuming from peers");
+                break;
+            }
+            replayed += 1;
+        }
+
+        let (next_index, mut tip_hash) = (chain.next_index(), chain.last_hash());
+        self.headers = journal.headers().into_iter()
+            .skip_while(|header| header.index < next_index)
+            .take_while(|header| {
+                let linked = header.previous_hash == tip_hash;
+                tip_hash = header.current_hash.clone();
+                linked
+            })
+            .collect();
+        if replayed > 0 || !self.headers.is_empty() {
+            info!(replayed, headers = self.headers.len(), next_index, "Sync resumed from journal");
+        }
+        Ok(next_index)
+    }
+
+    // Synchronise jusqu'à la tête la plus haute annoncée ; retourne l'index du
+    // prochain bloc attendu
+    pub async fn run(&mut self, peers: &[Arc<dyn SyncPeer>]) -> Result<u64, String> {
+        let statuses = join_all(peers.iter().map(|peer| peer.status())).await;
+        let mut peers: Vec<(Arc<dyn SyncPeer>, PeerStatus)> = peers.iter().cloned()
+            .zip(statuses)
+            .filter_map(|(peer, status)| match status {
+                Ok(status) => Some((peer, status)),
+                Err(e) => {
+                    warn!(peer = peer.id(), error = %e, "Peer status unavailable");
+                    None
+                }
+            })
+            .collect();
+        peers.sort_by_key(|(_, status)| std::cmp::Reverse(status.height));
+
+        // En-têtes d'abord, auprès du pair le plus avancé ; un pair fautif est écarté
+        while let Some((peer, status)) = peers.first().cloned() {
+            match self.download_headers(peer.as_ref(), status.height).await {
+                Ok(()) => break,
+                Err(e) => {
+                    warn!(peer = peer.id(), error = %e, "Header download failed");
+                    peers.remove(0);
+                }
+            }
+        }
+
+        let Some(target) = self.headers.last().map(|header| header.index) else {
+            return Ok(self.blockchain.lock().await.next_index());
+        };
+        info!(target, peers = peers.len(), "Downloading block bodies");
+        self.download_bodies(&peers).await?;
+        self.headers.clear();
+        if let Some(journal) = &self.journal {
+            journal.reset_headers()?;
+        }
+        Ok(self.blockchain.lock().await.next_index())
+    }
+
+    async fn download_headers(&mut self, peer: &dyn SyncPeer, target: u64) -> Result<(), String> {
+        let chain_id = self.blockchain.lock().await.config.chain_id;
+        loop {
+            let (from, mut parent) = match self.headers.last() {
+                Some(header) => (header.index + 1, (header.current_hash.clone(), header.slot)),
+                None => {
+                    let chain = self.blockchain.lock().await;
+                    let slot = chain.chain.last().map(|block| block.slot).unwrap_or(0);
+                    (chain.next_index(), (chain.last_hash(), slot))
+                }
+            };
+            if from > target {
+                return Ok(());
+            }
+            let headers = peer.headers(from, HEADER_BATCH_SIZE).await?;
+            if headers.is_empty() {
+                return Err("Peer has no headers up to its announced height".to_string());
+            }
+            for (offset, header) in headers.iter().enumerate() {
+                // Une branche différente de la chaîne locale n'est pas une faute du pair
+                if offset == 0 && header.previous_hash != parent.0 {
+                    return Err("Headers do not extend the local chain".to_string());
+                }
+                let verdict = if header.chain_id != chain_id {
+                    Err("Block for another chain")
+                } else if header.index != from + offset as u64 {
+                    Err("Invalid block index")
+                } else if header.previous_hash != parent.0 {
+                    Err("Headers not chained")
+                } else if header.slot <= parent.1 {
+                    Err("Invalid block slot")
+                } else {
+                    header.verify()
+                };
+                if let Err(e) = verdict {
+                    peer.report(Violation::InvalidSyncResponse);
+                    return Err(e.to_string());
+                }
+                parent = (header.current_hash.clone(), header.slot);
+            }
+            if let Some(journal) = &self.journal {
+                journal.append_headers(&headers)?;
+            }
+            self.headers.extend(headers);
+        }
+    }
+
+    // Lots de corps demandés en parallèle, chacun d'abord à un pair différent
+    // puis aux autres en cas d'échec, et appliqués dans l'ordre des en-têtes
+    async fn download_bodies(&mut self, peers: &[(Arc<dyn SyncPeer>, PeerStatus)]) -> Result<(), String> {
+        let batches: Vec<&[BlockHeader]> = self.headers.chunks(BODY_BATCH_SIZE).collect();
+        let mut fetched = stream::iter(batches.into_iter().enumerate())
+            .map(|(position, batch)| fetch_bodies(peers, position, batch))
+            .buffered(MAX_BODY_REQUESTS);
+
+        while let Some(blocks) = fetched.next().await {
+            let blocks = blocks?;
+            let mut chain = self.blockchain.lock().await;
+            for block in &blocks {
+                chain.add_block(block.clone())?;
+            }
+            drop(chain);
+            if let Some(journal) = &mut self.journal {
+                journal.append_blocks(&blocks)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn fetch_bodies(peers: &[(Arc<dyn SyncPeer>, PeerStatus)], position: usize, headers: &[BlockHeader]) -> Result<Vec<Block>, String> {
+    let (first, last) = (headers[0].index, headers[headers.len() - 1].index);
+    let holders: Vec<&Arc<dyn SyncPeer>> = peers.iter()
+        .filter(|(_, status)| status.height >= last)
+        .map(|(peer, _)| peer)
+        .collect();
+    for attempt in 0..holders.len() {
+        let peer = holders[(position + attempt) % holders.len()];
+        match peer.blocks(first, headers.len()).await {
+            Ok(blocks) if matches_headers(&blocks, headers) => return Ok(blocks),
+            Ok(_) => {
+                warn!(peer = peer.id(), first, "Block bodies do not match the headers");
+                peer.report(Violation::InvalidSyncResponse);
+            }
+            Err(e) => warn!(peer = peer.id(), first, error = %e, "Block body request failed"),
+        }
+    }
+    Err(format!("No peer provided the blocks from {}", first))
+}
+
+fn matches_headers(blocks: &[Block], headers: &[BlockHeader]) -> bool {
+    blocks.len() == headers.len()
+        && blocks.iter().zip(headers).all(|(block, header)| {
+            block.current_hash == header.current_hash && block.header().verify().is_ok()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use async_trait::async_trait;
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+
+    use super::{PeerSnapshot, SyncPeer, SyncRequest, SyncResponse};
+    use crate::clock::MockClock;
+    use crate::config::ChainConfig;
+    use crate::encoding::{Decoder, Encoder, SYNC_DOMAIN};
+    use crate::node::{Node, NodeConfig};
+    use crate::{Blockchain, SNAPSHOT_INTERVAL};
+
+    const GENESIS_TIME: u64 = 1_700_000_000;
+
+    // Pair local dont les réponses passent par l'encodage du protocole
+    struct LocalPeer(Blockchain);
+
+    #[async_trait]
+    impl SyncPeer for LocalPeer {
+        fn id(&self) -> &str {
+            "local"
+        }
+
+        async fn request(&self, request: SyncRequest) -> Result<SyncResponse, String> {
+            let mut encoder = Encoder::new(SYNC_DOMAIN);
+            request.answer(&self.0).encode_into(&mut encoder);
+            let encoded = encoder.finish();
+            let mut decoder = Decoder::new(&encoded, SYNC_DOMAIN)?;
+            Ok(SyncResponse::decode_from(&mut decoder)?)
+        }
+    }
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        Keypair { public: PublicKey::from(&secret), secret }
+    }
+
+    fn test_chain(validator: &Keypair, clock: &MockClock) -> Blockchain {
+        let mut chain = Blockchain::new(ChainConfig::default());
+        chain.set_clock(Arc::new(clock.clone()));
+        chain.register_validator(&validator.public, 1000).unwrap();
+        chain
+    }
+
+    #[tokio::test]
+    async fn fast_sync_restores_a_peer_snapshot_and_replays_later_blocks() {
+        let validator = keypair(1);
+        let clock = MockClock::new(Duration::from_secs(GENESIS_TIME));
+        let mut source = test_chain(&validator, &clock);
+        for slot in 1..=SNAPSHOT_INTERVAL + 5 {
+            clock.set(Duration::from_secs(GENESIS_TIME + slot * 5));
+            source.validate_and_create_block(&validator, slot).unwrap();
+        }
+        let (tip, state) = (source.last_hash(), source.snapshot().hash());
+        let next_index = source.next_index();
+        let peers: Vec<Arc<dyn SyncPeer>> = vec![Arc::new(LocalPeer(source))];
+
+        // Aucun instantané au-delà d'une chaîne déjà à jour
+        assert!(PeerSnapshot::fetch(&peers, next_index).await.is_none());
+        // État d'avant le bloc d'instantané, dont l'en-tête engage le hash
+        let snapshot = PeerSnapshot::fetch(&peers, 0).await.unwrap();
+        assert_eq!(snapshot.height(), SNAPSHOT_INTERVAL - 1);
+
+        let config = || NodeConfig { clock: Arc::new(clock.clone()), ..Default::default() };
+        let (node, _) = Node::new(test_chain(&validator, &clock), Arc::new(keypair(1)), config());
+        assert_eq!(node.fast_sync(&snapshot).await, Ok(next_index));
+        let chain = node.blockchain();
+        let chain = chain.lock().await;
+        assert_eq!(chain.last_hash(), tip);
+        assert_eq!(chain.snapshot().hash(), state);
+
+        // Un état modifié ne correspond plus au hash engagé dans les en-têtes
+        let mut tampered = snapshot;
+        tampered.snapshot.ledger.credit(&keypair(2).public, 1);
+        let (node, _) = Node::new(test_chain(&validator, &clock), Arc::new(keypair(1)), config());
+        assert_eq!(node.fast_sync(&tampered).await, Err("Snapshot hash mismatch"));
+    }
+}
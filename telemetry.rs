@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, EnvFilter, Layer, Registry};
+
+// En-têtes W3C (traceparent / tracestate) transportés avec une soumission,
+// pour rattacher l'admission et l'inclusion à la trace de l'intégrateur
+pub type TraceContext = HashMap<String, String>;
+
+// Format des journaux sur la sortie standard
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LogFormat {
+    // Lisible à la console
+    Pretty,
+    // Une ligne JSON par événement, avec les spans englobants, pour les agrégateurs
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            _ => Err("Unknown log format (expected pretty or json)"),
+        }
+    }
+}
+
+// Journaux filtrés par `RUST_LOG` (niveau info par défaut). Sans point de
+// terminaison OTLP, les spans restent locaux et ne sont pas exportés.
+pub fn init(format: LogFormat, otlp_endpoint: Option<String>) -> Result<(), &'static str> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let logs: Box<dyn Layer<Registry> + Send + Sync> = match format {
+        LogFormat::Pretty => fmt::layer().boxed(),
+        LogFormat::Json => fmt::layer().json().with_current_span(true).with_span_list(true).boxed(),
+    };
+    let traces = match otlp_endpoint {
+        Some(endpoint) => {
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(endpoint)
+                .build()
+                .map_err(|_| "Failed to build OTLP exporter")?;
+            let provider = TracerProvider::builder()
+                .with_batch_exporter(exporter, runtime::Tokio)
+                .with_resource(Resource::new(vec![KeyValue::new("service.name", "supplyx")]))
+                .build();
+            let tracer = provider.tracer("supplyx");
+            global::set_tracer_provider(provider);
+            Some(tracing_opentelemetry::layer().with_tracer(tracer))
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(logs)
+        .with(traces)
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .try_init()
+        .map_err(|_| "Tracing subscriber already installed")
+}
+
+// Contexte à joindre à une transaction transmise à un autre nœud
+pub fn inject(context: &opentelemetry::Context) -> TraceContext {
+    let mut carrier = TraceContext::new();
+    global::get_text_map_propagator(|propagator| propagator.inject_context(context, &mut carrier));
+    carrier
+}
+
+pub fn extract(carrier: &TraceContext) -> opentelemetry::Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(carrier))
+}
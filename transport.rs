@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+// Taille maximale d'un message (un bloc complet encodé)
+const MAX_FRAME_SIZE: usize = 64 * 1024 * 1024;
+
+// File d'envoi et de réception de chaque lien
+const LINK_QUEUE_SIZE: usize = 256;
+
+// Compteurs par transport
+#[derive(Default)]
+pub struct TransportMetrics {
+    pub connections: AtomicU64,
+    pub connect_failures: AtomicU64,
+    pub frames_sent: AtomicU64,
+    pub frames_received: AtomicU64,
+    pub bytes_sent: AtomicU64,
+    pub bytes_received: AtomicU64,
+}
+
+// Connexion établie, indépendante du transport : des messages complets dans
+// chaque sens. La connexion est fermée lorsque les deux côtés sont abandonnés.
+pub struct Link {
+    pub remote: String,
+    pub outgoing: mpsc::Sender<Vec<u8>>,
+    pub incoming: mpsc::Receiver<Vec<u8>>,
+}
+
+#[async_trait]
+pub trait Transport: Send + Sync {
+    // Schéma des adresses servies par ce transport (`tcp`, `ws`, `quic`)
+    fn scheme(&self) -> &'static str;
+    fn metrics(&self) -> Arc<TransportMetrics>;
+    async fn connect(&self, addr: &str) -> Result<Link, String>;
+    // Les connexions entrantes sont transmises sur le canal retourné
+    async fn listen(&self, addr: &str) -> Result<mpsc::Receiver<Link>, String>;
+}
+
+// Transports disponibles, par schéma
+#[derive(Clone, Default)]
+pub struct Transports {
+    transports: HashMap<&'static str, Arc<dyn Transport>>,
+}
+
+impl Transports {
+    pub fn register(&mut self, transport: Arc<dyn Transport>) {
+        self.transports.insert(transport.scheme(), transport);
+    }
+
+    // Compteurs de chaque transport, par schéma
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> HashMap<&'static str, Arc<TransportMetrics>> {
+        self.transports.iter().map(|(scheme, transport)| (*scheme, transport.metrics())).collect()
+    }
+
+    fn resolve<'a>(&self, endpoint: &'a str) -> Result<(&Arc<dyn Transport>, &'a str), String> {
+        let (scheme, addr) = endpoint.split_once("://").ok_or(format!("Invalid endpoint {}", endpoint))?;
+        let transport = self.transports.get(scheme).ok_or(format!("Unsupported transport {}", scheme))?;
+        Ok((transport, addr))
+    }
+
+    pub async fn listen(&self, endpoint: &str) -> Result<mpsc::Receiver<Link>, String> {
+        let (transport, addr) = self.resolve(endpoint)?;
+        transport.listen(addr).await
+    }
+
+    // `endpoints` est une liste de repli séparée par `|`, essayée dans l'ordre
+    // (par exemple `quic://pair:443|ws://pair:443`)
+    pub async fn connect(&self, endpoints: &str) -> Result<Link, String> {
+        let mut last_error = format!("No endpoint in {}", endpoints);
+        for endpoint in endpoints.split('|').map(str::trim).filter(|e| !e.is_empty()) {
+            let (transport, addr) = match self.resolve(endpoint) {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    last_error = e;
+                    continue;
+                }
+            };
+            match transport.connect(addr).await {
+                Ok(link) => {
+                    transport.metrics().connections.fetch_add(1, Ordering::Relaxed);
+                    return Ok(link);
+                }
+                Err(e) => {
+                    transport.metrics().connect_failures.fetch_add(1, Ordering::Relaxed);
+                    last_error = format!("{}: {}", endpoint, e);
+                }
+            }
+        }
+        Err(last_error)
+    }
+}
+
+// Transports compilés dans ce binaire
+pub fn default_transports() -> Transports {
+    let mut transports = Transports::default();
+    transports.register(Arc::new(TcpTransport::default()));
+    transports.register(Arc::new(WebSocketTransport::default()));
+    #[cfg(feature = "quic")]
+    match quic::QuicTransport::from_env() {
+        Ok(transport) => transports.register(Arc::new(transport)),
+        Err(e) => tracing::warn!(error = %e, "QUIC transport disabled"),
+    }
+    transports
+}
+
+// Flux d'octets découpé en messages préfixés par leur longueur (u32 big-endian)
+fn spawn_framed<R, W>(remote: String, mut reader: R, mut writer: W, metrics: Arc<TransportMetrics>) -> Link
+where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let (outgoing, mut to_send) = mpsc::channel::<Vec<u8>>(LINK_QUEUE_SIZE);
+    let (received, incoming) = mpsc::channel(LINK_QUEUE_SIZE);
+
+    let write_metrics = metrics.clone();
+    tokio::spawn(async move {
+        while let Some(frame) = to_send.recv().await {
+            if writer.write_u32(frame.len() as u32).await.is_err() || writer.write_all(&frame).await.is_err() {
+                break;
+            }
+            write_metrics.frames_sent.fetch_add(1, Ordering::Relaxed);
+            write_metrics.bytes_sent.fetch_add(frame.len() as u64, Ordering::Relaxed);
+        }
+        let _ = writer.shutdown().await;
+    });
+
+    tokio::spawn(async move {
+        while let Ok(length) = reader.read_u32().await {
+            if length as usize > MAX_FRAME_SIZE {
+                break;
+            }
+            // Le tampon grandit avec les octets effectivement reçus : une longueur
+            // annoncée ne réserve pas de mémoire à elle seule
+            let mut frame = Vec::new();
+            if (&mut reader).take(length as u64).read_to_end(&mut frame).await.is_err() || frame.len() != length as usize {
+                break;
+            }
+            metrics.frames_received.fetch_add(1, Ordering::Relaxed);
+            metrics.bytes_received.fetch_add(frame.len() as u64, Ordering::Relaxed);
+            if received.send(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Link { remote, outgoing, incoming }
+}
+
+#[derive(Default)]
+pub struct TcpTransport {
+    metrics: Arc<TransportMetrics>,
+}
+
+#[async_trait]
+impl Transport for TcpTransport {
+    fn scheme(&self) -> &'static str {
+        "tcp"
+    }
+
+    fn metrics(&self) -> Arc<TransportMetrics> {
+        self.metrics.clone()
+    }
+
+    async fn connect(&self, addr: &str) -> Result<Link, String> {
+        let stream = TcpStream::connect(addr).await.map_err(|e| e.to_string())?;
+        let (reader, writer) = stream.into_split();
+        Ok(spawn_framed(format!("tcp://{}", addr), reader, writer, self.metrics.clone()))
+    }
+
+    async fn listen(&self, addr: &str) -> Result<mpsc::Receiver<Link>, String> {
+        let listener = TcpListener::bind(addr).await.map_err(|e| e.to_string())?;
+        let (links, accepted) = mpsc::channel(LINK_QUEUE_SIZE);
+        let metrics = self.metrics.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, peer)) = listener.accept().await {
+                metrics.connections.fetch_add(1, Ordering::Relaxed);
+                let (reader, writer) = stream.into_split();
+                if links.send(spawn_framed(format!("tcp://{}", peer), reader, writer, metrics.clone())).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(accepted)
+    }
+}
+
+// Messages binaires WebSocket : traverse les réseaux n'autorisant que le trafic web sortant
+#[derive(Default)]
+pub struct WebSocketTransport {
+    metrics: Arc<TransportMetrics>,
+}
+
+fn spawn_websocket<S>(remote: String, socket: tokio_tungstenite::WebSocketStream<S>, metrics: Arc<TransportMetrics>) -> Link
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (mut sink, mut stream) = socket.split();
+    let (outgoing, mut to_send) = mpsc::channel::<Vec<u8>>(LINK_QUEUE_SIZE);
+    let (received, incoming) = mpsc::channel(LINK_QUEUE_SIZE);
+
+    let write_metrics = metrics.clone();
+    tokio::spawn(async move {
+        while let Some(frame) = to_send.recv().await {
+            let length = frame.len() as u64;
+            if sink.send(Message::Binary(frame)).await.is_err() {
+                break;
+            }
+            write_metrics.frames_sent.fetch_add(1, Ordering::Relaxed);
+            write_metrics.bytes_sent.fetch_add(length, Ordering::Relaxed);
+        }
+        let _ = sink.close().await;
+    });
+
+    tokio::spawn(async move {
+        while let Some(Ok(message)) = stream.next().await {
+            let frame = match message {
+                Message::Binary(frame) => frame,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            metrics.frames_received.fetch_add(1, Ordering::Relaxed);
+            metrics.bytes_received.fetch_add(frame.len() as u64, Ordering::Relaxed);
+            if received.send(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Link { remote, outgoing, incoming }
+}
+
+#[async_trait]
+impl Transport for WebSocketTransport {
+    fn scheme(&self) -> &'static str {
+        "ws"
+    }
+
+    fn metrics(&self) -> Arc<TransportMetrics> {
+        self.metrics.clone()
+    }
+
+    async fn connect(&self, addr: &str) -> Result<Link, String> {
+        let url = format!("ws://{}", addr);
+        let (socket, _) = tokio_tungstenite::connect_async(url.as_str()).await.map_err(|e| e.to_string())?;
+        Ok(spawn_websocket(url, socket, self.metrics.clone()))
+    }
+
+    async fn listen(&self, addr: &str) -> Result<mpsc::Receiver<Link>, String> {
+        let listener = TcpListener::bind(addr).await.map_err(|e| e.to_string())?;
+        let (links, accepted) = mpsc::channel(LINK_QUEUE_SIZE);
+        let metrics = self.metrics.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, peer)) = listener.accept().await {
+                let links = links.clone();
+                let metrics = metrics.clone();
+                tokio::spawn(async move {
+                    let Ok(socket) = tokio_tungstenite::accept_async(stream).await else {
+                        return;
+                    };
+                    metrics.connections.fetch_add(1, Ordering::Relaxed);
+                    let _ = links.send(spawn_websocket(format!("ws://{}", peer), socket, metrics)).await;
+                });
+            }
+        });
+        Ok(accepted)
+    }
+}
+
+// QUIC (TLS 1.3 sur UDP), un flux bidirectionnel par connexion. Le certificat et
+// la clé du nœud (DER) sont lus depuis SUPPLYX_QUIC_CERT / SUPPLYX_QUIC_KEY ; dans
+// les deux sens, les pairs doivent présenter un certificat signé par SUPPLYX_QUIC_CA.
+#[cfg(feature = "quic")]
+mod quic {
+    use std::sync::Arc;
+    use async_trait::async_trait;
+    use quinn::crypto::rustls::{QuicClientConfig, QuicServerConfig};
+    use quinn::rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+    use quinn::rustls::server::WebPkiClientVerifier;
+    use quinn::rustls::RootCertStore;
+    use quinn::{ClientConfig, Endpoint, ServerConfig};
+    use tokio::sync::{mpsc, OnceCell};
+
+    use super::{spawn_framed, Link, Transport, TransportMetrics, LINK_QUEUE_SIZE};
+    use std::sync::atomic::Ordering;
+
+    pub struct QuicTransport {
+        certificate: CertificateDer<'static>,
+        key: PrivatePkcs8KeyDer<'static>,
+        roots: Arc<RootCertStore>,
+        client_config: ClientConfig,
+        // Point de sortie partagé par toutes les connexions sortantes, ouvert à la première
+        client: OnceCell<Endpoint>,
+        metrics: Arc<TransportMetrics>,
+    }
+
+    fn read_env_file(name: &str) -> Result<Vec<u8>, String> {
+        let path = std::env::var(name).map_err(|_| format!("{} not set", name))?;
+        std::fs::read(&path).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    impl QuicTransport {
+        pub fn from_env() -> Result<Self, String> {
+            let certificate = CertificateDer::from(read_env_file("SUPPLYX_QUIC_CERT")?);
+            let key = PrivatePkcs8KeyDer::from(read_env_file("SUPPLYX_QUIC_KEY")?);
+
+            let mut roots = RootCertStore::empty();
+            roots.add(CertificateDer::from(read_env_file("SUPPLYX_QUIC_CA")?)).map_err(|e| e.to_string())?;
+            let roots = Arc::new(roots);
+
+            // Le nœud présente son certificat aux pairs qu'il contacte
+            let client_crypto = quinn::rustls::ClientConfig::builder()
+                .with_root_certificates(roots.clone())
+                .with_client_auth_cert(vec![certificate.clone()], PrivateKeyDer::Pkcs8(key.clone_key()))
+                .map_err(|e| e.to_string())?;
+            let client_config = ClientConfig::new(Arc::new(QuicClientConfig::try_from(client_crypto).map_err(|e| e.to_string())?));
+
+            Ok(QuicTransport { certificate, key, roots, client_config, client: OnceCell::new(), metrics: Arc::default() })
+        }
+
+        // Seuls les pairs dont le certificat est signé par l'autorité sont acceptés
+        fn server_config(&self) -> Result<ServerConfig, String> {
+            let verifier = WebPkiClientVerifier::builder(self.roots.clone()).build().map_err(|e| e.to_string())?;
+            let server_crypto = quinn::rustls::ServerConfig::builder()
+                .with_client_cert_verifier(verifier)
+                .with_single_cert(vec![self.certificate.clone()], PrivateKeyDer::Pkcs8(self.key.clone_key()))
+                .map_err(|e| e.to_string())?;
+            Ok(ServerConfig::with_crypto(Arc::new(QuicServerConfig::try_from(server_crypto).map_err(|e| e.to_string())?)))
+        }
+
+        async fn client(&self) -> Result<&Endpoint, String> {
+            self.client.get_or_try_init(|| async {
+                let mut endpoint = Endpoint::client("[::]:0".parse().unwrap()).map_err(|e| e.to_string())?;
+                endpoint.set_default_client_config(self.client_config.clone());
+                Ok::<_, String>(endpoint)
+            }).await
+        }
+    }
+
+    #[async_trait]
+    impl Transport for QuicTransport {
+        fn scheme(&self) -> &'static str {
+            "quic"
+        }
+
+        fn metrics(&self) -> Arc<TransportMetrics> {
+            self.metrics.clone()
+        }
+
+        async fn connect(&self, addr: &str) -> Result<Link, String> {
+            let server_name = addr.rsplit_once(':').map_or(addr, |(host, _)| host).to_string();
+            let remote = tokio::net::lookup_host(addr).await.map_err(|e| e.to_string())?
+                .next().ok_or("Unresolved QUIC address")?;
+
+            let connection = self.client().await?.connect(remote, &server_name).map_err(|e| e.to_string())?
+                .await.map_err(|e| e.to_string())?;
+            let (writer, reader) = connection.open_bi().await.map_err(|e| e.to_string())?;
+            Ok(spawn_framed(format!("quic://{}", addr), reader, writer, self.metrics.clone()))
+        }
+
+        async fn listen(&self, addr: &str) -> Result<mpsc::Receiver<Link>, String> {
+            let server_config = self.server_config()?;
+            let addr = addr.parse().map_err(|_| "Invalid QUIC listen address")?;
+            let endpoint = Endpoint::server(server_config, addr).map_err(|e| e.to_string())?;
+
+            let (links, accepted) = mpsc::channel(LINK_QUEUE_SIZE);
+            let metrics = self.metrics.clone();
+            tokio::spawn(async move {
+                while let Some(incoming) = endpoint.accept().await {
+                    let links = links.clone();
+                    let metrics = metrics.clone();
+                    tokio::spawn(async move {
+                        let Ok(connection) = incoming.await else {
+                            return;
+                        };
+                        let Ok((writer, reader)) = connection.accept_bi().await else {
+                            return;
+                        };
+                        metrics.connections.fetch_add(1, Ordering::Relaxed);
+                        let remote = format!("quic://{}", connection.remote_address());
+                        let _ = links.send(spawn_framed(remote, reader, writer, metrics)).await;
+                    });
+                }
+            });
+            Ok(accepted)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Adresse locale libre au moment de l'appel
+    fn free_addr() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn frames_keep_their_boundaries() {
+        let (left, right) = tokio::io::duplex(1024);
+        let (left_reader, left_writer) = tokio::io::split(left);
+        let (right_reader, right_writer) = tokio::io::split(right);
+        let metrics = Arc::new(TransportMetrics::default());
+        let sender = spawn_framed("left".into(), left_reader, left_writer, metrics.clone());
+        let mut receiver = spawn_framed("right".into(), right_reader, right_writer, metrics.clone());
+
+        let frames = vec![Vec::new(), vec![1, 2, 3], vec![7; 100_000]];
+        for frame in &frames {
+            sender.outgoing.send(frame.clone()).await.unwrap();
+        }
+        for frame in &frames {
+            assert_eq!(&receiver.incoming.recv().await.unwrap(), frame);
+        }
+        assert_eq!(metrics.frames_sent.load(Ordering::Relaxed), 3);
+        assert_eq!(metrics.frames_received.load(Ordering::Relaxed), 3);
+        assert_eq!(metrics.bytes_sent.load(Ordering::Relaxed), 100_003);
+        assert_eq!(metrics.bytes_received.load(Ordering::Relaxed), 100_003);
+    }
+
+    #[tokio::test]
+    async fn oversized_or_truncated_frames_close_the_link() {
+        for (length, body) in [(MAX_FRAME_SIZE as u32 + 1, Vec::new()), (10, vec![1, 2, 3])] {
+            let (mut peer, local) = tokio::io::duplex(1024);
+            let (reader, writer) = tokio::io::split(local);
+            let mut link = spawn_framed("peer".into(), reader, writer, Arc::default());
+            peer.write_u32(length).await.unwrap();
+            peer.write_all(&body).await.unwrap();
+            peer.shutdown().await.unwrap();
+            assert_eq!(link.incoming.recv().await, None);
+        }
+    }
+
+    #[tokio::test]
+    async fn connect_falls_back_and_counts_per_transport() {
+        let (server, client) = (default_transports(), default_transports());
+        let (listening, closed) = (free_addr(), free_addr());
+        let mut accepted = server.listen(&format!("tcp://{}", listening)).await.unwrap();
+
+        let endpoints = format!("udp://{}|tcp://{}|tcp://{}", listening, closed, listening);
+        let link = client.connect(&endpoints).await.unwrap();
+        assert_eq!(link.remote, format!("tcp://{}", listening));
+        let mut remote = accepted.recv().await.unwrap();
+        link.outgoing.send(b"bloc".to_vec()).await.unwrap();
+        assert_eq!(remote.incoming.recv().await.unwrap(), b"bloc");
+
+        let tcp = client.transports["tcp"].metrics();
+        assert_eq!(tcp.connections.load(Ordering::Relaxed), 1);
+        assert_eq!(tcp.connect_failures.load(Ordering::Relaxed), 1);
+        assert_eq!(tcp.frames_sent.load(Ordering::Relaxed), 1);
+        assert_eq!(tcp.bytes_sent.load(Ordering::Relaxed), 4);
+        let ws = client.transports["ws"].metrics();
+        assert_eq!(ws.connections.load(Ordering::Relaxed) + ws.connect_failures.load(Ordering::Relaxed), 0);
+        let served = server.transports["tcp"].metrics();
+        assert_eq!((served.connections.load(Ordering::Relaxed), served.frames_received.load(Ordering::Relaxed)), (1, 1));
+
+        assert_eq!(client.connect(&format!("tcp://{}", closed)).await.map(|_| ()).unwrap_err().split_once(':').unwrap().0, "tcp");
+        assert_eq!(client.connect("udp://x|").await.map(|_| ()), Err("Unsupported transport udp".to_string()));
+        assert_eq!(client.connect("").await.map(|_| ()), Err("No endpoint in ".to_string()));
+        assert_eq!(tcp.connect_failures.load(Ordering::Relaxed), 2);
+    }
+}
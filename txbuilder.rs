@@ -0,0 +1,212 @@
+use ed25519_dalek::PublicKey;
+
+use crate::config::ChainConfig;
+use crate::gas::DEFAULT_GAS_PRICE;
+use crate::multisig::MultisigPolicy;
+use crate::privacy::EncryptedPayload;
+use crate::signer::Signer;
+use crate::{Authorization, Expiry, Transaction, TransactionKind, TRANSACTION_VALIDITY, TRANSACTION_VERSION};
+
+// Construction d'une transaction signée par une clé unique, sans accès à la
+// chaîne : utilisable sur une machine isolée du réseau, la transaction signée
+// est ensuite soumise à un nœud, encodée comme un corps de bloc
+// (`SubmitBatch`). Le réseau est par défaut celui de la configuration par
+// défaut ; le nonce, qu'aucune transaction incluse du même émetteur ne doit
+// encore utiliser (voir `GetNextNonce`), et l'horodatage doivent être fournis.
+// Sans échéance explicite, la transaction expire TRANSACTION_VALIDITY secondes
+// après son horodatage.
+//
+//     TxBuilder::transfer().to(recipient).amount(50).fee(1).nonce(n).timestamp(now).sign(&keypair)
+#[derive(Clone, Debug)]
+pub struct TxBuilder {
+    version: u32,
+    kind: TransactionKind,
+    chain_id: u64,
+    nonce: Option<u64>,
+    recipient: Option<PublicKey>,
+    amount: u64,
+    gas_price: u64,
+    timestamp: Option<u64>,
+    valid_from: u64,
+    valid_until: Option<Expiry>,
+}
+
+impl TxBuilder {
+    // Transaction de type quelconque ; les constructeurs ci-dessous couvrent
+    // ceux qui ne demandent pas de preuve (voir `shielded.rs`)
+    pub fn new(kind: TransactionKind) -> Self {
+        TxBuilder {
+            version: TRANSACTION_VERSION,
+            kind,
+            chain_id: ChainConfig::default().chain_id,
+            nonce: None,
+            recipient: None,
+            amount: 0,
+            gas_price: DEFAULT_GAS_PRICE,
+            timestamp: None,
+            valid_from: 0,
+            valid_until: None,
+        }
+    }
+
+    pub fn transfer() -> Self {
+        Self::new(TransactionKind::Transfer)
+    }
+
+    // Dépôt du montant dans le solde confidentiel du destinataire
+    pub fn shield() -> Self {
+        Self::new(TransactionKind::Shield)
+    }
+
+    // Ancrage de l'empreinte `document` (voir `anchors::document_hash`) concernant le destinataire
+    pub fn anchor(document: Vec<u8>, cid: Option<String>) -> Self {
+        Self::new(TransactionKind::Anchor { document, cid })
+    }
+
+    pub fn escrow(unlock_height: u64, arbiter: Option<PublicKey>) -> Self {
+        Self::new(TransactionKind::Escrow { unlock_height, arbiter })
+    }
+
+    // Versement au bénéficiaire du séquestre `escrow` (hash de son dépôt)
+    pub fn claim(escrow: Vec<u8>) -> Self {
+        Self::new(TransactionKind::Claim { escrow })
+    }
+
+    // Remboursement au déposant du séquestre `escrow`
+    pub fn refund(escrow: Vec<u8>) -> Self {
+        Self::new(TransactionKind::Refund { escrow })
+    }
+
+    pub fn set_recovery(guardians: MultisigPolicy, challenge_period: u64) -> Self {
+        Self::new(TransactionKind::SetRecovery { guardians, challenge_period })
+    }
+
+    // Opposition de la clé d'origine à une récupération en cours, adressée au compte
+    pub fn veto_recovery() -> Self {
+        Self::new(TransactionKind::VetoRecovery)
+    }
+
+    // Actif lisible par `readers` (triés) en plus du propriétaire, destinataire
+    // de la transaction
+    pub fn register_asset(readers: Vec<PublicKey>) -> Self {
+        Self::new(TransactionKind::RegisterAsset { readers })
+    }
+
+    // Accès du destinataire à l'actif `asset` (hash de son enregistrement)
+    pub fn grant_access(asset: Vec<u8>) -> Self {
+        Self::new(TransactionKind::GrantAccess { asset })
+    }
+
+    pub fn revoke_access(asset: Vec<u8>) -> Self {
+        Self::new(TransactionKind::RevokeAccess { asset })
+    }
+
+    // Attestation déjà chiffrée pour les participants de l'actif (voir
+    // `Asset::participants`), adressée à son auteur
+    pub fn attest(asset: Vec<u8>, payload: EncryptedPayload) -> Self {
+        Self::new(TransactionKind::Attest { asset, payload: Box::new(payload) })
+    }
+
+    // Format de la transaction, le plus récent connu par défaut ; un nœud ne
+    // l'accepte qu'une fois activée la version du protocole qui l'introduit
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    pub fn to(mut self, recipient: PublicKey) -> Self {
+        self.recipient = Some(recipient);
+        self
+    }
+
+    pub fn amount(mut self, amount: u64) -> Self {
+        self.amount = amount;
+        self
+    }
+
+    // Prix payé par unité de gaz consommée
+    pub fn fee(mut self, gas_price: u64) -> Self {
+        self.gas_price = gas_price;
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    // Premier bloc pouvant inclure la transaction
+    pub fn valid_from(mut self, height: u64) -> Self {
+        self.valid_from = height;
+        self
+    }
+
+    pub fn valid_until(mut self, valid_until: Expiry) -> Self {
+        self.valid_until = Some(valid_until);
+        self
+    }
+
+    // Transaction signée, vérifiée sans état (montant, ancrage, signature)
+    pub fn sign(self, signer: &dyn Signer) -> Result<Transaction, &'static str> {
+        let recipient = self.recipient.ok_or("Missing transaction recipient")?;
+        let nonce = self.nonce.ok_or("Missing transaction nonce")?;
+        let timestamp = self.timestamp.ok_or("Missing transaction timestamp")?;
+        let valid_until = self.valid_until.unwrap_or(Expiry::Timestamp(timestamp.saturating_add(TRANSACTION_VALIDITY)));
+        let sender = signer.public_key();
+        let hash = Transaction::signing_hash(self.version, self.chain_id, &sender, nonce, &recipient, self.amount, &self.kind, self.gas_price, timestamp, self.valid_from, valid_until);
+        let transaction = Transaction {
+            version: self.version,
+            chain_id: self.chain_id,
+            sender,
+            nonce,
+            recipient,
+            amount: self.amount,
+            kind: self.kind,
+            gas_price: self.gas_price,
+            authorization: Authorization::Signature(signer.sign(&hash)?),
+            timestamp,
+            valid_from: self.valid_from,
+            valid_until,
+        };
+        transaction.verify()?;
+        Ok(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::Keypair;
+    use rand::rngs::OsRng;
+
+    use super::*;
+
+    #[test]
+    fn signs_nonce_and_bounds_expiry() {
+        let (sender, recipient) = (Keypair::generate(&mut OsRng), Keypair::generate(&mut OsRng));
+        let builder = TxBuilder::transfer().to(recipient.public).amount(50).timestamp(1_000);
+        assert_eq!(builder.clone().sign(&sender).err(), Some("Missing transaction nonce"));
+
+        let transaction = builder.clone().nonce(7).sign(&sender).unwrap();
+        assert_eq!(transaction.nonce, 7);
+        assert_eq!(transaction.valid_until, Expiry::Timestamp(1_000 + TRANSACTION_VALIDITY));
+        assert_eq!(transaction.check_lifetime(0, 1_000), Ok(()));
+
+        // Le nonce fait partie du message signé
+        let mut replayed = transaction.clone();
+        replayed.nonce = 8;
+        assert!(replayed.verify().is_err());
+
+        let transaction = builder.nonce(7).valid_until(Expiry::Height(100)).sign(&sender).unwrap();
+        assert_eq!(transaction.valid_until, Expiry::Height(100));
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::{BTreeMap, HashMap};
+use ed25519_dalek::PublicKey;
+
+use crate::encoding::{Decoder, Encoder};
+use crate::governance::ConsensusParams;
+use crate::Validator;
+
+// Version du protocole implémentée par ce nœud, signalée dans les en-têtes qu'il produit
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// Formats de transaction connus et version du protocole qui introduit chacun
+const TRANSACTION_VERSIONS: &[(u32, u32)] = &[(1, 1)];
+
+// Part de la mise signalant au moins chaque version
+#[derive(Clone, Debug, PartialEq)]
+pub struct VersionSignal {
+    pub version: u32,
+    pub stake: u64,
+    pub share: f64,
+}
+
+// Coordination des mises à niveau : version en vigueur, dernière version signalée
+// par chaque producteur durant l'époque en cours, et version candidate prête
+// depuis `ready_epochs` époques consécutives
+#[derive(Clone, Debug)]
+pub struct Upgrades {
+    active_version: u32,
+    signals: HashMap<PublicKey, u32>,
+    candidate: Option<(u32, u64)>,
+}
+
+impl Default for Upgrades {
+    fn default() -> Self {
+        Upgrades { active_version: PROTOCOL_VERSION, signals: HashMap::new(), candidate: None }
+    }
+}
+
+impl Upgrades {
+    pub fn active_version(&self) -> u32 {
+        self.active_version
+    }
+
+    pub fn candidate(&self) -> Option<(u32, u64)> {
+        self.candidate
+    }
+
+    // Un bloc ne peut signaler une version antérieure à celle en vigueur
+    pub fn check(&self, protocol_version: u32) -> Result<(), &'static str> {
+        if protocol_version < self.active_version {
+            return Err("Block signals an outdated protocol version");
+        }
+        Ok(())
+    }
+
+    // Une transaction n'est acceptée qu'une fois en vigueur la version du
+    // protocole qui introduit son format
+    pub fn check_transaction(&self, version: u32) -> Result<(), &'static str> {
+        self.check_format(TRANSACTION_VERSIONS, version)
+    }
+
+    fn check_format(&self, formats: &[(u32, u32)], version: u32) -> Result<(), &'static str> {
+        let (_, introduced) = formats.iter().find(|(known, _)| *known == version).ok_or("Unsupported transaction version")?;
+        if *introduced > self.active_version {
+            return Err("Transaction version not active");
+        }
+        Ok(())
+    }
+
+    // Mise signalant chaque version (ou une version supérieure) durant l'époque en cours
+    pub fn summary(&self, validators: &HashMap<PublicKey, Validator>) -> Vec<VersionSignal> {
+        let total: u128 = validators.values().map(|v| v.stake as u128).sum();
+        let mut by_version: BTreeMap<u32, u64> = BTreeMap::new();
+        for (validator, version) in &self.signals {
+            if let Some(validator) = validators.get(validator) {
+                *by_version.entry(*version).or_insert(0) += validator.stake;
+            }
+        }
+        let versions: Vec<u32> = by_version.keys().copied().collect();
+        versions.into_iter().map(|version| {
+            let stake = by_version.range(version..).map(|(_, stake)| *stake).sum();
+            let share = if total == 0 { 0.0 } else { stake as f64 / total as f64 };
+            VersionSignal { version, stake, share }
+        }).collect()
+    }
+
+    pub fn record(&mut self, producer: PublicKey, protocol_version: u32) {
+        self.signals.insert(producer, protocol_version);
+    }
+
+    pub fn rekey(&mut self, old_key: &PublicKey, new_key: PublicKey) {
+        if let Some(version) = self.signals.remove(old_key) {
+            self.signals.insert(new_key, version);
+        }
+    }
+
+    // En fin d'époque : la plus haute version signalée par au moins
+    // `upgrade_threshold` % de la mise devient candidate, et entre en vigueur après
+    // `upgrade_epochs` époques consécutives. Retourne la version activée.
+    pub fn end_epoch(&mut self, validators: &HashMap<PublicKey, Validator>, params: &ConsensusParams) -> Option<u32> {
+        let total: u128 = validators.values().map(|v| v.stake as u128).sum();
+        let ready = self.summary(validators).into_iter()
+            .filter(|signal| signal.version > self.active_version)
+            .filter(|signal| signal.stake as u128 * 100 >= total * params.upgrade_threshold as u128)
+            .map(|signal| signal.version)
+            .max();
+        self.signals.clear();
+
+        self.candidate = match (ready, self.candidate) {
+            (Some(version), Some((candidate, epochs))) if version == candidate => Some((version, epochs + 1)),
+            (Some(version), _) => Some((version, 1)),
+            (None, _) => None,
+        };
+        match self.candidate {
+            Some((version, epochs)) if epochs >= params.upgrade_epochs => {
+                self.active_version = version;
+                self.candidate = None;
+                Some(version)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        encoder.put_u32(self.active_version);
+        let mut signals: Vec<(&PublicKey, &u32)> = self.signals.iter().collect();
+        signals.sort_by_key(|(validator, _)| validator.to_bytes());
+        encoder.put_u32(signals.len() as u32);
+        for (validator, version) in signals {
+            encoder.put_public_key(validator);
+            encoder.put_u32(*version);
+        }
+        match self.candidate {
+            Some((version, epochs)) => {
+                encoder.put_u8(1);
+                encoder.put_u32(version);
+                encoder.put_u64(epochs);
+            }
+            None => encoder.put_u8(0),
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Upgrades, &'static str> {
+        let active_version = decoder.get_u32()?;
+        let mut signals = HashMap::new();
+        let mut previous: Option<PublicKey> = None;
+        for _ in 0..decoder.get_u32()? {
+            let validator = decoder.get_public_key()?;
+            if previous.is_some_and(|previous| previous.to_bytes() >= validator.to_bytes()) {
+                return Err("Version signals not in canonical order");
+            }
+            previous = Some(validator);
+            signals.insert(validator, decoder.get_u32()?);
+        }
+        let candidate = match decoder.get_u8()? {
+            0 => None,
+            1 => Some((decoder.get_u32()?, decoder.get_u64()?)),
+            _ => return Err("Invalid upgrade candidate flag"),
+        };
+        Ok(Upgrades { active_version, signals, candidate })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::SecretKey;
+
+    use super::*;
+    use crate::scoring::INITIAL_CONTRIBUTION_SCORE;
+
+    fn validators(stakes: &[u64]) -> HashMap<PublicKey, Validator> {
+        stakes.iter().enumerate().map(|(seed, stake)| {
+            let public_key = PublicKey::from(&SecretKey::from_bytes(&[seed as u8 + 1; 32]).unwrap());
+            let validator = Validator {
+                public_key,
+                stake: *stake,
+                contribution_score: INITIAL_CONTRIBUTION_SCORE,
+                last_validated_block: None,
+                missed_slots: 0,
+                inactive_since: None,
+                standby: false,
+            };
+            (public_key, validator)
+        }).collect()
+    }
+
+    #[test]
+    fn formats_of_a_higher_version_wait_for_its_activation() {
+        let validators = validators(&[700, 300]);
+        let mut keys: Vec<PublicKey> = validators.keys().copied().collect();
+        keys.sort_by_key(|key| validators[key].stake);
+        let (minority, majority) = (keys[0], keys[1]);
+        let params = ConsensusParams::default();
+        let formats = [(1, 1), (2, 2)];
+        let mut upgrades = Upgrades::default();
+
+        // Les en-têtes signalant une version supérieure sont acceptés : c'est le signal
+        assert_eq!(upgrades.check(2), Ok(()));
+        assert_eq!(upgrades.check_format(&formats, 2), Err("Transaction version not active"));
+        assert_eq!(upgrades.check_transaction(2), Err("Unsupported transaction version"));
+
+        // 70 % de la mise ne suffit pas au seuil de 80 %
+        upgrades.record(majority, 2);
+        upgrades.record(minority, 1);
+        assert_eq!((upgrades.end_epoch(&validators, &params), upgrades.candidate()), (None, None));
+
+        for epoch in 1..params.upgrade_epochs {
+            upgrades.record(majority, 2);
+            upgrades.record(minority, 2);
+            assert_eq!(upgrades.end_epoch(&validators, &params), None);
+            assert_eq!(upgrades.candidate(), Some((2, epoch)));
+            assert_eq!(upgrades.check_format(&formats, 2), Err("Transaction version not active"));
+        }
+        upgrades.record(majority, 2);
+        upgrades.record(minority, 2);
+        assert_eq!(upgrades.end_epoch(&validators, &params), Some(2));
+        assert_eq!((upgrades.active_version(), upgrades.candidate()), (2, None));
+        assert_eq!(upgrades.check_format(&formats, 2), Ok(()));
+        assert_eq!(upgrades.check_format(&formats, 1), Ok(()));
+        assert_eq!(upgrades.check(1), Err("Block signals an outdated protocol version"));
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use ed25519_dalek::PublicKey;
+
+use crate::encoding::{Decoder, Encoder};
+
+// Calendrier d'acquisition d'une allocation : rien avant `start + cliff`, puis
+// libération linéaire par paliers de `interval` blocs jusqu'à `start + duration`
+#[derive(Clone, Debug, PartialEq)]
+pub struct VestingSchedule {
+    pub total: u64,
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+    pub interval: u64,
+}
+
+impl VestingSchedule {
+    pub fn new(total: u64, start: u64, cliff: u64, duration: u64, interval: u64) -> Result<Self, &'static str> {
+        let schedule = VestingSchedule { total, start, cliff, duration, interval };
+        schedule.check()?;
+        Ok(schedule)
+    }
+
+    fn check(&self) -> Result<(), &'static str> {
+        if self.duration == 0 || self.interval == 0 || self.cliff > self.duration {
+            return Err("Invalid vesting schedule");
+        }
+        Ok(())
+    }
+
+    // Montant acquis à la hauteur `height`
+    pub fn vested_at(&self, height: u64) -> u64 {
+        let elapsed = height.saturating_sub(self.start);
+        if elapsed < self.cliff {
+            return 0;
+        }
+        if elapsed >= self.duration {
+            return self.total;
+        }
+        let elapsed = elapsed - elapsed % self.interval;
+        (self.total as u128 * elapsed as u128 / self.duration as u128) as u64
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VestingBalance {
+    pub vested: u64,
+    pub locked: u64,
+    // Solde du compte moins la part encore bloquée
+    pub available: u64,
+}
+
+// Comptes soumis à un calendrier d'acquisition (allocations de genèse des
+// membres) : la part non acquise de l'allocation reste bloquée sur le solde
+#[derive(Clone, Debug, Default)]
+pub struct Vesting {
+    schedules: HashMap<PublicKey, VestingSchedule>,
+}
+
+impl Vesting {
+    pub fn add(&mut self, account: PublicKey, schedule: VestingSchedule) -> Result<(), &'static str> {
+        schedule.check()?;
+        if self.schedules.contains_key(&account) {
+            return Err("Vesting account already exists");
+        }
+        self.schedules.insert(account, schedule);
+        Ok(())
+    }
+
+    pub fn balance(&self, account: &PublicKey, balance: u64, height: u64) -> Option<VestingBalance> {
+        let schedule = self.schedules.get(account)?;
+        let vested = schedule.vested_at(height);
+        let locked = schedule.total - vested;
+        Some(VestingBalance { vested, locked, available: balance.saturating_sub(locked) })
+    }
+
+    // Un compte en acquisition ne peut débiter (montant et frais) que la part non
+    // bloquée de son solde, pour une transaction incluse au bloc `height`
+    pub fn check(&self, account: &PublicKey, debit: u64, balance: u64, height: u64) -> Result<(), &'static str> {
+        match self.balance(account, balance, height) {
+            Some(vesting) if debit > vesting.available => Err("Amount exceeds vested balance"),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn has_schedule(&self, account: &PublicKey) -> bool {
+        self.schedules.contains_key(account)
+    }
+
+    // Compte récupéré : son calendrier suit le solde vers la nouvelle clé
+    pub fn rotate(&mut self, account: &PublicKey, new_key: &PublicKey) {
+        if let Some(schedule) = self.schedules.remove(account) {
+            self.schedules.insert(*new_key, schedule);
+        }
+    }
+
+    // Comptes dont un palier a été acquis au bloc `height`, triés par clé
+    pub fn unlocks(&self, height: u64) -> Vec<PublicKey> {
+        let mut unlocks: Vec<PublicKey> = self.schedules.iter()
+            .filter(|(_, schedule)| height > 0 && schedule.vested_at(height) > schedule.vested_at(height - 1))
+            .map(|(account, _)| *account)
+            .collect();
+        unlocks.sort_by_key(|account| account.to_bytes());
+        unlocks
+    }
+
+    pub fn encode_into(&self, encoder: &mut Encoder) {
+        let mut schedules: Vec<(&PublicKey, &VestingSchedule)> = self.schedules.iter().collect();
+        schedules.sort_by_key(|(account, _)| account.to_bytes());
+        encoder.put_u32(schedules.len() as u32);
+        for (account, schedule) in schedules {
+            encoder.put_public_key(account);
+            encoder.put_u64(schedule.total);
+            encoder.put_u64(schedule.start);
+            encoder.put_u64(schedule.cliff);
+            encoder.put_u64(schedule.duration);
+            encoder.put_u64(schedule.interval);
+        }
+    }
+
+    pub fn decode_from(decoder: &mut Decoder) -> Result<Vesting, &'static str> {
+        let mut schedules = HashMap::new();
+        let mut previous: Option<PublicKey> = None;
+        for _ in 0..decoder.get_u32()? {
+            let account = decoder.get_public_key()?;
+            if previous.is_some_and(|previous| previous.to_bytes() >= account.to_bytes()) {
+                return Err("Vesting accounts not in canonical order");
+            }
+            let schedule = VestingSchedule {
+                total: decoder.get_u64()?,
+                start: decoder.get_u64()?,
+                cliff: decoder.get_u64()?,
+                duration: decoder.get_u64()?,
+                interval: decoder.get_u64()?,
+            };
+            schedule.check()?;
+            previous = Some(account);
+            schedules.insert(account, schedule);
+        }
+        Ok(Vesting { schedules })
+    }
+}
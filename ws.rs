@@ -0,0 +1,373 @@
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::events::{ChainEvent, EventBus};
+use crate::lifecycle::TransactionStatus;
+use crate::{Block, Expiry, Transaction, TransactionKind};
+
+// Filtre envoyé par le client sous forme de message texte JSON :
+// {"blocks": true, "transactions": true, "addresses": ["<clé publique hex>", ...]}
+struct Filter {
+    blocks: bool,
+    transactions: bool,
+    addresses: HashSet<[u8; 32]>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter {
+            blocks: true,
+            transactions: true,
+            addresses: HashSet::new(),
+        }
+    }
+}
+
+impl Filter {
+    fn parse(text: &str) -> Result<Filter, &'static str> {
+        let request: Value = serde_json::from_str(text).map_err(|_| "Invalid filter message")?;
+        let mut filter = Filter::default();
+
+        if let Some(blocks) = request.get("blocks") {
+            filter.blocks = blocks.as_bool().ok_or("Invalid blocks flag")?;
+        }
+        if let Some(transactions) = request.get("transactions") {
+            filter.transactions = transactions.as_bool().ok_or("Invalid transactions flag")?;
+        }
+        if let Some(addresses) = request.get("addresses") {
+            for address in addresses.as_array().ok_or("Invalid address list")? {
+                let bytes = hex::decode(address.as_str().ok_or("Invalid address")?).map_err(|_| "Invalid address")?;
+                filter.addresses.insert(bytes.try_into().map_err(|_| "Invalid address")?);
+            }
+        }
+        Ok(filter)
+    }
+
+    // Sans adresse, toute l'activité est diffusée
+    fn matches(&self, transaction: &Transaction) -> bool {
+        self.addresses.is_empty()
+            || self.addresses.contains(transaction.sender.as_bytes())
+            || self.addresses.contains(transaction.recipient.as_bytes())
+    }
+
+    fn notifications(&self, event: &ChainEvent) -> Vec<Value> {
+        let mut notifications = Vec::new();
+        match event {
+            ChainEvent::BlockCommitted(block) => {
+                if self.blocks {
+                    notifications.push(block_json(block));
+                }
+                if !self.addresses.is_empty() {
+                    for transaction in block.transactions.iter().filter(|tx| self.matches(tx)) {
+                        notifications.push(json!({
+                            "type": "address_activity",
+                            "block": block.index,
+                            "transaction": transaction_json(transaction),
+                        }));
+                    }
+                }
+            }
+            ChainEvent::TransactionAccepted(transaction) => {
+                if self.transactions && self.matches(transaction) {
+                    notifications.push(json!({
+                        "type": "mempool_transaction",
+                        "transaction": transaction_json(transaction),
+                    }));
+                }
+            }
+            ChainEvent::TransactionDropped { transaction, status } => {
+                if self.transactions && self.matches(transaction) {
+                    let reason = match status {
+                        TransactionStatus::Dropped { reason } => reason,
+                        _ => "",
+                    };
+                    notifications.push(json!({
+                        "type": "mempool_transaction_dropped",
+                        "status": status.name(),
+                        "reason": reason,
+                        "transaction": transaction_json(transaction),
+                    }));
+                }
+            }
+            ChainEvent::CheckpointFinalized { height, block_hash } => {
+                if self.blocks {
+                    notifications.push(json!({
+                        "type": "finalized",
+                        "height": height,
+                        "hash": hex::encode(block_hash),
+                    }));
+                }
+            }
+            ChainEvent::VestingUnlocked { account, balance } => {
+                if self.addresses.contains(account.as_bytes()) {
+                    notifications.push(json!({
+                        "type": "vesting_unlock",
+                        "account": hex::encode(account.as_bytes()),
+                        "vested": balance.vested,
+                        "locked": balance.locked,
+                        "available": balance.available,
+                    }));
+                }
+            }
+            ChainEvent::FreezeOrderApplied(order) => {
+                if self.addresses.contains(order.account.as_bytes()) {
+                    notifications.push(json!({
+                        "type": if order.freeze { "account_frozen" } else { "account_unfrozen" },
+                        "account": hex::encode(order.account.as_bytes()),
+                        "issuer": hex::encode(order.issuer.as_bytes()),
+                        "reason_hash": hex::encode(&order.reason_hash),
+                    }));
+                }
+            }
+            ChainEvent::ContractExecuted { contract, caller, output, gas_used } => {
+                if self.addresses.contains(contract.as_bytes()) || self.addresses.contains(caller.as_bytes()) {
+                    notifications.push(json!({
+                        "type": "contract_executed",
+                        "contract": hex::encode(contract.as_bytes()),
+                        "caller": hex::encode(caller.as_bytes()),
+                        "output": hex::encode(output),
+                        "gas_used": gas_used,
+                    }));
+                }
+            }
+            ChainEvent::RecoveryInitiated { account, new_key, execute_at } => {
+                if self.addresses.contains(account.as_bytes()) || self.addresses.contains(new_key.as_bytes()) {
+                    notifications.push(json!({
+                        "type": "recovery_initiated",
+                        "account": hex::encode(account.as_bytes()),
+                        "new_key": hex::encode(new_key.as_bytes()),
+                        "execute_at": execute_at,
+                    }));
+                }
+            }
+            ChainEvent::AccountRecovered { account, new_key } => {
+                if self.addresses.contains(account.as_bytes()) || self.addresses.contains(new_key.as_bytes()) {
+                    notifications.push(json!({
+                        "type": "account_recovered",
+                        "account": hex::encode(account.as_bytes()),
+                        "new_key": hex::encode(new_key.as_bytes()),
+                    }));
+                }
+            }
+            // Changements qui concernent toute la chaîne, diffusés avec les blocs
+            ChainEvent::ParametersChanged(params) => {
+                if self.blocks {
+                    notifications.push(json!({
+                        "type": "parameters_changed",
+                        "min_registration_stake": params.min_registration_stake,
+                        "min_producer_stake": params.min_producer_stake,
+                        "max_block_transactions": params.max_block_transactions,
+                        "max_block_gas": params.max_block_gas,
+                        "upgrade_threshold": params.upgrade_threshold,
+                        "upgrade_epochs": params.upgrade_epochs,
+                        "max_active_validators": params.max_active_validators,
+                    }));
+                }
+            }
+            ChainEvent::ProtocolUpgraded { version, height } => {
+                if self.blocks {
+                    notifications.push(json!({
+                        "type": "protocol_upgraded",
+                        "version": version,
+                        "height": height,
+                    }));
+                }
+            }
+            ChainEvent::BeaconFinalized { epoch, value } => {
+                if self.blocks {
+                    notifications.push(json!({
+                        "type": "beacon",
+                        "epoch": epoch,
+                        "value": hex::encode(value),
+                    }));
+                }
+            }
+            ChainEvent::RulesFingerprintMismatch { validator, fingerprint, height } => {
+                if self.blocks {
+                    notifications.push(json!({
+                        "type": "rules_fingerprint_mismatch",
+                        "validator": hex::encode(validator.as_bytes()),
+                        "fingerprint": hex::encode(fingerprint),
+                        "height": height,
+                    }));
+                }
+            }
+            ChainEvent::ValidatorRegistered(_)
+            | ChainEvent::ValidatorUpdated(_)
+            | ChainEvent::CheckpointVoteAdded(_)
+            | ChainEvent::GovernanceTransactionAccepted(_)
+            | ChainEvent::PrivateTransactionAccepted(_)
+            | ChainEvent::MultisigApprovalAdded(_) => {}
+        }
+        notifications
+    }
+}
+
+pub fn block_json(block: &Block) -> Value {
+    json!({
+        "type": "block",
+        "index": block.index,
+        "timestamp": block.timestamp,
+        "hash": hex::encode(&block.current_hash),
+        "previous_hash": hex::encode(&block.previous_hash),
+        "validator": hex::encode(block.validator_pubkey.as_bytes()),
+        "transaction_count": block.transactions.len(),
+    })
+}
+
+pub fn transaction_json(transaction: &Transaction) -> Value {
+    json!({
+        "hash": hex::encode(transaction.hash()),
+        "sender": hex::encode(transaction.sender.as_bytes()),
+        "nonce": transaction.nonce,
+        "recipient": hex::encode(transaction.recipient.as_bytes()),
+        "amount": transaction.amount,
+        "kind": kind_json(&transaction.kind),
+        "gas_price": transaction.gas_price,
+        "timestamp": transaction.timestamp,
+        "valid_from": transaction.valid_from,
+        "valid_until": expiry_json(&transaction.valid_until),
+    })
+}
+
+fn expiry_json(expiry: &Expiry) -> Value {
+    match expiry {
+        Expiry::Never => Value::Null,
+        Expiry::Height(height) => json!({ "height": height }),
+        Expiry::Timestamp(timestamp) => json!({ "timestamp": timestamp }),
+    }
+}
+
+fn kind_json(kind: &TransactionKind) -> Value {
+    match kind {
+        TransactionKind::Transfer => json!({ "type": "transfer" }),
+        TransactionKind::Escrow { unlock_height, arbiter } => json!({
+            "type": "escrow",
+            "unlock_height": unlock_height,
+            "arbiter": arbiter.map(|key| hex::encode(key.as_bytes())),
+        }),
+        TransactionKind::Claim { escrow } => json!({ "type": "claim", "escrow": hex::encode(escrow) }),
+        TransactionKind::Refund { escrow } => json!({ "type": "refund", "escrow": hex::encode(escrow) }),
+        TransactionKind::Deploy { code } => json!({ "type": "deploy", "code": hex::encode(code) }),
+        TransactionKind::Call { input, gas_limit } => json!({
+            "type": "call",
+            "input": hex::encode(input),
+            "gas_limit": gas_limit,
+        }),
+        TransactionKind::SetRecovery { guardians, challenge_period } => json!({
+            "type": "set_recovery",
+            "threshold": guardians.threshold,
+            "guardians": guardians.members.iter().map(|key| hex::encode(key.as_bytes())).collect::<Vec<_>>(),
+            "challenge_period": challenge_period,
+        }),
+        TransactionKind::Recover { account } => json!({ "type": "recover", "account": hex::encode(account.as_bytes()) }),
+        TransactionKind::VetoRecovery => json!({ "type": "veto_recovery" }),
+        TransactionKind::Shield => json!({ "type": "shield" }),
+        TransactionKind::ConfidentialTransfer(transfer) => json!({
+            "type": "confidential_transfer",
+            "commitment": hex::encode(transfer.commitment),
+        }),
+        TransactionKind::Unshield { .. } => json!({ "type": "unshield" }),
+        TransactionKind::Anchor { document, cid } => json!({ "type": "anchor", "document": hex::encode(document), "cid": cid }),
+        TransactionKind::RegisterAsset { readers } => json!({
+            "type": "register_asset",
+            "readers": readers.iter().map(|key| hex::encode(key.as_bytes())).collect::<Vec<_>>(),
+        }),
+        TransactionKind::GrantAccess { asset } => json!({ "type": "grant_access", "asset": hex::encode(asset) }),
+        TransactionKind::RevokeAccess { asset } => json!({ "type": "revoke_access", "asset": hex::encode(asset) }),
+        // Contenu chiffré, lisible des seuls participants
+        TransactionKind::Attest { asset, .. } => json!({ "type": "attest", "asset": hex::encode(asset) }),
+        TransactionKind::Bundle(transactions) => json!({
+            "type": "bundle",
+            "transactions": transactions.iter().map(transaction_json).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+pub async fn serve(addr: SocketAddr, events: EventBus) -> Result<(), &'static str> {
+    let listener = TcpListener::bind(addr).await.map_err(|_| "Failed to bind WebSocket address")?;
+    loop {
+        if let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(handle_connection(stream, events.subscribe()));
+        }
+    }
+}
+
+async fn handle_connection(stream: TcpStream, mut events: broadcast::Receiver<ChainEvent>) {
+    let Ok(socket) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+    let (mut outgoing, mut incoming) = socket.split();
+    let mut filter = Filter::default();
+
+    loop {
+        let outbound = tokio::select! {
+            message = incoming.next() => match message {
+                Some(Ok(Message::Text(text))) => match Filter::parse(&text) {
+                    Ok(new_filter) => {
+                        filter = new_filter;
+                        vec![json!({"type": "subscribed"})]
+                    }
+                    Err(e) => vec![json!({"type": "error", "message": e})],
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => Vec::new(),
+            },
+            event = events.recv() => match event {
+                Ok(event) => filter.notifications(&event),
+                // Client trop lent : on le prévient plutôt que de couper la connexion
+                Err(RecvError::Lagged(skipped)) => vec![json!({"type": "lagged", "skipped": skipped})],
+                Err(RecvError::Closed) => break,
+            },
+        };
+
+        for notification in outbound {
+            if outgoing.send(Message::Text(notification.to_string())).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::PublicKey;
+
+    use crate::governance::ConsensusParams;
+
+    #[test]
+    fn chain_wide_changes_follow_the_blocks_flag() {
+        let params = ConsensusParams { max_block_transactions: 42, ..ConsensusParams::default() };
+        let event = ChainEvent::ParametersChanged(params);
+
+        let notifications = Filter::default().notifications(&event);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0]["type"], "parameters_changed");
+        assert_eq!(notifications[0]["max_block_transactions"], 42);
+
+        let filter = Filter::parse(r#"{"blocks": false}"#).unwrap();
+        assert!(filter.notifications(&event).is_empty());
+    }
+
+    #[test]
+    fn notifies_protocol_upgrades() {
+        let notifications = Filter::default().notifications(&ChainEvent::ProtocolUpgraded { version: 3, height: 120 });
+        assert_eq!(notifications, vec![json!({"type": "protocol_upgraded", "version": 3, "height": 120})]);
+    }
+
+    #[test]
+    fn notifies_producers_with_other_consensus_rules() {
+        let validator = PublicKey::from_bytes(&[0u8; 32]).unwrap();
+        let event = ChainEvent::RulesFingerprintMismatch { validator, fingerprint: vec![0xab; 4], height: 7 };
+        let notifications = Filter::default().notifications(&event);
+        assert_eq!(notifications[0]["type"], "rules_fingerprint_mismatch");
+        assert_eq!(notifications[0]["fingerprint"], "abababab");
+        assert_eq!(notifications[0]["height"], 7);
+    }
+}